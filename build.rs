@@ -0,0 +1,50 @@
+fn main() {
+    println!("cargo:rerun-if-changed=build.rs");
+
+    #[cfg(feature = "ffi")]
+    generate_c_header();
+
+    #[cfg(feature = "grpc")]
+    generate_grpc_service();
+}
+
+/// Generate the C header for the FFI layer; a failure here shouldn't break Rust builds
+///
+/// The header goes to `OUT_DIR`; `include/intent_verification.h` is its committed copy, which
+/// `tests/ffi_test.rs` keeps in sync.
+#[cfg(feature = "ffi")]
+fn generate_c_header() {
+    use std::env;
+    use std::path::PathBuf;
+
+    println!("cargo:rerun-if-changed=src");
+    println!("cargo:rerun-if-changed=cbindgen.toml");
+
+    let crate_dir = PathBuf::from(env::var("CARGO_MANIFEST_DIR").unwrap());
+    let header = PathBuf::from(env::var("OUT_DIR").unwrap()).join("intent_verification.h");
+
+    match cbindgen::generate(&crate_dir) {
+        Ok(bindings) => {
+            bindings.write_to_file(header);
+        }
+        Err(e) => println!("cargo:warning=Failed to generate C header: {}", e),
+    }
+}
+
+/// Generate the messages, service and client of `proto/intent_verification/v1`
+///
+/// Uses the `protoc` bundled by `protoc-bin-vendored`, so none needs to be installed.
+#[cfg(feature = "grpc")]
+fn generate_grpc_service() {
+    println!("cargo:rerun-if-changed=proto");
+
+    let mut config = tonic_prost_build::Config::new();
+    config.protoc_executable(protoc_bin_vendored::protoc_bin_path().unwrap());
+    tonic_prost_build::configure()
+        .compile_with_config(
+            config,
+            &["proto/intent_verification/v1/intent_verification.proto"],
+            &["proto"],
+        )
+        .unwrap();
+}
@@ -0,0 +1,617 @@
+/* Generated by cbindgen from the intent-verification crate. Do not edit. */
+
+#ifndef INTENT_VERIFICATION_H
+#define INTENT_VERIFICATION_H
+
+#include <stdarg.h>
+#include <stdbool.h>
+#include <stddef.h>
+#include <stdint.h>
+#include <stdlib.h>
+
+/**
+ * Most source files added to the context, so a test importing half the crate doesn't crowd
+ * out the changes
+ */
+#define MAX_TESTED_SOURCES 10
+
+/**
+ * Ambiguity above which an intent is sent back for clarification when no threshold is
+ * configured
+ */
+#define DEFAULT_MAX_AMBIGUITY 0.6
+
+/**
+ * Confidence below which the final judgment is re-run when no threshold is configured
+ */
+#define DEFAULT_RETRY_CONFIDENCE 0.75
+
+/**
+ * Confidence below which a result is escalated when no threshold is configured
+ */
+#define DEFAULT_ESCALATION_CONFIDENCE 0.6
+
+/**
+ * Request bodies larger than this are refused with `413` unless configured otherwise
+ */
+#define DEFAULT_MAX_BODY_BYTES (1024 * 1024)
+
+/**
+ * Finished jobs kept for `GET /jobs/{id}` unless configured otherwise
+ */
+#define DEFAULT_MAX_FINISHED_JOBS 1000
+
+/**
+ * Verifications run at once unless configured otherwise
+ */
+#define DEFAULT_MAX_RUNNING_JOBS 4
+
+/**
+ * Kind of change made to a file (C mirror of `ChangeType`)
+ */
+typedef enum CChangeType {
+  CChangeType_Added,
+  CChangeType_Modified,
+  CChangeType_Deleted,
+} CChangeType;
+
+/**
+ * Category of the last FFI failure on the calling thread
+ */
+typedef enum IvErrorCode {
+  /**
+   * The last call succeeded
+   */
+  IvErrorCode_Ok = 0,
+  /**
+   * A required pointer argument was NULL
+   */
+  IvErrorCode_NullArgument = 1,
+  /**
+   * A string argument was not valid UTF-8
+   */
+  IvErrorCode_InvalidUtf8 = 2,
+  /**
+   * A JSON argument could not be parsed
+   */
+  IvErrorCode_InvalidJson = 3,
+  /**
+   * Cloning or reading a repository failed (bad URL, unknown commit, network)
+   */
+  IvErrorCode_Git = 4,
+  /**
+   * The LLM API rejected the credentials
+   */
+  IvErrorCode_Authentication = 5,
+  /**
+   * Any other LLM API failure (rate limit, unreachable endpoint, bad response)
+   */
+  IvErrorCode_Api = 6,
+  /**
+   * The async runtime could not be started
+   */
+  IvErrorCode_Runtime = 7,
+  IvErrorCode_Unknown = 8,
+  /**
+   * The operation was stopped with `iv_cancel`
+   */
+  IvErrorCode_Cancelled = 9,
+  /**
+   * An argument was rejected before any work started, such as an empty intent
+   */
+  IvErrorCode_InvalidInput = 10,
+} IvErrorCode;
+
+/**
+ * State of an asynchronous verification job
+ */
+typedef enum IvJobStatus {
+  IvJobStatus_Running,
+  IvJobStatus_Succeeded,
+  IvJobStatus_Failed,
+} IvJobStatus;
+
+/**
+ * Pipeline stage of a progress update (C mirror of `ProgressStage`)
+ */
+typedef enum IvProgressStage {
+  IvProgressStage_ExtractingTargets,
+  IvProgressStage_ReadingTargets,
+  IvProgressStage_FetchingChanges,
+  IvProgressStage_AnalyzingFiles,
+  IvProgressStage_Assessing,
+  IvProgressStage_Done,
+} IvProgressStage;
+
+/**
+ * Kind of change an intent asks for, selecting tailored prompts, required evidence and a
+ * default verdict policy
+ */
+typedef struct IntentArchetype IntentArchetype;
+
+/**
+ * Settings shared by verification calls, built with `iv_config_new` and `iv_config_set_*`
+ *
+ * Opaque to C; release with `iv_config_free`. A config is copied when a call starts, so it
+ * can be changed or freed while an asynchronous job is still running.
+ */
+typedef struct IvConfig IvConfig;
+
+/**
+ * Handle to a verification running in the background
+ *
+ * Opaque to C; release with `iv_job_free`.
+ */
+typedef struct IvJob IvJob;
+
+/**
+ * Analysis of one changed file (C mirror of `FileIntentAnalysis`)
+ */
+typedef struct CFileIntentAnalysis {
+  char *file_path;
+  enum CChangeType change_type;
+  bool supports_intent;
+  char *reasoning;
+  float risk_score;
+} CFileIntentAnalysis;
+
+/**
+ * Verification result with typed fields (C mirror of `IntentVerificationResult`)
+ *
+ * Must be released with `iv_result_free`.
+ */
+typedef struct CIntentVerificationResult {
+  bool is_intent_fulfilled;
+  float confidence;
+  float risk_score;
+  bool is_partial;
+  char *explanation;
+  char *overall_assessment;
+  /**
+   * Array of `files_analyzed_len` entries
+   */
+  struct CFileIntentAnalysis *files_analyzed;
+  size_t files_analyzed_len;
+} CIntentVerificationResult;
+
+/**
+ * Completion callback for `verify_intent_async_c`
+ *
+ * Invoked exactly once from a library-owned thread, after the job is marked finished, so
+ * `iv_poll`, `iv_wait` and `iv_job_result` may be called from inside it. `result_json` is set
+ * when the job succeeded and `error_message` when it failed; both are only valid during the
+ * call. May be NULL.
+ */
+typedef void (*IvCompletionCallback)(void *user_data,
+                                     enum IvJobStatus status,
+                                     const char *result_json,
+                                     const char *error_message);
+
+/**
+ * Progress update passed to an `IvProgressCallback` (C mirror of `Progress`)
+ */
+typedef struct IvProgress {
+  enum IvProgressStage stage;
+  size_t files_done;
+  size_t files_total;
+  /**
+   * File that just finished, or NULL
+   */
+  const char *current_file;
+  /**
+   * Overall completion estimate (0-100)
+   */
+  float percent;
+} IvProgress;
+
+/**
+ * Progress callback set with `iv_config_set_progress_callback`
+ *
+ * Called from library-owned threads, possibly concurrently when files are analyzed in
+ * parallel. `progress` is only valid during the call. May be NULL.
+ */
+typedef void (*IvProgressCallback)(void *user_data, const struct IvProgress *progress);
+
+
+
+#ifdef __cplusplus
+extern "C" {
+#endif // __cplusplus
+
+/**
+ * FFI: Call OpenAI from C/FFI
+ * Returns NULL on failure; see `iv_last_error_code` / `iv_last_error_message`
+ * Thread-safe: may be called concurrently from multiple threads
+ *
+ * # Safety
+ * `prompt` and `api_key` must be NULL or NUL-terminated strings that stay valid for the
+ * duration of the call
+ */
+char *ask_openai(const char *prompt, const char *api_key);
+
+/**
+ * FFI: Free string allocated by ask_openai
+ *
+ * # Safety
+ * `ptr` must be NULL or a string returned by this library that hasn't been freed yet
+ */
+void free_str(char *ptr);
+
+/**
+ * FFI: Verify test intent with code changes
+ * Returns a JSON string with the verification result, or NULL on failure
+ * (see `iv_last_error_code` / `iv_last_error_message`)
+ * Thread-safe: may be called concurrently from multiple threads
+ *
+ * # Safety
+ * Every string argument must be NULL or a NUL-terminated string that stays valid for the
+ * duration of the call
+ */
+char *verify_intent_c(const char *test_repo_url,
+                      const char *test_commit,
+                      const char *solution_repo_url,
+                      const char *solution_commit1,
+                      const char *solution_commit2,
+                      const char *user_intent,
+                      const char *api_key,
+                      const char *model,
+                      const char *base_url);
+
+/**
+ * FFI: Extract the functions and files a prompt expects to work
+ * Returns `TestTargets` as JSON, or NULL on failure
+ *
+ * # Safety
+ * Every argument must be NULL or a NUL-terminated string that stays valid for the duration
+ * of the call
+ */
+char *extract_test_targets_with_ai_c(const char *prompt,
+                                     const char *api_key,
+                                     const char *model,
+                                     const char *base_url);
+
+/**
+ * FFI: Read the code for test targets from a repository at a commit
+ * `targets_json` is a `TestTargets` object; returns `TestTargetsWithCode` as JSON, or NULL on failure
+ *
+ * # Safety
+ * Every argument must be NULL or a NUL-terminated string that stays valid for the duration
+ * of the call
+ */
+char *read_test_targets_code_c(const char *targets_json,
+                               const char *repo_url,
+                               const char *commit);
+
+/**
+ * FFI: List files changed between two commits
+ * Returns an array of `FileChange` as JSON, or NULL on failure
+ *
+ * # Safety
+ * Every argument must be NULL or a NUL-terminated string that stays valid for the duration
+ * of the call
+ */
+char *get_git_changed_files_c(const char *repo_url,
+                              const char *commit_hash_1,
+                              const char *commit_hash_2);
+
+/**
+ * FFI: Verify test intent with code changes
+ * Returns a typed result (free with `iv_result_free`), or NULL on failure
+ *
+ * # Safety
+ * Every argument must be NULL or a NUL-terminated string that stays valid for the duration
+ * of the call
+ */
+struct CIntentVerificationResult *verify_intent_typed_c(const char *test_repo_url,
+                                                        const char *test_commit,
+                                                        const char *solution_repo_url,
+                                                        const char *solution_commit1,
+                                                        const char *solution_commit2,
+                                                        const char *user_intent,
+                                                        const char *api_key,
+                                                        const char *model,
+                                                        const char *base_url);
+
+/**
+ * FFI: Convert a JSON result (as returned by `verify_intent_c`) into a typed result
+ * Returns NULL if the JSON is invalid
+ *
+ * # Safety
+ * `json` must be NULL or point to a NUL-terminated string that stays valid for the
+ * duration of the call
+ */
+struct CIntentVerificationResult *iv_result_from_json(const char *json);
+
+/**
+ * FFI: Number of analyzed files in a typed result
+ *
+ * # Safety
+ * `result` must be NULL or a result returned by this library that hasn't been freed yet
+ */
+size_t iv_result_file_count(const struct CIntentVerificationResult *result);
+
+/**
+ * FFI: Analyzed file at `index`, or NULL if out of range
+ * The pointer is owned by the result and valid until `iv_result_free`
+ *
+ * # Safety
+ * `result` must be NULL or a result returned by this library that hasn't been freed yet
+ */
+const struct CFileIntentAnalysis *iv_result_file_at(const struct CIntentVerificationResult *result,
+                                                    size_t index);
+
+/**
+ * FFI: Free a typed result and all strings it owns
+ *
+ * # Safety
+ * `result` must be NULL or a result returned by this library that hasn't been freed yet;
+ * the strings and files it owns must not be used afterwards
+ */
+void iv_result_free(struct CIntentVerificationResult *result);
+
+/**
+ * FFI: Error code of the last failed call on this thread (`Ok` if it succeeded)
+ */
+enum IvErrorCode iv_last_error_code(void);
+
+/**
+ * FFI: Message of the last failed call on this thread, or NULL if it succeeded
+ * The returned string must be released with `free_str`
+ */
+char *iv_last_error_message(void);
+
+/**
+ * FFI: Clear the last error on this thread
+ */
+void iv_clear_error(void);
+
+/**
+ * FFI: Start verifying test intent in the background
+ *
+ * Returns immediately with a job handle (NULL if the arguments are invalid, see
+ * `iv_last_error_code`). Poll the handle with `iv_poll`/`iv_wait`, or pass a `callback`,
+ * which is called from a worker thread with `user_data` once the job is marked finished.
+ * `iv_wait` returns only after the callback has returned, except when called from the
+ * callback itself.
+ *
+ * # Safety
+ * Every string argument must be NULL or a NUL-terminated string that stays valid for the
+ * duration of the call.
+ * `user_data` is passed to `callback` from another thread, so whatever it points to must be
+ * safe to use from there
+ */
+struct IvJob *verify_intent_async_c(const char *test_repo_url,
+                                    const char *test_commit,
+                                    const char *solution_repo_url,
+                                    const char *solution_commit1,
+                                    const char *solution_commit2,
+                                    const char *user_intent,
+                                    const char *api_key,
+                                    const char *model,
+                                    const char *base_url,
+                                    IvCompletionCallback callback,
+                                    void *user_data);
+
+/**
+ * FFI: Ask a running job to stop
+ *
+ * The job finishes shortly afterwards with status `Failed` and error code `Cancelled`;
+ * its callback still runs. Does nothing if the job already finished.
+ *
+ * # Safety
+ * `job` must be NULL or a handle returned by this library that hasn't been freed with
+ * `iv_job_free`
+ */
+void iv_cancel(const struct IvJob *job);
+
+/**
+ * FFI: Current status of a job without blocking
+ *
+ * # Safety
+ * `job` must be NULL or a handle returned by this library that hasn't been freed with
+ * `iv_job_free`
+ */
+enum IvJobStatus iv_poll(const struct IvJob *job);
+
+/**
+ * FFI: Block until a job finishes and return its final status
+ *
+ * # Safety
+ * `job` must be NULL or a handle returned by this library that hasn't been freed with
+ * `iv_job_free`
+ */
+enum IvJobStatus iv_wait(const struct IvJob *job);
+
+/**
+ * FFI: JSON result of a succeeded job (free with `free_str`), or NULL otherwise
+ *
+ * # Safety
+ * `job` must be NULL or a handle returned by this library that hasn't been freed with
+ * `iv_job_free`
+ */
+char *iv_job_result(const struct IvJob *job);
+
+/**
+ * FFI: Error code of a failed job (`Ok` while running or after success)
+ *
+ * # Safety
+ * `job` must be NULL or a handle returned by this library that hasn't been freed with
+ * `iv_job_free`
+ */
+enum IvErrorCode iv_job_error_code(const struct IvJob *job);
+
+/**
+ * FFI: Error message of a failed job (free with `free_str`), or NULL otherwise
+ *
+ * # Safety
+ * `job` must be NULL or a handle returned by this library that hasn't been freed with
+ * `iv_job_free`
+ */
+char *iv_job_error_message(const struct IvJob *job);
+
+/**
+ * FFI: Release a job handle
+ *
+ * A job that is still running keeps going and still invokes its callback; only the handle
+ * becomes invalid.
+ *
+ * # Safety
+ * `job` must be NULL or a handle returned by this library that hasn't been freed yet
+ */
+void iv_job_free(struct IvJob *job);
+
+/**
+ * FFI: Create a config with default settings
+ */
+struct IvConfig *iv_config_new(void);
+
+/**
+ * FFI: Free a config
+ *
+ * # Safety
+ * `config` must be NULL or a config from `iv_config_new` that hasn't been freed yet
+ */
+void iv_config_free(struct IvConfig *config);
+
+/**
+ * FFI: Model to use (NULL for the default model)
+ *
+ * # Safety
+ * `config` must be NULL or a live config from `iv_config_new`, and `model` NULL or a
+ * NUL-terminated string
+ */
+enum IvErrorCode iv_config_set_model(struct IvConfig *config, const char *model);
+
+/**
+ * FFI: API base URL (NULL for the OpenAI endpoint)
+ *
+ * # Safety
+ * `config` must be NULL or a live config from `iv_config_new`, and `base_url` NULL or a
+ * NUL-terminated string
+ */
+enum IvErrorCode iv_config_set_base_url(struct IvConfig *config, const char *base_url);
+
+/**
+ * FFI: Timeout for each LLM request in seconds (0 for no timeout)
+ *
+ * # Safety
+ * `config` must be NULL or a live config from `iv_config_new`
+ */
+enum IvErrorCode iv_config_set_timeout_secs(struct IvConfig *config, uint64_t timeout_secs);
+
+/**
+ * FFI: Number of files analyzed at the same time (0 for the default of one)
+ *
+ * # Safety
+ * `config` must be NULL or a live config from `iv_config_new`
+ */
+enum IvErrorCode iv_config_set_concurrency(struct IvConfig *config, size_t concurrency);
+
+/**
+ * FFI: Directory repositories are cloned into (NULL for the system temp directory)
+ *
+ * # Safety
+ * `config` must be NULL or a live config from `iv_config_new`, and `cache_dir` NULL or a
+ * NUL-terminated string
+ */
+enum IvErrorCode iv_config_set_cache_dir(struct IvConfig *config, const char *cache_dir);
+
+/**
+ * FFI: Proxy URL for LLM requests and git clones (NULL for no proxy)
+ *
+ * # Safety
+ * `config` must be NULL or a live config from `iv_config_new`, and `proxy` NULL or a
+ * NUL-terminated string
+ */
+enum IvErrorCode iv_config_set_proxy(struct IvConfig *config, const char *proxy);
+
+/**
+ * FFI: Refuse network access except LLM requests to `allowed_endpoint`, e.g. an on-prem
+ * model; repositories must then be local paths (NULL to allow any)
+ *
+ * # Safety
+ * `config` must be NULL or a live config from `iv_config_new`, and `allowed_endpoint`
+ * NULL or a NUL-terminated string
+ */
+enum IvErrorCode iv_config_set_local_only(struct IvConfig *config, const char *allowed_endpoint);
+
+/**
+ * FFI: Language for reasoning and assessments, e.g. "vi" (NULL for English)
+ *
+ * # Safety
+ * `config` must be NULL or a live config from `iv_config_new`, and `language` NULL or a
+ * NUL-terminated string
+ */
+enum IvErrorCode iv_config_set_language(struct IvConfig *config, const char *language);
+
+/**
+ * FFI: Receive progress updates for calls made with this config (NULL callback to disable)
+ *
+ * # Safety
+ * `config` must be NULL or a live config from `iv_config_new`. `user_data` is passed to
+ * `callback` from library threads, so whatever it points to must be safe to use from there
+ */
+enum IvErrorCode iv_config_set_progress_callback(struct IvConfig *config,
+                                                 IvProgressCallback callback,
+                                                 void *user_data);
+
+/**
+ * FFI: Verify test intent using the settings in `config`
+ * Returns a JSON string with the verification result, or NULL on failure
+ *
+ * # Safety
+ * `config` must be NULL or a live config from `iv_config_new`; every string argument must
+ * be NULL or a NUL-terminated string that stays valid for the duration of the call
+ */
+char *verify_intent_with_config_c(const struct IvConfig *config,
+                                  const char *test_repo_url,
+                                  const char *test_commit,
+                                  const char *solution_repo_url,
+                                  const char *solution_commit1,
+                                  const char *solution_commit2,
+                                  const char *user_intent,
+                                  const char *api_key);
+
+/**
+ * FFI: Asynchronous variant of `verify_intent_with_config_c` (see `verify_intent_async_c`)
+ *
+ * # Safety
+ * `config` must be NULL or a live config from `iv_config_new`; every string argument must
+ * be NULL or a NUL-terminated string that stays valid for the duration of the call.
+ * `user_data` is passed to `callback` from another thread, so whatever it points to must be
+ * safe to use from there
+ */
+struct IvJob *verify_intent_with_config_async_c(const struct IvConfig *config,
+                                                const char *test_repo_url,
+                                                const char *test_commit,
+                                                const char *solution_repo_url,
+                                                const char *solution_commit1,
+                                                const char *solution_commit2,
+                                                const char *user_intent,
+                                                const char *api_key,
+                                                IvCompletionCallback callback,
+                                                void *user_data);
+
+/**
+ * FFI: Run any operation described by a JSON request and return a JSON response
+ *
+ * The request's `"command"` selects the operation (`version`, `ask_openai`,
+ * `extract_test_targets`, `read_test_targets_code`, `get_git_changed_files`,
+ * `verify_intent`, `render_report`); the other fields are its arguments, named as in the
+ * Rust API, plus an optional `options` object (`AnalysisOptions`).
+ *
+ * Always returns a response (free with `free_str`): `{"ok": true, "result": ...}` or
+ * `{"ok": false, "error": {"code": "...", "message": "..."}}`. New commands and fields can
+ * be added without changing the C ABI.
+ *
+ * # Safety
+ * `request_json` must be NULL or point to a NUL-terminated string that stays valid for the
+ * duration of the call
+ */
+char *iv_execute(const char *request_json);
+
+#ifdef __cplusplus
+}  // extern "C"
+#endif  // __cplusplus
+
+#endif  /* INTENT_VERIFICATION_H */
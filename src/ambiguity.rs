@@ -0,0 +1,210 @@
+use std::sync::LazyLock;
+
+use regex::Regex;
+
+use crate::types::{IntentVerificationResult, ResultMetadata};
+
+/// Ambiguity above which an intent is sent back for clarification when no threshold is
+/// configured
+pub const DEFAULT_MAX_AMBIGUITY: f32 = 0.6;
+
+/// Phrases that say something should change without saying what
+const VAGUE_PHRASES: &[&str] = &[
+    "make it work",
+    "make it better",
+    "fix it",
+    "fix this",
+    "fix the bug",
+    "fix bugs",
+    "clean up",
+    "improve things",
+    "and so on",
+    "as needed",
+    "somehow",
+    "whatever",
+    "stuff",
+    "things",
+    "etc",
+    "tbd",
+];
+
+/// Words that describe an observable behavior or outcome
+const OUTCOME_WORDS: &[&str] = &[
+    "should",
+    "must",
+    "return",
+    "returns",
+    "reject",
+    "rejects",
+    "accept",
+    "accepts",
+    "raise",
+    "raises",
+    "throw",
+    "throws",
+    "error",
+    "errors",
+    "fail",
+    "fails",
+    "pass",
+    "passes",
+    "when",
+    "if",
+    "given",
+    "then",
+    "instead",
+    "equal",
+    "equals",
+    "contain",
+    "contains",
+    "validate",
+    "validates",
+    "handle",
+    "handles",
+    "support",
+    "supports",
+    "add",
+    "adds",
+    "remove",
+    "removes",
+    "print",
+    "prints",
+    "log",
+    "logs",
+    "show",
+    "shows",
+    "display",
+    "displays",
+    "allow",
+    "allows",
+    "prevent",
+    "prevents",
+    "use",
+    "uses",
+    "rename",
+    "renames",
+    "emit",
+    "emits",
+    "store",
+    "stores",
+    "compute",
+    "computes",
+    "calculate",
+    "calculates",
+    "parse",
+    "parses",
+    "convert",
+    "converts",
+];
+
+/// Words too common to count toward an intent's length
+const FILLER_WORDS: &[&str] = &[
+    "the", "and", "for", "that", "with", "this", "from", "into", "are", "its", "all", "can", "has",
+    "have", "will", "make", "please", "just", "some", "get",
+];
+
+static WORD: LazyLock<Regex> = LazyLock::new(|| Regex::new(r"[A-Za-z0-9_]+").unwrap());
+
+/// Identifiers (`snake_case`, `camelCase`, `Type::method`, `call()`), quoted or backticked
+/// text, paths, file names and numbers: something concrete the intent is about
+static CONCRETE: LazyLock<Regex> = LazyLock::new(|| {
+    Regex::new(
+        r#"`[^`]+`|"[^"]+"|'[^']{2,}'|\b\w+_\w+\b|\b[a-z]+[A-Z]\w*\b|\b[A-Z][a-z]+[A-Z]\w*\b|\w+::\w+|\w+\(\)|\b[\w-]+/[\w./-]+|\b\w+\.[a-z]{1,4}\b|\b\d+\b"#,
+    )
+    .unwrap()
+});
+
+/// How underspecified an intent is, and what to ask its author
+#[derive(Debug, Clone, PartialEq, serde::Serialize, serde::Deserialize)]
+pub struct IntentAmbiguity {
+    /// From 0.0 (specific) to 1.0 (says nothing checkable)
+    pub score: f32,
+    /// What makes the intent ambiguous
+    pub reasons: Vec<String>,
+    /// Questions whose answers would make the intent verifiable
+    pub questions: Vec<String>,
+}
+
+/// Score how ambiguous an intent is from its wording, without asking the model
+///
+/// Adds up four signals: a short intent (up to 0.4), vague phrases like "make it work"
+/// (0.3), nothing concrete such as an identifier, path, quoted value or number (0.2) and no
+/// word describing an observable outcome, like "returns" or "rejects" (0.3).
+pub fn intent_ambiguity(intent: &str) -> IntentAmbiguity {
+    let lower = intent.to_lowercase();
+    let words: Vec<&str> = WORD.find_iter(&lower).map(|m| m.as_str()).collect();
+    let content_words = words
+        .iter()
+        .filter(|word| word.len() >= 3 && !FILLER_WORDS.contains(word))
+        .count();
+
+    let mut score: f32 = 0.0;
+    let mut reasons = Vec::new();
+    let mut questions = Vec::new();
+
+    if content_words < 6 {
+        score += if content_words < 3 { 0.4 } else { 0.2 };
+        reasons.push(format!(
+            "The intent has only {} meaningful words",
+            content_words
+        ));
+        questions.push("What exactly should change, and in which part of the code?".to_string());
+    }
+
+    let vague: Vec<&str> = VAGUE_PHRASES
+        .iter()
+        .copied()
+        .filter(|phrase| {
+            Regex::new(&format!(r"\b{}\b", regex::escape(phrase)))
+                .map(|re| re.is_match(&lower))
+                .unwrap_or(false)
+        })
+        .collect();
+    if let Some(phrase) = vague.first() {
+        score += 0.3;
+        reasons.push(format!("Vague wording: \"{}\"", vague.join("\", \"")));
+        questions.push(format!(
+            "What does \"{}\" mean concretely: what does the code do wrong now, and what should it do instead?",
+            phrase
+        ));
+    }
+
+    if !CONCRETE.is_match(intent) {
+        score += 0.2;
+        reasons.push("No function, file, endpoint or value is named".to_string());
+        questions.push(
+            "Which functions, files, endpoints or commands does the change concern?".to_string(),
+        );
+    }
+
+    if !words.iter().any(|word| OUTCOME_WORDS.contains(word)) {
+        score += 0.3;
+        reasons.push("No expected behavior is described".to_string());
+        questions.push(
+            "How can the result be checked: what should the code return, accept, reject or print afterwards?"
+                .to_string(),
+        );
+    }
+
+    IntentAmbiguity {
+        score: score.min(1.0),
+        reasons,
+        questions,
+    }
+}
+
+/// A result that doesn't judge the changes, since the intent needs clarifying first
+pub(crate) fn needs_clarification_result(
+    ambiguity: IntentAmbiguity,
+    metadata: ResultMetadata,
+) -> IntentVerificationResult {
+    IntentVerificationResult {
+        explanation: format!(
+            "The intent is too ambiguous to verify (ambiguity {:.2}); answer the clarifying questions and verify again",
+            ambiguity.score
+        ),
+        metadata,
+        needs_clarification: Some(ambiguity),
+        ..Default::default()
+    }
+}
@@ -0,0 +1,320 @@
+use std::path::Path;
+#[cfg(feature = "exec")]
+use std::process::Stdio;
+#[cfg(feature = "exec")]
+use std::time::Duration;
+
+#[cfg(feature = "git")]
+use crate::docs_drift::detect_docs_drift;
+#[cfg(feature = "exec")]
+use crate::git::{ChangeType, FileChange};
+#[cfg(feature = "git")]
+use crate::git::{checkout_workspace, spawn_git};
+#[cfg(feature = "git")]
+use crate::options::AnalysisOptions;
+use crate::types::{Finding, Severity};
+#[cfg(feature = "exec")]
+use crate::types::{Warning, WarningKind};
+
+/// Analyzer runs are killed after this long
+#[cfg(feature = "exec")]
+const ANALYZER_TIMEOUT_SECS: u64 = 300;
+
+/// Linter whose diagnostics are merged into the result's findings
+#[derive(Debug, Clone, Copy, PartialEq, Eq, serde::Serialize, serde::Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum StaticAnalyzer {
+    /// `cargo clippy` on Rust projects
+    Clippy,
+    /// `eslint` on JavaScript and TypeScript files
+    Eslint,
+    /// `ruff check` on Python files
+    Ruff,
+}
+
+impl StaticAnalyzer {
+    /// Whether the analyzer checks files like `path`
+    pub fn applies_to(self, path: &str) -> bool {
+        let extensions: &[&str] = match self {
+            StaticAnalyzer::Clippy => &[".rs"],
+            StaticAnalyzer::Eslint => &[".js", ".jsx", ".ts", ".tsx"],
+            StaticAnalyzer::Ruff => &[".py"],
+        };
+        extensions.iter().any(|extension| path.ends_with(extension))
+    }
+
+    /// Program and arguments producing JSON diagnostics for `files`
+    #[cfg(feature = "exec")]
+    fn command(self, files: &[&str]) -> Vec<String> {
+        let mut command: Vec<String> = match self {
+            StaticAnalyzer::Clippy => {
+                // Clippy checks the whole crate; diagnostics are filtered to `files` afterwards
+                return ["cargo", "clippy", "--quiet", "--message-format=json"]
+                    .map(str::to_string)
+                    .to_vec();
+            }
+            StaticAnalyzer::Eslint => ["eslint", "--format", "json"].map(str::to_string).to_vec(),
+            StaticAnalyzer::Ruff => ["ruff", "check", "--output-format=json", "--exit-zero"]
+                .map(str::to_string)
+                .to_vec(),
+        };
+        command.extend(files.iter().map(|file| file.to_string()));
+        command
+    }
+
+    /// Command that succeeds when the toolchain is installed
+    #[cfg(feature = "exec")]
+    fn version_command(self) -> &'static [&'static str] {
+        match self {
+            StaticAnalyzer::Clippy => &["cargo", "clippy", "--version"],
+            StaticAnalyzer::Eslint => &["eslint", "--version"],
+            StaticAnalyzer::Ruff => &["ruff", "--version"],
+        }
+    }
+
+    /// Findings from the analyzer's JSON output, with paths relative to `dir`
+    pub fn parse_output(self, output: &str, dir: &Path) -> Vec<Finding> {
+        let findings = match self {
+            StaticAnalyzer::Clippy => parse_clippy_output(output),
+            StaticAnalyzer::Eslint => parse_eslint_output(output),
+            StaticAnalyzer::Ruff => parse_ruff_output(output),
+        };
+        findings
+            .into_iter()
+            .map(|mut finding| {
+                finding.file_path = finding.file_path.map(|path| relative_path(&path, dir));
+                finding
+            })
+            .collect()
+    }
+}
+
+impl std::str::FromStr for StaticAnalyzer {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s.to_lowercase().as_str() {
+            "clippy" => Ok(StaticAnalyzer::Clippy),
+            "eslint" => Ok(StaticAnalyzer::Eslint),
+            "ruff" => Ok(StaticAnalyzer::Ruff),
+            other => Err(format!(
+                "Unknown analyzer '{}' (expected clippy, eslint or ruff)",
+                other
+            )),
+        }
+    }
+}
+
+/// Run the analyzers on the changed files of the project checked out in `dir`
+///
+/// Analyzers without changed files to check or whose toolchain isn't installed are skipped.
+/// Only diagnostics on the changed files are kept.
+#[cfg(feature = "exec")]
+pub async fn run_static_analyzers(
+    dir: &Path,
+    analyzers: &[StaticAnalyzer],
+    file_changes: &[FileChange],
+) -> (Vec<Finding>, Vec<Warning>) {
+    let mut findings = Vec::new();
+    let mut warnings = Vec::new();
+
+    for &analyzer in analyzers {
+        let files: Vec<&str> = file_changes
+            .iter()
+            .filter(|fc| fc.status != ChangeType::Deleted && analyzer.applies_to(&fc.path))
+            .map(|fc| fc.path.as_str())
+            .collect();
+        if files.is_empty() {
+            continue;
+        }
+        if run(dir, analyzer.version_command()).await.is_err() {
+            eprintln!("⏭️ Skipping {:?}: toolchain not available", analyzer);
+            continue;
+        }
+
+        let command = analyzer.command(&files);
+        match run(dir, &command).await {
+            Ok(output) => findings.extend(
+                analyzer
+                    .parse_output(&output, dir)
+                    .into_iter()
+                    .filter(|f| f.file_path.as_deref().is_some_and(|p| files.contains(&p))),
+            ),
+            Err(e) => warnings.push(Warning {
+                kind: WarningKind::StaticAnalysis,
+                file_path: None,
+                message: format!("Failed to run {:?}: {}", analyzer, e),
+            }),
+        }
+    }
+    (findings, warnings)
+}
+
+/// Check out the solution at `commit`, run the configured analyzers on its changed files and
+/// look for documentation drift
+#[cfg(feature = "git")]
+pub(crate) async fn analyze_solution(
+    repo_url: &str,
+    commit: &str,
+    file_changes: &[FileChange],
+    options: &AnalysisOptions,
+) -> (Vec<Finding>, Vec<Warning>) {
+    let (url, rev) = (repo_url.to_string(), commit.to_string());
+    let checkout = spawn_git(options, move |options| {
+        Ok(checkout_workspace(&url, &rev, "static_analysis", options)?.1)
+    })
+    .await
+    .map_err(|e| e.to_string());
+    let workdir = match checkout {
+        Ok(workdir) => workdir,
+        Err(e) => {
+            let warning = Warning {
+                kind: WarningKind::StaticAnalysis,
+                file_path: None,
+                message: format!(
+                    "Failed to check out the solution for static analysis: {}",
+                    e
+                ),
+            };
+            return (vec![], vec![warning]);
+        }
+    };
+    let (mut findings, warnings) =
+        run_static_analyzers(&workdir, &options.static_analyzers, file_changes).await;
+    if let Some(config) = &options.docs_drift {
+        let doc_files = config.read_doc_files(&workdir);
+        findings.extend(detect_docs_drift(file_changes, &doc_files, config));
+    }
+    std::fs::remove_dir_all(&workdir).ok();
+    (findings, warnings)
+}
+
+/// Stdout of a command run in `dir`; linters exit non-zero when they report problems, so
+/// only failing to start, timing out or printing nothing count as errors
+#[cfg(feature = "exec")]
+async fn run(dir: &Path, command: &[impl AsRef<str>]) -> Result<String, String> {
+    let mut process = tokio::process::Command::new(command[0].as_ref());
+    process
+        .args(command[1..].iter().map(AsRef::as_ref))
+        .current_dir(dir)
+        .stdin(Stdio::null())
+        .stdout(Stdio::piped())
+        .stderr(Stdio::piped())
+        .kill_on_drop(true);
+    let child = process.spawn().map_err(|e| e.to_string())?;
+    let output = tokio::time::timeout(
+        Duration::from_secs(ANALYZER_TIMEOUT_SECS),
+        child.wait_with_output(),
+    )
+    .await
+    .map_err(|_| format!("killed after {} seconds", ANALYZER_TIMEOUT_SECS))?
+    .map_err(|e| e.to_string())?;
+
+    if output.stdout.is_empty() && !output.status.success() {
+        let stderr = String::from_utf8_lossy(&output.stderr);
+        return Err(stderr.lines().last().unwrap_or("no output").to_string());
+    }
+    Ok(String::from_utf8_lossy(&output.stdout).into_owned())
+}
+
+fn relative_path(path: &str, dir: &Path) -> String {
+    Path::new(path)
+        .strip_prefix(dir)
+        .map(|p| p.to_string_lossy().into_owned())
+        .unwrap_or_else(|_| path.trim_start_matches("./").to_string())
+}
+
+/// `cargo clippy --message-format=json` prints one JSON object per line
+fn parse_clippy_output(output: &str) -> Vec<Finding> {
+    output
+        .lines()
+        .filter_map(|line| serde_json::from_str::<serde_json::Value>(line).ok())
+        .filter(|value| value["reason"] == "compiler-message")
+        .filter_map(|value| {
+            let message = &value["message"];
+            let span = message["spans"]
+                .as_array()?
+                .iter()
+                .find(|span| span["is_primary"] == true)?;
+            let code = message["code"]["code"].as_str().unwrap_or("rustc");
+            let severity = match message["level"].as_str() {
+                Some("error") => Severity::High,
+                Some("warning") => Severity::Medium,
+                _ => Severity::Info,
+            };
+            Some(Finding {
+                rule: format!("clippy/{}", code.trim_start_matches("clippy::")),
+                severity,
+                file_path: span["file_name"].as_str().map(str::to_string),
+                line: span["line_start"].as_u64().map(|line| line as usize),
+                snippet: span["text"][0]["text"]
+                    .as_str()
+                    .map(|text| text.trim().to_string()),
+                message: message["message"].as_str().unwrap_or_default().to_string(),
+                suppressed: false,
+                cwe: None,
+                content_hash: None,
+            })
+        })
+        .collect()
+}
+
+/// `eslint --format json` prints an array of files, each with its messages
+fn parse_eslint_output(output: &str) -> Vec<Finding> {
+    let Ok(serde_json::Value::Array(files)) = serde_json::from_str(output) else {
+        return vec![];
+    };
+    let mut findings = Vec::new();
+    for file in &files {
+        for message in file["messages"].as_array().into_iter().flatten() {
+            findings.push(Finding {
+                rule: format!(
+                    "eslint/{}",
+                    message["ruleId"].as_str().unwrap_or("parse-error")
+                ),
+                severity: if message["severity"] == 2 {
+                    Severity::Medium
+                } else {
+                    Severity::Low
+                },
+                file_path: file["filePath"].as_str().map(str::to_string),
+                line: message["line"].as_u64().map(|line| line as usize),
+                snippet: None,
+                message: message["message"].as_str().unwrap_or_default().to_string(),
+                suppressed: false,
+                cwe: None,
+                content_hash: None,
+            });
+        }
+    }
+    findings
+}
+
+/// `ruff check --output-format=json` prints an array of diagnostics
+fn parse_ruff_output(output: &str) -> Vec<Finding> {
+    let Ok(serde_json::Value::Array(diagnostics)) = serde_json::from_str(output) else {
+        return vec![];
+    };
+    diagnostics
+        .iter()
+        .map(|diagnostic| Finding {
+            rule: format!(
+                "ruff/{}",
+                diagnostic["code"].as_str().unwrap_or("syntax-error")
+            ),
+            severity: Severity::Low,
+            file_path: diagnostic["filename"].as_str().map(str::to_string),
+            line: diagnostic["location"]["row"]
+                .as_u64()
+                .map(|line| line as usize),
+            snippet: None,
+            message: diagnostic["message"]
+                .as_str()
+                .unwrap_or_default()
+                .to_string(),
+            suppressed: false,
+            cwe: None,
+            content_hash: None,
+        })
+        .collect()
+}
@@ -0,0 +1,203 @@
+use regex::Regex;
+
+use crate::git::{ChangeType, FileChange};
+use crate::types::{Finding, IntentVerificationResult, Severity};
+use crate::utils::is_test_path;
+
+/// Rule prefix of the findings reported for breaking changes
+pub const SEMVER_RULE_PREFIX: &str = "semver/";
+
+/// An exported item of a source file
+#[derive(Debug, Clone, PartialEq, serde::Serialize, serde::Deserialize)]
+pub struct ApiItem {
+    pub file_path: String,
+    /// Item kind as written in the source (`fn`, `struct`, `class`, `def`, ...)
+    pub kind: String,
+    pub name: String,
+    /// Declaration up to the body, with whitespace collapsed
+    pub signature: String,
+}
+
+/// An exported item that was removed or whose signature changed
+#[derive(Debug, Clone, PartialEq, serde::Serialize, serde::Deserialize)]
+pub struct BreakingChange {
+    pub before: ApiItem,
+    /// The item's new declaration; `None` when it was removed
+    pub after: Option<ApiItem>,
+}
+
+/// Exported items of a Rust, Python or JavaScript/TypeScript file
+///
+/// Rust items count when declared `pub` (not `pub(crate)`), JavaScript/TypeScript items when
+/// exported, and Python functions and classes at module level whose name doesn't start with `_`.
+pub fn public_api(path: &str, content: &str) -> Vec<ApiItem> {
+    let (pattern, is_python) = if path.ends_with(".rs") {
+        (
+            r"(?m)^[ \t]*pub[ \t]+(?:(?:async|unsafe|const|extern)[ \t]+)*(fn|struct|enum|trait|type|const|static|mod|union)[ \t]+(\w+)",
+            false,
+        )
+    } else if path.ends_with(".py") {
+        (r"(?m)^(?:async[ \t]+)?(def|class)[ \t]+([A-Za-z]\w*)", true)
+    } else if [".js", ".jsx", ".ts", ".tsx"]
+        .iter()
+        .any(|extension| path.ends_with(extension))
+    {
+        (
+            r"(?m)^[ \t]*export[ \t]+(?:default[ \t]+)?(?:declare[ \t]+)?(?:abstract[ \t]+)?(?:async[ \t]+)?(function|class|interface|type|const|let|enum)[ \t]+(\w+)",
+            false,
+        )
+    } else {
+        return vec![];
+    };
+
+    Regex::new(pattern)
+        .unwrap()
+        .captures_iter(content)
+        .map(|captures| {
+            let start = captures.get(0).unwrap().start();
+            let rest = &content[start..];
+            let declaration = if is_python {
+                // Up to the line ending the header with `:`
+                let mut end = rest.len();
+                let mut offset = 0;
+                for line in rest.split_inclusive('\n') {
+                    offset += line.len();
+                    if line.trim_end().ends_with(':') {
+                        end = offset;
+                        break;
+                    }
+                }
+                rest[..end].trim_end().trim_end_matches(':')
+            } else {
+                // Constants end at their value; elsewhere `=` can be a default argument
+                let end = match &captures[1] {
+                    "const" | "let" | "static" => rest.find(['{', ';', '=']),
+                    _ => rest.find(['{', ';']),
+                };
+                let end = end.unwrap_or(rest.len());
+                &rest[..end]
+            };
+            ApiItem {
+                file_path: path.to_string(),
+                kind: captures[1].to_string(),
+                name: captures[2].to_string(),
+                signature: declaration.split_whitespace().collect::<Vec<_>>().join(" "),
+            }
+        })
+        .collect()
+}
+
+/// Compare the exported items before and after the changes
+///
+/// Items are matched by kind and name across all changed files, so moving an item between
+/// files isn't reported. Test files and binary entry points (`main.rs`, `src/bin/`) are skipped.
+pub fn detect_breaking_changes(file_changes: &[FileChange]) -> Vec<BreakingChange> {
+    let library_files = file_changes.iter().filter(|fc| {
+        !is_test_path(&fc.path) && !fc.path.ends_with("main.rs") && !fc.path.contains("src/bin/")
+    });
+
+    let mut before = Vec::new();
+    let mut after = Vec::new();
+    for file_change in library_files {
+        if let Some(old) = &file_change.old_content {
+            before.extend(public_api(&file_change.path, old));
+        }
+        if file_change.status != ChangeType::Deleted
+            && let Some(new) = &file_change.content
+        {
+            after.extend(public_api(&file_change.path, new));
+        }
+    }
+
+    before
+        .into_iter()
+        .filter_map(|item| {
+            let same_name: Vec<&ApiItem> = after
+                .iter()
+                .filter(|a| a.kind == item.kind && a.name == item.name)
+                .collect();
+            if same_name.iter().any(|a| a.signature == item.signature) {
+                return None;
+            }
+            let new = same_name.first().map(|a| (*a).clone());
+            Some(BreakingChange {
+                before: item,
+                after: new,
+            })
+        })
+        .collect()
+}
+
+/// Whether the intent says the change must not break the public API
+pub fn claims_non_breaking(user_intent: &str) -> bool {
+    Regex::new(
+        r"(?i)non[- ]?breaking|backwards?[- ]compatib|without breaking|no (?:public )?(?:api|breaking) changes?|(?:don't|do not|must not|mustn't|shouldn't|should not) break",
+    )
+    .unwrap()
+    .is_match(user_intent)
+}
+
+/// Findings for breaking changes: high severity when the intent promises a non-breaking
+/// change, medium otherwise
+pub fn breaking_change_findings(changes: &[BreakingChange], non_breaking: bool) -> Vec<Finding> {
+    let severity = if non_breaking {
+        Severity::High
+    } else {
+        Severity::Medium
+    };
+    changes
+        .iter()
+        .map(|change| {
+            let (rule, message) = match &change.after {
+                None => (
+                    "removed",
+                    format!(
+                        "Public {} `{}` was removed",
+                        change.before.kind, change.before.name
+                    ),
+                ),
+                Some(after) => (
+                    "signature-changed",
+                    format!(
+                        "Signature of public {} `{}` changed to `{}`",
+                        change.before.kind, change.before.name, after.signature
+                    ),
+                ),
+            };
+            Finding {
+                rule: format!("{}{}", SEMVER_RULE_PREFIX, rule),
+                severity,
+                file_path: Some(change.before.file_path.clone()),
+                line: None,
+                snippet: Some(change.before.signature.clone()),
+                message,
+                suppressed: false,
+                cwe: None,
+                content_hash: None,
+            }
+        })
+        .collect()
+}
+
+/// Force a negative verdict when the intent promises a non-breaking change and unsuppressed
+/// breaking-change findings remain
+///
+/// Returns the number of such findings.
+pub fn apply_breaking_changes(result: &mut IntentVerificationResult, user_intent: &str) -> usize {
+    if !claims_non_breaking(user_intent) {
+        return 0;
+    }
+    let breaking = result
+        .findings
+        .iter()
+        .filter(|f| !f.suppressed && f.rule.starts_with(SEMVER_RULE_PREFIX))
+        .count();
+    if breaking > 0 {
+        result.is_intent_fulfilled = false;
+        result.explanation = format!(
+            "{}; the intent asks for a non-breaking change but {} public API item(s) were removed or changed",
+            result.explanation, breaking
+        );
+    }
+    breaking
+}
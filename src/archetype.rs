@@ -0,0 +1,290 @@
+use std::sync::LazyLock;
+
+use regex::Regex;
+
+use crate::api_surface::SEMVER_RULE_PREFIX;
+use crate::git::{ChangeType, FileChange};
+use crate::policy::VerdictPolicy;
+use crate::profile::VerificationProfile;
+use crate::secrets::added_lines;
+use crate::types::{Finding, IntentVerificationResult, Severity};
+use crate::utils::is_test_path;
+
+/// Rule prefix of the findings reported for evidence an archetype requires but the changes lack
+pub const ARCHETYPE_RULE_PREFIX: &str = "archetype/";
+
+/// Test declarations added inline, e.g. in a Rust `#[cfg(test)]` module
+static TEST_DECLARATION: LazyLock<Regex> = LazyLock::new(|| {
+    Regex::new(r"#\[(?:tokio::)?test\]|\bdef test_|(?:^|[^.\w])(?:it|test|describe)\s*\(|@Test\b")
+        .unwrap()
+});
+
+/// Kind of change an intent asks for, selecting tailored prompts, required evidence and a
+/// default verdict policy
+#[derive(Debug, Clone, Copy, PartialEq, Eq, serde::Serialize, serde::Deserialize)]
+#[serde(rename_all = "kebab-case")]
+pub enum IntentArchetype {
+    /// Fix incorrect behavior; a test reproducing the bug must come with the fix
+    BugFix,
+    /// Add new behavior, covered by new tests
+    Feature,
+    /// Restructure code without changing behavior or the public API
+    Refactor,
+    /// Make code faster or leaner; a benchmark must show it
+    Performance,
+    /// Fix a vulnerability; runs the security profile and needs a regression test
+    SecurityPatch,
+}
+
+/// Evidence an archetype requires from the changes
+#[derive(Debug, Clone, Copy, PartialEq, Eq, serde::Serialize, serde::Deserialize)]
+#[serde(rename_all = "kebab-case")]
+pub enum EvidenceRequirement {
+    /// New or modified tests
+    Tests,
+    /// New or modified benchmarks
+    Benchmarks,
+    /// No public API item removed or changed
+    StablePublicApi,
+}
+
+impl EvidenceRequirement {
+    fn rule(self) -> &'static str {
+        match self {
+            EvidenceRequirement::Tests => "missing-tests",
+            EvidenceRequirement::Benchmarks => "missing-benchmarks",
+            EvidenceRequirement::StablePublicApi => "public-api-changed",
+        }
+    }
+}
+
+impl IntentArchetype {
+    pub const ALL: [IntentArchetype; 5] = [
+        IntentArchetype::BugFix,
+        IntentArchetype::Feature,
+        IntentArchetype::Refactor,
+        IntentArchetype::Performance,
+        IntentArchetype::SecurityPatch,
+    ];
+
+    /// Name used in options files and on the command line, e.g. `bug-fix`
+    pub fn name(self) -> &'static str {
+        match self {
+            IntentArchetype::BugFix => "bug-fix",
+            IntentArchetype::Feature => "feature",
+            IntentArchetype::Refactor => "refactor",
+            IntentArchetype::Performance => "performance",
+            IntentArchetype::SecurityPatch => "security-patch",
+        }
+    }
+
+    /// Extra prompt instruction for the per-file analysis
+    pub fn file_analysis_instruction(self) -> &'static str {
+        match self {
+            IntentArchetype::BugFix => {
+                "The intent is a bug fix. A change supports it only if it corrects the faulty logic the intent describes, \
+                 not if it special-cases the failing input or silences the error. Tests that reproduce the bug are supporting changes."
+            }
+            IntentArchetype::Feature => {
+                "The intent is a new feature. A change supports it if it implements part of the requested behavior, \
+                 including edge cases the intent mentions. Tests exercising the new behavior are supporting changes."
+            }
+            IntentArchetype::Refactor => {
+                "The intent is a refactor: behavior must stay the same. A change supports it only if it restructures code \
+                 without altering results, error handling or the public API. Treat any change in behavior as not supporting the intent."
+            }
+            IntentArchetype::Performance => {
+                "The intent is a performance improvement. A change supports it if it plausibly reduces time or memory for the \
+                 case the intent names while keeping results identical. Mention the expected complexity before and after, \
+                 and treat changed results as not supporting the intent. Benchmarks are supporting changes."
+            }
+            IntentArchetype::SecurityPatch => {
+                "The intent is a security patch. Tests that reproduce the exploit are supporting changes."
+            }
+        }
+    }
+
+    /// Extra prompt instruction for the overall assessment
+    pub fn assessment_instruction(self) -> &'static str {
+        match self {
+            IntentArchetype::BugFix => {
+                "This is a bug fix: say whether the root cause is fixed and whether a test reproduces the bug."
+            }
+            IntentArchetype::Feature => {
+                "This is a new feature: say which parts of the requested behavior are implemented and tested, and which are missing."
+            }
+            IntentArchetype::Refactor => {
+                "This is a refactor: say whether behavior and the public API are preserved."
+            }
+            IntentArchetype::Performance => {
+                "This is a performance improvement: say whether the changes make the named case faster or leaner without changing results, and how a benchmark shows it."
+            }
+            IntentArchetype::SecurityPatch => {
+                "This is a security patch: say whether a test reproduces the exploit."
+            }
+        }
+    }
+
+    /// Verification profile the archetype runs under
+    pub fn profile(self) -> VerificationProfile {
+        match self {
+            IntentArchetype::SecurityPatch => VerificationProfile::Security,
+            _ => VerificationProfile::Standard,
+        }
+    }
+
+    /// Evidence the changes must contain for a positive verdict
+    pub fn required_evidence(self) -> &'static [EvidenceRequirement] {
+        match self {
+            IntentArchetype::BugFix | IntentArchetype::Feature | IntentArchetype::SecurityPatch => {
+                &[EvidenceRequirement::Tests]
+            }
+            IntentArchetype::Refactor => &[EvidenceRequirement::StablePublicApi],
+            IntentArchetype::Performance => &[EvidenceRequirement::Benchmarks],
+        }
+    }
+
+    /// Default verdict policy for the archetype
+    pub fn verdict_policy(self) -> VerdictPolicy {
+        let (min_confidence, fail_on_severity) = match self {
+            IntentArchetype::BugFix | IntentArchetype::Feature | IntentArchetype::Performance => {
+                (0.6, Severity::High)
+            }
+            IntentArchetype::Refactor | IntentArchetype::SecurityPatch => (0.7, Severity::Medium),
+        };
+        VerdictPolicy {
+            min_confidence,
+            fail_on_severity: Some(fail_on_severity),
+            ..Default::default()
+        }
+    }
+}
+
+impl std::fmt::Display for IntentArchetype {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.write_str(self.name())
+    }
+}
+
+impl std::str::FromStr for IntentArchetype {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let normalized = s.trim().to_lowercase().replace('_', "-");
+        IntentArchetype::ALL
+            .into_iter()
+            .find(|archetype| archetype.name() == normalized)
+            .ok_or_else(|| {
+                format!(
+                    "Unknown archetype '{}' (expected one of: {})",
+                    s,
+                    IntentArchetype::ALL.map(IntentArchetype::name).join(", ")
+                )
+            })
+    }
+}
+
+/// Whether a path looks like a benchmark (`benches/`, `bench_*`, `*_bench.*`, `*.bench.*`,
+/// `benchmark*`)
+fn is_benchmark_path(path: &str) -> bool {
+    let lower = path.to_lowercase();
+    lower.starts_with("benches/")
+        || lower.contains("/benches/")
+        || lower.contains("bench_")
+        || lower.contains("_bench.")
+        || lower.contains(".bench.")
+        || lower.contains("benchmark")
+}
+
+/// Whether the changes add or modify tests, as test files or inline test declarations
+fn changes_tests(file_changes: &[FileChange]) -> bool {
+    file_changes
+        .iter()
+        .filter(|f| f.status != ChangeType::Deleted)
+        .any(|f| {
+            is_test_path(&f.path)
+                || added_lines(f)
+                    .iter()
+                    .any(|(_, line)| TEST_DECLARATION.is_match(line))
+        })
+}
+
+/// Findings for the evidence the archetype requires but the changes don't provide
+///
+/// The public API check relies on the `semver/` findings already in `result`.
+pub fn missing_evidence(
+    archetype: IntentArchetype,
+    file_changes: &[FileChange],
+    result: &IntentVerificationResult,
+) -> Vec<Finding> {
+    archetype
+        .required_evidence()
+        .iter()
+        .filter_map(|&requirement| {
+            let message = match requirement {
+                EvidenceRequirement::Tests if !changes_tests(file_changes) => format!(
+                    "A {} needs new or updated tests, but the changes contain none",
+                    archetype
+                ),
+                EvidenceRequirement::Benchmarks
+                    if !file_changes
+                        .iter()
+                        .any(|f| f.status != ChangeType::Deleted && is_benchmark_path(&f.path)) =>
+                {
+                    format!(
+                        "A {} improvement needs a benchmark showing it, but the changes contain none",
+                        archetype
+                    )
+                }
+                EvidenceRequirement::StablePublicApi => {
+                    let changed = result
+                        .findings
+                        .iter()
+                        .filter(|f| !f.suppressed && f.rule.starts_with(SEMVER_RULE_PREFIX))
+                        .count();
+                    if changed == 0 {
+                        return None;
+                    }
+                    format!(
+                        "A {} must keep the public API, but {} public item(s) were removed or changed",
+                        archetype, changed
+                    )
+                }
+                _ => return None,
+            };
+            Some(Finding {
+                rule: format!("{}{}", ARCHETYPE_RULE_PREFIX, requirement.rule()),
+                severity: Severity::High,
+                file_path: None,
+                line: None,
+                snippet: None,
+                message,
+                suppressed: false,
+                cwe: None,
+                content_hash: None,
+            })
+        })
+        .collect()
+}
+
+/// Add the findings of [`missing_evidence`] and force a negative verdict when evidence is
+/// missing
+///
+/// Returns the number of unmet requirements.
+pub fn apply_archetype(
+    result: &mut IntentVerificationResult,
+    archetype: IntentArchetype,
+    file_changes: &[FileChange],
+) -> usize {
+    let missing = missing_evidence(archetype, file_changes, result);
+    let count = missing.len();
+    if count > 0 {
+        result.is_intent_fulfilled = false;
+        result.explanation = format!(
+            "{}; {} evidence requirement(s) of a {} unmet",
+            result.explanation, count, archetype
+        );
+        result.findings.extend(missing);
+    }
+    count
+}
@@ -0,0 +1,180 @@
+use std::path::Path;
+
+use ed25519_dalek::{Signature, Signer, SigningKey, Verifier, VerifyingKey};
+use sha2::{Digest, Sha256};
+
+use crate::types::{IntentVerificationResult, SCHEMA_VERSION};
+use crate::utils::sha256_hex;
+
+/// Signature scheme of [`Attestation`]
+pub const ATTESTATION_ALGORITHM: &str = "ed25519";
+
+/// A signed statement that a verification produced a given result
+///
+/// The signature covers the 32-byte SHA-256 digest of the result's canonical JSON, so the
+/// digest alone can be stored on-chain or in an escrow system and checked against the key.
+/// The signed JSON itself is kept in `payload`, so the attestation still verifies after the
+/// result types gain fields that change how the result serializes.
+#[derive(Debug, Clone, PartialEq, serde::Serialize, serde::Deserialize)]
+pub struct Attestation {
+    pub algorithm: String,
+    /// Hex SHA-256 of the canonical JSON of the result, see [`canonical_result_json`]
+    pub result_hash: String,
+    /// Hex ed25519 public key of the signer
+    pub public_key: String,
+    /// Hex ed25519 signature of the raw digest bytes
+    pub signature: String,
+    /// [`SCHEMA_VERSION`] of the crate that signed the result
+    #[serde(default, skip_serializing_if = "String::is_empty")]
+    pub schema_version: String,
+    /// The canonical JSON `result_hash` is the digest of; empty for attestations made before
+    /// it was stored, which are checked against the result re-serialized instead
+    #[serde(default, skip_serializing_if = "String::is_empty")]
+    pub payload: String,
+}
+
+/// Canonical JSON of a result: object keys sorted, no whitespace, without its attestation
+pub fn canonical_result_json(result: &IntentVerificationResult) -> String {
+    let mut value = serde_json::to_value(result).unwrap_or_default();
+    if let Some(object) = value.as_object_mut() {
+        object.remove("attestation");
+    }
+    let mut json = String::new();
+    write_canonical(&value, &mut json);
+    json
+}
+
+/// Hex SHA-256 of [`canonical_result_json`]
+pub fn canonical_result_hash(result: &IntentVerificationResult) -> String {
+    sha256_hex(canonical_result_json(result).as_bytes())
+}
+
+/// Sign the canonical hash of a result
+pub fn sign_result(result: &IntentVerificationResult, key: &SigningKey) -> Attestation {
+    let payload = canonical_result_json(result);
+    let digest = Sha256::digest(payload.as_bytes());
+    Attestation {
+        algorithm: ATTESTATION_ALGORITHM.to_string(),
+        result_hash: to_hex(&digest),
+        public_key: to_hex(key.verifying_key().as_bytes()),
+        signature: to_hex(&key.sign(&digest).to_bytes()),
+        schema_version: SCHEMA_VERSION.to_string(),
+        payload,
+    }
+}
+
+/// Check the attestation attached to a result
+///
+/// Fails when the result has no attestation, differs from the signed payload or the signature
+/// doesn't match. With `trusted_key` (hex), the attestation must also come from that key.
+pub fn verify_attestation(
+    result: &IntentVerificationResult,
+    trusted_key: Option<&str>,
+) -> Result<(), Box<dyn std::error::Error>> {
+    let attestation = result
+        .attestation
+        .as_ref()
+        .ok_or("Result has no attestation")?;
+    if attestation.algorithm != ATTESTATION_ALGORITHM {
+        return Err(format!("Unsupported algorithm '{}'", attestation.algorithm).into());
+    }
+    if let Some(trusted_key) = trusted_key
+        && !trusted_key
+            .trim()
+            .eq_ignore_ascii_case(&attestation.public_key)
+    {
+        return Err("Attestation was signed by a different key".into());
+    }
+
+    let canonical = canonical_result_json(result);
+    let signed = if attestation.payload.is_empty() {
+        &canonical
+    } else {
+        &attestation.payload
+    };
+    let digest = Sha256::digest(signed.as_bytes());
+    if to_hex(&digest) != attestation.result_hash {
+        return Err("Result was modified after it was signed".into());
+    }
+    // Read the payload with today's types too, so fields added since it was signed serialize
+    // the same way on both sides
+    if !attestation.payload.is_empty() {
+        let signed_result: IntentVerificationResult = serde_json::from_str(&attestation.payload)
+            .map_err(|e| {
+                format!(
+                    "Can't read the signed result (schema {}): {}",
+                    attestation.schema_version, e
+                )
+            })?;
+        if canonical_result_json(&signed_result) != canonical {
+            return Err("Result was modified after it was signed".into());
+        }
+    }
+    let public_key: [u8; 32] = from_hex(&attestation.public_key)?
+        .try_into()
+        .map_err(|_| "Public key must be 32 bytes")?;
+    let signature: [u8; 64] = from_hex(&attestation.signature)?
+        .try_into()
+        .map_err(|_| "Signature must be 64 bytes")?;
+    VerifyingKey::from_bytes(&public_key)?
+        .verify(&digest, &Signature::from_bytes(&signature))
+        .map_err(|_| "Signature doesn't match the result")?;
+    Ok(())
+}
+
+/// Signing key from its hex 32-byte secret seed
+pub fn signing_key_from_hex(hex: &str) -> Result<SigningKey, Box<dyn std::error::Error>> {
+    let seed: [u8; 32] = from_hex(hex.trim())?
+        .try_into()
+        .map_err(|_| "Signing key must be a 32-byte hex seed")?;
+    Ok(SigningKey::from_bytes(&seed))
+}
+
+/// Signing key from a file holding its hex 32-byte secret seed
+pub fn load_signing_key(path: impl AsRef<Path>) -> Result<SigningKey, Box<dyn std::error::Error>> {
+    signing_key_from_hex(&std::fs::read_to_string(path)?)
+}
+
+fn write_canonical(value: &serde_json::Value, out: &mut String) {
+    match value {
+        serde_json::Value::Object(object) => {
+            let mut keys: Vec<&String> = object.keys().collect();
+            keys.sort();
+            out.push('{');
+            for (i, key) in keys.into_iter().enumerate() {
+                if i > 0 {
+                    out.push(',');
+                }
+                out.push_str(&serde_json::Value::String(key.clone()).to_string());
+                out.push(':');
+                write_canonical(&object[key], out);
+            }
+            out.push('}');
+        }
+        serde_json::Value::Array(items) => {
+            out.push('[');
+            for (i, item) in items.iter().enumerate() {
+                if i > 0 {
+                    out.push(',');
+                }
+                write_canonical(item, out);
+            }
+            out.push(']');
+        }
+        scalar => out.push_str(&scalar.to_string()),
+    }
+}
+
+fn to_hex(bytes: &[u8]) -> String {
+    bytes.iter().map(|b| format!("{:02x}", b)).collect()
+}
+
+fn from_hex(hex: &str) -> Result<Vec<u8>, String> {
+    if !hex.len().is_multiple_of(2) || !hex.is_ascii() {
+        return Err("Invalid hex string".to_string());
+    }
+    (0..hex.len())
+        .step_by(2)
+        .map(|i| u8::from_str_radix(&hex[i..i + 2], 16).map_err(|_| "Invalid hex string".into()))
+        .collect()
+}
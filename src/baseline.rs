@@ -0,0 +1,109 @@
+use sha2::{Digest, Sha256};
+use std::path::Path;
+
+use crate::types::{Finding, IntentVerificationResult};
+
+/// Default file name of a baseline at the repository root
+pub const BASELINE_FILE_NAME: &str = ".intent-verify-baseline.json";
+
+/// Known, accepted findings that should not fail verification
+///
+/// Enables gradual adoption on legacy repositories: existing issues are recorded once and
+/// reported as suppressed afterwards, while new issues still count.
+#[derive(Debug, Clone, Default, serde::Serialize, serde::Deserialize)]
+pub struct Baseline {
+    pub entries: Vec<BaselineEntry>,
+}
+
+/// A single accepted finding
+#[derive(Debug, Clone, PartialEq, serde::Serialize, serde::Deserialize)]
+pub struct BaselineEntry {
+    /// See [`finding_fingerprint`]
+    pub fingerprint: String,
+    pub rule: String,
+    pub file_path: Option<String>,
+    /// Why the finding was accepted
+    #[serde(default)]
+    pub reason: Option<String>,
+}
+
+impl Baseline {
+    /// Load a baseline from a JSON file
+    pub fn load(path: impl AsRef<Path>) -> Result<Self, Box<dyn std::error::Error>> {
+        let content = std::fs::read_to_string(path)?;
+        Ok(serde_json::from_str(&content)?)
+    }
+
+    /// Write the baseline as pretty-printed JSON
+    pub fn save(&self, path: impl AsRef<Path>) -> Result<(), Box<dyn std::error::Error>> {
+        std::fs::write(path, serde_json::to_string_pretty(self)?)?;
+        Ok(())
+    }
+
+    /// Build a baseline accepting all the given findings
+    pub fn from_findings(findings: &[Finding]) -> Self {
+        let mut baseline = Baseline::default();
+        for finding in findings {
+            let fingerprint = finding_fingerprint(finding);
+            if !baseline.contains(&fingerprint) {
+                baseline.entries.push(BaselineEntry {
+                    fingerprint,
+                    rule: finding.rule.clone(),
+                    file_path: finding.file_path.clone(),
+                    reason: None,
+                });
+            }
+        }
+        baseline
+    }
+
+    /// Whether a fingerprint is part of the baseline
+    pub fn contains(&self, fingerprint: &str) -> bool {
+        self.entries.iter().any(|e| e.fingerprint == fingerprint)
+    }
+
+    /// Mark findings of `result` that match the baseline as suppressed
+    ///
+    /// Returns the number of suppressed findings.
+    pub fn apply(&self, result: &mut IntentVerificationResult) -> usize {
+        let mut suppressed = 0;
+        for finding in &mut result.findings {
+            if self.contains(&finding_fingerprint(finding)) {
+                finding.suppressed = true;
+                suppressed += 1;
+            }
+        }
+        suppressed
+    }
+}
+
+/// Stable fingerprint of a finding: hash of file, rule and offending content
+///
+/// Line numbers are deliberately left out so the fingerprint survives unrelated edits
+/// that shift the code. The content is `content_hash` when set, since snippets of secrets
+/// are redacted, otherwise the snippet or, without one, the message.
+pub fn finding_fingerprint(finding: &Finding) -> String {
+    let content = match &finding.content_hash {
+        Some(hash) => hash.clone(),
+        None => finding
+            .snippet
+            .as_deref()
+            .unwrap_or(&finding.message)
+            .split_whitespace()
+            .collect::<Vec<_>>()
+            .join(" "),
+    };
+
+    let mut hasher = Sha256::new();
+    hasher.update(finding.file_path.as_deref().unwrap_or("").as_bytes());
+    hasher.update([0]);
+    hasher.update(finding.rule.as_bytes());
+    hasher.update([0]);
+    hasher.update(content.as_bytes());
+
+    hasher
+        .finalize()
+        .iter()
+        .map(|b| format!("{:02x}", b))
+        .collect()
+}
@@ -0,0 +1,194 @@
+#[cfg(feature = "git")]
+use std::collections::HashMap;
+use std::error::Error;
+use std::path::Path;
+
+#[cfg(feature = "git")]
+use futures::stream::{self, StreamExt};
+
+#[cfg(feature = "git")]
+use crate::git::{mirror_repository, spawn_git};
+#[cfg(feature = "git")]
+use crate::openai::verify_intent_with_options;
+#[cfg(feature = "git")]
+use crate::options::AnalysisOptions;
+use crate::types::IntentVerificationResult;
+
+/// One verification listed in a batch manifest
+#[derive(Debug, Clone, PartialEq, serde::Serialize, serde::Deserialize)]
+pub struct BatchJob {
+    /// Label used in the summary (the job's position when `None`)
+    #[serde(default)]
+    pub name: Option<String>,
+    /// Repository containing the changes
+    pub repo_url: String,
+    pub base: String,
+    pub head: String,
+    pub intent: String,
+    /// Repository containing the tests (`repo_url` when `None`)
+    #[serde(default)]
+    pub test_repo_url: Option<String>,
+    /// Commit to read the tests from (`head` when `None`)
+    #[serde(default)]
+    pub test_commit: Option<String>,
+}
+
+/// A list of verifications to run together, read from JSON or TOML
+#[derive(Debug, Clone, Default, PartialEq, serde::Serialize, serde::Deserialize)]
+pub struct BatchManifest {
+    /// Number of verifications run at the same time (one at a time when `None`)
+    #[serde(default)]
+    pub concurrency: Option<usize>,
+    pub jobs: Vec<BatchJob>,
+}
+
+impl BatchManifest {
+    pub fn from_json(json: &str) -> Result<Self, Box<dyn Error>> {
+        Ok(serde_json::from_str(json)?)
+    }
+
+    /// Parse a TOML manifest, with jobs as `[[jobs]]` tables
+    pub fn from_toml(text: &str) -> Result<Self, Box<dyn Error>> {
+        Ok(toml::from_str(text)?)
+    }
+
+    /// Load a manifest, treating `.toml` files as TOML and anything else as JSON
+    pub fn load(path: impl AsRef<Path>) -> Result<Self, Box<dyn Error>> {
+        let path = path.as_ref();
+        let text = std::fs::read_to_string(path)?;
+        match path.extension().and_then(|ext| ext.to_str()) {
+            Some("toml") => Self::from_toml(&text),
+            _ => Self::from_json(&text),
+        }
+    }
+}
+
+/// Outcome of one job in a batch
+#[derive(Debug, Clone, serde::Serialize, serde::Deserialize)]
+pub struct BatchOutcome {
+    pub name: String,
+    pub repo_url: String,
+    pub head: String,
+    /// Set when the verification completed
+    pub result: Option<IntentVerificationResult>,
+    /// Set when the verification could not run
+    pub error: Option<String>,
+}
+
+/// Aggregate of a batch run, with one outcome per job in manifest order
+#[derive(Debug, Clone, serde::Serialize, serde::Deserialize)]
+pub struct BatchSummary {
+    pub total: usize,
+    pub fulfilled: usize,
+    pub not_fulfilled: usize,
+    /// Jobs that could not run
+    pub failed: usize,
+    pub outcomes: Vec<BatchOutcome>,
+}
+
+/// Run every job in a manifest and summarize the results
+///
+/// Each distinct repository is fetched once into a mirror under `options.cache_dir`, and
+/// jobs clone from that mirror instead of the remote. Failing jobs are recorded in the
+/// summary without stopping the others.
+#[cfg(feature = "git")]
+pub async fn run_batch(
+    manifest: &BatchManifest,
+    api_key: &str,
+    model: Option<&str>,
+    base_url: Option<&str>,
+    options: &AnalysisOptions,
+) -> BatchSummary {
+    // Fetch every repository once up front
+    let mut mirrors: HashMap<&str, Result<String, String>> = HashMap::new();
+    for job in &manifest.jobs {
+        for url in [Some(&job.repo_url), job.test_repo_url.as_ref()]
+            .into_iter()
+            .flatten()
+        {
+            if mirrors.contains_key(url.as_str()) {
+                continue;
+            }
+            eprintln!("📦 Fetching {}", url);
+            let owned_url = url.clone();
+            let mirror = spawn_git(options, move |options| {
+                Ok(mirror_repository(&owned_url, options)?)
+            })
+            .await
+            .map(|path| path.to_string_lossy().into_owned())
+            .map_err(|e| format!("Failed to fetch {}: {}", url, e));
+            mirrors.insert(url.as_str(), mirror);
+        }
+    }
+
+    let concurrency = manifest.concurrency.unwrap_or(1).max(1);
+    let outcomes: Vec<BatchOutcome> = stream::iter(manifest.jobs.iter().enumerate())
+        .map(|(index, job)| {
+            let mirrors = &mirrors;
+            async move {
+                let name = job
+                    .name
+                    .clone()
+                    .unwrap_or_else(|| format!("job {}", index + 1));
+                let test_repo_url = job.test_repo_url.as_ref().unwrap_or(&job.repo_url);
+                let test_commit = job.test_commit.as_ref().unwrap_or(&job.head);
+
+                let outcome = match (
+                    &mirrors[job.repo_url.as_str()],
+                    &mirrors[test_repo_url.as_str()],
+                ) {
+                    (Ok(solution_mirror), Ok(test_mirror)) => {
+                        eprintln!("🔍 Verifying {}", name);
+                        verify_intent_with_options(
+                            test_mirror,
+                            test_commit,
+                            solution_mirror,
+                            &job.base,
+                            &job.head,
+                            &job.intent,
+                            api_key,
+                            model,
+                            base_url,
+                            options,
+                        )
+                        .await
+                        .map_err(|e| e.to_string())
+                    }
+                    (Err(e), _) | (_, Err(e)) => Err(e.clone()),
+                };
+
+                let (result, error) = match outcome {
+                    Ok(result) => (Some(result), None),
+                    Err(error) => (None, Some(error)),
+                };
+                BatchOutcome {
+                    name,
+                    repo_url: job.repo_url.clone(),
+                    head: job.head.clone(),
+                    result,
+                    error,
+                }
+            }
+        })
+        .buffered(concurrency)
+        .collect()
+        .await;
+
+    let count = |fulfilled: bool| {
+        outcomes
+            .iter()
+            .filter(|o| {
+                o.result
+                    .as_ref()
+                    .is_some_and(|r| r.is_intent_fulfilled == fulfilled)
+            })
+            .count()
+    };
+    BatchSummary {
+        total: outcomes.len(),
+        fulfilled: count(true),
+        not_fulfilled: count(false),
+        failed: outcomes.iter().filter(|o| o.error.is_some()).count(),
+        outcomes,
+    }
+}
@@ -0,0 +1,233 @@
+use std::path::Path;
+
+use globset::Glob;
+
+use crate::git::{ChangeType, FileChange};
+use crate::types::{Finding, Severity};
+use crate::utils::sha256_hex;
+
+/// Rule of the findings reported for binary files the `BinaryPolicy` doesn't allow
+pub const UNEXPECTED_BINARY_RULE: &str = "binary/unexpected-addition";
+
+/// Start of the text standing in for a binary file's content
+const BINARY_PLACEHOLDER: &str = "[Binary file";
+
+/// Size, type and digest of a binary file's content
+#[derive(Debug, Clone, PartialEq, Eq, serde::Serialize, serde::Deserialize)]
+pub struct BinaryMetadata {
+    pub mime_type: String,
+    /// Size in bytes
+    pub size: u64,
+    /// Lowercase hex SHA-256 digest of the content
+    pub sha256: String,
+}
+
+/// A changed binary file, with the metadata of both sides when known
+#[derive(Debug, Clone, PartialEq, serde::Serialize, serde::Deserialize)]
+pub struct BinaryChange {
+    pub file_path: String,
+    pub change_type: ChangeType,
+    /// From the new content, or the old one for deleted files
+    pub mime_type: String,
+    /// `None` for added files, or when only a bare `[Binary file]` placeholder is known
+    pub old: Option<BinaryMetadata>,
+    /// `None` for deleted files, or when only a bare `[Binary file]` placeholder is known
+    pub new: Option<BinaryMetadata>,
+    /// New size minus old size in bytes, counting a missing side as empty
+    pub size_delta: i64,
+}
+
+/// Binary files that may be added to the solution; any other added binary is reported as an
+/// [`UNEXPECTED_BINARY_RULE`] finding
+#[derive(Debug, Clone, PartialEq, serde::Serialize, serde::Deserialize)]
+#[serde(default)]
+pub struct BinaryPolicy {
+    /// Glob patterns of binary files that may be added, e.g. `assets/**/*.png`
+    pub allowed: Vec<String>,
+    /// Severity of the findings; executables and shared libraries are always reported as
+    /// `High` or above
+    pub severity: Severity,
+}
+
+impl Default for BinaryPolicy {
+    fn default() -> Self {
+        BinaryPolicy {
+            allowed: vec![],
+            severity: Severity::Medium,
+        }
+    }
+}
+
+/// Whether file content is the placeholder for a binary file
+pub fn is_binary_placeholder(content: &str) -> bool {
+    content.starts_with(BINARY_PLACEHOLDER)
+}
+
+/// Whether file content couldn't be read as text: a binary or non-UTF-8 file
+pub(crate) fn is_unreadable(content: &str) -> bool {
+    is_binary_placeholder(content) || content == "[Non-UTF8 content]"
+}
+
+/// The placeholder text for a binary file, carrying its metadata, e.g.
+/// `[Binary file: image/png, 1024 bytes, sha256 9f86...]`
+pub fn binary_placeholder(path: &str, data: &[u8]) -> String {
+    format!(
+        "{}: {}, {} bytes, sha256 {}]",
+        BINARY_PLACEHOLDER,
+        mime_type(path, data),
+        data.len(),
+        sha256_hex(data)
+    )
+}
+
+/// The metadata in a placeholder built by [`binary_placeholder`]; `None` for other content,
+/// including a bare `[Binary file]` placeholder
+pub fn binary_metadata(content: &str) -> Option<BinaryMetadata> {
+    let fields = content
+        .strip_prefix(BINARY_PLACEHOLDER)?
+        .strip_prefix(": ")?
+        .strip_suffix(']')?;
+    let mut parts = fields.split(", ");
+    let mime_type = parts.next()?.to_string();
+    let size = parts.next()?.strip_suffix(" bytes")?.parse().ok()?;
+    let sha256 = parts.next()?.strip_prefix("sha256 ")?.to_string();
+    Some(BinaryMetadata {
+        mime_type,
+        size,
+        sha256,
+    })
+}
+
+/// MIME type of a file, from its leading bytes or else its extension
+pub fn mime_type(path: &str, data: &[u8]) -> &'static str {
+    const SIGNATURES: &[(&[u8], &str)] = &[
+        (b"\x89PNG\r\n\x1a\n", "image/png"),
+        (b"\xff\xd8\xff", "image/jpeg"),
+        (b"GIF87a", "image/gif"),
+        (b"GIF89a", "image/gif"),
+        (b"%PDF-", "application/pdf"),
+        (b"PK\x03\x04", "application/zip"),
+        (b"\x1f\x8b", "application/gzip"),
+        (b"\0asm", "application/wasm"),
+        (b"\x7fELF", "application/x-elf"),
+        (b"MZ", "application/vnd.microsoft.portable-executable"),
+        (b"\xcf\xfa\xed\xfe", "application/x-mach-binary"),
+        (b"\xce\xfa\xed\xfe", "application/x-mach-binary"),
+    ];
+    if let Some((_, mime)) = SIGNATURES
+        .iter()
+        .find(|(signature, _)| data.starts_with(signature))
+    {
+        return mime;
+    }
+    if data.len() >= 12 && &data[..4] == b"RIFF" && &data[8..12] == b"WEBP" {
+        return "image/webp";
+    }
+
+    let extension = Path::new(path)
+        .extension()
+        .and_then(|e| e.to_str())
+        .map(str::to_lowercase);
+    match extension.as_deref() {
+        Some("png") => "image/png",
+        Some("jpg" | "jpeg") => "image/jpeg",
+        Some("gif") => "image/gif",
+        Some("webp") => "image/webp",
+        Some("ico") => "image/vnd.microsoft.icon",
+        Some("pdf") => "application/pdf",
+        Some("zip" | "jar") => "application/zip",
+        Some("gz" | "tgz") => "application/gzip",
+        Some("wasm") => "application/wasm",
+        Some("so" | "o") => "application/x-elf",
+        Some("exe" | "dll") => "application/vnd.microsoft.portable-executable",
+        Some("dylib") => "application/x-mach-binary",
+        Some("woff2") => "font/woff2",
+        Some("ttf") => "font/ttf",
+        _ => "application/octet-stream",
+    }
+}
+
+/// Whether a MIME type is machine code: an executable, shared library or object file
+fn is_executable(mime_type: &str) -> bool {
+    matches!(
+        mime_type,
+        "application/x-elf"
+            | "application/vnd.microsoft.portable-executable"
+            | "application/x-mach-binary"
+    )
+}
+
+/// The binary files among the changes, with their size change
+pub fn binary_changes(file_changes: &[FileChange]) -> Vec<BinaryChange> {
+    file_changes
+        .iter()
+        .filter_map(|file_change| {
+            let old = file_change.old_content.as_deref();
+            let new = match file_change.status {
+                ChangeType::Deleted => None,
+                _ => file_change.content.as_deref(),
+            };
+            if ![old, new].into_iter().flatten().any(is_binary_placeholder) {
+                return None;
+            }
+            let old = old.and_then(binary_metadata);
+            let new = new.and_then(binary_metadata);
+            let size = |metadata: &Option<BinaryMetadata>| metadata.as_ref().map_or(0, |m| m.size);
+            Some(BinaryChange {
+                file_path: file_change.path.clone(),
+                change_type: file_change.status.clone(),
+                mime_type: new
+                    .as_ref()
+                    .or(old.as_ref())
+                    .map(|m| m.mime_type.clone())
+                    .unwrap_or_else(|| mime_type(&file_change.path, &[]).to_string()),
+                size_delta: size(&new) as i64 - size(&old) as i64,
+                old,
+                new,
+            })
+        })
+        .collect()
+}
+
+/// Findings for added binary files that no pattern of the policy allows
+pub fn unexpected_binary_findings(
+    binary_changes: &[BinaryChange],
+    policy: &BinaryPolicy,
+) -> Vec<Finding> {
+    // Invalid patterns allow nothing
+    let allowed: Vec<_> = policy
+        .allowed
+        .iter()
+        .filter_map(|pattern| Glob::new(pattern).ok())
+        .map(|glob| glob.compile_matcher())
+        .collect();
+
+    binary_changes
+        .iter()
+        .filter(|change| change.change_type == ChangeType::Added)
+        .filter(|change| !allowed.iter().any(|glob| glob.is_match(&change.file_path)))
+        .map(|change| {
+            let executable = is_executable(&change.mime_type);
+            Finding {
+                rule: UNEXPECTED_BINARY_RULE.to_string(),
+                severity: if executable {
+                    policy.severity.max(Severity::High)
+                } else {
+                    policy.severity
+                },
+                file_path: Some(change.file_path.clone()),
+                line: None,
+                snippet: None,
+                message: format!(
+                    "Unexpected binary {} added ({}, {} bytes)",
+                    if executable { "executable" } else { "file" },
+                    change.mime_type,
+                    change.size_delta
+                ),
+                suppressed: false,
+                cwe: None,
+                content_hash: None,
+            }
+        })
+        .collect()
+}
@@ -0,0 +1,105 @@
+use async_openai::types::chat::ChatChoiceLogprobs;
+use regex::Regex;
+use std::sync::LazyLock;
+
+use crate::types::IntentVerificationResult;
+
+/// How a result's confidence was derived from token log-probabilities, when
+/// [`crate::AnalysisOptions::calibrate_confidence`] is set and the provider returned them
+///
+/// The calibrated value is the probability of the verdict given how likely each file's
+/// `supports_intent` answer was, and replaces the result's confidence.
+#[derive(Debug, Clone, PartialEq, serde::Serialize, serde::Deserialize)]
+pub struct ConfidenceCalibration {
+    /// Confidence from the share of supporting files, used when there are no log-probabilities
+    pub raw: f32,
+    /// Mean of the confidences the model reported for the files, if it reported any
+    pub self_reported: Option<f32>,
+    /// Probability of the verdict (0.0-1.0)
+    pub calibrated: f32,
+    /// Number of files whose answer came with log-probabilities
+    pub files_with_logprobs: usize,
+}
+
+static SUPPORTS_INTENT: LazyLock<Regex> =
+    LazyLock::new(|| Regex::new(r#""supports_intent"\s*:\s*(true|false)"#).unwrap());
+
+/// Probability that the `supports_intent` answer of a reply is `true`, from the
+/// log-probability of the token the answer starts with
+pub(crate) fn support_probability(logprobs: &ChatChoiceLogprobs) -> Option<f32> {
+    let tokens = logprobs.content.as_ref()?;
+    let text: String = tokens.iter().map(|token| token.token.as_str()).collect();
+    let answer = SUPPORTS_INTENT.captures(&text)?.get(1)?;
+
+    let mut offset = 0;
+    let token = tokens.iter().find(|token| {
+        offset += token.token.len();
+        offset > answer.start()
+    })?;
+    let probability = token.logprob.exp().clamp(0.0, 1.0);
+    Some(if answer.as_str() == "true" {
+        probability
+    } else {
+        1.0 - probability
+    })
+}
+
+/// Probability that a file supports the intent when any of its blocks does
+pub(crate) fn any_block_supports(block_probabilities: &[f32]) -> f32 {
+    1.0 - block_probabilities
+        .iter()
+        .map(|probability| 1.0 - probability)
+        .product::<f32>()
+}
+
+/// Replace the result's confidence with the probability of its verdict, when some file
+/// analysis has a support probability
+///
+/// The verdict is "fulfilled" when at least one file and at least half of them support the
+/// intent; files are treated as independent, and those without a probability as certain.
+/// Refused and cancelled files are left out, like in the verdict.
+pub(crate) fn apply_calibration(result: &mut IntentVerificationResult) {
+    let files: Vec<_> = result
+        .files_analyzed
+        .iter()
+        .filter(|file| file.outcome.is_analyzed())
+        .collect();
+    let files_with_logprobs = files
+        .iter()
+        .filter(|file| file.support_probability.is_some())
+        .count();
+    if files_with_logprobs == 0 {
+        return;
+    }
+
+    // Distribution of the number of supporting files
+    let mut counts = vec![1.0f64];
+    for file in &files {
+        let p = file
+            .support_probability
+            .unwrap_or(if file.supports_intent { 1.0 } else { 0.0 }) as f64;
+        let mut next = vec![0.0; counts.len() + 1];
+        for (k, probability) in counts.iter().enumerate() {
+            next[k] += probability * (1.0 - p);
+            next[k + 1] += probability * p;
+        }
+        counts = next;
+    }
+    let needed = files.len().div_ceil(2).max(1);
+    let fulfilled = counts[needed..].iter().sum::<f64>() as f32;
+
+    let reported: Vec<f32> = files.iter().filter_map(|file| file.confidence).collect();
+    let calibrated = if result.is_intent_fulfilled {
+        fulfilled
+    } else {
+        1.0 - fulfilled
+    };
+    result.calibration = Some(ConfidenceCalibration {
+        raw: result.confidence,
+        self_reported: (!reported.is_empty())
+            .then(|| reported.iter().sum::<f32>() / reported.len() as f32),
+        calibrated: calibrated.clamp(0.0, 1.0),
+        files_with_logprobs,
+    });
+    result.confidence = calibrated.clamp(0.0, 1.0);
+}
@@ -0,0 +1,287 @@
+use std::sync::LazyLock;
+
+use regex::Regex;
+
+use crate::api_surface::{ApiItem, BreakingChange, detect_breaking_changes, public_api};
+use crate::criteria::changes_as_diff;
+use crate::git::{ChangeType, FileChange};
+use crate::openai::{
+    ask_openai_with_options, dry_run_prompt, observe_prompt, prompt_preview, record_exchange,
+    user_message,
+};
+use crate::options::AnalysisOptions;
+use crate::types::{PromptPreview, PromptStage, Warning, WarningKind};
+use crate::utils::is_test_path;
+
+/// Diff text passed to the model for the overview is cut off after this many characters
+const MAX_SUMMARY_DIFF_CHARS: usize = 20_000;
+
+/// An HTTP route declared in a source file
+#[derive(Debug, Clone, PartialEq, Eq, serde::Serialize, serde::Deserialize)]
+pub struct Endpoint {
+    /// Uppercase HTTP method, or `ANY` for routes that don't name one
+    pub method: String,
+    pub path: String,
+    pub file_path: String,
+}
+
+/// What the changes do to the code's interface, read from the diff before the intent is
+/// judged
+#[derive(Debug, Clone, Default, PartialEq, serde::Serialize, serde::Deserialize)]
+pub struct ChangeSummary {
+    /// Exported items that didn't exist before
+    #[serde(default)]
+    pub added: Vec<ApiItem>,
+    /// Exported items whose signature changed
+    #[serde(default)]
+    pub modified: Vec<BreakingChange>,
+    /// Exported items that no longer exist
+    #[serde(default)]
+    pub removed: Vec<ApiItem>,
+    #[serde(default)]
+    pub added_endpoints: Vec<Endpoint>,
+    #[serde(default)]
+    pub removed_endpoints: Vec<Endpoint>,
+    /// The model's prose overview of the changes; empty when it wasn't asked for or failed
+    #[serde(default)]
+    pub overview: String,
+}
+
+static RUST_ROUTE_ATTRIBUTE: LazyLock<Regex> =
+    LazyLock::new(|| Regex::new(r#"#\[(get|post|put|delete|patch)\(\s*"(/[^"]*)""#).unwrap());
+static ROUTE_WITH_METHOD: LazyLock<Regex> = LazyLock::new(|| {
+    Regex::new(r#"\.route\(\s*"(/[^"]*)"\s*,\s*(?:\w+::)*(get|post|put|delete|patch)\b"#).unwrap()
+});
+static METHOD_CALL: LazyLock<Regex> = LazyLock::new(|| {
+    Regex::new(r#"\.(get|post|put|delete|patch)\(\s*["'`](/[^"'`]*)["'`]"#).unwrap()
+});
+static PYTHON_ROUTE: LazyLock<Regex> =
+    LazyLock::new(|| Regex::new(r#"@\w+\.route\(\s*["'](/[^"']*)["']"#).unwrap());
+
+/// HTTP routes declared in a file: Rust route attributes (actix, rocket), axum `.route`
+/// calls, `app.get("/path")`-style registrations (Express, FastAPI) and Flask `@app.route`
+pub fn endpoints(path: &str, content: &str) -> Vec<Endpoint> {
+    let endpoint = |method: &str, route: &str| Endpoint {
+        method: method.to_uppercase(),
+        path: route.to_string(),
+        file_path: path.to_string(),
+    };
+    let mut found = Vec::new();
+    for captures in RUST_ROUTE_ATTRIBUTE.captures_iter(content) {
+        found.push(endpoint(&captures[1], &captures[2]));
+    }
+    for captures in ROUTE_WITH_METHOD.captures_iter(content) {
+        found.push(endpoint(&captures[2], &captures[1]));
+    }
+    for captures in METHOD_CALL.captures_iter(content) {
+        found.push(endpoint(&captures[1], &captures[2]));
+    }
+    if path.ends_with(".py") {
+        for captures in PYTHON_ROUTE.captures_iter(content) {
+            found.push(endpoint("any", &captures[1]));
+        }
+    }
+    found.dedup();
+    found
+}
+
+/// Added, changed and removed exported items and routes between the old and new content of
+/// the changed files
+///
+/// Exported items are compared like in [`detect_breaking_changes`]; routes are matched by
+/// method and path across files and only skip test files.
+pub fn summarize_changes(file_changes: &[FileChange]) -> ChangeSummary {
+    let breaking = detect_breaking_changes(file_changes);
+    let (modified, removed): (Vec<_>, Vec<_>) = breaking
+        .into_iter()
+        .partition(|change| change.after.is_some());
+
+    let library_files = file_changes.iter().filter(|fc| {
+        !is_test_path(&fc.path) && !fc.path.ends_with("main.rs") && !fc.path.contains("src/bin/")
+    });
+    let mut before = Vec::new();
+    let mut after = Vec::new();
+    for file_change in library_files {
+        if let Some(old) = &file_change.old_content {
+            before.extend(public_api(&file_change.path, old));
+        }
+        if let Some(new) = new_content(file_change) {
+            after.extend(public_api(&file_change.path, new));
+        }
+    }
+    let added = after
+        .into_iter()
+        .filter(|item| {
+            !before
+                .iter()
+                .any(|b| b.kind == item.kind && b.name == item.name)
+        })
+        .collect();
+
+    let mut endpoints_before = Vec::new();
+    let mut endpoints_after = Vec::new();
+    for file_change in file_changes.iter().filter(|fc| !is_test_path(&fc.path)) {
+        if let Some(old) = &file_change.old_content {
+            endpoints_before.extend(endpoints(&file_change.path, old));
+        }
+        if let Some(new) = new_content(file_change) {
+            endpoints_after.extend(endpoints(&file_change.path, new));
+        }
+    }
+    let missing_from = |endpoints: &[Endpoint], other: &[Endpoint]| -> Vec<Endpoint> {
+        endpoints
+            .iter()
+            .filter(|e| {
+                !other
+                    .iter()
+                    .any(|o| o.method == e.method && o.path == e.path)
+            })
+            .cloned()
+            .collect()
+    };
+
+    ChangeSummary {
+        added,
+        modified,
+        removed: removed.into_iter().map(|change| change.before).collect(),
+        added_endpoints: missing_from(&endpoints_after, &endpoints_before),
+        removed_endpoints: missing_from(&endpoints_before, &endpoints_after),
+        overview: String::new(),
+    }
+}
+
+fn new_content(file_change: &FileChange) -> Option<&String> {
+    match file_change.status {
+        ChangeType::Deleted => None,
+        _ => file_change.content.as_ref(),
+    }
+}
+
+impl ChangeSummary {
+    /// Whether no exported item or route changed
+    pub fn is_empty(&self) -> bool {
+        self.added.is_empty()
+            && self.modified.is_empty()
+            && self.removed.is_empty()
+            && self.added_endpoints.is_empty()
+            && self.removed_endpoints.is_empty()
+    }
+
+    /// A changelog draft in the "Keep a Changelog" layout: the overview, then `Added`,
+    /// `Changed` and `Removed` sections for the sections that have entries
+    pub fn to_changelog(&self) -> String {
+        let mut md = String::new();
+        if !self.overview.is_empty() {
+            md.push_str(&format!("{}\n\n", self.overview.trim()));
+        }
+        let item = |item: &ApiItem| format!("- `{}` ({})\n", item.signature, item.file_path);
+        let endpoint = |e: &Endpoint| format!("- `{} {}` ({})\n", e.method, e.path, e.file_path);
+
+        let added: String = self
+            .added
+            .iter()
+            .map(item)
+            .chain(self.added_endpoints.iter().map(endpoint))
+            .collect();
+        let changed: String = self
+            .modified
+            .iter()
+            .filter_map(|change| {
+                change.after.as_ref().map(|after| {
+                    format!(
+                        "- `{}` is now `{}` ({})\n",
+                        change.before.signature, after.signature, after.file_path
+                    )
+                })
+            })
+            .collect();
+        let removed: String = self
+            .removed
+            .iter()
+            .map(item)
+            .chain(self.removed_endpoints.iter().map(endpoint))
+            .collect();
+        for (heading, entries) in [("Added", added), ("Changed", changed), ("Removed", removed)] {
+            if !entries.is_empty() {
+                md.push_str(&format!("### {}\n\n{}\n", heading, entries));
+            }
+        }
+        md
+    }
+}
+
+/// The interface changes, with an overview written by the model when
+/// `AnalysisOptions::change_summary` is set
+///
+/// A failed overview is recorded in `warnings` and leaves it empty. In a dry run the prompt
+/// is recorded instead.
+#[allow(clippy::too_many_arguments)]
+pub(crate) async fn generate_change_summary(
+    file_changes: &[FileChange],
+    api_key: &str,
+    model: Option<&str>,
+    base_url: Option<&str>,
+    options: &AnalysisOptions,
+    warnings: &mut Vec<Warning>,
+    prompts: &mut Vec<PromptPreview>,
+) -> ChangeSummary {
+    let mut summary = summarize_changes(file_changes);
+    let prompt = overview_prompt(&summary, file_changes, options);
+    if options.dry_run {
+        dry_run_prompt(
+            options,
+            prompts,
+            prompt_preview(PromptStage::ChangeSummary, None, &[user_message(&prompt)]),
+        );
+        return summary;
+    }
+
+    observe_prompt(options, PromptStage::ChangeSummary, &prompt);
+    match ask_openai_with_options(&prompt, api_key, model, base_url, options).await {
+        Ok(overview) => {
+            record_exchange(options, PromptStage::ChangeSummary, &prompt, &overview);
+            summary.overview = overview.trim().to_string();
+        }
+        Err(e) => warnings.push(Warning {
+            kind: WarningKind::ChangeSummary,
+            file_path: None,
+            message: format!("Failed to summarize the changes: {}", e),
+        }),
+    }
+    summary
+}
+
+fn overview_prompt(
+    summary: &ChangeSummary,
+    file_changes: &[FileChange],
+    options: &AnalysisOptions,
+) -> String {
+    let mut diff = changes_as_diff(file_changes);
+    if diff.len() > MAX_SUMMARY_DIFF_CHARS {
+        let mut end = MAX_SUMMARY_DIFF_CHARS;
+        while !diff.is_char_boundary(end) {
+            end -= 1;
+        }
+        diff.truncate(end);
+        diff.push_str("\n[diff truncated]\n");
+    }
+    let interface = if summary.is_empty() {
+        "No exported items or routes changed.".to_string()
+    } else {
+        summary.to_changelog()
+    };
+
+    let mut prompt = format!(
+        "Summarize what the following code changes do, for a reviewer and as the start of a \
+         changelog entry. Describe the behavior that was added, changed or removed in a few \
+         short sentences; don't judge whether the changes are correct.\n\n\
+         INTERFACE CHANGES:\n{}\n\nDIFF:\n{}\n\n\
+         Respond with plain text, without headings.",
+        interface.trim(),
+        diff
+    );
+    if let Some(instruction) = options.language_instruction() {
+        prompt = format!("{}\n\n{}", prompt, instruction);
+    }
+    prompt
+}
@@ -0,0 +1,79 @@
+use std::error::Error;
+use std::path::{Path, PathBuf};
+
+use crate::git::FileChange;
+use crate::incremental::{AnalysisCache, CachedAnalysis};
+use crate::types::{FileIntentAnalysis, Finding, TestTargets};
+
+/// Intermediate results of a verification, written to disk as soon as each one is known, see
+/// [`crate::AnalysisOptions::checkpoint`]
+///
+/// Entries are keyed like the analysis cache, by file content, prompt version and analysis
+/// context, so a checkpoint from an unrelated run is never reused by mistake.
+pub(crate) struct Checkpoint {
+    path: PathBuf,
+    state: AnalysisCache,
+}
+
+impl Checkpoint {
+    /// Open the checkpoint at `path`, starting empty when it doesn't exist yet
+    pub(crate) fn open(path: impl AsRef<Path>) -> Result<Self, Box<dyn Error>> {
+        let path = path.as_ref().to_path_buf();
+        Ok(Checkpoint {
+            state: AnalysisCache::open(&path)?,
+            path,
+        })
+    }
+
+    pub(crate) fn targets(&self, key: &str) -> Option<TestTargets> {
+        self.state.targets(key)
+    }
+
+    pub(crate) fn save_targets(&self, key: &str, targets: &TestTargets) {
+        self.state.insert_targets(key, targets);
+        self.save();
+    }
+
+    pub(crate) fn get(
+        &self,
+        file_change: &FileChange,
+        prompt_version: &str,
+        context_hash: &str,
+    ) -> Option<CachedAnalysis> {
+        self.state.get(file_change, prompt_version, context_hash)
+    }
+
+    pub(crate) fn save_analysis(
+        &self,
+        file_change: &FileChange,
+        prompt_version: &str,
+        context_hash: &str,
+        analysis: &FileIntentAnalysis,
+        findings: &[Finding],
+    ) {
+        self.state.insert(
+            file_change,
+            prompt_version,
+            context_hash,
+            analysis,
+            findings,
+        );
+        self.save();
+    }
+
+    /// Remove the checkpoint once the verification no longer needs resuming
+    pub(crate) fn finish(self) {
+        if let Err(e) = std::fs::remove_file(&self.path)
+            && e.kind() != std::io::ErrorKind::NotFound
+        {
+            eprintln!("⚠️  Failed to remove the checkpoint: {}", e);
+        }
+    }
+
+    // A checkpoint only saves work on a rerun, so failing to write it doesn't fail this run
+    fn save(&self) {
+        if let Err(e) = self.state.save() {
+            eprintln!("⚠️  Failed to write the checkpoint: {}", e);
+        }
+    }
+}
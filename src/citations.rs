@@ -0,0 +1,99 @@
+use crate::git::FileChange;
+use crate::openai::strip_diff_markers;
+use crate::types::TestTargetsWithCode;
+use crate::utils::locate_snippet;
+
+/// A claim of a file analysis backed by code quoted from a file the model was shown
+#[derive(Debug, Clone, PartialEq, serde::Serialize, serde::Deserialize)]
+pub struct Citation {
+    /// What the quote supports, as stated in the reasoning
+    pub claim: String,
+    pub file_path: String,
+    /// First line of the quote (1-based); corrected to where the quote is when the model's
+    /// line numbers were off
+    pub start_line: usize,
+    /// Last line of the quote (1-based, inclusive)
+    pub end_line: usize,
+    /// Code quoted from the file
+    pub quote: String,
+    /// Whether the quote was found in the cited file; unverified citations may be hallucinated
+    pub verified: bool,
+}
+
+/// The `citations` of a file analysis response, each checked against the file it cites
+///
+/// Quotes may come from the analyzed file or from the test targets. Quotes of test functions
+/// read without their file are checked against the function, keeping the cited lines.
+pub(crate) fn verify_citations(
+    json: &serde_json::Value,
+    file_change: &FileChange,
+    targets_with_code: &TestTargetsWithCode,
+) -> Vec<Citation> {
+    let Some(citations) = json["citations"].as_array() else {
+        return vec![];
+    };
+    citations
+        .iter()
+        .filter_map(|citation| {
+            let quote = citation["quote"]
+                .as_str()
+                .filter(|q| !q.trim().is_empty())?;
+            let file_path = citation["file"]
+                .as_str()
+                .unwrap_or(&file_change.path)
+                .trim_start_matches("./");
+            let line = |key: &str| citation[key].as_u64().unwrap_or(0) as usize;
+            let (start_line, end_line) =
+                (line("start_line"), line("end_line").max(line("start_line")));
+
+            let located = if file_path == file_change.path {
+                file_change.content.as_deref().and_then(|content| {
+                    locate_near(content, quote, start_line, end_line).or_else(|| {
+                        locate_near(content, &strip_diff_markers(quote), start_line, end_line)
+                    })
+                })
+            } else if let Some(file) = targets_with_code
+                .file_contents
+                .iter()
+                .find(|file| file.path == file_path)
+            {
+                locate_near(&file.content, quote, start_line, end_line)
+            } else {
+                targets_with_code
+                    .function_contents
+                    .iter()
+                    .filter(|function| function.file_path.as_deref() == Some(file_path))
+                    .filter_map(|function| function.content.as_deref())
+                    .any(|content| locate_snippet(content, quote).is_some())
+                    .then_some((start_line, end_line))
+            };
+
+            let (start_line, end_line) = located.unwrap_or((start_line, end_line));
+            Some(Citation {
+                claim: citation["claim"].as_str().unwrap_or_default().to_string(),
+                file_path: file_path.to_string(),
+                start_line,
+                end_line,
+                quote: quote.to_string(),
+                verified: located.is_some(),
+            })
+        })
+        .collect()
+}
+
+/// Lines of `quote` in `content`, preferring a match within the cited lines
+fn locate_near(
+    content: &str,
+    quote: &str,
+    start_line: usize,
+    end_line: usize,
+) -> Option<(usize, usize)> {
+    let lines: Vec<&str> = content.lines().collect();
+    if start_line >= 1 && end_line <= lines.len() {
+        let cited = lines[start_line - 1..end_line].join("\n");
+        if let Some((start, end)) = locate_snippet(&cited, quote) {
+            return Some((start_line - 1 + start, start_line - 1 + end));
+        }
+    }
+    locate_snippet(content, quote)
+}
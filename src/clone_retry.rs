@@ -0,0 +1,106 @@
+use std::time::Duration;
+
+#[cfg(feature = "git")]
+use crate::options::AnalysisOptions;
+
+/// How clones and fetches are retried when the network fails mid-transfer
+///
+/// The wait before each retry doubles, from `initial_backoff_ms` up to `max_backoff_ms`.
+#[derive(Debug, Clone, PartialEq, serde::Serialize, serde::Deserialize)]
+#[serde(default)]
+pub struct CloneRetryConfig {
+    /// Attempts in total, the first included; 1 disables retries
+    pub max_attempts: u32,
+    /// Wait before the first retry, in milliseconds
+    pub initial_backoff_ms: u64,
+    /// Longest wait between two attempts, in milliseconds
+    pub max_backoff_ms: u64,
+}
+
+impl Default for CloneRetryConfig {
+    fn default() -> Self {
+        CloneRetryConfig {
+            max_attempts: 4,
+            initial_backoff_ms: 1_000,
+            max_backoff_ms: 30_000,
+        }
+    }
+}
+
+impl CloneRetryConfig {
+    /// Wait before the retry following the `attempt`-th (1-based) failed attempt
+    pub fn backoff(&self, attempt: u32) -> Duration {
+        let factor = 2u64.saturating_pow(attempt.saturating_sub(1));
+        Duration::from_millis(
+            self.initial_backoff_ms
+                .saturating_mul(factor)
+                .min(self.max_backoff_ms),
+        )
+    }
+}
+
+/// Whether a git error may go away on its own: network, TLS, SSH and HTTP failures
+///
+/// Failed authentication, rejected certificates and HTTP statuses blaming the request (like
+/// 404) fail the same way every time; 408, 429 and 5xx are worth retrying.
+#[cfg(feature = "git")]
+pub fn is_transient(error: &git2::Error) -> bool {
+    use git2::{ErrorClass, ErrorCode};
+
+    if matches!(error.code(), ErrorCode::Auth | ErrorCode::Certificate) {
+        return false;
+    }
+    match error.class() {
+        ErrorClass::Net | ErrorClass::Ssl | ErrorClass::Ssh => true,
+        ErrorClass::Http => match http_status(error.message()) {
+            Some(status) => status == 408 || status == 429 || status >= 500,
+            None => true,
+        },
+        _ => false,
+    }
+}
+
+/// Status code of libgit2's "unexpected http status code: 502" messages
+#[cfg(feature = "git")]
+fn http_status(message: &str) -> Option<u16> {
+    let (_, rest) = message.split_once("status code: ")?;
+    let digits: String = rest.chars().take_while(char::is_ascii_digit).collect();
+    digits.parse().ok()
+}
+
+/// Run a clone or fetch until it succeeds, fails for good (see [`is_transient`]) or runs out
+/// of `options.clone_retry` attempts
+///
+/// Cancelling the verification stops retrying; the last error is returned then.
+#[cfg(feature = "git")]
+pub(crate) fn retry_transient<T>(
+    options: &AnalysisOptions,
+    repo_url: &str,
+    mut operation: impl FnMut() -> Result<T, git2::Error>,
+) -> Result<T, git2::Error> {
+    let config = options.clone_retry();
+    let mut attempt = 1;
+    loop {
+        match operation() {
+            Err(e)
+                if attempt < config.max_attempts
+                    && is_transient(&e)
+                    && options.check_cancelled().is_ok() =>
+            {
+                let backoff = config.backoff(attempt);
+                eprintln!(
+                    "🔁 Fetching {} failed ({}), retrying in {:.1}s (attempt {} of {})",
+                    repo_url,
+                    e.message(),
+                    backoff.as_secs_f32(),
+                    attempt + 1,
+                    config.max_attempts
+                );
+                options.observe(|observer| observer.on_clone_retry(repo_url, attempt, &e));
+                std::thread::sleep(backoff);
+                attempt += 1;
+            }
+            result => return result,
+        }
+    }
+}
@@ -1,3 +1,6 @@
+#[cfg(feature = "git")]
+use crate::git::{ChangeType, FileChange};
+
 /// Check if a filename is a source code file (TypeScript, Rust, Python)
 pub fn is_source_file_by_name(filename: &str) -> bool {
     filename.ends_with(".rs")
@@ -29,6 +32,48 @@ pub fn extract_function_from_content_with_name(
     }
 }
 
+/// The changes to `function_name` within `file_changes`, one per file defining it, with the
+/// function's body before and after as the contents
+///
+/// Files where the function's body didn't change are left out.
+#[cfg(feature = "git")]
+pub(crate) fn function_changes(
+    file_changes: &[FileChange],
+    function_name: &str,
+) -> Vec<FileChange> {
+    file_changes
+        .iter()
+        .filter_map(|file_change| {
+            let extract = |content: &Option<String>| {
+                content.as_deref().and_then(|content| {
+                    extract_function_from_content_with_name(
+                        content,
+                        function_name,
+                        &file_change.path,
+                    )
+                })
+            };
+            let (before, after) = (
+                extract(&file_change.old_content),
+                extract(&file_change.content),
+            );
+            let status = match (&before, &after) {
+                (Some(before), Some(after)) if before == after => return None,
+                (Some(_), Some(_)) => ChangeType::Modified,
+                (None, Some(_)) => ChangeType::Added,
+                (Some(_), None) => ChangeType::Deleted,
+                (None, None) => return None,
+            };
+            Some(FileChange {
+                path: file_change.path.clone(),
+                status,
+                content: after,
+                old_content: before,
+            })
+        })
+        .collect()
+}
+
 /// Extract Rust function
 fn extract_rust_function(content: &str, function_name: &str) -> Option<String> {
     // Look for function definitions: pub fn, async fn, fn
@@ -173,11 +218,11 @@ fn extract_javascript_function(content: &str, function_name: &str) -> Option<Str
     ];
 
     for pattern in &patterns {
-        if let Some(start_pos) = content.find(pattern) {
-            if let Some(brace_start) = content[start_pos..].find('{') {
-                let func_end = find_matching_brace(content, start_pos + brace_start)?;
-                return Some(content[start_pos..func_end].to_string());
-            }
+        if let Some(start_pos) = content.find(pattern)
+            && let Some(brace_start) = content[start_pos..].find('{')
+        {
+            let func_end = find_matching_brace(content, start_pos + brace_start)?;
+            return Some(content[start_pos..func_end].to_string());
         }
     }
 
@@ -0,0 +1,174 @@
+use std::collections::BTreeMap;
+
+use globset::{GlobBuilder, GlobMatcher};
+
+#[cfg(feature = "git")]
+use crate::git::{read_repository_file, spawn_git};
+#[cfg(feature = "git")]
+use crate::options::AnalysisOptions;
+use crate::snapshot::RepoSnapshot;
+use crate::types::{FileIntentAnalysis, IntentVerificationResult};
+#[cfg(feature = "git")]
+use crate::types::{Warning, WarningKind};
+
+/// Where GitHub looks for the CODEOWNERS file, in order
+pub const CODEOWNERS_PATHS: [&str; 3] = [".github/CODEOWNERS", "CODEOWNERS", "docs/CODEOWNERS"];
+
+/// One line of a CODEOWNERS file
+#[derive(Debug, Clone)]
+struct OwnerRule {
+    matchers: Vec<GlobMatcher>,
+    owners: Vec<String>,
+}
+
+/// Owners of a repository's paths, parsed from its CODEOWNERS file
+#[derive(Debug, Clone, Default)]
+pub struct CodeOwners {
+    rules: Vec<OwnerRule>,
+}
+
+impl CodeOwners {
+    /// Parse a CODEOWNERS file; lines with an invalid pattern are skipped
+    ///
+    /// Patterns follow GitHub's rules: a leading `/` or a `/` inside anchors the pattern at the
+    /// repository root, a trailing `/` matches a directory's contents, and a pattern without
+    /// owners leaves the matching paths unowned.
+    pub fn parse(content: &str) -> Self {
+        let rules = content
+            .lines()
+            .filter_map(|line| {
+                let line = line.split_once(" #").map_or(line, |(rule, _)| rule).trim();
+                if line.is_empty() || line.starts_with('#') {
+                    return None;
+                }
+                let mut parts = line.split_whitespace();
+                let matchers = pattern_matchers(parts.next()?)?;
+                Some(OwnerRule {
+                    matchers,
+                    owners: parts.map(str::to_string).collect(),
+                })
+            })
+            .collect();
+        CodeOwners { rules }
+    }
+
+    /// The CODEOWNERS file of a snapshot, if it has one
+    pub fn from_snapshot(snapshot: &RepoSnapshot) -> Option<Self> {
+        CODEOWNERS_PATHS
+            .iter()
+            .find_map(|path| snapshot.files.get(*path))
+            .map(|content| CodeOwners::parse(content))
+    }
+
+    /// Owners of `path`; the last matching line wins
+    pub fn owners_of(&self, path: &str) -> &[String] {
+        self.rules
+            .iter()
+            .rev()
+            .find(|rule| rule.matchers.iter().any(|m| m.is_match(path)))
+            .map_or(&[], |rule| &rule.owners)
+    }
+}
+
+/// Glob matchers for a CODEOWNERS pattern
+fn pattern_matchers(pattern: &str) -> Option<Vec<GlobMatcher>> {
+    let directory = pattern.ends_with('/');
+    let trimmed = pattern.trim_end_matches('/');
+    let anchored = trimmed.starts_with('/') || trimmed.contains('/');
+    let trimmed = trimmed.trim_start_matches('/');
+    let base = if anchored {
+        trimmed.to_string()
+    } else {
+        format!("**/{}", trimmed)
+    };
+
+    let mut globs = Vec::new();
+    if !directory {
+        globs.push(base.clone());
+    }
+    // A directory owns everything below it, but `docs/*` only covers the files directly in docs
+    if !trimmed.ends_with("/*") {
+        globs.push(format!("{}/**", base));
+    }
+    globs
+        .iter()
+        .map(|glob| {
+            GlobBuilder::new(glob)
+                .literal_separator(true)
+                .build()
+                .map(|glob| glob.compile_matcher())
+                .ok()
+        })
+        .collect()
+}
+
+/// Set the owners of every analyzed file
+pub fn apply_code_owners(result: &mut IntentVerificationResult, code_owners: &CodeOwners) {
+    apply_code_owners_under(result, code_owners, "");
+}
+
+/// Set the owners of the analyzed files under `prefix`, whose paths are relative to it in
+/// `code_owners`
+pub(crate) fn apply_code_owners_under(
+    result: &mut IntentVerificationResult,
+    code_owners: &CodeOwners,
+    prefix: &str,
+) {
+    for analysis in &mut result.files_analyzed {
+        if let Some(path) = analysis.file_path.strip_prefix(prefix) {
+            analysis.owners = code_owners.owners_of(path).to_vec();
+        }
+    }
+}
+
+/// Analyzed files grouped by owner, sorted by owner, with unowned files last
+///
+/// A file with several owners appears in each of their groups.
+pub fn files_by_owner(
+    result: &IntentVerificationResult,
+) -> Vec<(Option<&str>, Vec<&FileIntentAnalysis>)> {
+    let mut owned: BTreeMap<&str, Vec<&FileIntentAnalysis>> = BTreeMap::new();
+    let mut unowned = Vec::new();
+    for analysis in &result.files_analyzed {
+        if analysis.owners.is_empty() {
+            unowned.push(analysis);
+        }
+        for owner in &analysis.owners {
+            owned.entry(owner).or_default().push(analysis);
+        }
+    }
+
+    let mut groups: Vec<_> = owned
+        .into_iter()
+        .map(|(owner, files)| (Some(owner), files))
+        .collect();
+    if !unowned.is_empty() {
+        groups.push((None, unowned));
+    }
+    groups
+}
+
+/// Read the CODEOWNERS file of `repo_url` at `commit`, turning failures into a warning
+#[cfg(feature = "git")]
+pub(crate) async fn read_code_owners(
+    repo_url: &str,
+    commit: &str,
+    options: &AnalysisOptions,
+) -> Result<Option<CodeOwners>, Warning> {
+    let (url, rev) = (repo_url.to_string(), commit.to_string());
+    spawn_git(options, move |options| {
+        Ok(read_repository_file(
+            &url,
+            &rev,
+            &CODEOWNERS_PATHS,
+            options,
+        )?)
+    })
+    .await
+    .map(|content| content.map(|content| CodeOwners::parse(&content)))
+    .map_err(|e| Warning {
+        kind: WarningKind::CodeOwners,
+        file_path: None,
+        message: format!("Failed to read CODEOWNERS of {}: {}", repo_url, e),
+    })
+}
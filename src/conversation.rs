@@ -0,0 +1,245 @@
+use crate::openai::{assistant_message, chat_with_options, prompt_preview, user_message};
+use crate::options::AnalysisOptions;
+use crate::types::{IntentVerificationResult, PromptMessage, PromptStage};
+use crate::utils::extract_json_from_response;
+
+/// An answer to a follow-up question, tied to the result it is about
+#[derive(Debug, Clone, PartialEq, serde::Serialize, serde::Deserialize)]
+pub struct FollowUpAnswer {
+    pub question: String,
+    pub answer: String,
+    /// Analyzed files of the result the answer refers to
+    #[serde(default)]
+    pub files: Vec<String>,
+    /// Whether the model no longer stands by the result's verdict after reconsidering it
+    #[serde(default)]
+    pub verdict_changed: bool,
+}
+
+/// Follow-up questions about a verification result, asked with the result and earlier
+/// questions and answers as context
+///
+/// ```no_run
+/// # async fn example(result: intent_verification::IntentVerificationResult) {
+/// use intent_verification::{AnalysisOptions, VerificationConversation};
+///
+/// let mut conversation = VerificationConversation::new(
+///     result,
+///     "The sum function adds two numbers",
+///     "api-key",
+///     None,
+///     None,
+///     &AnalysisOptions::default(),
+/// );
+/// let answer = conversation
+///     .ask("Why doesn't src/lib.rs support the intent?")
+///     .await
+///     .unwrap();
+/// println!("{} ({:?})", answer.answer, answer.files);
+/// # }
+/// ```
+#[derive(Debug, Clone)]
+pub struct VerificationConversation {
+    result: IntentVerificationResult,
+    user_intent: String,
+    api_key: String,
+    model: Option<String>,
+    base_url: Option<String>,
+    options: AnalysisOptions,
+    /// The raw exchanges after the context, alternating between `user` and `assistant`
+    history: Vec<PromptMessage>,
+    answers: Vec<FollowUpAnswer>,
+}
+
+impl VerificationConversation {
+    /// Start a conversation about `result`, the verification of `user_intent`
+    pub fn new(
+        result: IntentVerificationResult,
+        user_intent: &str,
+        api_key: &str,
+        model: Option<&str>,
+        base_url: Option<&str>,
+        options: &AnalysisOptions,
+    ) -> Self {
+        VerificationConversation {
+            result,
+            user_intent: user_intent.to_string(),
+            api_key: api_key.to_string(),
+            model: model.map(str::to_string),
+            base_url: base_url.map(str::to_string),
+            options: options.clone(),
+            history: Vec::new(),
+            answers: Vec::new(),
+        }
+    }
+
+    /// The result the questions are about
+    pub fn result(&self) -> &IntentVerificationResult {
+        &self.result
+    }
+
+    /// Answers so far, oldest first
+    pub fn answers(&self) -> &[FollowUpAnswer] {
+        &self.answers
+    }
+
+    /// Ask a question about the result; earlier questions and answers are sent along
+    ///
+    /// Replies that aren't the requested JSON are kept as the answer text, without files.
+    pub async fn ask(
+        &mut self,
+        question: &str,
+    ) -> Result<FollowUpAnswer, Box<dyn std::error::Error>> {
+        let question = question.trim();
+        if question.is_empty() {
+            return Err("The question is empty".into());
+        }
+        let prompt = format!(
+            "{}\n\nRespond in JSON format with:\n\
+             - answer (string): the answer, referring to the reasoning and code of the analysis\n\
+             - files (array): paths of the analyzed files the answer is about\n\
+             - verdict_changed (bool): true if, on reflection, the verdict above is wrong",
+            question
+        );
+
+        let mut messages = vec![
+            user_message(&self.context()),
+            assistant_message("Understood. I have the verification result. Ask me about it."),
+        ];
+        for message in &self.history {
+            messages.push(match message.role.as_str() {
+                "assistant" => assistant_message(&message.content),
+                _ => user_message(&message.content),
+            });
+        }
+        messages.push(user_message(&prompt));
+
+        let preview = prompt_preview(PromptStage::FollowUp, None, &messages);
+        self.options
+            .observe(|observer| observer.on_prompt_built(&preview));
+        let reply = chat_with_options(
+            messages,
+            &self.api_key,
+            self.model.as_deref(),
+            self.base_url.as_deref(),
+            &self.options,
+        )
+        .await?;
+        self.options
+            .observe(|observer| observer.on_llm_response(&preview, &reply));
+        if let Some(recorder) = &self.options.evidence {
+            recorder.record_exchange(preview, &reply);
+        }
+
+        let answer = self.parse_answer(question, &reply);
+        self.history.push(PromptMessage {
+            role: "user".to_string(),
+            content: prompt,
+        });
+        self.history.push(PromptMessage {
+            role: "assistant".to_string(),
+            content: reply,
+        });
+        self.answers.push(answer.clone());
+        Ok(answer)
+    }
+
+    /// The intent and the result, as the model is shown them before the first question
+    fn context(&self) -> String {
+        let result = &self.result;
+        let mut context = format!(
+            "You verified whether code changes fulfill this intent:\n\"{}\"\n\n\
+             VERDICT: {} (confidence {:.2})\n{}\n",
+            self.user_intent,
+            if result.is_intent_fulfilled {
+                "FULFILLED"
+            } else {
+                "NOT FULFILLED"
+            },
+            result.confidence,
+            result.explanation
+        );
+        if !result.overall_assessment.is_empty() {
+            context.push_str(&format!(
+                "\nOVERALL ASSESSMENT:\n{}\n",
+                result.overall_assessment.trim()
+            ));
+        }
+
+        context.push_str("\nFILE ANALYSES:\n");
+        for analysis in &result.files_analyzed {
+            context.push_str(&format!(
+                "\n- {} ({:?}): {}\n  Reasoning: {}\n",
+                analysis.file_path,
+                analysis.change_type,
+                if analysis.supports_intent {
+                    "SUPPORTS"
+                } else {
+                    "DOES NOT SUPPORT"
+                },
+                analysis.reasoning
+            ));
+            if !analysis.relevant_changes.is_empty() {
+                context.push_str(&format!(
+                    "  Relevant changes: {}\n",
+                    analysis.relevant_changes.join("; ")
+                ));
+            }
+            for citation in &analysis.citations {
+                context.push_str(&format!(
+                    "  Cited {}:{}-{}: {}\n",
+                    citation.file_path, citation.start_line, citation.end_line, citation.quote
+                ));
+            }
+        }
+
+        for criterion in &result.acceptance_criteria {
+            context.push_str(&format!(
+                "\nCRITERION {}: {}\n  {}\n",
+                if criterion.passed { "MET" } else { "UNMET" },
+                criterion.criterion,
+                criterion.evidence
+            ));
+        }
+        for warning in &result.warnings {
+            context.push_str(&format!("\nWARNING: {}\n", warning.message));
+        }
+        context
+    }
+
+    fn parse_answer(&self, question: &str, reply: &str) -> FollowUpAnswer {
+        let json = serde_json::from_str::<serde_json::Value>(&extract_json_from_response(reply))
+            .ok()
+            .filter(|json| json["answer"].is_string());
+        let Some(json) = json else {
+            return FollowUpAnswer {
+                question: question.to_string(),
+                answer: reply.trim().to_string(),
+                files: vec![],
+                verdict_changed: false,
+            };
+        };
+
+        // Only files of the result, so answers can be linked to their analyses
+        let files = json["files"]
+            .as_array()
+            .into_iter()
+            .flatten()
+            .filter_map(|file| file.as_str())
+            .map(|file| file.trim_start_matches("./"))
+            .filter(|file| {
+                self.result
+                    .files_analyzed
+                    .iter()
+                    .any(|analysis| analysis.file_path == *file)
+            })
+            .map(str::to_string)
+            .collect();
+        FollowUpAnswer {
+            question: question.to_string(),
+            answer: json["answer"].as_str().unwrap_or_default().to_string(),
+            files,
+            verdict_changed: json["verdict_changed"].as_bool().unwrap_or(false),
+        }
+    }
+}
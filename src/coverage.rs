@@ -0,0 +1,131 @@
+use std::collections::HashMap;
+
+use regex::Regex;
+
+use crate::code_parser::is_source_file_by_name;
+use crate::git::{ChangeType, FileChange, split_by_function};
+use crate::types::TestTargetsWithCode;
+use crate::utils::is_test_path;
+
+/// A changed function and the test targets that reference it
+#[derive(Debug, Clone, PartialEq, serde::Serialize, serde::Deserialize)]
+pub struct FunctionCoverage {
+    pub file_path: String,
+    pub function: String,
+    /// Test functions (by name) and test files (by path) whose code mentions the function
+    pub exercised_by: Vec<String>,
+}
+
+/// Whether the changed functions are actually exercised by the test targets named in the intent
+///
+/// Based on static analysis: a function counts as exercised when a test target's code refers
+/// to it by name, which can't see calls made through other functions or traits.
+#[derive(Debug, Clone, PartialEq, serde::Serialize, serde::Deserialize)]
+pub struct CoverageEvidence {
+    pub functions: Vec<FunctionCoverage>,
+    /// Share of changed functions exercised by at least one test target (1.0 when none changed)
+    pub exercised_ratio: f32,
+}
+
+impl CoverageEvidence {
+    /// Changed functions none of the test targets refer to
+    pub fn unexercised(&self) -> impl Iterator<Item = &FunctionCoverage> {
+        self.functions.iter().filter(|f| f.exercised_by.is_empty())
+    }
+}
+
+/// Map the functions changed in non-test source files to the test targets that reference them
+pub fn coverage_evidence(
+    targets_with_code: &TestTargetsWithCode,
+    file_changes: &[FileChange],
+) -> CoverageEvidence {
+    // Test code to search, labelled by function name or file path
+    let mut test_code: Vec<(&str, &str)> = targets_with_code
+        .function_contents
+        .iter()
+        .filter_map(|f| Some((f.name.as_str(), f.content.as_deref()?)))
+        .collect();
+    test_code.extend(
+        targets_with_code
+            .file_contents
+            .iter()
+            .filter(|f| f.error.is_none())
+            .map(|f| (f.path.as_str(), f.content.as_str())),
+    );
+
+    let mut functions = Vec::new();
+    for file_change in file_changes {
+        if file_change.status == ChangeType::Deleted
+            || !is_source_file_by_name(&file_change.path)
+            || is_test_path(&file_change.path)
+        {
+            continue;
+        }
+        for function in changed_functions(file_change) {
+            let reference = Regex::new(&format!(r"\b{}\b", regex::escape(&function))).unwrap();
+            let exercised_by = test_code
+                .iter()
+                .filter(|(label, code)| *label != function && reference.is_match(code))
+                .map(|(label, _)| label.to_string())
+                .collect();
+            functions.push(FunctionCoverage {
+                file_path: file_change.path.clone(),
+                function,
+                exercised_by,
+            });
+        }
+    }
+
+    let exercised = functions
+        .iter()
+        .filter(|f| !f.exercised_by.is_empty())
+        .count();
+    let exercised_ratio = if functions.is_empty() {
+        1.0
+    } else {
+        exercised as f32 / functions.len() as f32
+    };
+    CoverageEvidence {
+        functions,
+        exercised_ratio,
+    }
+}
+
+/// Names of the top-level functions added or modified by a file change
+fn changed_functions(file_change: &FileChange) -> Vec<String> {
+    let Some(content) = &file_change.content else {
+        return vec![];
+    };
+    let old_blocks: HashMap<String, String> = file_change
+        .old_content
+        .as_deref()
+        .map(function_blocks)
+        .unwrap_or_default()
+        .into_iter()
+        .collect();
+
+    let mut changed = Vec::new();
+    for (name, block) in function_blocks(content) {
+        let unchanged = old_blocks
+            .get(&name)
+            .is_some_and(|old| old.trim() == block.trim());
+        if !unchanged && !changed.contains(&name) {
+            changed.push(name);
+        }
+    }
+    changed
+}
+
+/// Function blocks of a source file, keyed by function name
+fn function_blocks(content: &str) -> Vec<(String, String)> {
+    let name =
+        Regex::new(r"^(?:pub\s+)?(?:export\s+)?(?:async\s+)?(?:fn|function|const|let)\s+(\w+)")
+            .unwrap();
+    split_by_function(content)
+        .into_iter()
+        .filter_map(|block| {
+            let function = name.captures(&block)?[1].to_string();
+            Some((function, block))
+        })
+        .collect()
+}
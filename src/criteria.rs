@@ -0,0 +1,280 @@
+use similar::TextDiff;
+
+use crate::binary::is_unreadable;
+use crate::git::{ChangeType, FileChange};
+use crate::openai::{
+    ask_openai_with_options, dry_run_prompt, observe_prompt, prompt_preview, record_exchange,
+    user_message,
+};
+use crate::options::AnalysisOptions;
+use crate::types::{IntentVerificationResult, PromptPreview, PromptStage, Warning, WarningKind};
+use crate::utils::extract_json_from_response;
+
+/// Diff text passed to the model is cut off after this many characters
+const MAX_DIFF_CHARS: usize = 60_000;
+
+/// A checkable acceptance criterion derived from the intent, and whether the changes meet it
+#[derive(Debug, Clone, PartialEq, serde::Serialize, serde::Deserialize)]
+pub struct CriterionResult {
+    pub criterion: String,
+    pub passed: bool,
+    /// The model's explanation, citing the code that meets (or fails) the criterion
+    pub evidence: String,
+    /// Changed files the evidence refers to
+    #[serde(default)]
+    pub files: Vec<String>,
+}
+
+/// Unified diff of the changes, skipping binary files
+pub fn changes_as_diff(file_changes: &[FileChange]) -> String {
+    let mut diff = String::new();
+    for file_change in file_changes {
+        let old = file_change.old_content.as_deref().unwrap_or("");
+        let new = match file_change.status {
+            ChangeType::Deleted => "",
+            _ => file_change.content.as_deref().unwrap_or(""),
+        };
+        if [old, new].iter().any(|c| is_unreadable(c)) {
+            diff.push_str(&format!("Binary file {} changed\n", file_change.path));
+            continue;
+        }
+        let old_path = format!("a/{}", file_change.path);
+        let new_path = format!("b/{}", file_change.path);
+        diff.push_str(
+            &TextDiff::from_lines(old, new)
+                .unified_diff()
+                .header(&old_path, &new_path)
+                .to_string(),
+        );
+    }
+
+    if diff.len() > MAX_DIFF_CHARS {
+        let mut end = MAX_DIFF_CHARS;
+        while !diff.is_char_boundary(end) {
+            end -= 1;
+        }
+        diff.truncate(end);
+        diff.push_str("\n[diff truncated]\n");
+    }
+    diff
+}
+
+/// Fold the per-criterion results into a verdict: any unmet criterion means the intent is
+/// not fulfilled
+pub fn merge_acceptance_criteria(
+    result: &mut IntentVerificationResult,
+    criteria: Vec<CriterionResult>,
+) {
+    if criteria.is_empty() {
+        return;
+    }
+    let met = criteria.iter().filter(|c| c.passed).count();
+    if met < criteria.len() {
+        result.is_intent_fulfilled = false;
+    }
+    result.explanation = format!(
+        "{}; {} of {} acceptance criteria met",
+        result.explanation,
+        met,
+        criteria.len()
+    );
+    result.acceptance_criteria = criteria;
+}
+
+/// Have the model break the intent into acceptance criteria, then check each against the diff
+///
+/// The criteria of `AnalysisOptions::previous_result` are checked again instead, when it has
+/// any, so runs can be compared criterion by criterion. Failures are recorded in `warnings`
+/// and leave the affected criteria out. In a dry run only the decomposition prompt is
+/// recorded, since the criteria depend on its answer.
+#[allow(clippy::too_many_arguments)]
+pub(crate) async fn check_acceptance_criteria(
+    user_intent: &str,
+    file_changes: &[FileChange],
+    api_key: &str,
+    model: Option<&str>,
+    base_url: Option<&str>,
+    options: &AnalysisOptions,
+    warnings: &mut Vec<Warning>,
+    prompts: &mut Vec<PromptPreview>,
+) -> Vec<CriterionResult> {
+    let diff = changes_as_diff(file_changes);
+    let previous_criteria = options.previous_criteria();
+    if !previous_criteria.is_empty() && options.dry_run {
+        for criterion in &previous_criteria {
+            dry_run_prompt(
+                options,
+                prompts,
+                prompt_preview(
+                    PromptStage::AcceptanceCriteria,
+                    None,
+                    &[user_message(&criterion_prompt(
+                        criterion,
+                        user_intent,
+                        &diff,
+                        options,
+                    ))],
+                ),
+            );
+        }
+        return vec![];
+    }
+    let decomposition = decomposition_prompt(user_intent, options);
+    if options.dry_run {
+        dry_run_prompt(
+            options,
+            prompts,
+            prompt_preview(
+                PromptStage::AcceptanceCriteria,
+                None,
+                &[user_message(&decomposition)],
+            ),
+        );
+        return vec![];
+    }
+
+    let criteria = if !previous_criteria.is_empty() {
+        previous_criteria
+    } else {
+        observe_prompt(options, PromptStage::AcceptanceCriteria, &decomposition);
+        match ask_openai_with_options(&decomposition, api_key, model, base_url, options)
+            .await
+            .map_err(|e| e.to_string())
+            .inspect(|reply| {
+                record_exchange(
+                    options,
+                    PromptStage::AcceptanceCriteria,
+                    &decomposition,
+                    reply,
+                )
+            })
+            .and_then(|reply| parse_criteria(&reply))
+        {
+            Ok(criteria) => criteria,
+            Err(e) => {
+                warnings.push(Warning {
+                    kind: WarningKind::AcceptanceCriteria,
+                    file_path: None,
+                    message: format!(
+                        "Failed to decompose the intent into acceptance criteria: {}",
+                        e
+                    ),
+                });
+                return vec![];
+            }
+        }
+    };
+
+    let mut results = Vec::new();
+    for criterion in criteria {
+        if options.check_cancelled().is_err() {
+            break;
+        }
+        let prompt = criterion_prompt(&criterion, user_intent, &diff, options);
+        observe_prompt(options, PromptStage::AcceptanceCriteria, &prompt);
+        let checked = ask_openai_with_options(&prompt, api_key, model, base_url, options)
+            .await
+            .map_err(|e| e.to_string())
+            .inspect(|reply| {
+                record_exchange(options, PromptStage::AcceptanceCriteria, &prompt, reply)
+            })
+            .and_then(|reply| parse_criterion_result(&criterion, &reply));
+        match checked {
+            Ok(result) => results.push(result),
+            Err(e) => warnings.push(Warning {
+                kind: WarningKind::AcceptanceCriteria,
+                file_path: None,
+                message: format!(
+                    "Failed to check acceptance criterion \"{}\": {}",
+                    criterion, e
+                ),
+            }),
+        }
+    }
+    results
+}
+
+fn decomposition_prompt(user_intent: &str, options: &AnalysisOptions) -> String {
+    let mut prompt = format!(
+        r#"Break the following intent down into discrete acceptance criteria. Each criterion must be a single, independently checkable statement about the behavior of the code. Use between 1 and 8 criteria and don't invent requirements the intent doesn't state.
+
+Intent: "{}"
+
+Respond ONLY in this strict JSON format:
+{{
+  "criteria": ["..."]
+}}"#,
+        user_intent
+    );
+    if let Some(clarifications) = options.clarification_instruction() {
+        prompt = format!("{}\n\n{}", prompt, clarifications);
+    }
+    prompt
+}
+
+fn criterion_prompt(
+    criterion: &str,
+    user_intent: &str,
+    diff: &str,
+    options: &AnalysisOptions,
+) -> String {
+    let mut prompt = format!(
+        r#"Decide whether the code changes below meet ONE acceptance criterion of the intent. Judge only this criterion.
+
+Intent: "{}"
+Acceptance criterion: "{}"
+
+Changes (unified diff):
+```diff
+{}
+```
+
+Respond ONLY in this strict JSON format:
+{{
+  "passed": true,
+  "evidence": "the code that meets or fails the criterion, and why",
+  "files": ["paths of the changed files the evidence refers to"]
+}}"#,
+        user_intent, criterion, diff
+    );
+    if let Some(instruction) = options.language_instruction() {
+        prompt = format!("{}\n\n{}", prompt, instruction);
+    }
+    prompt
+}
+
+fn parse_criteria(reply: &str) -> Result<Vec<String>, String> {
+    let json: serde_json::Value =
+        serde_json::from_str(&extract_json_from_response(reply)).map_err(|e| e.to_string())?;
+    let criteria: Vec<String> = json["criteria"]
+        .as_array()
+        .ok_or("Response has no criteria list")?
+        .iter()
+        .filter_map(|c| c.as_str())
+        .map(|c| c.trim().to_string())
+        .filter(|c| !c.is_empty())
+        .collect();
+    if criteria.is_empty() {
+        return Err("Response lists no criteria".to_string());
+    }
+    Ok(criteria)
+}
+
+fn parse_criterion_result(criterion: &str, reply: &str) -> Result<CriterionResult, String> {
+    let json: serde_json::Value =
+        serde_json::from_str(&extract_json_from_response(reply)).map_err(|e| e.to_string())?;
+    Ok(CriterionResult {
+        criterion: criterion.to_string(),
+        passed: json["passed"].as_bool().ok_or("Response has no verdict")?,
+        evidence: json["evidence"].as_str().unwrap_or_default().to_string(),
+        files: json["files"]
+            .as_array()
+            .map(|files| {
+                files
+                    .iter()
+                    .filter_map(|f| f.as_str().map(str::to_string))
+                    .collect()
+            })
+            .unwrap_or_default(),
+    })
+}
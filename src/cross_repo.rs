@@ -0,0 +1,167 @@
+use std::error::Error;
+
+use crate::types::IntentVerificationResult;
+
+/// Changes between two commits of one of the repositories a solution spans
+#[derive(Debug, Clone, PartialEq, serde::Serialize, serde::Deserialize)]
+pub struct RepoChanges {
+    /// Label of the repository, used as the prefix of its file paths in the result
+    pub name: String,
+    pub repo_url: String,
+    pub commit1: String,
+    pub commit2: String,
+}
+
+impl RepoChanges {
+    /// Changes named after the last segment of the repository URL (without `.git`)
+    pub fn new(repo_url: &str, commit1: &str, commit2: &str) -> Self {
+        let name = repo_url
+            .trim_end_matches('/')
+            .rsplit(['/', ':'])
+            .next()
+            .unwrap_or(repo_url)
+            .trim_end_matches(".git");
+        RepoChanges {
+            name: name.to_string(),
+            repo_url: repo_url.to_string(),
+            commit1: commit1.to_string(),
+            commit2: commit2.to_string(),
+        }
+    }
+
+    /// Parse `URL@COMMIT1..COMMIT2`, optionally prefixed with `NAME=`
+    pub fn parse(spec: &str) -> Result<Self, Box<dyn Error>> {
+        let (name, rest) = match spec.split_once('=') {
+            Some((name, rest)) if !name.contains('/') => (Some(name), rest),
+            _ => (None, spec),
+        };
+        let (repo_url, range) = rest
+            .rsplit_once('@')
+            .ok_or_else(|| format!("Expected URL@COMMIT1..COMMIT2, got '{}'", spec))?;
+        let (commit1, commit2) = range
+            .split_once("..")
+            .ok_or_else(|| format!("Expected a COMMIT1..COMMIT2 range, got '{}'", range))?;
+        let mut changes = RepoChanges::new(repo_url, commit1, commit2);
+        if let Some(name) = name {
+            changes.name = name.to_string();
+        }
+        Ok(changes)
+    }
+
+    /// Path of one of the repository's files in the combined result
+    pub fn qualified_path(&self, path: &str) -> String {
+        format!("{}/{}", self.name, path)
+    }
+}
+
+/// What one repository's changes contribute to the intent
+#[derive(Debug, Clone, PartialEq, serde::Serialize, serde::Deserialize)]
+pub struct RepoContribution {
+    pub name: String,
+    pub repo_url: String,
+    pub files_changed: usize,
+    /// Changed files (with the repository prefix) that support the intent
+    pub supporting_files: Vec<String>,
+    pub summary: String,
+}
+
+impl RepoContribution {
+    pub fn supports_intent(&self) -> bool {
+        !self.supporting_files.is_empty()
+    }
+}
+
+/// Combined verdict over several repositories, see `verify_cross_repo_intent`
+///
+/// `result` is the verification of all changes together, with each file path prefixed by its
+/// repository's name; `contributions` break it down per repository.
+#[derive(Debug, Clone, serde::Serialize, serde::Deserialize)]
+pub struct CrossRepoResult {
+    pub result: IntentVerificationResult,
+    pub contributions: Vec<RepoContribution>,
+}
+
+impl CrossRepoResult {
+    /// Split a combined result into the contribution of each repository
+    pub fn from_result(result: IntentVerificationResult, repos: &[RepoChanges]) -> Self {
+        let contributions = repos
+            .iter()
+            .map(|repo| {
+                let prefix = repo.qualified_path("");
+                let analyses: Vec<_> = result
+                    .files_analyzed
+                    .iter()
+                    .filter(|a| a.file_path.starts_with(&prefix))
+                    .collect();
+                let supporting_files: Vec<String> = analyses
+                    .iter()
+                    .filter(|a| a.supports_intent)
+                    .map(|a| a.file_path.clone())
+                    .collect();
+                let summary = if analyses.is_empty() {
+                    format!("{}: no changes", repo.name)
+                } else if supporting_files.is_empty() {
+                    format!(
+                        "{}: none of the {} changed files support the intent",
+                        repo.name,
+                        analyses.len()
+                    )
+                } else {
+                    let reasons: Vec<String> = analyses
+                        .iter()
+                        .filter(|a| a.supports_intent)
+                        .map(|a| format!("{} ({})", a.file_path, a.reasoning))
+                        .collect();
+                    format!(
+                        "{}: {} out of {} changed files support the intent: {}",
+                        repo.name,
+                        supporting_files.len(),
+                        analyses.len(),
+                        reasons.join("; ")
+                    )
+                };
+                RepoContribution {
+                    name: repo.name.clone(),
+                    repo_url: repo.repo_url.clone(),
+                    files_changed: analyses.len(),
+                    supporting_files,
+                    summary,
+                }
+            })
+            .collect();
+        CrossRepoResult {
+            result,
+            contributions,
+        }
+    }
+
+    pub fn is_intent_fulfilled(&self) -> bool {
+        self.result.is_intent_fulfilled
+    }
+
+    /// Repositories whose changes don't support the intent at all
+    pub fn idle_repos(&self) -> impl Iterator<Item = &RepoContribution> {
+        self.contributions.iter().filter(|c| !c.supports_intent())
+    }
+}
+
+/// Check the repositories have distinct, non-empty names
+#[cfg(feature = "git")]
+pub(crate) fn check_repo_names(repos: &[RepoChanges]) -> Result<(), Box<dyn Error>> {
+    if repos.is_empty() {
+        return Err("At least one repository is required".into());
+    }
+    for (i, repo) in repos.iter().enumerate() {
+        if repo.name.is_empty() || repo.name.contains('/') {
+            return Err(format!("Invalid repository name '{}'", repo.name).into());
+        }
+        if repos[..i].iter().any(|other| other.name == repo.name) {
+            return Err(format!(
+                "Repository name '{}' is used twice; give the repositories distinct names",
+                repo.name
+            )
+            .into());
+        }
+    }
+    Ok(())
+}
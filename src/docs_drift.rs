@@ -0,0 +1,236 @@
+use std::collections::BTreeMap;
+use std::path::Path;
+
+use regex::Regex;
+
+use crate::api_surface::{ApiItem, public_api};
+use crate::git::{ChangeType, FileChange};
+use crate::types::{Finding, Severity};
+use crate::utils::is_test_path;
+
+/// Rule of the findings reported for documentation that wasn't updated with the code
+pub const DOCS_DRIFT_RULE: &str = "docs_out_of_date";
+
+/// Extensions of the files read under the configured documentation directories
+const DOC_EXTENSIONS: &[&str] = &["md", "markdown", "rst", "txt", "adoc"];
+
+/// Where to look for documentation that should change along with the code
+#[derive(Debug, Clone, PartialEq, serde::Serialize, serde::Deserialize)]
+#[serde(default)]
+pub struct DocsDriftConfig {
+    /// Documentation files or directories, relative to the repository root
+    pub doc_paths: Vec<String>,
+    /// Also report doc comments left untouched when the signature below them changed
+    pub check_doc_comments: bool,
+    /// Item names never reported
+    pub ignore: Vec<String>,
+}
+
+impl Default for DocsDriftConfig {
+    fn default() -> Self {
+        DocsDriftConfig {
+            doc_paths: vec!["README.md".to_string(), "docs".to_string()],
+            check_doc_comments: true,
+            ignore: vec![],
+        }
+    }
+}
+
+impl DocsDriftConfig {
+    /// Whether `path` is documentation covered by [`doc_paths`](Self::doc_paths)
+    pub fn is_doc_path(&self, path: &str) -> bool {
+        self.doc_paths.iter().any(|doc_path| {
+            let doc_path = doc_path.trim_matches('/');
+            path == doc_path
+                || (path.starts_with(&format!("{}/", doc_path))
+                    && Path::new(path)
+                        .extension()
+                        .and_then(|e| e.to_str())
+                        .is_some_and(|e| DOC_EXTENSIONS.contains(&e.to_lowercase().as_str())))
+        })
+    }
+
+    /// Documentation files of a checked-out repository, keyed by relative path
+    pub fn read_doc_files(&self, dir: &Path) -> BTreeMap<String, String> {
+        let mut files = BTreeMap::new();
+        let mut pending: Vec<_> = self
+            .doc_paths
+            .iter()
+            .map(|p| dir.join(p.trim_matches('/')))
+            .collect();
+        while let Some(path) = pending.pop() {
+            if path.is_dir() {
+                if let Ok(entries) = std::fs::read_dir(&path) {
+                    pending.extend(entries.flatten().map(|entry| entry.path()));
+                }
+                continue;
+            }
+            let Ok(relative) = path.strip_prefix(dir) else {
+                continue;
+            };
+            let relative = relative.to_string_lossy().replace('\\', "/");
+            if self.is_doc_path(&relative)
+                && let Ok(content) = std::fs::read_to_string(&path)
+            {
+                files.insert(relative, content);
+            }
+        }
+        files
+    }
+}
+
+/// Findings for documentation that still describes public items the changes modified
+///
+/// An item counts as changed when its signature changed or it was removed. Documentation
+/// files in `doc_files` (the solution's docs, keyed by path) that mention a changed item in
+/// code formatting but weren't part of the diff are reported with the items they mention.
+/// With [`check_doc_comments`](DocsDriftConfig::check_doc_comments), doc comments that
+/// stayed identical above a changed signature are reported too.
+pub fn detect_docs_drift(
+    file_changes: &[FileChange],
+    doc_files: &BTreeMap<String, String>,
+    config: &DocsDriftConfig,
+) -> Vec<Finding> {
+    let mut findings = Vec::new();
+    let mut changed_items = Vec::new();
+
+    for file_change in file_changes.iter().filter(|fc| !is_test_path(&fc.path)) {
+        let Some(old) = &file_change.old_content else {
+            continue;
+        };
+        let new = match file_change.status {
+            ChangeType::Deleted => "",
+            _ => file_change.content.as_deref().unwrap_or(""),
+        };
+        let after = public_api(&file_change.path, new);
+        for item in public_api(&file_change.path, old) {
+            if config.ignore.contains(&item.name) {
+                continue;
+            }
+            let new_item = after
+                .iter()
+                .find(|a| a.kind == item.kind && a.name == item.name);
+            if new_item.is_some_and(|a| a.signature == item.signature) {
+                continue;
+            }
+            if config.check_doc_comments
+                && new_item.is_some()
+                && let Some(doc) = doc_comment(&file_change.path, old, &item)
+                && doc_comment(&file_change.path, new, &item).as_deref() == Some(doc.as_str())
+            {
+                findings.push(Finding {
+                    rule: DOCS_DRIFT_RULE.to_string(),
+                    severity: Severity::Low,
+                    file_path: Some(file_change.path.clone()),
+                    line: None,
+                    snippet: Some(item.name.clone()),
+                    message: format!(
+                        "Doc comment of `{}` wasn't updated although its signature changed",
+                        item.name
+                    ),
+                    suppressed: false,
+                    cwe: None,
+                    content_hash: None,
+                });
+            }
+            if !changed_items.contains(&item.name) {
+                changed_items.push(item.name);
+            }
+        }
+    }
+
+    for (path, content) in doc_files {
+        if file_changes.iter().any(|fc| &fc.path == path) {
+            continue;
+        }
+        let mentioned: Vec<&str> = changed_items
+            .iter()
+            .filter(|name| mentions(content, name))
+            .map(String::as_str)
+            .collect();
+        if mentioned.is_empty() {
+            continue;
+        }
+        findings.push(Finding {
+            rule: DOCS_DRIFT_RULE.to_string(),
+            severity: Severity::Low,
+            file_path: Some(path.clone()),
+            line: None,
+            snippet: Some(mentioned.join(", ")),
+            message: format!(
+                "{} documents {} but wasn't updated with the changes",
+                path,
+                mentioned
+                    .iter()
+                    .map(|name| format!("`{}`", name))
+                    .collect::<Vec<_>>()
+                    .join(", ")
+            ),
+            suppressed: false,
+            cwe: None,
+            content_hash: None,
+        });
+    }
+    findings
+}
+
+/// Whether documentation text refers to `name` as code: in backticks or as a call
+fn mentions(content: &str, name: &str) -> bool {
+    let name = regex::escape(name);
+    Regex::new(&format!(r"`[^`\n]*\b{}\b[^`\n]*`|\b{}\(", name, name))
+        .unwrap()
+        .is_match(content)
+}
+
+/// Doc comment of an item: the comment lines right above its declaration, or for Python the
+/// docstring right below it
+fn doc_comment(path: &str, content: &str, item: &ApiItem) -> Option<String> {
+    let declaration = Regex::new(&format!(
+        r"\b{}\s+{}\b",
+        regex::escape(&item.kind),
+        regex::escape(&item.name)
+    ))
+    .unwrap();
+    let lines: Vec<&str> = content.lines().collect();
+    let index = lines.iter().position(|line| declaration.is_match(line))?;
+
+    let doc: Vec<&str> = if path.ends_with(".py") {
+        let header_end = index
+            + lines[index..]
+                .iter()
+                .position(|line| line.trim_end().ends_with(':'))?;
+        let mut body = lines[header_end + 1..]
+            .iter()
+            .map(|line| line.trim())
+            .filter(|line| !line.is_empty());
+        let first = body.next()?;
+        let quote = first.get(..3).filter(|q| *q == "\"\"\"" || *q == "'''")?;
+        if first.len() > 3 && first[3..].contains(quote) {
+            vec![first]
+        } else {
+            let mut doc = vec![first];
+            for line in body {
+                doc.push(line);
+                if line.contains(quote) {
+                    break;
+                }
+            }
+            doc
+        }
+    } else {
+        let mut doc: Vec<&str> = lines[..index]
+            .iter()
+            .rev()
+            .map(|line| line.trim())
+            .take_while(|line| {
+                ["///", "//", "/*", "*", "#[", "@"]
+                    .iter()
+                    .any(|prefix| line.starts_with(prefix))
+            })
+            .filter(|line| !line.starts_with("#[") && !line.starts_with('@'))
+            .collect();
+        doc.reverse();
+        doc
+    };
+    (!doc.is_empty()).then(|| doc.join("\n"))
+}
@@ -0,0 +1,216 @@
+use crate::git::ChangeType;
+use crate::types::{ChangeLocation, IntentVerificationResult, Severity, WarningKind};
+
+/// Confidence below which a result is escalated when no threshold is configured
+pub const DEFAULT_ESCALATION_CONFIDENCE: f32 = 0.6;
+
+/// Most questions listed for one result
+const MAX_QUESTIONS: usize = 20;
+
+/// A question a human reviewer should answer, with the code to look at
+#[derive(Debug, Clone, PartialEq, serde::Serialize, serde::Deserialize)]
+pub struct ReviewQuestion {
+    pub question: String,
+    pub file_path: Option<String>,
+    /// Code region the question is about, when it can be pinned to lines
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub location: Option<ChangeLocation>,
+}
+
+/// Why a verdict needs a human decision, and what to check
+#[derive(Debug, Clone, PartialEq, serde::Serialize, serde::Deserialize)]
+pub struct Escalation {
+    /// Low confidence, or the signals that disagree with each other
+    pub reasons: Vec<String>,
+    pub questions: Vec<ReviewQuestion>,
+}
+
+/// Questions for a human reviewer when the result is uncertain
+///
+/// A result is escalated when its confidence is below `threshold`, when the tests disagree
+/// with the verdict or pass without the solution, when deterministic findings or unmet
+/// acceptance criteria contradict files the model judged to support the intent, or when some
+/// files couldn't be analyzed. Returns `None` for results that need no review.
+pub fn escalation(result: &IntentVerificationResult, threshold: f32) -> Option<Escalation> {
+    let mut reasons = Vec::new();
+    let mut questions = Vec::new();
+    let supporting: Vec<&str> = result
+        .files_analyzed
+        .iter()
+        .filter(|a| a.supports_intent)
+        .map(|a| a.file_path.as_str())
+        .collect();
+    let failed_files: Vec<_> = result
+        .warnings
+        .iter()
+        .filter(|w| {
+            matches!(
+                w.kind,
+                WarningKind::FileAnalysis | WarningKind::ResponseParsing
+            )
+        })
+        .filter_map(|w| w.file_path.as_ref().map(|path| (path, &w.message)))
+        .collect();
+
+    if result.confidence < threshold {
+        reasons.push(format!(
+            "Confidence {:.0}% is below the {:.0}% threshold",
+            result.confidence * 100.0,
+            threshold * 100.0
+        ));
+        for analysis in &result.files_analyzed {
+            if analysis.change_type == ChangeType::Deleted
+                || failed_files
+                    .iter()
+                    .any(|(path, _)| **path == analysis.file_path)
+            {
+                continue;
+            }
+            let question = if analysis.supports_intent {
+                if !analysis.locations.is_empty() {
+                    continue;
+                }
+                format!(
+                    "Which part of `{}` implements the intent? The analysis couldn't pin its reasoning to specific lines: {}",
+                    analysis.file_path, analysis.reasoning
+                )
+            } else {
+                format!(
+                    "Does the change to `{}` help fulfill the intent? The analysis concluded it doesn't: {}",
+                    analysis.file_path, analysis.reasoning
+                )
+            };
+            questions.push(ReviewQuestion {
+                question,
+                file_path: Some(analysis.file_path.clone()),
+                location: analysis.locations.first().cloned(),
+            });
+        }
+    }
+
+    if let Some(run) = &result.test_run {
+        if run.passed && !result.is_intent_fulfilled {
+            reasons.push("The tests pass but the verdict is negative".to_string());
+            questions.push(ReviewQuestion {
+                question: format!(
+                    "The tests pass (`{}`). Is the negative verdict right, or do the remaining concerns not matter for the intent?",
+                    run.command.join(" ")
+                ),
+                file_path: None,
+                location: None,
+            });
+        } else if !run.passed && !supporting.is_empty() {
+            reasons.push(format!(
+                "The tests fail although {} changed file(s) appear to support the intent",
+                supporting.len()
+            ));
+            questions.push(ReviewQuestion {
+                question: format!(
+                    "The tests fail (`{}`) although the changes look right. Is the failure caused by these changes or by the environment?",
+                    run.command.join(" ")
+                ),
+                file_path: None,
+                location: None,
+            });
+        }
+    }
+    if result
+        .test_run_without_solution
+        .as_ref()
+        .is_some_and(|run| run.passed)
+    {
+        reasons.push("The tests also pass without the solution".to_string());
+        questions.push(ReviewQuestion {
+            question:
+                "The tests pass without the solution. Do they exercise the changed code at all?"
+                    .to_string(),
+            file_path: None,
+            location: None,
+        });
+    }
+
+    let conflicting_findings: Vec<_> = result
+        .findings
+        .iter()
+        .filter(|f| {
+            !f.suppressed
+                && f.severity >= Severity::High
+                && f.file_path
+                    .as_deref()
+                    .is_some_and(|path| supporting.contains(&path))
+        })
+        .collect();
+    if !conflicting_findings.is_empty() {
+        reasons.push(format!(
+            "{} high-severity finding(s) in files that appear to support the intent",
+            conflicting_findings.len()
+        ));
+    }
+    for finding in conflicting_findings {
+        let file_path = finding.file_path.clone().unwrap_or_default();
+        questions.push(ReviewQuestion {
+            question: format!(
+                "`{}` appears to support the intent, but `{}` reports: {}. Is this acceptable?",
+                file_path, finding.rule, finding.message
+            ),
+            location: finding.line.map(|line| ChangeLocation {
+                file_path: file_path.clone(),
+                start_line: line,
+                end_line: line,
+                snippet: finding.snippet.clone().unwrap_or_default(),
+            }),
+            file_path: Some(file_path),
+        });
+    }
+
+    let unmet: Vec<_> = result
+        .acceptance_criteria
+        .iter()
+        .filter(|c| !c.passed)
+        .collect();
+    if !unmet.is_empty() && !supporting.is_empty() {
+        reasons.push(format!(
+            "{} acceptance criteria unmet although changed files appear to support the intent",
+            unmet.len()
+        ));
+        for criterion in unmet {
+            questions.push(ReviewQuestion {
+                question: format!(
+                    "Do the changes meet \"{}\"? The check says no: {}",
+                    criterion.criterion, criterion.evidence
+                ),
+                file_path: criterion.files.first().cloned(),
+                location: None,
+            });
+        }
+    }
+
+    if !failed_files.is_empty() {
+        reasons.push(format!(
+            "{} changed file(s) couldn't be analyzed",
+            failed_files.len()
+        ));
+        for (path, message) in failed_files {
+            questions.push(ReviewQuestion {
+                question: format!(
+                    "Does the change to `{}` support the intent? It couldn't be analyzed ({})",
+                    path, message
+                ),
+                file_path: Some(path.clone()),
+                location: None,
+            });
+        }
+    }
+
+    if reasons.is_empty() {
+        return None;
+    }
+    questions.dedup_by(|a, b| a.question == b.question);
+    questions.truncate(MAX_QUESTIONS);
+    Some(Escalation { reasons, questions })
+}
+
+/// Set `result.escalation` from [`escalation`]
+pub fn apply_escalation(result: &mut IntentVerificationResult, threshold: f32) {
+    result.escalation = escalation(result, threshold);
+}
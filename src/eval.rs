@@ -0,0 +1,170 @@
+use std::error::Error;
+use std::path::Path;
+
+use crate::batch::BatchJob;
+#[cfg(feature = "git")]
+use crate::batch::{BatchManifest, run_batch};
+#[cfg(feature = "git")]
+use crate::openai::DEFAULT_MODEL;
+#[cfg(feature = "git")]
+use crate::options::AnalysisOptions;
+
+/// A verification case with the verdict a human reviewer gave it
+#[derive(Debug, Clone, PartialEq, serde::Serialize, serde::Deserialize)]
+pub struct EvalCase {
+    #[serde(flatten)]
+    pub job: BatchJob,
+    /// Whether the changes really fulfill the intent
+    pub expected: bool,
+}
+
+/// A golden dataset of labeled cases, read from JSON or TOML (`[[cases]]` tables)
+#[derive(Debug, Clone, Default, PartialEq, serde::Serialize, serde::Deserialize)]
+pub struct EvalCorpus {
+    /// Number of cases run at the same time (one at a time when `None`)
+    #[serde(default)]
+    pub concurrency: Option<usize>,
+    pub cases: Vec<EvalCase>,
+}
+
+impl EvalCorpus {
+    pub fn from_json(json: &str) -> Result<Self, Box<dyn Error>> {
+        Ok(serde_json::from_str(json)?)
+    }
+
+    pub fn from_toml(text: &str) -> Result<Self, Box<dyn Error>> {
+        Ok(toml::from_str(text)?)
+    }
+
+    /// Load a corpus, treating `.toml` files as TOML and anything else as JSON
+    pub fn load(path: impl AsRef<Path>) -> Result<Self, Box<dyn Error>> {
+        let path = path.as_ref();
+        let text = std::fs::read_to_string(path)?;
+        match path.extension().and_then(|ext| ext.to_str()) {
+            Some("toml") => Self::from_toml(&text),
+            _ => Self::from_json(&text),
+        }
+    }
+}
+
+/// How the verifier did on one case
+#[derive(Debug, Clone, PartialEq, serde::Serialize, serde::Deserialize)]
+pub struct EvalCaseOutcome {
+    pub name: String,
+    pub expected: bool,
+    /// Verdict of the verifier; `None` when the verification could not run
+    pub predicted: Option<bool>,
+    pub confidence: Option<f32>,
+    pub error: Option<String>,
+}
+
+impl EvalCaseOutcome {
+    /// Whether the verdict matches the label
+    pub fn is_correct(&self) -> bool {
+        self.predicted == Some(self.expected)
+    }
+}
+
+/// Accuracy of the current prompts and model on a corpus
+///
+/// "Fulfilled" is the positive class: precision is the share of fulfilled verdicts that were
+/// right, recall the share of truly fulfilled cases that were recognized. Cases that failed to
+/// run are counted in `errors` and left out of the metrics, which are `None` when undefined.
+#[derive(Debug, Clone, PartialEq, serde::Serialize, serde::Deserialize)]
+pub struct EvalReport {
+    pub model: String,
+    pub prompt_version: String,
+    pub total: usize,
+    pub errors: usize,
+    pub true_positives: usize,
+    pub false_positives: usize,
+    pub true_negatives: usize,
+    pub false_negatives: usize,
+    pub accuracy: Option<f32>,
+    pub precision: Option<f32>,
+    pub recall: Option<f32>,
+    pub f1: Option<f32>,
+    pub cases: Vec<EvalCaseOutcome>,
+}
+
+impl EvalReport {
+    /// Confusion matrix and metrics of a set of outcomes
+    pub fn from_outcomes(model: &str, prompt_version: &str, cases: Vec<EvalCaseOutcome>) -> Self {
+        let count = |expected: bool, predicted: bool| {
+            cases
+                .iter()
+                .filter(|c| c.expected == expected && c.predicted == Some(predicted))
+                .count()
+        };
+        let (tp, fp) = (count(true, true), count(false, true));
+        let (tn, fn_) = (count(false, false), count(true, false));
+        let ratio = |numerator: usize, denominator: usize| {
+            (denominator > 0).then(|| numerator as f32 / denominator as f32)
+        };
+        let precision = ratio(tp, tp + fp);
+        let recall = ratio(tp, tp + fn_);
+        let f1 = match (precision, recall) {
+            (Some(p), Some(r)) if p + r > 0.0 => Some(2.0 * p * r / (p + r)),
+            (Some(_), Some(_)) => Some(0.0),
+            _ => None,
+        };
+
+        EvalReport {
+            model: model.to_string(),
+            prompt_version: prompt_version.to_string(),
+            total: cases.len(),
+            errors: cases.iter().filter(|c| c.predicted.is_none()).count(),
+            true_positives: tp,
+            false_positives: fp,
+            true_negatives: tn,
+            false_negatives: fn_,
+            accuracy: ratio(tp + tn, tp + fp + tn + fn_),
+            precision,
+            recall,
+            f1,
+            cases,
+        }
+    }
+
+    /// Cases whose verdict didn't match the label, including those that failed to run
+    pub fn mismatches(&self) -> impl Iterator<Item = &EvalCaseOutcome> {
+        self.cases.iter().filter(|c| !c.is_correct())
+    }
+}
+
+/// Replay every case of a corpus against the prompts in `options` and the model
+///
+/// Cases run like a batch (see `run_batch`): each repository is fetched once and failures
+/// are recorded without stopping the other cases.
+#[cfg(feature = "git")]
+pub async fn run_eval(
+    corpus: &EvalCorpus,
+    api_key: &str,
+    model: Option<&str>,
+    base_url: Option<&str>,
+    options: &AnalysisOptions,
+) -> EvalReport {
+    let manifest = BatchManifest {
+        concurrency: corpus.concurrency,
+        jobs: corpus.cases.iter().map(|case| case.job.clone()).collect(),
+    };
+    let summary = run_batch(&manifest, api_key, model, base_url, options).await;
+
+    let cases = summary
+        .outcomes
+        .into_iter()
+        .zip(&corpus.cases)
+        .map(|(outcome, case)| EvalCaseOutcome {
+            name: outcome.name,
+            expected: case.expected,
+            predicted: outcome.result.as_ref().map(|r| r.is_intent_fulfilled),
+            confidence: outcome.result.as_ref().map(|r| r.confidence),
+            error: outcome.error,
+        })
+        .collect();
+    EvalReport::from_outcomes(
+        model.unwrap_or(DEFAULT_MODEL),
+        &options.prompt_templates().version,
+        cases,
+    )
+}
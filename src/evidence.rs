@@ -0,0 +1,134 @@
+use std::path::Path;
+use std::sync::{Arc, Mutex};
+
+use crate::types::{IntentVerificationResult, PromptPreview, TestTargetsWithCode};
+use crate::utils::sha256_hex;
+
+/// Format version of [`EvidenceBundle`]
+pub const EVIDENCE_BUNDLE_VERSION: &str = "1";
+
+/// One request sent to the model and its raw reply
+#[derive(Debug, Clone, serde::Serialize, serde::Deserialize)]
+pub struct LlmExchange {
+    pub prompt: PromptPreview,
+    /// Reply text exactly as received, before any parsing
+    pub response: String,
+}
+
+/// SHA-256 digests (hex) of the JSON of each part of an [`EvidenceBundle`]
+#[derive(Debug, Clone, PartialEq, serde::Serialize, serde::Deserialize)]
+pub struct EvidenceHashes {
+    pub diff: String,
+    pub targets: String,
+    pub exchanges: String,
+    pub result: String,
+}
+
+/// Everything a verdict was based on, for auditing a disputed verification later
+///
+/// Holds the diff, the test targets and their code, every model exchange and the final
+/// result, each with a hash so tampering with any part can be detected.
+#[derive(Debug, Clone, serde::Serialize, serde::Deserialize)]
+pub struct EvidenceBundle {
+    pub bundle_version: String,
+    /// RFC 3339 timestamp
+    pub created_at: String,
+    /// Unified diff of the analyzed changes
+    pub diff: String,
+    /// Test targets with the code read for them; `None` when the run stopped before that
+    pub targets: Option<TestTargetsWithCode>,
+    pub exchanges: Vec<LlmExchange>,
+    pub result: IntentVerificationResult,
+    pub hashes: EvidenceHashes,
+}
+
+impl EvidenceBundle {
+    /// Digests of the bundle's parts as they are now
+    pub fn compute_hashes(&self) -> EvidenceHashes {
+        let hash =
+            |value: serde_json::Result<String>| sha256_hex(value.unwrap_or_default().as_bytes());
+        EvidenceHashes {
+            diff: sha256_hex(self.diff.as_bytes()),
+            targets: hash(serde_json::to_string(&self.targets)),
+            exchanges: hash(serde_json::to_string(&self.exchanges)),
+            result: hash(serde_json::to_string(&self.result)),
+        }
+    }
+
+    /// Whether every part still matches the hash recorded when the bundle was created
+    pub fn verify_hashes(&self) -> bool {
+        self.compute_hashes() == self.hashes
+    }
+
+    /// Load a bundle written by [`save`](Self::save)
+    pub fn load(path: impl AsRef<Path>) -> Result<Self, Box<dyn std::error::Error>> {
+        Ok(serde_json::from_str(&std::fs::read_to_string(path)?)?)
+    }
+
+    /// Write the bundle as pretty-printed JSON
+    pub fn save(&self, path: impl AsRef<Path>) -> Result<(), Box<dyn std::error::Error>> {
+        std::fs::write(path, serde_json::to_string_pretty(self)?)?;
+        Ok(())
+    }
+}
+
+#[derive(Debug, Default)]
+struct EvidenceLog {
+    diff: String,
+    targets: Option<TestTargetsWithCode>,
+    exchanges: Vec<LlmExchange>,
+}
+
+/// Collects evidence while a verification runs, set on [`crate::AnalysisOptions::evidence`]
+///
+/// Clones share the same log. Use a fresh recorder for each verification, then call
+/// [`bundle`](Self::bundle) with its result.
+#[derive(Debug, Clone, Default)]
+pub struct EvidenceRecorder(Arc<Mutex<EvidenceLog>>);
+
+impl EvidenceRecorder {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub(crate) fn record_diff(&self, diff: String) {
+        self.0.lock().unwrap().diff = diff;
+    }
+
+    pub(crate) fn record_targets(&self, targets: &TestTargetsWithCode) {
+        self.0.lock().unwrap().targets = Some(targets.clone());
+    }
+
+    pub(crate) fn record_exchange(&self, prompt: PromptPreview, response: &str) {
+        self.0.lock().unwrap().exchanges.push(LlmExchange {
+            prompt,
+            response: response.to_string(),
+        });
+    }
+
+    /// Number of model exchanges recorded so far
+    pub fn exchange_count(&self) -> usize {
+        self.0.lock().unwrap().exchanges.len()
+    }
+
+    /// Package the recorded evidence with the verification's result
+    pub fn bundle(&self, result: &IntentVerificationResult) -> EvidenceBundle {
+        let log = self.0.lock().unwrap();
+        let mut bundle = EvidenceBundle {
+            bundle_version: EVIDENCE_BUNDLE_VERSION.to_string(),
+            created_at: chrono::Utc::now().to_rfc3339(),
+            diff: log.diff.clone(),
+            targets: log.targets.clone(),
+            exchanges: log.exchanges.clone(),
+            result: result.clone(),
+            hashes: EvidenceHashes {
+                diff: String::new(),
+                targets: String::new(),
+                exchanges: String::new(),
+                result: String::new(),
+            },
+        };
+        bundle.hashes = bundle.compute_hashes();
+        bundle
+    }
+}
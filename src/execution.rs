@@ -0,0 +1,534 @@
+#[cfg(feature = "exec")]
+use std::error::Error;
+use std::path::Path;
+#[cfg(feature = "exec")]
+use std::process::Stdio;
+#[cfg(feature = "exec")]
+use std::time::{Duration, Instant};
+
+use regex::Regex;
+
+#[cfg(feature = "git")]
+use crate::git::{prepare_test_workspace, spawn_git};
+#[cfg(feature = "git")]
+use crate::options::AnalysisOptions;
+use crate::target_resolution::TargetKind;
+use crate::types::{IntentVerificationResult, Warning, WarningKind};
+
+/// Test runs are killed after this long unless configured otherwise
+#[cfg(feature = "exec")]
+const DEFAULT_TIMEOUT_SECS: u64 = 600;
+
+/// Lines of test output kept in [`TestRunResult::output_tail`]
+#[cfg(feature = "exec")]
+const OUTPUT_TAIL_LINES: usize = 40;
+
+/// Limits of the test container: memory, CPUs and processes
+#[cfg(feature = "exec")]
+const CONTAINER_LIMITS: &[&str] = &["--memory", "2g", "--cpus", "2", "--pids-limit", "512"];
+
+/// Environment variables passed through to local test runs; everything else (API keys,
+/// tokens) is withheld from the project's code
+#[cfg(feature = "exec")]
+const PASSTHROUGH_ENV: &[&str] = &[
+    "PATH",
+    "HOME",
+    "LANG",
+    "TMPDIR",
+    "CARGO_HOME",
+    "RUSTUP_HOME",
+    "VIRTUAL_ENV",
+    "NODE_PATH",
+];
+
+/// How to run the project's tests to corroborate the model's verdict
+#[derive(Debug, Clone, Default, PartialEq, serde::Serialize, serde::Deserialize)]
+#[serde(default)]
+pub struct ExecutionConfig {
+    /// Program and arguments; detected from the project files when `None`
+    pub command: Option<Vec<String>>,
+    /// Seconds before the run is killed and counted as failed (600 when `None`)
+    pub timeout_secs: Option<u64>,
+    /// Run inside this image with `docker run --rm --network none` and resource limits, as the
+    /// owner of the checkout; when `None`, an image for the project's toolchain (see
+    /// [`default_container_image`]). Without network access, the image must already hold the
+    /// test runner and the project's dependencies
+    pub container_image: Option<String>,
+    /// Also run the tests with the solution reverted, and require them to fail there
+    pub counterfactual: bool,
+    /// Run the tests as a local process in a throwaway checkout with a minimal environment when
+    /// no `container_image` is set; the solution's code then runs unsandboxed, so otherwise the
+    /// run is refused when no image is known for the command
+    pub allow_unsandboxed: bool,
+    /// Container CLI taking `docker` arguments, e.g. `podman`; `docker` when `None`
+    pub container_runtime: Option<String>,
+}
+
+/// Outcome of running the project's tests on the tests commit plus the solution's changes
+#[derive(Debug, Clone, PartialEq, serde::Serialize, serde::Deserialize)]
+pub struct TestRunResult {
+    pub command: Vec<String>,
+    /// True when the command exited successfully within the timeout
+    pub passed: bool,
+    pub exit_code: Option<i32>,
+    pub timed_out: bool,
+    /// Counts parsed from the runner's summary, when it could be recognized
+    pub tests_passed: Option<usize>,
+    pub tests_failed: Option<usize>,
+    /// Last lines of the combined stdout and stderr
+    pub output_tail: String,
+    pub duration_ms: u64,
+    /// Outcome of each test the runner listed by name, see [`parse_test_outcomes`]
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub tests: Vec<TestOutcome>,
+}
+
+impl TestRunResult {
+    /// Why a failed run says nothing about the solution: the runner or the container couldn't
+    /// start, the dependencies couldn't be fetched, or no test results were reported
+    ///
+    /// `None` for passed and timed out runs.
+    pub fn inconclusive_reason(&self) -> Option<&'static str> {
+        if self.passed || self.timed_out {
+            return None;
+        }
+        let dependencies = Regex::new(
+            "(?i)could not resolve host|temporary failure in name resolution|getaddrinfo|\
+             EAI_AGAIN|ENOTFOUND|network is unreachable|failed to download|\
+             no matching distribution found|could not find a version that satisfies",
+        )
+        .unwrap();
+        match self.exit_code {
+            Some(125) => Some("the container couldn't be started"),
+            Some(126) => Some("the test command couldn't be executed"),
+            Some(127) => Some("the test command wasn't found"),
+            _ if dependencies.is_match(&self.output_tail) => {
+                Some("the dependencies couldn't be fetched")
+            }
+            _ if self.tests_failed.is_none() && self.tests.is_empty() => {
+                Some("no test results were reported")
+            }
+            _ => None,
+        }
+    }
+}
+
+/// Whether a single test passed, as reported by the test runner
+#[derive(Debug, Clone, PartialEq, serde::Serialize, serde::Deserialize)]
+pub struct TestOutcome {
+    /// Name as the runner printed it, e.g. `tests::sum_adds` or `tests/test_sum.py::test_add`
+    pub name: String,
+    pub passed: bool,
+}
+
+impl TestOutcome {
+    /// Whether this is the test function `name`, given with or without its module path
+    fn is_named(&self, name: &str) -> bool {
+        self.name == name
+            || self
+                .name
+                .rsplit_once("::")
+                .is_some_and(|(_, function)| function == name)
+    }
+}
+
+/// Test command for the project in `dir`: `cargo test`, `pytest` or `npm test`
+pub fn detect_test_command(dir: &Path) -> Option<Vec<String>> {
+    let command: &[&str] = if dir.join("Cargo.toml").exists() {
+        &["cargo", "test"]
+    } else if [
+        "pyproject.toml",
+        "pytest.ini",
+        "setup.py",
+        "setup.cfg",
+        "tox.ini",
+    ]
+    .iter()
+    .any(|file| dir.join(file).exists())
+    {
+        &["pytest"]
+    } else if dir.join("package.json").exists() {
+        &["npm", "test"]
+    } else {
+        return None;
+    };
+    Some(command.iter().map(|s| s.to_string()).collect())
+}
+
+/// Passed and failed test counts from cargo, pytest or jest output
+///
+/// Cargo prints one summary per test binary, which are added up.
+pub fn parse_test_counts(output: &str) -> (Option<usize>, Option<usize>) {
+    let cargo = Regex::new(r"test result: \w+\. (\d+) passed; (\d+) failed").unwrap();
+    let mut totals: Option<(usize, usize)> = None;
+    for captures in cargo.captures_iter(output) {
+        let (passed, failed) = totals.unwrap_or_default();
+        totals = Some((
+            passed + captures[1].parse::<usize>().unwrap_or(0),
+            failed + captures[2].parse::<usize>().unwrap_or(0),
+        ));
+    }
+    if let Some((passed, failed)) = totals {
+        return (Some(passed), Some(failed));
+    }
+
+    // pytest: "=== 3 passed, 1 failed in 0.12s ===", jest: "Tests: 1 failed, 3 passed, 4 total"
+    let summary = Regex::new(r"(?m)^(?:=+ .*(?:passed|failed).* =+|Tests:.*)$").unwrap();
+    let Some(line) = summary.find_iter(output).last() else {
+        return (None, None);
+    };
+    let count = |label: &str| {
+        Regex::new(&format!(r"(\d+) {}", label))
+            .unwrap()
+            .captures(line.as_str())
+            .and_then(|captures| captures[1].parse().ok())
+    };
+    let passed = count("passed");
+    let failed = count("failed");
+    if passed.is_none() && failed.is_none() {
+        return (None, None);
+    }
+    (passed.or(Some(0)), failed.or(Some(0)))
+}
+
+/// Image for running `command` when no image is configured: the official image of its
+/// toolchain, or `None` for commands of other toolchains
+///
+/// These images have no project dependencies, and `python:3` has no pytest, so only projects
+/// without dependencies can be tested in them; other runs are inconclusive (see
+/// [`TestRunResult::inconclusive_reason`]) until a prepared image is configured.
+pub fn default_container_image(command: &[String]) -> Option<&'static str> {
+    match command.first()?.as_str() {
+        "cargo" => Some("rust:1"),
+        "pytest" | "python" | "python3" => Some("python:3"),
+        "npm" | "npx" | "yarn" | "node" => Some("node:lts"),
+        _ => None,
+    }
+}
+
+/// Per-test outcomes listed by the runner: `cargo test` lines like `test tests::sum ... ok` and
+/// verbose pytest lines like `tests/test_sum.py::test_add PASSED`
+///
+/// Ignored and skipped tests are left out.
+pub fn parse_test_outcomes(output: &str) -> Vec<TestOutcome> {
+    let cargo = Regex::new(r"(?m)^test (\S+) \.\.\. (ok|FAILED)\s*$").unwrap();
+    let pytest = Regex::new(r"(?m)^(\S+::\S+) (PASSED|FAILED|ERROR)\b").unwrap();
+    cargo
+        .captures_iter(output)
+        .chain(pytest.captures_iter(output))
+        .map(|captures| TestOutcome {
+            name: captures[1].to_string(),
+            passed: matches!(&captures[2], "ok" | "PASSED"),
+        })
+        .collect()
+}
+
+/// Run the project's tests in `dir`, in a container unless `config.allow_unsandboxed`
+///
+/// Fails when no command is configured or detected, no image is configured or known for it
+/// and running outside a container isn't allowed, or the command can't be started.
+#[cfg(feature = "exec")]
+pub async fn run_tests(
+    dir: &Path,
+    config: &ExecutionConfig,
+) -> Result<TestRunResult, Box<dyn Error>> {
+    let command = match &config.command {
+        Some(command) if !command.is_empty() => command.clone(),
+        _ => detect_test_command(dir).ok_or("No test command configured or detected")?,
+    };
+    let image = match &config.container_image {
+        Some(image) => Some(image.clone()),
+        None if config.allow_unsandboxed => None,
+        None => Some(
+            default_container_image(&command)
+                .ok_or_else(|| {
+                    format!(
+                        "No container image known for `{}`; set one, or allow running the tests \
+                         outside a container",
+                        command.join(" ")
+                    )
+                })?
+                .to_string(),
+        ),
+    };
+    // Named, so the container itself can be killed: killing `docker run` leaves it running
+    let container = image.as_ref().map(|_| {
+        format!(
+            "intent-verification-{}-{}",
+            std::process::id(),
+            std::time::SystemTime::now()
+                .duration_since(std::time::UNIX_EPOCH)
+                .unwrap_or_default()
+                .as_nanos()
+        )
+    });
+
+    let runtime = config.container_runtime.as_deref().unwrap_or("docker");
+    let mut process = match (&image, &container) {
+        (Some(image), Some(container)) => {
+            let mut process = tokio::process::Command::new(runtime);
+            process
+                .args(["run", "--rm", "--name", container, "--network", "none"])
+                .args(CONTAINER_LIMITS)
+                .args(["--security-opt", "no-new-privileges"]);
+            // As root, the container would leave files in the checkout that can't be removed
+            #[cfg(unix)]
+            {
+                use std::os::unix::fs::MetadataExt;
+                let owner = std::fs::metadata(dir)?;
+                process
+                    .arg("--user")
+                    .arg(format!("{}:{}", owner.uid(), owner.gid()))
+                    .args(["-e", "HOME=/tmp"]);
+            }
+            process
+                .arg("-v")
+                .arg(format!("{}:/workspace", dir.display()))
+                .args(["-w", "/workspace", image])
+                .args(&command);
+            process
+        }
+        _ => {
+            let mut process = tokio::process::Command::new(&command[0]);
+            process.args(&command[1..]).current_dir(dir).env_clear();
+            for name in PASSTHROUGH_ENV {
+                if let Ok(value) = std::env::var(name) {
+                    process.env(name, value);
+                }
+            }
+            process
+        }
+    };
+    process
+        .stdin(Stdio::null())
+        .stdout(Stdio::piped())
+        .stderr(Stdio::piped())
+        .kill_on_drop(true);
+
+    let started = Instant::now();
+    let timeout = Duration::from_secs(config.timeout_secs.unwrap_or(DEFAULT_TIMEOUT_SECS));
+    let child = process
+        .spawn()
+        .map_err(|e| format!("Failed to run `{}`: {}", command.join(" "), e))?;
+    let output = tokio::time::timeout(timeout, child.wait_with_output()).await;
+    let duration_ms = started.elapsed().as_millis() as u64;
+
+    let Ok(output) = output else {
+        if let Some(container) = &container {
+            let kill = tokio::process::Command::new(runtime)
+                .args(["kill", container])
+                .stdout(Stdio::null())
+                .stderr(Stdio::null())
+                .status()
+                .await;
+            if let Err(e) = kill {
+                eprintln!("⚠️  Failed to kill test container {}: {}", container, e);
+            }
+        }
+        return Ok(TestRunResult {
+            command,
+            passed: false,
+            exit_code: None,
+            timed_out: true,
+            tests_passed: None,
+            tests_failed: None,
+            output_tail: format!("Killed after {} seconds", timeout.as_secs()),
+            duration_ms,
+            tests: vec![],
+        });
+    };
+    let output = output?;
+    let combined = format!(
+        "{}{}",
+        String::from_utf8_lossy(&output.stdout),
+        String::from_utf8_lossy(&output.stderr)
+    );
+    let (tests_passed, tests_failed) = parse_test_counts(&combined);
+    let lines: Vec<&str> = combined.lines().collect();
+
+    Ok(TestRunResult {
+        command,
+        passed: output.status.success(),
+        exit_code: output.status.code(),
+        timed_out: false,
+        tests_passed,
+        tests_failed,
+        output_tail: lines[lines.len().saturating_sub(OUTPUT_TAIL_LINES)..].join("\n"),
+        duration_ms,
+        tests: parse_test_outcomes(&combined),
+    })
+}
+
+/// Check out the tests with the solution applied (or reverted) and run them
+#[cfg(feature = "git")]
+#[allow(clippy::too_many_arguments)]
+pub(crate) async fn run_tests_on_solution(
+    test_repo_url: &str,
+    test_commit: &str,
+    solution_repo_url: &str,
+    solution_commit1: &str,
+    solution_commit2: &str,
+    with_solution: bool,
+    config: &ExecutionConfig,
+    options: &AnalysisOptions,
+) -> Result<TestRunResult, Box<dyn Error>> {
+    let urls = [
+        test_repo_url,
+        test_commit,
+        solution_repo_url,
+        solution_commit1,
+        solution_commit2,
+    ]
+    .map(str::to_string);
+    let workdir = spawn_git(options, move |options| {
+        let [
+            test_repo_url,
+            test_commit,
+            solution_repo_url,
+            solution_commit1,
+            solution_commit2,
+        ] = &urls;
+        prepare_test_workspace(
+            test_repo_url,
+            test_commit,
+            solution_repo_url,
+            solution_commit1,
+            solution_commit2,
+            with_solution,
+            options,
+        )
+    })
+    .await?;
+    eprintln!(
+        "🧪 Running tests {} the solution in {}",
+        if with_solution { "with" } else { "without" },
+        workdir.display()
+    );
+    let run = run_tests(&workdir, config).await;
+    if let Err(e) = std::fs::remove_dir_all(&workdir) {
+        eprintln!(
+            "⚠️  Failed to remove the test workspace {}: {}",
+            workdir.display(),
+            e
+        );
+    }
+    run
+}
+
+/// Fold a test run into a verdict
+///
+/// Failing tests mean the intent is not fulfilled, whatever the model concluded. Passing tests
+/// don't overrule a negative verdict (tests can pass without the intended change), but when the
+/// run agrees with the model the confidence moves halfway towards 1.0. Inconclusive runs leave
+/// the verdict alone and are reported as a warning.
+pub fn merge_test_run(result: &mut IntentVerificationResult, run: TestRunResult) {
+    if let Some(reason) = run.inconclusive_reason() {
+        warn_inconclusive(result, &run, true, reason);
+        result.test_run = Some(run);
+        return;
+    }
+    let agrees = run.passed == result.is_intent_fulfilled;
+    let outcome = match (run.passed, run.timed_out) {
+        (true, _) => "passed",
+        (false, true) => "timed out",
+        (false, false) => "failed",
+    };
+    result.explanation = format!(
+        "{}; the tests {} when run (`{}`)",
+        result.explanation,
+        outcome,
+        run.command.join(" ")
+    );
+
+    if agrees {
+        result.confidence = (result.confidence + 1.0) / 2.0;
+    } else if !run.passed {
+        result.is_intent_fulfilled = false;
+    }
+    result.test_run = Some(run);
+}
+
+/// Fold the run without the solution into a verdict
+///
+/// Tests that already pass without the solution don't show that the solution does anything,
+/// so the intent is not considered fulfilled. When both runs list the outcomes of the test
+/// functions named by the intent, those decide: at least one must fail without the solution
+/// and pass with it. Otherwise the whole suite must fail without the solution. Inconclusive
+/// runs leave the verdict alone and are reported as a warning.
+pub fn merge_counterfactual_run(result: &mut IntentVerificationResult, run: TestRunResult) {
+    if let Some(reason) = run.inconclusive_reason() {
+        warn_inconclusive(result, &run, false, reason);
+        result.test_run_without_solution = Some(run);
+        return;
+    }
+    let named: Vec<&str> = result
+        .target_resolution
+        .iter()
+        .filter(|target| target.kind == TargetKind::Function)
+        .map(|target| target.name.as_str())
+        .collect();
+    let with_solution = result.test_run.as_ref().map_or(&[][..], |run| &run.tests);
+    let outcome = |tests: &[TestOutcome], name: &str| {
+        let mut matching = tests.iter().filter(|test| test.is_named(name)).peekable();
+        matching.peek()?;
+        Some(matching.all(|test| test.passed))
+    };
+    let compared: Vec<(bool, bool)> = named
+        .iter()
+        .filter_map(|name| Some((outcome(&run.tests, name)?, outcome(with_solution, name)?)))
+        .collect();
+
+    if !compared.is_empty() {
+        let fixed = compared
+            .iter()
+            .filter(|(before, after)| !before && *after)
+            .count();
+        if fixed == 0 {
+            result.is_intent_fulfilled = false;
+            result.explanation = format!(
+                "{}; none of the named tests went from failing without the solution to passing \
+                 with it",
+                result.explanation
+            );
+        } else {
+            result.explanation = format!(
+                "{}; {} of {} named tests failed without the solution and passed with it",
+                result.explanation,
+                fixed,
+                compared.len()
+            );
+        }
+    } else if run.passed {
+        result.is_intent_fulfilled = false;
+        result.explanation = format!(
+            "{}; the tests also passed without the solution",
+            result.explanation
+        );
+    } else {
+        result.explanation = format!(
+            "{}; the tests failed without the solution",
+            result.explanation
+        );
+    }
+    result.test_run_without_solution = Some(run);
+}
+
+/// Record that the run with or without the solution couldn't tell anything about it
+fn warn_inconclusive(
+    result: &mut IntentVerificationResult,
+    run: &TestRunResult,
+    with_solution: bool,
+    reason: &str,
+) {
+    result.warnings.push(Warning {
+        kind: WarningKind::TestExecution,
+        file_path: None,
+        message: format!(
+            "The tests {} the solution were inconclusive (`{}`): {}",
+            if with_solution { "with" } else { "without" },
+            run.command.join(" "),
+            reason
+        ),
+    });
+    result.is_partial = true;
+}
@@ -1,48 +1,51 @@
-use std::ffi::{CStr, CString};
+use async_openai::error::OpenAIError;
+use std::cell::RefCell;
+use std::ffi::{CStr, CString, c_void};
 use std::os::raw::c_char;
+use std::panic::AssertUnwindSafe;
+use std::sync::OnceLock;
 
-use crate::openai::{ask_openai_internal, verify_intent};
+use crate::git::{ChangeType, get_git_changed_files, read_test_targets_code};
+use crate::openai::{
+    ask_openai_internal, extract_test_targets_with_ai, verify_intent_with_options,
+};
+use crate::options::AnalysisOptions;
+use crate::progress::Cancelled;
+use crate::types::{IntentVerificationResult, TestTargets};
+use crate::validation::ValidationError;
 
 /// FFI: Call OpenAI from C/FFI
+/// Returns NULL on failure; see `iv_last_error_code` / `iv_last_error_message`
+/// Thread-safe: may be called concurrently from multiple threads
+///
+/// # Safety
+/// `prompt` and `api_key` must be NULL or NUL-terminated strings that stay valid for the
+/// duration of the call
 #[unsafe(no_mangle)]
-pub extern "C" fn ask_openai(prompt: *const c_char, api_key: *const c_char) -> *mut c_char {
-    let prompt_c_str = unsafe {
-        if prompt.is_null() {
-            return std::ptr::null_mut();
-        }
-        CStr::from_ptr(prompt)
-    };
-
-    let api_key_c_str = unsafe {
-        if api_key.is_null() {
-            return std::ptr::null_mut();
-        }
-        CStr::from_ptr(api_key)
-    };
+pub unsafe extern "C" fn ask_openai(prompt: *const c_char, api_key: *const c_char) -> *mut c_char {
+    let result = (|| {
+        let prompt_str = unsafe { arg_str(prompt, "prompt") }?;
+        let api_key_str = unsafe { arg_str(api_key, "api_key") }?;
 
-    let prompt_str = match prompt_c_str.to_str() {
-        Ok(s) => s,
-        Err(_) => return std::ptr::null_mut(),
-    };
-
-    let api_key_str = match api_key_c_str.to_str() {
-        Ok(s) => s,
-        Err(_) => return std::ptr::null_mut(),
-    };
+        block_on(|| async {
+            ask_openai_internal(prompt_str, api_key_str, None, None)
+                .await
+                .map_err(|e| FfiError::from_error(e.as_ref()))
+        })?
+    })();
 
-    let result = tokio::runtime::Runtime::new()
-        .unwrap()
-        .block_on(ask_openai_internal(prompt_str, api_key_str, None, None));
-
-    match result {
-        Ok(output) => CString::new(output).unwrap().into_raw(),
-        Err(_) => std::ptr::null_mut(),
+    match record(result) {
+        Some(output) => to_c_string(&output),
+        None => std::ptr::null_mut(),
     }
 }
 
 /// FFI: Free string allocated by ask_openai
+///
+/// # Safety
+/// `ptr` must be NULL or a string returned by this library that hasn't been freed yet
 #[unsafe(no_mangle)]
-pub extern "C" fn free_str(ptr: *mut c_char) {
+pub unsafe extern "C" fn free_str(ptr: *mut c_char) {
     if ptr.is_null() {
         return;
     }
@@ -52,9 +55,16 @@ pub extern "C" fn free_str(ptr: *mut c_char) {
 }
 
 /// FFI: Verify test intent with code changes
-/// Returns a JSON string with the verification result
+/// Returns a JSON string with the verification result, or NULL on failure
+/// (see `iv_last_error_code` / `iv_last_error_message`)
+/// Thread-safe: may be called concurrently from multiple threads
+///
+/// # Safety
+/// Every string argument must be NULL or a NUL-terminated string that stays valid for the
+/// duration of the call
 #[unsafe(no_mangle)]
-pub extern "C" fn verify_intent_c(
+#[allow(clippy::too_many_arguments)]
+pub unsafe extern "C" fn verify_intent_c(
     test_repo_url: *const c_char,
     test_commit: *const c_char,
     solution_repo_url: *const c_char,
@@ -65,77 +75,631 @@ pub extern "C" fn verify_intent_c(
     model: *const c_char,
     base_url: *const c_char,
 ) -> *mut c_char {
-    // Helper to convert c_char pointer to Option<&str>
-    let to_str = |ptr: *const c_char| -> Option<String> {
-        if ptr.is_null() {
-            return None;
-        }
-        unsafe { CStr::from_ptr(ptr).to_str().ok().map(|s| s.to_string()) }
+    let result = unsafe {
+        run_verify_intent(
+            test_repo_url,
+            test_commit,
+            solution_repo_url,
+            solution_commit1,
+            solution_commit2,
+            user_intent,
+            api_key,
+            model,
+            base_url,
+        )
     };
 
-    // Convert all required parameters
-    let test_repo_url_str = match to_str(test_repo_url) {
-        Some(s) => s,
-        None => return std::ptr::null_mut(),
-    };
+    to_c_json(result)
+}
 
-    let test_commit_str = match to_str(test_commit) {
-        Some(s) => s,
-        None => return std::ptr::null_mut(),
-    };
+/// FFI: Extract the functions and files a prompt expects to work
+/// Returns `TestTargets` as JSON, or NULL on failure
+///
+/// # Safety
+/// Every argument must be NULL or a NUL-terminated string that stays valid for the duration
+/// of the call
+#[unsafe(no_mangle)]
+pub unsafe extern "C" fn extract_test_targets_with_ai_c(
+    prompt: *const c_char,
+    api_key: *const c_char,
+    model: *const c_char,
+    base_url: *const c_char,
+) -> *mut c_char {
+    let result = (|| {
+        let prompt_str = unsafe { arg_str(prompt, "prompt") }?;
+        let api_key_str = unsafe { arg_str(api_key, "api_key") }?;
+        let model_opt = unsafe { opt_arg_str(model, "model") }?;
+        let base_url_opt = unsafe { opt_arg_str(base_url, "base_url") }?;
 
-    let solution_repo_url_str = match to_str(solution_repo_url) {
-        Some(s) => s,
-        None => return std::ptr::null_mut(),
-    };
+        block_on(|| async {
+            extract_test_targets_with_ai(prompt_str, api_key_str, model_opt, base_url_opt)
+                .await
+                .map_err(|e| FfiError::from_error(e.as_ref()))
+        })?
+    })();
 
-    let solution_commit1_str = match to_str(solution_commit1) {
-        Some(s) => s,
-        None => return std::ptr::null_mut(),
-    };
+    to_c_json(result)
+}
 
-    let solution_commit2_str = match to_str(solution_commit2) {
-        Some(s) => s,
-        None => return std::ptr::null_mut(),
-    };
+/// FFI: Read the code for test targets from a repository at a commit
+/// `targets_json` is a `TestTargets` object; returns `TestTargetsWithCode` as JSON, or NULL on failure
+///
+/// # Safety
+/// Every argument must be NULL or a NUL-terminated string that stays valid for the duration
+/// of the call
+#[unsafe(no_mangle)]
+pub unsafe extern "C" fn read_test_targets_code_c(
+    targets_json: *const c_char,
+    repo_url: *const c_char,
+    commit: *const c_char,
+) -> *mut c_char {
+    let result = (|| {
+        let targets: TestTargets =
+            serde_json::from_str(unsafe { arg_str(targets_json, "targets_json") }?)
+                .map_err(|e| FfiError::new(IvErrorCode::InvalidJson, e.to_string()))?;
+        let repo_url_str = unsafe { arg_str(repo_url, "repo_url") }?;
+        let commit_str = unsafe { arg_str(commit, "commit") }?;
 
-    let user_intent_str = match to_str(user_intent) {
-        Some(s) => s,
-        None => return std::ptr::null_mut(),
-    };
+        catch_panic(|| {
+            read_test_targets_code(&targets, repo_url_str, commit_str)
+                .map_err(|e| FfiError::from_error(e.as_ref()))
+        })?
+    })();
+
+    to_c_json(result)
+}
+
+/// FFI: List files changed between two commits
+/// Returns an array of `FileChange` as JSON, or NULL on failure
+///
+/// # Safety
+/// Every argument must be NULL or a NUL-terminated string that stays valid for the duration
+/// of the call
+#[unsafe(no_mangle)]
+pub unsafe extern "C" fn get_git_changed_files_c(
+    repo_url: *const c_char,
+    commit_hash_1: *const c_char,
+    commit_hash_2: *const c_char,
+) -> *mut c_char {
+    let result = (|| {
+        let repo_url_str = unsafe { arg_str(repo_url, "repo_url") }?;
+        let commit1_str = unsafe { arg_str(commit_hash_1, "commit_hash_1") }?;
+        let commit2_str = unsafe { arg_str(commit_hash_2, "commit_hash_2") }?;
+
+        catch_panic(|| {
+            get_git_changed_files(repo_url_str, commit1_str, commit2_str)
+                .map_err(|e| FfiError::from_error(e.as_ref()))
+        })?
+    })();
+
+    to_c_json(result)
+}
+
+/// Kind of change made to a file (C mirror of `ChangeType`)
+#[repr(C)]
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum CChangeType {
+    Added,
+    Modified,
+    Deleted,
+}
+
+/// Analysis of one changed file (C mirror of `FileIntentAnalysis`)
+#[repr(C)]
+pub struct CFileIntentAnalysis {
+    pub file_path: *mut c_char,
+    pub change_type: CChangeType,
+    pub supports_intent: bool,
+    pub reasoning: *mut c_char,
+    pub risk_score: f32,
+}
 
-    let api_key_str = match to_str(api_key) {
-        Some(s) => s,
-        None => return std::ptr::null_mut(),
+/// Verification result with typed fields (C mirror of `IntentVerificationResult`)
+///
+/// Must be released with `iv_result_free`.
+#[repr(C)]
+pub struct CIntentVerificationResult {
+    pub is_intent_fulfilled: bool,
+    pub confidence: f32,
+    pub risk_score: f32,
+    pub is_partial: bool,
+    pub explanation: *mut c_char,
+    pub overall_assessment: *mut c_char,
+    /// Array of `files_analyzed_len` entries
+    pub files_analyzed: *mut CFileIntentAnalysis,
+    pub files_analyzed_len: usize,
+}
+
+/// FFI: Verify test intent with code changes
+/// Returns a typed result (free with `iv_result_free`), or NULL on failure
+///
+/// # Safety
+/// Every argument must be NULL or a NUL-terminated string that stays valid for the duration
+/// of the call
+#[unsafe(no_mangle)]
+#[allow(clippy::too_many_arguments)]
+pub unsafe extern "C" fn verify_intent_typed_c(
+    test_repo_url: *const c_char,
+    test_commit: *const c_char,
+    solution_repo_url: *const c_char,
+    solution_commit1: *const c_char,
+    solution_commit2: *const c_char,
+    user_intent: *const c_char,
+    api_key: *const c_char,
+    model: *const c_char,
+    base_url: *const c_char,
+) -> *mut CIntentVerificationResult {
+    let result = unsafe {
+        run_verify_intent(
+            test_repo_url,
+            test_commit,
+            solution_repo_url,
+            solution_commit1,
+            solution_commit2,
+            user_intent,
+            api_key,
+            model,
+            base_url,
+        )
     };
 
-    // Optional parameters
-    let model_opt = to_str(model);
-    let base_url_opt = to_str(base_url);
-
-    // Call the async function
-    let result = tokio::runtime::Runtime::new()
-        .unwrap()
-        .block_on(verify_intent(
-            &test_repo_url_str,
-            &test_commit_str,
-            &solution_repo_url_str,
-            &solution_commit1_str,
-            &solution_commit2_str,
-            &user_intent_str,
-            &api_key_str,
-            model_opt.as_deref(),
-            base_url_opt.as_deref(),
+    match record(result) {
+        Some(verification_result) => to_c_result(&verification_result),
+        None => std::ptr::null_mut(),
+    }
+}
+
+/// FFI: Convert a JSON result (as returned by `verify_intent_c`) into a typed result
+/// Returns NULL if the JSON is invalid
+///
+/// # Safety
+/// `json` must be NULL or point to a NUL-terminated string that stays valid for the
+/// duration of the call
+#[unsafe(no_mangle)]
+pub unsafe extern "C" fn iv_result_from_json(
+    json: *const c_char,
+) -> *mut CIntentVerificationResult {
+    let result = unsafe { arg_str(json, "json") }.and_then(|json_str| {
+        serde_json::from_str::<IntentVerificationResult>(json_str)
+            .map_err(|e| FfiError::new(IvErrorCode::InvalidJson, e.to_string()))
+    });
+
+    match record(result) {
+        Some(result) => to_c_result(&result),
+        None => std::ptr::null_mut(),
+    }
+}
+
+/// FFI: Number of analyzed files in a typed result
+///
+/// # Safety
+/// `result` must be NULL or a result returned by this library that hasn't been freed yet
+#[unsafe(no_mangle)]
+pub unsafe extern "C" fn iv_result_file_count(result: *const CIntentVerificationResult) -> usize {
+    if result.is_null() {
+        return 0;
+    }
+    unsafe { (*result).files_analyzed_len }
+}
+
+/// FFI: Analyzed file at `index`, or NULL if out of range
+/// The pointer is owned by the result and valid until `iv_result_free`
+///
+/// # Safety
+/// `result` must be NULL or a result returned by this library that hasn't been freed yet
+#[unsafe(no_mangle)]
+pub unsafe extern "C" fn iv_result_file_at(
+    result: *const CIntentVerificationResult,
+    index: usize,
+) -> *const CFileIntentAnalysis {
+    if result.is_null() {
+        return std::ptr::null();
+    }
+    unsafe {
+        let result = &*result;
+        if index >= result.files_analyzed_len {
+            return std::ptr::null();
+        }
+        result.files_analyzed.add(index)
+    }
+}
+
+/// FFI: Free a typed result and all strings it owns
+///
+/// # Safety
+/// `result` must be NULL or a result returned by this library that hasn't been freed yet;
+/// the strings and files it owns must not be used afterwards
+#[unsafe(no_mangle)]
+pub unsafe extern "C" fn iv_result_free(result: *mut CIntentVerificationResult) {
+    if result.is_null() {
+        return;
+    }
+    unsafe {
+        let result = Box::from_raw(result);
+        // The strings were allocated by `to_c_string` when the result was built
+        free_str(result.explanation);
+        free_str(result.overall_assessment);
+        let files = Box::from_raw(std::ptr::slice_from_raw_parts_mut(
+            result.files_analyzed,
+            result.files_analyzed_len,
         ));
+        for file in files.iter() {
+            free_str(file.file_path);
+            free_str(file.reasoning);
+        }
+    }
+}
 
-    match result {
-        Ok(verification_result) => {
-            // Serialize the result to JSON
-            match serde_json::to_string(&verification_result) {
-                Ok(json) => CString::new(json).unwrap().into_raw(),
-                Err(_) => std::ptr::null_mut(),
+/// Convert a result into its heap-allocated C mirror
+fn to_c_result(result: &IntentVerificationResult) -> *mut CIntentVerificationResult {
+    let files: Box<[CFileIntentAnalysis]> = result
+        .files_analyzed
+        .iter()
+        .map(|fa| CFileIntentAnalysis {
+            file_path: to_c_string(&fa.file_path),
+            change_type: match fa.change_type {
+                ChangeType::Added => CChangeType::Added,
+                ChangeType::Modified => CChangeType::Modified,
+                ChangeType::Deleted => CChangeType::Deleted,
+            },
+            supports_intent: fa.supports_intent,
+            reasoning: to_c_string(&fa.reasoning),
+            risk_score: fa.risk_score,
+        })
+        .collect();
+    let files_analyzed_len = files.len();
+
+    Box::into_raw(Box::new(CIntentVerificationResult {
+        is_intent_fulfilled: result.is_intent_fulfilled,
+        confidence: result.confidence,
+        risk_score: result.risk_score,
+        is_partial: result.is_partial,
+        explanation: to_c_string(&result.explanation),
+        overall_assessment: to_c_string(&result.overall_assessment),
+        files_analyzed: Box::into_raw(files) as *mut CFileIntentAnalysis,
+        files_analyzed_len,
+    }))
+}
+
+/// Serialize a successful result to a JSON C string, recording the error otherwise
+pub(crate) fn to_c_json<T: serde::Serialize>(result: Result<T, FfiError>) -> *mut c_char {
+    let json = result.and_then(|value| {
+        serde_json::to_string(&value)
+            .map_err(|e| FfiError::new(IvErrorCode::Unknown, e.to_string()))
+    });
+
+    match record(json) {
+        Some(json) => to_c_string(&json),
+        None => std::ptr::null_mut(),
+    }
+}
+
+/// Allocate a C string, dropping interior NUL bytes that C can't represent
+pub(crate) fn to_c_string(s: &str) -> *mut c_char {
+    CString::new(s.replace('\0', ""))
+        .unwrap_or_default()
+        .into_raw()
+}
+
+/// Convert the raw FFI arguments and run `verify_intent` on the shared runtime
+///
+/// # Safety
+/// Every argument must be NULL or a valid NUL-terminated string
+#[allow(clippy::too_many_arguments)]
+unsafe fn run_verify_intent(
+    test_repo_url: *const c_char,
+    test_commit: *const c_char,
+    solution_repo_url: *const c_char,
+    solution_commit1: *const c_char,
+    solution_commit2: *const c_char,
+    user_intent: *const c_char,
+    api_key: *const c_char,
+    model: *const c_char,
+    base_url: *const c_char,
+) -> Result<IntentVerificationResult, FfiError> {
+    let args = unsafe {
+        VerifyArgs::from_raw(
+            test_repo_url,
+            test_commit,
+            solution_repo_url,
+            solution_commit1,
+            solution_commit2,
+            user_intent,
+            api_key,
+            model,
+            base_url,
+        )
+    }?;
+
+    block_on(|| args.run())?
+}
+
+/// Owned copy of the `verify_intent` FFI arguments
+pub(crate) struct VerifyArgs {
+    test_repo_url: String,
+    test_commit: String,
+    solution_repo_url: String,
+    solution_commit1: String,
+    solution_commit2: String,
+    user_intent: String,
+    api_key: String,
+    pub(crate) model: Option<String>,
+    pub(crate) base_url: Option<String>,
+    pub(crate) options: AnalysisOptions,
+}
+
+impl VerifyArgs {
+    /// Copy the arguments of a `verify_intent` FFI call
+    ///
+    /// # Safety
+    /// Every argument must be NULL or a valid NUL-terminated string
+    #[allow(clippy::too_many_arguments)]
+    pub(crate) unsafe fn from_raw(
+        test_repo_url: *const c_char,
+        test_commit: *const c_char,
+        solution_repo_url: *const c_char,
+        solution_commit1: *const c_char,
+        solution_commit2: *const c_char,
+        user_intent: *const c_char,
+        api_key: *const c_char,
+        model: *const c_char,
+        base_url: *const c_char,
+    ) -> Result<Self, FfiError> {
+        Ok(VerifyArgs {
+            // Required parameters
+            test_repo_url: unsafe { arg_str(test_repo_url, "test_repo_url") }?.to_string(),
+            test_commit: unsafe { arg_str(test_commit, "test_commit") }?.to_string(),
+            solution_repo_url: unsafe { arg_str(solution_repo_url, "solution_repo_url") }?
+                .to_string(),
+            solution_commit1: unsafe { arg_str(solution_commit1, "solution_commit1") }?.to_string(),
+            solution_commit2: unsafe { arg_str(solution_commit2, "solution_commit2") }?.to_string(),
+            user_intent: unsafe { arg_str(user_intent, "user_intent") }?.to_string(),
+            api_key: unsafe { arg_str(api_key, "api_key") }?.to_string(),
+            // Optional parameters
+            model: unsafe { opt_arg_str(model, "model") }?.map(str::to_string),
+            base_url: unsafe { opt_arg_str(base_url, "base_url") }?.map(str::to_string),
+            options: AnalysisOptions::default(),
+        })
+    }
+
+    pub(crate) async fn run(&self) -> Result<IntentVerificationResult, FfiError> {
+        verify_intent_with_options(
+            &self.test_repo_url,
+            &self.test_commit,
+            &self.solution_repo_url,
+            &self.solution_commit1,
+            &self.solution_commit2,
+            &self.user_intent,
+            &self.api_key,
+            self.model.as_deref(),
+            self.base_url.as_deref(),
+            &self.options,
+        )
+        .await
+        .map_err(|e| FfiError::from_error(e.as_ref()))
+    }
+}
+
+/// Host pointer handed back to callbacks untouched
+#[derive(Debug, Clone, Copy)]
+pub(crate) struct UserData(pub(crate) *mut c_void);
+
+// The library never dereferences the pointer; thread-safety of what it points to is the host's
+// responsibility, as documented on the functions taking callbacks
+unsafe impl Send for UserData {}
+unsafe impl Sync for UserData {}
+
+/// Runtime shared by every FFI call, created on first use
+///
+/// Multi-threaded, so FFI functions may be called concurrently from any number of host threads.
+static RUNTIME: OnceLock<tokio::runtime::Runtime> = OnceLock::new();
+
+pub(crate) fn shared_runtime() -> Result<&'static tokio::runtime::Runtime, FfiError> {
+    if let Some(runtime) = RUNTIME.get() {
+        return Ok(runtime);
+    }
+    let runtime = tokio::runtime::Builder::new_multi_thread()
+        .enable_all()
+        .thread_name("intent-verification")
+        .build()
+        .map_err(|e| FfiError::new(IvErrorCode::Runtime, e.to_string()))?;
+    // If another thread won the race, our runtime is simply dropped
+    Ok(RUNTIME.get_or_init(|| runtime))
+}
+
+/// Block the calling thread on a future driven by the shared runtime
+///
+/// Blocking on a runtime from inside another runtime panics, so when the caller is already
+/// on a Tokio thread (e.g. a Rust host that also links this library) the future is driven
+/// from a helper thread instead. A panic becomes a `Runtime` error rather than unwinding into
+/// the host.
+pub(crate) fn block_on<F>(make_future: impl FnOnce() -> F + Send) -> Result<F::Output, FfiError>
+where
+    F: Future,
+    F::Output: Send,
+{
+    let runtime = shared_runtime()?;
+    if tokio::runtime::Handle::try_current().is_err() {
+        return catch_panic(|| runtime.block_on(make_future()));
+    }
+    std::thread::scope(|scope| {
+        scope
+            .spawn(|| runtime.block_on(make_future()))
+            .join()
+            .map_err(|panic| FfiError::from_panic(panic.as_ref()))
+    })
+}
+
+/// Run `f`, turning a panic into a `Runtime` error rather than unwinding into the host
+pub(crate) fn catch_panic<T>(f: impl FnOnce() -> T) -> Result<T, FfiError> {
+    std::panic::catch_unwind(AssertUnwindSafe(f))
+        .map_err(|panic| FfiError::from_panic(panic.as_ref()))
+}
+
+/// Category of the last FFI failure on the calling thread
+#[repr(C)]
+#[derive(Debug, Clone, Copy, PartialEq, serde::Serialize)]
+pub enum IvErrorCode {
+    /// The last call succeeded
+    Ok = 0,
+    /// A required pointer argument was NULL
+    NullArgument = 1,
+    /// A string argument was not valid UTF-8
+    InvalidUtf8 = 2,
+    /// A JSON argument could not be parsed
+    InvalidJson = 3,
+    /// Cloning or reading a repository failed (bad URL, unknown commit, network)
+    Git = 4,
+    /// The LLM API rejected the credentials
+    Authentication = 5,
+    /// Any other LLM API failure (rate limit, unreachable endpoint, bad response)
+    Api = 6,
+    /// The async runtime could not be started
+    Runtime = 7,
+    Unknown = 8,
+    /// The operation was stopped with `iv_cancel`
+    Cancelled = 9,
+    /// An argument was rejected before any work started, such as an empty intent
+    InvalidInput = 10,
+}
+
+/// Error recorded for `iv_last_error_code` / `iv_last_error_message`
+#[derive(Debug, Clone)]
+pub(crate) struct FfiError {
+    pub(crate) code: IvErrorCode,
+    pub(crate) message: String,
+}
+
+impl FfiError {
+    pub(crate) fn new(code: IvErrorCode, message: impl Into<String>) -> Self {
+        FfiError {
+            code,
+            message: message.into(),
+        }
+    }
+
+    /// Error for a verification that panicked instead of returning
+    pub(crate) fn from_panic(panic: &(dyn std::any::Any + Send)) -> Self {
+        let message = panic
+            .downcast_ref::<&str>()
+            .copied()
+            .or_else(|| panic.downcast_ref::<String>().map(String::as_str))
+            .unwrap_or("unknown cause");
+        FfiError::new(
+            IvErrorCode::Runtime,
+            format!("verification panicked: {}", message),
+        )
+    }
+
+    /// Classify an error returned by the library
+    pub(crate) fn from_error(error: &(dyn std::error::Error + 'static)) -> Self {
+        let code = if error.downcast_ref::<Cancelled>().is_some() {
+            IvErrorCode::Cancelled
+        } else if error.downcast_ref::<git2::Error>().is_some() {
+            IvErrorCode::Git
+        } else if let Some(invalid) = error.downcast_ref::<ValidationError>() {
+            match invalid {
+                _ if invalid.is_repository_error() => IvErrorCode::Git,
+                ValidationError::MissingApiKey | ValidationError::InvalidApiKey { .. } => {
+                    IvErrorCode::Authentication
+                }
+                _ => IvErrorCode::InvalidInput,
             }
+        } else if let Some(openai_error) = error.downcast_ref::<OpenAIError>() {
+            match openai_error {
+                OpenAIError::ApiError(api_error)
+                    if api_error.code.as_deref() == Some("invalid_api_key")
+                        || api_error.r#type.as_deref() == Some("authentication_error") =>
+                {
+                    IvErrorCode::Authentication
+                }
+                OpenAIError::Reqwest(e) if e.status().map(|s| s.as_u16()) == Some(401) => {
+                    IvErrorCode::Authentication
+                }
+                _ => IvErrorCode::Api,
+            }
+        } else if error.downcast_ref::<serde_json::Error>().is_some() {
+            IvErrorCode::InvalidJson
+        } else {
+            IvErrorCode::Unknown
+        };
+
+        FfiError::new(code, error.to_string())
+    }
+}
+
+thread_local! {
+    static LAST_ERROR: RefCell<Option<FfiError>> = const { RefCell::new(None) };
+}
+
+/// Store the outcome of an FFI call as the thread's last error (or clear it on success)
+pub(crate) fn record<T>(result: Result<T, FfiError>) -> Option<T> {
+    match result {
+        Ok(value) => {
+            LAST_ERROR.with(|e| *e.borrow_mut() = None);
+            Some(value)
+        }
+        Err(error) => {
+            LAST_ERROR.with(|e| *e.borrow_mut() = Some(error));
+            None
         }
-        Err(_) => std::ptr::null_mut(),
     }
 }
+
+/// Borrow a required string argument
+///
+/// # Safety
+/// `ptr` must be NULL or point to a NUL-terminated string that outlives `'a`
+pub(crate) unsafe fn arg_str<'a>(ptr: *const c_char, name: &str) -> Result<&'a str, FfiError> {
+    unsafe { opt_arg_str(ptr, name) }?.ok_or_else(|| {
+        FfiError::new(
+            IvErrorCode::NullArgument,
+            format!("argument `{}` is NULL", name),
+        )
+    })
+}
+
+/// Borrow an optional string argument; NULL means "not set"
+///
+/// # Safety
+/// `ptr` must be NULL or point to a NUL-terminated string that outlives `'a`
+pub(crate) unsafe fn opt_arg_str<'a>(
+    ptr: *const c_char,
+    name: &str,
+) -> Result<Option<&'a str>, FfiError> {
+    if ptr.is_null() {
+        return Ok(None);
+    }
+    // SAFETY: non-NULL, and valid for `'a` per the caller's contract
+    unsafe { CStr::from_ptr(ptr) }
+        .to_str()
+        .map(Some)
+        .map_err(|e| {
+            FfiError::new(
+                IvErrorCode::InvalidUtf8,
+                format!("argument `{}` is not valid UTF-8: {}", name, e),
+            )
+        })
+}
+
+/// FFI: Error code of the last failed call on this thread (`Ok` if it succeeded)
+#[unsafe(no_mangle)]
+pub extern "C" fn iv_last_error_code() -> IvErrorCode {
+    LAST_ERROR.with(|e| e.borrow().as_ref().map_or(IvErrorCode::Ok, |e| e.code))
+}
+
+/// FFI: Message of the last failed call on this thread, or NULL if it succeeded
+/// The returned string must be released with `free_str`
+#[unsafe(no_mangle)]
+pub extern "C" fn iv_last_error_message() -> *mut c_char {
+    LAST_ERROR.with(|e| match e.borrow().as_ref() {
+        Some(error) => to_c_string(&error.message),
+        None => std::ptr::null_mut(),
+    })
+}
+
+/// FFI: Clear the last error on this thread
+#[unsafe(no_mangle)]
+pub extern "C" fn iv_clear_error() {
+    LAST_ERROR.with(|e| *e.borrow_mut() = None);
+}
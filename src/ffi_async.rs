@@ -0,0 +1,328 @@
+use std::ffi::c_void;
+use std::os::raw::c_char;
+use std::panic::AssertUnwindSafe;
+use std::sync::{Arc, Condvar, Mutex, OnceLock};
+use std::thread::ThreadId;
+
+use crate::ffi::{
+    FfiError, IvErrorCode, UserData, VerifyArgs, free_str, record, shared_runtime, to_c_string,
+};
+use crate::progress::{CancellationToken, Cancelled};
+
+/// State of an asynchronous verification job
+#[repr(C)]
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum IvJobStatus {
+    Running,
+    Succeeded,
+    Failed,
+}
+
+/// Completion callback for `verify_intent_async_c`
+///
+/// Invoked exactly once from a library-owned thread, after the job is marked finished, so
+/// `iv_poll`, `iv_wait` and `iv_job_result` may be called from inside it. `result_json` is set
+/// when the job succeeded and `error_message` when it failed; both are only valid during the
+/// call. May be NULL.
+pub type IvCompletionCallback = Option<
+    extern "C" fn(
+        user_data: *mut c_void,
+        status: IvJobStatus,
+        result_json: *const c_char,
+        error_message: *const c_char,
+    ),
+>;
+
+/// Handle to a verification running in the background
+///
+/// Opaque to C; release with `iv_job_free`.
+pub struct IvJob {
+    shared: Arc<JobShared>,
+}
+
+struct JobShared {
+    /// `None` while running, otherwise the result JSON or the failure
+    outcome: Mutex<Option<Result<String, FfiError>>>,
+    finished: Condvar,
+    /// Whether the completion callback has returned (or there is none)
+    callback_done: Mutex<bool>,
+    callback_returned: Condvar,
+    /// Thread running the job and its callback
+    worker: OnceLock<ThreadId>,
+    cancellation: CancellationToken,
+}
+
+impl JobShared {
+    fn status(outcome: &Option<Result<String, FfiError>>) -> IvJobStatus {
+        match outcome {
+            None => IvJobStatus::Running,
+            Some(Ok(_)) => IvJobStatus::Succeeded,
+            Some(Err(_)) => IvJobStatus::Failed,
+        }
+    }
+}
+
+/// FFI: Start verifying test intent in the background
+///
+/// Returns immediately with a job handle (NULL if the arguments are invalid, see
+/// `iv_last_error_code`). Poll the handle with `iv_poll`/`iv_wait`, or pass a `callback`,
+/// which is called from a worker thread with `user_data` once the job is marked finished.
+/// `iv_wait` returns only after the callback has returned, except when called from the
+/// callback itself.
+///
+/// # Safety
+/// Every string argument must be NULL or a NUL-terminated string that stays valid for the
+/// duration of the call.
+/// `user_data` is passed to `callback` from another thread, so whatever it points to must be
+/// safe to use from there
+#[unsafe(no_mangle)]
+#[allow(clippy::too_many_arguments)]
+pub unsafe extern "C" fn verify_intent_async_c(
+    test_repo_url: *const c_char,
+    test_commit: *const c_char,
+    solution_repo_url: *const c_char,
+    solution_commit1: *const c_char,
+    solution_commit2: *const c_char,
+    user_intent: *const c_char,
+    api_key: *const c_char,
+    model: *const c_char,
+    base_url: *const c_char,
+    callback: IvCompletionCallback,
+    user_data: *mut c_void,
+) -> *mut IvJob {
+    let args = unsafe {
+        VerifyArgs::from_raw(
+            test_repo_url,
+            test_commit,
+            solution_repo_url,
+            solution_commit1,
+            solution_commit2,
+            user_intent,
+            api_key,
+            model,
+            base_url,
+        )
+    };
+
+    match record(args.and_then(|args| spawn_job(args, callback, user_data))) {
+        Some(job) => Box::into_raw(Box::new(job)),
+        None => std::ptr::null_mut(),
+    }
+}
+
+/// Run a verification on a background thread, returning its handle
+pub(crate) fn spawn_job(
+    mut args: VerifyArgs,
+    callback: IvCompletionCallback,
+    user_data: *mut c_void,
+) -> Result<IvJob, FfiError> {
+    let runtime = shared_runtime()?;
+    let shared = Arc::new(JobShared {
+        outcome: Mutex::new(None),
+        finished: Condvar::new(),
+        callback_done: Mutex::new(false),
+        callback_returned: Condvar::new(),
+        worker: OnceLock::new(),
+        cancellation: CancellationToken::new(),
+    });
+    let job = Arc::clone(&shared);
+    args.options.cancellation = Some(shared.cancellation.clone());
+    let user_data = UserData(user_data);
+
+    std::thread::Builder::new()
+        .name("intent-verification-job".to_string())
+        .spawn(move || {
+            // Move the whole wrapper in; capturing only the raw pointer field isn't `Send`
+            let user_data = user_data;
+            let _ = job.worker.set(std::thread::current().id());
+            // Stop waiting as soon as the job is cancelled instead of at the next pipeline step.
+            // A panic must still finish the job, or `iv_wait` would block forever.
+            let outcome = std::panic::catch_unwind(AssertUnwindSafe(|| {
+                runtime.block_on(async {
+                    tokio::select! {
+                        result = args.run() => result,
+                        _ = job.cancellation.cancelled() => {
+                            Err(FfiError::from_error(&Cancelled))
+                        }
+                    }
+                })
+            }))
+            .unwrap_or_else(|panic| Err(FfiError::from_panic(panic.as_ref())));
+            let outcome = outcome.and_then(|result| {
+                serde_json::to_string(&result)
+                    .map_err(|e| FfiError::new(IvErrorCode::Unknown, e.to_string()))
+            });
+
+            // Mark the job finished before the callback, which may poll or wait on it
+            *job.outcome.lock().unwrap() = Some(outcome.clone());
+            job.finished.notify_all();
+
+            if let Some(callback) = callback {
+                notify(callback, user_data.0, &outcome);
+            }
+            *job.callback_done.lock().unwrap() = true;
+            job.callback_returned.notify_all();
+        })
+        .map_err(|e| FfiError::new(IvErrorCode::Runtime, e.to_string()))?;
+
+    Ok(IvJob { shared })
+}
+
+fn notify(
+    callback: extern "C" fn(*mut c_void, IvJobStatus, *const c_char, *const c_char),
+    user_data: *mut c_void,
+    outcome: &Result<String, FfiError>,
+) {
+    match outcome {
+        Ok(json) => {
+            let result_json = to_c_string(json);
+            callback(
+                user_data,
+                IvJobStatus::Succeeded,
+                result_json,
+                std::ptr::null(),
+            );
+            // SAFETY: allocated above and no longer used by the callback
+            unsafe { free_str(result_json) };
+        }
+        Err(error) => {
+            let error_message = to_c_string(&error.message);
+            callback(
+                user_data,
+                IvJobStatus::Failed,
+                std::ptr::null(),
+                error_message,
+            );
+            // SAFETY: allocated above and no longer used by the callback
+            unsafe { free_str(error_message) };
+        }
+    }
+}
+
+/// FFI: Ask a running job to stop
+///
+/// The job finishes shortly afterwards with status `Failed` and error code `Cancelled`;
+/// its callback still runs. Does nothing if the job already finished.
+///
+/// # Safety
+/// `job` must be NULL or a handle returned by this library that hasn't been freed with
+/// `iv_job_free`
+#[unsafe(no_mangle)]
+pub unsafe extern "C" fn iv_cancel(job: *const IvJob) {
+    if job.is_null() {
+        return;
+    }
+    unsafe { &(*job).shared }.cancellation.cancel();
+}
+
+/// FFI: Current status of a job without blocking
+///
+/// # Safety
+/// `job` must be NULL or a handle returned by this library that hasn't been freed with
+/// `iv_job_free`
+#[unsafe(no_mangle)]
+pub unsafe extern "C" fn iv_poll(job: *const IvJob) -> IvJobStatus {
+    if job.is_null() {
+        return IvJobStatus::Failed;
+    }
+    let shared = unsafe { &(*job).shared };
+    JobShared::status(&shared.outcome.lock().unwrap())
+}
+
+/// FFI: Block until a job finishes and return its final status
+///
+/// # Safety
+/// `job` must be NULL or a handle returned by this library that hasn't been freed with
+/// `iv_job_free`
+#[unsafe(no_mangle)]
+pub unsafe extern "C" fn iv_wait(job: *const IvJob) -> IvJobStatus {
+    if job.is_null() {
+        return IvJobStatus::Failed;
+    }
+    let shared = unsafe { &(*job).shared };
+    let status = {
+        let outcome = shared
+            .finished
+            .wait_while(shared.outcome.lock().unwrap(), |outcome| outcome.is_none())
+            .unwrap();
+        JobShared::status(&outcome)
+    };
+    // The callback waiting for itself would never return
+    if shared.worker.get() != Some(&std::thread::current().id()) {
+        drop(
+            shared
+                .callback_returned
+                .wait_while(shared.callback_done.lock().unwrap(), |done| !*done)
+                .unwrap(),
+        );
+    }
+    status
+}
+
+/// FFI: JSON result of a succeeded job (free with `free_str`), or NULL otherwise
+///
+/// # Safety
+/// `job` must be NULL or a handle returned by this library that hasn't been freed with
+/// `iv_job_free`
+#[unsafe(no_mangle)]
+pub unsafe extern "C" fn iv_job_result(job: *const IvJob) -> *mut c_char {
+    if job.is_null() {
+        return std::ptr::null_mut();
+    }
+    let shared = unsafe { &(*job).shared };
+    match shared.outcome.lock().unwrap().as_ref() {
+        Some(Ok(json)) => to_c_string(json),
+        _ => std::ptr::null_mut(),
+    }
+}
+
+/// FFI: Error code of a failed job (`Ok` while running or after success)
+///
+/// # Safety
+/// `job` must be NULL or a handle returned by this library that hasn't been freed with
+/// `iv_job_free`
+#[unsafe(no_mangle)]
+pub unsafe extern "C" fn iv_job_error_code(job: *const IvJob) -> IvErrorCode {
+    if job.is_null() {
+        return IvErrorCode::NullArgument;
+    }
+    let shared = unsafe { &(*job).shared };
+    match shared.outcome.lock().unwrap().as_ref() {
+        Some(Err(error)) => error.code,
+        _ => IvErrorCode::Ok,
+    }
+}
+
+/// FFI: Error message of a failed job (free with `free_str`), or NULL otherwise
+///
+/// # Safety
+/// `job` must be NULL or a handle returned by this library that hasn't been freed with
+/// `iv_job_free`
+#[unsafe(no_mangle)]
+pub unsafe extern "C" fn iv_job_error_message(job: *const IvJob) -> *mut c_char {
+    if job.is_null() {
+        return std::ptr::null_mut();
+    }
+    let shared = unsafe { &(*job).shared };
+    match shared.outcome.lock().unwrap().as_ref() {
+        Some(Err(error)) => to_c_string(&error.message),
+        _ => std::ptr::null_mut(),
+    }
+}
+
+/// FFI: Release a job handle
+///
+/// A job that is still running keeps going and still invokes its callback; only the handle
+/// becomes invalid.
+///
+/// # Safety
+/// `job` must be NULL or a handle returned by this library that hasn't been freed yet
+#[unsafe(no_mangle)]
+pub unsafe extern "C" fn iv_job_free(job: *mut IvJob) {
+    if job.is_null() {
+        return;
+    }
+    unsafe {
+        drop(Box::from_raw(job));
+    }
+}
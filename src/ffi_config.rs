@@ -0,0 +1,406 @@
+use std::ffi::{CString, c_void};
+use std::os::raw::c_char;
+
+use crate::ffi::{
+    FfiError, IvErrorCode, UserData, VerifyArgs, block_on, opt_arg_str, record, to_c_json,
+};
+use crate::ffi_async::{IvCompletionCallback, IvJob, spawn_job};
+use crate::options::AnalysisOptions;
+use crate::privacy::LocalOnlyPolicy;
+use crate::progress::{Progress, ProgressHandler, ProgressStage};
+
+/// Settings shared by verification calls, built with `iv_config_new` and `iv_config_set_*`
+///
+/// Opaque to C; release with `iv_config_free`. A config is copied when a call starts, so it
+/// can be changed or freed while an asynchronous job is still running.
+#[derive(Debug, Clone, Default)]
+pub struct IvConfig {
+    model: Option<String>,
+    base_url: Option<String>,
+    options: AnalysisOptions,
+}
+
+/// Pipeline stage of a progress update (C mirror of `ProgressStage`)
+#[repr(C)]
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum IvProgressStage {
+    ExtractingTargets,
+    ReadingTargets,
+    FetchingChanges,
+    AnalyzingFiles,
+    Assessing,
+    Done,
+}
+
+/// Progress update passed to an `IvProgressCallback` (C mirror of `Progress`)
+#[repr(C)]
+pub struct IvProgress {
+    pub stage: IvProgressStage,
+    pub files_done: usize,
+    pub files_total: usize,
+    /// File that just finished, or NULL
+    pub current_file: *const c_char,
+    /// Overall completion estimate (0-100)
+    pub percent: f32,
+}
+
+/// Progress callback set with `iv_config_set_progress_callback`
+///
+/// Called from library-owned threads, possibly concurrently when files are analyzed in
+/// parallel. `progress` is only valid during the call. May be NULL.
+pub type IvProgressCallback =
+    Option<extern "C" fn(user_data: *mut c_void, progress: *const IvProgress)>;
+
+/// FFI: Create a config with default settings
+#[unsafe(no_mangle)]
+pub extern "C" fn iv_config_new() -> *mut IvConfig {
+    Box::into_raw(Box::new(IvConfig::default()))
+}
+
+/// FFI: Free a config
+///
+/// # Safety
+/// `config` must be NULL or a config from `iv_config_new` that hasn't been freed yet
+#[unsafe(no_mangle)]
+pub unsafe extern "C" fn iv_config_free(config: *mut IvConfig) {
+    if config.is_null() {
+        return;
+    }
+    unsafe {
+        drop(Box::from_raw(config));
+    }
+}
+
+/// Apply a change to a config, reporting NULL configs and invalid values
+///
+/// # Safety
+/// `config` must be NULL or a live config from `iv_config_new`
+unsafe fn update_config(
+    config: *mut IvConfig,
+    update: impl FnOnce(&mut IvConfig) -> Result<(), FfiError>,
+) -> IvErrorCode {
+    let result = match unsafe { config.as_mut() } {
+        Some(config) => update(config),
+        None => Err(FfiError::new(
+            IvErrorCode::NullArgument,
+            "argument `config` is NULL",
+        )),
+    };
+    let code = result.as_ref().err().map_or(IvErrorCode::Ok, |e| e.code);
+    record(result);
+    code
+}
+
+/// Read an optional string setting; NULL resets it to the default
+///
+/// # Safety
+/// `value` must be NULL or a valid NUL-terminated string
+unsafe fn setting(value: *const c_char, name: &str) -> Result<Option<String>, FfiError> {
+    Ok(unsafe { opt_arg_str(value, name) }?.map(str::to_string))
+}
+
+/// FFI: Model to use (NULL for the default model)
+///
+/// # Safety
+/// `config` must be NULL or a live config from `iv_config_new`, and `model` NULL or a
+/// NUL-terminated string
+#[unsafe(no_mangle)]
+pub unsafe extern "C" fn iv_config_set_model(
+    config: *mut IvConfig,
+    model: *const c_char,
+) -> IvErrorCode {
+    unsafe {
+        update_config(config, |config| {
+            config.model = setting(model, "model")?;
+            Ok(())
+        })
+    }
+}
+
+/// FFI: API base URL (NULL for the OpenAI endpoint)
+///
+/// # Safety
+/// `config` must be NULL or a live config from `iv_config_new`, and `base_url` NULL or a
+/// NUL-terminated string
+#[unsafe(no_mangle)]
+pub unsafe extern "C" fn iv_config_set_base_url(
+    config: *mut IvConfig,
+    base_url: *const c_char,
+) -> IvErrorCode {
+    unsafe {
+        update_config(config, |config| {
+            config.base_url = setting(base_url, "base_url")?;
+            Ok(())
+        })
+    }
+}
+
+/// FFI: Timeout for each LLM request in seconds (0 for no timeout)
+///
+/// # Safety
+/// `config` must be NULL or a live config from `iv_config_new`
+#[unsafe(no_mangle)]
+pub unsafe extern "C" fn iv_config_set_timeout_secs(
+    config: *mut IvConfig,
+    timeout_secs: u64,
+) -> IvErrorCode {
+    unsafe {
+        update_config(config, |config| {
+            config.options.timeout_secs = (timeout_secs > 0).then_some(timeout_secs);
+            Ok(())
+        })
+    }
+}
+
+/// FFI: Number of files analyzed at the same time (0 for the default of one)
+///
+/// # Safety
+/// `config` must be NULL or a live config from `iv_config_new`
+#[unsafe(no_mangle)]
+pub unsafe extern "C" fn iv_config_set_concurrency(
+    config: *mut IvConfig,
+    concurrency: usize,
+) -> IvErrorCode {
+    unsafe {
+        update_config(config, |config| {
+            config.options.concurrency = (concurrency > 0).then_some(concurrency);
+            Ok(())
+        })
+    }
+}
+
+/// FFI: Directory repositories are cloned into (NULL for the system temp directory)
+///
+/// # Safety
+/// `config` must be NULL or a live config from `iv_config_new`, and `cache_dir` NULL or a
+/// NUL-terminated string
+#[unsafe(no_mangle)]
+pub unsafe extern "C" fn iv_config_set_cache_dir(
+    config: *mut IvConfig,
+    cache_dir: *const c_char,
+) -> IvErrorCode {
+    unsafe {
+        update_config(config, |config| {
+            config.options.cache_dir = setting(cache_dir, "cache_dir")?;
+            Ok(())
+        })
+    }
+}
+
+/// FFI: Proxy URL for LLM requests and git clones (NULL for no proxy)
+///
+/// # Safety
+/// `config` must be NULL or a live config from `iv_config_new`, and `proxy` NULL or a
+/// NUL-terminated string
+#[unsafe(no_mangle)]
+pub unsafe extern "C" fn iv_config_set_proxy(
+    config: *mut IvConfig,
+    proxy: *const c_char,
+) -> IvErrorCode {
+    unsafe {
+        update_config(config, |config| {
+            config.options.proxy = setting(proxy, "proxy")?;
+            Ok(())
+        })
+    }
+}
+
+/// FFI: Refuse network access except LLM requests to `allowed_endpoint`, e.g. an on-prem
+/// model; repositories must then be local paths (NULL to allow any)
+///
+/// # Safety
+/// `config` must be NULL or a live config from `iv_config_new`, and `allowed_endpoint`
+/// NULL or a NUL-terminated string
+#[unsafe(no_mangle)]
+pub unsafe extern "C" fn iv_config_set_local_only(
+    config: *mut IvConfig,
+    allowed_endpoint: *const c_char,
+) -> IvErrorCode {
+    unsafe {
+        update_config(config, |config| {
+            config.options.local_only = setting(allowed_endpoint, "allowed_endpoint")?
+                .map(|endpoint| LocalOnlyPolicy::new([endpoint]));
+            Ok(())
+        })
+    }
+}
+
+/// FFI: Language for reasoning and assessments, e.g. "vi" (NULL for English)
+///
+/// # Safety
+/// `config` must be NULL or a live config from `iv_config_new`, and `language` NULL or a
+/// NUL-terminated string
+#[unsafe(no_mangle)]
+pub unsafe extern "C" fn iv_config_set_language(
+    config: *mut IvConfig,
+    language: *const c_char,
+) -> IvErrorCode {
+    unsafe {
+        update_config(config, |config| {
+            config.options.language = setting(language, "language")?;
+            Ok(())
+        })
+    }
+}
+
+/// FFI: Receive progress updates for calls made with this config (NULL callback to disable)
+///
+/// # Safety
+/// `config` must be NULL or a live config from `iv_config_new`. `user_data` is passed to
+/// `callback` from library threads, so whatever it points to must be safe to use from there
+#[unsafe(no_mangle)]
+pub unsafe extern "C" fn iv_config_set_progress_callback(
+    config: *mut IvConfig,
+    callback: IvProgressCallback,
+    user_data: *mut c_void,
+) -> IvErrorCode {
+    unsafe {
+        update_config(config, |config| {
+            config.options.progress = callback.map(|callback| {
+                let user_data = UserData(user_data);
+                ProgressHandler::new(move |progress| report_progress(callback, user_data, progress))
+            });
+            Ok(())
+        })
+    }
+}
+
+fn report_progress(
+    callback: extern "C" fn(*mut c_void, *const IvProgress),
+    user_data: UserData,
+    progress: &Progress,
+) {
+    let current_file = progress
+        .current_file
+        .as_deref()
+        .map(|path| CString::new(path.replace('\0', "")).unwrap_or_default());
+    let c_progress = IvProgress {
+        stage: match progress.stage {
+            ProgressStage::ExtractingTargets => IvProgressStage::ExtractingTargets,
+            ProgressStage::ReadingTargets => IvProgressStage::ReadingTargets,
+            ProgressStage::FetchingChanges => IvProgressStage::FetchingChanges,
+            ProgressStage::AnalyzingFiles => IvProgressStage::AnalyzingFiles,
+            ProgressStage::Assessing => IvProgressStage::Assessing,
+            ProgressStage::Done => IvProgressStage::Done,
+        },
+        files_done: progress.files_done,
+        files_total: progress.files_total,
+        current_file: current_file
+            .as_ref()
+            .map_or(std::ptr::null(), |path| path.as_ptr()),
+        percent: progress.percent,
+    };
+    callback(user_data.0, &c_progress);
+}
+
+/// Combine the per-call arguments with a config (NULL config means defaults)
+///
+/// # Safety
+/// `config` must be NULL or a live config, and every string argument NULL or a valid
+/// NUL-terminated string
+#[allow(clippy::too_many_arguments)]
+unsafe fn configured_args(
+    config: *const IvConfig,
+    test_repo_url: *const c_char,
+    test_commit: *const c_char,
+    solution_repo_url: *const c_char,
+    solution_commit1: *const c_char,
+    solution_commit2: *const c_char,
+    user_intent: *const c_char,
+    api_key: *const c_char,
+) -> Result<VerifyArgs, FfiError> {
+    let mut args = unsafe {
+        VerifyArgs::from_raw(
+            test_repo_url,
+            test_commit,
+            solution_repo_url,
+            solution_commit1,
+            solution_commit2,
+            user_intent,
+            api_key,
+            std::ptr::null(),
+            std::ptr::null(),
+        )
+    }?;
+    if let Some(config) = unsafe { config.as_ref() } {
+        args.model = config.model.clone();
+        args.base_url = config.base_url.clone();
+        args.options = config.options.clone();
+    }
+    Ok(args)
+}
+
+/// FFI: Verify test intent using the settings in `config`
+/// Returns a JSON string with the verification result, or NULL on failure
+///
+/// # Safety
+/// `config` must be NULL or a live config from `iv_config_new`; every string argument must
+/// be NULL or a NUL-terminated string that stays valid for the duration of the call
+#[unsafe(no_mangle)]
+#[allow(clippy::too_many_arguments)]
+pub unsafe extern "C" fn verify_intent_with_config_c(
+    config: *const IvConfig,
+    test_repo_url: *const c_char,
+    test_commit: *const c_char,
+    solution_repo_url: *const c_char,
+    solution_commit1: *const c_char,
+    solution_commit2: *const c_char,
+    user_intent: *const c_char,
+    api_key: *const c_char,
+) -> *mut c_char {
+    let result = unsafe {
+        configured_args(
+            config,
+            test_repo_url,
+            test_commit,
+            solution_repo_url,
+            solution_commit1,
+            solution_commit2,
+            user_intent,
+            api_key,
+        )
+    }
+    .and_then(|args| block_on(|| args.run())?);
+
+    to_c_json(result)
+}
+
+/// FFI: Asynchronous variant of `verify_intent_with_config_c` (see `verify_intent_async_c`)
+///
+/// # Safety
+/// `config` must be NULL or a live config from `iv_config_new`; every string argument must
+/// be NULL or a NUL-terminated string that stays valid for the duration of the call.
+/// `user_data` is passed to `callback` from another thread, so whatever it points to must be
+/// safe to use from there
+#[unsafe(no_mangle)]
+#[allow(clippy::too_many_arguments)]
+pub unsafe extern "C" fn verify_intent_with_config_async_c(
+    config: *const IvConfig,
+    test_repo_url: *const c_char,
+    test_commit: *const c_char,
+    solution_repo_url: *const c_char,
+    solution_commit1: *const c_char,
+    solution_commit2: *const c_char,
+    user_intent: *const c_char,
+    api_key: *const c_char,
+    callback: IvCompletionCallback,
+    user_data: *mut c_void,
+) -> *mut IvJob {
+    let args = unsafe {
+        configured_args(
+            config,
+            test_repo_url,
+            test_commit,
+            solution_repo_url,
+            solution_commit1,
+            solution_commit2,
+            user_intent,
+            api_key,
+        )
+    };
+
+    match record(args.and_then(|args| spawn_job(args, callback, user_data))) {
+        Some(job) => Box::into_raw(Box::new(job)),
+        None => std::ptr::null_mut(),
+    }
+}
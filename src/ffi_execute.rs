@@ -0,0 +1,274 @@
+use std::os::raw::c_char;
+
+use crate::ffi::{FfiError, IvErrorCode, arg_str, block_on, record, to_c_string};
+use crate::git::{
+    FileChange, get_git_changed_files_with_options, read_test_targets_code_with_options,
+};
+use crate::openai::{
+    ask_openai_with_options, extract_test_targets_with_options, verify_intent_with_options,
+};
+use crate::options::AnalysisOptions;
+use crate::report::{
+    render_github_annotations, render_html, render_junit, render_markdown, render_sarif,
+};
+use crate::types::{IntentVerificationResult, PROMPT_VERSION, SCHEMA_VERSION, TestTargets};
+
+/// Request accepted by `iv_execute`, selected by its `"command"` field
+#[derive(Debug, serde::Deserialize)]
+#[serde(tag = "command", rename_all = "snake_case")]
+enum ExecuteRequest {
+    Version,
+    AskOpenai {
+        prompt: String,
+        api_key: String,
+        model: Option<String>,
+        base_url: Option<String>,
+        #[serde(default)]
+        options: AnalysisOptions,
+    },
+    ExtractTestTargets {
+        prompt: String,
+        api_key: String,
+        model: Option<String>,
+        base_url: Option<String>,
+        #[serde(default)]
+        options: AnalysisOptions,
+    },
+    ReadTestTargetsCode {
+        targets: TestTargets,
+        repo_url: String,
+        commit: String,
+        #[serde(default)]
+        options: AnalysisOptions,
+    },
+    GetGitChangedFiles {
+        repo_url: String,
+        commit_hash_1: String,
+        commit_hash_2: String,
+        #[serde(default)]
+        options: AnalysisOptions,
+    },
+    VerifyIntent {
+        test_repo_url: String,
+        test_commit: String,
+        solution_repo_url: String,
+        solution_commit1: String,
+        solution_commit2: String,
+        user_intent: String,
+        api_key: String,
+        model: Option<String>,
+        base_url: Option<String>,
+        #[serde(default)]
+        options: AnalysisOptions,
+    },
+    RenderReport {
+        result: Box<IntentVerificationResult>,
+        format: ReportFormat,
+        /// Needed for the side-by-side diffs of the HTML report
+        #[serde(default)]
+        file_changes: Vec<FileChange>,
+    },
+}
+
+#[derive(Debug, serde::Deserialize)]
+#[serde(rename_all = "snake_case")]
+enum ReportFormat {
+    Markdown,
+    Html,
+    Junit,
+    Sarif,
+    GithubAnnotations,
+}
+
+/// Envelope returned by `iv_execute`
+#[derive(Debug, serde::Serialize)]
+struct ExecuteResponse {
+    ok: bool,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    result: Option<serde_json::Value>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    error: Option<ExecuteError>,
+}
+
+#[derive(Debug, serde::Serialize)]
+struct ExecuteError {
+    code: IvErrorCode,
+    message: String,
+}
+
+/// FFI: Run any operation described by a JSON request and return a JSON response
+///
+/// The request's `"command"` selects the operation (`version`, `ask_openai`,
+/// `extract_test_targets`, `read_test_targets_code`, `get_git_changed_files`,
+/// `verify_intent`, `render_report`); the other fields are its arguments, named as in the
+/// Rust API, plus an optional `options` object (`AnalysisOptions`).
+///
+/// Always returns a response (free with `free_str`): `{"ok": true, "result": ...}` or
+/// `{"ok": false, "error": {"code": "...", "message": "..."}}`. New commands and fields can
+/// be added without changing the C ABI.
+///
+/// # Safety
+/// `request_json` must be NULL or point to a NUL-terminated string that stays valid for the
+/// duration of the call
+#[unsafe(no_mangle)]
+pub unsafe extern "C" fn iv_execute(request_json: *const c_char) -> *mut c_char {
+    let outcome = unsafe { arg_str(request_json, "request_json") }
+        .and_then(|json| {
+            serde_json::from_str::<ExecuteRequest>(json)
+                .map_err(|e| FfiError::new(IvErrorCode::InvalidJson, e.to_string()))
+        })
+        .and_then(execute);
+
+    // The error is also kept for `iv_last_error_code`, like every other FFI call
+    let response = match outcome {
+        Ok(result) => {
+            record(Ok(()));
+            ExecuteResponse {
+                ok: true,
+                result: Some(result),
+                error: None,
+            }
+        }
+        Err(error) => {
+            let response = ExecuteResponse {
+                ok: false,
+                result: None,
+                error: Some(ExecuteError {
+                    code: error.code,
+                    message: error.message.clone(),
+                }),
+            };
+            record::<()>(Err(error));
+            response
+        }
+    };
+
+    match serde_json::to_string(&response) {
+        Ok(json) => to_c_string(&json),
+        Err(e) => {
+            record::<()>(Err(FfiError::new(IvErrorCode::Unknown, e.to_string())));
+            std::ptr::null_mut()
+        }
+    }
+}
+
+fn execute(request: ExecuteRequest) -> Result<serde_json::Value, FfiError> {
+    match request {
+        ExecuteRequest::Version => Ok(serde_json::json!({
+            "tool_version": env!("CARGO_PKG_VERSION"),
+            "schema_version": SCHEMA_VERSION,
+            "prompt_version": PROMPT_VERSION,
+        })),
+        ExecuteRequest::AskOpenai {
+            prompt,
+            api_key,
+            model,
+            base_url,
+            options,
+        } => {
+            let reply = block_on(|| async {
+                ask_openai_with_options(
+                    &prompt,
+                    &api_key,
+                    model.as_deref(),
+                    base_url.as_deref(),
+                    &options,
+                )
+                .await
+                .map_err(|e| FfiError::from_error(e.as_ref()))
+            })??;
+            to_value(reply)
+        }
+        ExecuteRequest::ExtractTestTargets {
+            prompt,
+            api_key,
+            model,
+            base_url,
+            options,
+        } => {
+            let targets = block_on(|| async {
+                extract_test_targets_with_options(
+                    &prompt,
+                    &api_key,
+                    model.as_deref(),
+                    base_url.as_deref(),
+                    &options,
+                )
+                .await
+                .map_err(|e| FfiError::from_error(e.as_ref()))
+            })??;
+            to_value(targets)
+        }
+        ExecuteRequest::ReadTestTargetsCode {
+            targets,
+            repo_url,
+            commit,
+            options,
+        } => {
+            let targets_with_code =
+                read_test_targets_code_with_options(&targets, &repo_url, &commit, &options)
+                    .map_err(|e| FfiError::from_error(e.as_ref()))?;
+            to_value(targets_with_code)
+        }
+        ExecuteRequest::GetGitChangedFiles {
+            repo_url,
+            commit_hash_1,
+            commit_hash_2,
+            options,
+        } => {
+            let file_changes = get_git_changed_files_with_options(
+                &repo_url,
+                &commit_hash_1,
+                &commit_hash_2,
+                &options,
+            )
+            .map_err(|e| FfiError::from_error(e.as_ref()))?;
+            to_value(file_changes)
+        }
+        ExecuteRequest::VerifyIntent {
+            test_repo_url,
+            test_commit,
+            solution_repo_url,
+            solution_commit1,
+            solution_commit2,
+            user_intent,
+            api_key,
+            model,
+            base_url,
+            options,
+        } => {
+            let result = block_on(|| async {
+                verify_intent_with_options(
+                    &test_repo_url,
+                    &test_commit,
+                    &solution_repo_url,
+                    &solution_commit1,
+                    &solution_commit2,
+                    &user_intent,
+                    &api_key,
+                    model.as_deref(),
+                    base_url.as_deref(),
+                    &options,
+                )
+                .await
+                .map_err(|e| FfiError::from_error(e.as_ref()))
+            })??;
+            to_value(result)
+        }
+        ExecuteRequest::RenderReport {
+            result,
+            format,
+            file_changes,
+        } => match format {
+            ReportFormat::Markdown => to_value(render_markdown(&result)),
+            ReportFormat::Html => to_value(render_html(&result, &file_changes)),
+            ReportFormat::Junit => to_value(render_junit(&result)),
+            ReportFormat::Sarif => Ok(render_sarif(&result)),
+            ReportFormat::GithubAnnotations => to_value(render_github_annotations(&result)),
+        },
+    }
+}
+
+fn to_value(value: impl serde::Serialize) -> Result<serde_json::Value, FfiError> {
+    serde_json::to_value(value).map_err(|e| FfiError::new(IvErrorCode::Unknown, e.to_string()))
+}
@@ -0,0 +1,158 @@
+use std::error::Error;
+use std::path::Path;
+
+use crate::evidence::EvidenceBundle;
+use crate::redaction::{RedactionConfig, Redactor};
+use crate::types::PromptMessage;
+
+/// A recorded verification with the verdict a human reviewer approved
+#[derive(Debug, Clone, PartialEq, serde::Serialize, serde::Deserialize)]
+pub struct ReviewedRun {
+    /// Evidence bundle of the run, as written by `--evidence`; relative paths are resolved
+    /// against the manifest's directory by [`FineTuneManifest::load`]
+    pub bundle: String,
+    /// Whether the changes really fulfill the intent
+    pub approved: bool,
+}
+
+/// Reviewed runs to export, read from JSON or TOML (`[[runs]]` tables)
+#[derive(Debug, Clone, Default, PartialEq, serde::Serialize, serde::Deserialize)]
+pub struct FineTuneManifest {
+    /// What to strip from the examples; bundles hold the prompts as they were built
+    #[serde(default)]
+    pub redaction: Option<RedactionConfig>,
+    pub runs: Vec<ReviewedRun>,
+}
+
+impl FineTuneManifest {
+    pub fn from_json(json: &str) -> Result<Self, Box<dyn Error>> {
+        Ok(serde_json::from_str(json)?)
+    }
+
+    pub fn from_toml(text: &str) -> Result<Self, Box<dyn Error>> {
+        Ok(toml::from_str(text)?)
+    }
+
+    /// Load a manifest, treating `.toml` files as TOML and anything else as JSON
+    pub fn load(path: impl AsRef<Path>) -> Result<Self, Box<dyn Error>> {
+        let path = path.as_ref();
+        let text = std::fs::read_to_string(path)?;
+        let mut manifest = match path.extension().and_then(|ext| ext.to_str()) {
+            Some("toml") => Self::from_toml(&text)?,
+            _ => Self::from_json(&text)?,
+        };
+        if let Some(dir) = path.parent() {
+            for run in &mut manifest.runs {
+                run.bundle = dir.join(&run.bundle).to_string_lossy().into_owned();
+            }
+        }
+        Ok(manifest)
+    }
+}
+
+/// One chat example, a line of the exported JSONL
+///
+/// The format of the OpenAI fine-tuning API: the prompt's messages followed by the reply as
+/// an `assistant` message.
+#[derive(Debug, Clone, PartialEq, serde::Serialize, serde::Deserialize)]
+pub struct FineTuneExample {
+    pub messages: Vec<PromptMessage>,
+}
+
+/// A run left out of the export, and why
+#[derive(Debug, Clone, PartialEq, serde::Serialize, serde::Deserialize)]
+pub struct SkippedRun {
+    pub bundle: String,
+    pub reason: String,
+}
+
+/// Examples exported from a [`FineTuneManifest`]
+#[derive(Debug, Clone, Default, PartialEq, serde::Serialize, serde::Deserialize)]
+pub struct FineTuneExport {
+    pub examples: Vec<FineTuneExample>,
+    /// Number of runs the examples come from
+    pub runs_used: usize,
+    pub skipped: Vec<SkippedRun>,
+}
+
+impl FineTuneExport {
+    /// The examples as JSONL, one per line
+    pub fn to_jsonl(&self) -> Result<String, serde_json::Error> {
+        let mut jsonl = String::new();
+        for example in &self.examples {
+            jsonl.push_str(&serde_json::to_string(example)?);
+            jsonl.push('\n');
+        }
+        Ok(jsonl)
+    }
+}
+
+/// Chat examples from the model exchanges of a recorded run
+///
+/// Only runs whose verdict the reviewer approved teach the model anything right, so the
+/// run is refused when the verdict differs from `approved`, when it was partial, or when
+/// the bundle no longer matches its hashes.
+pub fn fine_tuning_examples(
+    bundle: &EvidenceBundle,
+    approved: bool,
+) -> Result<Vec<FineTuneExample>, String> {
+    if !bundle.verify_hashes() {
+        return Err("the bundle doesn't match its hashes".to_string());
+    }
+    if bundle.result.is_partial {
+        return Err("the verification was partial".to_string());
+    }
+    if bundle.result.is_intent_fulfilled != approved {
+        return Err(format!(
+            "the reviewer overrode the verdict ({} instead of {})",
+            approved, bundle.result.is_intent_fulfilled
+        ));
+    }
+    if bundle.exchanges.is_empty() {
+        return Err("no model exchanges were recorded".to_string());
+    }
+
+    Ok(bundle
+        .exchanges
+        .iter()
+        .map(|exchange| {
+            let mut messages = exchange.prompt.messages.clone();
+            messages.push(PromptMessage {
+                role: "assistant".to_string(),
+                content: exchange.response.clone(),
+            });
+            FineTuneExample { messages }
+        })
+        .collect())
+}
+
+/// Export the examples of every run in `manifest` whose bundle can be used
+///
+/// Runs that can't be read or are refused by [`fine_tuning_examples`] are listed in
+/// `skipped` instead of failing the export.
+pub fn export_fine_tuning(manifest: &FineTuneManifest) -> Result<FineTuneExport, Box<dyn Error>> {
+    let redactor = manifest.redaction.as_ref().map(Redactor::new).transpose()?;
+    let mut export = FineTuneExport::default();
+
+    for run in &manifest.runs {
+        let examples = EvidenceBundle::load(&run.bundle)
+            .map_err(|e| format!("couldn't read the bundle: {}", e))
+            .and_then(|bundle| fine_tuning_examples(&bundle, run.approved));
+        match examples {
+            Ok(mut examples) => {
+                if let Some(redactor) = &redactor {
+                    for message in examples.iter_mut().flat_map(|e| &mut e.messages) {
+                        message.content = redactor.redact(&message.content).0;
+                    }
+                }
+                export.examples.extend(examples);
+                export.runs_used += 1;
+            }
+            Err(reason) => export.skipped.push(SkippedRun {
+                bundle: run.bundle.clone(),
+                reason,
+            }),
+        }
+    }
+    Ok(export)
+}
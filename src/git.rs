@@ -1,9 +1,31 @@
-use git2::{Delta, Repository};
+#[cfg(feature = "git")]
+use git2::{Delta, FetchOptions, ProxyOptions, RemoteCallbacks, Repository};
 use regex::Regex;
-use std::path::Path;
+use similar::TextDiff;
+#[cfg(feature = "git")]
+use std::path::{Path, PathBuf};
 
+#[cfg(feature = "git")]
+use crate::binary::binary_placeholder;
+use crate::binary::is_unreadable;
+#[cfg(feature = "git")]
+use crate::clone_retry::retry_transient;
+#[cfg(feature = "git")]
 use crate::code_parser::{extract_function_from_content_with_name, is_source_file_by_name};
+#[cfg(feature = "git")]
+use crate::options::AnalysisOptions;
+#[cfg(feature = "git")]
+use crate::privacy::check_repository;
+#[cfg(feature = "git")]
+use crate::snapshot::RepoSnapshot;
+#[cfg(feature = "git")]
+use crate::test_mapping::map_tests_to_sources;
+#[cfg(feature = "git")]
 use crate::types::{FileContent, FunctionContent, TestTargets, TestTargetsWithCode};
+#[cfg(feature = "git")]
+use crate::utils::{is_test_path, locate_snippet};
+#[cfg(feature = "git")]
+use crate::validation::{ValidationError, commit_not_found};
 
 #[derive(Debug, Clone, PartialEq, serde::Serialize, serde::Deserialize)]
 pub enum ChangeType {
@@ -17,115 +39,759 @@ pub struct FileChange {
     pub path: String,
     pub status: ChangeType,
     pub content: Option<String>,
+    /// Content before the change (for modified and deleted files)
+    pub old_content: Option<String>,
 }
 
 /// Get list of files that were added or changed between two commits
 /// This function clones the repository from the given URL and compares the commits
+#[cfg(feature = "git")]
 pub fn get_git_changed_files(
     repo_url: &str,
     commit_hash_1: &str,
     commit_hash_2: &str,
 ) -> Result<Vec<FileChange>, Box<dyn std::error::Error>> {
-    // Create a temporary directory for cloning
-    let temp_dir = format!(
-        "/tmp/git_changed_files_{}_{}",
+    get_git_changed_files_with_options(
+        repo_url,
+        commit_hash_1,
+        commit_hash_2,
+        &AnalysisOptions::default(),
+    )
+}
+
+/// Same as [`get_git_changed_files`], run on the blocking thread pool so a service embedding
+/// the crate doesn't stall its async runtime while the repository is cloned
+#[cfg(feature = "git")]
+pub async fn get_git_changed_files_async(
+    repo_url: &str,
+    commit_hash_1: &str,
+    commit_hash_2: &str,
+) -> Result<Vec<FileChange>, Box<dyn std::error::Error>> {
+    get_git_changed_files_async_with_options(
+        repo_url,
+        commit_hash_1,
+        commit_hash_2,
+        &AnalysisOptions::default(),
+    )
+    .await
+}
+
+/// Same as [`get_git_changed_files_async`], cloning into `options.cache_dir` through
+/// `options.proxy`
+#[cfg(feature = "git")]
+pub(crate) async fn get_git_changed_files_async_with_options(
+    repo_url: &str,
+    commit_hash_1: &str,
+    commit_hash_2: &str,
+    options: &AnalysisOptions,
+) -> Result<Vec<FileChange>, Box<dyn std::error::Error>> {
+    let (repo_url, commit_hash_1, commit_hash_2) = (
+        repo_url.to_string(),
+        commit_hash_1.to_string(),
+        commit_hash_2.to_string(),
+    );
+    spawn_git(options, move |options| {
+        get_git_changed_files_with_options(&repo_url, &commit_hash_1, &commit_hash_2, options)
+    })
+    .await
+}
+
+/// Run blocking git work (clones, checkouts, tree reads) on tokio's blocking thread pool
+///
+/// `work` receives only the options git uses, `cache_dir`, `proxy`, `observer` and
+/// `local_only`. Git and validation errors are passed back as they are, others as their
+/// message.
+#[cfg(feature = "git")]
+pub(crate) async fn spawn_git<T: Send + 'static>(
+    options: &AnalysisOptions,
+    work: impl FnOnce(&AnalysisOptions) -> Result<T, Box<dyn std::error::Error>> + Send + 'static,
+) -> Result<T, Box<dyn std::error::Error>> {
+    let git_options = git_options(options);
+    // Spans opened by `work` belong to the caller's span and subscriber, not the blocking thread's
+    let span = tracing::Span::current();
+    let dispatch = tracing::dispatcher::get_default(Clone::clone);
+    let outcome = tokio::task::spawn_blocking(move || {
+        let _dispatch = tracing::dispatcher::set_default(&dispatch);
+        let _entered = span.enter();
+        work(&git_options).map_err(|e| match e.downcast::<git2::Error>() {
+            Ok(git_error) => git_error as Box<dyn std::error::Error + Send + Sync>,
+            Err(other) => match other.downcast::<ValidationError>() {
+                Ok(invalid) => invalid as Box<dyn std::error::Error + Send + Sync>,
+                Err(other) => other.to_string().into(),
+            },
+        })
+    })
+    .await?;
+    outcome.map_err(|e| e as Box<dyn std::error::Error>)
+}
+
+/// Same as [`get_git_changed_files`], cloning into `options.cache_dir` through `options.proxy`
+///
+/// Loads every changed file's contents at once; the verification functions need them all for
+/// the checks spanning the whole diff (scope, risk, secrets), so only
+/// `analyze_repository_changes_stream` keeps to [`get_git_changed_files_lazy`].
+#[cfg(feature = "git")]
+pub(crate) fn get_git_changed_files_with_options(
+    repo_url: &str,
+    commit_hash_1: &str,
+    commit_hash_2: &str,
+    options: &AnalysisOptions,
+) -> Result<Vec<FileChange>, Box<dyn std::error::Error>> {
+    let (store, changes) =
+        get_git_changed_files_lazy_with_options(repo_url, commit_hash_1, commit_hash_2, options)?;
+    let repo = Repository::open(&store.dir)?;
+    Ok(changes
+        .iter()
+        .map(|change| load_file_change(&repo, change))
+        .collect())
+}
+
+/// Username and password for fetching private repositories over HTTPS
+///
+/// Handed to git when the server asks for them, so they never end up in clone URLs and the
+/// logs, errors and results that mention those. Not serialized, and redacted in `Debug`.
+#[derive(Clone)]
+pub struct GitCredentials {
+    pub username: String,
+    pub password: String,
+}
+
+impl GitCredentials {
+    /// Credentials for an access token, like `GITHUB_TOKEN`
+    pub fn token(token: &str) -> Self {
+        GitCredentials {
+            username: "x-access-token".to_string(),
+            password: token.to_string(),
+        }
+    }
+}
+
+impl std::fmt::Debug for GitCredentials {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("GitCredentials")
+            .field("username", &self.username)
+            .field("password", &"[redacted]")
+            .finish()
+    }
+}
+
+/// A changed file whose contents are read on demand from a [`BlobStore`]
+#[derive(Debug, Clone, serde::Serialize, serde::Deserialize)]
+pub struct LazyFileChange {
+    pub path: String,
+    pub status: ChangeType,
+    /// Blob after the change (for added and modified files)
+    pub new_oid: Option<String>,
+    /// Blob before the change (for modified and deleted files)
+    pub old_oid: Option<String>,
+}
+
+/// Clone kept on disk so file contents can be read after the diff; removed when dropped
+#[cfg(feature = "git")]
+#[derive(Debug)]
+pub struct BlobStore {
+    dir: PathBuf,
+}
+
+#[cfg(feature = "git")]
+impl BlobStore {
+    /// Read a changed file's contents, as [`get_git_changed_files`] would have returned them
+    pub fn load(&self, change: &LazyFileChange) -> Result<FileChange, git2::Error> {
+        let repo = Repository::open(&self.dir)?;
+        Ok(load_file_change(&repo, change))
+    }
+}
+
+#[cfg(feature = "git")]
+impl Drop for BlobStore {
+    fn drop(&mut self) {
+        std::fs::remove_dir_all(&self.dir).ok();
+    }
+}
+
+/// Like [`get_git_changed_files`], but only records each file's blob ids, so large diffs
+/// don't hold every file's contents in memory at once
+///
+/// Contents are read with [`BlobStore::load`] while the returned store is alive. The
+/// `verify_intent*` functions still load every file, so memory stays bounded only for callers
+/// reading one file at a time, like `analyze_repository_changes_stream`.
+#[cfg(feature = "git")]
+pub fn get_git_changed_files_lazy(
+    repo_url: &str,
+    commit_hash_1: &str,
+    commit_hash_2: &str,
+) -> Result<(BlobStore, Vec<LazyFileChange>), Box<dyn std::error::Error>> {
+    get_git_changed_files_lazy_with_options(
+        repo_url,
+        commit_hash_1,
+        commit_hash_2,
+        &AnalysisOptions::default(),
+    )
+}
+
+/// Same as [`get_git_changed_files_lazy`], cloning into `options.cache_dir` through
+/// `options.proxy`
+#[cfg(feature = "git")]
+#[tracing::instrument(
+    name = "diff",
+    skip_all,
+    fields(repo = repo_url, from = commit_hash_1, to = commit_hash_2, files = tracing::field::Empty)
+)]
+pub(crate) fn get_git_changed_files_lazy_with_options(
+    repo_url: &str,
+    commit_hash_1: &str,
+    commit_hash_2: &str,
+    options: &AnalysisOptions,
+) -> Result<(BlobStore, Vec<LazyFileChange>), Box<dyn std::error::Error>> {
+    let (repo, temp_dir) = clone_repository(repo_url, "git_changed_files", options)?;
+    // Removes the clone on every return from here on
+    let store = BlobStore { dir: temp_dir };
+
+    let revparse = |name: &str, commit: &str| {
+        resolve_revision(&repo, commit, options)
+            .map_err(|e| commit_not_found(e, name, commit, repo_url))
+    };
+    let commit1 = repo.find_commit(revparse("commit1", commit_hash_1)?.id())?;
+    let commit2 = repo.find_commit(revparse("commit2", commit_hash_2)?.id())?;
+
+    let tree1 = commit1.tree()?;
+    let tree2 = commit2.tree()?;
+
+    let changes = tree_changes(&repo, &tree1, &tree2)?;
+
+    tracing::Span::current().record("files", changes.len());
+    Ok((store, changes))
+}
+
+/// The options cloning and fetching use: cache directory, proxy, retries, cancellation,
+/// observer, local-only policy and credentials
+#[cfg(feature = "git")]
+pub(crate) fn git_options(options: &AnalysisOptions) -> AnalysisOptions {
+    AnalysisOptions {
+        cache_dir: options.cache_dir.clone(),
+        proxy: options.proxy.clone(),
+        clone_retry: options.clone_retry.clone(),
+        cancellation: options.cancellation.clone(),
+        observer: options.observer.clone(),
+        local_only: options.local_only.clone(),
+        git_credentials: options.git_credentials.clone(),
+        ..Default::default()
+    }
+}
+
+/// The added, modified and deleted files between two trees, with their blob ids
+#[cfg(feature = "git")]
+pub(crate) fn tree_changes(
+    repo: &Repository,
+    tree1: &git2::Tree,
+    tree2: &git2::Tree,
+) -> Result<Vec<LazyFileChange>, git2::Error> {
+    let diff = repo.diff_tree_to_tree(Some(tree1), Some(tree2), None)?;
+
+    let mut changes = Vec::new();
+    for delta in diff.deltas() {
+        let (file, status) = match delta.status() {
+            Delta::Added => (delta.new_file(), ChangeType::Added),
+            Delta::Modified => (delta.new_file(), ChangeType::Modified),
+            Delta::Deleted => (delta.old_file(), ChangeType::Deleted),
+            _ => continue, // Skip other types
+        };
+        let Some(path) = file.path() else {
+            continue; // Skip if no path
+        };
+        // Newer contents for added and modified files, older ones for modified and deleted files
+        let new_oid = (status != ChangeType::Deleted).then(|| delta.new_file().id().to_string());
+        let old_oid = (status != ChangeType::Added).then(|| delta.old_file().id().to_string());
+        changes.push(LazyFileChange {
+            path: path.to_string_lossy().to_string(),
+            status,
+            new_oid,
+            old_oid,
+        });
+    }
+    Ok(changes)
+}
+
+/// A lazily diffed file with its contents read from `repo`
+#[cfg(feature = "git")]
+pub(crate) fn load_file_change(repo: &Repository, change: &LazyFileChange) -> FileChange {
+    let read = |oid: &Option<String>| {
+        let oid = git2::Oid::from_str(oid.as_deref()?).ok()?;
+        Some(blob_text(&change.path, &repo.find_blob(oid).ok()?))
+    };
+    FileChange {
+        path: change.path.clone(),
+        status: change.status.clone(),
+        content: read(&change.new_oid),
+        old_content: read(&change.old_oid),
+    }
+}
+
+/// Clone a repository into a fresh directory under `options.cache_dir` (or the temp directory)
+///
+/// Returns the repository and its directory, which the caller removes when done.
+#[cfg(feature = "git")]
+#[tracing::instrument(name = "clone", skip_all, fields(repo = repo_url))]
+pub(crate) fn clone_repository(
+    repo_url: &str,
+    prefix: &str,
+    options: &AnalysisOptions,
+) -> Result<(Repository, PathBuf), git2::Error> {
+    let base_dir = options
+        .cache_dir
+        .as_ref()
+        .map(PathBuf::from)
+        .unwrap_or_else(std::env::temp_dir);
+    let temp_dir = base_dir.join(format!(
+        "{}_{}_{}",
+        prefix,
         std::process::id(),
         std::time::SystemTime::now()
             .duration_since(std::time::UNIX_EPOCH)
             .unwrap()
             .as_nanos()
-    );
+    ));
 
     // Clean up any existing temp directory
-    if Path::new(&temp_dir).exists() {
+    if temp_dir.exists() {
         std::fs::remove_dir_all(&temp_dir).ok();
     }
 
-    // Clone the repository
-    let repo = Repository::clone(repo_url, &temp_dir)?;
+    check_repository(options, repo_url).map_err(|e| git2::Error::from_str(&e.to_string()))?;
+    options.observe(|observer| observer.on_clone_start(repo_url));
+    let repo = Repository::init(&temp_dir)?;
+    if let Err(e) = fetch_origin(&repo, repo_url, options) {
+        std::fs::remove_dir_all(&temp_dir).ok();
+        return Err(e);
+    }
+    Ok((repo, temp_dir))
+}
 
-    let commit1 = repo.find_commit(repo.revparse_single(commit_hash_1)?.id())?;
-    let commit2 = repo.find_commit(repo.revparse_single(commit_hash_2)?.id())?;
+/// Fetch the branches and tags of `repo_url` into a new `repo` as its `origin` and check out
+/// the default branch, like `git clone`
+///
+/// `RepoBuilder::clone` deletes the repository when the transfer fails, so this fetches into
+/// it instead, retrying network failures with `options.clone_retry`: what an earlier attempt
+/// stored is kept and not downloaded again.
+#[cfg(feature = "git")]
+fn fetch_origin(
+    repo: &Repository,
+    repo_url: &str,
+    options: &AnalysisOptions,
+) -> Result<(), git2::Error> {
+    let mut remote = repo.remote("origin", repo_url)?;
+    let default_branch = retry_transient(options, repo_url, || {
+        let mut fetch_options = fetch_options(options);
+        fetch_options.download_tags(git2::AutotagOption::All);
+        remote.fetch::<&str>(&[], Some(&mut fetch_options), None)?;
+        Ok(remote.default_branch().ok().and_then(|name| {
+            name.as_str()?
+                .strip_prefix("refs/heads/")
+                .map(str::to_string)
+        }))
+    })?;
 
-    let tree1 = commit1.tree()?;
-    let tree2 = commit2.tree()?;
+    // Servers that don't advertise their HEAD get the usual default, or any branch
+    let remote_branch = |name: &str| repo.find_reference(&format!("refs/remotes/origin/{}", name));
+    let branch = default_branch
+        .into_iter()
+        .chain(["main".to_string(), "master".to_string()])
+        .find(|name| remote_branch(name).is_ok())
+        .or_else(|| {
+            repo.references_glob("refs/remotes/origin/*")
+                .ok()?
+                .flatten()
+                .find_map(|reference| {
+                    Some(
+                        reference
+                            .name()?
+                            .strip_prefix("refs/remotes/origin/")?
+                            .to_string(),
+                    )
+                })
+        });
+    // An empty repository has nothing to check out
+    let Some(branch) = branch else {
+        return Ok(());
+    };
+
+    let commit = remote_branch(&branch)?.peel_to_commit()?;
+    repo.branch(&branch, &commit, true)?
+        .set_upstream(Some(&format!("origin/{}", branch)))?;
+    repo.reference_symbolic(
+        "refs/remotes/origin/HEAD",
+        &format!("refs/remotes/origin/{}", branch),
+        true,
+        "clone",
+    )?;
+    repo.set_head(&format!("refs/heads/{}", branch))?;
+    repo.checkout_head(Some(git2::build::CheckoutBuilder::new().force()))
+}
+
+/// Fetch options going through `options.proxy` and answering with `options.git_credentials`
+#[cfg(feature = "git")]
+fn fetch_options(options: &AnalysisOptions) -> FetchOptions<'static> {
+    let mut fetch_options = FetchOptions::new();
+    if let Some(proxy) = &options.proxy {
+        let mut proxy_options = ProxyOptions::new();
+        proxy_options.url(proxy);
+        fetch_options.proxy_options(proxy_options);
+    }
+    if let Some(credentials) = options.git_credentials.clone() {
+        let mut callbacks = RemoteCallbacks::new();
+        let mut asked = false;
+        callbacks.credentials(move |_url, _username, allowed| {
+            // git asks again after a rejection; answering the same would loop forever
+            if asked || !allowed.contains(git2::CredentialType::USER_PASS_PLAINTEXT) {
+                return Err(git2::Error::from_str("credentials were rejected"));
+            }
+            asked = true;
+            git2::Cred::userpass_plaintext(&credentials.username, &credentials.password)
+        });
+        fetch_options.remote_callbacks(callbacks);
+    }
+    fetch_options
+}
+
+/// Look up `rev` in a clone of `origin`, fetching every ref of `origin` when it's missing
+///
+/// Clones only get the branches and tags, so commits that are only reachable from pull request
+/// refs or other custom refs are fetched on demand. Branch names resolve to `origin`'s
+/// branches, and refs like `refs/pull/12/head` to their fetched copy.
+#[cfg(feature = "git")]
+pub(crate) fn resolve_revision<'r>(
+    repo: &'r Repository,
+    rev: &str,
+    options: &AnalysisOptions,
+) -> Result<git2::Object<'r>, git2::Error> {
+    let lookup = || {
+        let unqualified = rev.strip_prefix("refs/").unwrap_or(rev);
+        [rev.to_string(), format!("origin/{}", unqualified)]
+            .iter()
+            .find_map(|candidate| repo.revparse_single(candidate).ok())
+    };
+    let error = match repo.revparse_single(rev) {
+        Ok(object) => return Ok(object),
+        Err(e) if e.code() == git2::ErrorCode::NotFound => e,
+        Err(e) => return Err(e),
+    };
+    if let Some(object) = lookup() {
+        return Ok(object);
+    }
+
+    let mut remote = repo.find_remote("origin")?;
+    let url = remote.url().unwrap_or_default().to_string();
+    retry_transient(options, &url, || {
+        remote.fetch(
+            &["+refs/*:refs/remotes/origin/*"],
+            Some(&mut fetch_options(options)),
+            None,
+        )
+    })?;
+    // Servers that allow it can also send a commit no ref points to
+    if rev.len() == 40 && rev.chars().all(|c| c.is_ascii_hexdigit()) {
+        remote
+            .fetch(&[rev], Some(&mut fetch_options(options)), None)
+            .ok();
+    }
+    lookup().ok_or(error)
+}
+
+/// Full ids of `commit` and its first parent in `repo_url`
+///
+/// Root commits have no parent to diff against and fail.
+#[cfg(feature = "git")]
+pub(crate) fn commit_and_parent(
+    repo_url: &str,
+    commit: &str,
+    options: &AnalysisOptions,
+) -> Result<(String, String), Box<dyn std::error::Error>> {
+    let (repo, temp_dir) = clone_repository(repo_url, "commit_parent", options)?;
+    let ids = resolve_revision(&repo, commit, options)
+        .map_err(|e| commit_not_found(e, "commit", commit, repo_url))
+        .and_then(|object| Ok(object.peel_to_commit()?))
+        .and_then(|resolved| match resolved.parent_ids().next() {
+            Some(parent) => Ok((resolved.id().to_string(), parent.to_string())),
+            None => Err(format!("Commit {} has no parent to diff against", commit).into()),
+        });
+    drop(repo);
+    std::fs::remove_dir_all(&temp_dir).ok();
+    ids
+}
+
+/// Check out the tests at `test_commit` with the solution's changes applied on top
+///
+/// Without `with_solution`, the solution's changes are reverted instead, except for test
+/// files, giving the counterfactual the tests should fail on. Returns the working directory,
+/// which the caller removes when done.
+#[cfg(feature = "git")]
+pub(crate) fn prepare_test_workspace(
+    test_repo_url: &str,
+    test_commit: &str,
+    solution_repo_url: &str,
+    solution_commit1: &str,
+    solution_commit2: &str,
+    with_solution: bool,
+    options: &AnalysisOptions,
+) -> Result<PathBuf, Box<dyn std::error::Error>> {
+    let (repo, workdir) =
+        checkout_workspace(test_repo_url, test_commit, "test_workspace", options)?;
+    let prepared = (|| -> Result<(), Box<dyn std::error::Error>> {
+        // Copy the solution's changes as raw blobs, so binary files survive intact
+        let solution_clone = if solution_repo_url == test_repo_url {
+            None
+        } else {
+            Some(clone_repository(
+                solution_repo_url,
+                "test_workspace_solution",
+                options,
+            )?)
+        };
+        let solution = solution_clone.as_ref().map_or(&repo, |(repo, _)| repo);
+        let copied = if with_solution {
+            copy_changes(
+                solution,
+                solution_commit1,
+                solution_commit2,
+                &workdir,
+                false,
+                options,
+            )
+        } else {
+            copy_changes(
+                solution,
+                solution_commit2,
+                solution_commit1,
+                &workdir,
+                true,
+                options,
+            )
+        };
+        if let Some((_, dir)) = &solution_clone {
+            std::fs::remove_dir_all(dir).ok();
+        }
+        copied?;
+        Ok(())
+    })();
+
+    if let Err(e) = prepared {
+        std::fs::remove_dir_all(&workdir).ok();
+        return Err(e);
+    }
+    Ok(workdir)
+}
+
+/// Text files of `repo_url` at `commit`, cloned once so they can be read repeatedly
+#[cfg(feature = "git")]
+pub(crate) fn snapshot_repository(
+    repo_url: &str,
+    commit: &str,
+    options: &AnalysisOptions,
+) -> Result<RepoSnapshot, git2::Error> {
+    let (repo, dir) = clone_repository(repo_url, "snapshot", options)?;
+    let snapshot = resolve_revision(&repo, commit, options)
+        .and_then(|object| RepoSnapshot::from_commit(&dir, &object.id().to_string()));
+    std::fs::remove_dir_all(&dir).ok();
+    snapshot
+}
+
+/// Text of the first of `paths` that exists in `repo_url` at `commit`, or `None` when none does
+#[cfg(feature = "git")]
+pub(crate) fn read_repository_file(
+    repo_url: &str,
+    commit: &str,
+    paths: &[&str],
+    options: &AnalysisOptions,
+) -> Result<Option<String>, git2::Error> {
+    let (repo, dir) = clone_repository(repo_url, "read_file", options)?;
+    let content = resolve_revision(&repo, commit, options)
+        .and_then(|object| object.peel_to_tree())
+        .map(|tree| {
+            paths
+                .iter()
+                .find_map(|path| read_blob_text(&repo, &tree, path))
+        });
+    std::fs::remove_dir_all(&dir).ok();
+    content
+}
+
+/// Clone `repo_url` and check out `commit` (detached) in its working directory
+///
+/// Returns the repository and its directory, which the caller removes when done.
+#[cfg(feature = "git")]
+pub(crate) fn checkout_workspace(
+    repo_url: &str,
+    commit: &str,
+    prefix: &str,
+    options: &AnalysisOptions,
+) -> Result<(Repository, PathBuf), git2::Error> {
+    let (repo, workdir) = clone_repository(repo_url, prefix, options)?;
+    let checked_out = (|| {
+        let commit = resolve_revision(&repo, commit, options)?.peel_to_commit()?;
+        repo.checkout_tree(
+            commit.as_object(),
+            Some(git2::build::CheckoutBuilder::new().force()),
+        )?;
+        repo.set_head_detached(commit.id())
+    })();
+
+    if let Err(e) = checked_out {
+        std::fs::remove_dir_all(&workdir).ok();
+        return Err(e);
+    }
+    Ok((repo, workdir))
+}
 
+/// Write the files changed between two commits of `repo` into `workdir`, deleting removed ones
+#[cfg(feature = "git")]
+fn copy_changes(
+    repo: &Repository,
+    commit_hash_1: &str,
+    commit_hash_2: &str,
+    workdir: &Path,
+    skip_tests: bool,
+    options: &AnalysisOptions,
+) -> Result<(), Box<dyn std::error::Error>> {
+    let tree1 = resolve_revision(repo, commit_hash_1, options)?.peel_to_tree()?;
+    let tree2 = resolve_revision(repo, commit_hash_2, options)?.peel_to_tree()?;
     let diff = repo.diff_tree_to_tree(Some(&tree1), Some(&tree2), None)?;
 
-    let mut file_changes = Vec::new();
+    for delta in diff.deltas() {
+        let is_test = [delta.old_file().path(), delta.new_file().path()]
+            .into_iter()
+            .flatten()
+            .any(|path| is_test_path(&path.to_string_lossy()));
+        if skip_tests && is_test {
+            continue;
+        }
+        if delta.status() == Delta::Deleted {
+            if let Some(path) = delta.old_file().path() {
+                std::fs::remove_file(workdir.join(path)).ok();
+            }
+            continue;
+        }
+        if let Some(path) = delta.new_file().path() {
+            let blob = repo.find_blob(delta.new_file().id())?;
+            let path = workdir.join(path);
+            if let Some(parent) = path.parent() {
+                std::fs::create_dir_all(parent)?;
+            }
+            std::fs::write(&path, blob.content())?;
+        }
+    }
+    Ok(())
+}
 
-    diff.foreach(
-        &mut |delta, _| {
-            let (path, change_type) = match delta.status() {
-                Delta::Added => {
-                    if let Some(path) = delta.new_file().path() {
-                        (path.to_string_lossy().to_string(), ChangeType::Added)
-                    } else {
-                        return true; // Skip if no path
-                    }
-                }
-                Delta::Modified => {
-                    if let Some(path) = delta.new_file().path() {
-                        (path.to_string_lossy().to_string(), ChangeType::Modified)
-                    } else {
-                        return true; // Skip if no path
-                    }
-                }
-                Delta::Deleted => {
-                    if let Some(path) = delta.old_file().path() {
-                        (path.to_string_lossy().to_string(), ChangeType::Deleted)
-                    } else {
-                        return true; // Skip if no path
-                    }
-                }
-                _ => return true, // Skip other types
-            };
-
-            // Get file content for added and modified files
-            let content = match change_type {
-                ChangeType::Added | ChangeType::Modified => {
-                    // Get the file content from the second commit (newer version)
-                    match tree2.get_path(Path::new(&path)) {
-                        Ok(entry) => {
-                            if let Ok(blob) =
-                                entry.to_object(&repo).and_then(|obj| obj.peel_to_blob())
-                            {
-                                // Try to convert to UTF-8 string, skip binary files
-                                if blob.is_binary() {
-                                    Some("[Binary file]".to_string())
-                                } else {
-                                    std::str::from_utf8(blob.content())
-                                        .map(|s| s.to_string())
-                                        .unwrap_or_else(|_| "[Non-UTF8 content]".to_string())
-                                        .into()
-                                }
-                            } else {
-                                None
-                            }
-                        }
-                        Err(_) => None,
-                    }
-                }
-                ChangeType::Deleted => None, // No content for deleted files
-            };
+/// Bare mirror of `repo_url` under the cache directory, fetched once and then reused
+///
+/// Cloning from the returned local path is much cheaper than cloning the remote again, so
+/// callers running many verifications against the same repository can share one fetch.
+#[cfg(feature = "git")]
+#[tracing::instrument(name = "mirror", skip_all, fields(repo = repo_url))]
+pub(crate) fn mirror_repository(
+    repo_url: &str,
+    options: &AnalysisOptions,
+) -> Result<PathBuf, git2::Error> {
+    use sha2::{Digest, Sha256};
+
+    let base_dir = options
+        .cache_dir
+        .as_ref()
+        .map(PathBuf::from)
+        .unwrap_or_else(std::env::temp_dir);
+    let digest = Sha256::digest(repo_url.as_bytes());
+    let name: String = digest[..8].iter().map(|b| format!("{:02x}", b)).collect();
+    let mirror_dir = base_dir.join(format!("mirror_{}", name));
 
-            file_changes.push(FileChange {
-                path,
-                status: change_type,
-                content,
+    check_repository(options, repo_url).map_err(|e| git2::Error::from_str(&e.to_string()))?;
+    options.observe(|observer| observer.on_clone_start(repo_url));
+    let (repo, created) = match Repository::open_bare(&mirror_dir) {
+        Ok(repo) => (repo, false),
+        Err(_) => {
+            std::fs::remove_dir_all(&mirror_dir).ok();
+            (Repository::init_bare(&mirror_dir)?, true)
+        }
+    };
+    let fetched = repo.remote_anonymous(repo_url).and_then(|mut remote| {
+        retry_transient(options, repo_url, || {
+            remote.fetch(
+                &["+refs/heads/*:refs/heads/*", "+refs/tags/*:refs/tags/*"],
+                Some(&mut fetch_options(options)),
+                None,
+            )
+        })
+    });
+    if let Err(e) = fetched {
+        // Don't leave an empty mirror behind for a repository that was never fetched
+        if created {
+            std::fs::remove_dir_all(&mirror_dir).ok();
+        }
+        return Err(e);
+    }
+
+    // Clones of the mirror check out its HEAD, so point it at a branch that exists
+    if repo.head().is_err() {
+        let branch = ["refs/heads/main", "refs/heads/master"]
+            .into_iter()
+            .map(str::to_string)
+            .find(|name| repo.find_reference(name).is_ok())
+            .or_else(|| {
+                repo.references_glob("refs/heads/*")
+                    .ok()?
+                    .flatten()
+                    .find_map(|reference| reference.name().map(str::to_string))
             });
+        if let Some(branch) = branch {
+            repo.set_head(&branch)?;
+        }
+    }
+    Ok(mirror_dir)
+}
 
-            true
-        },
-        None,
-        None,
-        None,
-    )?;
+/// Read a file's text from a git tree, using placeholders for binary or non-UTF8 content
+#[cfg(feature = "git")]
+fn read_blob_text(repo: &Repository, tree: &git2::Tree, path: &str) -> Option<String> {
+    let entry = tree.get_path(Path::new(path)).ok()?;
+    let blob = entry
+        .to_object(repo)
+        .and_then(|obj| obj.peel_to_blob())
+        .ok()?;
+    Some(blob_text(path, &blob))
+}
 
-    // Clean up the temporary directory
-    std::fs::remove_dir_all(&temp_dir).ok();
+/// A blob's text, with placeholders for binary and non-UTF-8 content
+///
+/// The placeholder of a binary file carries its MIME type, size and digest, see
+/// [`binary_placeholder`].
+#[cfg(feature = "git")]
+pub(crate) fn blob_text(path: &str, blob: &git2::Blob) -> String {
+    // Try to convert to UTF-8 string, skip binary files
+    if blob.is_binary() {
+        binary_placeholder(path, blob.content())
+    } else {
+        std::str::from_utf8(blob.content())
+            .map(|s| s.to_string())
+            .unwrap_or_else(|_| "[Non-UTF8 content]".to_string())
+    }
+}
 
-    Ok(file_changes)
+/// Unified diff hunks of a modified file, each with `context_lines` unchanged lines around
+/// its changes
+///
+/// Empty for added and deleted files, binary files and files whose text didn't change.
+pub fn diff_hunks(file_change: &FileChange, context_lines: usize) -> Vec<String> {
+    let (Some(old), Some(new)) = (&file_change.old_content, &file_change.content) else {
+        return vec![];
+    };
+    if file_change.status != ChangeType::Modified || [old, new].iter().any(|c| is_unreadable(c)) {
+        return vec![];
+    }
+    TextDiff::from_lines(old, new)
+        .unified_diff()
+        .context_radius(context_lines)
+        .iter_hunks()
+        .map(|hunk| hunk.to_string())
+        .collect()
 }
 
 pub fn split_by_function(content: &str) -> Vec<String> {
@@ -161,29 +827,65 @@ pub fn split_by_function(content: &str) -> Vec<String> {
 ///
 /// # Returns
 /// * `TestTargetsWithCode` - The targets with their actual code content
+#[cfg(feature = "git")]
 pub fn read_test_targets_code(
     targets: &TestTargets,
     repo_url: &str,
     commit: &str,
 ) -> Result<TestTargetsWithCode, Box<dyn std::error::Error>> {
-    // Create a temporary directory for cloning if needed
-    let temp_dir = format!(
-        "/tmp/git_read_targets_{}_{}",
-        std::process::id(),
-        std::time::SystemTime::now()
-            .duration_since(std::time::UNIX_EPOCH)
-            .unwrap()
-            .as_nanos()
-    );
+    read_test_targets_code_with_options(targets, repo_url, commit, &AnalysisOptions::default())
+}
 
-    // Clean up any existing temp directory
-    if Path::new(&temp_dir).exists() {
-        std::fs::remove_dir_all(&temp_dir).ok();
-    }
+/// Same as [`read_test_targets_code`], run on the blocking thread pool so a service embedding
+/// the crate doesn't stall its async runtime while the repository is cloned
+#[cfg(feature = "git")]
+pub async fn read_test_targets_code_async(
+    targets: &TestTargets,
+    repo_url: &str,
+    commit: &str,
+) -> Result<TestTargetsWithCode, Box<dyn std::error::Error>> {
+    read_test_targets_code_async_with_options(
+        targets,
+        repo_url,
+        commit,
+        &AnalysisOptions::default(),
+    )
+    .await
+}
+
+/// Same as [`read_test_targets_code_async`], cloning into `options.cache_dir` through
+/// `options.proxy`
+#[cfg(feature = "git")]
+pub(crate) async fn read_test_targets_code_async_with_options(
+    targets: &TestTargets,
+    repo_url: &str,
+    commit: &str,
+    options: &AnalysisOptions,
+) -> Result<TestTargetsWithCode, Box<dyn std::error::Error>> {
+    let (targets, repo_url, commit) = (targets.clone(), repo_url.to_string(), commit.to_string());
+    spawn_git(options, move |options| {
+        read_test_targets_code_with_options(&targets, &repo_url, &commit, options)
+    })
+    .await
+}
 
-    // Clone the repository
-    let repo = Repository::clone(repo_url, &temp_dir)?;
-    let commit_obj = repo.find_commit(repo.revparse_single(commit)?.id())?;
+/// Same as [`read_test_targets_code`], cloning into `options.cache_dir` through `options.proxy`
+#[cfg(feature = "git")]
+#[tracing::instrument(name = "read_targets", skip_all, fields(repo = repo_url, commit))]
+pub(crate) fn read_test_targets_code_with_options(
+    targets: &TestTargets,
+    repo_url: &str,
+    commit: &str,
+    options: &AnalysisOptions,
+) -> Result<TestTargetsWithCode, Box<dyn std::error::Error>> {
+    let (repo, temp_dir) = clone_repository(repo_url, "git_read_targets", options)?;
+    let commit_obj = match resolve_revision(&repo, commit, options) {
+        Ok(object) => repo.find_commit(object.id())?,
+        Err(e) => {
+            std::fs::remove_dir_all(&temp_dir).ok();
+            return Err(commit_not_found(e, "test commit", commit, repo_url));
+        }
+    };
     let tree = commit_obj.tree()?;
 
     // Read file contents from the git tree
@@ -237,11 +939,13 @@ pub fn read_test_targets_code(
     // Extract function contents by searching through all source files in the tree
     let mut function_contents = Vec::new();
     for function_name in &targets.functions {
-        let (found_file, found_content) = find_function_in_tree(&repo, &tree, function_name)?;
+        let (found_file, found_line, found_content) =
+            find_function_in_tree(&repo, &tree, function_name)?;
 
         function_contents.push(FunctionContent {
             name: function_name.clone(),
             file_path: found_file.clone(),
+            line: found_line,
             content: found_content.clone(),
             error: if found_content.is_none() {
                 Some(format!(
@@ -254,32 +958,54 @@ pub fn read_test_targets_code(
         });
     }
 
-    // Clean up the temporary directory
-    std::fs::remove_dir_all(&temp_dir).ok();
-
-    Ok(TestTargetsWithCode {
+    let mut targets_with_code = TestTargetsWithCode {
         targets: targets.clone(),
         file_contents,
         function_contents,
-    })
+        tested_sources: vec![],
+    };
+    targets_with_code.tested_sources = map_tests_to_sources(&targets_with_code, |path| {
+        let blob = tree
+            .get_path(Path::new(path))
+            .ok()?
+            .to_object(&repo)
+            .ok()?
+            .peel_to_blob()
+            .ok()?;
+        (!blob.is_binary())
+            .then(|| String::from_utf8(blob.content().to_vec()).ok())
+            .flatten()
+    });
+
+    // Clean up the temporary directory
+    std::fs::remove_dir_all(&temp_dir).ok();
+
+    Ok(targets_with_code)
 }
 
-/// Search for a function definition in a git tree recursively
+/// File, start line and code of a function found in a git tree
+#[cfg(feature = "git")]
+type FoundFunction = (Option<String>, Option<usize>, Option<String>);
+
+/// Search for a function definition in a git tree recursively, returning its file, start line
+/// and code
+#[cfg(feature = "git")]
 fn find_function_in_tree(
     repo: &git2::Repository,
     tree: &git2::Tree,
     function_name: &str,
-) -> Result<(Option<String>, Option<String>), Box<dyn std::error::Error>> {
+) -> Result<FoundFunction, Box<dyn std::error::Error>> {
     search_tree_for_function(repo, tree, function_name, "")
 }
 
 /// Recursive helper to search through a git tree
+#[cfg(feature = "git")]
 fn search_tree_for_function(
     repo: &git2::Repository,
     tree: &git2::Tree,
     function_name: &str,
     current_path: &str,
-) -> Result<(Option<String>, Option<String>), Box<dyn std::error::Error>> {
+) -> Result<FoundFunction, Box<dyn std::error::Error>> {
     for entry in tree.iter() {
         let entry_name = entry.name().unwrap_or("");
         let entry_path = if current_path.is_empty() {
@@ -297,36 +1023,28 @@ fn search_tree_for_function(
             Some(git2::ObjectType::Tree) => {
                 // Recursively search subdirectories
                 if let Ok(subtree) = entry.to_object(repo).and_then(|obj| obj.peel_to_tree()) {
-                    let (found_file, found_content) =
+                    let found =
                         search_tree_for_function(repo, &subtree, function_name, &entry_path)?;
-                    if found_content.is_some() {
-                        return Ok((found_file, found_content));
+                    if found.2.is_some() {
+                        return Ok(found);
                     }
                 }
             }
-            Some(git2::ObjectType::Blob) => {
-                // Check if this is a source file
-                if is_source_file_by_name(entry_name) {
-                    if let Ok(blob) = entry.to_object(repo).and_then(|obj| obj.peel_to_blob()) {
-                        if !blob.is_binary() {
-                            if let Ok(content) = std::str::from_utf8(blob.content()) {
-                                if let Some(function_content) =
-                                    extract_function_from_content_with_name(
-                                        content,
-                                        function_name,
-                                        entry_name,
-                                    )
-                                {
-                                    return Ok((Some(entry_path), Some(function_content)));
-                                }
-                            }
-                        }
-                    }
+            Some(git2::ObjectType::Blob) if is_source_file_by_name(entry_name) => {
+                // Check if this source file contains the function
+                if let Ok(blob) = entry.to_object(repo).and_then(|obj| obj.peel_to_blob())
+                    && !blob.is_binary()
+                    && let Ok(content) = std::str::from_utf8(blob.content())
+                    && let Some(function_content) =
+                        extract_function_from_content_with_name(content, function_name, entry_name)
+                {
+                    let line = locate_snippet(content, &function_content).map(|(start, _)| start);
+                    return Ok((Some(entry_path), line, Some(function_content)));
                 }
             }
             _ => {}
         }
     }
 
-    Ok((None, None))
+    Ok((None, None, None))
 }
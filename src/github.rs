@@ -0,0 +1,382 @@
+use std::error::Error;
+
+#[cfg(feature = "git")]
+use crate::openai::verify_intent_with_options;
+#[cfg(feature = "git")]
+use crate::options::AnalysisOptions;
+#[cfg(feature = "git")]
+use crate::types::IntentVerificationResult;
+#[cfg(feature = "git")]
+use crate::validation::ValidationError;
+
+/// Hidden marker identifying the comment this tool keeps updated on a pull request
+pub const STICKY_COMMENT_MARKER: &str = "<!-- intent-verification -->";
+
+const DEFAULT_API_URL: &str = "https://api.github.com";
+
+/// Pull request being verified, read from a GitHub Actions `pull_request` event
+#[derive(Debug, Clone, PartialEq, serde::Serialize, serde::Deserialize)]
+pub struct PullRequestContext {
+    /// `owner/name` of the repository the pull request targets
+    pub repository: String,
+    pub number: u64,
+    /// Clone URL of the repository containing the head commit (may be a fork)
+    pub clone_url: String,
+    pub base_sha: String,
+    pub head_sha: String,
+    pub title: String,
+    pub body: Option<String>,
+}
+
+#[derive(serde::Deserialize)]
+struct PullRequestEvent {
+    pull_request: EventPullRequest,
+    repository: EventRepository,
+}
+
+#[derive(serde::Deserialize)]
+struct EventPullRequest {
+    number: u64,
+    title: String,
+    body: Option<String>,
+    base: EventRef,
+    head: EventRef,
+}
+
+#[derive(serde::Deserialize)]
+struct EventRef {
+    sha: String,
+    repo: Option<EventRepository>,
+}
+
+#[derive(serde::Deserialize)]
+struct EventRepository {
+    full_name: String,
+    clone_url: String,
+}
+
+impl PullRequestContext {
+    /// Parse the payload of a `pull_request` or `pull_request_target` event
+    pub fn from_event_json(json: &str) -> Result<Self, Box<dyn Error>> {
+        let event: PullRequestEvent =
+            serde_json::from_str(json).map_err(|e| format!("Not a pull request event: {}", e))?;
+        let pr = event.pull_request;
+
+        // The head repository is missing when a fork has been deleted
+        let clone_url = pr
+            .head
+            .repo
+            .map(|repo| repo.clone_url)
+            .unwrap_or(event.repository.clone_url);
+
+        Ok(PullRequestContext {
+            repository: event.repository.full_name,
+            number: pr.number,
+            clone_url,
+            base_sha: pr.base.sha,
+            head_sha: pr.head.sha,
+            title: pr.title,
+            body: pr.body,
+        })
+    }
+
+    /// Read the event that triggered the current workflow run (`GITHUB_EVENT_PATH`)
+    pub fn from_env() -> Result<Self, Box<dyn Error>> {
+        let path = std::env::var("GITHUB_EVENT_PATH")
+            .map_err(|_| "GITHUB_EVENT_PATH is not set; not running in GitHub Actions?")?;
+        Self::from_event_json(&std::fs::read_to_string(path)?)
+    }
+
+    /// Intent stated by the pull request: its title followed by its description
+    pub fn intent(&self) -> String {
+        match self.body.as_deref().map(str::trim) {
+            Some(body) if !body.is_empty() => format!("{}\n\n{}", self.title, body),
+            _ => self.title.clone(),
+        }
+    }
+}
+
+/// Body of the sticky comment: the report preceded by the marker used to find it again
+pub fn sticky_comment_body(markdown: &str) -> String {
+    format!("{}\n{}", STICKY_COMMENT_MARKER, markdown)
+}
+
+#[derive(serde::Deserialize)]
+struct IssueComment {
+    id: u64,
+    body: Option<String>,
+}
+
+/// Create or update the sticky comment on a pull request
+///
+/// Looks for an existing comment containing [`STICKY_COMMENT_MARKER`] and edits it, so
+/// repeated runs keep a single up-to-date report instead of piling up comments. Uses
+/// `GITHUB_API_URL` when set (GitHub Enterprise), otherwise `https://api.github.com`.
+pub async fn post_sticky_comment(
+    repository: &str,
+    pr_number: u64,
+    token: &str,
+    markdown: &str,
+) -> Result<(), Box<dyn Error>> {
+    let api_url = std::env::var("GITHUB_API_URL").unwrap_or_else(|_| DEFAULT_API_URL.to_string());
+    let api_url = api_url.trim_end_matches('/');
+    let client = reqwest::Client::new();
+    let request = |method: reqwest::Method, url: String| {
+        client
+            .request(method, url)
+            .bearer_auth(token)
+            .header(reqwest::header::ACCEPT, "application/vnd.github+json")
+            .header(reqwest::header::USER_AGENT, "intent-verification")
+    };
+
+    // Find our previous comment, if any
+    let mut existing = None;
+    for page in 1.. {
+        let comments: Vec<IssueComment> = request(
+            reqwest::Method::GET,
+            format!(
+                "{}/repos/{}/issues/{}/comments?per_page=100&page={}",
+                api_url, repository, pr_number, page
+            ),
+        )
+        .send()
+        .await?
+        .error_for_status()?
+        .json()
+        .await?;
+
+        existing = comments.iter().find_map(|comment| {
+            comment
+                .body
+                .as_deref()
+                .is_some_and(|body| body.contains(STICKY_COMMENT_MARKER))
+                .then_some(comment.id)
+        });
+        if existing.is_some() || comments.len() < 100 {
+            break;
+        }
+    }
+
+    let payload = serde_json::json!({ "body": sticky_comment_body(markdown) });
+    let response = match existing {
+        Some(id) => {
+            request(
+                reqwest::Method::PATCH,
+                format!("{}/repos/{}/issues/comments/{}", api_url, repository, id),
+            )
+            .json(&payload)
+            .send()
+            .await?
+        }
+        None => {
+            request(
+                reqwest::Method::POST,
+                format!(
+                    "{}/repos/{}/issues/{}/comments",
+                    api_url, repository, pr_number
+                ),
+            )
+            .json(&payload)
+            .send()
+            .await?
+        }
+    };
+    response.error_for_status()?;
+    Ok(())
+}
+
+/// Issue whose text is used as the intent to verify
+#[derive(Debug, Clone, PartialEq, serde::Serialize, serde::Deserialize)]
+pub struct GithubIssue {
+    /// `owner/name` of the repository the issue belongs to
+    pub repository: String,
+    pub number: u64,
+    pub title: String,
+    pub body: Option<String>,
+    /// Link to the issue on GitHub
+    pub html_url: String,
+    pub labels: Vec<String>,
+}
+
+#[derive(serde::Deserialize)]
+struct ApiIssue {
+    number: u64,
+    title: String,
+    body: Option<String>,
+    html_url: String,
+    #[serde(default)]
+    labels: Vec<ApiLabel>,
+    pull_request: Option<serde_json::Value>,
+}
+
+#[derive(serde::Deserialize)]
+struct ApiLabel {
+    name: String,
+}
+
+impl GithubIssue {
+    /// Parse an issue as returned by `GET /repos/{owner}/{repo}/issues/{number}`
+    pub fn from_json(repository: &str, json: &str) -> Result<Self, Box<dyn Error>> {
+        let issue: ApiIssue =
+            serde_json::from_str(json).map_err(|e| format!("Not an issue: {}", e))?;
+        if issue.pull_request.is_some() {
+            return Err(format!(
+                "{}#{} is a pull request, not an issue",
+                repository, issue.number
+            )
+            .into());
+        }
+        Ok(GithubIssue {
+            repository: repository.to_string(),
+            number: issue.number,
+            title: issue.title,
+            body: issue.body,
+            html_url: issue.html_url,
+            labels: issue.labels.into_iter().map(|label| label.name).collect(),
+        })
+    }
+
+    /// List items of the body's "Acceptance criteria" section, if it has one
+    ///
+    /// The section starts at a Markdown heading or bold line mentioning acceptance criteria
+    /// and ends at the next heading. Task list checkboxes are stripped.
+    pub fn acceptance_criteria(&self) -> Vec<String> {
+        let Some(body) = self.body.as_deref() else {
+            return vec![];
+        };
+        let mut criteria = Vec::new();
+        let mut in_section = false;
+        for line in body.lines().map(str::trim) {
+            let is_heading = line.starts_with('#') || line.starts_with("**");
+            if is_heading {
+                in_section = line.to_lowercase().contains("acceptance criteria");
+                continue;
+            }
+            if !in_section {
+                continue;
+            }
+            let item = line
+                .strip_prefix("- ")
+                .or_else(|| line.strip_prefix("* "))
+                .or_else(|| {
+                    let (number, rest) = line.split_once(". ")?;
+                    number.chars().all(|c| c.is_ascii_digit()).then_some(rest)
+                });
+            if let Some(item) = item {
+                let item = ["[ ] ", "[x] ", "[X] "]
+                    .iter()
+                    .find_map(|checkbox| item.strip_prefix(checkbox))
+                    .unwrap_or(item)
+                    .trim();
+                if !item.is_empty() {
+                    criteria.push(item.to_string());
+                }
+            }
+        }
+        criteria
+    }
+
+    /// Intent stated by the issue: its title followed by its acceptance criteria when the
+    /// body lists them, or by the whole body otherwise
+    pub fn intent(&self) -> String {
+        let criteria = self.acceptance_criteria();
+        if !criteria.is_empty() {
+            let list: Vec<String> = criteria.iter().map(|c| format!("- {}", c)).collect();
+            return format!(
+                "{}\n\nAcceptance criteria:\n{}",
+                self.title,
+                list.join("\n")
+            );
+        }
+        match self.body.as_deref().map(str::trim) {
+            Some(body) if !body.is_empty() => format!("{}\n\n{}", self.title, body),
+            _ => self.title.clone(),
+        }
+    }
+}
+
+/// Parse an issue reference like `owner/name#42`
+pub fn parse_issue_reference(reference: &str) -> Result<(String, u64), String> {
+    let (repository, number) = reference
+        .split_once('#')
+        .ok_or_else(|| format!("Expected owner/name#number, got '{}'", reference))?;
+    let number = number
+        .parse()
+        .map_err(|_| format!("Invalid issue number in '{}'", reference))?;
+    if repository.split('/').count() != 2 || repository.split('/').any(str::is_empty) {
+        return Err(format!("Expected owner/name#number, got '{}'", reference));
+    }
+    Ok((repository.to_string(), number))
+}
+
+/// Fetch an issue through the GitHub API
+///
+/// A token is only needed for private repositories. Uses `GITHUB_API_URL` when set.
+pub async fn fetch_issue(
+    repository: &str,
+    number: u64,
+    token: Option<&str>,
+) -> Result<GithubIssue, Box<dyn Error>> {
+    let api_url = std::env::var("GITHUB_API_URL").unwrap_or_else(|_| DEFAULT_API_URL.to_string());
+    let mut request = reqwest::Client::new()
+        .get(format!(
+            "{}/repos/{}/issues/{}",
+            api_url.trim_end_matches('/'),
+            repository,
+            number
+        ))
+        .header(reqwest::header::ACCEPT, "application/vnd.github+json")
+        .header(reqwest::header::USER_AGENT, "intent-verification");
+    if let Some(token) = token {
+        request = request.bearer_auth(token);
+    }
+    let json = request.send().await?.error_for_status()?.text().await?;
+    GithubIssue::from_json(repository, &json)
+}
+
+/// Verify changes against the intent stated by a GitHub issue
+///
+/// Same as [`verify_intent_with_options`](crate::verify_intent_with_options), with the intent
+/// taken from [`GithubIssue::intent`]. The issue URL is recorded in the result metadata.
+#[cfg(feature = "git")]
+#[allow(clippy::too_many_arguments)]
+pub async fn verify_intent_from_issue(
+    repository: &str,
+    issue_number: u64,
+    github_token: Option<&str>,
+    test_repo_url: &str,
+    test_commit: &str,
+    solution_repo_url: &str,
+    solution_commit1: &str,
+    solution_commit2: &str,
+    api_key: &str,
+    model: Option<&str>,
+    base_url: Option<&str>,
+    options: &AnalysisOptions,
+) -> Result<IntentVerificationResult, Box<dyn Error>> {
+    if options.local_only.is_some() {
+        return Err(Box::new(ValidationError::NetworkAccessNotAllowed {
+            url: format!("the GitHub issue {}#{}", repository, issue_number),
+        }));
+    }
+    let issue = fetch_issue(repository, issue_number, github_token).await?;
+    eprintln!(
+        "🐙 Using {}#{} as the intent: {}",
+        repository, issue.number, issue.title
+    );
+    let mut result = verify_intent_with_options(
+        test_repo_url,
+        test_commit,
+        solution_repo_url,
+        solution_commit1,
+        solution_commit2,
+        &issue.intent(),
+        api_key,
+        model,
+        base_url,
+        options,
+    )
+    .await?;
+    result.metadata.issue_url = Some(issue.html_url);
+    Ok(result)
+}
@@ -0,0 +1,185 @@
+use std::sync::Arc;
+
+use futures::stream;
+use tokio::net::TcpListener;
+use tokio::sync::Semaphore;
+use tonic::{Request, Response, Status};
+
+use crate::openai::verify_intent_with_options;
+use crate::server::{self, ServerConfig};
+use crate::types::IntentVerificationResult;
+
+// Messages, service trait, server and client generated by build.rs from
+// proto/intent_verification/v1/intent_verification.proto
+mod generated {
+    tonic::include_proto!("intent_verification.v1");
+}
+pub use generated::intent_verification_client::IntentVerificationClient;
+pub use generated::intent_verification_server::{IntentVerification, IntentVerificationServer};
+pub use generated::{
+    AnalyzeRepositoryRequest, FileAnalysis, RequestOptions, VerificationResult, VerifyIntentRequest,
+};
+
+impl TryFrom<RequestOptions> for server::RequestOptions {
+    type Error = Status;
+
+    fn try_from(options: RequestOptions) -> Result<Self, Status> {
+        Ok(server::RequestOptions {
+            language: options.language,
+            archetype: options
+                .archetype
+                .map(|archetype| archetype.parse())
+                .transpose()
+                .map_err(Status::invalid_argument)?,
+            profile: options
+                .profile
+                .map(|profile| profile.parse())
+                .transpose()
+                .map_err(Status::invalid_argument)?,
+            acceptance_criteria: options.acceptance_criteria,
+            change_summary: options.change_summary,
+            clarifications: options.clarifications,
+        })
+    }
+}
+
+impl From<&IntentVerificationResult> for VerificationResult {
+    fn from(result: &IntentVerificationResult) -> Self {
+        VerificationResult {
+            is_intent_fulfilled: result.is_intent_fulfilled,
+            confidence: result.confidence,
+            explanation: result.explanation.clone(),
+            overall_assessment: result.overall_assessment.clone(),
+            files_analyzed: result
+                .files_analyzed
+                .iter()
+                .map(|analysis| FileAnalysis {
+                    file_path: analysis.file_path.clone(),
+                    change_type: format!("{:?}", analysis.change_type),
+                    supports_intent: analysis.supports_intent,
+                    reasoning: analysis.reasoning.clone(),
+                    relevant_changes: analysis.relevant_changes.clone(),
+                })
+                .collect(),
+            is_partial: result.is_partial,
+            warnings: result.warnings.iter().map(|w| w.message.clone()).collect(),
+            result_json: serde_json::to_string(result).unwrap_or_default(),
+        }
+    }
+}
+
+/// gRPC counterpart of the REST API, answering each call once its verification finishes
+///
+/// Like the REST API, calls need the `auth_token` as `authorization: Bearer <token>` metadata
+/// when one is configured (`UNAUTHENTICATED` otherwise), and are refused with `UNAVAILABLE`
+/// once `max_running_jobs` verifications are running.
+pub struct GrpcService {
+    config: Arc<ServerConfig>,
+    running: Arc<Semaphore>,
+}
+
+impl GrpcService {
+    pub fn new(config: ServerConfig) -> Self {
+        GrpcService {
+            running: Arc::new(Semaphore::new(config.max_running_jobs())),
+            config: Arc::new(config),
+        }
+    }
+
+    fn authorize<T>(&self, request: &Request<T>) -> Result<(), Status> {
+        let authorization = request
+            .metadata()
+            .get("authorization")
+            .and_then(|value| value.to_str().ok());
+        if !self.config.is_authorized(authorization) {
+            return Err(Status::unauthenticated("Missing or invalid bearer token"));
+        }
+        Ok(())
+    }
+
+    async fn run(&self, request: VerifyIntentRequest) -> Result<VerificationResult, Status> {
+        let required = [
+            ("test_repo_url", &request.test_repo_url),
+            ("test_commit", &request.test_commit),
+            ("solution_repo_url", &request.solution_repo_url),
+            ("solution_commit1", &request.solution_commit1),
+            ("solution_commit2", &request.solution_commit2),
+            ("user_intent", &request.user_intent),
+        ];
+        if let Some((field, _)) = required.iter().find(|(_, value)| value.trim().is_empty()) {
+            return Err(Status::invalid_argument(format!("{} is required", field)));
+        }
+        let request_options =
+            server::RequestOptions::try_from(request.options.clone().unwrap_or_default())?;
+        let _permit = self.running.try_acquire().map_err(|_| {
+            Status::unavailable(format!(
+                "{} verifications are already running; retry later",
+                self.config.max_running_jobs()
+            ))
+        })?;
+
+        let config = &self.config;
+        let options = request_options.apply(&config.options);
+        verify_intent_with_options(
+            &request.test_repo_url,
+            &request.test_commit,
+            &request.solution_repo_url,
+            &request.solution_commit1,
+            &request.solution_commit2,
+            &request.user_intent,
+            &config.api_key,
+            request.model.as_deref().or(config.model.as_deref()),
+            config.base_url.as_deref(),
+            &options,
+        )
+        .await
+        .map(|result| VerificationResult::from(&result))
+        .map_err(|e| Status::internal(e.to_string()))
+    }
+}
+
+#[tonic::async_trait]
+impl IntentVerification for GrpcService {
+    async fn verify_intent(
+        &self,
+        request: Request<VerifyIntentRequest>,
+    ) -> Result<Response<VerificationResult>, Status> {
+        self.authorize(&request)?;
+        self.run(request.into_inner()).await.map(Response::new)
+    }
+
+    async fn analyze_repository(
+        &self,
+        request: Request<AnalyzeRepositoryRequest>,
+    ) -> Result<Response<VerificationResult>, Status> {
+        self.authorize(&request)?;
+        let request = request.into_inner();
+        self.run(VerifyIntentRequest {
+            test_repo_url: request.repo_url.clone(),
+            test_commit: request.head.clone(),
+            solution_repo_url: request.repo_url,
+            solution_commit1: request.base,
+            solution_commit2: request.head,
+            user_intent: request.user_intent,
+            model: request.model,
+            options: request.options,
+        })
+        .await
+        .map(Response::new)
+    }
+}
+
+/// Serve the `intent_verification.v1.IntentVerification` gRPC service on `listener`
+pub async fn serve_grpc(
+    listener: TcpListener,
+    config: ServerConfig,
+) -> Result<(), tonic::transport::Error> {
+    let incoming = stream::unfold(listener, |listener| async {
+        let connection = listener.accept().await.map(|(stream, _)| stream);
+        Some((connection, listener))
+    });
+    tonic::transport::Server::builder()
+        .add_service(IntentVerificationServer::new(GrpcService::new(config)))
+        .serve_with_incoming(incoming)
+        .await
+}
@@ -0,0 +1,238 @@
+use std::collections::BTreeMap;
+use std::error::Error;
+use std::path::{Path, PathBuf};
+use std::sync::{Arc, Mutex};
+
+use crate::git::FileChange;
+use crate::openai::DIFF_CONTEXT_LINES;
+use crate::options::AnalysisOptions;
+use crate::types::{FileIntentAnalysis, Finding, TestTargets, TestTargetsWithCode};
+use crate::utils::sha256_hex;
+
+/// Format version of the state file; files of another version are ignored
+const STATE_VERSION: u32 = 2;
+
+/// Per-file analysis stored for reuse by a later run
+#[derive(Debug, Clone, serde::Serialize, serde::Deserialize)]
+pub struct CachedAnalysis {
+    /// Git blob hash of the file content the analysis was made for
+    pub blob_hash: String,
+    /// Git blob hash of the content the file was changed from; the analysis judged the
+    /// change, so the same new content on another base needs analyzing again
+    #[serde(default)]
+    pub old_blob_hash: Option<String>,
+    pub prompt_version: String,
+    /// Hash of everything else the analysis depended on: intent, model, test targets and
+    /// prompt instructions
+    pub context_hash: String,
+    pub analysis: FileIntentAnalysis,
+    /// Findings the model reported for the file
+    #[serde(default)]
+    pub findings: Vec<Finding>,
+}
+
+#[derive(Debug, Default, serde::Serialize, serde::Deserialize)]
+struct State {
+    version: u32,
+    /// Test targets extracted for an intent, keyed by a hash of the intent, model and prompt
+    /// version, so later runs analyze against the same targets
+    #[serde(default)]
+    targets: BTreeMap<String, TestTargets>,
+    /// Analyses keyed by file path
+    #[serde(default)]
+    files: BTreeMap<String, Vec<CachedAnalysis>>,
+}
+
+/// Per-file analyses kept between runs, set on [`crate::AnalysisOptions::analysis_cache`]
+///
+/// A changed file whose old and new content (by git blob hash), prompt version and analysis
+/// context match a stored entry is not sent to the model again, so re-verifying after a small follow-up
+/// commit only analyzes the files that changed. The test targets extracted for an intent are
+/// reused too. File-backed caches are written back at the end of each verification; clones
+/// share the same state.
+#[derive(Debug, Clone)]
+pub struct AnalysisCache {
+    path: Option<PathBuf>,
+    state: Arc<Mutex<State>>,
+}
+
+impl AnalysisCache {
+    /// Cache backed by a JSON file, starting empty when the file doesn't exist yet
+    pub fn open(path: impl AsRef<Path>) -> Result<Self, Box<dyn Error>> {
+        let path = path.as_ref().to_path_buf();
+        let state = match std::fs::read_to_string(&path) {
+            Ok(text) => {
+                let state: State = serde_json::from_str(&text)?;
+                if state.version == STATE_VERSION {
+                    state
+                } else {
+                    State::default()
+                }
+            }
+            Err(e) if e.kind() == std::io::ErrorKind::NotFound => State::default(),
+            Err(e) => return Err(e.into()),
+        };
+        Ok(AnalysisCache {
+            path: Some(path),
+            state: Arc::new(Mutex::new(state)),
+        })
+    }
+
+    /// Cache kept in memory only, e.g. for several verifications in one process
+    pub fn in_memory() -> Self {
+        AnalysisCache {
+            path: None,
+            state: Arc::new(Mutex::new(State::default())),
+        }
+    }
+
+    /// Write the state back to its file; a no-op for in-memory caches
+    pub fn save(&self) -> Result<(), Box<dyn Error>> {
+        let Some(path) = &self.path else {
+            return Ok(());
+        };
+        let mut state = self.state.lock().unwrap();
+        state.version = STATE_VERSION;
+        // Written aside and renamed, so an interrupted write never leaves a truncated file
+        let partial = path.with_extension("partial");
+        std::fs::write(&partial, serde_json::to_string_pretty(&*state)?)?;
+        std::fs::rename(&partial, path)?;
+        Ok(())
+    }
+
+    /// Number of stored file analyses
+    pub fn len(&self) -> usize {
+        self.state
+            .lock()
+            .unwrap()
+            .files
+            .values()
+            .map(Vec::len)
+            .sum()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
+
+    pub(crate) fn targets(&self, key: &str) -> Option<TestTargets> {
+        self.state.lock().unwrap().targets.get(key).cloned()
+    }
+
+    pub(crate) fn insert_targets(&self, key: &str, targets: &TestTargets) {
+        self.state
+            .lock()
+            .unwrap()
+            .targets
+            .insert(key.to_string(), targets.clone());
+    }
+
+    pub(crate) fn get(
+        &self,
+        file_change: &FileChange,
+        prompt_version: &str,
+        context_hash: &str,
+    ) -> Option<CachedAnalysis> {
+        let blob_hash = blob_hash(file_change)?;
+        let old_blob_hash = old_blob_hash(file_change);
+        self.state
+            .lock()
+            .unwrap()
+            .files
+            .get(&file_change.path)?
+            .iter()
+            .find(|entry| {
+                entry.blob_hash == blob_hash
+                    && entry.old_blob_hash == old_blob_hash
+                    && entry.analysis.change_type == file_change.status
+                    && entry.prompt_version == prompt_version
+                    && entry.context_hash == context_hash
+            })
+            .cloned()
+    }
+
+    /// Store an analysis, replacing the file's entry for the same prompt version and context
+    pub(crate) fn insert(
+        &self,
+        file_change: &FileChange,
+        prompt_version: &str,
+        context_hash: &str,
+        analysis: &FileIntentAnalysis,
+        findings: &[Finding],
+    ) {
+        let Some(blob_hash) = blob_hash(file_change) else {
+            return;
+        };
+        let mut state = self.state.lock().unwrap();
+        let entries = state.files.entry(file_change.path.clone()).or_default();
+        entries.retain(|e| e.prompt_version != prompt_version || e.context_hash != context_hash);
+        entries.push(CachedAnalysis {
+            blob_hash,
+            old_blob_hash: old_blob_hash(file_change),
+            prompt_version: prompt_version.to_string(),
+            context_hash: context_hash.to_string(),
+            analysis: analysis.clone(),
+            findings: findings.to_vec(),
+        });
+    }
+}
+
+/// Git blob hash of the file's new content; `None` for deleted files
+pub fn blob_hash(file_change: &FileChange) -> Option<String> {
+    file_change.content.as_deref().map(git_blob_hash)
+}
+
+/// Git blob hash of the file's old content; `None` for added files
+fn old_blob_hash(file_change: &FileChange) -> Option<String> {
+    file_change.old_content.as_deref().map(git_blob_hash)
+}
+
+fn git_blob_hash(content: &str) -> String {
+    use sha1::{Digest, Sha1};
+
+    let mut hasher = Sha1::new();
+    hasher.update(format!("blob {}\0", content.len()));
+    hasher.update(content);
+    hasher
+        .finalize()
+        .iter()
+        .map(|b| format!("{:02x}", b))
+        .collect()
+}
+
+/// Key of the test targets extracted for an intent
+pub(crate) fn targets_key(user_intent: &str, model: &str, options: &AnalysisOptions) -> String {
+    sha256_hex(
+        format!(
+            "{}\0{}\0{}",
+            user_intent,
+            model,
+            options.prompt_templates().version
+        )
+        .as_bytes(),
+    )
+}
+
+/// Hash of the inputs of a file analysis besides the file itself
+///
+/// Modified files are prompted as their diff hunks, so the hunks' context size counts too;
+/// the hunks themselves follow from the old and new content the entries are keyed on.
+pub(crate) fn context_hash(
+    user_intent: &str,
+    model: &str,
+    targets_with_code: &TestTargetsWithCode,
+    options: &AnalysisOptions,
+) -> String {
+    let context = serde_json::json!({
+        "intent": user_intent,
+        "model": model,
+        "targets": targets_with_code,
+        "language": options.language,
+        "profile": options.verification_profile(),
+        "archetype": options.archetype,
+        "clarifications": options.clarifications,
+        "redaction": options.redaction,
+        "diff_context_lines": DIFF_CONTEXT_LINES,
+    });
+    sha256_hex(context.to_string().as_bytes())
+}
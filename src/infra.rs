@@ -0,0 +1,374 @@
+use std::collections::BTreeMap;
+
+use similar::{ChangeTag, TextDiff};
+
+use crate::git::{ChangeType, FileChange};
+
+/// Infrastructure or configuration file reviewed with a structural diff
+#[derive(Debug, Clone, Copy, PartialEq, Eq, serde::Serialize, serde::Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum InfraKind {
+    Dockerfile,
+    /// Docker Compose file
+    Compose,
+    /// Kubernetes manifest
+    Kubernetes,
+    /// Terraform or other HCL configuration
+    Terraform,
+    /// CI pipeline (GitHub Actions, GitLab CI, CircleCI, ...)
+    CiConfig,
+}
+
+impl InfraKind {
+    /// Misconfiguration risks the model is asked to look for in this kind of file
+    fn risks(self) -> &'static str {
+        match self {
+            InfraKind::Dockerfile => {
+                "unpinned or `latest` base images, running as root (no USER), secrets in ENV or ARG, piping downloads into a shell, ADD from remote URLs, exposed ports"
+            }
+            InfraKind::Compose => {
+                "privileged services, host networking, ports published on all interfaces, the Docker socket mounted into a container, plaintext secrets in environment, `latest` images"
+            }
+            InfraKind::Kubernetes => {
+                "privileged containers, hostNetwork/hostPID/hostPath, running as root or allowPrivilegeEscalation, removed securityContext, missing resource limits, `latest` images, secrets in plain env values, wildcard RBAC rules, services newly exposed through LoadBalancer or NodePort, removed probes"
+            }
+            InfraKind::Terraform => {
+                "ingress from 0.0.0.0/0, public buckets or ACLs, disabled encryption or logging, wildcard IAM actions or resources, deletion protection turned off, hardcoded credentials, resources that will be destroyed or replaced"
+            }
+            InfraKind::CiConfig => {
+                "write-all permissions, pull_request_target jobs checking out untrusted code, untrusted event data interpolated into scripts, third-party actions not pinned to a commit, secrets printed to logs, removed or skipped checks"
+            }
+        }
+    }
+}
+
+/// Kind of infrastructure file at `path`, if it is one
+///
+/// YAML files count as Kubernetes manifests when they declare an `apiVersion` and a `kind`.
+pub fn infra_kind(path: &str, content: &str) -> Option<InfraKind> {
+    let lower = path.to_lowercase();
+    let name = lower.rsplit('/').next().unwrap_or(&lower);
+    let is_yaml = is_yaml_path(name);
+
+    if name == "dockerfile"
+        || name.starts_with("dockerfile.")
+        || name.ends_with(".dockerfile")
+        || name == "containerfile"
+    {
+        Some(InfraKind::Dockerfile)
+    } else if name.ends_with(".tf") || name.ends_with(".tfvars") || name.ends_with(".hcl") {
+        Some(InfraKind::Terraform)
+    } else if (lower.starts_with(".github/workflows/") && is_yaml)
+        || lower.starts_with(".circleci/")
+        || [
+            ".gitlab-ci.yml",
+            "azure-pipelines.yml",
+            "bitbucket-pipelines.yml",
+            ".travis.yml",
+            "jenkinsfile",
+        ]
+        .contains(&name)
+    {
+        Some(InfraKind::CiConfig)
+    } else if is_yaml && (name.starts_with("docker-compose") || name.starts_with("compose.")) {
+        Some(InfraKind::Compose)
+    } else if is_yaml
+        && content.lines().any(|l| l.starts_with("apiVersion:"))
+        && content.lines().any(|l| l.starts_with("kind:"))
+    {
+        Some(InfraKind::Kubernetes)
+    } else {
+        None
+    }
+}
+
+/// A setting added, removed or changed by the changes
+#[derive(Debug, Clone, PartialEq, serde::Serialize, serde::Deserialize)]
+pub struct StructuralChange {
+    /// Dotted path of the setting, e.g. `Deployment/web.spec.replicas`; the instruction
+    /// keyword for Dockerfiles
+    pub path: String,
+    pub before: Option<String>,
+    pub after: Option<String>,
+}
+
+impl std::fmt::Display for StructuralChange {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match (&self.before, &self.after) {
+            (None, Some(after)) => write!(f, "+ {} = {}", self.path, after),
+            (Some(before), None) => write!(f, "- {} = {}", self.path, before),
+            (Some(before), Some(after)) => {
+                write!(f, "~ {}: {} -> {}", self.path, before, after)
+            }
+            (None, None) => write!(f, "  {}", self.path),
+        }
+    }
+}
+
+/// Settings changed in an infrastructure file, compared by structure rather than by line
+///
+/// Returns `None` for other files, and when the YAML doesn't parse (e.g. Helm templates).
+pub fn structural_diff(file_change: &FileChange) -> Option<Vec<StructuralChange>> {
+    let new = match file_change.status {
+        ChangeType::Deleted => "",
+        _ => file_change.content.as_deref().unwrap_or(""),
+    };
+    let old = file_change.old_content.as_deref().unwrap_or("");
+    let kind = infra_kind(&file_change.path, if new.is_empty() { old } else { new })?;
+
+    if kind == InfraKind::Dockerfile {
+        return Some(dockerfile_diff(old, new));
+    }
+
+    let (old, new) = match kind {
+        InfraKind::Terraform => (flatten_hcl(old), flatten_hcl(new)),
+        // Jenkinsfiles are Groovy; the line diff is all there is
+        _ if !is_yaml_path(&file_change.path) => return None,
+        _ => (flatten_yaml(old)?, flatten_yaml(new)?),
+    };
+
+    let mut changes = Vec::new();
+    for (path, before) in &old {
+        match new.get(path) {
+            Some(after) if after == before => {}
+            after => changes.push(StructuralChange {
+                path: path.clone(),
+                before: Some(before.clone()),
+                after: after.cloned(),
+            }),
+        }
+    }
+    for (path, after) in &new {
+        if !old.contains_key(path) {
+            changes.push(StructuralChange {
+                path: path.clone(),
+                before: None,
+                after: Some(after.clone()),
+            });
+        }
+    }
+    changes.sort_by(|a, b| a.path.cmp(&b.path));
+    Some(changes)
+}
+
+/// Prompt instruction for reviewing an infrastructure file, with its structural diff, or
+/// `None` for other files
+pub fn infra_review_instruction(file_change: &FileChange) -> Option<String> {
+    let content = file_change
+        .content
+        .as_deref()
+        .or(file_change.old_content.as_deref())
+        .unwrap_or("");
+    let kind = infra_kind(&file_change.path, content)?;
+    let mut instruction = format!(
+        "{} is infrastructure configuration ({:?}). Besides the intent, check the change for misconfiguration risks such as: {}. \
+         Mention every risk the change introduces in the reasoning, and don't count a change that introduces one as supporting the intent unless the intent asks for it.",
+        file_change.path,
+        kind,
+        kind.risks()
+    );
+    if let Some(changes) = structural_diff(file_change)
+        && !changes.is_empty()
+    {
+        instruction.push_str("\n\nSettings changed (structural diff):\n");
+        for change in changes {
+            instruction.push_str(&format!("{}\n", change));
+        }
+    }
+    Some(instruction)
+}
+
+fn is_yaml_path(path: &str) -> bool {
+    let path = path.to_lowercase();
+    path.ends_with(".yml") || path.ends_with(".yaml")
+}
+
+/// Instructions added and removed between two Dockerfiles, keyed by their keyword; a removed
+/// instruction followed by an added one with the same keyword is reported as a change
+fn dockerfile_diff(old: &str, new: &str) -> Vec<StructuralChange> {
+    let (old, new) = (dockerfile_instructions(old), dockerfile_instructions(new));
+    let old: Vec<&str> = old.iter().map(String::as_str).collect();
+    let new: Vec<&str> = new.iter().map(String::as_str).collect();
+    let split = |instruction: &str| {
+        let (keyword, rest) = instruction.split_once(' ').unwrap_or((instruction, ""));
+        (keyword.to_uppercase(), rest.to_string())
+    };
+
+    let mut changes: Vec<StructuralChange> = Vec::new();
+    let mut removed: Vec<StructuralChange> = Vec::new();
+    for change in TextDiff::from_slices(&old, &new).iter_all_changes() {
+        let (keyword, value) = split(change.value());
+        match change.tag() {
+            ChangeTag::Delete => removed.push(StructuralChange {
+                path: keyword,
+                before: Some(value),
+                after: None,
+            }),
+            ChangeTag::Insert => match removed.iter().position(|r| r.path == keyword) {
+                Some(i) => {
+                    let mut changed = removed.remove(i);
+                    changed.after = Some(value);
+                    changes.push(changed);
+                }
+                None => changes.push(StructuralChange {
+                    path: keyword,
+                    before: None,
+                    after: Some(value),
+                }),
+            },
+            ChangeTag::Equal => changes.append(&mut removed),
+        }
+    }
+    changes.append(&mut removed);
+    changes
+}
+
+/// Dockerfile instructions with continuation lines joined and comments dropped
+fn dockerfile_instructions(content: &str) -> Vec<String> {
+    let mut instructions = Vec::new();
+    let mut current = String::new();
+    for line in content.lines() {
+        let line = line.trim();
+        if line.is_empty() || line.starts_with('#') {
+            continue;
+        }
+        match line.strip_suffix('\\') {
+            Some(continued) => {
+                current.push_str(continued.trim());
+                current.push(' ');
+            }
+            None => {
+                current.push_str(line);
+                instructions.push(current.split_whitespace().collect::<Vec<_>>().join(" "));
+                current.clear();
+            }
+        }
+    }
+    if !current.trim().is_empty() {
+        instructions.push(current.trim().to_string());
+    }
+    instructions
+}
+
+/// Scalar settings of every YAML document, keyed by dotted path
+///
+/// Kubernetes documents are prefixed with `Kind/name` so reordering manifests doesn't show up
+/// as changes; other documents with their index when there are several.
+fn flatten_yaml(content: &str) -> Option<BTreeMap<String, String>> {
+    let mut settings = BTreeMap::new();
+    let documents: Vec<serde_yaml::Value> = serde_yaml::Deserializer::from_str(content)
+        .map(serde::Deserialize::deserialize)
+        .collect::<Result<_, _>>()
+        .ok()?;
+    let several = documents.len() > 1;
+    for (i, document) in documents.iter().enumerate() {
+        if document.is_null() {
+            continue;
+        }
+        let prefix = match (
+            document["kind"].as_str(),
+            document["metadata"]["name"].as_str(),
+        ) {
+            (Some(kind), Some(name)) => format!("{}/{}", kind, name),
+            _ if several => format!("[{}]", i),
+            _ => String::new(),
+        };
+        flatten_yaml_value(document, prefix, &mut settings);
+    }
+    Some(settings)
+}
+
+fn flatten_yaml_value(
+    value: &serde_yaml::Value,
+    path: String,
+    settings: &mut BTreeMap<String, String>,
+) {
+    let join = |key: &str| {
+        if path.is_empty() {
+            key.to_string()
+        } else {
+            format!("{}.{}", path, key)
+        }
+    };
+    match value {
+        serde_yaml::Value::Mapping(mapping) => {
+            for (key, value) in mapping {
+                let key = match key {
+                    serde_yaml::Value::String(key) => key.clone(),
+                    other => serde_yaml::to_string(other)
+                        .unwrap_or_default()
+                        .trim()
+                        .to_string(),
+                };
+                flatten_yaml_value(value, join(&key), settings);
+            }
+        }
+        serde_yaml::Value::Sequence(items) => {
+            for (i, item) in items.iter().enumerate() {
+                // Named items (containers, env vars, steps) are keyed by name, so inserting
+                // one doesn't shift the others
+                let key = ["name", "id"]
+                    .iter()
+                    .find_map(|field| item[*field].as_str())
+                    .map(|name| format!("{}[{}]", path, name))
+                    .unwrap_or_else(|| format!("{}[{}]", path, i));
+                flatten_yaml_value(item, key, settings);
+            }
+        }
+        serde_yaml::Value::Tagged(tagged) => flatten_yaml_value(&tagged.value, path, settings),
+        scalar => {
+            let text = serde_yaml::to_string(scalar).unwrap_or_default();
+            settings.insert(path, text.trim().to_string());
+        }
+    }
+}
+
+/// Attributes of an HCL file keyed by their block path, e.g.
+/// `resource.aws_s3_bucket.logs.acl`
+///
+/// Multi-line values keep their first line only.
+fn flatten_hcl(content: &str) -> BTreeMap<String, String> {
+    let mut settings = BTreeMap::new();
+    let mut blocks: Vec<String> = Vec::new();
+    for line in content.lines() {
+        let line = line.trim();
+        if line.is_empty() || line.starts_with('#') || line.starts_with("//") {
+            continue;
+        }
+        if line == "}" || line == "})" || line == "]" {
+            blocks.pop();
+            continue;
+        }
+        if let Some(header) = line.strip_suffix('{')
+            && !header.contains('=')
+        {
+            let name = header
+                .split_whitespace()
+                .map(|part| part.trim_matches('"'))
+                .collect::<Vec<_>>()
+                .join(".");
+            blocks.push(name);
+            continue;
+        }
+        if let Some((key, value)) = line.split_once('=') {
+            let key = key.trim().trim_matches('"');
+            let value = value.trim();
+            let path = blocks
+                .iter()
+                .map(String::as_str)
+                .chain([key])
+                .collect::<Vec<_>>()
+                .join(".");
+            if value.ends_with('{') || value.ends_with('[') {
+                // Nested object or list value: its lines belong under the key
+                blocks.push(key.to_string());
+                if value.len() > 1 {
+                    settings.insert(path, value.to_string());
+                }
+            } else {
+                settings.insert(path, value.to_string());
+            }
+        }
+    }
+    settings
+}
@@ -0,0 +1,83 @@
+use regex::Regex;
+
+use crate::types::IntentVerificationResult;
+
+/// Verdict for one of several intents verified against the same changes
+#[derive(Debug, Clone, serde::Serialize, serde::Deserialize)]
+pub struct IntentVerdict {
+    pub intent: String,
+    pub result: IntentVerificationResult,
+}
+
+/// Per-intent verdicts plus an aggregate, see `verify_intents_with_options`
+#[derive(Debug, Clone, serde::Serialize, serde::Deserialize)]
+pub struct MultiIntentResult {
+    pub verdicts: Vec<IntentVerdict>,
+    /// True when every intent is fulfilled
+    pub all_fulfilled: bool,
+    pub fulfilled_count: usize,
+    /// Lowest confidence among the verdicts (0.0 when there are none)
+    pub confidence: f32,
+}
+
+impl MultiIntentResult {
+    pub fn from_verdicts(verdicts: Vec<IntentVerdict>) -> Self {
+        let fulfilled_count = verdicts
+            .iter()
+            .filter(|v| v.result.is_intent_fulfilled)
+            .count();
+        let confidence = verdicts
+            .iter()
+            .map(|v| v.result.confidence)
+            .reduce(f32::min)
+            .unwrap_or(0.0);
+        MultiIntentResult {
+            all_fulfilled: !verdicts.is_empty() && fulfilled_count == verdicts.len(),
+            fulfilled_count,
+            confidence,
+            verdicts,
+        }
+    }
+}
+
+/// Split an intent with an enumerated list of acceptance criteria into one intent per criterion
+///
+/// List items (`-`, `*`, `1.`, `1)` or `[ ]`) become separate intents, each prefixed with the
+/// text before the list as context. Indented lines continue the previous item. An intent
+/// with fewer than two items is returned as is.
+pub fn split_acceptance_criteria(intent: &str) -> Vec<String> {
+    let item = Regex::new(r"^\s*(?:[-*•]|\d+[.)]|\[[ xX]\])\s+(.+)$").unwrap();
+    let mut preamble: Vec<&str> = Vec::new();
+    let mut items: Vec<String> = Vec::new();
+
+    for line in intent.lines() {
+        let trimmed = line.trim();
+        if let Some(captures) = item.captures(line) {
+            items.push(captures[1].trim().to_string());
+        } else if trimmed.is_empty() {
+            continue;
+        } else if let Some(last) = items.last_mut()
+            && line.starts_with(char::is_whitespace)
+        {
+            last.push(' ');
+            last.push_str(trimmed);
+        } else if items.is_empty() {
+            preamble.push(trimmed);
+        }
+    }
+
+    if items.len() < 2 {
+        return vec![intent.trim().to_string()];
+    }
+    let context = preamble.join(" ");
+    items
+        .into_iter()
+        .map(|criterion| {
+            if context.is_empty() {
+                criterion
+            } else {
+                format!("{}\nAcceptance criterion: {}", context, criterion)
+            }
+        })
+        .collect()
+}
@@ -0,0 +1,117 @@
+use std::path::Path;
+
+use similar::{ChangeTag, TextDiff};
+
+use crate::git::{ChangeType, FileChange};
+
+/// Changed files and lines of one language in the change set
+#[derive(Debug, Clone, PartialEq, Eq, serde::Serialize, serde::Deserialize)]
+pub struct LanguageStats {
+    /// Language name, e.g. `Rust`, or `Other` for files that aren't recognized
+    pub language: String,
+    pub files: usize,
+    pub lines_added: usize,
+    pub lines_removed: usize,
+}
+
+impl LanguageStats {
+    pub fn lines_changed(&self) -> usize {
+        self.lines_added + self.lines_removed
+    }
+}
+
+/// Language of a file, from its extension or well-known file name
+pub fn detect_language(path: &str) -> &'static str {
+    let file_name = Path::new(path)
+        .file_name()
+        .and_then(|name| name.to_str())
+        .unwrap_or(path);
+    match file_name {
+        "Dockerfile" => return "Dockerfile",
+        "Makefile" | "GNUmakefile" => return "Makefile",
+        "CMakeLists.txt" => return "CMake",
+        _ => {}
+    }
+    let Some(extension) = Path::new(file_name).extension().and_then(|e| e.to_str()) else {
+        return "Other";
+    };
+    match extension.to_lowercase().as_str() {
+        "rs" => "Rust",
+        "py" | "pyi" => "Python",
+        "ts" | "tsx" | "mts" | "cts" => "TypeScript",
+        "js" | "jsx" | "mjs" | "cjs" => "JavaScript",
+        "go" => "Go",
+        "java" => "Java",
+        "kt" | "kts" => "Kotlin",
+        "swift" => "Swift",
+        "c" | "h" => "C",
+        "cc" | "cpp" | "cxx" | "hpp" | "hh" | "hxx" => "C++",
+        "cs" => "C#",
+        "rb" => "Ruby",
+        "php" => "PHP",
+        "scala" => "Scala",
+        "sh" | "bash" | "zsh" => "Shell",
+        "sql" => "SQL",
+        "html" | "htm" => "HTML",
+        "css" | "scss" | "sass" | "less" => "CSS",
+        "vue" => "Vue",
+        "proto" => "Protocol Buffers",
+        "md" | "markdown" | "rst" => "Markdown",
+        "json" => "JSON",
+        "yml" | "yaml" => "YAML",
+        "toml" => "TOML",
+        "xml" => "XML",
+        _ => "Other",
+    }
+}
+
+/// Per-language file and line counts of the changes, most changed lines first
+///
+/// Lines are counted like a line diff: a modified line counts as one removed and one added
+/// line. Files without text content (binaries) count as files with no lines.
+pub fn language_stats(file_changes: &[FileChange]) -> Vec<LanguageStats> {
+    let mut stats: Vec<LanguageStats> = Vec::new();
+    for file_change in file_changes {
+        let language = detect_language(&file_change.path);
+        let (added, removed) = count_added_removed(file_change);
+        let entry = match stats.iter().position(|s| s.language == language) {
+            Some(i) => &mut stats[i],
+            None => {
+                stats.push(LanguageStats {
+                    language: language.to_string(),
+                    files: 0,
+                    lines_added: 0,
+                    lines_removed: 0,
+                });
+                stats.last_mut().unwrap()
+            }
+        };
+        entry.files += 1;
+        entry.lines_added += added;
+        entry.lines_removed += removed;
+    }
+    stats.sort_by(|a, b| {
+        b.lines_changed()
+            .cmp(&a.lines_changed())
+            .then_with(|| a.language.cmp(&b.language))
+    });
+    stats
+}
+
+fn count_added_removed(file_change: &FileChange) -> (usize, usize) {
+    let old = file_change.old_content.as_deref().unwrap_or("");
+    let new = file_change.content.as_deref().unwrap_or("");
+    match file_change.status {
+        ChangeType::Added => (new.lines().count(), 0),
+        ChangeType::Deleted => (0, old.lines().count()),
+        ChangeType::Modified => {
+            let diff = TextDiff::from_lines(old, new);
+            diff.iter_all_changes()
+                .fold((0, 0), |(added, removed), change| match change.tag() {
+                    ChangeTag::Insert => (added + 1, removed),
+                    ChangeTag::Delete => (added, removed + 1),
+                    ChangeTag::Equal => (added, removed),
+                })
+        }
+    }
+}
@@ -1,17 +1,158 @@
 // Git-related functionality
 mod git;
-pub use git::{ChangeType, FileChange, get_git_changed_files, read_test_targets_code};
+#[cfg(feature = "git")]
+pub use git::{
+    BlobStore, get_git_changed_files, get_git_changed_files_async, get_git_changed_files_lazy,
+    read_test_targets_code, read_test_targets_code_async,
+};
+pub use git::{ChangeType, FileChange, GitCredentials, LazyFileChange, diff_hunks};
+
+// Retrying clones and fetches on network failures
+mod clone_retry;
+pub use clone_retry::CloneRetryConfig;
+#[cfg(feature = "git")]
+pub use clone_retry::is_transient;
+
+// Version control backends
+mod vcs;
+#[cfg(feature = "git")]
+pub use vcs::GitVersionControl;
+pub use vcs::{VersionControl, read_test_targets_code_from};
+
+// In-memory repository input
+mod snapshot;
+pub use snapshot::{RepoSnapshot, diff_directories};
 
 // Type definitions
 mod types;
 pub use types::{
-    FileContent, FileIntentAnalysis, FunctionContent, IntentVerificationResult, TestTargets,
-    TestTargetsWithCode,
+    AnalysisOutcome, ChangeLocation, ChangeRelevance, FileAnalysisResult, FileContent,
+    FileIntentAnalysis, Finding, FunctionContent, IntentVerificationResult, PROMPT_VERSION,
+    PromptMessage, PromptPreview, PromptStage, ResultMetadata, SCHEMA_VERSION, Severity,
+    TestTargets, TestTargetsWithCode, Warning, WarningKind,
+};
+
+// Running the project's tests
+mod execution;
+#[cfg(feature = "exec")]
+pub use execution::run_tests;
+pub use execution::{
+    ExecutionConfig, TestOutcome, TestRunResult, default_container_image, detect_test_command,
+    merge_counterfactual_run, merge_test_run, parse_test_counts, parse_test_outcomes,
+};
+
+// Test coverage evidence
+mod coverage;
+pub use coverage::{CoverageEvidence, FunctionCoverage, coverage_evidence};
+
+// Static analyzers (clippy, eslint, ruff)
+mod analyzers;
+pub use analyzers::StaticAnalyzer;
+#[cfg(feature = "exec")]
+pub use analyzers::run_static_analyzers;
+
+// Secret and credential detection
+mod secrets;
+pub use secrets::{SECRET_RULE_PREFIX, apply_secret_findings, scan_for_secrets, shannon_entropy};
+
+// Redaction of content sent to the model
+mod redaction;
+pub use redaction::{Redaction, RedactionConfig, redact_text};
+
+// Local-only mode
+mod privacy;
+pub use privacy::LocalOnlyPolicy;
+
+// Confidence calibration from token log-probabilities
+mod calibration;
+pub use calibration::ConfidenceCalibration;
+
+// Follow-up questions about a result
+mod conversation;
+pub use conversation::{FollowUpAnswer, VerificationConversation};
+
+// Choosing the changed files worth analyzing
+mod prioritization;
+pub use prioritization::{SkipReason, SkippedFile, change_priority, prioritize_changes};
+
+// Provider refusals and content-filter blocks
+mod refusal;
+
+// Summary of what the changes add, change and remove
+mod change_summary;
+pub use change_summary::{ChangeSummary, Endpoint, endpoints, summarize_changes};
+
+// Metadata and policy for changed binary files
+mod binary;
+pub use binary::{
+    BinaryChange, BinaryMetadata, BinaryPolicy, UNEXPECTED_BINARY_RULE, binary_changes,
+    binary_metadata, binary_placeholder, is_binary_placeholder, mime_type,
+    unexpected_binary_findings,
 };
 
+// Source files exercised by the test targets
+mod test_mapping;
+pub use test_mapping::{MAX_TESTED_SOURCES, TestedSource, map_tests_to_sources};
+
+// Where the extracted targets were found
+mod target_resolution;
+pub use target_resolution::{TargetKind, TargetResolution, TargetStatus, target_resolution};
+
+// Intents too vague to verify
+mod ambiguity;
+pub use ambiguity::{DEFAULT_MAX_AMBIGUITY, IntentAmbiguity, intent_ambiguity};
+
+// Re-running uncertain verdicts with a stronger model
+mod stronger_model;
+pub use stronger_model::{
+    DEFAULT_RETRY_CONFIDENCE, Judgment, ModelRetry, StrongerModelConfig, retry_reasons,
+};
+
+// Languages of the changed files
+mod language_stats;
+pub use language_stats::{LanguageStats, detect_language, language_stats};
+
+// Citations backing the model's claims
+mod citations;
+pub use citations::Citation;
+
+// Public API surface and breaking changes
+mod api_surface;
+pub use api_surface::{
+    ApiItem, BreakingChange, SEMVER_RULE_PREFIX, apply_breaking_changes, breaking_change_findings,
+    claims_non_breaking, detect_breaking_changes, public_api,
+};
+
+// Hardcoded answers to the tests
+mod shortcuts;
+pub use shortcuts::{SHORTCUT_RULE, apply_shortcut_findings, detect_hardcoded_answers};
+
+// Similarity to prior art and earlier submissions
+mod similarity;
+pub use similarity::{
+    SIMILARITY_RULE, SimilarityConfig, apply_similarity_findings, containment, detect_near_copies,
+    shingles,
+};
+
+// Scope creep and unrelated changes
+mod scope;
+pub use scope::apply_scope;
+
+// Analysis options
+mod options;
+pub use options::AnalysisOptions;
+
+// Progress reporting and cancellation
+mod progress;
+pub use progress::{CancellationToken, Cancelled, Progress, ProgressHandler, ProgressStage};
+
 // Utility functions
 mod utils;
-pub use utils::extract_json_from_response;
+pub use utils::{estimate_tokens, extract_json_from_response, locate_snippet};
+
+// Test targets named literally in the intent
+mod target_heuristics;
+pub use target_heuristics::{HeuristicTargets, extract_test_targets_heuristically};
 
 // Code parsing utilities
 mod code_parser;
@@ -19,8 +160,263 @@ pub use code_parser::{extract_function_from_content_with_name, is_source_file_by
 
 // OpenAI-related functionality
 mod openai;
-pub use openai::{ask_openai_internal, extract_test_targets_with_ai, verify_intent};
+pub use openai::{
+    DEFAULT_MODEL, analyze_file, ask_openai_internal, extract_test_targets_with_ai,
+    verify_intent_from_changes, verify_intent_with_snapshots, verify_intent_with_vcs,
+};
+#[cfg(feature = "git")]
+pub use openai::{
+    analyze_commit, analyze_repository_changes_stream, verify_cross_repo_intent,
+    verify_function_intent, verify_intent, verify_intent_with_options, verify_intents_with_options,
+};
+
+// Checkpoints for resuming interrupted verifications
+mod checkpoint;
+
+// Shared LLM client
+mod llm_client;
+pub use llm_client::{FetchFuture, FetchRequest, FetchResponse, LlmClient};
+
+// Mock model provider for tests
+mod mock;
+pub use mock::{MockCall, MockProvider};
+
+// Solutions spanning several repositories
+mod cross_repo;
+pub use cross_repo::{CrossRepoResult, RepoChanges, RepoContribution};
+
+// Acceptance criteria checked one by one
+mod criteria;
+pub use criteria::{CriterionResult, changes_as_diff, merge_acceptance_criteria};
+
+// Documentation drift
+mod docs_drift;
+pub use docs_drift::{DOCS_DRIFT_RULE, DocsDriftConfig, detect_docs_drift};
+
+// Infrastructure and CI configuration review
+mod infra;
+pub use infra::{
+    InfraKind, StructuralChange, infra_kind, infra_review_instruction, structural_diff,
+};
+
+// SQL migration review
+mod migrations;
+pub use migrations::{
+    MIGRATION_RULE_PREFIX, SqlStatement, apply_migration_findings, is_migration_path, parse_sql,
+    scan_migrations,
+};
+
+// Evidence bundles for auditing verdicts
+mod evidence;
+pub use evidence::{
+    EVIDENCE_BUNDLE_VERSION, EvidenceBundle, EvidenceHashes, EvidenceRecorder, LlmExchange,
+};
+
+// Signed attestations of results
+mod attestation;
+pub use attestation::{
+    ATTESTATION_ALGORITHM, Attestation, canonical_result_hash, canonical_result_json,
+    load_signing_key, sign_result, signing_key_from_hex, verify_attestation,
+};
+pub use ed25519_dalek::SigningKey;
+
+// Regressions since an earlier verification
+mod regression;
+pub use regression::{Regression, apply_regression};
+
+// Escalation to a human reviewer
+mod escalation;
+pub use escalation::{
+    DEFAULT_ESCALATION_CONFIDENCE, Escalation, ReviewQuestion, apply_escalation, escalation,
+};
+
+// Verification profiles (security review of vulnerability fixes)
+mod profile;
+pub use profile::{
+    SECURITY_RULE_PREFIX, VerificationProfile, apply_security_profile, cwe_for_rule, tag_cwe,
+};
+
+// Intent archetypes (bug fix, feature, refactor, performance, security patch)
+mod archetype;
+pub use archetype::{
+    ARCHETYPE_RULE_PREFIX, EvidenceRequirement, IntentArchetype, apply_archetype, missing_evidence,
+};
+
+// Verifying several intents at once
+mod intents;
+pub use intents::{IntentVerdict, MultiIntentResult, split_acceptance_criteria};
+
+// Batch verification from a manifest
+mod batch;
+#[cfg(feature = "git")]
+pub use batch::run_batch;
+pub use batch::{BatchJob, BatchManifest, BatchOutcome, BatchSummary};
+
+// Prompt template versions and A/B comparison
+mod prompts;
+#[cfg(feature = "git")]
+pub use prompts::compare_prompt_versions;
+pub use prompts::{PromptComparison, PromptRegistry, PromptTemplates};
+
+// Incremental re-verification
+mod incremental;
+pub use incremental::{AnalysisCache, CachedAnalysis, blob_hash};
+
+// Evaluation against a labeled corpus
+mod eval;
+#[cfg(feature = "git")]
+pub use eval::run_eval;
+pub use eval::{EvalCase, EvalCaseOutcome, EvalCorpus, EvalReport};
+
+// Fine-tuning datasets from reviewed runs
+mod finetune;
+pub use finetune::{
+    FineTuneExample, FineTuneExport, FineTuneManifest, ReviewedRun, SkippedRun, export_fine_tuning,
+    fine_tuning_examples,
+};
+
+// Watching a local working tree
+#[cfg(feature = "git")]
+mod watch;
+#[cfg(feature = "git")]
+pub use watch::{WorkingTreeWatcher, working_tree_fingerprint};
+
+// Comparing verification runs
+mod result_diff;
+pub use result_diff::ResultDiff;
+
+// Baseline / suppression of accepted findings
+mod baseline;
+pub use baseline::{BASELINE_FILE_NAME, Baseline, BaselineEntry, finding_fingerprint};
+
+// Verdict policies
+mod policy;
+pub use policy::{PolicyDecision, VerdictPolicy};
+
+// Persistent results history
+#[cfg(feature = "store")]
+mod store;
+#[cfg(feature = "store")]
+pub use store::{PassRatePoint, ResultStore, StoredResult, VerificationKey};
+
+// OpenTelemetry export of the tracing spans
+#[cfg(feature = "otel")]
+mod telemetry;
+#[cfg(feature = "otel")]
+pub use telemetry::{OtelGuard, init_otel_tracing};
+
+// REST API server
+#[cfg(feature = "server")]
+mod server;
+#[cfg(feature = "server")]
+pub use server::{
+    DEFAULT_MAX_BODY_BYTES, DEFAULT_MAX_FINISHED_JOBS, DEFAULT_MAX_RUNNING_JOBS, JobRecord,
+    JobStatus, ServerConfig, serve,
+};
+
+// gRPC service
+#[cfg(feature = "grpc")]
+mod grpc;
+#[cfg(feature = "grpc")]
+pub use grpc::{
+    AnalyzeRepositoryRequest, FileAnalysis, GrpcService, IntentVerification,
+    IntentVerificationClient, IntentVerificationServer, RequestOptions, VerificationResult,
+    VerifyIntentRequest, serve_grpc,
+};
+
+// Webhook receivers for server mode
+#[cfg(feature = "server")]
+mod webhook;
+#[cfg(feature = "server")]
+pub use webhook::{
+    WebhookTrigger, github_trigger, gitlab_trigger, linked_issues, verify_github_signature,
+    verify_gitlab_token,
+};
+
+// Code owners of the changed files
+mod codeowners;
+pub use codeowners::{CODEOWNERS_PATHS, CodeOwners, apply_code_owners, files_by_owner};
+
+// Builder API for verifications
+mod verifier;
+pub use verifier::VerificationRequest;
+#[cfg(feature = "git")]
+pub use verifier::{IntentVerifier, IntentVerifierBuilder};
+
+// Pipeline observers
+mod observer;
+pub use observer::{AnalysisObserver, ObserverHandle};
+
+// Input validation
+mod validation;
+pub use validation::{
+    ValidationError, validate_api_key, validate_commit, validate_intent, validate_repo_url,
+};
+
+// Concurrent verification scheduling
+mod scheduler;
+pub use scheduler::RateLimiter;
+#[cfg(feature = "git")]
+pub use scheduler::{
+    ScheduledJob, ScheduledJobStatus, SchedulerConfig, VerificationJob, VerificationScheduler,
+};
+
+// Risk scoring
+mod risk;
+pub use risk::{apply_risk_scores, file_criticality, file_risk_score};
+
+// Report rendering
+mod report;
+pub use report::{
+    GithubAnnotation, render_github_annotations, render_html, render_junit, render_markdown,
+    render_sarif,
+};
+
+// GitHub Actions integration
+mod github;
+#[cfg(feature = "git")]
+pub use github::verify_intent_from_issue;
+pub use github::{
+    GithubIssue, PullRequestContext, STICKY_COMMENT_MARKER, fetch_issue, parse_issue_reference,
+    post_sticky_comment, sticky_comment_body,
+};
+
+// Verdict notifications (Slack, Discord, webhooks)
+mod notify;
+pub use notify::{
+    NotifyChannel, NotifyConfig, NotifyKind, notification_payload, send_notifications,
+    verdict_summary,
+};
 
 // FFI-related functionality
+#[cfg(feature = "ffi")]
 mod ffi;
-pub use ffi::{ask_openai, free_str, verify_intent_c};
+#[cfg(feature = "ffi")]
+pub use ffi::{
+    CChangeType, CFileIntentAnalysis, CIntentVerificationResult, IvErrorCode, ask_openai,
+    extract_test_targets_with_ai_c, free_str, get_git_changed_files_c, iv_clear_error,
+    iv_last_error_code, iv_last_error_message, iv_result_file_at, iv_result_file_count,
+    iv_result_free, iv_result_from_json, read_test_targets_code_c, verify_intent_c,
+    verify_intent_typed_c,
+};
+#[cfg(feature = "ffi")]
+mod ffi_async;
+#[cfg(feature = "ffi")]
+pub use ffi_async::{
+    IvCompletionCallback, IvJob, IvJobStatus, iv_cancel, iv_job_error_code, iv_job_error_message,
+    iv_job_free, iv_job_result, iv_poll, iv_wait, verify_intent_async_c,
+};
+#[cfg(feature = "ffi")]
+mod ffi_config;
+#[cfg(feature = "ffi")]
+pub use ffi_config::{
+    IvConfig, IvProgress, IvProgressCallback, IvProgressStage, iv_config_free, iv_config_new,
+    iv_config_set_base_url, iv_config_set_cache_dir, iv_config_set_concurrency,
+    iv_config_set_language, iv_config_set_local_only, iv_config_set_model,
+    iv_config_set_progress_callback, iv_config_set_proxy, iv_config_set_timeout_secs,
+    verify_intent_with_config_async_c, verify_intent_with_config_c,
+};
+#[cfg(feature = "ffi")]
+mod ffi_execute;
+#[cfg(feature = "ffi")]
+pub use ffi_execute::iv_execute;
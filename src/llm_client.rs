@@ -0,0 +1,224 @@
+use std::future::Future;
+use std::pin::Pin;
+use std::sync::Arc;
+
+use async_openai::error::{ApiError, OpenAIError};
+use async_openai::types::chat::{CreateChatCompletionRequest, CreateChatCompletionResponse};
+use async_openai::{Client, config::OpenAIConfig};
+
+use crate::mock::MockProvider;
+use crate::options::AnalysisOptions;
+
+/// Client for an OpenAI-compatible API, shared by every request of a verification so they
+/// reuse one connection pool
+///
+/// Clones share the same pool. Set it on [`AnalysisOptions::llm_client`] to share it across
+/// verifications too; requests for another API key or base URL build their own client.
+#[derive(Clone)]
+pub struct LlmClient {
+    backend: Backend,
+    api_key: String,
+    base_url: Option<String>,
+}
+
+#[derive(Clone)]
+enum Backend {
+    Http(Box<Client<OpenAIConfig>>),
+    Fetch(Arc<HostFetch>),
+    Mock(MockProvider),
+}
+
+/// HTTP request a host sends on the client's behalf, see [`LlmClient::with_fetch`]
+#[derive(Debug, Clone, PartialEq)]
+pub struct FetchRequest {
+    pub method: String,
+    pub url: String,
+    pub headers: Vec<(String, String)>,
+    pub body: String,
+}
+
+/// The host's answer to a [`FetchRequest`]
+#[derive(Debug, Clone, PartialEq)]
+pub struct FetchResponse {
+    pub status: u16,
+    pub body: String,
+}
+
+/// Pending answer of the host
+#[cfg(not(target_arch = "wasm32"))]
+pub type FetchFuture = Pin<Box<dyn Future<Output = Result<FetchResponse, String>> + Send>>;
+
+/// Pending answer of the host; not `Send`, like the promises of a JavaScript host
+#[cfg(target_arch = "wasm32")]
+pub type FetchFuture = Pin<Box<dyn Future<Output = Result<FetchResponse, String>>>>;
+
+type HostFetch = dyn Fn(FetchRequest) -> FetchFuture + Send + Sync;
+
+const DEFAULT_BASE_URL: &str = "https://api.openai.com/v1";
+
+impl LlmClient {
+    /// Client honoring the timeout and proxy in `options`
+    ///
+    /// On wasm32, requests go through the browser's `fetch`, which sets neither.
+    #[cfg_attr(target_arch = "wasm32", allow(unused_mut, unused_variables))]
+    pub fn new(
+        api_key: &str,
+        base_url: Option<&str>,
+        options: &AnalysisOptions,
+    ) -> Result<Self, Box<dyn std::error::Error>> {
+        let mut http_client = reqwest::Client::builder();
+        #[cfg(not(target_arch = "wasm32"))]
+        {
+            if let Some(timeout_secs) = options.timeout_secs {
+                http_client = http_client.timeout(std::time::Duration::from_secs(timeout_secs));
+            }
+            if let Some(proxy) = &options.proxy {
+                http_client = http_client.proxy(reqwest::Proxy::all(proxy)?);
+            }
+        }
+        Ok(Self::with_http_client(
+            api_key,
+            base_url,
+            http_client.build()?,
+        ))
+    }
+
+    /// Client sending its requests through `http_client`, e.g. one with custom TLS or headers
+    pub fn with_http_client(
+        api_key: &str,
+        base_url: Option<&str>,
+        http_client: reqwest::Client,
+    ) -> Self {
+        let mut config = OpenAIConfig::new().with_api_key(api_key);
+        if let Some(url) = base_url {
+            config = config.with_api_base(url);
+        }
+        LlmClient {
+            backend: Backend::Http(Box::new(
+                Client::with_config(config).with_http_client(http_client),
+            )),
+            api_key: api_key.to_string(),
+            base_url: base_url.map(str::to_string),
+        }
+    }
+
+    /// Client sending its requests through `fetch`, a transport provided by the host, e.g.
+    /// the `fetch` of a browser extension or Cloudflare Worker
+    ///
+    /// Requests are `POST {base_url}/chat/completions` with a JSON body; the timeout and proxy
+    /// of [`AnalysisOptions`] are left to the host.
+    pub fn with_fetch(
+        api_key: &str,
+        base_url: Option<&str>,
+        fetch: impl Fn(FetchRequest) -> FetchFuture + Send + Sync + 'static,
+    ) -> Self {
+        LlmClient {
+            backend: Backend::Fetch(Arc::new(fetch)),
+            api_key: api_key.to_string(),
+            base_url: base_url.map(str::to_string),
+        }
+    }
+
+    /// Client answering every request from `provider`, whatever the API key and base URL
+    pub fn mock(provider: MockProvider) -> Self {
+        LlmClient {
+            backend: Backend::Mock(provider),
+            api_key: String::new(),
+            base_url: None,
+        }
+    }
+
+    /// Whether the client talks to `base_url` with `api_key`; mock clients serve any
+    pub fn serves(&self, api_key: &str, base_url: Option<&str>) -> bool {
+        self.is_mock() || (self.api_key == api_key && self.base_url.as_deref() == base_url)
+    }
+
+    /// Base URL requests go to; `None` for the default OpenAI endpoint
+    pub(crate) fn base_url(&self) -> Option<&str> {
+        self.base_url.as_deref()
+    }
+
+    pub(crate) fn is_mock(&self) -> bool {
+        matches!(self.backend, Backend::Mock(_))
+    }
+
+    pub(crate) async fn create(
+        &self,
+        request: CreateChatCompletionRequest,
+    ) -> Result<CreateChatCompletionResponse, OpenAIError> {
+        match &self.backend {
+            Backend::Http(client) => client.chat().create(request).await,
+            Backend::Fetch(fetch) => self.fetch_completion(fetch.as_ref(), &request).await,
+            Backend::Mock(provider) => provider.create(&request).map_err(OpenAIError::ApiError),
+        }
+    }
+}
+
+impl LlmClient {
+    async fn fetch_completion(
+        &self,
+        fetch: &HostFetch,
+        request: &CreateChatCompletionRequest,
+    ) -> Result<CreateChatCompletionResponse, OpenAIError> {
+        let base_url = self.base_url.as_deref().unwrap_or(DEFAULT_BASE_URL);
+        let body = serde_json::to_string(request)
+            .map_err(|e| OpenAIError::InvalidArgument(e.to_string()))?;
+        let response = fetch(FetchRequest {
+            method: "POST".to_string(),
+            url: format!("{}/chat/completions", base_url.trim_end_matches('/')),
+            headers: vec![
+                ("content-type".to_string(), "application/json".to_string()),
+                (
+                    "authorization".to_string(),
+                    format!("Bearer {}", self.api_key),
+                ),
+            ],
+            body,
+        })
+        .await
+        .map_err(|message| api_error(format!("Host fetch failed: {}", message)))?;
+
+        if !(200..300).contains(&response.status) {
+            #[derive(serde::Deserialize)]
+            struct ErrorBody {
+                error: ApiError,
+            }
+            return Err(match serde_json::from_str::<ErrorBody>(&response.body) {
+                Ok(body) => OpenAIError::ApiError(body.error),
+                Err(_) => api_error(format!("HTTP {}: {}", response.status, response.body)),
+            });
+        }
+        serde_json::from_str(&response.body)
+            .map_err(|e| OpenAIError::JSONDeserialize(e, response.body))
+    }
+}
+
+fn api_error(message: String) -> OpenAIError {
+    OpenAIError::ApiError(ApiError {
+        message,
+        r#type: None,
+        param: None,
+        code: None,
+    })
+}
+
+impl std::fmt::Debug for LlmClient {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("LlmClient")
+            .field("base_url", &self.base_url)
+            .field("mock", &self.is_mock())
+            .finish_non_exhaustive()
+    }
+}
+
+/// The shared client in `options` if it serves `api_key` and `base_url`, a new one otherwise
+pub(crate) fn llm_client_for(
+    api_key: &str,
+    base_url: Option<&str>,
+    options: &AnalysisOptions,
+) -> Result<LlmClient, Box<dyn std::error::Error>> {
+    match &options.llm_client {
+        Some(client) if client.serves(api_key, base_url) => Ok(client.clone()),
+        _ => LlmClient::new(api_key, base_url, options),
+    }
+}
@@ -1,10 +1,1593 @@
-use std::env;
-
-use async_openai::config::OpenAIConfig;
-use colored::Colorize;
+use clap::{Args, Parser, Subcommand, ValueEnum};
 use dotenvy::dotenv;
+use intent_verification::{
+    AnalysisCache, AnalysisOptions, Baseline, BatchManifest, BinaryPolicy, CloneRetryConfig,
+    DocsDriftConfig, EvalCorpus, EvidenceRecorder, ExecutionConfig, FineTuneManifest,
+    GitCredentials, IntentArchetype, IntentVerificationResult, LocalOnlyPolicy, NotifyConfig,
+    PromptTemplates, PullRequestContext, RepoChanges, RepoSnapshot, Severity, SimilarityConfig,
+    StaticAnalyzer, StrongerModelConfig, ValidationError, VerdictPolicy, VerificationConversation,
+    VerificationProfile, WorkingTreeWatcher, analyze_commit, analyze_file, compare_prompt_versions,
+    export_fine_tuning, extract_test_targets_with_ai, fetch_issue, load_signing_key,
+    parse_issue_reference, post_sticky_comment, read_test_targets_code, render_junit,
+    render_markdown, render_sarif, run_batch, run_eval, send_notifications, sign_result,
+    verify_attestation, verify_cross_repo_intent, verify_intent_with_options,
+    verify_intent_with_snapshots,
+};
+use std::io::Read;
+use std::process::ExitCode;
+use std::time::Duration;
+
+/// Exit code when the verdict fails the policy
+const EXIT_POLICY_FAILED: u8 = 1;
+/// Exit code when verification could not run
+const EXIT_ERROR: u8 = 2;
+
+/// Verify that code changes fulfill the intent of a set of tests
+///
+/// Exits with 0 when the verdict passes the policy, 1 when it fails and 2 on errors.
+#[derive(Debug, Parser)]
+#[command(name = "intent-verify", version)]
+struct Cli {
+    #[command(subcommand)]
+    command: Command,
+}
+
+#[derive(Debug, Subcommand)]
+enum Command {
+    /// Verify changes in a repository whose tests live alongside the code
+    Analyze {
+        /// Repository URL or local path
+        #[arg(long, required_unless_present = "github")]
+        repo: Option<String>,
+        /// Commit before the changes (the parent of `--head` when omitted)
+        #[arg(long)]
+        base: Option<String>,
+        /// Commit with the changes; tests are also read from here
+        #[arg(long, required_unless_present = "github")]
+        head: Option<String>,
+        /// What the tests are expected to prove (defaults to the PR title and description
+        /// in `--github` mode)
+        #[arg(long, required_unless_present_any = ["github", "issue"])]
+        intent: Option<String>,
+        /// Take the intent from a GitHub issue, e.g. `acme/calc#42`
+        #[arg(long, value_parser = parse_issue_reference, conflicts_with_all = ["intent", "github"])]
+        issue: Option<(String, u64)>,
+        /// Verify the pull request from `GITHUB_EVENT_PATH` and post the report as a PR comment
+        #[arg(long, conflicts_with_all = ["repo", "base", "head"])]
+        github: bool,
+        /// Token used to clone the repository and comment on the pull request
+        #[arg(long, env = "GITHUB_TOKEN", hide_env_values = true)]
+        github_token: Option<String>,
+        /// Review the extracted test targets before verifying
+        #[arg(long, conflicts_with = "github")]
+        interactive: bool,
+        #[command(flatten)]
+        llm: LlmArgs,
+        #[command(flatten)]
+        output: OutputArgs,
+        #[command(flatten)]
+        policy: PolicyArgs,
+    },
+    /// Verify changes in one repository against tests from another
+    Verify {
+        /// Repository URL or local path containing the tests
+        #[arg(long)]
+        test_repo: String,
+        /// Commit to read the tests from
+        #[arg(long)]
+        test_commit: String,
+        /// Repository URL or local path containing the changes
+        #[arg(long)]
+        repo: String,
+        /// Commit before the changes
+        #[arg(long)]
+        base: String,
+        /// Commit with the changes
+        #[arg(long)]
+        head: String,
+        /// What the tests are expected to prove
+        #[arg(long, required_unless_present = "issue")]
+        intent: Option<String>,
+        /// Take the intent from a GitHub issue, e.g. `acme/calc#42`
+        #[arg(long, value_parser = parse_issue_reference, conflicts_with = "intent")]
+        issue: Option<(String, u64)>,
+        /// Token used to read issues of private repositories
+        #[arg(long, env = "GITHUB_TOKEN", hide_env_values = true)]
+        github_token: Option<String>,
+        /// Review the extracted test targets before verifying
+        #[arg(long)]
+        interactive: bool,
+        #[command(flatten)]
+        llm: LlmArgs,
+        #[command(flatten)]
+        output: OutputArgs,
+        #[command(flatten)]
+        policy: PolicyArgs,
+    },
+    /// Verify an intent whose changes span several repositories
+    VerifyCrossRepo {
+        /// Repository URL or local path containing the tests
+        #[arg(long)]
+        test_repo: String,
+        /// Commit to read the tests from
+        #[arg(long)]
+        test_commit: String,
+        /// Changes of one repository as `[NAME=]URL@BASE..HEAD`; repeat for each repository
+        #[arg(long = "changes", required = true, value_parser = parse_repo_changes)]
+        changes: Vec<RepoChanges>,
+        /// What the tests are expected to prove
+        #[arg(long)]
+        intent: String,
+        #[command(flatten)]
+        llm: LlmArgs,
+        #[command(flatten)]
+        output: OutputArgs,
+        #[command(flatten)]
+        policy: PolicyArgs,
+    },
+    /// Verify a unified diff read from stdin, e.g. `git diff | intent-verify analyze-diff`
+    AnalyzeDiff {
+        /// What the tests are expected to prove
+        #[arg(long)]
+        intent: String,
+        /// Local repository whose working tree the tests are read from (the diff otherwise)
+        #[arg(long)]
+        tests: Option<String>,
+        #[command(flatten)]
+        llm: LlmArgs,
+        #[command(flatten)]
+        output: OutputArgs,
+        #[command(flatten)]
+        policy: PolicyArgs,
+    },
+    /// Verify the differences between two folders, e.g. exported archives or build artifacts
+    /// without any version control history
+    ///
+    /// Any of the folders can be a `.zip`, `.tar.gz` or `.tar` archive of the repository
+    /// instead, as downloaded from a code host, to avoid cloning it.
+    AnalyzeDirs {
+        /// Folder or archive before the change
+        before: String,
+        /// Folder or archive after the change
+        after: String,
+        /// What the tests are expected to prove
+        #[arg(long)]
+        intent: String,
+        /// Folder or archive the tests are read from (the after one otherwise)
+        #[arg(long)]
+        tests: Option<String>,
+        #[command(flatten)]
+        llm: LlmArgs,
+        #[command(flatten)]
+        output: OutputArgs,
+        #[command(flatten)]
+        policy: PolicyArgs,
+    },
+    /// Analyze a single file against the intent, outside of any diff
+    ///
+    /// Exits with 1 when the file doesn't support the intent.
+    AnalyzeFile {
+        /// File to analyze, or `-` to read its contents from stdin
+        file: String,
+        /// Language of the file (rust, python, typescript or javascript), needed for stdin
+        /// and files without a recognized extension
+        #[arg(long)]
+        language: Option<String>,
+        /// What the tests are expected to prove
+        #[arg(long)]
+        intent: String,
+        /// Write the JSON analysis to a file instead of stdout
+        #[arg(long, short)]
+        output: Option<String>,
+        #[command(flatten)]
+        llm: LlmArgs,
+    },
+    /// Run a REST API that verifies changes in the background
+    #[cfg(feature = "server")]
+    Serve {
+        /// Address to listen on
+        #[arg(long, default_value = "127.0.0.1:8080")]
+        listen: String,
+        /// Bearer token clients must send to start and read jobs; anyone who can reach the
+        /// server may use it without one
+        #[arg(long, env = "INTENT_AUTH_TOKEN", hide_env_values = true)]
+        auth_token: Option<String>,
+        /// Secret GitHub/GitLab webhook deliveries must be signed with; webhooks are disabled
+        /// without it
+        #[arg(long, env = "WEBHOOK_SECRET", hide_env_values = true)]
+        webhook_secret: Option<String>,
+        /// Token for reading issues linked from pull requests in private repositories
+        #[arg(long, env = "GITHUB_TOKEN", hide_env_values = true)]
+        github_token: Option<String>,
+        /// URL every finished job is POSTed to; not allowed with `--local-only`
+        #[arg(long)]
+        callback_url: Option<String>,
+        /// Only clone repositories from this host (repeatable); any remote host when omitted
+        #[arg(long = "allowed-repo-host")]
+        allowed_repo_hosts: Vec<String>,
+        /// Largest request body accepted, in bytes
+        #[arg(long, default_value_t = intent_verification::DEFAULT_MAX_BODY_BYTES)]
+        max_body_bytes: usize,
+        /// Finished jobs kept in memory before the oldest are dropped
+        #[arg(long, default_value_t = intent_verification::DEFAULT_MAX_FINISHED_JOBS)]
+        max_finished_jobs: usize,
+        /// Verifications run at once; more are refused until one finishes
+        #[arg(long, default_value_t = intent_verification::DEFAULT_MAX_RUNNING_JOBS)]
+        max_running_jobs: usize,
+        /// Also serve the gRPC API on this address
+        #[cfg(feature = "grpc")]
+        #[arg(long)]
+        grpc_listen: Option<String>,
+        #[command(flatten)]
+        llm: LlmArgs,
+    },
+    /// Run every verification listed in a JSON or TOML manifest
+    Batch {
+        /// Manifest file (`.toml` for TOML, JSON otherwise)
+        #[arg(long)]
+        manifest: String,
+        /// Write the JSON summary to a file instead of stdout
+        #[arg(long, short)]
+        output: Option<String>,
+        #[command(flatten)]
+        llm: LlmArgs,
+        #[command(flatten)]
+        policy: PolicyArgs,
+    },
+    /// Verify the same changes under two prompt versions and diff the outcomes
+    ComparePrompts {
+        /// Repository URL or local path
+        #[arg(long)]
+        repo: String,
+        /// Commit before the changes
+        #[arg(long)]
+        base: String,
+        /// Commit with the changes; tests are also read from here
+        #[arg(long)]
+        head: String,
+        /// What the tests are expected to prove
+        #[arg(long)]
+        intent: String,
+        /// Templates of version A (the current version when omitted)
+        #[arg(long)]
+        prompts_a: Option<String>,
+        /// Templates of version B
+        #[arg(long)]
+        prompts_b: String,
+        /// Write the JSON comparison to a file instead of stdout
+        #[arg(long, short)]
+        output: Option<String>,
+        #[command(flatten)]
+        llm: LlmArgs,
+    },
+    /// Replay a labeled corpus of cases and report accuracy, precision and recall
+    Eval {
+        /// Corpus file (`.toml` for TOML, JSON otherwise)
+        #[arg(long)]
+        corpus: String,
+        /// Write the JSON report to a file instead of stdout
+        #[arg(long, short)]
+        output: Option<String>,
+        /// Exit with 1 when the accuracy is below this value (0.0-1.0)
+        #[arg(long)]
+        min_accuracy: Option<f32>,
+        #[command(flatten)]
+        llm: LlmArgs,
+    },
+    /// Export reviewed runs' model exchanges as JSONL for fine-tuning
+    ExportFinetune {
+        /// Manifest listing evidence bundles and their approved verdicts (`.toml` for TOML,
+        /// JSON otherwise)
+        #[arg(long)]
+        manifest: String,
+        /// Write the JSONL to a file instead of stdout
+        #[arg(long, short)]
+        output: Option<String>,
+    },
+    /// Re-verify the uncommitted changes of a local repository whenever its files change
+    Watch {
+        /// Local repository to watch
+        #[arg(long, default_value = ".")]
+        path: String,
+        /// What the tests are expected to prove
+        #[arg(long)]
+        intent: String,
+        /// Time the working tree must stay unchanged before re-running, in milliseconds
+        #[arg(long, default_value_t = 1000)]
+        debounce_ms: u64,
+        #[command(flatten)]
+        llm: LlmArgs,
+    },
+    /// Ask follow-up questions about a JSON report, each with the earlier answers as context
+    Ask {
+        /// JSON report to ask about
+        #[arg(long)]
+        result: String,
+        /// The intent the report verified
+        #[arg(long)]
+        intent: String,
+        /// Questions, asked in order
+        #[arg(required = true)]
+        questions: Vec<String>,
+        #[command(flatten)]
+        llm: LlmArgs,
+    },
+    /// Check the attestation of a signed JSON report
+    VerifyAttestation {
+        /// JSON report written with `--sign-key`
+        #[arg(long)]
+        result: String,
+        /// Hex public key the attestation must come from
+        #[arg(long)]
+        public_key: Option<String>,
+    },
+    /// Print the functions and files an intent refers to
+    ExtractTargets {
+        /// What the tests are expected to prove
+        #[arg(long)]
+        intent: String,
+        #[command(flatten)]
+        llm: LlmArgs,
+    },
+}
+
+/// LLM and analysis settings shared by the subcommands
+#[derive(Debug, Args)]
+struct LlmArgs {
+    /// OpenAI API key
+    #[arg(long, env = "OPENAI_API_KEY", hide_env_values = true)]
+    api_key: String,
+    /// Model to use
+    #[arg(long, env = "OPENAI_MODEL")]
+    model: Option<String>,
+    /// API base URL for OpenAI-compatible endpoints
+    #[arg(long, env = "OPENAI_BASE_URL")]
+    base_url: Option<String>,
+    /// Number of files analyzed at the same time
+    #[arg(long, default_value_t = 1)]
+    concurrency: usize,
+    /// Timeout for each LLM request, in seconds
+    #[arg(long)]
+    timeout: Option<u64>,
+    /// Language for reasoning and assessments (e.g. `vi`)
+    #[arg(long)]
+    language: Option<String>,
+    /// Proxy URL for LLM requests and git clones
+    #[arg(long)]
+    proxy: Option<String>,
+    /// Directory repositories are cloned into
+    #[arg(long)]
+    cache_dir: Option<String>,
+    /// Attempts at cloning or fetching a repository when the network fails, the first
+    /// included
+    #[arg(long)]
+    clone_attempts: Option<u32>,
+    /// Baseline file of accepted findings
+    #[arg(long)]
+    baseline: Option<String>,
+    /// Build the prompts and print them with token estimates instead of calling the model
+    #[arg(long)]
+    dry_run: bool,
+    /// Run the project's tests on the solution and fold the outcome into the verdict
+    #[arg(long)]
+    run_tests: bool,
+    /// Test command to run (detected from Cargo.toml, pyproject.toml or package.json otherwise)
+    #[arg(long, requires = "run_tests")]
+    test_command: Option<String>,
+    /// Seconds before the test run is killed
+    #[arg(long, requires = "run_tests")]
+    test_timeout: Option<u64>,
+    /// Docker image to run the tests in, without network access, so it must hold the test runner
+    /// and the project's dependencies (by default the official image of the test command's
+    /// toolchain, which only suits projects without dependencies)
+    #[arg(long, requires = "run_tests")]
+    sandbox_image: Option<String>,
+    /// Run the tests as a local process instead of in a container when no sandbox image is
+    /// given; the solution's code then runs on this machine
+    #[arg(long, requires = "run_tests", conflicts_with = "sandbox_image")]
+    allow_unsandboxed_tests: bool,
+    /// Also run the tests with the solution reverted; they must fail there
+    #[arg(long, requires = "run_tests")]
+    counterfactual: bool,
+    /// Linter to run on the changed files when its toolchain is installed (clippy, eslint or
+    /// ruff); repeatable
+    #[arg(long = "analyzer")]
+    analyzers: Vec<StaticAnalyzer>,
+    /// Break the intent into acceptance criteria and check each one against the diff
+    #[arg(long)]
+    acceptance_criteria: bool,
+    /// Verification profile: standard, or security for vulnerability fixes
+    #[arg(long, default_value = "standard")]
+    profile: VerificationProfile,
+    /// Kind of change the intent asks for (bug-fix, feature, refactor, performance or
+    /// security-patch), selecting tailored prompts, required evidence and the default policy
+    #[arg(long)]
+    archetype: Option<IntentArchetype>,
+    /// JSON result of an earlier verification of the same intent; its acceptance criteria are
+    /// checked again and regressions since then are reported
+    #[arg(long)]
+    previous: Option<String>,
+    /// Report README and docs sections that still describe changed public items
+    #[arg(long)]
+    docs_drift: bool,
+    /// Documentation file or directory checked for drift (README.md and docs/ by default);
+    /// repeatable
+    #[arg(long = "doc-path", requires = "docs_drift")]
+    doc_paths: Vec<String>,
+    /// Confidence (0.0-1.0) below which the report lists questions for a human reviewer
+    #[arg(long)]
+    escalation_threshold: Option<f32>,
+    /// TOML or JSON file of prompt templates to use instead of the current version
+    #[arg(long)]
+    prompts: Option<String>,
+    /// JSON file of per-file analyses kept between runs; files whose content didn't change
+    /// since an earlier run with the same intent aren't sent to the model again
+    #[arg(long)]
+    analysis_cache: Option<String>,
+    /// File results are saved to while verifying; rerun with the same file to resume an
+    /// interrupted verification
+    #[arg(long)]
+    checkpoint: Option<String>,
+    /// Repository (`URL[@COMMIT]`) the added code is compared against to flag near-copies,
+    /// e.g. prior art or earlier submissions; repeatable
+    #[arg(long = "reference")]
+    references: Vec<String>,
+    /// Share (0.0-1.0) of a file's added code matching a reference file above which it is
+    /// reported
+    #[arg(long, requires = "references")]
+    similarity_threshold: Option<f32>,
+    /// Attach the owners from the solution's CODEOWNERS file to each file and group the
+    /// report by owner
+    #[arg(long)]
+    code_owners: bool,
+    /// Refuse the verification any network access except LLM requests to the allowed
+    /// endpoints; repositories must be local paths
+    #[arg(long)]
+    local_only: bool,
+    /// LLM base URL allowed in local-only mode, e.g. an on-prem model; repeatable
+    #[arg(long = "allowed-endpoint", requires = "local_only")]
+    allowed_endpoints: Vec<String>,
+    /// Derive the confidence from the token log-probabilities of the file analyses, when the
+    /// provider returns them
+    #[arg(long)]
+    calibrate_confidence: bool,
+    /// Analyze at most this many changed files, the most relevant to the intent first
+    #[arg(long)]
+    max_files: Option<usize>,
+    /// Skip changed files larger than this many bytes
+    #[arg(long)]
+    max_file_bytes: Option<usize>,
+    /// Analyze at most this many changed files, in diff order
+    #[arg(long)]
+    max_total_files: Option<usize>,
+    /// Skip changed files once their estimated tokens would exceed this budget
+    #[arg(long)]
+    max_total_tokens: Option<usize>,
+    /// Summarize the added, changed and removed functions and routes before the intent is
+    /// judged
+    #[arg(long)]
+    change_summary: bool,
+    /// Report binary files added by the solution, e.g. an executable in a source-only
+    /// repository
+    #[arg(long)]
+    flag_binaries: bool,
+    /// Glob of binary files the solution may add without a finding; repeatable
+    #[arg(long = "allowed-binary", requires = "flag_binaries")]
+    allowed_binaries: Vec<String>,
+    /// Model the final judgment is re-run with when the verdict is uncertain
+    #[arg(long)]
+    stronger_model: Option<String>,
+    /// Confidence (0.0-1.0) below which the stronger model re-judges the verdict
+    #[arg(long, requires = "stronger_model")]
+    retry_confidence: Option<f32>,
+    /// Ask clarifying questions instead of verifying intents more ambiguous than this
+    /// (0.0-1.0)
+    #[arg(long, num_args = 0..=1, default_missing_value = "0.6")]
+    max_ambiguity: Option<f32>,
+}
+
+impl LlmArgs {
+    fn options(&self) -> Result<AnalysisOptions, Box<dyn std::error::Error>> {
+        Ok(AnalysisOptions {
+            language: self.language.clone(),
+            baseline: self.baseline.as_ref().map(Baseline::load).transpose()?,
+            timeout_secs: self.timeout,
+            concurrency: Some(self.concurrency),
+            cache_dir: self.cache_dir.clone(),
+            proxy: self.proxy.clone(),
+            clone_retry: self.clone_attempts.map(|max_attempts| CloneRetryConfig {
+                max_attempts,
+                ..Default::default()
+            }),
+            dry_run: self.dry_run,
+            execution: self.run_tests.then(|| ExecutionConfig {
+                command: self
+                    .test_command
+                    .as_ref()
+                    .map(|command| command.split_whitespace().map(str::to_string).collect()),
+                timeout_secs: self.test_timeout,
+                container_image: self.sandbox_image.clone(),
+                counterfactual: self.counterfactual,
+                allow_unsandboxed: self.allow_unsandboxed_tests,
+                ..Default::default()
+            }),
+            static_analyzers: self.analyzers.clone(),
+            acceptance_criteria: self.acceptance_criteria,
+            profile: self.profile,
+            archetype: self.archetype,
+            code_owners: self.code_owners,
+            local_only: self
+                .local_only
+                .then(|| LocalOnlyPolicy::new(self.allowed_endpoints.clone())),
+            calibrate_confidence: self.calibrate_confidence,
+            max_files_analyzed: self.max_files,
+            max_file_bytes: self.max_file_bytes,
+            max_total_files: self.max_total_files,
+            max_total_tokens: self.max_total_tokens,
+            change_summary: self.change_summary,
+            max_intent_ambiguity: self.max_ambiguity,
+            stronger_model: self.stronger_model.as_ref().map(|model| {
+                let mut config = StrongerModelConfig {
+                    model: model.clone(),
+                    ..Default::default()
+                };
+                if let Some(threshold) = self.retry_confidence {
+                    config.min_confidence = threshold;
+                }
+                config
+            }),
+            binary_policy: self.flag_binaries.then(|| BinaryPolicy {
+                allowed: self.allowed_binaries.clone(),
+                ..Default::default()
+            }),
+            previous_result: self
+                .previous
+                .as_ref()
+                .map(load_result)
+                .transpose()?
+                .map(Box::new),
+            docs_drift: self.docs_drift.then(|| {
+                let mut config = DocsDriftConfig::default();
+                if !self.doc_paths.is_empty() {
+                    config.doc_paths = self.doc_paths.clone();
+                }
+                config
+            }),
+            escalation_threshold: self.escalation_threshold,
+            prompts: self
+                .prompts
+                .as_ref()
+                .map(PromptTemplates::load)
+                .transpose()?,
+            analysis_cache: self
+                .analysis_cache
+                .as_ref()
+                .map(AnalysisCache::open)
+                .transpose()?,
+            checkpoint: self.checkpoint.clone(),
+            similarity: (!self.references.is_empty()).then(|| {
+                let mut config = SimilarityConfig {
+                    references: self.references.clone(),
+                    ..Default::default()
+                };
+                if let Some(threshold) = self.similarity_threshold {
+                    config.threshold = threshold;
+                }
+                config
+            }),
+            ..Default::default()
+        })
+    }
+}
+
+#[derive(Debug, Args)]
+struct OutputArgs {
+    /// Output format
+    #[arg(long, value_enum, default_value_t = Format::Json)]
+    format: Format,
+    /// Write the report to a file instead of stdout
+    #[arg(long, short)]
+    output: Option<String>,
+    /// TOML file listing Slack, Discord or webhook channels to post the verdict to
+    #[arg(long)]
+    notify: Option<String>,
+    /// Link to the full report included in notifications (overrides `report_url` in the file)
+    #[arg(long, requires = "notify")]
+    report_url: Option<String>,
+    /// Also write an evidence bundle (diff, targets, model exchanges and result, with hashes)
+    /// to this JSON file
+    #[arg(long)]
+    evidence: Option<String>,
+    /// File holding a hex ed25519 secret seed; the result's canonical hash is signed with it
+    /// and the attestation added to the report
+    #[arg(long, env = "INTENT_SIGNING_KEY_FILE")]
+    sign_key: Option<String>,
+}
+
+impl OutputArgs {
+    /// `options` recording evidence when `--evidence` is given
+    fn with_evidence(&self, mut options: AnalysisOptions) -> AnalysisOptions {
+        if self.evidence.is_some() {
+            options.evidence = Some(EvidenceRecorder::new());
+        }
+        options
+    }
+
+    /// Attach an attestation to `result` when `--sign-key` is given
+    fn sign(
+        &self,
+        result: &mut IntentVerificationResult,
+    ) -> Result<(), Box<dyn std::error::Error>> {
+        if let Some(path) = &self.sign_key {
+            let attestation = sign_result(result, &load_signing_key(path)?);
+            eprintln!("🔏 Signed result hash {}", attestation.result_hash);
+            result.attestation = Some(attestation);
+        }
+        Ok(())
+    }
+
+    fn write_evidence(
+        &self,
+        options: &AnalysisOptions,
+        result: &IntentVerificationResult,
+    ) -> Result<(), Box<dyn std::error::Error>> {
+        if let (Some(path), Some(recorder)) = (&self.evidence, &options.evidence) {
+            recorder.bundle(result).save(path)?;
+            eprintln!("🧾 Evidence bundle written to {}", path);
+        }
+        Ok(())
+    }
+}
+
+#[derive(Debug, Clone, Copy, ValueEnum)]
+enum Format {
+    Json,
+    Sarif,
+    Markdown,
+    Junit,
+}
+
+/// Rules deciding the exit code, on top of an optional policy file
+#[derive(Debug, Args)]
+struct PolicyArgs {
+    /// JSON file with a `VerdictPolicy`
+    #[arg(long)]
+    policy: Option<String>,
+    /// Minimum overall confidence (0.0-1.0)
+    #[arg(long)]
+    min_confidence: Option<f32>,
+    /// Fail when any finding is at or above this severity
+    #[arg(long, value_parser = parse_severity)]
+    fail_on_severity: Option<Severity>,
+}
+
+impl PolicyArgs {
+    /// Policy from the file, the archetype's default or the default, with the flags applied
+    fn policy(
+        &self,
+        archetype: Option<IntentArchetype>,
+    ) -> Result<VerdictPolicy, Box<dyn std::error::Error>> {
+        let mut policy = match (&self.policy, archetype) {
+            (Some(path), _) => serde_json::from_str(&std::fs::read_to_string(path)?)?,
+            (None, Some(archetype)) => archetype.verdict_policy(),
+            (None, None) => VerdictPolicy::default(),
+        };
+        if let Some(min_confidence) = self.min_confidence {
+            policy.min_confidence = min_confidence;
+        }
+        if self.fail_on_severity.is_some() {
+            policy.fail_on_severity = self.fail_on_severity;
+        }
+        Ok(policy)
+    }
+}
+
+/// Read a JSON result written by an earlier run
+fn load_result(path: &String) -> Result<IntentVerificationResult, Box<dyn std::error::Error>> {
+    Ok(serde_json::from_str(&std::fs::read_to_string(path)?)?)
+}
+
+/// A folder, or a repository archive when `path` is a file
+fn read_snapshot(path: &str) -> std::io::Result<RepoSnapshot> {
+    if std::path::Path::new(path).is_file() {
+        RepoSnapshot::from_archive(path)
+    } else {
+        RepoSnapshot::from_directory(path)
+    }
+}
+
+fn parse_severity(value: &str) -> Result<Severity, String> {
+    serde_json::from_value(serde_json::Value::String(value.to_lowercase()))
+        .map_err(|_| "expected one of: info, low, medium, high, critical".to_string())
+}
+
+fn parse_repo_changes(value: &str) -> Result<RepoChanges, String> {
+    RepoChanges::parse(value).map_err(|e| e.to_string())
+}
 
 #[tokio::main]
-async fn main() {
+async fn main() -> ExitCode {
     // Load .env file
+    dotenv().ok();
+
+    let cli = Cli::parse();
+
+    // Traces are exported only when a collector is configured
+    #[cfg(feature = "otel")]
+    let _telemetry = std::env::var_os("OTEL_EXPORTER_OTLP_ENDPOINT")
+        .is_some()
+        .then(|| intent_verification::init_otel_tracing("intent-verify", None))
+        .and_then(|telemetry| {
+            telemetry
+                .map_err(|e| eprintln!("⚠️  Failed to set up trace export: {}", e))
+                .ok()
+        });
+
+    match run(cli.command).await {
+        Ok(code) => code,
+        Err(e) => {
+            eprintln!("❌ {}", e);
+            ExitCode::from(EXIT_ERROR)
+        }
+    }
+}
+
+async fn run(command: Command) -> Result<ExitCode, Box<dyn std::error::Error>> {
+    match command {
+        Command::Analyze {
+            repo,
+            base,
+            head,
+            intent,
+            issue,
+            github,
+            github_token,
+            interactive,
+            llm,
+            output,
+            policy,
+        } => {
+            let policy = policy.policy(llm.archetype)?;
+            if github {
+                return run_github(intent, github_token, &llm, &output, &policy).await;
+            }
+
+            // clap guarantees these outside of `--github` mode
+            let (Some(repo), Some(head)) = (repo, head) else {
+                unreachable!("required arguments are missing");
+            };
+            let (intent, issue_url) =
+                resolve_intent(intent, issue, github_token.as_deref(), llm.local_only).await?;
+            let options = output.with_evidence(if interactive {
+                review_targets(&intent, &repo, &head, &llm).await?
+            } else {
+                llm.options()?
+            });
+            let mut result = match &base {
+                Some(base) => {
+                    verify_intent_with_options(
+                        &repo,
+                        &head,
+                        &repo,
+                        base,
+                        &head,
+                        &intent,
+                        &llm.api_key,
+                        llm.model.as_deref(),
+                        llm.base_url.as_deref(),
+                        &options,
+                    )
+                    .await?
+                }
+                None => {
+                    analyze_commit(
+                        &repo,
+                        &head,
+                        &intent,
+                        &llm.api_key,
+                        llm.model.as_deref(),
+                        llm.base_url.as_deref(),
+                        &options,
+                    )
+                    .await?
+                }
+            };
+            result.metadata.issue_url = issue_url;
+            if llm.dry_run {
+                return write_prompts(&result, &output);
+            }
+            output.sign(&mut result)?;
+            write_report(&result, &output)?;
+            output.write_evidence(&options, &result)?;
+            notify(&result, &output, llm.local_only).await?;
+            Ok(apply_policy(&policy, &result))
+        }
+        Command::Verify {
+            test_repo,
+            test_commit,
+            repo,
+            base,
+            head,
+            intent,
+            issue,
+            github_token,
+            interactive,
+            llm,
+            output,
+            policy,
+        } => {
+            let policy = policy.policy(llm.archetype)?;
+            let (intent, issue_url) =
+                resolve_intent(intent, issue, github_token.as_deref(), llm.local_only).await?;
+            let options = output.with_evidence(if interactive {
+                review_targets(&intent, &test_repo, &test_commit, &llm).await?
+            } else {
+                llm.options()?
+            });
+            let mut result = verify_intent_with_options(
+                &test_repo,
+                &test_commit,
+                &repo,
+                &base,
+                &head,
+                &intent,
+                &llm.api_key,
+                llm.model.as_deref(),
+                llm.base_url.as_deref(),
+                &options,
+            )
+            .await?;
+            result.metadata.issue_url = issue_url;
+            if llm.dry_run {
+                return write_prompts(&result, &output);
+            }
+            output.sign(&mut result)?;
+            write_report(&result, &output)?;
+            output.write_evidence(&options, &result)?;
+            notify(&result, &output, llm.local_only).await?;
+            Ok(apply_policy(&policy, &result))
+        }
+        Command::VerifyCrossRepo {
+            test_repo,
+            test_commit,
+            changes,
+            intent,
+            llm,
+            output,
+            policy,
+        } => {
+            let policy = policy.policy(llm.archetype)?;
+            let options = output.with_evidence(llm.options()?);
+            let mut combined = verify_cross_repo_intent(
+                &test_repo,
+                &test_commit,
+                &changes,
+                &intent,
+                &llm.api_key,
+                llm.model.as_deref(),
+                llm.base_url.as_deref(),
+                &options,
+            )
+            .await?;
+            if llm.dry_run {
+                return write_prompts(&combined.result, &output);
+            }
+            for contribution in &combined.contributions {
+                eprintln!(
+                    "{} {}",
+                    if contribution.supports_intent() {
+                        "✅"
+                    } else {
+                        "➖"
+                    },
+                    contribution.summary
+                );
+            }
+            output.sign(&mut combined.result)?;
+            write_report(&combined.result, &output)?;
+            output.write_evidence(&options, &combined.result)?;
+            notify(&combined.result, &output, llm.local_only).await?;
+            Ok(apply_policy(&policy, &combined.result))
+        }
+        Command::AnalyzeDiff {
+            intent,
+            tests,
+            llm,
+            output,
+            policy,
+        } => {
+            let policy = policy.policy(llm.archetype)?;
+            let mut diff = String::new();
+            std::io::stdin().read_to_string(&mut diff)?;
+            if diff.trim().is_empty() {
+                return Err("No diff on stdin".into());
+            }
+
+            let (before, after) = RepoSnapshot::from_unified_diff(&diff)?;
+            let tests = match &tests {
+                Some(path) => RepoSnapshot::from_working_tree(path)?,
+                None => after.clone(),
+            };
+            let options = output.with_evidence(llm.options()?);
+            let mut result = verify_intent_with_snapshots(
+                &tests,
+                &before,
+                &after,
+                &intent,
+                &llm.api_key,
+                llm.model.as_deref(),
+                llm.base_url.as_deref(),
+                &options,
+            )
+            .await?;
+            if llm.dry_run {
+                return write_prompts(&result, &output);
+            }
+            output.sign(&mut result)?;
+            write_report(&result, &output)?;
+            output.write_evidence(&options, &result)?;
+            notify(&result, &output, llm.local_only).await?;
+            Ok(apply_policy(&policy, &result))
+        }
+        Command::AnalyzeDirs {
+            before,
+            after,
+            intent,
+            tests,
+            llm,
+            output,
+            policy,
+        } => {
+            let policy = policy.policy(llm.archetype)?;
+            let before = read_snapshot(&before)?;
+            let after = read_snapshot(&after)?;
+            let tests = match &tests {
+                Some(path) => read_snapshot(path)?,
+                None => after.clone(),
+            };
+            let options = output.with_evidence(llm.options()?);
+            let mut result = verify_intent_with_snapshots(
+                &tests,
+                &before,
+                &after,
+                &intent,
+                &llm.api_key,
+                llm.model.as_deref(),
+                llm.base_url.as_deref(),
+                &options,
+            )
+            .await?;
+            if llm.dry_run {
+                return write_prompts(&result, &output);
+            }
+            output.sign(&mut result)?;
+            write_report(&result, &output)?;
+            output.write_evidence(&options, &result)?;
+            notify(&result, &output, llm.local_only).await?;
+            Ok(apply_policy(&policy, &result))
+        }
+        Command::AnalyzeFile {
+            file,
+            language,
+            intent,
+            output,
+            llm,
+        } => {
+            let file = if file == "-" {
+                let mut content = String::new();
+                std::io::stdin().read_to_string(&mut content)?;
+                content
+            } else if std::path::Path::new(&file).is_file() {
+                file
+            } else {
+                return Err(format!("No such file: {}", file).into());
+            };
+            let result = analyze_file(
+                &file,
+                language.as_deref(),
+                &intent,
+                &llm.api_key,
+                llm.model.as_deref(),
+                llm.base_url.as_deref(),
+                &llm.options()?,
+            )
+            .await?;
+            for warning in &result.warnings {
+                eprintln!("⚠️  {}", warning.message);
+            }
+
+            let json = serde_json::to_string_pretty(&result)?;
+            match output {
+                Some(path) => std::fs::write(path, json)?,
+                None => println!("{}", json),
+            }
+            Ok(if result.analysis.supports_intent {
+                ExitCode::SUCCESS
+            } else {
+                ExitCode::from(EXIT_POLICY_FAILED)
+            })
+        }
+        #[cfg(feature = "server")]
+        Command::Serve {
+            listen,
+            auth_token,
+            webhook_secret,
+            github_token,
+            callback_url,
+            allowed_repo_hosts,
+            max_body_bytes,
+            max_finished_jobs,
+            max_running_jobs,
+            #[cfg(feature = "grpc")]
+            grpc_listen,
+            llm,
+        } => {
+            if llm.local_only && callback_url.is_some() {
+                return Err(
+                    "--callback-url POSTs every finished job over the network, which \
+                     --local-only doesn't allow"
+                        .into(),
+                );
+            }
+            let listener = tokio::net::TcpListener::bind(&listen).await?;
+            eprintln!("🚀 Listening on http://{}", listener.local_addr()?);
+            let config = intent_verification::ServerConfig {
+                api_key: llm.api_key.clone(),
+                model: llm.model.clone(),
+                base_url: llm.base_url.clone(),
+                options: llm.options()?,
+                auth_token,
+                webhook_secret,
+                github_token,
+                callback_url,
+                allowed_repo_hosts,
+                max_body_bytes: Some(max_body_bytes),
+                max_finished_jobs: Some(max_finished_jobs),
+                max_running_jobs: Some(max_running_jobs),
+            };
+
+            #[cfg(feature = "grpc")]
+            if let Some(grpc_listen) = grpc_listen {
+                let grpc_listener = tokio::net::TcpListener::bind(&grpc_listen).await?;
+                eprintln!("🚀 Serving gRPC on {}", grpc_listener.local_addr()?);
+                // Both servers run until one of them fails
+                tokio::select! {
+                    served = intent_verification::serve(listener, config.clone()) => served?,
+                    served = intent_verification::serve_grpc(grpc_listener, config) => served?,
+                }
+                return Ok(ExitCode::SUCCESS);
+            }
+
+            intent_verification::serve(listener, config).await?;
+            Ok(ExitCode::SUCCESS)
+        }
+        Command::Batch {
+            manifest,
+            output,
+            llm,
+            policy,
+        } => {
+            let policy = policy.policy(llm.archetype)?;
+            let manifest = BatchManifest::load(&manifest)?;
+            let summary = run_batch(
+                &manifest,
+                &llm.api_key,
+                llm.model.as_deref(),
+                llm.base_url.as_deref(),
+                &llm.options()?,
+            )
+            .await;
+
+            let mut code = ExitCode::SUCCESS;
+            for outcome in &summary.outcomes {
+                match (&outcome.result, &outcome.error) {
+                    (Some(result), _) if policy.evaluate(result).passed => {
+                        eprintln!("✅ {}", outcome.name)
+                    }
+                    (Some(_), _) => {
+                        eprintln!("❌ {}", outcome.name);
+                        if code == ExitCode::SUCCESS {
+                            code = ExitCode::from(EXIT_POLICY_FAILED);
+                        }
+                    }
+                    (None, error) => {
+                        eprintln!(
+                            "⚠️  {}: {}",
+                            outcome.name,
+                            error.as_deref().unwrap_or_default()
+                        );
+                        code = ExitCode::from(EXIT_ERROR);
+                    }
+                }
+            }
+            eprintln!(
+                "📊 {} jobs: {} fulfilled, {} not fulfilled, {} failed",
+                summary.total, summary.fulfilled, summary.not_fulfilled, summary.failed
+            );
+
+            let json = serde_json::to_string_pretty(&summary)?;
+            match output {
+                Some(path) => std::fs::write(path, json)?,
+                None => println!("{}", json),
+            }
+            Ok(code)
+        }
+        Command::ComparePrompts {
+            repo,
+            base,
+            head,
+            intent,
+            prompts_a,
+            prompts_b,
+            output,
+            llm,
+        } => {
+            let version_a = match &prompts_a {
+                Some(path) => PromptTemplates::load(path)?,
+                None => PromptTemplates::current().clone(),
+            };
+            let version_b = PromptTemplates::load(&prompts_b)?;
+            let comparison = compare_prompt_versions(
+                &version_a,
+                &version_b,
+                &repo,
+                &head,
+                &repo,
+                &base,
+                &head,
+                &intent,
+                &llm.api_key,
+                llm.model.as_deref(),
+                llm.base_url.as_deref(),
+                &llm.options()?,
+            )
+            .await?;
+
+            let verdict = |fulfilled: bool| {
+                if fulfilled {
+                    "fulfilled"
+                } else {
+                    "not fulfilled"
+                }
+            };
+            eprintln!(
+                "🧪 Version {}: {} ({:.2}), version {}: {} ({:.2})",
+                comparison.version_a,
+                verdict(comparison.result_a.is_intent_fulfilled),
+                comparison.result_a.confidence,
+                comparison.version_b,
+                verdict(comparison.result_b.is_intent_fulfilled),
+                comparison.result_b.confidence
+            );
+            let json = serde_json::to_string_pretty(&comparison)?;
+            match output {
+                Some(path) => std::fs::write(path, json)?,
+                None => println!("{}", json),
+            }
+            Ok(ExitCode::SUCCESS)
+        }
+        Command::Eval {
+            corpus,
+            output,
+            min_accuracy,
+            llm,
+        } => {
+            let corpus = EvalCorpus::load(&corpus)?;
+            let report = run_eval(
+                &corpus,
+                &llm.api_key,
+                llm.model.as_deref(),
+                llm.base_url.as_deref(),
+                &llm.options()?,
+            )
+            .await;
+
+            for case in report.mismatches() {
+                match &case.error {
+                    Some(error) => eprintln!("⚠️  {}: {}", case.name, error),
+                    None => eprintln!(
+                        "❌ {}: expected {}, got {}",
+                        case.name, case.expected, !case.expected
+                    ),
+                }
+            }
+            let percent = |metric: Option<f32>| {
+                metric.map_or("n/a".to_string(), |m| format!("{:.1}%", m * 100.0))
+            };
+            eprintln!(
+                "📊 {} cases ({} errors): accuracy {}, precision {}, recall {}",
+                report.total,
+                report.errors,
+                percent(report.accuracy),
+                percent(report.precision),
+                percent(report.recall)
+            );
+
+            let json = serde_json::to_string_pretty(&report)?;
+            match output {
+                Some(path) => std::fs::write(path, json)?,
+                None => println!("{}", json),
+            }
+            match min_accuracy {
+                Some(min) if report.accuracy.unwrap_or(0.0) < min => {
+                    Ok(ExitCode::from(EXIT_POLICY_FAILED))
+                }
+                _ => Ok(ExitCode::SUCCESS),
+            }
+        }
+        Command::ExportFinetune { manifest, output } => {
+            let export = export_fine_tuning(&FineTuneManifest::load(&manifest)?)?;
+            for skipped in &export.skipped {
+                eprintln!("⚠️  Skipped {}: {}", skipped.bundle, skipped.reason);
+            }
+            eprintln!(
+                "📦 {} examples from {} runs ({} skipped)",
+                export.examples.len(),
+                export.runs_used,
+                export.skipped.len()
+            );
+
+            let jsonl = export.to_jsonl()?;
+            match output {
+                Some(path) => std::fs::write(path, jsonl)?,
+                None => print!("{}", jsonl),
+            }
+            Ok(ExitCode::SUCCESS)
+        }
+        Command::Watch {
+            path,
+            intent,
+            debounce_ms,
+            llm,
+        } => {
+            let options = llm.options()?;
+            let mut watcher = WorkingTreeWatcher::new(&path, Duration::from_millis(debounce_ms))?;
+            loop {
+                eprintln!("🔄 Verifying uncommitted changes in {}", path);
+                let head = RepoSnapshot::from_working_tree(&path)?;
+                let verified = verify_intent_with_snapshots(
+                    &head,
+                    &RepoSnapshot::from_commit(&path, "HEAD")?,
+                    &head,
+                    &intent,
+                    &llm.api_key,
+                    llm.model.as_deref(),
+                    llm.base_url.as_deref(),
+                    &options,
+                )
+                .await;
+                match verified {
+                    Ok(result) => print_watch_summary(&result),
+                    Err(e) => eprintln!("⚠️  Verification failed: {}", e),
+                }
+
+                eprintln!("👀 Watching {} for changes (Ctrl+C to stop)", path);
+                watcher.wait_for_change().await?;
+            }
+        }
+        Command::Ask {
+            result,
+            intent,
+            questions,
+            llm,
+        } => {
+            let mut conversation = VerificationConversation::new(
+                load_result(&result)?,
+                &intent,
+                &llm.api_key,
+                llm.model.as_deref(),
+                llm.base_url.as_deref(),
+                &llm.options()?,
+            );
+            for question in &questions {
+                conversation.ask(question).await?;
+            }
+            println!("{}", serde_json::to_string_pretty(conversation.answers())?);
+            Ok(ExitCode::SUCCESS)
+        }
+        Command::VerifyAttestation { result, public_key } => {
+            let result = load_result(&result)?;
+            verify_attestation(&result, public_key.as_deref())?;
+            if let Some(attestation) = &result.attestation {
+                eprintln!(
+                    "✅ Attestation is valid (key {}, result hash {})",
+                    attestation.public_key, attestation.result_hash
+                );
+            }
+            Ok(ExitCode::SUCCESS)
+        }
+        Command::ExtractTargets { intent, llm } => {
+            let targets = extract_test_targets_with_ai(
+                &intent,
+                &llm.api_key,
+                llm.model.as_deref(),
+                llm.base_url.as_deref(),
+            )
+            .await?;
+            println!("{}", serde_json::to_string_pretty(&targets)?);
+            Ok(ExitCode::SUCCESS)
+        }
+    }
+}
+
+/// Verify the pull request that triggered the workflow and update its sticky comment
+async fn run_github(
+    intent: Option<String>,
+    github_token: Option<String>,
+    llm: &LlmArgs,
+    output: &OutputArgs,
+    policy: &VerdictPolicy,
+) -> Result<ExitCode, Box<dyn std::error::Error>> {
+    if llm.local_only {
+        return Err(
+            "--github clones the pull request and comments on it over the network, which \
+             --local-only doesn't allow"
+                .into(),
+        );
+    }
+    let token = github_token.ok_or("--github requires a token (--github-token or GITHUB_TOKEN)")?;
+    let pr = PullRequestContext::from_env()?;
+    let intent = intent.unwrap_or_else(|| pr.intent());
+    let repo = &pr.clone_url;
+    let options = AnalysisOptions {
+        git_credentials: Some(GitCredentials::token(&token)),
+        ..output.with_evidence(llm.options()?)
+    };
+
+    eprintln!(
+        "🐙 Verifying {}#{} ({}..{})",
+        pr.repository, pr.number, pr.base_sha, pr.head_sha
+    );
+    let mut result = verify_intent_with_options(
+        repo,
+        &pr.head_sha,
+        repo,
+        &pr.base_sha,
+        &pr.head_sha,
+        &intent,
+        &llm.api_key,
+        llm.model.as_deref(),
+        llm.base_url.as_deref(),
+        &options,
+    )
+    .await?;
+
+    if llm.dry_run {
+        return write_prompts(&result, output);
+    }
+    output.sign(&mut result)?;
+    post_sticky_comment(&pr.repository, pr.number, &token, &render_markdown(&result)).await?;
+    write_report(&result, output)?;
+    output.write_evidence(&options, &result)?;
+    notify(&result, output, llm.local_only).await?;
+    Ok(apply_policy(policy, &result))
+}
+
+/// Show the test targets extracted from the intent and let the user correct them
+///
+/// Each line read from stdin is either a target edit (`+fn name`, `-fn name`, `+file path`,
+/// `-file path`) or a clarification passed on to the model. An empty line confirms the
+/// targets, which then replace the extraction step of the verification.
+async fn review_targets(
+    intent: &str,
+    test_repo: &str,
+    test_commit: &str,
+    llm: &LlmArgs,
+) -> Result<AnalysisOptions, Box<dyn std::error::Error>> {
+    let mut options = llm.options()?;
+    let mut targets = extract_test_targets_with_ai(
+        intent,
+        &llm.api_key,
+        llm.model.as_deref(),
+        llm.base_url.as_deref(),
+    )
+    .await?;
+
+    let stdin = std::io::stdin();
+    loop {
+        let resolved = read_test_targets_code(&targets, test_repo, test_commit)?;
+        eprintln!("\n🎯 Test targets:");
+        for function in &resolved.function_contents {
+            match (&function.file_path, &function.error) {
+                (Some(file_path), None) => eprintln!("  ✅ fn {} ({})", function.name, file_path),
+                (_, error) => eprintln!(
+                    "  ❌ fn {}: {}",
+                    function.name,
+                    error.as_deref().unwrap_or("not found")
+                ),
+            }
+        }
+        for file in &resolved.file_contents {
+            match &file.error {
+                None => eprintln!("  ✅ file {}", file.path),
+                Some(error) => eprintln!("  ❌ file {}: {}", file.path, error),
+            }
+        }
+        for (kind, names) in [
+            ("module", &targets.modules),
+            ("class", &targets.classes),
+            ("command", &targets.commands),
+            ("endpoint", &targets.endpoints),
+        ] {
+            for name in names {
+                eprintln!("  • {} {}", kind, name);
+            }
+        }
+        eprintln!(
+            "Correct with `+fn name`, `-fn name`, `+file path` or `-file path` (likewise \
+             `module`, `class`, `command` and `endpoint`), add any other text as a \
+             clarification, or press Enter on an empty line to verify."
+        );
+
+        let mut edited = false;
+        loop {
+            let mut line = String::new();
+            if stdin.read_line(&mut line)? == 0 {
+                break;
+            }
+            let line = line.trim();
+            if line.is_empty() {
+                break;
+            }
+            match line.split_once(' ').map(|(op, arg)| (op, arg.trim())) {
+                Some(("+fn", name)) => targets.functions.push(name.to_string()),
+                Some(("-fn", name)) => targets.functions.retain(|f| f != name),
+                Some(("+file", path)) => targets.files.push(path.to_string()),
+                Some(("-file", path)) => targets.files.retain(|f| f != path),
+                Some(("+module", name)) => targets.modules.push(name.to_string()),
+                Some(("-module", name)) => targets.modules.retain(|m| m != name),
+                Some(("+class", name)) => targets.classes.push(name.to_string()),
+                Some(("-class", name)) => targets.classes.retain(|c| c != name),
+                Some(("+command", command)) => targets.commands.push(command.to_string()),
+                Some(("-command", command)) => targets.commands.retain(|c| c != command),
+                Some(("+endpoint", endpoint)) => targets.endpoints.push(endpoint.to_string()),
+                Some(("-endpoint", endpoint)) => targets.endpoints.retain(|e| e != endpoint),
+                _ => {
+                    options.clarifications.push(line.to_string());
+                    continue;
+                }
+            }
+            edited = true;
+        }
+        if !edited {
+            break;
+        }
+    }
+
+    options.targets = Some(targets);
+    Ok(options)
+}
+
+/// One line per analyzed file followed by the verdict, for continuous feedback
+fn print_watch_summary(result: &IntentVerificationResult) {
+    for analysis in &result.files_analyzed {
+        let mark = if analysis.supports_intent {
+            "✅"
+        } else {
+            "➖"
+        };
+        println!("  {} {}", mark, analysis.file_path);
+    }
+    for warning in &result.warnings {
+        println!("  ⚠️  {}", warning.message);
+    }
+    let verdict = if result.is_intent_fulfilled {
+        "✅ Intent fulfilled"
+    } else {
+        "❌ Intent not fulfilled yet"
+    };
+    println!(
+        "{} (confidence {:.2}): {}",
+        verdict, result.confidence, result.explanation
+    );
+}
+
+/// The intent given on the command line, or the one stated by `issue` along with its URL
+async fn resolve_intent(
+    intent: Option<String>,
+    issue: Option<(String, u64)>,
+    github_token: Option<&str>,
+    local_only: bool,
+) -> Result<(String, Option<String>), Box<dyn std::error::Error>> {
+    match (intent, issue) {
+        (Some(intent), _) => Ok((intent, None)),
+        (None, Some((repository, number))) => {
+            if local_only {
+                return Err(Box::new(ValidationError::NetworkAccessNotAllowed {
+                    url: format!("the GitHub issue {}#{}", repository, number),
+                }));
+            }
+            let issue = fetch_issue(&repository, number, github_token).await?;
+            eprintln!(
+                "🐙 Using {}#{} as the intent: {}",
+                repository, number, issue.title
+            );
+            Ok((issue.intent(), Some(issue.html_url)))
+        }
+        // clap requires one of them
+        (None, None) => unreachable!("intent is missing"),
+    }
+}
+
+/// Output the prompts of a dry run, with the total token estimate
+fn write_prompts(
+    result: &IntentVerificationResult,
+    output: &OutputArgs,
+) -> Result<ExitCode, Box<dyn std::error::Error>> {
+    let estimated_tokens: usize = result.prompts.iter().map(|p| p.estimated_tokens).sum();
+    eprintln!(
+        "🧪 Dry run: {} prompts, ~{} input tokens",
+        result.prompts.len(),
+        estimated_tokens
+    );
+    let json = serde_json::to_string_pretty(&serde_json::json!({
+        "estimated_tokens": estimated_tokens,
+        "prompts": result.prompts,
+    }))?;
+
+    match &output.output {
+        Some(path) => std::fs::write(path, json)?,
+        None => println!("{}", json),
+    }
+    Ok(ExitCode::SUCCESS)
+}
+
+fn write_report(
+    result: &IntentVerificationResult,
+    output: &OutputArgs,
+) -> Result<(), Box<dyn std::error::Error>> {
+    let report = match output.format {
+        Format::Json => serde_json::to_string_pretty(result)?,
+        Format::Sarif => serde_json::to_string_pretty(&render_sarif(result))?,
+        Format::Markdown => render_markdown(result),
+        Format::Junit => render_junit(result),
+    };
+
+    match &output.output {
+        Some(path) => std::fs::write(path, report)?,
+        None => println!("{}", report),
+    }
+    Ok(())
+}
+
+/// Post the verdict to the channels configured with `--notify`
+///
+/// A channel that can't be reached is reported but doesn't fail the run. Refused in local-only
+/// mode, since every channel is reached over the network.
+async fn notify(
+    result: &IntentVerificationResult,
+    output: &OutputArgs,
+    local_only: bool,
+) -> Result<(), Box<dyn std::error::Error>> {
+    let Some(path) = &output.notify else {
+        return Ok(());
+    };
+    if local_only {
+        return Err(format!(
+            "--local-only doesn't allow sending the notifications configured in {}",
+            path
+        )
+        .into());
+    }
+    let mut config = NotifyConfig::load(path)?;
+    if let Some(report_url) = &output.report_url {
+        config.report_url = Some(report_url.clone());
+    }
+    if let Err(e) = send_notifications(&config, result).await {
+        eprintln!("⚠️  {}", e);
+    }
+    Ok(())
+}
+
+/// Exit code for a result, explaining any policy failure on stderr
+fn apply_policy(policy: &VerdictPolicy, result: &IntentVerificationResult) -> ExitCode {
+    let decision = policy.evaluate(result);
+    if decision.passed {
+        return ExitCode::SUCCESS;
+    }
+    eprintln!("❌ Verdict failed the policy:");
+    for reason in &decision.reasons {
+        eprintln!("  - {}", reason);
+    }
+    ExitCode::from(EXIT_POLICY_FAILED)
 }
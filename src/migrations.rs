@@ -0,0 +1,371 @@
+use regex::Regex;
+
+use crate::git::{ChangeType, FileChange};
+use crate::types::{Finding, IntentVerificationResult, Severity};
+
+/// Rule prefix of the findings reported for destructive migration statements
+pub const MIGRATION_RULE_PREFIX: &str = "migrations/";
+
+/// Longest statement quoted in a finding's snippet
+const MAX_SNIPPET_CHARS: usize = 200;
+
+/// Whether `path` is a SQL migration: a `.sql` file in a migrations directory, or named like
+/// a versioned migration (`V3__add_users.sql`, `20240101120000_add_users.sql`)
+pub fn is_migration_path(path: &str) -> bool {
+    let lower = path.to_lowercase();
+    if !lower.ends_with(".sql") {
+        return false;
+    }
+    let name = lower.rsplit('/').next().unwrap_or(&lower);
+    lower.contains("migrat")
+        || lower.contains("db/migrate/")
+        || Regex::new(r"^(?:v\d+(?:[._]\d+)*__|\d{3,}[_-])")
+            .unwrap()
+            .is_match(name)
+}
+
+/// A statement of a SQL file with the 1-based line it starts on
+#[derive(Debug, Clone, PartialEq)]
+pub struct SqlStatement {
+    pub line: usize,
+    /// Statement text with comments removed and whitespace collapsed, without the `;`
+    pub text: String,
+}
+
+/// Split SQL into statements, skipping comments and `;` inside quotes
+pub fn parse_sql(sql: &str) -> Vec<SqlStatement> {
+    let mut statements = Vec::new();
+    let mut current = String::new();
+    let mut start_line = None;
+    let mut line = 1;
+    let mut chars = sql.chars().peekable();
+    let mut quote: Option<char> = None;
+
+    let mut finish = |current: &mut String, start_line: &mut Option<usize>| {
+        let text = current.split_whitespace().collect::<Vec<_>>().join(" ");
+        if !text.is_empty() {
+            statements.push(SqlStatement {
+                line: start_line.unwrap_or(1),
+                text,
+            });
+        }
+        current.clear();
+        *start_line = None;
+    };
+
+    while let Some(c) = chars.next() {
+        if c == '\n' {
+            line += 1;
+        }
+        if let Some(q) = quote {
+            current.push(c);
+            if c == q {
+                quote = None;
+            }
+            continue;
+        }
+        match c {
+            '-' if chars.peek() == Some(&'-') => {
+                // Line comment
+                for c in chars.by_ref() {
+                    if c == '\n' {
+                        line += 1;
+                        break;
+                    }
+                }
+                current.push(' ');
+            }
+            '/' if chars.peek() == Some(&'*') => {
+                // Block comment
+                chars.next();
+                let mut previous = ' ';
+                for c in chars.by_ref() {
+                    if c == '\n' {
+                        line += 1;
+                    }
+                    if previous == '*' && c == '/' {
+                        break;
+                    }
+                    previous = c;
+                }
+                current.push(' ');
+            }
+            ';' => finish(&mut current, &mut start_line),
+            _ => {
+                if start_line.is_none() && !c.is_whitespace() {
+                    start_line = Some(line);
+                }
+                if c == '\'' || c == '"' || c == '`' {
+                    quote = Some(c);
+                }
+                current.push(c);
+            }
+        }
+    }
+    finish(&mut current, &mut start_line);
+    statements
+}
+
+/// Destructive operation found in a statement: rule name, severity, affected object and
+/// guidance
+struct Operation {
+    rule: &'static str,
+    severity: Severity,
+    object: String,
+    message: String,
+}
+
+/// Findings for destructive statements the changes add to SQL migrations
+///
+/// Dropped tables and columns, truncations, unfiltered deletes and column type changes are
+/// high severity; renames and new `NOT NULL` constraints medium. An operation on an object
+/// the intent names (e.g. "drop the legacy_users table") is expected and reported as low
+/// severity instead.
+pub fn scan_migrations(file_changes: &[FileChange], user_intent: &str) -> Vec<Finding> {
+    let mut findings = Vec::new();
+    for file_change in file_changes {
+        if file_change.status == ChangeType::Deleted || !is_migration_path(&file_change.path) {
+            continue;
+        }
+        let Some(content) = &file_change.content else {
+            continue;
+        };
+        let existing: Vec<String> = file_change
+            .old_content
+            .as_deref()
+            .map(parse_sql)
+            .unwrap_or_default()
+            .into_iter()
+            .map(|statement| statement.text)
+            .collect();
+
+        for statement in parse_sql(content) {
+            if existing.contains(&statement.text) {
+                continue;
+            }
+            for operation in destructive_operations(&statement.text) {
+                let severity = if mentions_word(user_intent, &operation.object) {
+                    Severity::Low
+                } else {
+                    operation.severity
+                };
+                findings.push(Finding {
+                    rule: format!("{}{}", MIGRATION_RULE_PREFIX, operation.rule),
+                    severity,
+                    file_path: Some(file_change.path.clone()),
+                    line: Some(statement.line),
+                    snippet: Some(statement.text.chars().take(MAX_SNIPPET_CHARS).collect()),
+                    message: operation.message,
+                    suppressed: false,
+                    cwe: None,
+                    content_hash: None,
+                });
+            }
+        }
+    }
+    findings
+}
+
+/// Force a negative verdict when unsuppressed high-severity migration findings remain
+///
+/// Returns the number of such findings.
+pub fn apply_migration_findings(result: &mut IntentVerificationResult) -> usize {
+    let destructive = result
+        .findings
+        .iter()
+        .filter(|f| {
+            !f.suppressed
+                && f.rule.starts_with(MIGRATION_RULE_PREFIX)
+                && f.severity >= Severity::High
+        })
+        .count();
+    if destructive > 0 {
+        result.is_intent_fulfilled = false;
+        result.explanation = format!(
+            "{}; {} destructive migration statement(s) the intent doesn't ask for",
+            result.explanation, destructive
+        );
+    }
+    destructive
+}
+
+fn destructive_operations(statement: &str) -> Vec<Operation> {
+    let captures = |pattern: &str| {
+        Regex::new(&format!("(?i){}", pattern))
+            .unwrap()
+            .captures(statement)
+    };
+
+    if let Some(c) = captures(r"^DROP\s+(SCHEMA|DATABASE)\s+(?:IF\s+EXISTS\s+)?(\S+)") {
+        let object = object_name(&c[2]);
+        return vec![Operation {
+            rule: "drop-schema",
+            severity: Severity::Critical,
+            message: format!(
+                "Dropping {} `{}` deletes every table in it irreversibly",
+                c[1].to_lowercase(),
+                object
+            ),
+            object,
+        }];
+    }
+    if let Some(c) = captures(r"^DROP\s+TABLE\s+(?:IF\s+EXISTS\s+)?([^\s,]+)") {
+        let object = object_name(&c[1]);
+        return vec![Operation {
+            rule: "drop-table",
+            severity: Severity::High,
+            message: format!(
+                "Dropping table `{}` deletes its data irreversibly; back it up or rename it first and drop it in a later release",
+                object
+            ),
+            object,
+        }];
+    }
+    if let Some(c) = captures(r"^TRUNCATE\s+(?:TABLE\s+)?(\S+)") {
+        let object = object_name(&c[1]);
+        return vec![Operation {
+            rule: "truncate",
+            severity: Severity::High,
+            message: format!(
+                "Truncating `{}` deletes all of its rows; migrations shouldn't discard data",
+                object
+            ),
+            object,
+        }];
+    }
+    if let Some(c) = captures(r"^DELETE\s+FROM\s+(\S+)")
+        && captures(r"\bWHERE\b").is_none()
+    {
+        let object = object_name(&c[1]);
+        return vec![Operation {
+            rule: "delete-all",
+            severity: Severity::High,
+            message: format!(
+                "Deleting from `{}` without a WHERE clause removes every row",
+                object
+            ),
+            object,
+        }];
+    }
+
+    let Some(c) = captures(r"^ALTER\s+TABLE\s+(?:IF\s+EXISTS\s+)?(?:ONLY\s+)?(\S+)\s+(.*)$") else {
+        return vec![];
+    };
+    let table = object_name(&c[1]);
+    split_top_level(&c[2])
+        .into_iter()
+        .filter_map(|action| alter_table_operation(&table, action.trim()))
+        .collect()
+}
+
+/// Destructive action of an `ALTER TABLE` statement
+fn alter_table_operation(table: &str, action: &str) -> Option<Operation> {
+    let captures = |pattern: &str| {
+        Regex::new(&format!("(?i){}", pattern))
+            .unwrap()
+            .captures(action)
+    };
+
+    if captures(r"^DROP\s+(?:CONSTRAINT|INDEX|KEY|PRIMARY|FOREIGN|DEFAULT|NOT\s+NULL)\b").is_none()
+        && let Some(c) = captures(r"^DROP\s+(?:COLUMN\s+)?(?:IF\s+EXISTS\s+)?(\S+)")
+    {
+        let column = object_name(&c[1]);
+        return Some(Operation {
+            rule: "drop-column",
+            severity: Severity::High,
+            message: format!(
+                "Dropping column `{}.{}` deletes its data irreversibly; stop using it first and drop it in a later migration",
+                table, column
+            ),
+            object: column,
+        });
+    }
+    if let Some(c) = captures(r"^ALTER\s+(?:COLUMN\s+)?(\S+)\s+(?:SET\s+DATA\s+)?TYPE\s")
+        .or_else(|| captures(r"^(?:MODIFY|CHANGE)\s+(?:COLUMN\s+)?(\S+)"))
+    {
+        let column = object_name(&c[1]);
+        return Some(Operation {
+            rule: "column-type",
+            severity: Severity::High,
+            message: format!(
+                "Changing the type of `{}.{}` can truncate or fail to convert existing values; add a new column, backfill it, then switch over",
+                table, column
+            ),
+            object: column,
+        });
+    }
+    if let Some(c) = captures(r"^ALTER\s+(?:COLUMN\s+)?(\S+)\s+SET\s+NOT\s+NULL") {
+        let column = object_name(&c[1]);
+        return Some(Operation {
+            rule: "not-null",
+            severity: Severity::Medium,
+            message: format!(
+                "Making `{}.{}` NOT NULL fails when existing rows hold NULL; backfill them first",
+                table, column
+            ),
+            object: column,
+        });
+    }
+    if let Some(c) = captures(r"^RENAME\s+(?:COLUMN\s+)?(\S+)\s+TO\s") {
+        let column = object_name(&c[1]);
+        if !column.eq_ignore_ascii_case("to") {
+            return Some(Operation {
+                rule: "rename-column",
+                severity: Severity::Medium,
+                message: format!(
+                    "Renaming `{}.{}` breaks code still using the old name; add the new column alongside it first",
+                    table, column
+                ),
+                object: column,
+            });
+        }
+    }
+    if captures(r"^RENAME\s+TO\s").is_some() {
+        return Some(Operation {
+            rule: "rename-table",
+            severity: Severity::Medium,
+            message: format!(
+                "Renaming table `{}` breaks code still using the old name",
+                table
+            ),
+            object: table.to_string(),
+        });
+    }
+    None
+}
+
+/// Split `ALTER TABLE` actions on commas outside parentheses
+fn split_top_level(actions: &str) -> Vec<&str> {
+    let mut parts = Vec::new();
+    let mut depth = 0;
+    let mut start = 0;
+    for (i, c) in actions.char_indices() {
+        match c {
+            '(' => depth += 1,
+            ')' => depth -= 1,
+            ',' if depth == 0 => {
+                parts.push(&actions[start..i]);
+                start = i + 1;
+            }
+            _ => {}
+        }
+    }
+    parts.push(&actions[start..]);
+    parts
+}
+
+/// Unquoted object name without its schema
+fn object_name(raw: &str) -> String {
+    let unquoted: String = raw
+        .chars()
+        .filter(|c| !matches!(c, '"' | '`' | '[' | ']' | '(' | ')' | ';'))
+        .collect();
+    unquoted.rsplit('.').next().unwrap_or(&unquoted).to_string()
+}
+
+fn mentions_word(text: &str, word: &str) -> bool {
+    !word.is_empty()
+        && Regex::new(&format!(r"(?i)\b{}\b", regex::escape(word)))
+            .unwrap()
+            .is_match(text)
+}
@@ -0,0 +1,276 @@
+use std::collections::VecDeque;
+use std::sync::{Arc, Mutex};
+
+use async_openai::error::ApiError;
+use async_openai::types::chat::{CreateChatCompletionRequest, CreateChatCompletionResponse};
+
+use crate::types::PromptMessage;
+use crate::utils::estimate_tokens;
+
+/// A request the [`MockProvider`] received
+#[derive(Debug, Clone, PartialEq)]
+pub struct MockCall {
+    pub model: String,
+    pub messages: Vec<PromptMessage>,
+    /// Whether token log-probabilities were asked for
+    pub logprobs: bool,
+}
+
+impl MockCall {
+    /// Every message's content, one after the other
+    pub fn prompt(&self) -> String {
+        self.messages
+            .iter()
+            .map(|message| message.content.as_str())
+            .collect::<Vec<_>>()
+            .join("\n\n")
+    }
+}
+
+/// A canned reply, with the log-probability of each token when the reply has them
+#[derive(Clone, Default)]
+struct MockReply {
+    content: String,
+    logprobs: Option<Vec<(String, f32)>>,
+    /// Refusal message, sent instead of the content
+    refusal: Option<String>,
+    /// Whether the content filter stopped the reply
+    filtered: bool,
+}
+
+impl From<String> for MockReply {
+    fn from(content: String) -> Self {
+        MockReply {
+            content,
+            ..Default::default()
+        }
+    }
+}
+
+type MockRule = Box<dyn Fn(&MockCall) -> Option<Result<MockReply, String>> + Send + Sync>;
+
+#[derive(Default)]
+struct MockState {
+    rules: Vec<MockRule>,
+    default_reply: Option<String>,
+    failures: VecDeque<String>,
+    calls: Vec<MockCall>,
+}
+
+/// Model provider answering from canned replies instead of the network, for testing code that
+/// runs verifications
+///
+/// Pass it to [`LlmClient::mock`](crate::LlmClient::mock) and set the client on
+/// [`AnalysisOptions::llm_client`](crate::AnalysisOptions::llm_client); every LLM request of
+/// the verification then goes to the mock, whatever the API key and base URL. Rules are tried
+/// in the order they were added, then the default reply; requests nothing answers fail.
+/// Clones share the rules and recorded calls.
+///
+/// ```
+/// use intent_verification::{AnalysisOptions, LlmClient, MockProvider};
+///
+/// let mock = MockProvider::new()
+///     .respond_when("Extract from the following prompt", r#"{"functions": [], "files": []}"#)
+///     .respond(r#"{"supports_intent": true, "reasoning": "ok", "relevant_changes": []}"#);
+/// let options = AnalysisOptions {
+///     llm_client: Some(LlmClient::mock(mock.clone())),
+///     ..Default::default()
+/// };
+/// ```
+#[derive(Clone, Default)]
+pub struct MockProvider {
+    state: Arc<Mutex<MockState>>,
+}
+
+impl MockProvider {
+    /// Provider without any reply yet
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Reply with `reply` to requests no rule answers
+    pub fn respond(self, reply: &str) -> Self {
+        self.state.lock().unwrap().default_reply = Some(reply.to_string());
+        self
+    }
+
+    /// Reply with `reply` to requests with a message containing `needle`
+    pub fn respond_when(self, needle: &str, reply: &str) -> Self {
+        let (needle, reply) = (needle.to_string(), reply.to_string());
+        self.rule(move |call| contains(call, &needle).then(|| Ok(reply.clone().into())))
+    }
+
+    /// Reply with the concatenated `tokens` to requests with a message containing `needle`,
+    /// giving each token's log-probability like providers do when asked for logprobs
+    pub fn respond_with_logprobs(self, needle: &str, tokens: &[(&str, f32)]) -> Self {
+        let needle = needle.to_string();
+        let reply = MockReply {
+            content: tokens.iter().map(|(token, _)| *token).collect(),
+            logprobs: Some(
+                tokens
+                    .iter()
+                    .map(|(token, logprob)| (token.to_string(), *logprob))
+                    .collect(),
+            ),
+            ..Default::default()
+        };
+        self.rule(move |call| contains(call, &needle).then(|| Ok(reply.clone())))
+    }
+
+    /// Refuse requests with a message containing `needle`, replying with a `refusal` message
+    /// and no content
+    pub fn refuse_when(self, needle: &str, refusal: &str) -> Self {
+        let needle = needle.to_string();
+        let reply = MockReply {
+            refusal: Some(refusal.to_string()),
+            ..Default::default()
+        };
+        self.rule(move |call| contains(call, &needle).then(|| Ok(reply.clone())))
+    }
+
+    /// Stop the replies to requests with a message containing `needle` with the
+    /// `content_filter` finish reason
+    pub fn filter_when(self, needle: &str) -> Self {
+        let needle = needle.to_string();
+        let reply = MockReply {
+            filtered: true,
+            ..Default::default()
+        };
+        self.rule(move |call| contains(call, &needle).then(|| Ok(reply.clone())))
+    }
+
+    /// Reply with what `rule` returns, when it returns something
+    pub fn respond_with(
+        self,
+        rule: impl Fn(&MockCall) -> Option<String> + Send + Sync + 'static,
+    ) -> Self {
+        self.rule(move |call| rule(call).map(|reply| Ok(reply.into())))
+    }
+
+    /// Fail requests with a message containing `needle` with an API error saying `message`
+    pub fn fail_when(self, needle: &str, message: &str) -> Self {
+        let (needle, message) = (needle.to_string(), message.to_string());
+        self.rule(move |call| contains(call, &needle).then(|| Err(message.clone())))
+    }
+
+    /// Fail the next request, whatever it is, with an API error saying `message`
+    ///
+    /// Each call queues one more failure, which come before any rule.
+    pub fn fail_next(&self, message: &str) {
+        self.state
+            .lock()
+            .unwrap()
+            .failures
+            .push_back(message.to_string());
+    }
+
+    /// Every request received so far, oldest first
+    pub fn calls(&self) -> Vec<MockCall> {
+        self.state.lock().unwrap().calls.clone()
+    }
+
+    pub fn call_count(&self) -> usize {
+        self.state.lock().unwrap().calls.len()
+    }
+
+    fn rule(
+        self,
+        rule: impl Fn(&MockCall) -> Option<Result<MockReply, String>> + Send + Sync + 'static,
+    ) -> Self {
+        self.state.lock().unwrap().rules.push(Box::new(rule));
+        self
+    }
+
+    /// Record `request` and answer it like the chat completions endpoint would
+    pub(crate) fn create(
+        &self,
+        request: &CreateChatCompletionRequest,
+    ) -> Result<CreateChatCompletionResponse, ApiError> {
+        let call = MockCall {
+            model: request.model.clone(),
+            messages: crate::openai::prompt_messages(&request.messages),
+            logprobs: request.logprobs == Some(true),
+        };
+        let mut state = self.state.lock().unwrap();
+        let reply = match state.failures.pop_front() {
+            Some(message) => Err(message),
+            None => state
+                .rules
+                .iter()
+                .find_map(|rule| rule(&call))
+                .or_else(|| state.default_reply.clone().map(|reply| Ok(reply.into())))
+                .unwrap_or_else(|| {
+                    Err(format!(
+                        "MockProvider has no reply for request #{}",
+                        state.calls.len() + 1
+                    ))
+                }),
+        };
+        let prompt_tokens = estimate_tokens(&call.prompt());
+        state.calls.push(call);
+        drop(state);
+
+        let reply = reply.map_err(|message| ApiError {
+            message,
+            r#type: Some("mock_error".to_string()),
+            param: None,
+            code: None,
+        })?;
+        let completion_tokens = estimate_tokens(&reply.content);
+        let logprobs = reply.logprobs.map(|tokens| {
+            let content: Vec<_> = tokens
+                .into_iter()
+                .map(|(token, logprob)| {
+                    serde_json::json!({
+                        "token": token,
+                        "logprob": logprob,
+                        "bytes": null,
+                        "top_logprobs": []
+                    })
+                })
+                .collect();
+            serde_json::json!({"content": content, "refusal": null})
+        });
+        serde_json::from_value(serde_json::json!({
+            "id": "chatcmpl-mock",
+            "object": "chat.completion",
+            "created": 0,
+            "model": request.model,
+            "choices": [{
+                "index": 0,
+                "message": {
+                    "role": "assistant",
+                    "content": reply.refusal.is_none().then_some(&reply.content),
+                    "refusal": reply.refusal
+                },
+                "finish_reason": if reply.filtered { "content_filter" } else { "stop" },
+                "logprobs": logprobs
+            }],
+            "usage": {
+                "prompt_tokens": prompt_tokens,
+                "completion_tokens": completion_tokens,
+                "total_tokens": prompt_tokens + completion_tokens
+            }
+        }))
+        .map_err(|e| ApiError {
+            message: format!("MockProvider built an invalid response: {}", e),
+            r#type: Some("mock_error".to_string()),
+            param: None,
+            code: None,
+        })
+    }
+}
+
+impl std::fmt::Debug for MockProvider {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("MockProvider")
+            .field("calls", &self.call_count())
+            .finish_non_exhaustive()
+    }
+}
+
+fn contains(call: &MockCall, needle: &str) -> bool {
+    call.messages
+        .iter()
+        .any(|message| message.content.contains(needle))
+}
@@ -0,0 +1,131 @@
+use std::error::Error;
+use std::path::Path;
+
+use crate::types::IntentVerificationResult;
+
+/// Service a notification channel posts to
+#[derive(Debug, Clone, Copy, PartialEq, Eq, serde::Serialize, serde::Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum NotifyKind {
+    /// Slack incoming webhook
+    Slack,
+    /// Discord channel webhook
+    Discord,
+    /// Any endpoint accepting a JSON POST
+    Webhook,
+}
+
+#[derive(Debug, Clone, PartialEq, serde::Serialize, serde::Deserialize)]
+pub struct NotifyChannel {
+    pub kind: NotifyKind,
+    pub url: String,
+}
+
+/// Where verdict summaries are posted, read from a TOML file
+///
+/// ```toml
+/// report_url = "https://ci.example.com/runs/42/report.html"
+///
+/// [[channels]]
+/// kind = "slack"
+/// url = "https://hooks.slack.com/services/T000/B000/XXXX"
+/// ```
+#[derive(Debug, Clone, Default, PartialEq, serde::Serialize, serde::Deserialize)]
+pub struct NotifyConfig {
+    /// Link to the full report included in every notification
+    #[serde(default)]
+    pub report_url: Option<String>,
+    #[serde(default)]
+    pub channels: Vec<NotifyChannel>,
+}
+
+impl NotifyConfig {
+    pub fn from_toml(text: &str) -> Result<Self, Box<dyn Error>> {
+        Ok(toml::from_str(text)?)
+    }
+
+    pub fn load(path: impl AsRef<Path>) -> Result<Self, Box<dyn Error>> {
+        Self::from_toml(&std::fs::read_to_string(path)?)
+    }
+}
+
+/// One-line verdict, e.g. `✅ Intent fulfilled (confidence 85%): 2 out of 3 changed files ...`
+pub fn verdict_summary(result: &IntentVerificationResult) -> String {
+    let verdict = if result.is_intent_fulfilled {
+        "✅ Intent fulfilled"
+    } else {
+        "❌ Intent not fulfilled"
+    };
+    let partial = if result.is_partial {
+        ", partial analysis"
+    } else {
+        ""
+    };
+    format!(
+        "{} (confidence {:.0}%{}): {}",
+        verdict,
+        result.confidence * 100.0,
+        partial,
+        result.explanation
+    )
+}
+
+/// JSON body posted to a channel of the given kind
+pub fn notification_payload(
+    kind: NotifyKind,
+    result: &IntentVerificationResult,
+    report_url: Option<&str>,
+) -> serde_json::Value {
+    let summary = verdict_summary(result);
+    match kind {
+        NotifyKind::Slack => serde_json::json!({
+            "text": match report_url {
+                Some(url) => format!("{}\n<{}|Full report>", summary, url),
+                None => summary,
+            }
+        }),
+        NotifyKind::Discord => serde_json::json!({
+            "content": match report_url {
+                Some(url) => format!("{}\n[Full report]({})", summary, url),
+                None => summary,
+            }
+        }),
+        NotifyKind::Webhook => serde_json::json!({
+            "summary": summary,
+            "is_intent_fulfilled": result.is_intent_fulfilled,
+            "confidence": result.confidence,
+            "is_partial": result.is_partial,
+            "explanation": result.explanation,
+            "report_url": report_url,
+        }),
+    }
+}
+
+/// Post the verdict summary to every configured channel
+///
+/// All channels are tried; the error lists the ones that failed.
+pub async fn send_notifications(
+    config: &NotifyConfig,
+    result: &IntentVerificationResult,
+) -> Result<(), Box<dyn Error>> {
+    let client = reqwest::Client::new();
+    let mut failures = Vec::new();
+    for channel in &config.channels {
+        let payload = notification_payload(channel.kind, result, config.report_url.as_deref());
+        let sent = client
+            .post(&channel.url)
+            .json(&payload)
+            .send()
+            .await
+            .and_then(|response| response.error_for_status());
+        if let Err(e) = sent {
+            failures.push(format!("{:?} ({}): {}", channel.kind, channel.url, e));
+        }
+    }
+
+    if failures.is_empty() {
+        Ok(())
+    } else {
+        Err(format!("Failed to notify {}", failures.join("; ")).into())
+    }
+}
@@ -0,0 +1,57 @@
+use std::sync::Arc;
+
+use crate::types::{FileAnalysisResult, IntentVerificationResult, PromptPreview, TestTargets};
+
+/// Hooks called as a verification goes through its pipeline, for logging, metering or keeping
+/// intermediate artifacts
+///
+/// Every method does nothing by default. Hooks may be called from any runtime thread, and
+/// concurrently when files are analyzed in parallel, so they should return quickly.
+pub trait AnalysisObserver: Send + Sync {
+    /// A repository is about to be cloned or fetched
+    fn on_clone_start(&self, _repo_url: &str) {}
+
+    /// The `attempt`-th clone or fetch of a repository failed with a network error and is
+    /// retried after a backoff
+    #[cfg(feature = "git")]
+    fn on_clone_retry(&self, _repo_url: &str, _attempt: u32, _error: &git2::Error) {}
+
+    /// The test targets are known, whether extracted by the model, given in the options or
+    /// reused from an earlier run
+    fn on_targets_extracted(&self, _targets: &TestTargets) {}
+
+    /// A prompt was built; it is sent to the model next, except in a dry run
+    fn on_prompt_built(&self, _prompt: &PromptPreview) {}
+
+    /// The model answered `prompt`
+    fn on_llm_response(&self, _prompt: &PromptPreview, _response: &str) {}
+
+    /// A changed file's analysis is done
+    fn on_file_result(&self, _result: &FileAnalysisResult) {}
+
+    /// The verification finished with `result`
+    fn on_complete(&self, _result: &IntentVerificationResult) {}
+}
+
+/// Observer set on [`crate::AnalysisOptions::observer`]
+///
+/// Clones call the same observer.
+#[derive(Clone)]
+pub struct ObserverHandle(Arc<dyn AnalysisObserver>);
+
+impl ObserverHandle {
+    /// Share `observer`; keep a clone of the `Arc` to read what it collected afterwards
+    pub fn new(observer: Arc<impl AnalysisObserver + 'static>) -> Self {
+        ObserverHandle(observer)
+    }
+
+    pub fn observer(&self) -> &dyn AnalysisObserver {
+        self.0.as_ref()
+    }
+}
+
+impl std::fmt::Debug for ObserverHandle {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.write_str("ObserverHandle")
+    }
+}
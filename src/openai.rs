@@ -1,20 +1,105 @@
-use async_openai::{
-    Client,
-    config::OpenAIConfig,
-    types::{
-        ChatCompletionRequestAssistantMessage, ChatCompletionRequestMessage,
-        ChatCompletionRequestUserMessage, ChatCompletionRequestUserMessageContent,
-        CreateChatCompletionRequest,
-    },
+use async_openai::error::OpenAIError;
+use async_openai::types::chat::{
+    ChatCompletionRequestAssistantMessage, ChatCompletionRequestMessage,
+    ChatCompletionRequestUserMessage, ChatCompletionRequestUserMessageContent,
+    CreateChatCompletionRequest, CreateChatCompletionResponse,
 };
+use tracing::Instrument;
 
-use crate::git::{read_test_targets_code, split_by_function};
+#[cfg(feature = "git")]
+use futures::Stream;
+use futures::{StreamExt, stream};
+use std::path::Path;
+#[cfg(feature = "git")]
+use std::sync::Arc;
+use std::sync::Mutex;
+use std::sync::atomic::{AtomicUsize, Ordering};
+
+use crate::ambiguity::{intent_ambiguity, needs_clarification_result};
+#[cfg(feature = "git")]
+use crate::analyzers::analyze_solution;
+use crate::api_surface::{
+    apply_breaking_changes, breaking_change_findings, claims_non_breaking, detect_breaking_changes,
+};
+use crate::archetype::apply_archetype;
+use crate::binary::{binary_changes, is_unreadable, unexpected_binary_findings};
+use crate::calibration::{any_block_supports, apply_calibration, support_probability};
+use crate::change_summary::generate_change_summary;
+use crate::checkpoint::Checkpoint;
+use crate::citations::{Citation, verify_citations};
+#[cfg(feature = "git")]
+use crate::code_parser::function_changes;
+use crate::code_parser::is_source_file_by_name;
+use crate::codeowners::{CODEOWNERS_PATHS, CodeOwners, apply_code_owners};
+#[cfg(feature = "git")]
+use crate::codeowners::{apply_code_owners_under, read_code_owners};
+use crate::coverage::coverage_evidence;
+use crate::criteria::{changes_as_diff, check_acceptance_criteria, merge_acceptance_criteria};
+#[cfg(feature = "git")]
+use crate::cross_repo::{CrossRepoResult, RepoChanges, check_repo_names};
+use crate::docs_drift::detect_docs_drift;
+use crate::escalation::apply_escalation;
+#[cfg(feature = "git")]
+use crate::execution::{
+    TestRunResult, merge_counterfactual_run, merge_test_run, run_tests_on_solution,
+};
+#[cfg(feature = "git")]
+use crate::git::{
+    commit_and_parent, get_git_changed_files_async_with_options,
+    get_git_changed_files_lazy_with_options, read_test_targets_code_async_with_options,
+    snapshot_repository, spawn_git,
+};
+use crate::git::{diff_hunks, split_by_function};
+use crate::incremental::{context_hash, targets_key};
+use crate::infra::infra_review_instruction;
+#[cfg(feature = "git")]
+use crate::intents::{IntentVerdict, MultiIntentResult};
+use crate::language_stats::language_stats;
+use crate::llm_client::{LlmClient, llm_client_for};
+use crate::migrations::{apply_migration_findings, scan_migrations};
+use crate::options::AnalysisOptions;
+use crate::prioritization::{SkipReason, limit_changes, prioritize_change_refs};
+use crate::privacy::check_llm_endpoint;
+use crate::profile::{VerificationProfile, apply_security_profile, parse_security_issues};
+#[cfg(feature = "git")]
+use crate::progress::Cancelled;
+use crate::progress::{Progress, ProgressStage};
+use crate::prompts::{PromptTemplates, render};
+use crate::redaction::{Redaction, RedactionConfig, Redactor, redaction_report};
+use crate::refusal::{filtered_request_reason, refusal_reason, sanitize_code, sanitize_targets};
+use crate::regression::apply_regression;
+use crate::risk::apply_risk_scores;
+use crate::scope::apply_scope;
+use crate::secrets::{apply_secret_findings, scan_for_secrets};
+use crate::shortcuts::{apply_shortcut_findings, detect_hardcoded_answers, test_sources};
+use crate::similarity::{apply_similarity_findings, check_similarity};
+use crate::snapshot::RepoSnapshot;
+use crate::stronger_model::retry_with_stronger_model;
+use crate::target_heuristics::extract_test_targets_heuristically;
+use crate::target_resolution::target_resolution;
 use crate::types::{
-    FileIntentAnalysis, IntentVerificationResult, TestTargets, TestTargetsWithCode,
+    AnalysisOutcome, ChangeLocation, FileAnalysisResult, FileIntentAnalysis, Finding,
+    IntentVerificationResult, PromptMessage, PromptPreview, PromptStage, ResultMetadata,
+    TestTargets, TestTargetsWithCode, Warning, WarningKind,
 };
-use crate::utils::extract_json_from_response;
+use crate::utils::{estimate_tokens, extract_json_from_response, locate_snippet};
+#[cfg(feature = "git")]
+use crate::validation::validate_inputs;
+use crate::validation::{ValidationError, validate_api_key, validate_intent};
+use crate::vcs::{VersionControl, read_test_targets_code_from};
+#[cfg(feature = "git")]
+use crate::verifier::{IntentVerifier, VerificationRequest};
 use crate::{ChangeType, FileChange};
 
+/// Model used when the caller doesn't specify one
+pub const DEFAULT_MODEL: &str = "gpt-3.5-turbo";
+
+/// Unchanged lines shown around each change when a modified file is prompted as diff hunks
+pub(crate) const DIFF_CONTEXT_LINES: usize = 3;
+
+/// Size above which a file's prompt is split into several blocks
+const MAX_BLOCK_CHARS: usize = 12_000;
+
 /// Internal async OpenAI function
 pub async fn ask_openai_internal(
     prompt: &str,
@@ -22,65 +107,286 @@ pub async fn ask_openai_internal(
     model: Option<&str>,
     base_url: Option<&str>,
 ) -> Result<String, Box<dyn std::error::Error>> {
-    let mut config = OpenAIConfig::new().with_api_key(api_key);
-
-    if let Some(url) = base_url {
-        config = config.with_api_base(url);
-    }
+    ask_openai_with_options(
+        prompt,
+        api_key,
+        model,
+        base_url,
+        &AnalysisOptions::default(),
+    )
+    .await
+}
 
-    let client = Client::with_config(config);
+/// Same as [`ask_openai_internal`], honoring the timeout and proxy in `options`
+pub(crate) async fn ask_openai_with_options(
+    prompt: &str,
+    api_key: &str,
+    model: Option<&str>,
+    base_url: Option<&str>,
+    options: &AnalysisOptions,
+) -> Result<String, Box<dyn std::error::Error>> {
+    chat_with_options(
+        vec![user_message(prompt)],
+        api_key,
+        model,
+        base_url,
+        options,
+    )
+    .await
+}
 
-    let messages = vec![ChatCompletionRequestMessage::User(
-        ChatCompletionRequestUserMessage {
-            content: ChatCompletionRequestUserMessageContent::Text(prompt.to_string()),
-            name: None,
-        },
-    )];
+/// Send a whole conversation and return the reply, honoring the timeout and proxy in `options`
+pub(crate) async fn chat_with_options(
+    messages: Vec<ChatCompletionRequestMessage>,
+    api_key: &str,
+    model: Option<&str>,
+    base_url: Option<&str>,
+    options: &AnalysisOptions,
+) -> Result<String, Box<dyn std::error::Error>> {
+    let client = llm_client_for(api_key, base_url, options)?;
 
     let request = CreateChatCompletionRequest {
-        model: model.unwrap_or("gpt-3.5-turbo").to_string(),
+        model: model.unwrap_or(DEFAULT_MODEL).to_string(),
         messages,
         ..Default::default()
     };
 
-    let response = client.chat().create(request).await?;
+    let response = create_chat_completion(&client, request, None, options).await?;
     let reply = response
         .choices
-        .get(0)
+        .first()
         .and_then(|c| c.message.content.clone())
         .unwrap_or_else(|| "No response.".to_string());
 
     Ok(reply)
 }
 
+/// Send a chat request once the rate limiter allows it, in an `llm_request` span recording the
+/// model, file, token usage and latency
+async fn create_chat_completion(
+    client: &LlmClient,
+    request: CreateChatCompletionRequest,
+    file_path: Option<&str>,
+    options: &AnalysisOptions,
+) -> Result<CreateChatCompletionResponse, OpenAIError> {
+    let span = tracing::info_span!(
+        "llm_request",
+        model = %request.model,
+        file = file_path,
+        prompt_tokens = tracing::field::Empty,
+        completion_tokens = tracing::field::Empty,
+        latency_ms = tracing::field::Empty,
+    );
+    async {
+        if let Some(policy) = &options.local_only
+            && !client.is_mock()
+            && !policy.allows_llm_endpoint(client.base_url())
+        {
+            return Err(OpenAIError::InvalidArgument(
+                ValidationError::LlmEndpointNotAllowed {
+                    endpoint: client
+                        .base_url()
+                        .unwrap_or("the default OpenAI endpoint")
+                        .to_string(),
+                }
+                .to_string(),
+            ));
+        }
+        let request = match &options.redaction {
+            Some(config) => {
+                redact_request(request, config).map_err(OpenAIError::InvalidArgument)?
+            }
+            None => request,
+        };
+        options.acquire_request_slot().await;
+        let started = web_time::Instant::now();
+        let response = client.create(request).await;
+        let span = tracing::Span::current();
+        span.record("latency_ms", started.elapsed().as_millis() as u64);
+        if let Ok(response) = &response
+            && let Some(usage) = &response.usage
+        {
+            span.record("prompt_tokens", usage.prompt_tokens);
+            span.record("completion_tokens", usage.completion_tokens);
+        }
+        response
+    }
+    .instrument(span)
+    .await
+}
+
+/// `request` with what `config` covers stripped from its messages
+fn redact_request(
+    mut request: CreateChatCompletionRequest,
+    config: &RedactionConfig,
+) -> Result<CreateChatCompletionRequest, String> {
+    let redactor =
+        Redactor::new(config).map_err(|e| format!("Invalid redaction pattern: {}", e))?;
+    let mut messages = serde_json::to_value(&request.messages).map_err(|e| e.to_string())?;
+    redactor.redact_json(&mut messages);
+    request.messages = serde_json::from_value(messages).map_err(|e| e.to_string())?;
+    Ok(request)
+}
+
+pub(crate) fn user_message(text: &str) -> ChatCompletionRequestMessage {
+    ChatCompletionRequestMessage::User(ChatCompletionRequestUserMessage {
+        content: ChatCompletionRequestUserMessageContent::Text(text.to_string()),
+        name: None,
+    })
+}
+
+pub(crate) fn assistant_message(text: &str) -> ChatCompletionRequestMessage {
+    ChatCompletionRequestMessage::Assistant(ChatCompletionRequestAssistantMessage {
+        content: Some(text.into()),
+        name: None,
+        ..Default::default()
+    })
+}
+
+/// Tell the observer, if any, about a single-prompt request about to be sent
+pub(crate) fn observe_prompt(options: &AnalysisOptions, stage: PromptStage, prompt: &str) {
+    options.observe(|observer| {
+        observer.on_prompt_built(&prompt_preview(stage, None, &[user_message(prompt)]))
+    });
+}
+
+/// Add a single-prompt model exchange to the evidence being recorded and tell the observer,
+/// if any
+pub(crate) fn record_exchange(
+    options: &AnalysisOptions,
+    stage: PromptStage,
+    prompt: &str,
+    response: &str,
+) {
+    if options.evidence.is_none() && options.observer.is_none() {
+        return;
+    }
+    let preview = prompt_preview(stage, None, &[user_message(prompt)]);
+    options.observe(|observer| observer.on_llm_response(&preview, response));
+    if let Some(recorder) = &options.evidence {
+        recorder.record_exchange(preview, response);
+    }
+}
+
+/// Keep a prompt a dry run built instead of sending it, telling the observer, if any
+pub(crate) fn dry_run_prompt(
+    options: &AnalysisOptions,
+    prompts: &mut Vec<PromptPreview>,
+    mut prompt: PromptPreview,
+) {
+    // Previews show what would be sent
+    if let Some(config) = &options.redaction
+        && let Ok(redactor) = Redactor::new(config)
+    {
+        for message in &mut prompt.messages {
+            message.content = redactor.redact(&message.content).0;
+        }
+    }
+    options.observe(|observer| observer.on_prompt_built(&prompt));
+    prompts.push(prompt);
+}
+
+/// Record chat messages that a dry run builds instead of sending
+pub(crate) fn prompt_preview(
+    stage: PromptStage,
+    file_path: Option<&str>,
+    messages: &[ChatCompletionRequestMessage],
+) -> PromptPreview {
+    let messages = prompt_messages(messages);
+    // Chat formatting adds a few tokens per message
+    let estimated_tokens = messages
+        .iter()
+        .map(|m| estimate_tokens(&m.content) + 4)
+        .sum();
+
+    PromptPreview {
+        stage,
+        file_path: file_path.map(str::to_string),
+        messages,
+        estimated_tokens,
+    }
+}
+
+/// The role and text of each chat message
+pub(crate) fn prompt_messages(messages: &[ChatCompletionRequestMessage]) -> Vec<PromptMessage> {
+    messages
+        .iter()
+        .map(|message| {
+            let json = serde_json::to_value(message).unwrap_or_default();
+            let content = match &json["content"] {
+                serde_json::Value::String(text) => text.clone(),
+                serde_json::Value::Array(parts) => parts
+                    .iter()
+                    .filter_map(|part| part["text"].as_str())
+                    .collect::<Vec<_>>()
+                    .join("\n"),
+                _ => String::new(),
+            };
+            PromptMessage {
+                role: json["role"].as_str().unwrap_or_default().to_string(),
+                content,
+            }
+        })
+        .collect()
+}
+
 pub async fn extract_test_targets_with_ai(
     prompt: &str,
     api_key: &str,
     model: Option<&str>,
     base_url: Option<&str>,
 ) -> Result<TestTargets, Box<dyn std::error::Error>> {
-    let extraction_prompt = format!(
-        r#"Extract from the following prompt the list of function names and file path that the user expects to work.
-
-Respond ONLY in this strict JSON format:
-{{
-  "functions": ["..."],
-  "files": ["..."]
-}}
-
-Prompt:
-"{prompt}"
-"#,
-        prompt = prompt
-    );
+    extract_test_targets_with_options(
+        prompt,
+        api_key,
+        model,
+        base_url,
+        &AnalysisOptions::default(),
+    )
+    .await
+}
 
-    let raw_response = ask_openai_internal(&extraction_prompt, api_key, model, base_url).await?;
+/// Same as [`extract_test_targets_with_ai`], honoring the timeout and proxy in `options`
+///
+/// Prompts that name their functions and files literally are answered without the model, see
+/// `extract_test_targets_heuristically`.
+#[tracing::instrument(name = "extract_targets", skip_all)]
+pub(crate) async fn extract_test_targets_with_options(
+    prompt: &str,
+    api_key: &str,
+    model: Option<&str>,
+    base_url: Option<&str>,
+    options: &AnalysisOptions,
+) -> Result<TestTargets, Box<dyn std::error::Error>> {
+    let heuristic = extract_test_targets_heuristically(prompt);
+    if heuristic.is_conclusive() {
+        return Ok(heuristic.targets);
+    }
+
+    let extraction_prompt = target_extraction_prompt(prompt, options);
+    observe_prompt(options, PromptStage::TargetExtraction, &extraction_prompt);
+
+    let raw_response =
+        ask_openai_with_options(&extraction_prompt, api_key, model, base_url, options).await?;
+    record_exchange(
+        options,
+        PromptStage::TargetExtraction,
+        &extraction_prompt,
+        &raw_response,
+    );
 
-    let parsed: TestTargets = serde_json::from_str(&raw_response)?;
+    let parsed: TestTargets = serde_json::from_str(&extract_json_from_response(&raw_response))?;
 
     Ok(parsed)
 }
 
+fn target_extraction_prompt(prompt: &str, options: &AnalysisOptions) -> String {
+    render(
+        &options.prompt_templates().target_extraction,
+        &[("intent", prompt)],
+    )
+}
+
 /// Analyze git changes to verify if they fulfill the intended test requirements
 ///
 /// # Arguments
@@ -96,6 +402,11 @@ Prompt:
 ///
 /// # Returns
 /// * `IntentVerificationResult` - Analysis of whether changes fulfill the intent
+///
+/// Kept for compatibility; [`IntentVerifier::builder`] names each argument and takes options
+/// and hooks.
+#[cfg(feature = "git")]
+#[allow(clippy::too_many_arguments)]
 pub async fn verify_intent(
     test_repo_url: &str,
     test_commit: &str,
@@ -107,86 +418,1314 @@ pub async fn verify_intent(
     model: Option<&str>,
     base_url: Option<&str>,
 ) -> Result<IntentVerificationResult, Box<dyn std::error::Error>> {
-    // First, extract test targets from the user intent using AI
-    let test_targets = extract_test_targets_with_ai(user_intent, api_key, model, base_url).await?;
+    let mut builder = IntentVerifier::builder()
+        .request(VerificationRequest {
+            test_repo_url: test_repo_url.to_string(),
+            test_commit: test_commit.to_string(),
+            solution_repo_url: solution_repo_url.to_string(),
+            solution_commit1: solution_commit1.to_string(),
+            solution_commit2: solution_commit2.to_string(),
+            user_intent: user_intent.to_string(),
+        })
+        .api_key(api_key);
+    if let Some(model) = model {
+        builder = builder.model(model);
+    }
+    if let Some(base_url) = base_url {
+        builder = builder.base_url(base_url);
+    }
+    builder.build()?.verify().await
+}
 
-    // Then, read the actual code of the test targets from the repository at the specified commit
-    let targets_with_code = read_test_targets_code(&test_targets, test_repo_url, test_commit)?;
+/// Same as [`verify_intent`], with additional [`AnalysisOptions`]
+#[cfg(feature = "git")]
+#[allow(clippy::too_many_arguments)]
+pub async fn verify_intent_with_options(
+    test_repo_url: &str,
+    test_commit: &str,
+    solution_repo_url: &str,
+    solution_commit1: &str,
+    solution_commit2: &str,
+    user_intent: &str,
+    api_key: &str,
+    model: Option<&str>,
+    base_url: Option<&str>,
+    options: &AnalysisOptions,
+) -> Result<IntentVerificationResult, Box<dyn std::error::Error>> {
+    options.check_cancelled()?;
+    let request = VerificationRequest::new(
+        solution_repo_url,
+        solution_commit1,
+        solution_commit2,
+        user_intent,
+    )
+    .with_tests(test_repo_url, test_commit);
+    validate_inputs(&request, api_key, base_url, options)?;
 
-    // Get changed files from git
-    let file_changes =
-        crate::git::get_git_changed_files(solution_repo_url, solution_commit1, solution_commit2)?;
+    let mut result = verify_changes(
+        user_intent,
+        api_key,
+        model,
+        base_url,
+        options,
+        async |targets| {
+            read_test_targets_code_async_with_options(targets, test_repo_url, test_commit, options)
+                .await
+        },
+        async || {
+            let file_changes = get_git_changed_files_async_with_options(
+                solution_repo_url,
+                solution_commit1,
+                solution_commit2,
+                options,
+            )
+            .await?;
+            eprintln!(
+                "📝 Found {} changed files between commits {} and {}",
+                file_changes.len(),
+                solution_commit1,
+                solution_commit2
+            );
+            Ok(file_changes)
+        },
+        async |file_changes| {
+            analyze_solution(solution_repo_url, solution_commit2, file_changes, options).await
+        },
+    )
+    .await?;
+    if result.needs_clarification.is_some() {
+        complete(&mut result, options);
+        return Ok(result);
+    }
+    if options.code_owners {
+        attach_code_owners(
+            &mut result,
+            read_code_owners(solution_repo_url, solution_commit2, options).await,
+            "",
+        );
+    }
+
+    let runs = execute_tests(
+        test_repo_url,
+        test_commit,
+        solution_repo_url,
+        solution_commit1,
+        solution_commit2,
+        options,
+    )
+    .await?;
+    if !runs.is_empty() {
+        merge_test_runs(&mut result, &runs);
+        if let Some(previous) = &options.previous_result {
+            apply_regression(&mut result, previous);
+        }
+        apply_escalation(&mut result, options.escalation_threshold());
+    }
+    complete(&mut result, options);
+    Ok(result)
+}
+
+/// Verify only the change to one function between two commits, e.g. for a fine-grained check
+/// inside a big diff
+///
+/// The function's bodies before and after stand in for the changed files, so the model is
+/// asked about that change alone; the tests are read from `commit2`. Fails when no file
+/// changes the function.
+#[cfg(feature = "git")]
+#[allow(clippy::too_many_arguments)]
+pub async fn verify_function_intent(
+    function_name: &str,
+    repo_url: &str,
+    commit1: &str,
+    commit2: &str,
+    user_intent: &str,
+    api_key: &str,
+    model: Option<&str>,
+    base_url: Option<&str>,
+    options: &AnalysisOptions,
+) -> Result<IntentVerificationResult, Box<dyn std::error::Error>> {
+    options.check_cancelled()?;
+    if function_name.trim().is_empty() {
+        return Err("The function name is empty".into());
+    }
+    validate_inputs(
+        &VerificationRequest::new(repo_url, commit1, commit2, user_intent),
+        api_key,
+        base_url,
+        options,
+    )?;
+
+    let mut result = verify_changes(
+        user_intent,
+        api_key,
+        model,
+        base_url,
+        options,
+        async |targets| {
+            read_test_targets_code_async_with_options(targets, repo_url, commit2, options).await
+        },
+        async || {
+            let file_changes =
+                get_git_changed_files_async_with_options(repo_url, commit1, commit2, options)
+                    .await?;
+            let changes = function_changes(&file_changes, function_name);
+            if changes.is_empty() {
+                return Err(format!(
+                    "Function {} isn't changed between {} and {}",
+                    function_name, commit1, commit2
+                )
+                .into());
+            }
+            eprintln!(
+                "📝 Found {} changed definitions of {}",
+                changes.len(),
+                function_name
+            );
+            Ok(changes)
+        },
+        // Linters and docs look at whole files, not at one function
+        async |_| (vec![], vec![]),
+    )
+    .await?;
+    complete(&mut result, options);
+    Ok(result)
+}
+
+/// Everything the per-file analyses of a stream share
+#[cfg(feature = "git")]
+struct FileStreamContext {
+    checkpoint: Option<Checkpoint>,
+    /// Key of the analyses in the checkpoint
+    context_hash: String,
+    targets_with_code: TestTargetsWithCode,
+    user_intent: String,
+    api_key: String,
+    model: Option<String>,
+    base_url: Option<String>,
+    options: AnalysisOptions,
+}
+
+/// Verify the changes of a single commit, diffed against its first parent
+///
+/// Same as [`verify_intent_with_options`] with the tests read from `commit` and the parent
+/// resolved from the repository. `commit` may be any revision, e.g. a branch name; merge
+/// commits are diffed against their first parent and root commits fail.
+#[cfg(feature = "git")]
+#[allow(clippy::too_many_arguments)]
+pub async fn analyze_commit(
+    repo_url: &str,
+    commit: &str,
+    user_intent: &str,
+    api_key: &str,
+    model: Option<&str>,
+    base_url: Option<&str>,
+    options: &AnalysisOptions,
+) -> Result<IntentVerificationResult, Box<dyn std::error::Error>> {
+    options.check_cancelled()?;
+    validate_inputs(
+        &VerificationRequest::new(repo_url, commit, commit, user_intent),
+        api_key,
+        base_url,
+        options,
+    )?;
+    let (url, rev) = (repo_url.to_string(), commit.to_string());
+    let (commit, parent) = spawn_git(options, move |options| {
+        commit_and_parent(&url, &rev, options)
+    })
+    .await?;
+    eprintln!("🔗 Diffing {} against its parent {}", commit, parent);
+    verify_intent_with_options(
+        repo_url,
+        &commit,
+        repo_url,
+        &parent,
+        &commit,
+        user_intent,
+        api_key,
+        model,
+        base_url,
+        options,
+    )
+    .await
+}
+
+/// Analyze each changed file like [`verify_intent_with_options`], yielding every file's result
+/// as soon as it's done instead of waiting for the whole verification
+///
+/// Results arrive in completion order, up to `options.concurrency()` files at a time. Each
+/// file's contents are read only when its analysis starts and released when it ends, so memory
+/// stays bounded however many files changed.
+/// Warnings from extracting and reading the test targets come with the first result. Fails
+/// only when the changes can't be read. With `options.checkpoint`, files analyzed by an
+/// earlier, interrupted stream are yielded from the checkpoint; the file is left in place.
+#[cfg(feature = "git")]
+#[allow(clippy::too_many_arguments)]
+pub async fn analyze_repository_changes_stream(
+    test_repo_url: &str,
+    test_commit: &str,
+    solution_repo_url: &str,
+    solution_commit1: &str,
+    solution_commit2: &str,
+    user_intent: &str,
+    api_key: &str,
+    model: Option<&str>,
+    base_url: Option<&str>,
+    options: &AnalysisOptions,
+) -> Result<impl Stream<Item = FileAnalysisResult> + use<>, Box<dyn std::error::Error>> {
+    options.check_cancelled()?;
+    let request = VerificationRequest::new(
+        solution_repo_url,
+        solution_commit1,
+        solution_commit2,
+        user_intent,
+    )
+    .with_tests(test_repo_url, test_commit);
+    validate_inputs(&request, api_key, base_url, options)?;
+    let options = &*options.with_shared_llm_client(api_key, base_url);
+    let checkpoint = match options.checkpoint.as_ref().filter(|_| !options.dry_run) {
+        Some(path) => Some(Checkpoint::open(path)?),
+        None => None,
+    };
+    let mut warnings = Vec::new();
+    let test_targets = resolve_test_targets(
+        user_intent,
+        api_key,
+        model,
+        base_url,
+        options,
+        checkpoint.as_ref(),
+        &mut warnings,
+        &mut Vec::new(),
+    )
+    .await;
+    let targets_with_code = match read_test_targets_code_async_with_options(
+        &test_targets,
+        test_repo_url,
+        test_commit,
+        options,
+    )
+    .await
+    {
+        Ok(targets_with_code) => targets_with_code,
+        Err(e) => {
+            warnings.push(Warning {
+                kind: WarningKind::TargetLookup,
+                file_path: None,
+                message: format!("Failed to read test target code: {}", e),
+            });
+            TestTargetsWithCode {
+                targets: test_targets,
+                file_contents: vec![],
+                function_contents: vec![],
+                tested_sources: vec![],
+            }
+        }
+    };
+    options.check_cancelled()?;
+    let (url, from, to) = (
+        solution_repo_url.to_string(),
+        solution_commit1.to_string(),
+        solution_commit2.to_string(),
+    );
+    let (blobs, changes) = spawn_git(options, move |options| {
+        get_git_changed_files_lazy_with_options(&url, &from, &to, options)
+    })
+    .await?;
+    let blobs = Arc::new(blobs);
+
+    let context = Arc::new(FileStreamContext {
+        context_hash: context_hash(
+            user_intent,
+            model.unwrap_or(DEFAULT_MODEL),
+            &targets_with_code,
+            options,
+        ),
+        checkpoint,
+        targets_with_code,
+        user_intent: user_intent.to_string(),
+        api_key: api_key.to_string(),
+        model: model.map(str::to_string),
+        base_url: base_url.map(str::to_string),
+        options: options.clone(),
+    });
+    let mut setup_warnings = Some(warnings);
+    let observer = options.observer.clone();
+    Ok(stream::iter(changes)
+        .map(move |change| {
+            let context = Arc::clone(&context);
+            let blobs = Arc::clone(&blobs);
+            async move {
+                // Contents are read just before the file is analyzed and dropped right after
+                let path = change.path.clone();
+                let status = change.status.clone();
+                let (file_change, load_warning) =
+                    match spawn_git(&context.options, move |_| Ok(blobs.load(&change)?)).await {
+                        Ok(file_change) => (file_change, None),
+                        Err(e) => (
+                            FileChange {
+                                path: path.clone(),
+                                status,
+                                content: None,
+                                old_content: None,
+                            },
+                            Some(Warning {
+                                kind: WarningKind::FileAnalysis,
+                                file_path: Some(path),
+                                message: format!("Failed to read the file's contents: {}", e),
+                            }),
+                        ),
+                    };
+                let prompt_version = &context.options.prompt_templates().version;
+                if let Some(cached) = context.checkpoint.as_ref().and_then(|checkpoint| {
+                    checkpoint.get(&file_change, prompt_version, &context.context_hash)
+                }) {
+                    return FileAnalysisResult {
+                        analysis: cached.analysis,
+                        warnings: vec![],
+                        prompts: vec![],
+                        findings: cached.findings,
+                    };
+                }
+
+                let mut result = analyze_file_change(
+                    &file_change,
+                    &context.targets_with_code,
+                    &context.user_intent,
+                    &context.api_key,
+                    context.model.as_deref(),
+                    context.base_url.as_deref(),
+                    &context.options,
+                )
+                .await;
+                if let Some(checkpoint) = &context.checkpoint
+                    && result.warnings.is_empty()
+                    && context.options.check_cancelled().is_ok()
+                {
+                    checkpoint.save_analysis(
+                        &file_change,
+                        prompt_version,
+                        &context.context_hash,
+                        &result.analysis,
+                        &result.findings,
+                    );
+                }
+                result.warnings.extend(load_warning);
+                result
+            }
+        })
+        .buffer_unordered(options.concurrency())
+        .map(move |mut result| {
+            if let Some(mut warnings) = setup_warnings.take() {
+                warnings.append(&mut result.warnings);
+                result.warnings = warnings;
+            }
+            if let Some(handle) = &observer {
+                handle.observer().on_file_result(&result);
+            }
+            result
+        }))
+}
 
-    println!(
-        "📝 Found {} changed files between commits {} and {}",
+/// Analyze one file against the intent outside of any diff, as if the file had been added,
+/// e.g. for an ad-hoc review from an editor or a script
+///
+/// `path_or_content` is read as a path when a file exists there and taken as the file's
+/// contents otherwise. `language` (`rust`, `python`, `typescript` or `javascript`) is needed
+/// for contents and for paths without a recognized extension. The test targets the intent
+/// names are looked up in the file itself.
+#[allow(clippy::too_many_arguments)]
+pub async fn analyze_file(
+    path_or_content: &str,
+    language: Option<&str>,
+    user_intent: &str,
+    api_key: &str,
+    model: Option<&str>,
+    base_url: Option<&str>,
+    options: &AnalysisOptions,
+) -> Result<FileAnalysisResult, Box<dyn std::error::Error>> {
+    options.check_cancelled()?;
+    validate_intent(user_intent)?;
+    if options.calls_model() {
+        validate_api_key(api_key, base_url)?;
+    }
+    check_llm_endpoint(options, base_url)?;
+    let (path, content) = if Path::new(path_or_content).is_file() {
+        (
+            path_or_content.to_string(),
+            std::fs::read_to_string(path_or_content)?,
+        )
+    } else {
+        ("snippet".to_string(), path_or_content.to_string())
+    };
+    let path = match language {
+        Some(language) => {
+            let extension = language_extension(language)
+                .ok_or_else(|| format!("Unsupported language: {}", language))?;
+            if path.ends_with(&format!(".{}", extension)) {
+                path
+            } else {
+                format!("{}.{}", path, extension)
+            }
+        }
+        None if is_source_file_by_name(&path) => path,
+        None => return Err(format!("Can't tell the language of {}", path).into()),
+    };
+
+    let options = &*options.with_shared_llm_client(api_key, base_url);
+    let mut warnings = Vec::new();
+    let test_targets = resolve_test_targets(
+        user_intent,
+        api_key,
+        model,
+        base_url,
+        options,
+        None,
+        &mut warnings,
+        &mut Vec::new(),
+    )
+    .await;
+    let file = RepoSnapshot::from_files([(path.as_str(), content.as_str())]);
+    let targets_with_code = file.read_test_targets_code(&test_targets);
+
+    options.check_cancelled()?;
+    let file_change = FileChange {
+        path,
+        status: ChangeType::Added,
+        content: Some(content),
+        old_content: None,
+    };
+    let mut result = analyze_file_change(
+        &file_change,
+        &targets_with_code,
+        user_intent,
+        api_key,
+        model,
+        base_url,
+        options,
+    )
+    .await;
+    warnings.append(&mut result.warnings);
+    result.warnings = warnings;
+    options.observe(|observer| observer.on_file_result(&result));
+    Ok(result)
+}
+
+/// File extension of a language `analyze_file` accepts
+fn language_extension(language: &str) -> Option<&'static str> {
+    match language.to_lowercase().as_str() {
+        "rust" | "rs" => Some("rs"),
+        "python" | "py" => Some("py"),
+        "typescript" | "ts" => Some("ts"),
+        "javascript" | "js" => Some("js"),
+        _ => None,
+    }
+}
+
+/// Verify several intents against the same changes
+///
+/// The repositories are cloned, diffed, linted and tested once, and only the model stages run
+/// per intent. See `split_acceptance_criteria` to verify the criteria of one intent separately.
+#[cfg(feature = "git")]
+#[allow(clippy::too_many_arguments)]
+pub async fn verify_intents_with_options(
+    test_repo_url: &str,
+    test_commit: &str,
+    solution_repo_url: &str,
+    solution_commit1: &str,
+    solution_commit2: &str,
+    intents: &[String],
+    api_key: &str,
+    model: Option<&str>,
+    base_url: Option<&str>,
+    options: &AnalysisOptions,
+) -> Result<MultiIntentResult, Box<dyn std::error::Error>> {
+    options.check_cancelled()?;
+    for intent in intents {
+        let request = VerificationRequest::new(
+            solution_repo_url,
+            solution_commit1,
+            solution_commit2,
+            intent,
+        )
+        .with_tests(test_repo_url, test_commit);
+        validate_inputs(&request, api_key, base_url, options)?;
+    }
+    // Every intent's requests share one connection pool
+    let options = &*options.with_shared_llm_client(api_key, base_url);
+    let (url, commit) = (test_repo_url.to_string(), test_commit.to_string());
+    let tests = spawn_git(options, move |options| {
+        Ok(snapshot_repository(&url, &commit, options)?)
+    })
+    .await?;
+    let file_changes = get_git_changed_files_async_with_options(
+        solution_repo_url,
+        solution_commit1,
+        solution_commit2,
+        options,
+    )
+    .await?;
+    eprintln!(
+        "📝 Found {} changed files between commits {} and {}, verifying {} intents",
         file_changes.len(),
         solution_commit1,
-        solution_commit2
+        solution_commit2,
+        intents.len()
     );
+
+    let (findings, warnings) = if options.checks_solution() && !options.dry_run {
+        analyze_solution(solution_repo_url, solution_commit2, &file_changes, options).await
+    } else {
+        (vec![], vec![])
+    };
+    let code_owners = match options.code_owners {
+        true => Some(read_code_owners(solution_repo_url, solution_commit2, options).await),
+        false => None,
+    };
+    let runs = execute_tests(
+        test_repo_url,
+        test_commit,
+        solution_repo_url,
+        solution_commit1,
+        solution_commit2,
+        options,
+    )
+    .await?;
+
+    let mut verdicts = Vec::new();
+    for intent in intents {
+        let mut result = verify_changes(
+            intent,
+            api_key,
+            model,
+            base_url,
+            options,
+            async |targets| Ok(tests.read_test_targets_code(targets)),
+            async || Ok(file_changes.clone()),
+            async |_| (findings.clone(), warnings.clone()),
+        )
+        .await?;
+        if let Some(code_owners) = &code_owners {
+            attach_code_owners(&mut result, code_owners.clone(), "");
+        }
+        if !runs.is_empty() {
+            merge_test_runs(&mut result, &runs);
+            if let Some(previous) = &options.previous_result {
+                apply_regression(&mut result, previous);
+            }
+            apply_escalation(&mut result, options.escalation_threshold());
+        }
+        complete(&mut result, options);
+        verdicts.push(IntentVerdict {
+            intent: intent.clone(),
+            result,
+        });
+    }
+    Ok(MultiIntentResult::from_verdicts(verdicts))
+}
+
+/// Verify an intent whose solution spans several repositories, e.g. a contract and its SDK
+///
+/// The changes of every repository are analyzed together, with file paths prefixed by the
+/// repository name, so the overall assessment sees the whole solution. The combined result is
+/// then broken down into what each repository contributes. Test execution isn't supported
+/// here, since the tests would need all repositories checked out together.
+#[cfg(feature = "git")]
+#[allow(clippy::too_many_arguments)]
+pub async fn verify_cross_repo_intent(
+    test_repo_url: &str,
+    test_commit: &str,
+    repos: &[RepoChanges],
+    user_intent: &str,
+    api_key: &str,
+    model: Option<&str>,
+    base_url: Option<&str>,
+    options: &AnalysisOptions,
+) -> Result<CrossRepoResult, Box<dyn std::error::Error>> {
+    check_repo_names(repos)?;
+    options.check_cancelled()?;
+    for repo in repos {
+        let request =
+            VerificationRequest::new(&repo.repo_url, &repo.commit1, &repo.commit2, user_intent)
+                .with_tests(test_repo_url, test_commit);
+        validate_inputs(&request, api_key, base_url, options)?;
+    }
+    let mut file_changes = Vec::new();
+    let mut findings = Vec::new();
+    let mut warnings = Vec::new();
+    let mut code_owners = Vec::new();
+    for repo in repos {
+        options.check_cancelled()?;
+        let changes = get_git_changed_files_async_with_options(
+            &repo.repo_url,
+            &repo.commit1,
+            &repo.commit2,
+            options,
+        )
+        .await?;
+        eprintln!(
+            "📝 Found {} changed files in {} between commits {} and {}",
+            changes.len(),
+            repo.name,
+            repo.commit1,
+            repo.commit2
+        );
+        if options.checks_solution() && !options.dry_run {
+            let (repo_findings, repo_warnings) =
+                analyze_solution(&repo.repo_url, &repo.commit2, &changes, options).await;
+            findings.extend(repo_findings.into_iter().map(|mut finding| {
+                finding.file_path = finding.file_path.map(|path| repo.qualified_path(&path));
+                finding
+            }));
+            warnings.extend(repo_warnings.into_iter().map(|mut warning| {
+                warning.file_path = warning.file_path.map(|path| repo.qualified_path(&path));
+                warning
+            }));
+        }
+        if options.code_owners {
+            code_owners.push((
+                repo.qualified_path(""),
+                read_code_owners(&repo.repo_url, &repo.commit2, options).await,
+            ));
+        }
+        file_changes.extend(changes.into_iter().map(|change| FileChange {
+            path: repo.qualified_path(&change.path),
+            ..change
+        }));
+    }
+
+    let mut result = verify_changes(
+        user_intent,
+        api_key,
+        model,
+        base_url,
+        options,
+        async |targets| {
+            read_test_targets_code_async_with_options(targets, test_repo_url, test_commit, options)
+                .await
+        },
+        async || Ok(file_changes),
+        async |_| (findings, warnings),
+    )
+    .await?;
+    for (prefix, repo_owners) in code_owners {
+        attach_code_owners(&mut result, repo_owners, &prefix);
+    }
+    complete(&mut result, options);
+    Ok(CrossRepoResult::from_result(result, repos))
+}
+
+/// Outcome of one test run: whether the solution was applied, and the run or its error
+#[cfg(feature = "git")]
+type TestRun = (bool, Result<TestRunResult, String>);
+
+/// Run the tests with the solution, and without it for counterfactual runs, when
+/// `options.execution` is set
+#[cfg(feature = "git")]
+async fn execute_tests(
+    test_repo_url: &str,
+    test_commit: &str,
+    solution_repo_url: &str,
+    solution_commit1: &str,
+    solution_commit2: &str,
+    options: &AnalysisOptions,
+) -> Result<Vec<TestRun>, Cancelled> {
+    let Some(execution) = &options.execution else {
+        return Ok(vec![]);
+    };
+    if options.dry_run {
+        return Ok(vec![]);
+    }
+
+    let mut runs = vec![true];
+    if execution.counterfactual {
+        runs.push(false);
+    }
+    let mut results = Vec::new();
+    for with_solution in runs {
+        options.check_cancelled()?;
+        let run = run_tests_on_solution(
+            test_repo_url,
+            test_commit,
+            solution_repo_url,
+            solution_commit1,
+            solution_commit2,
+            with_solution,
+            execution,
+            options,
+        )
+        .await;
+        results.push((with_solution, run.map_err(|e| e.to_string())));
+    }
+    Ok(results)
+}
+
+/// Fold the test runs into a verdict, turning failures to run into warnings
+#[cfg(feature = "git")]
+fn merge_test_runs(result: &mut IntentVerificationResult, runs: &[TestRun]) {
+    for (with_solution, run) in runs {
+        match (run, with_solution) {
+            (Ok(run), true) => merge_test_run(result, run.clone()),
+            (Ok(run), false) => merge_counterfactual_run(result, run.clone()),
+            (Err(e), _) => {
+                result.warnings.push(Warning {
+                    kind: WarningKind::TestExecution,
+                    file_path: None,
+                    message: format!(
+                        "Failed to run the tests {} the solution: {}",
+                        if *with_solution { "with" } else { "without" },
+                        e
+                    ),
+                });
+                result.is_partial = true;
+            }
+        }
+    }
+}
+
+/// Same as [`verify_intent_with_options`], reading everything from in-memory snapshots
+///
+/// The changes are those from `base` to `head`, and the test targets are read from `tests`.
+#[allow(clippy::too_many_arguments)]
+pub async fn verify_intent_with_snapshots(
+    tests: &RepoSnapshot,
+    base: &RepoSnapshot,
+    head: &RepoSnapshot,
+    user_intent: &str,
+    api_key: &str,
+    model: Option<&str>,
+    base_url: Option<&str>,
+    options: &AnalysisOptions,
+) -> Result<IntentVerificationResult, Box<dyn std::error::Error>> {
+    options.check_cancelled()?;
+    validate_intent(user_intent)?;
+    if options.calls_model() {
+        validate_api_key(api_key, base_url)?;
+    }
+    let mut result = verify_changes(
+        user_intent,
+        api_key,
+        model,
+        base_url,
+        options,
+        async |targets| Ok(tests.read_test_targets_code(targets)),
+        async || {
+            let file_changes = base.diff(head);
+            eprintln!("📝 Found {} changed files", file_changes.len());
+            Ok(file_changes)
+        },
+        // Linters need a checkout, which snapshots don't have; their docs are at hand
+        async |file_changes| match &options.docs_drift {
+            Some(config) => {
+                let doc_files = head
+                    .files
+                    .iter()
+                    .filter(|(path, _)| config.is_doc_path(path))
+                    .map(|(path, content)| (path.clone(), content.clone()))
+                    .collect();
+                (detect_docs_drift(file_changes, &doc_files, config), vec![])
+            }
+            None => (vec![], vec![]),
+        },
+    )
+    .await?;
+    if options.code_owners
+        && let Some(code_owners) = CodeOwners::from_snapshot(head)
+    {
+        apply_code_owners(&mut result, &code_owners);
+    }
+    complete(&mut result, options);
+    Ok(result)
+}
+
+/// Same as [`verify_intent_with_options`], reading the changes and the test targets through
+/// [`VersionControl`] backends instead of git
+///
+/// The changes are those of `solution` from `from_rev` to `to_rev`, and the test targets are
+/// read from `tests` at `test_rev`; both may be the same backend. Checks that need a checkout,
+/// like linters and test runs, are skipped; CODEOWNERS is read at `to_rev`.
+#[allow(clippy::too_many_arguments)]
+pub async fn verify_intent_with_vcs(
+    tests: &dyn VersionControl,
+    test_rev: &str,
+    solution: &dyn VersionControl,
+    from_rev: &str,
+    to_rev: &str,
+    user_intent: &str,
+    api_key: &str,
+    model: Option<&str>,
+    base_url: Option<&str>,
+    options: &AnalysisOptions,
+) -> Result<IntentVerificationResult, Box<dyn std::error::Error>> {
+    options.check_cancelled()?;
+    validate_intent(user_intent)?;
+    if options.calls_model() {
+        validate_api_key(api_key, base_url)?;
+    }
+    let mut result = verify_changes(
+        user_intent,
+        api_key,
+        model,
+        base_url,
+        options,
+        async |targets| read_test_targets_code_from(tests, targets, test_rev),
+        async || {
+            let file_changes = solution.list_changes(from_rev, to_rev)?;
+            eprintln!("📝 Found {} changed files", file_changes.len());
+            Ok(file_changes)
+        },
+        async |_| (vec![], vec![]),
+    )
+    .await?;
+    if options.code_owners {
+        let code_owners = CODEOWNERS_PATHS
+            .iter()
+            .find_map(|path| solution.read_file_at(to_rev, path).ok().flatten());
+        if let Some(content) = code_owners {
+            apply_code_owners(&mut result, &CodeOwners::parse(&content));
+        }
+    }
+    complete(&mut result, options);
+    Ok(result)
+}
+
+/// Verify changes the caller diffed itself against test target code it read itself, e.g.
+/// from Gerrit, Perforce or another VCS
+///
+/// Runs the same model stages as [`verify_intent_with_options`]; the targets in
+/// `targets_with_code` replace the extraction step. Checks that need a checkout, like linters,
+/// test runs and CODEOWNERS, are skipped.
+#[allow(clippy::too_many_arguments)]
+pub async fn verify_intent_from_changes(
+    file_changes: Vec<FileChange>,
+    targets_with_code: TestTargetsWithCode,
+    user_intent: &str,
+    api_key: &str,
+    model: Option<&str>,
+    base_url: Option<&str>,
+    options: &AnalysisOptions,
+) -> Result<IntentVerificationResult, Box<dyn std::error::Error>> {
+    options.check_cancelled()?;
+    validate_intent(user_intent)?;
+    if options.calls_model() {
+        validate_api_key(api_key, base_url)?;
+    }
+    let options = AnalysisOptions {
+        targets: Some(targets_with_code.targets.clone()),
+        ..options.clone()
+    };
+    let mut result = verify_changes(
+        user_intent,
+        api_key,
+        model,
+        base_url,
+        &options,
+        async |_| Ok(targets_with_code),
+        async || {
+            eprintln!("📝 Found {} changed files", file_changes.len());
+            Ok(file_changes)
+        },
+        async |_| (vec![], vec![]),
+    )
+    .await?;
+    complete(&mut result, &options);
+    Ok(result)
+}
+
+/// Set the owners of the analyzed files under `prefix` from a repository's CODEOWNERS file,
+/// or record why it couldn't be read
+#[cfg(feature = "git")]
+fn attach_code_owners(
+    result: &mut IntentVerificationResult,
+    code_owners: Result<Option<CodeOwners>, Warning>,
+    prefix: &str,
+) {
+    match code_owners {
+        Ok(Some(code_owners)) => apply_code_owners_under(result, &code_owners, prefix),
+        Ok(None) => {}
+        Err(warning) => {
+            result.warnings.push(warning);
+            result.is_partial = true;
+        }
+    }
+}
+
+/// Stamp the end time of a result about to be returned and tell the observer
+///
+/// Called at each public entry point's return rather than in `verify_changes`, so the
+/// duration covers the steps that run after it, like test execution.
+fn complete(result: &mut IntentVerificationResult, options: &AnalysisOptions) {
+    result.metadata = std::mem::take(&mut result.metadata).finish();
+    options.observe(|observer| observer.on_complete(result));
+}
+
+/// The verification pipeline, with the test target code and the changes supplied by the caller
+#[allow(clippy::too_many_arguments)]
+#[tracing::instrument(name = "verification", skip_all, fields(model = model.unwrap_or(DEFAULT_MODEL)))]
+async fn verify_changes(
+    user_intent: &str,
+    api_key: &str,
+    model: Option<&str>,
+    base_url: Option<&str>,
+    options: &AnalysisOptions,
+    read_targets: impl AsyncFnOnce(
+        &TestTargets,
+    ) -> Result<TestTargetsWithCode, Box<dyn std::error::Error>>,
+    read_changes: impl AsyncFnOnce() -> Result<Vec<FileChange>, Box<dyn std::error::Error>>,
+    analyze_statically: impl AsyncFnOnce(&[FileChange]) -> (Vec<Finding>, Vec<Warning>),
+) -> Result<IntentVerificationResult, Box<dyn std::error::Error>> {
+    // One connection pool for all of this verification's requests
+    let options = &*options.with_shared_llm_client(api_key, base_url);
+    // Picks up the results an interrupted run with the same checkpoint already saved
+    let checkpoint = match options.checkpoint.as_ref().filter(|_| !options.dry_run) {
+        Some(path) => Some(Checkpoint::open(path)?),
+        None => None,
+    };
+    // Checked up front, since requests fail rather than go out unredacted or to a public
+    // provider
+    let redactor = options.redaction.as_ref().map(Redactor::new).transpose()?;
+    check_llm_endpoint(options, base_url)?;
+    let mut metadata = ResultMetadata::start(model.unwrap_or(DEFAULT_MODEL));
+    metadata.prompt_version = options.prompt_templates().version.clone();
+
+    // A verdict on an intent that says nothing checkable is meaningless; ask about it instead
+    if let Some(max_ambiguity) = options.max_intent_ambiguity {
+        let mut intent = user_intent.to_string();
+        for clarification in &options.clarifications {
+            intent.push('\n');
+            intent.push_str(clarification);
+        }
+        let ambiguity = intent_ambiguity(&intent);
+        if ambiguity.score > max_ambiguity {
+            eprintln!(
+                "❓ The intent is too ambiguous to verify ({:.2})",
+                ambiguity.score
+            );
+            return Ok(needs_clarification_result(ambiguity, metadata));
+        }
+    }
+
+    let mut warnings = Vec::new();
+    let mut prompts = Vec::new();
+
+    // First, extract test targets from the user intent using AI
+    options.check_cancelled()?;
+    options.report_progress(Progress::new(ProgressStage::ExtractingTargets, 0, 0));
+    let model_name = model.unwrap_or(DEFAULT_MODEL);
+    let test_targets = resolve_test_targets(
+        user_intent,
+        api_key,
+        model,
+        base_url,
+        options,
+        checkpoint.as_ref(),
+        &mut warnings,
+        &mut prompts,
+    )
+    .await;
+
+    // Then, read the actual code of the test targets from the repository at the specified commit
+    options.check_cancelled()?;
+    options.report_progress(Progress::new(ProgressStage::ReadingTargets, 0, 0));
+    let targets_with_code = match read_targets(&test_targets).await {
+        Ok(targets_with_code) => targets_with_code,
+        Err(e) => {
+            warnings.push(Warning {
+                kind: WarningKind::TargetLookup,
+                file_path: None,
+                message: format!("Failed to read test target code: {}", e),
+            });
+            TestTargetsWithCode {
+                targets: test_targets.clone(),
+                file_contents: vec![],
+                function_contents: vec![],
+                tested_sources: vec![],
+            }
+        }
+    };
+    if let Some(recorder) = &options.evidence {
+        recorder.record_targets(&targets_with_code);
+    }
+
+    // Get changed files from git
+    options.check_cancelled()?;
+    options.report_progress(Progress::new(ProgressStage::FetchingChanges, 0, 0));
+    let file_changes = read_changes().await?;
     for (i, fc) in file_changes.iter().enumerate() {
-        println!("  {}. {} [{:?}]", i + 1, fc.path, fc.status);
+        eprintln!("  {}. {} [{:?}]", i + 1, fc.path, fc.status);
+    }
+    if let Some(recorder) = &options.evidence {
+        recorder.record_diff(changes_as_diff(&file_changes));
+    }
+    if let Some(redactor) = &redactor {
+        metadata.redactions = redact_inputs(
+            redactor,
+            user_intent,
+            options,
+            &targets_with_code,
+            &file_changes,
+        );
+    }
+
+    let change_summary = if options.change_summary {
+        options.check_cancelled()?;
+        Some(
+            generate_change_summary(
+                &file_changes,
+                api_key,
+                model,
+                base_url,
+                options,
+                &mut warnings,
+                &mut prompts,
+            )
+            .await,
+        )
+    } else {
+        None
+    };
+
+    // Files over the size limits aren't attempted, and only the most relevant of the rest are
+    // analyzed when there are too many
+    let (within_limits, mut skipped_files) =
+        limit_changes(&file_changes, user_intent, &targets_with_code, options);
+    let analyzed_changes = match options.max_files_analyzed {
+        Some(max_files) => {
+            let (analyzed, less_relevant) =
+                prioritize_change_refs(&within_limits, user_intent, &targets_with_code, max_files);
+            skipped_files.extend(less_relevant);
+            analyzed
+        }
+        None => within_limits,
+    };
+    if !skipped_files.is_empty() {
+        eprintln!(
+            "✂️  Analyzing {} of {} changed files",
+            analyzed_changes.len(),
+            file_changes.len()
+        );
     }
 
-    let mut file_analyses = Vec::new();
-    let mut total_supporting = 0;
+    // Analyze each changed file in context of the test intent, up to `concurrency` at a time
+    options.check_cancelled()?;
+    let files_total = analyzed_changes.len();
+    options.report_progress(Progress::new(ProgressStage::AnalyzingFiles, 0, files_total));
+    let files_done = AtomicUsize::new(0);
+    let prompt_version = &options.prompt_templates().version;
+    let context_hash = context_hash(user_intent, model_name, &targets_with_code, options);
+    let reused = Mutex::new(Vec::new());
+    // The futures are built up front: a stream mapping the changes through the closure would
+    // keep the pipeline future from being `Send`
+    let analyses: Vec<_> = analyzed_changes
+        .into_iter()
+        .map(|file_change| async {
+            let cached = options
+                .analysis_cache
+                .as_ref()
+                .filter(|_| !options.dry_run)
+                .and_then(|cache| cache.get(file_change, prompt_version, &context_hash))
+                .or_else(|| {
+                    checkpoint.as_ref().and_then(|checkpoint| {
+                        checkpoint.get(file_change, prompt_version, &context_hash)
+                    })
+                });
+            let analysis = match cached {
+                Some(cached) => {
+                    eprintln!("♻️  Reusing the analysis of {}", file_change.path);
+                    reused.lock().unwrap().push(file_change.path.clone());
+                    FileAnalysisResult {
+                        analysis: cached.analysis,
+                        warnings: vec![],
+                        prompts: vec![],
+                        findings: cached.findings,
+                    }
+                }
+                None => {
+                    let analysis = analyze_file_change(
+                        file_change,
+                        &targets_with_code,
+                        user_intent,
+                        api_key,
+                        model,
+                        base_url,
+                        options,
+                    )
+                    .await;
+                    // Only complete analyses are worth reusing
+                    let complete = !options.dry_run
+                        && analysis.warnings.is_empty()
+                        && options.check_cancelled().is_ok();
+                    if let Some(cache) = &options.analysis_cache
+                        && complete
+                    {
+                        cache.insert(
+                            file_change,
+                            prompt_version,
+                            &context_hash,
+                            &analysis.analysis,
+                            &analysis.findings,
+                        );
+                    }
+                    if let Some(checkpoint) = &checkpoint
+                        && complete
+                    {
+                        checkpoint.save_analysis(
+                            file_change,
+                            prompt_version,
+                            &context_hash,
+                            &analysis.analysis,
+                            &analysis.findings,
+                        );
+                    }
+                    analysis
+                }
+            };
 
-    // Analyze each changed file in context of the test intent
-    for file_change in &file_changes {
-        if file_change.status == ChangeType::Deleted {
-            // Deleted files generally don't support making tests pass
-            file_analyses.push(FileIntentAnalysis {
-                file_path: file_change.path.clone(),
-                change_type: file_change.status.clone(),
-                supports_intent: false,
-                reasoning: "File was deleted, which typically doesn't help tests pass".to_string(),
-                relevant_changes: vec![],
+            options.observe(|observer| observer.on_file_result(&analysis));
+            let done = files_done.fetch_add(1, Ordering::SeqCst) + 1;
+            options.report_progress(Progress {
+                current_file: Some(file_change.path.clone()),
+                ..Progress::new(ProgressStage::AnalyzingFiles, done, files_total)
             });
-            continue;
-        }
+            analysis
+        })
+        .collect();
+    let analyses: Vec<FileAnalysisResult> = stream::iter(analyses)
+        .buffered(options.concurrency())
+        .collect()
+        .await;
+    metadata.reused_analyses = reused.into_inner().unwrap();
+    metadata.reused_analyses.sort();
+
+    let mut file_analyses = Vec::new();
+    let mut findings = Vec::new();
+    for analysis in analyses {
+        file_analyses.push(analysis.analysis);
+        warnings.extend(analysis.warnings);
+        prompts.extend(analysis.prompts);
+        findings.extend(analysis.findings);
+    }
+    let total_supporting = file_analyses.iter().filter(|fa| fa.supports_intent).count();
+
+    // Deterministic findings: credentials in the added lines, destructive migrations, values
+    // hardcoded from the tests, breaking API changes, unexpected binaries, then the configured linters,
+    // documentation drift and near-copies of reference code
+    findings.extend(scan_for_secrets(&file_changes));
+    findings.extend(scan_migrations(&file_changes, user_intent));
+    findings.extend(detect_hardcoded_answers(
+        &file_changes,
+        &test_sources(&targets_with_code, &file_changes),
+        user_intent,
+    ));
+    findings.extend(breaking_change_findings(
+        &detect_breaking_changes(&file_changes),
+        claims_non_breaking(user_intent),
+    ));
+    let binary_changes = binary_changes(&file_changes);
+    if let Some(policy) = &options.binary_policy {
+        findings.extend(unexpected_binary_findings(&binary_changes, policy));
+    }
+    if options.checks_solution() && !options.dry_run {
+        options.check_cancelled()?;
+        let (analyzer_findings, analyzer_warnings) = analyze_statically(&file_changes).await;
+        findings.extend(analyzer_findings);
+        warnings.extend(analyzer_warnings);
+    }
+    if let Some(config) = &options.similarity
+        && !options.dry_run
+    {
+        options.check_cancelled()?;
+        let (similarity_findings, similarity_warnings) =
+            check_similarity(&file_changes, config, options).await;
+        findings.extend(similarity_findings);
+        warnings.extend(similarity_warnings);
+    }
+    let has_target_code = targets_with_code
+        .function_contents
+        .iter()
+        .any(|f| f.content.is_some())
+        || targets_with_code
+            .file_contents
+            .iter()
+            .any(|f| f.error.is_none());
+    let coverage = has_target_code.then(|| coverage_evidence(&targets_with_code, &file_changes));
 
-        // Analyze if this file change supports the test intent
-        match analyze_file_for_test_intent(
-            file_change,
+    // Generate overall assessment using AI
+    options.check_cancelled()?;
+    options.report_progress(Progress::new(
+        ProgressStage::Assessing,
+        files_total,
+        files_total,
+    ));
+    let overall_assessment = if options.dry_run {
+        dry_run_prompt(
+            options,
+            &mut prompts,
+            prompt_preview(
+                PromptStage::OverallAssessment,
+                None,
+                &[user_message(&overall_assessment_prompt(
+                    &file_analyses,
+                    &targets_with_code,
+                    user_intent,
+                    options,
+                ))],
+            ),
+        );
+        "Dry run: no assessment was requested.".to_string()
+    } else {
+        match generate_overall_intent_assessment(
+            &file_analyses,
             &targets_with_code,
             user_intent,
             api_key,
             model,
             base_url,
+            options,
         )
         .await
         {
-            Ok(analysis) => {
-                if analysis.supports_intent {
-                    total_supporting += 1;
-                }
-                file_analyses.push(analysis);
-            }
+            Ok(assessment) => assessment,
             Err(e) => {
-                file_analyses.push(FileIntentAnalysis {
-                    file_path: file_change.path.clone(),
-                    change_type: file_change.status.clone(),
-                    supports_intent: false,
-                    reasoning: format!("Error analyzing file: {}", e),
-                    relevant_changes: vec![],
+                warnings.push(Warning {
+                    kind: WarningKind::OverallAssessment,
+                    file_path: None,
+                    message: format!("Failed to generate overall assessment: {}", e),
                 });
+                "Overall assessment unavailable; verdict is based on per-file analysis only."
+                    .to_string()
             }
         }
-    }
+    };
 
-    // Generate overall assessment using AI
-    let overall_assessment = generate_overall_intent_assessment(
-        &file_analyses,
-        &targets_with_code,
-        user_intent,
-        api_key,
-        model,
-        base_url,
-    )
-    .await?;
+    let criteria = if options.acceptance_criteria || !options.previous_criteria().is_empty() {
+        options.check_cancelled()?;
+        check_acceptance_criteria(
+            user_intent,
+            &file_changes,
+            api_key,
+            model,
+            base_url,
+            options,
+            &mut warnings,
+            &mut prompts,
+        )
+        .await
+    } else {
+        vec![]
+    };
 
-    // Calculate confidence based on number of supporting files and AI assessment
-    let support_ratio = if !file_analyses.is_empty() {
-        total_supporting as f32 / file_analyses.len() as f32
+    // Calculate confidence based on number of supporting files and AI assessment; refused and
+    // cancelled files weren't judged, so they count on neither side
+    let refused = file_analyses
+        .iter()
+        .filter(|fa| fa.outcome == AnalysisOutcome::Refused)
+        .count();
+    let judged = file_analyses
+        .iter()
+        .filter(|fa| fa.outcome.is_analyzed())
+        .count();
+    let support_ratio = if judged > 0 {
+        total_supporting as f32 / judged as f32
     } else {
         0.0
     };
@@ -194,20 +1733,363 @@ pub async fn verify_intent(
     let is_intent_fulfilled = total_supporting > 0 && support_ratio >= 0.5;
     let confidence = (support_ratio * 0.7 + 0.3).min(1.0); // Base confidence on support ratio
 
-    Ok(IntentVerificationResult {
+    // The cache only speeds up later runs, so failing to write it isn't a problem for this one
+    if let Some(cache) = &options.analysis_cache
+        && !options.dry_run
+        && let Err(e) = cache.save()
+    {
+        eprintln!("⚠️  Failed to save the analysis cache: {}", e);
+    }
+
+    let mut explanation = format!(
+        "{} out of {} changed files support the test intent",
+        total_supporting, judged
+    );
+    if refused > 0 {
+        explanation.push_str(&format!(" ({} refused by the provider)", refused));
+    }
+    let skipped_count = |reason| skipped_files.iter().filter(|s| s.reason == reason).count();
+    if skipped_count(SkipReason::MaxFiles) > 0 {
+        explanation.push_str(&format!(
+            " ({} less relevant files not analyzed)",
+            skipped_count(SkipReason::MaxFiles)
+        ));
+    }
+    if skipped_count(SkipReason::TooLarge) > 0 {
+        explanation.push_str(&format!(
+            " ({} files over the size limits not analyzed)",
+            skipped_count(SkipReason::TooLarge)
+        ));
+    }
+    let mut result = IntentVerificationResult {
         is_intent_fulfilled,
         confidence,
-        explanation: format!(
-            "{} out of {} changed files support the test intent",
-            total_supporting,
-            file_analyses.len()
-        ),
+        explanation,
         files_analyzed: file_analyses,
         overall_assessment,
-    })
+        metadata,
+        is_partial: !warnings.is_empty(),
+        warnings,
+        findings,
+        risk_score: 0.0,
+        prompts,
+        test_run: None,
+        test_run_without_solution: None,
+        coverage_evidence: coverage,
+        unrelated_changes: vec![],
+        scope_score: 1.0,
+        acceptance_criteria: vec![],
+        attestation: None,
+        escalation: None,
+        regression: None,
+        calibration: None,
+        skipped_files,
+        change_summary,
+        language_stats: language_stats(&file_changes),
+        binary_changes,
+        model_retry: None,
+        needs_clarification: None,
+        target_resolution: target_resolution(&targets_with_code),
+    };
+    apply_calibration(&mut result);
+    if let Some(config) = &options.stronger_model
+        && !options.dry_run
+    {
+        options.check_cancelled()?;
+        retry_with_stronger_model(
+            &mut result,
+            &targets_with_code,
+            user_intent,
+            api_key,
+            model,
+            base_url,
+            config,
+            options,
+        )
+        .await;
+    }
+    merge_acceptance_criteria(&mut result, criteria);
+
+    if let Some(baseline) = &options.baseline {
+        baseline.apply(&mut result);
+    }
+    apply_secret_findings(&mut result);
+    apply_similarity_findings(&mut result);
+    apply_shortcut_findings(&mut result);
+    apply_breaking_changes(&mut result, user_intent);
+    apply_migration_findings(&mut result);
+    apply_security_profile(&mut result, options.verification_profile());
+    apply_risk_scores(&mut result, &file_changes);
+    apply_scope(&mut result, &file_changes);
+    if let Some(archetype) = options.archetype {
+        apply_archetype(&mut result, archetype, &file_changes);
+    }
+    if !options.dry_run {
+        if let Some(previous) = &options.previous_result {
+            apply_regression(&mut result, previous);
+        }
+        apply_escalation(&mut result, options.escalation_threshold());
+    }
+
+    // Nothing is left to resume once every step succeeded
+    if let Some(checkpoint) = checkpoint
+        && !result.is_partial
+    {
+        checkpoint.finish();
+    }
+
+    options.report_progress(Progress::new(ProgressStage::Done, files_total, files_total));
+    Ok(result)
+}
+
+/// What redaction strips from the content the prompts are built from: the intent,
+/// clarifications, test target code and changed files
+fn redact_inputs(
+    redactor: &Redactor,
+    user_intent: &str,
+    options: &AnalysisOptions,
+    targets_with_code: &TestTargetsWithCode,
+    file_changes: &[FileChange],
+) -> Vec<Redaction> {
+    let mut sources = vec![("intent", user_intent)];
+    sources.extend(
+        options
+            .clarifications
+            .iter()
+            .map(|clarification| ("clarifications", clarification.as_str())),
+    );
+    sources.extend(
+        targets_with_code
+            .file_contents
+            .iter()
+            .map(|file| (file.path.as_str(), file.content.as_str())),
+    );
+    // Functions found in a file that was read whole are already covered
+    sources.extend(
+        targets_with_code
+            .function_contents
+            .iter()
+            .filter_map(|function| {
+                let path = function.file_path.as_deref().unwrap_or(&function.name);
+                let read_whole = targets_with_code
+                    .file_contents
+                    .iter()
+                    .any(|file| file.path == path);
+                Some((path, function.content.as_deref().filter(|_| !read_whole)?))
+            }),
+    );
+    sources.extend(
+        targets_with_code
+            .tested_sources
+            .iter()
+            .map(|source| (source.path.as_str(), source.content.as_str())),
+    );
+    sources.extend(file_changes.iter().filter_map(|file_change| {
+        let content = file_change
+            .content
+            .as_deref()
+            .or(file_change.old_content.as_deref())?;
+        Some((file_change.path.as_str(), content))
+    }));
+    redaction_report(redactor, sources)
+}
+
+/// Test targets from the options, the analysis cache or checkpoint, the intent text or the
+/// model, in that order
+#[allow(clippy::too_many_arguments)]
+async fn resolve_test_targets(
+    user_intent: &str,
+    api_key: &str,
+    model: Option<&str>,
+    base_url: Option<&str>,
+    options: &AnalysisOptions,
+    checkpoint: Option<&Checkpoint>,
+    warnings: &mut Vec<Warning>,
+    prompts: &mut Vec<PromptPreview>,
+) -> TestTargets {
+    let targets_key = targets_key(user_intent, model.unwrap_or(DEFAULT_MODEL), options);
+    let targets = if let Some(targets) = &options.targets {
+        // Targets confirmed by the user replace the extraction step
+        targets.clone()
+    } else if let Some(targets) = options
+        .analysis_cache
+        .as_ref()
+        .and_then(|cache| cache.targets(&targets_key))
+        .or_else(|| checkpoint.and_then(|checkpoint| checkpoint.targets(&targets_key)))
+    {
+        // Same targets as the earlier run, so its file analyses stay valid
+        targets
+    } else if let Some(heuristic) =
+        Some(extract_test_targets_heuristically(user_intent)).filter(|h| h.is_conclusive())
+    {
+        // The intent names its targets literally, so the model isn't needed
+        heuristic.targets
+    } else if options.dry_run {
+        // Without the model's answer there are no targets to read
+        dry_run_prompt(
+            options,
+            prompts,
+            prompt_preview(
+                PromptStage::TargetExtraction,
+                None,
+                &[user_message(&target_extraction_prompt(
+                    user_intent,
+                    options,
+                ))],
+            ),
+        );
+        TestTargets::default()
+    } else {
+        match extract_test_targets_with_options(user_intent, api_key, model, base_url, options)
+            .await
+        {
+            Ok(targets) => {
+                if let Some(cache) = &options.analysis_cache {
+                    cache.insert_targets(&targets_key, &targets);
+                }
+                if let Some(checkpoint) = checkpoint {
+                    checkpoint.save_targets(&targets_key, &targets);
+                }
+                targets
+            }
+            Err(e) => {
+                // Offline, fall back to whatever the intent names literally
+                let heuristic = extract_test_targets_heuristically(user_intent).targets;
+                let found = heuristic.functions.len() + heuristic.files.len();
+                warnings.push(Warning {
+                    kind: WarningKind::TargetExtraction,
+                    file_path: None,
+                    message: if found > 0 {
+                        format!(
+                            "Failed to extract test targets from intent: {}; using the {} target(s) named in the intent",
+                            e, found
+                        )
+                    } else {
+                        format!("Failed to extract test targets from intent: {}", e)
+                    },
+                });
+                heuristic
+            }
+        }
+    };
+    options.observe(|observer| observer.on_targets_extracted(&targets));
+    targets
+}
+
+/// Analyze one changed file, turning failures into a placeholder analysis plus a warning
+#[tracing::instrument(name = "analyze_file", skip_all, fields(file = %file_change.path))]
+async fn analyze_file_change(
+    file_change: &FileChange,
+    targets_with_code: &TestTargetsWithCode,
+    user_intent: &str,
+    api_key: &str,
+    model: Option<&str>,
+    base_url: Option<&str>,
+    options: &AnalysisOptions,
+) -> FileAnalysisResult {
+    let mut warnings = Vec::new();
+    let mut prompts = Vec::new();
+    let mut findings = Vec::new();
+
+    if file_change.status == ChangeType::Deleted {
+        // Deleted files generally don't support making tests pass
+        let analysis = FileIntentAnalysis {
+            file_path: file_change.path.clone(),
+            change_type: file_change.status.clone(),
+            supports_intent: false,
+            reasoning: "File was deleted, which typically doesn't help tests pass".to_string(),
+            relevant_changes: vec![],
+            locations: vec![],
+            risk_score: 0.0,
+            relevance: None,
+            owners: vec![],
+            citations: vec![],
+            confidence: None,
+            support_probability: None,
+            outcome: AnalysisOutcome::Analyzed,
+        };
+        return FileAnalysisResult {
+            analysis,
+            warnings,
+            prompts,
+            findings,
+        };
+    }
+
+    if options.check_cancelled().is_err() {
+        let analysis = FileIntentAnalysis {
+            file_path: file_change.path.clone(),
+            change_type: file_change.status.clone(),
+            supports_intent: false,
+            reasoning: "Skipped because the verification was cancelled".to_string(),
+            relevant_changes: vec![],
+            locations: vec![],
+            risk_score: 0.0,
+            relevance: None,
+            owners: vec![],
+            citations: vec![],
+            confidence: None,
+            support_probability: None,
+            outcome: AnalysisOutcome::Cancelled,
+        };
+        return FileAnalysisResult {
+            analysis,
+            warnings,
+            prompts,
+            findings,
+        };
+    }
+
+    // Analyze if this file change supports the test intent
+    let analysis = match analyze_file_for_test_intent(
+        file_change,
+        targets_with_code,
+        user_intent,
+        api_key,
+        model,
+        base_url,
+        options,
+        &mut warnings,
+        &mut prompts,
+        &mut findings,
+    )
+    .await
+    {
+        Ok(analysis) => analysis,
+        Err(e) => {
+            warnings.push(Warning {
+                kind: WarningKind::FileAnalysis,
+                file_path: Some(file_change.path.clone()),
+                message: e.to_string(),
+            });
+            FileIntentAnalysis {
+                file_path: file_change.path.clone(),
+                change_type: file_change.status.clone(),
+                supports_intent: false,
+                reasoning: format!("Error analyzing file: {}", e),
+                relevant_changes: vec![],
+                locations: vec![],
+                risk_score: 0.0,
+                relevance: None,
+                owners: vec![],
+                citations: vec![],
+                confidence: None,
+                support_probability: None,
+                outcome: AnalysisOutcome::Analyzed,
+            }
+        }
+    };
+
+    FileAnalysisResult {
+        analysis,
+        warnings,
+        prompts,
+        findings,
+    }
 }
 
 /// Analyze a single file change to determine if it supports the test intent
+#[allow(clippy::too_many_arguments)]
 async fn analyze_file_for_test_intent(
     file_change: &FileChange,
     targets_with_code: &TestTargetsWithCode,
@@ -215,6 +2097,10 @@ async fn analyze_file_for_test_intent(
     api_key: &str,
     model: Option<&str>,
     base_url: Option<&str>,
+    options: &AnalysisOptions,
+    warnings: &mut Vec<Warning>,
+    prompts: &mut Vec<PromptPreview>,
+    findings: &mut Vec<Finding>,
 ) -> Result<FileIntentAnalysis, Box<dyn std::error::Error>> {
     let content = match &file_change.content {
         Some(c) => c,
@@ -225,71 +2111,135 @@ async fn analyze_file_for_test_intent(
                 supports_intent: false,
                 reasoning: "No content available to analyze".to_string(),
                 relevant_changes: vec![],
+                locations: vec![],
+                risk_score: 0.0,
+                relevance: None,
+                owners: vec![],
+                citations: vec![],
+                confidence: None,
+                support_probability: None,
+                outcome: AnalysisOutcome::Analyzed,
             });
         }
     };
 
-    if content == "[Binary file]" || content == "[Non-UTF8 content]" {
+    if is_unreadable(content) {
         return Ok(FileIntentAnalysis {
             file_path: file_change.path.clone(),
             change_type: file_change.status.clone(),
             supports_intent: false,
             reasoning: "Binary or non-UTF8 file, cannot analyze for test intent".to_string(),
             relevant_changes: vec![],
+            locations: vec![],
+            risk_score: 0.0,
+            relevance: None,
+            owners: vec![],
+            citations: vec![],
+            confidence: None,
+            support_probability: None,
+            outcome: AnalysisOutcome::Analyzed,
         });
     }
 
-    // Split content into blocks if too large
-    let blocks = if content.len() > 12_000 {
+    // Modified files are prompted with their changed hunks, added ones with the whole file;
+    // either is split into blocks if too large
+    let hunks = match file_change.status {
+        ChangeType::Modified => diff_hunks(file_change, DIFF_CONTEXT_LINES),
+        _ => vec![],
+    };
+    let diff_context = (!hunks.is_empty()).then_some(DIFF_CONTEXT_LINES);
+    let blocks = if !hunks.is_empty() {
+        group_hunks(hunks, MAX_BLOCK_CHARS)
+    } else if content.len() > MAX_BLOCK_CHARS {
         split_by_function(content)
     } else {
         vec![content.clone()]
     };
 
-    println!(
+    eprintln!(
         "\n📄 Analyzing file {} ({} blocks)",
         file_change.path,
         blocks.len()
     );
 
-    let mut config = OpenAIConfig::new().with_api_key(api_key);
-    if let Some(url) = base_url {
-        config = config.with_api_base(url);
-    }
-    let client = Client::with_config(config);
+    let client = llm_client_for(api_key, base_url, options)?;
+    // Dockerfiles, manifests and CI configs get a structural diff and a misconfiguration review
+    let infra_instruction = infra_review_instruction(file_change);
 
     let mut all_supports_intent = Vec::new();
+    let mut all_relevance = Vec::new();
     let mut all_reasoning = Vec::new();
     let mut all_relevant_changes = Vec::new();
+    let mut all_locations: Vec<ChangeLocation> = Vec::new();
+    let mut all_citations: Vec<Citation> = Vec::new();
+    let mut all_confidences = Vec::new();
+    // Probability each block supports the intent, when its reply came with logprobs
+    let mut all_probabilities: Vec<Option<f32>> = Vec::new();
+
+    // Blocks the provider refused to analyze, with the reason
+    let mut refusals = Vec::new();
 
     // Analyze each block
     for (i, block) in blocks.iter().enumerate() {
-        let mut messages = vec![intent_verification_system_rules()];
-        messages.extend(add_test_target_context(targets_with_code));
-        messages.push(add_file_change_context_for_block(
-            file_change,
-            user_intent,
-            block,
-            i + 1,
-            blocks.len(),
-        ));
-
-        let request = CreateChatCompletionRequest {
-            model: model.unwrap_or("gpt-3.5-turbo").to_string(),
-            messages,
-            ..Default::default()
+        let block_request = |block: &str, targets_with_code: &TestTargetsWithCode| {
+            file_analysis_request(
+                file_change,
+                targets_with_code,
+                user_intent,
+                model,
+                options,
+                infra_instruction.as_deref(),
+                block,
+                (i + 1, blocks.len()),
+                diff_context,
+            )
         };
+        let request = block_request(block, targets_with_code);
 
-        let response = client.chat().create(request).await?;
-        let response_text = response
-            .choices
-            .get(0)
-            .and_then(|c| c.message.content.clone())
-            .unwrap_or_else(|| "No response.".to_string());
+        if options.dry_run {
+            dry_run_prompt(
+                options,
+                prompts,
+                prompt_preview(
+                    PromptStage::FileAnalysis,
+                    Some(&file_change.path),
+                    &request.messages,
+                ),
+            );
+            continue;
+        }
+
+        let mut reply = send_file_analysis(&client, request, file_change, options).await?;
+        if let Some(reason) = &reply.refusal {
+            // Retry once without the strings and comments that usually trip content filters
+            eprintln!(
+                "🚫 Block {} of {} was refused ({}), retrying with string literals and comments masked",
+                i + 1,
+                file_change.path,
+                reason
+            );
+            let request =
+                block_request(&sanitize_code(block), &sanitize_targets(targets_with_code));
+            reply = send_file_analysis(&client, request, file_change, options).await?;
+        }
+        if let Some(reason) = reply.refusal {
+            warnings.push(Warning {
+                kind: WarningKind::Refusal,
+                file_path: Some(file_change.path.clone()),
+                message: format!(
+                    "Block {} was refused, also with string literals and comments masked: {}",
+                    i + 1,
+                    reason
+                ),
+            });
+            refusals.push(reason);
+            continue;
+        }
+        let (response_text, support_probability) = (reply.text, reply.support_probability);
 
-        println!("\n🤖 OPENAI RESPONSE for block {}:", i + 1);
-        println!("{}", response_text);
-        println!("---");
+        eprintln!("\n🤖 OPENAI RESPONSE for block {}:", i + 1);
+        eprintln!("{}", response_text);
+        eprintln!("---");
 
         let json_str = extract_json_from_response(&response_text);
 
@@ -312,8 +2262,59 @@ async fn analyze_file_for_test_intent(
                 all_supports_intent.push(supports_intent);
                 all_reasoning.push(reasoning);
                 all_relevant_changes.extend(relevant_changes);
+                all_probabilities.push(support_probability);
+                if let Some(confidence) = json["confidence"].as_f64() {
+                    all_confidences.push((confidence as f32).clamp(0.0, 1.0));
+                }
+                if let Ok(relevance) = serde_json::from_value(json["relevance"].clone()) {
+                    all_relevance.push(relevance);
+                }
+                if options.verification_profile() == VerificationProfile::Security {
+                    findings.extend(parse_security_issues(&json, &file_change.path));
+                }
+
+                for citation in verify_citations(&json, file_change, targets_with_code) {
+                    if !all_citations.contains(&citation) {
+                        all_citations.push(citation);
+                    }
+                }
+
+                // Only keep locations whose snippet can be found in the actual file content
+                if let Some(locations) = json["locations"].as_array() {
+                    for location in locations {
+                        let Some(snippet) = location["snippet"].as_str() else {
+                            continue;
+                        };
+                        let located = locate_snippet(content, snippet).or_else(|| {
+                            diff_context
+                                .and_then(|_| locate_snippet(content, &strip_diff_markers(snippet)))
+                        });
+                        if let Some((start_line, end_line)) = located
+                            && !all_locations
+                                .iter()
+                                .any(|l| l.start_line == start_line && l.end_line == end_line)
+                        {
+                            all_locations.push(ChangeLocation {
+                                file_path: file_change.path.clone(),
+                                start_line,
+                                end_line,
+                                snippet: snippet.to_string(),
+                            });
+                        }
+                    }
+                }
             }
-            Err(_) => {
+            Err(e) => {
+                warnings.push(Warning {
+                    kind: WarningKind::ResponseParsing,
+                    file_path: Some(file_change.path.clone()),
+                    message: format!(
+                        "Block {} response was not valid JSON ({}), used keyword fallback",
+                        i + 1,
+                        e
+                    ),
+                });
+
                 // Fallback parsing
                 let supports_intent = response_text.to_lowercase().contains("true")
                     || response_text.to_lowercase().contains("yes")
@@ -321,12 +2322,77 @@ async fn analyze_file_for_test_intent(
 
                 all_supports_intent.push(supports_intent);
                 all_reasoning.push(response_text);
+                all_probabilities.push(None);
             }
         }
     }
 
-    // Combine results from all blocks
+    if options.dry_run {
+        return Ok(FileIntentAnalysis {
+            file_path: file_change.path.clone(),
+            change_type: file_change.status.clone(),
+            supports_intent: false,
+            reasoning: "Dry run: the prompt was built but not sent".to_string(),
+            relevant_changes: vec![],
+            locations: vec![],
+            risk_score: 0.0,
+            relevance: None,
+            owners: vec![],
+            citations: vec![],
+            confidence: None,
+            support_probability: None,
+            outcome: AnalysisOutcome::Analyzed,
+        });
+    }
+
+    // Nothing the model said about the file can be trusted to judge it
+    if refusals.len() == blocks.len() {
+        return Ok(FileIntentAnalysis {
+            file_path: file_change.path.clone(),
+            change_type: file_change.status.clone(),
+            supports_intent: false,
+            reasoning: format!(
+                "The provider refused to analyze this file: {}",
+                refusals.join("; ")
+            ),
+            relevant_changes: vec![],
+            locations: vec![],
+            risk_score: 0.0,
+            relevance: None,
+            owners: vec![],
+            citations: vec![],
+            confidence: None,
+            support_probability: None,
+            outcome: AnalysisOutcome::Refused,
+        });
+    }
+
+    let unverified = all_citations.iter().filter(|c| !c.verified).count();
+    if unverified > 0 {
+        warnings.push(Warning {
+            kind: WarningKind::ResponseParsing,
+            file_path: Some(file_change.path.clone()),
+            message: format!(
+                "{} of {} citations quote code that isn't in the cited file",
+                unverified,
+                all_citations.len()
+            ),
+        });
+    }
+
+    // Combine results from all blocks; the file is as relevant as its most relevant block
     let final_supports_intent = all_supports_intent.iter().any(|&x| x);
+    let support_probability = all_probabilities.iter().any(Option::is_some).then(|| {
+        let blocks: Vec<f32> = all_probabilities
+            .iter()
+            .zip(&all_supports_intent)
+            .map(|(probability, &supports)| probability.unwrap_or(if supports { 1.0 } else { 0.0 }))
+            .collect();
+        any_block_supports(&blocks)
+    });
+    let confidence = (!all_confidences.is_empty())
+        .then(|| all_confidences.iter().sum::<f32>() / all_confidences.len() as f32);
+    let final_relevance = all_relevance.into_iter().max();
     let final_reasoning = if blocks.len() > 1 {
         format!(
             "Analysis of {} blocks:\n{}",
@@ -348,10 +2414,163 @@ async fn analyze_file_for_test_intent(
         supports_intent: final_supports_intent,
         reasoning: final_reasoning,
         relevant_changes: all_relevant_changes,
+        locations: all_locations,
+        risk_score: 0.0,
+        relevance: final_relevance,
+        owners: vec![],
+        citations: all_citations,
+        confidence,
+        support_probability,
+        outcome: AnalysisOutcome::Analyzed,
+    })
+}
+
+/// The file analysis request for one block of a changed file
+#[allow(clippy::too_many_arguments)]
+fn file_analysis_request(
+    file_change: &FileChange,
+    targets_with_code: &TestTargetsWithCode,
+    user_intent: &str,
+    model: Option<&str>,
+    options: &AnalysisOptions,
+    infra_instruction: Option<&str>,
+    block: &str,
+    (block_num, total_blocks): (usize, usize),
+    diff_context: Option<usize>,
+) -> CreateChatCompletionRequest {
+    let templates = options.prompt_templates();
+    let mut messages = vec![ChatCompletionRequestMessage::System(
+        templates.file_analysis_system.clone().into(),
+    )];
+    if let Some(instruction) = options.language_instruction() {
+        messages.push(ChatCompletionRequestMessage::System(instruction.into()));
+    }
+    if let Some(instruction) = options.verification_profile().file_analysis_instruction() {
+        messages.push(ChatCompletionRequestMessage::System(instruction.into()));
+    }
+    if let Some(archetype) = options.archetype {
+        messages.push(ChatCompletionRequestMessage::System(
+            archetype.file_analysis_instruction().into(),
+        ));
+    }
+    messages.extend(add_test_target_context(targets_with_code));
+    if let Some(clarifications) = options.clarification_instruction() {
+        messages.push(ChatCompletionRequestMessage::System(clarifications.into()));
+    }
+    if let Some(instruction) = infra_instruction {
+        messages.push(ChatCompletionRequestMessage::System(instruction.into()));
+    }
+    messages.push(add_file_change_context_for_block(
+        templates,
+        file_change,
+        user_intent,
+        block,
+        block_num,
+        total_blocks,
+        diff_context,
+    ));
+
+    CreateChatCompletionRequest {
+        model: model.unwrap_or(DEFAULT_MODEL).to_string(),
+        messages,
+        logprobs: options.calibrate_confidence.then_some(true),
+        ..Default::default()
+    }
+}
+
+/// The model's reply to one block's file analysis request
+struct BlockReply {
+    text: String,
+    /// Probability that the block supports the intent, from the reply's logprobs
+    support_probability: Option<f32>,
+    /// Why the model or the provider's content filter declined the request
+    refusal: Option<String>,
+}
+
+/// Send a file analysis request, telling the observer and recording the exchange
+async fn send_file_analysis(
+    client: &LlmClient,
+    request: CreateChatCompletionRequest,
+    file_change: &FileChange,
+    options: &AnalysisOptions,
+) -> Result<BlockReply, Box<dyn std::error::Error>> {
+    let preview = (options.evidence.is_some() || options.observer.is_some()).then(|| {
+        prompt_preview(
+            PromptStage::FileAnalysis,
+            Some(&file_change.path),
+            &request.messages,
+        )
+    });
+    if let Some(preview) = &preview {
+        options.observe(|observer| observer.on_prompt_built(preview));
+    }
+    let response =
+        match create_chat_completion(client, request, Some(&file_change.path), options).await {
+            Ok(response) => response,
+            Err(e) => {
+                return match filtered_request_reason(&e) {
+                    Some(reason) => Ok(BlockReply {
+                        text: String::new(),
+                        support_probability: None,
+                        refusal: Some(reason),
+                    }),
+                    None => Err(e.into()),
+                };
+            }
+        };
+    let choice = response.choices.first();
+    let refusal = choice.and_then(refusal_reason);
+    let text = choice
+        .and_then(|c| {
+            c.message
+                .content
+                .clone()
+                .or_else(|| c.message.refusal.clone())
+        })
+        .unwrap_or_else(|| "No response.".to_string());
+    let support_probability = choice
+        .and_then(|c| c.logprobs.as_ref())
+        .filter(|_| options.calibrate_confidence)
+        .and_then(support_probability);
+    if let Some(preview) = preview {
+        options.observe(|observer| observer.on_llm_response(&preview, &text));
+        if let Some(recorder) = &options.evidence {
+            recorder.record_exchange(preview, &text);
+        }
+    }
+
+    Ok(BlockReply {
+        text,
+        support_probability,
+        refusal,
     })
 }
 
+/// Join diff hunks into blocks of at most `max_chars`, keeping each hunk whole
+fn group_hunks(hunks: Vec<String>, max_chars: usize) -> Vec<String> {
+    let mut blocks: Vec<String> = Vec::new();
+    for hunk in hunks {
+        match blocks.last_mut() {
+            Some(block) if block.len() + hunk.len() <= max_chars => block.push_str(&hunk),
+            _ => blocks.push(hunk),
+        }
+    }
+    blocks
+}
+
+/// A snippet quoted from diff hunks, without the hunk headers, removed lines and `+`/` `
+/// markers
+pub(crate) fn strip_diff_markers(snippet: &str) -> String {
+    snippet
+        .lines()
+        .filter(|line| !line.starts_with('-') && !line.starts_with("@@"))
+        .map(|line| line.strip_prefix(['+', ' ']).unwrap_or(line))
+        .collect::<Vec<_>>()
+        .join("\n")
+}
+
 /// Generate an overall assessment of whether the changes fulfill the test intent
+#[tracing::instrument(name = "assess", skip_all)]
 async fn generate_overall_intent_assessment(
     file_analyses: &[FileIntentAnalysis],
     targets_with_code: &TestTargetsWithCode,
@@ -359,7 +2578,26 @@ async fn generate_overall_intent_assessment(
     api_key: &str,
     model: Option<&str>,
     base_url: Option<&str>,
+    options: &AnalysisOptions,
 ) -> Result<String, Box<dyn std::error::Error>> {
+    let prompt = overall_assessment_prompt(file_analyses, targets_with_code, user_intent, options);
+    observe_prompt(options, PromptStage::OverallAssessment, &prompt);
+    let assessment = ask_openai_with_options(&prompt, api_key, model, base_url, options).await?;
+    record_exchange(
+        options,
+        PromptStage::OverallAssessment,
+        &prompt,
+        &assessment,
+    );
+    Ok(assessment.trim().to_string())
+}
+
+pub(crate) fn overall_assessment_prompt(
+    file_analyses: &[FileIntentAnalysis],
+    targets_with_code: &TestTargetsWithCode,
+    user_intent: &str,
+    options: &AnalysisOptions,
+) -> String {
     // Summarize file analyses
     let summary = file_analyses
         .iter()
@@ -393,50 +2631,37 @@ async fn generate_overall_intent_assessment(
         .count();
     let total_files = targets_with_code.targets.files.len();
 
-    let prompt = format!(
-        r#"Provide a concise overall assessment of whether the code changes fulfill the test intent.
-
-User Intent: "{}"
-Target Functions: {} (found {}/{} in codebase)
-Target Files: {} (found {}/{})
-
-File Analysis Summary:
-{}
-
-Provide a 2-3 sentence assessment covering:
-1. Whether the changes are likely to make the specified tests work
-2. Key supporting or missing changes
-3. Overall confidence in test success
-
-Respond with just the assessment text (no JSON):"#,
-        user_intent,
-        targets_with_code.targets.functions.join(", "),
-        found_functions,
-        total_functions,
-        targets_with_code.targets.files.join(", "),
-        found_files,
-        total_files,
-        summary
+    let prompt = render(
+        &options.prompt_templates().overall_assessment,
+        &[
+            ("intent", user_intent),
+            ("functions", &targets_with_code.targets.functions.join(", ")),
+            ("found_functions", &found_functions.to_string()),
+            ("total_functions", &total_functions.to_string()),
+            ("files", &targets_with_code.targets.files.join(", ")),
+            ("other_targets", &targets_with_code.targets.other_targets()),
+            ("found_files", &found_files.to_string()),
+            ("total_files", &total_files.to_string()),
+            ("summary", &summary),
+        ],
     );
 
-    let assessment = ask_openai_internal(&prompt, api_key, model, base_url).await?;
-    Ok(assessment.trim().to_string())
-}
-
-/// Create system message for intent verification analysis
-pub fn intent_verification_system_rules() -> ChatCompletionRequestMessage {
-    ChatCompletionRequestMessage::System(
-        "You are an AI specialized in code analysis for test intent verification.\n\
-         Your task:\n\
-         1. First, understand the test requirements and what functionality needs to work\n\
-         2. Then, analyze the code changes in the solution commits\n\
-         3. Verify if these code changes would make the specified tests pass\n\
-         4. Determine if changes support fulfilling the user's intent\n\
-         5. Identify specific relevant changes that address test requirements\n\
-         - Return strict JSON format with: supports_intent (bool), reasoning (string), relevant_changes (array), confidence (float)\n\
-         - Be specific about what works and what might still be missing\n"
-            .into(),
-    )
+    [
+        options.clarification_instruction(),
+        options
+            .verification_profile()
+            .assessment_instruction()
+            .map(str::to_string),
+        options
+            .archetype
+            .map(|archetype| archetype.assessment_instruction().to_string()),
+        options.language_instruction(),
+    ]
+    .into_iter()
+    .flatten()
+    .fold(prompt, |prompt, instruction| {
+        format!("{}\n\n{}", prompt, instruction)
+    })
 }
 
 /// Add test target context (functions and files that need to work)
@@ -482,6 +2707,25 @@ pub fn add_test_target_context(
         }
     }
 
+    // Sources the tests import, so the intent needn't name them
+    if !targets_with_code.tested_sources.is_empty() {
+        context.push_str("\nSource Files Exercised by the Tests:\n");
+        for source in &targets_with_code.tested_sources {
+            let functions = if source.functions.is_empty() {
+                String::new()
+            } else {
+                format!("; calls {}", source.functions.join(", "))
+            };
+            context.push_str(&format!(
+                "- File '{}' (imported by {}{}):\n```\n{}\n```\n\n",
+                source.path,
+                source.tested_by.join(", "),
+                functions,
+                source.content
+            ));
+        }
+    }
+
     context.push_str("\nAnalyze what these tests require to pass successfully.\n");
 
     let messages = vec![
@@ -498,56 +2742,58 @@ pub fn add_test_target_context(
         }),
     ];
 
-    println!("\n🎯 TEST TARGET CONTEXT:");
-    println!("{}", context);
-    println!("---");
+    eprintln!("\n🎯 TEST TARGET CONTEXT:");
+    eprintln!("{}", context);
+    eprintln!("---");
 
     messages
 }
 
 /// Add file change context for a specific block (for large files split into multiple blocks)
+///
+/// `diff_context` is set when the block holds diff hunks with that many lines of context
+/// rather than file content.
 pub fn add_file_change_context_for_block(
+    templates: &PromptTemplates,
     file_change: &FileChange,
     user_intent: &str,
     block_content: &str,
     block_num: usize,
     total_blocks: usize,
+    diff_context: Option<usize>,
 ) -> ChatCompletionRequestMessage {
-    let block_info = if total_blocks > 1 {
+    let mut block_info = if total_blocks > 1 {
         format!(" (Block {}/{})", block_num, total_blocks)
     } else {
         String::new()
     };
+    if let Some(lines) = diff_context {
+        block_info.push_str(&format!(
+            " (changed hunks only, with {} lines of context; lines starting with - were removed and + were added, quote snippets without these markers)",
+            lines
+        ));
+    }
 
-    let message_content = format!(
-        "STEP 2: ANALYZE THE SOLUTION CODE CHANGES\n\n\
-         USER INTENT: \"{}\"\n\n\
-         SOLUTION FILE: {}{}\n\
-         CHANGE TYPE: {:?}\n\n\
-         CODE IMPLEMENTATION:\n\
-         ```\n{}\n```\n\n\
-         STEP 3: VERIFY IF THIS SOLUTION MAKES THE TESTS PASS\n\
-         - Does this code implement the functionality required by the tests?\n\
-         - Are there any missing implementations or bugs?\n\
-         - Would the test functions work correctly with these changes?\n\
-         - Does this fulfill the user's intent?\n\n\
-         Respond in JSON format with:\n\
-         - supports_intent (bool): true if this code would make the tests pass\n\
-         - reasoning (string): explain what works and what might be missing\n\
-         - relevant_changes (array): list specific code changes that address test requirements\n\
-         - confidence (float): your confidence level (0.0-1.0)",
-        user_intent, file_change.path, block_info, file_change.status, block_content
+    let message_content = render(
+        &templates.file_analysis,
+        &[
+            ("intent", user_intent),
+            ("path", &file_change.path),
+            ("block_info", &block_info),
+            ("change_type", &format!("{:?}", file_change.status)),
+            ("code", block_content),
+        ],
     );
 
-    println!("message_content: {}", message_content);
-    println!(
+    eprintln!("message_content: {}", message_content);
+    eprintln!(
         "\n📄 FILE CHANGE CONTEXT for {}{}:",
         file_change.path, block_info
     );
     if total_blocks > 1 {
-        println!("  ⚠️  Large file split into {} blocks", total_blocks);
+        eprintln!("  ⚠️  Large file split into {} blocks", total_blocks);
     }
-    println!("---");
+    eprintln!("---");
 
     ChatCompletionRequestMessage::User(ChatCompletionRequestUserMessage {
         content: ChatCompletionRequestUserMessageContent::Text(message_content),
@@ -0,0 +1,299 @@
+use std::borrow::Cow;
+
+use crate::analyzers::StaticAnalyzer;
+use crate::archetype::IntentArchetype;
+use crate::baseline::Baseline;
+use crate::binary::BinaryPolicy;
+use crate::clone_retry::CloneRetryConfig;
+use crate::docs_drift::DocsDriftConfig;
+use crate::escalation::DEFAULT_ESCALATION_CONFIDENCE;
+use crate::evidence::EvidenceRecorder;
+use crate::execution::ExecutionConfig;
+use crate::git::GitCredentials;
+use crate::incremental::AnalysisCache;
+use crate::llm_client::LlmClient;
+use crate::observer::{AnalysisObserver, ObserverHandle};
+use crate::privacy::LocalOnlyPolicy;
+use crate::profile::VerificationProfile;
+use crate::progress::{CancellationToken, Cancelled, Progress, ProgressHandler};
+use crate::prompts::PromptTemplates;
+use crate::redaction::RedactionConfig;
+use crate::scheduler::RateLimiter;
+use crate::similarity::SimilarityConfig;
+use crate::stronger_model::StrongerModelConfig;
+use crate::types::{IntentVerificationResult, TestTargets};
+
+/// Options controlling how a verification is performed
+#[derive(Debug, Clone, Default, serde::Serialize, serde::Deserialize)]
+#[serde(default)]
+pub struct AnalysisOptions {
+    /// Language for human-readable output such as reasoning and assessments
+    /// (ISO 639-1 code like `vi`, `ja`, `ko`, or a language name). English when `None`.
+    pub language: Option<String>,
+    /// Accepted findings to report as suppressed
+    pub baseline: Option<Baseline>,
+    /// Timeout for each LLM request, in seconds
+    pub timeout_secs: Option<u64>,
+    /// Number of changed files analyzed at the same time (one at a time when `None`)
+    pub concurrency: Option<usize>,
+    /// Directory repositories are cloned into (the system temp directory when `None`)
+    pub cache_dir: Option<String>,
+    /// Proxy URL for LLM requests and git clones
+    pub proxy: Option<String>,
+    /// Receives progress updates while the verification runs
+    #[serde(skip)]
+    pub progress: Option<ProgressHandler>,
+    /// Stops the verification early when cancelled
+    #[serde(skip)]
+    pub cancellation: Option<CancellationToken>,
+    /// Notified of each step of the pipeline and its intermediate artifacts
+    #[serde(skip)]
+    pub observer: Option<ObserverHandle>,
+    /// Limits LLM requests, possibly shared with other verifications
+    #[serde(skip)]
+    pub rate_limiter: Option<RateLimiter>,
+    /// Client reused by every LLM request; each verification creates one when `None`
+    #[serde(skip)]
+    pub llm_client: Option<LlmClient>,
+    /// Clone, diff and build every prompt, but return them in `prompts` instead of calling
+    /// the model
+    pub dry_run: bool,
+    /// Test targets to use instead of extracting them from the intent with the model
+    pub targets: Option<TestTargets>,
+    /// Corrections or details from the user, passed to the model with the test requirements
+    pub clarifications: Vec<String>,
+    /// Run the project's tests on the solution and fold the outcome into the verdict
+    pub execution: Option<ExecutionConfig>,
+    /// Linters to run on the changed files, their diagnostics reported as findings
+    pub static_analyzers: Vec<StaticAnalyzer>,
+    /// Have the model break the intent into acceptance criteria and check each one against
+    /// the diff; any unmet criterion fails the verdict
+    pub acceptance_criteria: bool,
+    /// How the changes are judged, e.g. with a security focus for vulnerability fixes
+    pub profile: VerificationProfile,
+    /// Report documentation that still describes public items the changes modified
+    pub docs_drift: Option<DocsDriftConfig>,
+    /// Records the diff, test targets and every model exchange for an evidence bundle
+    #[serde(skip)]
+    pub evidence: Option<EvidenceRecorder>,
+    /// Confidence below which the result lists questions for a human reviewer
+    /// ([`DEFAULT_ESCALATION_CONFIDENCE`] when `None`)
+    pub escalation_threshold: Option<f32>,
+    /// Prompt templates to use instead of the current version's
+    pub prompts: Option<PromptTemplates>,
+    /// Per-file analyses from earlier runs, reused for files whose content didn't change
+    #[serde(skip)]
+    pub analysis_cache: Option<AnalysisCache>,
+    /// File the extracted targets and each file's analysis are saved to as soon as they're
+    /// known, so rerunning an interrupted verification with the same file resumes where it
+    /// stopped; removed once a verification completes without warnings
+    pub checkpoint: Option<String>,
+    /// Compare the added code against reference repositories and report near-copies
+    pub similarity: Option<SimilarityConfig>,
+    /// Kind of change the intent asks for, with its tailored prompts and required evidence
+    pub archetype: Option<IntentArchetype>,
+    /// Earlier result for the same intent; its acceptance criteria are checked again and the
+    /// new result reports what regressed since
+    pub previous_result: Option<Box<IntentVerificationResult>>,
+    /// Attach the owners from the solution repository's CODEOWNERS file to each analyzed file
+    pub code_owners: bool,
+    /// Strip emails, secrets, internal hostnames and proprietary identifiers from everything
+    /// sent to the model; what was found is listed in `ResultMetadata::redactions`
+    pub redaction: Option<RedactionConfig>,
+    /// Refuse network access except LLM requests to the allowed endpoints; repositories must
+    /// be local
+    pub local_only: Option<LocalOnlyPolicy>,
+    /// Ask for token log-probabilities of the file analyses and derive the confidence from
+    /// them, see `ConfidenceCalibration`; providers that don't return them are unaffected
+    pub calibrate_confidence: bool,
+    /// Analyze at most this many changed files, the most relevant to the intent first; the
+    /// rest are listed in `IntentVerificationResult::skipped_files`
+    pub max_files_analyzed: Option<usize>,
+    /// Skip changed files whose old or new content is larger than this many bytes
+    pub max_file_bytes: Option<usize>,
+    /// Analyze at most this many changed files, in diff order, before `max_files_analyzed`
+    /// ranks them
+    pub max_total_files: Option<usize>,
+    /// Skip changed files once the estimated tokens of their contents would exceed this
+    /// budget, see `estimate_tokens`
+    pub max_total_tokens: Option<usize>,
+    /// Summarize the added, changed and removed functions and routes before the intent is
+    /// judged, with an overview written by the model
+    pub change_summary: bool,
+    /// Report added binary files the policy doesn't allow, e.g. an executable in a
+    /// source-only repository
+    pub binary_policy: Option<BinaryPolicy>,
+    /// Re-run the final judgment with a stronger model when the verdict's confidence is low
+    /// or the file analyses disagree with it; both verdicts are kept in
+    /// `IntentVerificationResult::model_retry`
+    pub stronger_model: Option<StrongerModelConfig>,
+    /// Don't verify intents (with their clarifications) scoring above this ambiguity, see
+    /// `intent_ambiguity`; the result asks clarifying questions instead
+    pub max_intent_ambiguity: Option<f32>,
+    /// Retrying clones and fetches that fail on the network, with the defaults of
+    /// [`CloneRetryConfig`] when `None`
+    pub clone_retry: Option<CloneRetryConfig>,
+    /// Credentials for fetching private repositories, instead of embedding them in the URL
+    #[serde(skip)]
+    pub git_credentials: Option<GitCredentials>,
+}
+
+impl AnalysisOptions {
+    pub(crate) fn report_progress(&self, progress: Progress) {
+        if let Some(handler) = &self.progress {
+            handler.report(&progress);
+        }
+    }
+
+    /// Call `event` with the observer, if any
+    pub(crate) fn observe(&self, event: impl FnOnce(&dyn AnalysisObserver)) {
+        if let Some(handle) = &self.observer {
+            event(handle.observer());
+        }
+    }
+
+    pub(crate) fn check_cancelled(&self) -> Result<(), Cancelled> {
+        match &self.cancellation {
+            Some(token) => token.check(),
+            None => Ok(()),
+        }
+    }
+
+    /// Whether any check needs the solution's files beyond the diff (linters, docs drift)
+    /// These options with a client for `api_key` and `base_url` that all their LLM requests
+    /// share, unless they already have one
+    ///
+    /// If the client can't be built, each request builds its own and reports the error.
+    pub(crate) fn with_shared_llm_client(
+        &self,
+        api_key: &str,
+        base_url: Option<&str>,
+    ) -> Cow<'_, AnalysisOptions> {
+        let shared = self
+            .llm_client
+            .as_ref()
+            .is_some_and(|client| client.serves(api_key, base_url));
+        if shared || self.dry_run {
+            return Cow::Borrowed(self);
+        }
+        match LlmClient::new(api_key, base_url, self) {
+            Ok(client) => Cow::Owned(AnalysisOptions {
+                llm_client: Some(client),
+                ..self.clone()
+            }),
+            Err(_) => Cow::Borrowed(self),
+        }
+    }
+
+    /// Whether requests go to a real model, needing an API key and an allowed endpoint: not in
+    /// a dry run or with a mock client
+    pub(crate) fn calls_model(&self) -> bool {
+        !self.dry_run && !self.llm_client.as_ref().is_some_and(LlmClient::is_mock)
+    }
+
+    /// Wait until the rate limiter allows another LLM request
+    pub(crate) async fn acquire_request_slot(&self) {
+        if let Some(rate_limiter) = &self.rate_limiter {
+            rate_limiter.acquire().await;
+        }
+    }
+
+    pub(crate) fn checks_solution(&self) -> bool {
+        !self.static_analyzers.is_empty() || self.docs_drift.is_some()
+    }
+
+    /// Profile the verification runs under: security for security patches, the configured
+    /// one otherwise
+    pub fn verification_profile(&self) -> VerificationProfile {
+        match self.archetype {
+            Some(archetype) if archetype.profile() == VerificationProfile::Security => {
+                VerificationProfile::Security
+            }
+            _ => self.profile,
+        }
+    }
+
+    /// Acceptance criteria of the previous result, checked again in this run
+    pub(crate) fn previous_criteria(&self) -> Vec<String> {
+        self.previous_result
+            .iter()
+            .flat_map(|previous| &previous.acceptance_criteria)
+            .map(|c| c.criterion.clone())
+            .collect()
+    }
+
+    /// Configured clone retries, or the default ones
+    pub fn clone_retry(&self) -> CloneRetryConfig {
+        self.clone_retry.clone().unwrap_or_default()
+    }
+
+    /// Configured escalation threshold, or the default one
+    pub fn escalation_threshold(&self) -> f32 {
+        self.escalation_threshold
+            .unwrap_or(DEFAULT_ESCALATION_CONFIDENCE)
+    }
+
+    /// Configured prompt templates, or the current version's
+    pub fn prompt_templates(&self) -> &PromptTemplates {
+        self.prompts.as_ref().unwrap_or(PromptTemplates::current())
+    }
+
+    /// Number of files to analyze concurrently, at least 1
+    pub fn concurrency(&self) -> usize {
+        self.concurrency.unwrap_or(1).max(1)
+    }
+
+    /// Prompt instruction asking the model to answer in the configured language
+    ///
+    /// Returns `None` when no language (or English) is configured.
+    pub fn language_instruction(&self) -> Option<String> {
+        let language = self.language.as_deref()?.trim();
+        if language.is_empty() || language.eq_ignore_ascii_case("en") {
+            return None;
+        }
+        Some(format!(
+            "Write all human-readable text (reasoning, descriptions of relevant changes, assessments, suggestions) in {}. \
+             Keep JSON keys, booleans, numbers, file paths and quoted code snippets exactly as they are.",
+            language_name(language)
+        ))
+    }
+
+    /// Prompt text carrying the user's clarifications, or `None` when there are none
+    pub fn clarification_instruction(&self) -> Option<String> {
+        let clarifications: Vec<&str> = self
+            .clarifications
+            .iter()
+            .map(|c| c.trim())
+            .filter(|c| !c.is_empty())
+            .collect();
+        if clarifications.is_empty() {
+            return None;
+        }
+        Some(format!(
+            "The user reviewed the test requirements and added these clarifications; they take precedence over the original intent where they conflict:\n{}",
+            clarifications
+                .iter()
+                .map(|c| format!("- {}", c))
+                .collect::<Vec<_>>()
+                .join("\n")
+        ))
+    }
+}
+
+/// Map common ISO 639-1 codes to language names, passing anything else through
+fn language_name(code: &str) -> &str {
+    match code.to_ascii_lowercase().as_str() {
+        "vi" => "Vietnamese",
+        "ja" => "Japanese",
+        "ko" => "Korean",
+        "zh" => "Chinese",
+        "fr" => "French",
+        "de" => "German",
+        "es" => "Spanish",
+        "pt" => "Portuguese",
+        "ru" => "Russian",
+        "th" => "Thai",
+        "id" => "Indonesian",
+        _ => code,
+    }
+}
@@ -0,0 +1,119 @@
+use globset::Glob;
+
+use crate::types::{IntentVerificationResult, Severity};
+
+/// Rules that turn a raw verification result into a final pass/fail decision
+///
+/// Different products can apply different strictness to the same result without
+/// reinterpreting the JSON themselves.
+#[derive(Debug, Clone, serde::Serialize, serde::Deserialize)]
+#[serde(default)]
+pub struct VerdictPolicy {
+    /// Require the model's verdict to be "intent fulfilled"
+    pub require_intent_fulfilled: bool,
+    /// Minimum overall confidence (0.0-1.0)
+    pub min_confidence: f32,
+    /// Maximum number of files that may have (unsuppressed) findings
+    pub max_files_with_issues: Option<usize>,
+    /// Glob patterns; each must match at least one analyzed file that supports the intent
+    pub required_files: Vec<String>,
+    /// Fail when any unsuppressed finding is at or above this severity
+    pub fail_on_severity: Option<Severity>,
+}
+
+impl Default for VerdictPolicy {
+    fn default() -> Self {
+        VerdictPolicy {
+            require_intent_fulfilled: true,
+            min_confidence: 0.0,
+            max_files_with_issues: None,
+            required_files: vec![],
+            fail_on_severity: None,
+        }
+    }
+}
+
+/// Outcome of applying a `VerdictPolicy` to a result
+#[derive(Debug, Clone, PartialEq, serde::Serialize, serde::Deserialize)]
+pub struct PolicyDecision {
+    pub passed: bool,
+    /// Why the policy failed (empty when it passed)
+    pub reasons: Vec<String>,
+}
+
+impl VerdictPolicy {
+    /// Evaluate a verification result against this policy
+    pub fn evaluate(&self, result: &IntentVerificationResult) -> PolicyDecision {
+        let mut reasons = Vec::new();
+
+        if self.require_intent_fulfilled && !result.is_intent_fulfilled {
+            reasons.push("Intent is not fulfilled".to_string());
+        }
+
+        if result.confidence < self.min_confidence {
+            reasons.push(format!(
+                "Confidence {:.2} is below the required {:.2}",
+                result.confidence, self.min_confidence
+            ));
+        }
+
+        if let Some(max) = self.max_files_with_issues {
+            let mut files_with_issues: Vec<&str> = result
+                .findings
+                .iter()
+                .filter(|f| !f.suppressed)
+                .filter_map(|f| f.file_path.as_deref())
+                .collect();
+            files_with_issues.sort_unstable();
+            files_with_issues.dedup();
+            if files_with_issues.len() > max {
+                reasons.push(format!(
+                    "{} files have issues (maximum allowed: {})",
+                    files_with_issues.len(),
+                    max
+                ));
+            }
+        }
+
+        for pattern in &self.required_files {
+            match Glob::new(pattern) {
+                Ok(glob) => {
+                    let matcher = glob.compile_matcher();
+                    let satisfied = result
+                        .files_analyzed
+                        .iter()
+                        .any(|fa| fa.supports_intent && matcher.is_match(&fa.file_path));
+                    if !satisfied {
+                        reasons.push(format!(
+                            "No changed file matching '{}' supports the intent",
+                            pattern
+                        ));
+                    }
+                }
+                Err(e) => reasons.push(format!(
+                    "Invalid required file pattern '{}': {}",
+                    pattern, e
+                )),
+            }
+        }
+
+        if let Some(threshold) = self.fail_on_severity {
+            let blocking = result
+                .findings
+                .iter()
+                .filter(|f| !f.suppressed && f.severity >= threshold)
+                .count();
+            if blocking > 0 {
+                reasons.push(format!(
+                    "{} findings at or above {:?} severity",
+                    blocking, threshold
+                ));
+            }
+        }
+
+        PolicyDecision {
+            passed: reasons.is_empty(),
+            reasons,
+        }
+    }
+}
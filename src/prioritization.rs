@@ -0,0 +1,228 @@
+use std::collections::HashMap;
+use std::sync::LazyLock;
+
+use regex::Regex;
+
+use crate::git::FileChange;
+use crate::options::AnalysisOptions;
+use crate::secrets::added_lines;
+use crate::types::TestTargetsWithCode;
+use crate::utils::estimate_tokens;
+
+/// Words too common in intents to say anything about a file
+const STOP_WORDS: &[&str] = &[
+    "the", "and", "for", "that", "with", "this", "from", "should", "when", "into", "are", "its",
+    "not", "all", "can", "has", "have", "will", "two", "src", "lib", "mod", "main", "test",
+    "tests", "index",
+];
+
+/// Why a changed file wasn't analyzed
+#[derive(Debug, Clone, Copy, PartialEq, Eq, serde::Serialize, serde::Deserialize)]
+pub enum SkipReason {
+    /// Less relevant than the files analyzed under `AnalysisOptions::max_files_analyzed`
+    MaxFiles,
+    /// Over `AnalysisOptions::max_file_bytes`, or beyond `max_total_files` or
+    /// `max_total_tokens`
+    TooLarge,
+}
+
+/// A changed file left out of the analysis
+#[derive(Debug, Clone, PartialEq, serde::Serialize, serde::Deserialize)]
+pub struct SkippedFile {
+    pub file_path: String,
+    pub reason: SkipReason,
+    /// Relevance to the intent the file was ranked by, see `change_priority`
+    pub priority: f32,
+}
+
+static WORD: LazyLock<Regex> = LazyLock::new(|| Regex::new(r"[A-Za-z][a-z]*|\d+").unwrap());
+static IDENTIFIER: LazyLock<Regex> =
+    LazyLock::new(|| Regex::new(r"[A-Za-z_][A-Za-z0-9_]*").unwrap());
+
+/// Lowercase words of a text, with identifiers split at underscores and case changes
+fn words(text: &str) -> impl Iterator<Item = String> + '_ {
+    WORD.find_iter(text)
+        .map(|word| word.as_str().to_lowercase())
+        .filter(|word| word.len() >= 3 && !STOP_WORDS.contains(&word.as_str()))
+}
+
+fn word_counts(text: &str) -> HashMap<String, f32> {
+    let mut counts = HashMap::new();
+    for word in words(text) {
+        *counts.entry(word).or_insert(0.0) += 1.0;
+    }
+    counts
+}
+
+/// Cosine similarity of two word-count vectors
+fn cosine(a: &HashMap<String, f32>, b: &HashMap<String, f32>) -> f32 {
+    let dot: f32 = a
+        .iter()
+        .filter_map(|(word, count)| b.get(word).map(|other| count * other))
+        .sum();
+    let norm = |counts: &HashMap<String, f32>| counts.values().map(|c| c * c).sum::<f32>().sqrt();
+    if dot == 0.0 {
+        0.0
+    } else {
+        dot / (norm(a) * norm(b))
+    }
+}
+
+/// The code a change is about: the added lines, or the removed file's content
+fn changed_code(file_change: &FileChange) -> String {
+    let added = added_lines(file_change);
+    if added.is_empty() {
+        return file_change.old_content.clone().unwrap_or_default();
+    }
+    added
+        .into_iter()
+        .map(|(_, line)| line)
+        .collect::<Vec<_>>()
+        .join("\n")
+}
+
+/// How relevant a changed file is to the intent, from 0.0 to 6.0
+///
+/// Sums three signals, strongest first: overlap with the test targets (the file is a target
+/// file, or its changed code names target functions; up to 3), intent words in the file's
+/// path (up to 2) and the lexical similarity of the changed code to the intent and the target
+/// code (up to 1). The similarity compares word counts, so no embedding model is needed.
+pub fn change_priority(
+    file_change: &FileChange,
+    user_intent: &str,
+    targets_with_code: &TestTargetsWithCode,
+) -> f32 {
+    let code = changed_code(file_change);
+    let identifiers: Vec<&str> = IDENTIFIER.find_iter(&code).map(|m| m.as_str()).collect();
+
+    let functions = &targets_with_code.targets.functions;
+    let named_functions = functions
+        .iter()
+        .filter(|function| {
+            let name = function.rsplit("::").next().unwrap_or(function);
+            identifiers.contains(&name)
+        })
+        .count();
+    let target_overlap = if targets_with_code
+        .targets
+        .files
+        .iter()
+        .any(|file| file.trim_start_matches("./") == file_change.path)
+    {
+        1.0
+    } else if functions.is_empty() {
+        0.0
+    } else {
+        named_functions as f32 / functions.len() as f32
+    };
+
+    let intent_words: Vec<String> = words(user_intent).collect();
+    let path_matches = words(&file_change.path)
+        .filter(|word| intent_words.contains(word))
+        .count();
+    let path_match = (path_matches as f32 / 2.0).min(1.0);
+
+    let mut reference = user_intent.to_string();
+    for function in &targets_with_code.function_contents {
+        reference.push('\n');
+        reference.push_str(function.content.as_deref().unwrap_or(&function.name));
+    }
+    let similarity = cosine(&word_counts(&reference), &word_counts(&code));
+
+    3.0 * target_overlap + 2.0 * path_match + similarity
+}
+
+/// The `max_files` changes most relevant to the intent, in their original order, and the
+/// rest as skipped files, most relevant first
+///
+/// Files of equal priority keep their order, so the first ones are analyzed.
+pub fn prioritize_changes<'a>(
+    file_changes: &'a [FileChange],
+    user_intent: &str,
+    targets_with_code: &TestTargetsWithCode,
+    max_files: usize,
+) -> (Vec<&'a FileChange>, Vec<SkippedFile>) {
+    let file_changes: Vec<&FileChange> = file_changes.iter().collect();
+    prioritize_change_refs(&file_changes, user_intent, targets_with_code, max_files)
+}
+
+/// [`prioritize_changes`] over changes already narrowed down, e.g. by [`limit_changes`]
+pub(crate) fn prioritize_change_refs<'a>(
+    file_changes: &[&'a FileChange],
+    user_intent: &str,
+    targets_with_code: &TestTargetsWithCode,
+    max_files: usize,
+) -> (Vec<&'a FileChange>, Vec<SkippedFile>) {
+    if file_changes.len() <= max_files {
+        return (file_changes.to_vec(), vec![]);
+    }
+    let mut ranked: Vec<(usize, f32)> = file_changes
+        .iter()
+        .map(|file_change| change_priority(file_change, user_intent, targets_with_code))
+        .enumerate()
+        .collect();
+    ranked.sort_by(|a, b| b.1.total_cmp(&a.1).then(a.0.cmp(&b.0)));
+    let kept: Vec<usize> = ranked.iter().take(max_files).map(|(i, _)| *i).collect();
+
+    let skipped = ranked[max_files..]
+        .iter()
+        .map(|&(i, priority)| SkippedFile {
+            file_path: file_changes[i].path.clone(),
+            reason: SkipReason::MaxFiles,
+            priority,
+        })
+        .collect();
+    let analyzed = file_changes
+        .iter()
+        .enumerate()
+        .filter(|(i, _)| kept.contains(i))
+        .map(|(_, file_change)| *file_change)
+        .collect();
+    (analyzed, skipped)
+}
+
+/// The changes within the size limits of `options`, in their original order, and the rest as
+/// [`SkipReason::TooLarge`] skipped files
+///
+/// Files over `max_file_bytes` are dropped first. The others are taken in their original
+/// order until `max_total_files` files are taken; a file that would bring the estimated
+/// tokens of the old and new content over `max_total_tokens` is skipped, and smaller files
+/// after it may still fit.
+pub(crate) fn limit_changes<'a>(
+    file_changes: &'a [FileChange],
+    user_intent: &str,
+    targets_with_code: &TestTargetsWithCode,
+    options: &AnalysisOptions,
+) -> (Vec<&'a FileChange>, Vec<SkippedFile>) {
+    let mut kept = Vec::new();
+    let mut skipped = Vec::new();
+    let mut total_tokens = 0;
+    for file_change in file_changes {
+        let contents = [&file_change.content, &file_change.old_content];
+        let bytes = contents
+            .iter()
+            .filter_map(|content| content.as_ref().map(String::len))
+            .max()
+            .unwrap_or(0);
+        let tokens: usize = contents
+            .iter()
+            .filter_map(|content| content.as_deref().map(estimate_tokens))
+            .sum();
+        let too_large = options.max_file_bytes.is_some_and(|max| bytes > max)
+            || options.max_total_files.is_some_and(|max| kept.len() >= max)
+            || options
+                .max_total_tokens
+                .is_some_and(|max| total_tokens + tokens > max);
+        if too_large {
+            skipped.push(SkippedFile {
+                file_path: file_change.path.clone(),
+                reason: SkipReason::TooLarge,
+                priority: change_priority(file_change, user_intent, targets_with_code),
+            });
+        } else {
+            total_tokens += tokens;
+            kept.push(file_change);
+        }
+    }
+    (kept, skipped)
+}
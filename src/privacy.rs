@@ -0,0 +1,83 @@
+use std::path::Path;
+
+use crate::options::AnalysisOptions;
+use crate::validation::ValidationError;
+
+/// What a verification may reach over the network in local-only mode, see
+/// [`crate::AnalysisOptions::local_only`]
+///
+/// Repositories must be local paths or `file://` URLs, and LLM requests may only go to the
+/// allowlisted endpoints, e.g. an on-prem model. Anything else fails before it's sent.
+/// Commands the verification runs, like linters and test suites, aren't covered.
+#[derive(Debug, Clone, Default, PartialEq, serde::Serialize, serde::Deserialize)]
+#[serde(default)]
+pub struct LocalOnlyPolicy {
+    /// Base URLs LLM requests may go to, e.g. `http://llm.internal:8000/v1`; a URL also
+    /// allows the paths under it
+    pub allowed_llm_endpoints: Vec<String>,
+}
+
+impl LocalOnlyPolicy {
+    /// Policy allowing LLM requests to `endpoints` only
+    pub fn new(endpoints: impl IntoIterator<Item = impl Into<String>>) -> Self {
+        LocalOnlyPolicy {
+            allowed_llm_endpoints: endpoints.into_iter().map(Into::into).collect(),
+        }
+    }
+
+    /// Whether LLM requests may go to `base_url` (`None` is the default OpenAI endpoint)
+    pub fn allows_llm_endpoint(&self, base_url: Option<&str>) -> bool {
+        let Some(base_url) = base_url else {
+            return false;
+        };
+        let base_url = base_url.trim_end_matches('/');
+        self.allowed_llm_endpoints.iter().any(|allowed| {
+            let allowed = allowed.trim_end_matches('/');
+            base_url == allowed
+                || base_url
+                    .strip_prefix(allowed)
+                    .is_some_and(|rest| rest.starts_with('/'))
+        })
+    }
+
+    /// Whether `url` can be read without the network: a local path or `file://` URL
+    pub fn allows_repository(&self, url: &str) -> bool {
+        match url.split_once("://") {
+            Some((scheme, _)) => scheme == "file",
+            None => Path::new(url).exists(),
+        }
+    }
+}
+
+/// Fail when local-only mode doesn't allow LLM requests to `base_url`
+pub(crate) fn check_llm_endpoint(
+    options: &AnalysisOptions,
+    base_url: Option<&str>,
+) -> Result<(), ValidationError> {
+    match &options.local_only {
+        Some(policy) if options.calls_model() && !policy.allows_llm_endpoint(base_url) => {
+            Err(ValidationError::LlmEndpointNotAllowed {
+                endpoint: base_url
+                    .unwrap_or("the default OpenAI endpoint")
+                    .to_string(),
+            })
+        }
+        _ => Ok(()),
+    }
+}
+
+/// Fail when local-only mode doesn't allow fetching `url`
+#[cfg(feature = "git")]
+pub(crate) fn check_repository(
+    options: &AnalysisOptions,
+    url: &str,
+) -> Result<(), ValidationError> {
+    match &options.local_only {
+        Some(policy) if !policy.allows_repository(url) => {
+            Err(ValidationError::NetworkAccessNotAllowed {
+                url: url.to_string(),
+            })
+        }
+        _ => Ok(()),
+    }
+}
@@ -0,0 +1,145 @@
+use crate::types::{Finding, IntentVerificationResult, Severity};
+
+/// Rule prefix of the vulnerabilities reported by the model under the security profile
+pub const SECURITY_RULE_PREFIX: &str = "security/";
+
+/// How the changes are judged
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, serde::Serialize, serde::Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum VerificationProfile {
+    /// Whether the changes make the tests of the intent pass
+    #[default]
+    Standard,
+    /// For intents like "fix the vulnerability": the model also reports vulnerabilities the
+    /// changes introduce or leave in place, findings are tagged with CWE identifiers, and any
+    /// unsuppressed security finding of medium severity or above fails the verdict
+    Security,
+}
+
+impl VerificationProfile {
+    /// Extra prompt instruction for the per-file analysis, or `None` for the standard profile
+    pub fn file_analysis_instruction(self) -> Option<&'static str> {
+        match self {
+            VerificationProfile::Standard => None,
+            VerificationProfile::Security => Some(
+                "This is a security review: the intent is to fix a vulnerability. A change only supports the intent if it removes the root cause \
+                 (not just the symptom a test checks) without opening another hole. \
+                 Also include security_issues (array): for every vulnerability this code introduces or still contains, an object with \
+                 cwe (string like \"CWE-89\"), severity (\"low\", \"medium\", \"high\" or \"critical\"), line (int, 1-based, if known) and description (string). \
+                 Use an empty array when there are none.",
+            ),
+        }
+    }
+
+    /// Extra prompt instruction for the overall assessment, or `None` for the standard profile
+    pub fn assessment_instruction(self) -> Option<&'static str> {
+        match self {
+            VerificationProfile::Standard => None,
+            VerificationProfile::Security => Some(
+                "This is a security review. State whether the vulnerability is fixed at its root cause and mention any security issue the changes leave open.",
+            ),
+        }
+    }
+}
+
+impl std::str::FromStr for VerificationProfile {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s.to_lowercase().as_str() {
+            "standard" => Ok(VerificationProfile::Standard),
+            "security" => Ok(VerificationProfile::Security),
+            other => Err(format!(
+                "Unknown profile '{}' (expected standard or security)",
+                other
+            )),
+        }
+    }
+}
+
+/// CWE identifier for the deterministic rules that detect a weakness
+pub fn cwe_for_rule(rule: &str) -> Option<&'static str> {
+    if rule.starts_with("secrets/") {
+        return Some("CWE-798");
+    }
+    let cwe = match rule {
+        "ruff/S105" | "ruff/S106" | "ruff/S107" => "CWE-259",
+        "ruff/S301" | "ruff/S403" => "CWE-502",
+        "ruff/S324" => "CWE-327",
+        "ruff/S501" => "CWE-295",
+        "ruff/S602" | "ruff/S605" => "CWE-78",
+        "ruff/S608" => "CWE-89",
+        "eslint/no-eval" | "eslint/no-implied-eval" | "eslint/no-new-func" => "CWE-95",
+        _ => return None,
+    };
+    Some(cwe)
+}
+
+/// Tag findings from rules with a known weakness with its CWE identifier
+pub fn tag_cwe(findings: &mut [Finding]) {
+    for finding in findings.iter_mut().filter(|f| f.cwe.is_none()) {
+        finding.cwe = cwe_for_rule(&finding.rule).map(str::to_string);
+    }
+}
+
+/// Findings for the `security_issues` the model listed in a file analysis response
+pub(crate) fn parse_security_issues(json: &serde_json::Value, file_path: &str) -> Vec<Finding> {
+    json["security_issues"]
+        .as_array()
+        .into_iter()
+        .flatten()
+        .filter_map(|issue| {
+            let description = issue["description"].as_str()?.trim();
+            let cwe = issue["cwe"]
+                .as_str()
+                .map(|cwe| cwe.trim().to_uppercase())
+                .filter(|cwe| cwe.starts_with("CWE-"));
+            Some(Finding {
+                rule: format!(
+                    "{}{}",
+                    SECURITY_RULE_PREFIX,
+                    cwe.as_deref().unwrap_or("unclassified").to_lowercase()
+                ),
+                severity: serde_json::from_value(issue["severity"].clone())
+                    .unwrap_or(Severity::Medium),
+                file_path: Some(file_path.to_string()),
+                line: issue["line"]
+                    .as_u64()
+                    .filter(|&line| line > 0)
+                    .map(|line| line as usize),
+                snippet: None,
+                message: description.to_string(),
+                suppressed: false,
+                cwe,
+                content_hash: None,
+            })
+        })
+        .collect()
+}
+
+/// Under the security profile, force a negative verdict when unsuppressed security findings
+/// of medium severity or above remain
+///
+/// Returns the number of such findings.
+pub fn apply_security_profile(
+    result: &mut IntentVerificationResult,
+    profile: VerificationProfile,
+) -> usize {
+    if profile != VerificationProfile::Security {
+        return 0;
+    }
+    tag_cwe(&mut result.findings);
+    let issues = result
+        .findings
+        .iter()
+        .filter(|f| !f.suppressed && f.cwe.is_some() && f.severity >= Severity::Medium)
+        .count();
+    if issues > 0 {
+        result.is_intent_fulfilled = false;
+        result.explanation = format!(
+            "{}; {} security issue(s) remain in the changes",
+            result.explanation, issues
+        );
+    }
+    issues
+}
@@ -0,0 +1,135 @@
+use std::sync::Arc;
+use std::sync::atomic::{AtomicBool, Ordering};
+
+use tokio::sync::Notify;
+
+/// Pipeline stage a progress update refers to
+#[derive(Debug, Clone, Copy, PartialEq, Eq, serde::Serialize, serde::Deserialize)]
+pub enum ProgressStage {
+    ExtractingTargets,
+    ReadingTargets,
+    FetchingChanges,
+    AnalyzingFiles,
+    Assessing,
+    Done,
+}
+
+/// Snapshot of how far a verification has got
+#[derive(Debug, Clone, PartialEq, serde::Serialize, serde::Deserialize)]
+pub struct Progress {
+    pub stage: ProgressStage,
+    /// Changed files analyzed so far
+    pub files_done: usize,
+    /// Changed files to analyze (0 until the changes are known)
+    pub files_total: usize,
+    /// File that just finished, for `AnalyzingFiles` updates
+    pub current_file: Option<String>,
+    /// Overall completion estimate (0.0-100.0)
+    pub percent: f32,
+}
+
+impl Progress {
+    /// Progress at the start of `stage`, with per-file analysis filling most of the range
+    pub fn new(stage: ProgressStage, files_done: usize, files_total: usize) -> Self {
+        let percent = match stage {
+            ProgressStage::ExtractingTargets => 0.0,
+            ProgressStage::ReadingTargets => 5.0,
+            ProgressStage::FetchingChanges => 10.0,
+            ProgressStage::AnalyzingFiles if files_total > 0 => {
+                15.0 + 80.0 * files_done as f32 / files_total as f32
+            }
+            ProgressStage::AnalyzingFiles => 15.0,
+            ProgressStage::Assessing => 95.0,
+            ProgressStage::Done => 100.0,
+        };
+        Progress {
+            stage,
+            files_done,
+            files_total,
+            current_file: None,
+            percent,
+        }
+    }
+}
+
+/// Callback receiving progress updates, set on [`crate::AnalysisOptions::progress`]
+///
+/// May be called from any runtime thread, and concurrently when files are analyzed in parallel.
+#[derive(Clone)]
+pub struct ProgressHandler(Arc<dyn Fn(&Progress) + Send + Sync>);
+
+impl ProgressHandler {
+    pub fn new(handler: impl Fn(&Progress) + Send + Sync + 'static) -> Self {
+        ProgressHandler(Arc::new(handler))
+    }
+
+    pub fn report(&self, progress: &Progress) {
+        (self.0)(progress)
+    }
+}
+
+impl std::fmt::Debug for ProgressHandler {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.write_str("ProgressHandler")
+    }
+}
+
+/// Shared flag for stopping a verification early
+///
+/// Clones share the same state. The pipeline checks it between steps and returns
+/// [`Cancelled`] as soon as it notices.
+#[derive(Debug, Clone, Default)]
+pub struct CancellationToken(Arc<CancellationState>);
+
+#[derive(Debug, Default)]
+struct CancellationState {
+    cancelled: AtomicBool,
+    notify: Notify,
+}
+
+impl CancellationToken {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn cancel(&self) {
+        self.0.cancelled.store(true, Ordering::SeqCst);
+        self.0.notify.notify_waiters();
+    }
+
+    pub fn is_cancelled(&self) -> bool {
+        self.0.cancelled.load(Ordering::SeqCst)
+    }
+
+    /// Wait until the token is cancelled
+    pub async fn cancelled(&self) {
+        loop {
+            let notified = self.0.notify.notified();
+            if self.is_cancelled() {
+                return;
+            }
+            notified.await;
+        }
+    }
+
+    /// `Err(Cancelled)` once the token has been cancelled
+    pub fn check(&self) -> Result<(), Cancelled> {
+        if self.is_cancelled() {
+            Err(Cancelled)
+        } else {
+            Ok(())
+        }
+    }
+}
+
+/// Error returned when a verification is stopped through its [`CancellationToken`]
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Cancelled;
+
+impl std::fmt::Display for Cancelled {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.write_str("verification was cancelled")
+    }
+}
+
+impl std::error::Error for Cancelled {}
@@ -0,0 +1,269 @@
+use std::collections::BTreeMap;
+use std::error::Error;
+use std::path::Path;
+use std::sync::LazyLock;
+
+#[cfg(feature = "git")]
+use crate::openai::verify_intent_with_options;
+#[cfg(feature = "git")]
+use crate::options::AnalysisOptions;
+use crate::result_diff::ResultDiff;
+use crate::types::{IntentVerificationResult, PROMPT_VERSION};
+
+/// The prompt templates of one version
+///
+/// Templates reference values as `{name}`; any other brace is kept as is, so JSON examples
+/// need no escaping. Versions are recorded in `ResultMetadata::prompt_version`.
+#[derive(Debug, Clone, PartialEq, serde::Serialize, serde::Deserialize)]
+pub struct PromptTemplates {
+    pub version: String,
+    /// Asks for the functions, files, modules, classes, commands and endpoints the intent
+    /// refers to. Placeholder: `{intent}`
+    pub target_extraction: String,
+    /// System message of the per-file analysis
+    pub file_analysis_system: String,
+    /// Per-file analysis request. Placeholders: `{intent}`, `{path}`, `{block_info}`,
+    /// `{change_type}`, `{code}`
+    pub file_analysis: String,
+    /// Overall assessment request. Placeholders: `{intent}`, `{functions}`,
+    /// `{found_functions}`, `{total_functions}`, `{files}`, `{found_files}`, `{total_files}`,
+    /// `{other_targets}`, `{summary}`
+    pub overall_assessment: String,
+}
+
+static CURRENT: LazyLock<PromptTemplates> = LazyLock::new(|| {
+    PromptTemplates {
+    version: PROMPT_VERSION.to_string(),
+    target_extraction: r#"Extract from the following prompt what the user expects to work: function names, file paths, modules or packages, classes (or structs, traits and other types), CLI commands and API endpoints.
+Only list what the prompt mentions; leave a list empty when it names nothing of that kind. Write endpoints as "METHOD /path", or just the path when no method is named.
+
+Respond ONLY in this strict JSON format:
+{
+  "functions": ["..."],
+  "files": ["..."],
+  "modules": ["..."],
+  "classes": ["..."],
+  "commands": ["..."],
+  "endpoints": ["..."]
+}
+
+Prompt:
+"{intent}"
+"#
+    .to_string(),
+    file_analysis_system: "You are an AI specialized in code analysis for test intent verification.\n\
+         Your task:\n\
+         1. First, understand the test requirements and what functionality needs to work\n\
+         2. Then, analyze the code changes in the solution commits\n\
+         3. Verify if these code changes would make the specified tests pass\n\
+         4. Determine if changes support fulfilling the user's intent\n\
+         5. Identify specific relevant changes that address test requirements\n\
+         6. Classify whether each change is required for the intent, merely supporting it, or unrelated\n\
+         7. Back every claim of your reasoning with a citation of the exact lines it rests on\n\
+         - Return strict JSON format with: supports_intent (bool), relevance (string), reasoning (string), relevant_changes (array), locations (array of {start_line, end_line, snippet}), citations (array of {claim, file, start_line, end_line, quote}), confidence (float)\n\
+         - Be specific about what works and what might still be missing\n"
+        .to_string(),
+    file_analysis: "STEP 2: ANALYZE THE SOLUTION CODE CHANGES\n\n\
+         USER INTENT: \"{intent}\"\n\n\
+         SOLUTION FILE: {path}{block_info}\n\
+         CHANGE TYPE: {change_type}\n\n\
+         CODE IMPLEMENTATION:\n\
+         ```\n{code}\n```\n\n\
+         STEP 3: VERIFY IF THIS SOLUTION MAKES THE TESTS PASS\n\
+         - Does this code implement the functionality required by the tests?\n\
+         - Are there any missing implementations or bugs?\n\
+         - Would the test functions work correctly with these changes?\n\
+         - Does this fulfill the user's intent?\n\n\
+         Respond in JSON format with:\n\
+         - supports_intent (bool): true if this code would make the tests pass\n\
+         - relevance (string): \"required\" if the intent can't be met without this change, \"supporting\" if it helps (tests, docs, wiring) but isn't essential, \"unrelated\" if it has nothing to do with the intent\n\
+         - reasoning (string): explain what works and what might be missing\n\
+         - relevant_changes (array): list specific code changes that address test requirements\n\
+         - locations (array): for each relevant change, an object with start_line (int), end_line (int) and snippet (string, code quoted exactly from the file)\n\
+         - citations (array): for every claim in the reasoning, an object with claim (string), file (string, the solution file or a test file), start_line (int), end_line (int) and quote (string, the cited lines copied exactly); don't make claims you can't cite\n\
+         - confidence (float): your confidence level (0.0-1.0)"
+        .to_string(),
+    overall_assessment: r#"Provide a concise overall assessment of whether the code changes fulfill the test intent.
+
+User Intent: "{intent}"
+Target Functions: {functions} (found {found_functions}/{total_functions} in codebase)
+Target Files: {files} (found {found_files}/{total_files})
+Other Targets:
+{other_targets}
+
+File Analysis Summary:
+{summary}
+
+Provide a 2-3 sentence assessment covering:
+1. Whether the changes are likely to make the specified tests work
+2. Key supporting or missing changes
+3. Overall confidence in test success
+
+Respond with just the assessment text (no JSON):"#
+        .to_string(),
+}
+});
+
+impl Default for PromptTemplates {
+    fn default() -> Self {
+        Self::current().clone()
+    }
+}
+
+impl PromptTemplates {
+    /// The templates the crate ships with, version [`PROMPT_VERSION`]
+    pub fn current() -> &'static PromptTemplates {
+        &CURRENT
+    }
+
+    /// Load templates from a TOML (`.toml`) or JSON file
+    pub fn load(path: impl AsRef<Path>) -> Result<Self, Box<dyn Error>> {
+        let path = path.as_ref();
+        let text = std::fs::read_to_string(path)?;
+        match path.extension().and_then(|ext| ext.to_str()) {
+            Some("toml") => Ok(toml::from_str(&text)?),
+            _ => Ok(serde_json::from_str(&text)?),
+        }
+    }
+}
+
+/// Fill the `{name}` placeholders of a template in one pass, so substituted text is never
+/// expanded again
+pub(crate) fn render(template: &str, values: &[(&str, &str)]) -> String {
+    let mut rendered = String::with_capacity(template.len());
+    let mut rest = template;
+    while let Some(start) = rest.find('{') {
+        rendered.push_str(&rest[..start]);
+        let after = &rest[start + 1..];
+        let value = after.find('}').and_then(|end| {
+            values
+                .iter()
+                .find(|(name, _)| *name == &after[..end])
+                .map(|(name, value)| (name.len(), *value))
+        });
+        match value {
+            Some((len, value)) => {
+                rendered.push_str(value);
+                rest = &after[len + 1..];
+            }
+            None => {
+                rendered.push('{');
+                rest = after;
+            }
+        }
+    }
+    rendered.push_str(rest);
+    rendered
+}
+
+/// Prompt template versions by identifier, starting with the current one
+#[derive(Debug, Clone)]
+pub struct PromptRegistry {
+    versions: BTreeMap<String, PromptTemplates>,
+}
+
+impl Default for PromptRegistry {
+    fn default() -> Self {
+        let current = PromptTemplates::current().clone();
+        PromptRegistry {
+            versions: BTreeMap::from([(current.version.clone(), current)]),
+        }
+    }
+}
+
+impl PromptRegistry {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Add a version, replacing any registered under the same identifier
+    pub fn register(&mut self, templates: PromptTemplates) {
+        self.versions.insert(templates.version.clone(), templates);
+    }
+
+    pub fn get(&self, version: &str) -> Option<&PromptTemplates> {
+        self.versions.get(version)
+    }
+
+    /// Registered version identifiers, sorted
+    pub fn versions(&self) -> impl Iterator<Item = &str> {
+        self.versions.keys().map(String::as_str)
+    }
+}
+
+/// Outcome of the same verification run under two prompt versions
+#[derive(Debug, Clone, serde::Serialize, serde::Deserialize)]
+pub struct PromptComparison {
+    pub version_a: String,
+    pub version_b: String,
+    pub result_a: IntentVerificationResult,
+    pub result_b: IntentVerificationResult,
+    /// Changes from version A's result to version B's
+    pub diff: ResultDiff,
+}
+
+impl PromptComparison {
+    pub fn new(result_a: IntentVerificationResult, result_b: IntentVerificationResult) -> Self {
+        PromptComparison {
+            version_a: result_a.metadata.prompt_version.clone(),
+            version_b: result_b.metadata.prompt_version.clone(),
+            diff: result_a.diff(&result_b),
+            result_a,
+            result_b,
+        }
+    }
+
+    /// Whether both versions reached the same verdict
+    pub fn verdicts_agree(&self) -> bool {
+        self.result_a.is_intent_fulfilled == self.result_b.is_intent_fulfilled
+    }
+}
+
+/// Run the same verification under two prompt versions and diff the outcomes
+///
+/// The runs are sequential and share `options` apart from the templates. Any difference
+/// besides the prompts (e.g. model sampling) shows up in the diff too, so compare over a
+/// corpus (see `run_eval`) before trusting a single case.
+#[cfg(feature = "git")]
+#[allow(clippy::too_many_arguments)]
+pub async fn compare_prompt_versions(
+    version_a: &PromptTemplates,
+    version_b: &PromptTemplates,
+    test_repo_url: &str,
+    test_commit: &str,
+    solution_repo_url: &str,
+    solution_commit1: &str,
+    solution_commit2: &str,
+    user_intent: &str,
+    api_key: &str,
+    model: Option<&str>,
+    base_url: Option<&str>,
+    options: &AnalysisOptions,
+) -> Result<PromptComparison, Box<dyn Error>> {
+    let mut results = Vec::new();
+    for templates in [version_a, version_b] {
+        eprintln!("🧪 Verifying with prompt version {}", templates.version);
+        let options = AnalysisOptions {
+            prompts: Some(templates.clone()),
+            ..options.clone()
+        };
+        results.push(
+            verify_intent_with_options(
+                test_repo_url,
+                test_commit,
+                solution_repo_url,
+                solution_commit1,
+                solution_commit2,
+                user_intent,
+                api_key,
+                model,
+                base_url,
+                &options,
+            )
+            .await?,
+        );
+    }
+    let result_b = results.pop().unwrap();
+    let result_a = results.pop().unwrap();
+    Ok(PromptComparison::new(result_a, result_b))
+}
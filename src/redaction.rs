@@ -0,0 +1,161 @@
+use std::collections::BTreeMap;
+
+use regex::{Regex, escape};
+
+use crate::secrets::SECRET_PATTERNS;
+
+/// What to strip from content before it's sent to the model, see
+/// [`crate::AnalysisOptions::redaction`]
+///
+/// Each match is replaced by `[REDACTED:<kind>]`, e.g. `[REDACTED:email]`, in every message
+/// of every request, dry-run previews included.
+#[derive(Debug, Clone, PartialEq, serde::Serialize, serde::Deserialize)]
+#[serde(default)]
+pub struct RedactionConfig {
+    /// Replace email addresses
+    pub emails: bool,
+    /// Replace credentials in the formats `scan_for_secrets` knows
+    pub secrets: bool,
+    /// Domains whose hostnames are internal; `corp.example.com` also covers
+    /// `ci.corp.example.com`
+    pub internal_domains: Vec<String>,
+    /// Regexes of proprietary identifiers, e.g. project code names or customer ids
+    pub patterns: Vec<String>,
+    /// Matches kept as is, e.g. a public support address
+    pub allowlist: Vec<String>,
+}
+
+impl Default for RedactionConfig {
+    fn default() -> Self {
+        RedactionConfig {
+            emails: true,
+            secrets: true,
+            internal_domains: vec![],
+            patterns: vec![],
+            allowlist: vec![],
+        }
+    }
+}
+
+/// Matches of one kind redacted from one source
+#[derive(Debug, Clone, PartialEq, serde::Serialize, serde::Deserialize)]
+pub struct Redaction {
+    /// `email`, `internal-host`, `secret/<rule>` or `pattern/<regex>`
+    pub kind: String,
+    /// Where the matches were: `intent`, `clarifications` or a file path
+    pub source: String,
+    pub count: usize,
+}
+
+/// A [`RedactionConfig`] with its regexes compiled
+pub(crate) struct Redactor {
+    rules: Vec<(String, Regex)>,
+    allowlist: Vec<String>,
+}
+
+impl Redactor {
+    pub(crate) fn new(config: &RedactionConfig) -> Result<Self, regex::Error> {
+        let mut rules = Vec::new();
+        if config.secrets {
+            for (rule, _, pattern) in SECRET_PATTERNS {
+                rules.push((format!("secret/{}", rule), Regex::new(pattern)?));
+            }
+        }
+        if config.emails {
+            rules.push((
+                "email".to_string(),
+                Regex::new(
+                    r"\b[A-Za-z0-9._%+-]+@[A-Za-z0-9-]+(?:\.[A-Za-z0-9-]+)*\.[A-Za-z]{2,}\b",
+                )?,
+            ));
+        }
+        for domain in &config.internal_domains {
+            let domain = domain.trim_start_matches('.');
+            rules.push((
+                "internal-host".to_string(),
+                Regex::new(&format!(r"(?i)\b(?:[a-z0-9-]+\.)*{}\b", escape(domain)))?,
+            ));
+        }
+        for pattern in &config.patterns {
+            rules.push((format!("pattern/{}", pattern), Regex::new(pattern)?));
+        }
+        Ok(Redactor {
+            rules,
+            allowlist: config.allowlist.clone(),
+        })
+    }
+
+    /// `text` with every match replaced, and how many matches of each kind there were
+    pub(crate) fn redact(&self, text: &str) -> (String, BTreeMap<String, usize>) {
+        let mut text = text.to_string();
+        let mut counts = BTreeMap::new();
+        for (kind, regex) in &self.rules {
+            let mut count = 0;
+            let redacted = regex.replace_all(&text, |captures: &regex::Captures| {
+                let matched = &captures[0];
+                if self.allowlist.iter().any(|allowed| allowed == matched) {
+                    matched.to_string()
+                } else {
+                    count += 1;
+                    format!("[REDACTED:{}]", kind.split('/').next().unwrap_or(kind))
+                }
+            });
+            if count > 0 {
+                text = redacted.into_owned();
+                *counts.entry(kind.clone()).or_default() += count;
+            }
+        }
+        (text, counts)
+    }
+
+    /// Redact every string in a JSON value, e.g. serialized chat messages
+    pub(crate) fn redact_json(&self, value: &mut serde_json::Value) {
+        match value {
+            serde_json::Value::String(text) => *text = self.redact(text).0,
+            serde_json::Value::Array(values) => {
+                values.iter_mut().for_each(|value| self.redact_json(value))
+            }
+            serde_json::Value::Object(map) => {
+                map.values_mut().for_each(|value| self.redact_json(value))
+            }
+            _ => {}
+        }
+    }
+}
+
+/// `text` with what `config` covers replaced, and what was redacted from it
+///
+/// Shows what the model would see, e.g. to check a configuration before turning it on.
+pub fn redact_text(
+    text: &str,
+    config: &RedactionConfig,
+) -> Result<(String, Vec<Redaction>), regex::Error> {
+    let (text, counts) = Redactor::new(config)?.redact(text);
+    let redactions = counts
+        .into_iter()
+        .map(|(kind, count)| Redaction {
+            kind,
+            source: "text".to_string(),
+            count,
+        })
+        .collect();
+    Ok((text, redactions))
+}
+
+/// What the redaction pass strips from each source, as `(source, content)` pairs
+pub(crate) fn redaction_report<'a>(
+    redactor: &Redactor,
+    sources: impl IntoIterator<Item = (&'a str, &'a str)>,
+) -> Vec<Redaction> {
+    let mut redactions = Vec::new();
+    for (source, content) in sources {
+        for (kind, count) in redactor.redact(content).1 {
+            redactions.push(Redaction {
+                kind,
+                source: source.to_string(),
+                count,
+            });
+        }
+    }
+    redactions
+}
@@ -0,0 +1,79 @@
+use std::sync::LazyLock;
+
+use async_openai::error::OpenAIError;
+use async_openai::types::chat::{ChatChoice, FinishReason};
+use regex::Regex;
+
+use crate::types::{FunctionContent, TestTargetsWithCode};
+
+/// How replies that decline the request start, when the provider doesn't flag them
+static REFUSAL_TEXT: LazyLock<Regex> = LazyLock::new(|| {
+    Regex::new(
+        r"(?i)^\s*(i'm sorry|i am sorry|sorry, (but )?i|i can(no|')t (help|assist|comply)|i'm (not able|unable) to|i am (not able|unable) to|i won't)",
+    )
+    .unwrap()
+});
+
+static STRING_LITERAL: LazyLock<Regex> =
+    LazyLock::new(|| Regex::new(r#""(?:[^"\\\n]|\\.)*""#).unwrap());
+static LINE_COMMENT: LazyLock<Regex> =
+    LazyLock::new(|| Regex::new(r"(?m)(//|^\s*#\s)(.*)$").unwrap());
+static BLOCK_COMMENT: LazyLock<Regex> = LazyLock::new(|| Regex::new(r"(?s)/\*.*?\*/").unwrap());
+
+/// Why the model didn't answer a chat request, when it refused or the provider's content
+/// filter blocked the reply
+///
+/// Besides `refusal` messages and the `content_filter` finish reason, replies that open with
+/// an apology and contain no JSON count as refusals.
+pub(crate) fn refusal_reason(choice: &ChatChoice) -> Option<String> {
+    if choice.finish_reason == Some(FinishReason::ContentFilter) {
+        return Some("The provider's content filter blocked the reply".to_string());
+    }
+    if let Some(refusal) = choice.message.refusal.as_deref()
+        && !refusal.trim().is_empty()
+    {
+        return Some(refusal.trim().to_string());
+    }
+    let content = choice.message.content.as_deref()?;
+    (REFUSAL_TEXT.is_match(content) && !content.contains('{')).then(|| content.trim().to_string())
+}
+
+/// Why the provider rejected a chat request, when its content filter blocked the prompt
+pub(crate) fn filtered_request_reason(error: &OpenAIError) -> Option<String> {
+    match error {
+        OpenAIError::ApiError(e)
+            if e.code.as_deref() == Some("content_filter")
+                || e.message.contains("content management policy") =>
+        {
+            Some(format!(
+                "The provider's content filter blocked the prompt: {}",
+                e.message
+            ))
+        }
+        _ => None,
+    }
+}
+
+/// `code` with string literals and comments masked, which are what usually trips content
+/// filters in otherwise ordinary code
+pub(crate) fn sanitize_code(code: &str) -> String {
+    // Strings first, so `//` in URLs isn't taken for a comment
+    let code = STRING_LITERAL.replace_all(code, "\"[string]\"");
+    let code = BLOCK_COMMENT.replace_all(&code, "/* [comment] */");
+    LINE_COMMENT.replace_all(&code, "$1 [comment]").into_owned()
+}
+
+/// The test targets with their code sanitized like [`sanitize_code`]
+pub(crate) fn sanitize_targets(targets_with_code: &TestTargetsWithCode) -> TestTargetsWithCode {
+    let mut sanitized = targets_with_code.clone();
+    for file in &mut sanitized.file_contents {
+        file.content = sanitize_code(&file.content);
+    }
+    for FunctionContent { content, .. } in &mut sanitized.function_contents {
+        *content = content.as_deref().map(sanitize_code);
+    }
+    for source in &mut sanitized.tested_sources {
+        source.content = sanitize_code(&source.content);
+    }
+    sanitized
+}
@@ -0,0 +1,92 @@
+use crate::criteria::CriterionResult;
+use crate::result_diff::ResultDiff;
+use crate::types::IntentVerificationResult;
+
+/// What changed since an earlier verification of the same intent, e.g. the previous run on a
+/// long-running bounty branch
+#[derive(Debug, Clone, PartialEq, serde::Serialize, serde::Deserialize)]
+pub struct Regression {
+    /// When the earlier verification finished (RFC 3339)
+    pub previous_run_at: String,
+    /// Changes from the earlier result to this one
+    pub diff: ResultDiff,
+    /// Criteria that were met before and aren't anymore
+    pub broken_criteria: Vec<CriterionResult>,
+    /// Criteria that were met before but couldn't be checked this time
+    pub unchecked_criteria: Vec<String>,
+    /// Criteria that failed before and are met now
+    pub restored_criteria: Vec<String>,
+}
+
+impl Regression {
+    /// Compare an earlier result with the current one
+    pub fn between(
+        previous: &IntentVerificationResult,
+        current: &IntentVerificationResult,
+    ) -> Self {
+        let now = |criterion: &str| {
+            current
+                .acceptance_criteria
+                .iter()
+                .find(|c| c.criterion == criterion)
+        };
+        let mut broken_criteria = Vec::new();
+        let mut unchecked_criteria = Vec::new();
+        let mut restored_criteria = Vec::new();
+        for before in &previous.acceptance_criteria {
+            match (before.passed, now(&before.criterion)) {
+                (true, Some(after)) if !after.passed => broken_criteria.push(after.clone()),
+                (true, None) => unchecked_criteria.push(before.criterion.clone()),
+                (false, Some(after)) if after.passed => {
+                    restored_criteria.push(before.criterion.clone())
+                }
+                _ => {}
+            }
+        }
+
+        Regression {
+            previous_run_at: previous.metadata.finished_at.clone(),
+            diff: previous.diff(current),
+            broken_criteria,
+            unchecked_criteria,
+            restored_criteria,
+        }
+    }
+
+    /// True when anything that held before no longer does
+    pub fn has_regressions(&self) -> bool {
+        self.diff.has_regressions() || !self.broken_criteria.is_empty()
+    }
+
+    /// One line per regression, for reports
+    pub fn summary(&self) -> Vec<String> {
+        let mut lines = Vec::new();
+        if self.diff.was_fulfilled && !self.diff.is_fulfilled {
+            lines.push("The intent was fulfilled before and isn't anymore".to_string());
+        }
+        for criterion in &self.broken_criteria {
+            lines.push(format!(
+                "Criterion no longer met: {} ({})",
+                criterion.criterion, criterion.evidence
+            ));
+        }
+        for path in &self.diff.no_longer_supporting {
+            lines.push(format!("`{}` no longer supports the intent", path));
+        }
+        for finding in &self.diff.new_findings {
+            lines.push(format!(
+                "New finding `{}`: {}",
+                finding.rule, finding.message
+            ));
+        }
+        lines
+    }
+}
+
+/// Set `result.regression` by comparing it with the earlier result
+pub fn apply_regression(
+    result: &mut IntentVerificationResult,
+    previous: &IntentVerificationResult,
+) {
+    result.regression = Some(Regression::between(previous, result));
+}
@@ -0,0 +1,815 @@
+use similar::{ChangeTag, TextDiff};
+
+use crate::codeowners::files_by_owner;
+use crate::git::{ChangeType, FileChange};
+use crate::types::{FileIntentAnalysis, IntentVerificationResult, Severity};
+
+/// Render an intent verification result as a Markdown summary
+///
+/// The output contains a verdict banner, a per-file table and the model's reasoning
+/// for each file inside collapsible `<details>` blocks, so it can be posted as a PR comment.
+pub fn render_markdown(result: &IntentVerificationResult) -> String {
+    let mut md = String::new();
+
+    // Nothing was judged; only the questions matter
+    if let Some(ambiguity) = &result.needs_clarification {
+        md.push_str("## ❓ Intent needs clarification\n\n");
+        md.push_str(&format!(
+            "**Ambiguity:** {:.2}  \n**Summary:** {}\n\n",
+            ambiguity.score, result.explanation
+        ));
+        for reason in &ambiguity.reasons {
+            md.push_str(&format!("- {}\n", reason));
+        }
+        md.push_str("\n### Clarifying questions\n\n");
+        for (i, question) in ambiguity.questions.iter().enumerate() {
+            md.push_str(&format!("{}. {}\n", i + 1, question));
+        }
+        return md;
+    }
+
+    // Verdict banner
+    if result.is_intent_fulfilled {
+        md.push_str("## ✅ Intent fulfilled\n\n");
+    } else {
+        md.push_str("## ❌ Intent not fulfilled\n\n");
+    }
+    md.push_str(&format!(
+        "**Confidence:** {:.0}%  \n**Risk score:** {:.2}  \n**Summary:** {}\n\n",
+        result.confidence * 100.0,
+        result.risk_score,
+        result.explanation
+    ));
+    if let Some(calibration) = &result.calibration {
+        md.push_str(&format!(
+            "_Confidence calibrated from the log-probabilities of {} file(s); uncalibrated {:.0}%_\n\n",
+            calibration.files_with_logprobs,
+            calibration.raw * 100.0
+        ));
+    }
+    if let Some(retry) = &result.model_retry {
+        md.push_str(&format!(
+            "_Re-judged by {} ({}); {} first found the intent {} with {:.0}% confidence_\n\n",
+            retry.retried.model,
+            retry.reasons.join("; "),
+            retry.initial.model,
+            if retry.initial.is_intent_fulfilled {
+                "fulfilled"
+            } else {
+                "not fulfilled"
+            },
+            retry.initial.confidence * 100.0
+        ));
+    }
+    let unresolved: Vec<String> = result
+        .target_resolution
+        .iter()
+        .filter(|target| target.is_unresolved())
+        .map(|target| format!("`{}`", target.name))
+        .collect();
+    if !result.is_intent_fulfilled && !unresolved.is_empty() {
+        md.push_str(&format!(
+            "> ⚠️ {} of {} targets weren't found in the test repository: {}. The verdict may come \
+             from a failed target lookup rather than a missing implementation.\n\n",
+            unresolved.len(),
+            result.target_resolution.len(),
+            unresolved.join(", ")
+        ));
+    }
+    if !result.language_stats.is_empty() {
+        let languages: Vec<String> = result
+            .language_stats
+            .iter()
+            .map(|stats| {
+                format!(
+                    "{} ({} files, +{} -{})",
+                    stats.language, stats.files, stats.lines_added, stats.lines_removed
+                )
+            })
+            .collect();
+        md.push_str(&format!("**Languages:** {}\n\n", languages.join(", ")));
+    }
+
+    if !result.overall_assessment.is_empty() {
+        md.push_str("### Overall assessment\n\n");
+        md.push_str(&format!("{}\n\n", result.overall_assessment.trim()));
+    }
+
+    if let Some(summary) = &result.change_summary {
+        let changelog = summary.to_changelog();
+        if !changelog.is_empty() {
+            md.push_str("### What changed\n\n");
+            // Demote the changelog headings below the section heading
+            md.push_str(&changelog.replace("### ", "#### "));
+        }
+    }
+
+    if !result.warnings.is_empty() {
+        md.push_str("> ⚠️ **Partial result:** some stages or files could not be analyzed.\n>\n");
+        for warning in &result.warnings {
+            match &warning.file_path {
+                Some(path) => md.push_str(&format!(
+                    "> - {:?} (`{}`): {}\n",
+                    warning.kind, path, warning.message
+                )),
+                None => md.push_str(&format!("> - {:?}: {}\n", warning.kind, warning.message)),
+            }
+        }
+        md.push('\n');
+    }
+
+    if !result.skipped_files.is_empty() {
+        md.push_str(&format!(
+            "> ✂️ **{} changed files were not analyzed:**\n>\n",
+            result.skipped_files.len()
+        ));
+        for skipped in &result.skipped_files {
+            md.push_str(&format!(
+                "> - `{}` ({:?}, priority {:.2})\n",
+                skipped.file_path, skipped.reason, skipped.priority
+            ));
+        }
+        md.push('\n');
+    }
+
+    if !result.binary_changes.is_empty() {
+        md.push_str("### Binary files\n\n");
+        md.push_str("| File | Change | Type | Size change | SHA-256 |\n");
+        md.push_str("| --- | --- | --- | --- | --- |\n");
+        for change in &result.binary_changes {
+            let sha256 = change
+                .new
+                .as_ref()
+                .or(change.old.as_ref())
+                .map(|metadata| format!("`{}`", &metadata.sha256[..metadata.sha256.len().min(12)]))
+                .unwrap_or_else(|| "-".to_string());
+            md.push_str(&format!(
+                "| `{}` | {:?} | {} | {:+} bytes | {} |\n",
+                escape_table_cell(&change.file_path),
+                change.change_type,
+                change.mime_type,
+                change.size_delta,
+                sha256
+            ));
+        }
+        md.push('\n');
+    }
+
+    if !result.findings.is_empty() {
+        md.push_str("### Findings\n\n");
+        md.push_str("| Severity | Rule | Location | Message |\n");
+        md.push_str("| --- | --- | --- | --- |\n");
+        for finding in &result.findings {
+            let location = match (&finding.file_path, finding.line) {
+                (Some(path), Some(line)) => format!("`{}:{}`", escape_table_cell(path), line),
+                (Some(path), None) => format!("`{}`", escape_table_cell(path)),
+                _ => "-".to_string(),
+            };
+            md.push_str(&format!(
+                "| {:?}{} | `{}` | {} | {} |\n",
+                finding.severity,
+                if finding.suppressed {
+                    " (suppressed)"
+                } else {
+                    ""
+                },
+                finding.rule,
+                location,
+                escape_table_cell(&finding.message)
+            ));
+        }
+        md.push('\n');
+    }
+
+    if !result.acceptance_criteria.is_empty() {
+        md.push_str("### Acceptance criteria\n\n");
+        md.push_str("| | Criterion | Evidence |\n");
+        md.push_str("| --- | --- | --- |\n");
+        for criterion in &result.acceptance_criteria {
+            md.push_str(&format!(
+                "| {} | {} | {} |\n",
+                if criterion.passed { "✅" } else { "❌" },
+                escape_table_cell(&criterion.criterion),
+                escape_table_cell(&criterion.evidence)
+            ));
+        }
+        md.push('\n');
+    }
+
+    if let Some(escalation) = &result.escalation {
+        md.push_str("### Needs human review\n\n");
+        for reason in &escalation.reasons {
+            md.push_str(&format!("- {}\n", reason));
+        }
+        md.push('\n');
+        for (i, question) in escalation.questions.iter().enumerate() {
+            let region = match (&question.location, &question.file_path) {
+                (Some(location), _) => format!(
+                    " (`{}:{}-{}`)",
+                    location.file_path, location.start_line, location.end_line
+                ),
+                (None, Some(path)) => format!(" (`{}`)", path),
+                (None, None) => String::new(),
+            };
+            md.push_str(&format!("{}. {}{}\n", i + 1, question.question, region));
+        }
+        md.push('\n');
+    }
+
+    if let Some(regression) = &result.regression
+        && regression.has_regressions()
+    {
+        md.push_str(&format!(
+            "### Regressions since the previous run\n\nCompared with the verification of {}:\n\n",
+            regression.previous_run_at
+        ));
+        for line in regression.summary() {
+            md.push_str(&format!("- {}\n", line));
+        }
+        md.push('\n');
+    }
+
+    if !result.unrelated_changes.is_empty() {
+        md.push_str(&format!(
+            "### Unrelated changes\n\nScope score: {:.2}. These files don't appear to be needed for the intent:\n\n",
+            result.scope_score
+        ));
+        for path in &result.unrelated_changes {
+            md.push_str(&format!("- `{}`\n", path));
+        }
+        md.push('\n');
+    }
+
+    if let Some(coverage) = &result.coverage_evidence
+        && !coverage.functions.is_empty()
+    {
+        md.push_str(&format!(
+            "### Coverage evidence\n\n{:.0}% of the changed functions are referenced by the test targets.\n\n",
+            coverage.exercised_ratio * 100.0
+        ));
+        md.push_str("| Function | Exercised by |\n");
+        md.push_str("| --- | --- |\n");
+        for function in &coverage.functions {
+            let exercised_by = if function.exercised_by.is_empty() {
+                "⚠️ none".to_string()
+            } else {
+                function
+                    .exercised_by
+                    .iter()
+                    .map(|label| format!("`{}`", escape_table_cell(label)))
+                    .collect::<Vec<_>>()
+                    .join(", ")
+            };
+            md.push_str(&format!(
+                "| `{}` (`{}`) | {} |\n",
+                function.function,
+                escape_table_cell(&function.file_path),
+                exercised_by
+            ));
+        }
+        md.push('\n');
+    }
+
+    if !result.target_resolution.is_empty() {
+        md.push_str("### Targets\n\n");
+        md.push_str("| Target | Kind | Status | Location |\n");
+        md.push_str("| --- | --- | --- | --- |\n");
+        for target in &result.target_resolution {
+            let location = match (&target.file_path, target.line, &target.error) {
+                (Some(file_path), Some(line), _) => format!("`{}:{}`", file_path, line),
+                (Some(file_path), None, _) => format!("`{}`", file_path),
+                (None, _, Some(error)) => error.clone(),
+                (None, _, None) => "-".to_string(),
+            };
+            md.push_str(&format!(
+                "| `{}` | {} | {} | {} |\n",
+                escape_table_cell(&target.name),
+                target.kind.as_str(),
+                target.status.as_str(),
+                escape_table_cell(&location)
+            ));
+        }
+        md.push('\n');
+    }
+
+    if result.files_analyzed.is_empty() {
+        md.push_str("_No changed files were analyzed._\n");
+        return md;
+    }
+
+    // Per-file table
+    md.push_str("### Files analyzed\n\n");
+    md.push_str("| File | Change | Verdict |\n");
+    md.push_str("| --- | --- | --- |\n");
+    for fa in &result.files_analyzed {
+        md.push_str(&format!(
+            "| `{}` | {:?} | {} |\n",
+            escape_table_cell(&fa.file_path),
+            fa.change_type,
+            verdict_label(fa)
+        ));
+    }
+    md.push('\n');
+
+    if result.files_analyzed.iter().any(|fa| !fa.owners.is_empty()) {
+        md.push_str("### Files by owner\n\n");
+        for (owner, files) in files_by_owner(result) {
+            let supporting = files.iter().filter(|fa| fa.supports_intent).count();
+            md.push_str(&format!(
+                "- **{}** ({} of {} files support the intent): {}\n",
+                owner.unwrap_or("No owner"),
+                supporting,
+                files.len(),
+                files
+                    .iter()
+                    .map(|fa| format!("`{}`", fa.file_path))
+                    .collect::<Vec<_>>()
+                    .join(", ")
+            ));
+        }
+        md.push('\n');
+    }
+
+    // Collapsible reasoning per file. Paths and model output are escaped so they can't close
+    // the `<details>` block or inject markup
+    md.push_str("### Reasoning\n\n");
+    for fa in &result.files_analyzed {
+        md.push_str(&format!(
+            "<details>\n<summary><code>{}</code> — {}</summary>\n\n",
+            escape_html(&fa.file_path),
+            verdict_label(fa)
+        ));
+        md.push_str(&format!("{}\n", escape_markdown_html(fa.reasoning.trim())));
+        if !fa.relevant_changes.is_empty() {
+            md.push_str("\n**Relevant changes:**\n\n");
+            for change in &fa.relevant_changes {
+                md.push_str(&format!(
+                    "- {}\n",
+                    escape_markdown_html(&escape_table_cell(change))
+                ));
+            }
+        }
+        if !fa.locations.is_empty() {
+            md.push_str("\n**Locations:**\n\n");
+            for loc in &fa.locations {
+                md.push_str(&format!(
+                    "- `{}:{}-{}`\n",
+                    loc.file_path, loc.start_line, loc.end_line
+                ));
+            }
+        }
+        if !fa.citations.is_empty() {
+            md.push_str("\n**Citations:**\n\n");
+            for citation in &fa.citations {
+                md.push_str(&format!(
+                    "- {}`{}:{}-{}` {}\n",
+                    if citation.verified {
+                        ""
+                    } else {
+                        "⚠️ unverified "
+                    },
+                    citation.file_path,
+                    citation.start_line,
+                    citation.end_line,
+                    escape_markdown_html(&escape_table_cell(&citation.claim))
+                ));
+            }
+        }
+        md.push_str("\n</details>\n\n");
+    }
+
+    md
+}
+
+/// Short verdict label for a single file analysis
+fn verdict_label(fa: &FileIntentAnalysis) -> &'static str {
+    if fa.supports_intent {
+        "✅ Supports"
+    } else {
+        "❌ Does not support"
+    }
+}
+
+/// Escape characters that would break a Markdown table cell
+fn escape_table_cell(text: &str) -> String {
+    text.replace('|', "\\|").replace('\n', " ")
+}
+
+/// Escape HTML in Markdown text, leaving code spans alone since Markdown shows them verbatim
+fn escape_markdown_html(text: &str) -> String {
+    let mut escaped = String::with_capacity(text.len());
+    let mut rest = text;
+    while let Some(start) = rest.find('`') {
+        escaped.push_str(&escape_html(&rest[..start]));
+        let ticks = rest[start..].len() - rest[start..].trim_start_matches('`').len();
+        let fence = &rest[start..start + ticks];
+        let after = &rest[start + ticks..];
+        match after.find(fence) {
+            // An unclosed backtick run is plain text, so what follows still needs escaping
+            None => {
+                escaped.push_str(fence);
+                rest = after;
+            }
+            Some(end) => {
+                escaped.push_str(&rest[start..start + ticks + end + ticks]);
+                rest = &after[end + ticks..];
+            }
+        }
+    }
+    escaped.push_str(&escape_html(rest));
+    escaped
+}
+
+/// Render an intent verification result as a standalone HTML report
+///
+/// Each analyzed file is shown with a side-by-side diff of its old and new content (taken from
+/// the matching entry in `file_changes`), next to the model's reasoning. Lines quoted in the
+/// file's `relevant_changes` or `locations` are highlighted so reviewers can audit the verdict.
+pub fn render_html(result: &IntentVerificationResult, file_changes: &[FileChange]) -> String {
+    let mut html = String::new();
+
+    html.push_str("<!DOCTYPE html>\n<html lang=\"en\">\n<head>\n<meta charset=\"utf-8\">\n");
+    html.push_str("<title>Intent Verification Report</title>\n");
+    html.push_str(HTML_STYLE);
+    html.push_str("</head>\n<body>\n");
+
+    // Verdict banner
+    let (banner_class, banner_text) = if result.is_intent_fulfilled {
+        ("pass", "✅ Intent fulfilled")
+    } else {
+        ("fail", "❌ Intent not fulfilled")
+    };
+    html.push_str(&format!(
+        "<div class=\"banner {}\"><h1>{}</h1><p>Confidence: {:.0}% — {}</p></div>\n",
+        banner_class,
+        banner_text,
+        result.confidence * 100.0,
+        escape_html(&result.explanation)
+    ));
+
+    if !result.overall_assessment.is_empty() {
+        html.push_str(&format!(
+            "<section class=\"assessment\"><h2>Overall assessment</h2><p>{}</p></section>\n",
+            escape_html(result.overall_assessment.trim())
+        ));
+    }
+
+    for fa in &result.files_analyzed {
+        let file_change = file_changes.iter().find(|fc| fc.path == fa.file_path);
+
+        html.push_str(&format!(
+            "<section class=\"file\">\n<h2><code>{}</code> <span class=\"tag\">{:?}</span> <span class=\"{}\">{}</span></h2>\n",
+            escape_html(&fa.file_path),
+            fa.change_type,
+            if fa.supports_intent { "pass" } else { "fail" },
+            verdict_label(fa)
+        ));
+        html.push_str("<div class=\"layout\">\n<div class=\"diff\">\n");
+        match file_change {
+            Some(fc) => html.push_str(&render_side_by_side_diff(fc, fa)),
+            None => html.push_str("<p class=\"muted\">No diff available for this file.</p>\n"),
+        }
+        html.push_str("</div>\n<aside class=\"reasoning\">\n<h3>Reasoning</h3>\n");
+        html.push_str(&format!(
+            "<p>{}</p>\n",
+            escape_markdown_html(fa.reasoning.trim())
+        ));
+        if !fa.relevant_changes.is_empty() {
+            html.push_str("<h3>Relevant changes</h3>\n<ul>\n");
+            for change in &fa.relevant_changes {
+                html.push_str(&format!("<li>{}</li>\n", escape_html(change)));
+            }
+            html.push_str("</ul>\n");
+        }
+        html.push_str("</aside>\n</div>\n</section>\n");
+    }
+
+    html.push_str("</body>\n</html>\n");
+    html
+}
+
+/// Render the old and new content of a file change as a two-column diff table
+fn render_side_by_side_diff(file_change: &FileChange, analysis: &FileIntentAnalysis) -> String {
+    let old = file_change.old_content.as_deref().unwrap_or("");
+    let new = file_change.content.as_deref().unwrap_or("");
+
+    let diff = TextDiff::from_lines(old, new);
+    let mut rows = String::new();
+
+    for change in diff.iter_all_changes() {
+        let text = change.value().trim_end_matches(['\n', '\r']);
+        let in_location = change.new_index().is_some_and(|i| {
+            analysis
+                .locations
+                .iter()
+                .any(|loc| (loc.start_line..=loc.end_line).contains(&(i + 1)))
+        });
+        let highlight = if in_location || is_relevant_line(text, &analysis.relevant_changes) {
+            " relevant"
+        } else {
+            ""
+        };
+        let old_no = change
+            .old_index()
+            .map(|i| (i + 1).to_string())
+            .unwrap_or_default();
+        let new_no = change
+            .new_index()
+            .map(|i| (i + 1).to_string())
+            .unwrap_or_default();
+
+        let row = match change.tag() {
+            ChangeTag::Equal => format!(
+                "<tr><td class=\"ln\">{}</td><td>{}</td><td class=\"ln\">{}</td><td>{}</td></tr>\n",
+                old_no,
+                escape_html(text),
+                new_no,
+                escape_html(text)
+            ),
+            ChangeTag::Delete => format!(
+                "<tr><td class=\"ln\">{}</td><td class=\"del\">{}</td><td class=\"ln\"></td><td></td></tr>\n",
+                old_no,
+                escape_html(text)
+            ),
+            ChangeTag::Insert => format!(
+                "<tr><td class=\"ln\"></td><td></td><td class=\"ln\">{}</td><td class=\"ins{}\">{}</td></tr>\n",
+                new_no,
+                highlight,
+                escape_html(text)
+            ),
+        };
+        rows.push_str(&row);
+    }
+
+    format!(
+        "<table>\n<tr><th colspan=\"2\">Before</th><th colspan=\"2\">After</th></tr>\n{}</table>\n",
+        rows
+    )
+}
+
+/// Check whether an added line is quoted by any of the relevant changes
+fn is_relevant_line(line: &str, relevant_changes: &[String]) -> bool {
+    let trimmed = line.trim();
+    // Very short lines (braces, `else`, ...) would match almost anything
+    if trimmed.len() < 8 {
+        return false;
+    }
+    relevant_changes.iter().any(|rc| rc.contains(trimmed))
+}
+
+/// Render an intent verification result as JUnit XML
+///
+/// The overall verdict becomes one test case and every analyzed file another, with a
+/// `<failure>` for anything that does not support the intent, so CI dashboards
+/// (Jenkins, GitLab, ...) can display verification outcomes natively.
+pub fn render_junit(result: &IntentVerificationResult) -> String {
+    let failures = result
+        .files_analyzed
+        .iter()
+        .filter(|fa| !fa.supports_intent)
+        .count()
+        + usize::from(!result.is_intent_fulfilled);
+    let tests = result.files_analyzed.len() + 1;
+
+    let mut xml = String::from("<?xml version=\"1.0\" encoding=\"UTF-8\"?>\n");
+    xml.push_str(&format!(
+        "<testsuites name=\"intent-verification\" tests=\"{}\" failures=\"{}\">\n",
+        tests, failures
+    ));
+    xml.push_str(&format!(
+        "  <testsuite name=\"intent-verification\" tests=\"{}\" failures=\"{}\">\n",
+        tests, failures
+    ));
+
+    // Overall verdict
+    xml.push_str("    <testcase classname=\"intent\" name=\"overall intent\">\n");
+    if !result.is_intent_fulfilled {
+        xml.push_str(&format!(
+            "      <failure message=\"{}\">{}</failure>\n",
+            escape_xml(&result.explanation),
+            escape_xml(result.overall_assessment.trim())
+        ));
+    }
+    xml.push_str(&format!(
+        "      <system-out>Confidence: {:.2}</system-out>\n",
+        result.confidence
+    ));
+    xml.push_str("    </testcase>\n");
+
+    // One test case per analyzed file
+    for fa in &result.files_analyzed {
+        xml.push_str(&format!(
+            "    <testcase classname=\"files\" name=\"{}\">\n",
+            escape_xml(&fa.file_path)
+        ));
+        if !fa.supports_intent {
+            xml.push_str(&format!(
+                "      <failure message=\"{:?} file does not support the intent\">{}</failure>\n",
+                fa.change_type,
+                escape_xml(fa.reasoning.trim())
+            ));
+        } else {
+            xml.push_str(&format!(
+                "      <system-out>{}</system-out>\n",
+                escape_xml(fa.reasoning.trim())
+            ));
+        }
+        xml.push_str("    </testcase>\n");
+    }
+
+    xml.push_str("  </testsuite>\n</testsuites>\n");
+    xml
+}
+
+/// An annotation in the format expected by the GitHub Checks API
+/// (`output.annotations` of a check run)
+#[derive(Debug, Clone, PartialEq, serde::Serialize, serde::Deserialize)]
+pub struct GithubAnnotation {
+    pub path: String,
+    pub start_line: usize,
+    pub end_line: usize,
+    /// `notice`, `warning` or `failure`
+    pub annotation_level: String,
+    pub title: String,
+    pub message: String,
+}
+
+/// Convert a verification result into GitHub Checks annotations
+///
+/// Findings become annotations at their line, with the level derived from severity. Each
+/// change location of a supporting file becomes a `notice`, and files that don't support
+/// the intent get a `warning` on their first relevant line. Deleted files are skipped since
+/// they can't be annotated on the PR diff. GitHub accepts at most 50 annotations per
+/// request, so callers should send the list in chunks.
+pub fn render_github_annotations(result: &IntentVerificationResult) -> Vec<GithubAnnotation> {
+    let mut annotations = Vec::new();
+
+    for finding in result.findings.iter().filter(|f| !f.suppressed) {
+        let Some(path) = &finding.file_path else {
+            continue;
+        };
+        let line = finding.line.unwrap_or(1);
+        let level = match finding.severity {
+            Severity::Info | Severity::Low => "notice",
+            Severity::Medium => "warning",
+            Severity::High | Severity::Critical => "failure",
+        };
+        annotations.push(GithubAnnotation {
+            path: path.clone(),
+            start_line: line,
+            end_line: line,
+            annotation_level: level.to_string(),
+            title: format!("{} ({:?})", finding.rule, finding.severity),
+            message: finding.message.clone(),
+        });
+    }
+
+    for fa in &result.files_analyzed {
+        if fa.change_type == ChangeType::Deleted {
+            continue;
+        }
+
+        if fa.supports_intent {
+            for loc in &fa.locations {
+                annotations.push(GithubAnnotation {
+                    path: fa.file_path.clone(),
+                    start_line: loc.start_line,
+                    end_line: loc.end_line,
+                    annotation_level: "notice".to_string(),
+                    title: "Supports the intent".to_string(),
+                    message: fa.reasoning.clone(),
+                });
+            }
+        } else {
+            let (start_line, end_line) = fa
+                .locations
+                .first()
+                .map(|loc| (loc.start_line, loc.end_line))
+                .unwrap_or((1, 1));
+            annotations.push(GithubAnnotation {
+                path: fa.file_path.clone(),
+                start_line,
+                end_line,
+                annotation_level: "warning".to_string(),
+                title: "Does not support the intent".to_string(),
+                message: fa.reasoning.clone(),
+            });
+        }
+    }
+
+    annotations
+}
+
+/// Render an intent verification result as a SARIF 2.1.0 log
+///
+/// Findings become results at their line, files that don't support the intent become
+/// `intent/unsupported-change` warnings, and an unfulfilled verdict becomes an
+/// `intent/not-fulfilled` error. Findings accepted through a baseline are kept with an
+/// external suppression, so code scanning UIs show them as dismissed.
+pub fn render_sarif(result: &IntentVerificationResult) -> serde_json::Value {
+    let mut results = Vec::new();
+
+    if !result.is_intent_fulfilled {
+        results.push(serde_json::json!({
+            "ruleId": "intent/not-fulfilled",
+            "level": "error",
+            "message": { "text": result.explanation },
+        }));
+    }
+
+    for finding in &result.findings {
+        let level = match finding.severity {
+            Severity::Info | Severity::Low => "note",
+            Severity::Medium => "warning",
+            Severity::High | Severity::Critical => "error",
+        };
+        let mut sarif_result = serde_json::json!({
+            "ruleId": finding.rule,
+            "level": level,
+            "message": { "text": finding.message },
+        });
+        if let Some(path) = &finding.file_path {
+            let line = finding.line.unwrap_or(1);
+            sarif_result["locations"] = serde_json::json!([sarif_location(path, line, line)]);
+        }
+        if finding.suppressed {
+            sarif_result["suppressions"] = serde_json::json!([{ "kind": "external" }]);
+        }
+        results.push(sarif_result);
+    }
+
+    for fa in result
+        .files_analyzed
+        .iter()
+        .filter(|fa| !fa.supports_intent)
+    {
+        let (start_line, end_line) = fa
+            .locations
+            .first()
+            .map(|loc| (loc.start_line, loc.end_line))
+            .unwrap_or((1, 1));
+        results.push(serde_json::json!({
+            "ruleId": "intent/unsupported-change",
+            "level": "warning",
+            "message": { "text": fa.reasoning },
+            "locations": [sarif_location(&fa.file_path, start_line, end_line)],
+        }));
+    }
+
+    serde_json::json!({
+        "$schema": "https://json.schemastore.org/sarif-2.1.0.json",
+        "version": "2.1.0",
+        "runs": [{
+            "tool": {
+                "driver": {
+                    "name": "intent-verification",
+                    "version": env!("CARGO_PKG_VERSION"),
+                }
+            },
+            "results": results,
+        }],
+    })
+}
+
+fn sarif_location(path: &str, start_line: usize, end_line: usize) -> serde_json::Value {
+    serde_json::json!({
+        "physicalLocation": {
+            "artifactLocation": { "uri": path },
+            "region": { "startLine": start_line.max(1), "endLine": end_line.max(start_line).max(1) },
+        }
+    })
+}
+
+/// Escape text for inclusion in HTML
+fn escape_html(text: &str) -> String {
+    text.replace('&', "&amp;")
+        .replace('<', "&lt;")
+        .replace('>', "&gt;")
+        .replace('"', "&quot;")
+}
+
+/// Escape text for inclusion in XML content or attributes
+fn escape_xml(text: &str) -> String {
+    escape_html(text).replace('\'', "&apos;")
+}
+
+const HTML_STYLE: &str = r#"<style>
+body { font-family: -apple-system, Segoe UI, Helvetica, Arial, sans-serif; margin: 2rem; color: #24292f; }
+.banner { padding: 1rem 1.5rem; border-radius: 6px; margin-bottom: 1.5rem; }
+.banner.pass { background: #dafbe1; }
+.banner.fail { background: #ffebe9; }
+span.pass { color: #1a7f37; }
+span.fail { color: #cf222e; }
+.tag { font-size: 0.8em; background: #eaeef2; border-radius: 4px; padding: 0 0.4em; }
+.layout { display: flex; gap: 1rem; align-items: flex-start; }
+.diff { flex: 3; overflow-x: auto; }
+.reasoning { flex: 1; background: #f6f8fa; padding: 0 1rem; border-radius: 6px; }
+table { border-collapse: collapse; width: 100%; font-family: ui-monospace, Menlo, monospace; font-size: 12px; }
+td { white-space: pre; vertical-align: top; padding: 0 0.5em; }
+td.ln { color: #8c959f; text-align: right; user-select: none; }
+td.del { background: #ffebe9; }
+td.ins { background: #dafbe1; }
+td.ins.relevant { background: #fff8c5; font-weight: bold; }
+.muted { color: #8c959f; }
+</style>
+"#;
@@ -0,0 +1,102 @@
+use crate::types::{Finding, IntentVerificationResult};
+
+/// Differences between two verification runs of the same intent
+#[derive(Debug, Clone, PartialEq, serde::Serialize, serde::Deserialize)]
+pub struct ResultDiff {
+    /// Verdict of the earlier run
+    pub was_fulfilled: bool,
+    /// Verdict of the later run
+    pub is_fulfilled: bool,
+    /// Later confidence minus earlier confidence
+    pub confidence_delta: f32,
+    /// Files that flipped from not supporting to supporting the intent
+    pub newly_supporting: Vec<String>,
+    /// Files that flipped from supporting to not supporting the intent
+    pub no_longer_supporting: Vec<String>,
+    /// Files only analyzed in the later run
+    pub added_files: Vec<String>,
+    /// Files only analyzed in the earlier run
+    pub removed_files: Vec<String>,
+    /// Findings present in the later run but not in the earlier one
+    pub new_findings: Vec<Finding>,
+    /// Findings present in the earlier run but not in the later one
+    pub resolved_findings: Vec<Finding>,
+}
+
+impl ResultDiff {
+    /// True when the later run is worse: verdict lost, files regressed or new findings appeared
+    pub fn has_regressions(&self) -> bool {
+        (self.was_fulfilled && !self.is_fulfilled)
+            || !self.no_longer_supporting.is_empty()
+            || !self.new_findings.is_empty()
+    }
+}
+
+impl IntentVerificationResult {
+    /// Compare this result (the earlier run) with `other` (the later run)
+    pub fn diff(&self, other: &IntentVerificationResult) -> ResultDiff {
+        let mut newly_supporting = Vec::new();
+        let mut no_longer_supporting = Vec::new();
+        let mut removed_files = Vec::new();
+
+        for before in &self.files_analyzed {
+            match other
+                .files_analyzed
+                .iter()
+                .find(|after| after.file_path == before.file_path)
+            {
+                Some(after) if !before.supports_intent && after.supports_intent => {
+                    newly_supporting.push(before.file_path.clone())
+                }
+                Some(after) if before.supports_intent && !after.supports_intent => {
+                    no_longer_supporting.push(before.file_path.clone())
+                }
+                Some(_) => {}
+                None => removed_files.push(before.file_path.clone()),
+            }
+        }
+
+        let added_files = other
+            .files_analyzed
+            .iter()
+            .filter(|after| {
+                !self
+                    .files_analyzed
+                    .iter()
+                    .any(|before| before.file_path == after.file_path)
+            })
+            .map(|after| after.file_path.clone())
+            .collect();
+
+        let new_findings = other
+            .findings
+            .iter()
+            .filter(|f| !self.findings.iter().any(|g| same_finding(f, g)))
+            .cloned()
+            .collect();
+        let resolved_findings = self
+            .findings
+            .iter()
+            .filter(|f| !other.findings.iter().any(|g| same_finding(f, g)))
+            .cloned()
+            .collect();
+
+        ResultDiff {
+            was_fulfilled: self.is_intent_fulfilled,
+            is_fulfilled: other.is_intent_fulfilled,
+            confidence_delta: other.confidence - self.confidence,
+            newly_supporting,
+            no_longer_supporting,
+            added_files,
+            removed_files,
+            new_findings,
+            resolved_findings,
+        }
+    }
+}
+
+/// Findings are the same issue when rule, file and offending snippet match
+/// (line numbers are ignored because they shift between commits)
+fn same_finding(a: &Finding, b: &Finding) -> bool {
+    a.rule == b.rule && a.file_path == b.file_path && a.snippet == b.snippet
+}
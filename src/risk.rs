@@ -0,0 +1,138 @@
+use similar::{ChangeTag, TextDiff};
+
+use crate::git::{ChangeType, FileChange};
+use crate::types::{FileIntentAnalysis, IntentVerificationResult, Severity};
+use crate::utils::is_test_path;
+
+/// Number of changed lines at which the size factor saturates
+const LARGE_CHANGE_LINES: f32 = 300.0;
+
+/// Fill in `risk_score` on every analyzed file and on the result itself
+///
+/// Scores range from 0.0 (trivial) to 1.0 (needs careful review) and combine change size,
+/// file criticality (path heuristics), the most severe finding for the file and the
+/// uncertainty of the verdict.
+pub fn apply_risk_scores(result: &mut IntentVerificationResult, file_changes: &[FileChange]) {
+    let uncertainty = (1.0 - result.confidence).clamp(0.0, 1.0);
+
+    for analysis in &mut result.files_analyzed {
+        let changed_lines = file_changes
+            .iter()
+            .find(|fc| fc.path == analysis.file_path)
+            .map(count_changed_lines)
+            .unwrap_or(0);
+        let severity = result
+            .findings
+            .iter()
+            .filter(|f| {
+                !f.suppressed && f.file_path.as_deref() == Some(analysis.file_path.as_str())
+            })
+            .map(|f| f.severity)
+            .max();
+
+        analysis.risk_score = file_risk_score(analysis, changed_lines, severity, uncertainty);
+    }
+
+    result.risk_score = overall_risk_score(&result.files_analyzed, uncertainty);
+}
+
+/// Risk score of a single file
+pub fn file_risk_score(
+    analysis: &FileIntentAnalysis,
+    changed_lines: usize,
+    max_severity: Option<Severity>,
+    uncertainty: f32,
+) -> f32 {
+    let size = (changed_lines as f32 / LARGE_CHANGE_LINES).min(1.0);
+    let criticality = file_criticality(&analysis.file_path);
+    let severity = max_severity.map(severity_weight).unwrap_or(0.0);
+    // Changes that don't contribute to the intent deserve a closer look
+    let unsupported = if analysis.supports_intent { 0.0 } else { 1.0 };
+
+    let score =
+        0.25 * size + 0.25 * criticality + 0.3 * severity + 0.1 * unsupported + 0.1 * uncertainty;
+    score.clamp(0.0, 1.0)
+}
+
+/// Aggregate risk of a verification: dominated by the riskiest file, raised by the average
+fn overall_risk_score(files: &[FileIntentAnalysis], uncertainty: f32) -> f32 {
+    if files.is_empty() {
+        return uncertainty * 0.5;
+    }
+    let max = files.iter().map(|f| f.risk_score).fold(0.0, f32::max);
+    let mean = files.iter().map(|f| f.risk_score).sum::<f32>() / files.len() as f32;
+    (0.6 * max + 0.3 * mean + 0.1 * uncertainty).clamp(0.0, 1.0)
+}
+
+/// How critical a file is judging by its path (0.0-1.0)
+pub fn file_criticality(path: &str) -> f32 {
+    let lower = path.to_lowercase();
+    let file_name = lower.rsplit('/').next().unwrap_or(&lower);
+
+    const CRITICAL: [&str; 10] = [
+        "auth",
+        "security",
+        "crypto",
+        "password",
+        "secret",
+        "payment",
+        "billing",
+        "migration",
+        "permission",
+        "token",
+    ];
+    const BUILD_AND_DEPLOY: [&str; 7] = [
+        "cargo.toml",
+        "package.json",
+        "dockerfile",
+        "makefile",
+        "build.rs",
+        "setup.py",
+        "pyproject.toml",
+    ];
+
+    if CRITICAL.iter().any(|k| lower.contains(k))
+        || lower.starts_with(".github/workflows/")
+        || lower.ends_with(".sql")
+    {
+        1.0
+    } else if BUILD_AND_DEPLOY.contains(&file_name)
+        || lower.ends_with(".yml")
+        || lower.ends_with(".yaml")
+        || lower.ends_with(".tf")
+    {
+        0.7
+    } else if is_test_path(&lower)
+        || lower.starts_with("docs/")
+        || lower.ends_with(".md")
+        || lower.ends_with(".txt")
+    {
+        0.2
+    } else {
+        0.5
+    }
+}
+
+fn severity_weight(severity: Severity) -> f32 {
+    match severity {
+        Severity::Info => 0.1,
+        Severity::Low => 0.25,
+        Severity::Medium => 0.5,
+        Severity::High => 0.8,
+        Severity::Critical => 1.0,
+    }
+}
+
+/// Number of inserted plus deleted lines in a file change
+pub(crate) fn count_changed_lines(file_change: &FileChange) -> usize {
+    let old = file_change.old_content.as_deref().unwrap_or("");
+    let new = file_change.content.as_deref().unwrap_or("");
+    match file_change.status {
+        ChangeType::Added => new.lines().count(),
+        ChangeType::Deleted => old.lines().count(),
+        ChangeType::Modified => TextDiff::from_lines(old, new)
+            .iter_all_changes()
+            .filter(|c| c.tag() != ChangeTag::Equal)
+            .count(),
+    }
+}
@@ -0,0 +1,367 @@
+#[cfg(feature = "git")]
+use std::collections::{BTreeMap, HashMap};
+#[cfg(feature = "git")]
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::sync::{Arc, Mutex};
+use std::time::Duration;
+
+#[cfg(feature = "git")]
+use futures::stream::{FuturesUnordered, StreamExt};
+#[cfg(feature = "git")]
+use tokio::sync::Notify;
+use tokio::time::Instant;
+
+#[cfg(feature = "git")]
+use crate::git::{mirror_repository, spawn_git};
+#[cfg(feature = "git")]
+use crate::llm_client::LlmClient;
+#[cfg(feature = "git")]
+use crate::openai::verify_intent_with_options;
+#[cfg(feature = "git")]
+use crate::options::AnalysisOptions;
+#[cfg(feature = "git")]
+use crate::progress::{CancellationToken, Cancelled};
+#[cfg(feature = "git")]
+use crate::types::IntentVerificationResult;
+#[cfg(feature = "git")]
+use crate::verifier::VerificationRequest;
+
+/// Spaces out LLM requests evenly, however many verifications share it
+///
+/// Clones share the same budget.
+#[derive(Debug, Clone)]
+pub struct RateLimiter {
+    interval: Duration,
+    next_slot: Arc<Mutex<Instant>>,
+}
+
+impl RateLimiter {
+    /// Allow at most `requests` requests per minute
+    pub fn per_minute(requests: u32) -> Self {
+        RateLimiter {
+            interval: Duration::from_secs(60) / requests.max(1),
+            next_slot: Arc::new(Mutex::new(Instant::now())),
+        }
+    }
+
+    /// Wait for the next free request slot
+    pub async fn acquire(&self) {
+        let slot = {
+            let mut next_slot = self.next_slot.lock().unwrap();
+            let slot = (*next_slot).max(Instant::now());
+            *next_slot = slot + self.interval;
+            slot
+        };
+        tokio::time::sleep_until(slot).await;
+    }
+}
+
+/// Settings shared by every job of a [`VerificationScheduler`]
+#[cfg(feature = "git")]
+#[derive(Debug, Clone)]
+pub struct SchedulerConfig {
+    pub api_key: String,
+    pub model: Option<String>,
+    pub base_url: Option<String>,
+    /// Options of jobs that don't bring their own
+    pub options: AnalysisOptions,
+    /// Number of verifications run at the same time
+    pub max_concurrent_jobs: usize,
+    /// LLM requests per minute across all jobs (unlimited when `None`)
+    pub requests_per_minute: Option<u32>,
+}
+
+#[cfg(feature = "git")]
+impl Default for SchedulerConfig {
+    fn default() -> Self {
+        SchedulerConfig {
+            api_key: String::new(),
+            model: None,
+            base_url: None,
+            options: AnalysisOptions::default(),
+            max_concurrent_jobs: 4,
+            requests_per_minute: None,
+        }
+    }
+}
+
+/// A verification to schedule
+#[cfg(feature = "git")]
+#[derive(Debug, Clone, serde::Serialize, serde::Deserialize)]
+pub struct VerificationJob {
+    #[serde(flatten)]
+    pub request: VerificationRequest,
+    /// Jobs with a higher priority start first; equal priorities start in submission order
+    #[serde(default)]
+    pub priority: i32,
+    /// Options replacing the scheduler's for this job
+    #[serde(default)]
+    pub options: Option<AnalysisOptions>,
+}
+
+#[cfg(feature = "git")]
+impl VerificationJob {
+    /// Job verifying the changes from `base` to `head`, with the tests read at `head`
+    pub fn new(repo_url: &str, base: &str, head: &str, user_intent: &str) -> Self {
+        VerificationJob {
+            request: VerificationRequest::new(repo_url, base, head, user_intent),
+            priority: 0,
+            options: None,
+        }
+    }
+
+    pub fn with_priority(mut self, priority: i32) -> Self {
+        self.priority = priority;
+        self
+    }
+}
+
+/// State of a scheduled job
+#[cfg(feature = "git")]
+#[derive(Debug, Clone, Copy, PartialEq, Eq, serde::Serialize, serde::Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum ScheduledJobStatus {
+    Queued,
+    Running,
+    Succeeded,
+    Failed,
+    Cancelled,
+}
+
+/// A job as returned by status queries
+#[cfg(feature = "git")]
+#[derive(Debug, Clone, serde::Serialize, serde::Deserialize)]
+pub struct ScheduledJob {
+    pub id: u64,
+    pub priority: i32,
+    pub status: ScheduledJobStatus,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub result: Option<IntentVerificationResult>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub error: Option<String>,
+}
+
+#[cfg(feature = "git")]
+struct Entry {
+    record: ScheduledJob,
+    /// Taken when the job starts
+    job: Option<VerificationJob>,
+    cancellation: CancellationToken,
+}
+
+/// Runs many verifications concurrently, highest priority first
+///
+/// Repositories are cloned from mirrors under `options.cache_dir` that every job shares, and
+/// all jobs draw their LLM requests from one [`RateLimiter`]. Jobs run while
+/// [`run`](Self::run) or [`run_until_idle`](Self::run_until_idle) is awaited; submitting and
+/// querying work from anywhere in the meantime.
+#[cfg(feature = "git")]
+pub struct VerificationScheduler {
+    config: SchedulerConfig,
+    rate_limiter: Option<RateLimiter>,
+    jobs: Mutex<BTreeMap<u64, Entry>>,
+    next_id: AtomicU64,
+    /// One lock per repository, so concurrent jobs don't fetch into the same mirror at once
+    mirror_locks: Mutex<HashMap<String, Arc<tokio::sync::Mutex<()>>>>,
+    submitted: Notify,
+}
+
+#[cfg(feature = "git")]
+impl VerificationScheduler {
+    pub fn new(mut config: SchedulerConfig) -> Self {
+        // Jobs without their own options share one connection pool
+        if config.options.llm_client.is_none() {
+            config.options.llm_client =
+                LlmClient::new(&config.api_key, config.base_url.as_deref(), &config.options).ok();
+        }
+        VerificationScheduler {
+            rate_limiter: config.requests_per_minute.map(RateLimiter::per_minute),
+            config,
+            jobs: Mutex::new(BTreeMap::new()),
+            next_id: AtomicU64::new(1),
+            mirror_locks: Mutex::new(HashMap::new()),
+            submitted: Notify::new(),
+        }
+    }
+
+    /// Queue a job, returning its id
+    pub fn submit(&self, job: VerificationJob) -> u64 {
+        let id = self.next_id.fetch_add(1, Ordering::SeqCst);
+        let record = ScheduledJob {
+            id,
+            priority: job.priority,
+            status: ScheduledJobStatus::Queued,
+            result: None,
+            error: None,
+        };
+        self.jobs.lock().unwrap().insert(
+            id,
+            Entry {
+                record,
+                job: Some(job),
+                cancellation: CancellationToken::new(),
+            },
+        );
+        self.submitted.notify_one();
+        id
+    }
+
+    /// The job with `id`, or `None` if it was never submitted
+    pub fn job(&self, id: u64) -> Option<ScheduledJob> {
+        self.jobs
+            .lock()
+            .unwrap()
+            .get(&id)
+            .map(|entry| entry.record.clone())
+    }
+
+    pub fn status(&self, id: u64) -> Option<ScheduledJobStatus> {
+        self.jobs
+            .lock()
+            .unwrap()
+            .get(&id)
+            .map(|entry| entry.record.status)
+    }
+
+    /// Every submitted job, in submission order
+    pub fn jobs(&self) -> Vec<ScheduledJob> {
+        self.jobs
+            .lock()
+            .unwrap()
+            .values()
+            .map(|entry| entry.record.clone())
+            .collect()
+    }
+
+    /// Cancel a queued or running job; returns false if it already finished or doesn't exist
+    pub fn cancel(&self, id: u64) -> bool {
+        let mut jobs = self.jobs.lock().unwrap();
+        let Some(entry) = jobs.get_mut(&id) else {
+            return false;
+        };
+        match entry.record.status {
+            ScheduledJobStatus::Queued => {
+                entry.record.status = ScheduledJobStatus::Cancelled;
+                entry.job = None;
+                true
+            }
+            ScheduledJobStatus::Running => {
+                entry.cancellation.cancel();
+                true
+            }
+            _ => false,
+        }
+    }
+
+    /// Run jobs until the queue is empty and every started job has finished
+    pub async fn run_until_idle(&self) {
+        self.drive(true).await
+    }
+
+    /// Run jobs as they are submitted, until the future is dropped
+    pub async fn run(&self) {
+        self.drive(false).await
+    }
+
+    async fn drive(&self, until_idle: bool) {
+        let mut running = FuturesUnordered::new();
+        loop {
+            while running.len() < self.config.max_concurrent_jobs.max(1)
+                && let Some((id, job, cancellation)) = self.start_next()
+            {
+                running.push(self.run_job(id, job, cancellation));
+            }
+            if running.is_empty() && until_idle {
+                return;
+            }
+            tokio::select! {
+                Some(()) = running.next(), if !running.is_empty() => {}
+                _ = self.submitted.notified() => {}
+            }
+        }
+    }
+
+    /// Mark the next queued job as running: highest priority first, then oldest
+    fn start_next(&self) -> Option<(u64, VerificationJob, CancellationToken)> {
+        let mut jobs = self.jobs.lock().unwrap();
+        let entry = jobs
+            .values_mut()
+            .filter(|entry| entry.record.status == ScheduledJobStatus::Queued)
+            .min_by_key(|entry| (-(entry.record.priority as i64), entry.record.id))?;
+        entry.record.status = ScheduledJobStatus::Running;
+        Some((
+            entry.record.id,
+            entry.job.take()?,
+            entry.cancellation.clone(),
+        ))
+    }
+
+    async fn run_job(&self, id: u64, job: VerificationJob, cancellation: CancellationToken) {
+        let mut options = job.options.unwrap_or_else(|| self.config.options.clone());
+        options.cancellation = Some(cancellation);
+        if self.rate_limiter.is_some() {
+            options.rate_limiter = self.rate_limiter.clone();
+        }
+
+        let request = &job.request;
+        let outcome = async {
+            let solution_mirror = self.mirror(&request.solution_repo_url, &options).await?;
+            let test_mirror = self.mirror(&request.test_repo_url, &options).await?;
+            verify_intent_with_options(
+                &test_mirror,
+                &request.test_commit,
+                &solution_mirror,
+                &request.solution_commit1,
+                &request.solution_commit2,
+                &request.user_intent,
+                &self.config.api_key,
+                self.config.model.as_deref(),
+                self.config.base_url.as_deref(),
+                &options,
+            )
+            .await
+        }
+        .await;
+
+        let mut jobs = self.jobs.lock().unwrap();
+        let Some(entry) = jobs.get_mut(&id) else {
+            return;
+        };
+        match outcome {
+            Ok(result) => {
+                entry.record.status = ScheduledJobStatus::Succeeded;
+                entry.record.result = Some(result);
+            }
+            Err(e) => {
+                entry.record.status = if e.downcast_ref::<Cancelled>().is_some() {
+                    ScheduledJobStatus::Cancelled
+                } else {
+                    ScheduledJobStatus::Failed
+                };
+                entry.record.error = Some(e.to_string());
+            }
+        }
+    }
+
+    /// Fetch `repo_url` into its shared mirror, returning the mirror's path
+    async fn mirror(
+        &self,
+        repo_url: &str,
+        options: &AnalysisOptions,
+    ) -> Result<String, Box<dyn std::error::Error>> {
+        let lock = Arc::clone(
+            self.mirror_locks
+                .lock()
+                .unwrap()
+                .entry(repo_url.to_string())
+                .or_default(),
+        );
+        let _guard = lock.lock().await;
+        let url = repo_url.to_string();
+        let path = spawn_git(options, move |options| {
+            Ok(mirror_repository(&url, options)?)
+        })
+        .await?;
+        Ok(path.to_string_lossy().into_owned())
+    }
+}
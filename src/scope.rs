@@ -0,0 +1,39 @@
+use crate::git::FileChange;
+use crate::risk::count_changed_lines;
+use crate::types::{ChangeRelevance, IntentVerificationResult};
+
+/// Fill in `unrelated_changes` and `scope_score` from the per-file relevance
+///
+/// The score weighs files by their changed lines, so one large unrelated rewrite lowers it
+/// more than a stray one-line edit. Files the model didn't classify are left out; the score
+/// is 1.0 when none were classified.
+pub fn apply_scope(result: &mut IntentVerificationResult, file_changes: &[FileChange]) {
+    let mut in_scope_lines = 0;
+    let mut classified_lines = 0;
+    result.unrelated_changes.clear();
+
+    for analysis in &result.files_analyzed {
+        let Some(relevance) = analysis.relevance else {
+            continue;
+        };
+        // Count every classified file, even one whose change has no lines (e.g. a rename)
+        let lines = file_changes
+            .iter()
+            .find(|fc| fc.path == analysis.file_path)
+            .map(count_changed_lines)
+            .unwrap_or(0)
+            .max(1);
+        classified_lines += lines;
+        if relevance == ChangeRelevance::Unrelated {
+            result.unrelated_changes.push(analysis.file_path.clone());
+        } else {
+            in_scope_lines += lines;
+        }
+    }
+
+    result.scope_score = if classified_lines == 0 {
+        1.0
+    } else {
+        in_scope_lines as f32 / classified_lines as f32
+    };
+}
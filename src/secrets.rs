@@ -0,0 +1,171 @@
+use regex::Regex;
+use similar::{ChangeTag, TextDiff};
+
+use crate::git::FileChange;
+use crate::types::{Finding, IntentVerificationResult, Severity};
+use crate::utils::sha256_hex;
+
+/// Rule prefix of the findings reported by [`scan_for_secrets`]
+pub const SECRET_RULE_PREFIX: &str = "secrets/";
+
+/// Minimum Shannon entropy (bits per character) for a value assigned to a secret-looking name
+const HIGH_ENTROPY_THRESHOLD: f64 = 3.5;
+
+/// Known credential formats: rule name, description and pattern
+pub(crate) const SECRET_PATTERNS: &[(&str, &str, &str)] = &[
+    (
+        "aws-access-key",
+        "AWS access key",
+        r"\b(?:AKIA|ASIA)[0-9A-Z]{16}\b",
+    ),
+    (
+        "private-key",
+        "private key",
+        r"-----BEGIN (?:RSA |EC |DSA |OPENSSH |PGP |ENCRYPTED )?PRIVATE KEY",
+    ),
+    (
+        "github-token",
+        "GitHub token",
+        r"\bgh[pousr]_[A-Za-z0-9]{36,}\b",
+    ),
+    (
+        "slack-token",
+        "Slack token",
+        r"\bxox[abprs]-[A-Za-z0-9-]{10,}",
+    ),
+    (
+        "openai-api-key",
+        "OpenAI API key",
+        r"\bsk-(?:proj-)?[A-Za-z0-9_-]{32,}",
+    ),
+    (
+        "stripe-secret-key",
+        "Stripe secret key",
+        r"\b[rs]k_live_[A-Za-z0-9]{24,}",
+    ),
+    (
+        "google-api-key",
+        "Google API key",
+        r"\bAIza[0-9A-Za-z_-]{35}\b",
+    ),
+    (
+        "jwt",
+        "JSON web token",
+        r"\beyJ[A-Za-z0-9_-]{10,}\.eyJ[A-Za-z0-9_-]{10,}\.[A-Za-z0-9_-]{10,}",
+    ),
+];
+
+/// Scan the lines added by the changes for credentials
+///
+/// Reports known key and token formats, plus high-entropy strings assigned to names like
+/// `password` or `api_key`. Snippets are redacted so reports don't repeat the secret.
+pub fn scan_for_secrets(file_changes: &[FileChange]) -> Vec<Finding> {
+    let patterns: Vec<(&str, &str, Regex)> = SECRET_PATTERNS
+        .iter()
+        .map(|(rule, description, pattern)| (*rule, *description, Regex::new(pattern).unwrap()))
+        .collect();
+    let assignment = Regex::new(
+        r#"(?i)(?:secret|token|passw(?:or)?d|api_?key|access_?key|auth|credential)\w*["']?\s*[:=]+\s*["']([^"'\s]{16,})["']"#,
+    )
+    .unwrap();
+
+    let mut findings = Vec::new();
+    for file_change in file_changes {
+        for (line_number, line) in added_lines(file_change) {
+            let mut finding = |rule: &str, description: &str, secret: &str| {
+                findings.push(Finding {
+                    rule: format!("{}{}", SECRET_RULE_PREFIX, rule),
+                    severity: Severity::High,
+                    file_path: Some(file_change.path.clone()),
+                    line: Some(line_number),
+                    snippet: Some(redact(secret)),
+                    message: format!("Possible {} added in the changes", description),
+                    suppressed: false,
+                    cwe: None,
+                    // Secrets sharing their first characters must still be told apart
+                    content_hash: Some(sha256_hex(secret.as_bytes())),
+                });
+            };
+
+            let mut matched = false;
+            for (rule, description, pattern) in &patterns {
+                if let Some(m) = pattern.find(&line) {
+                    finding(rule, description, m.as_str());
+                    matched = true;
+                }
+            }
+            if matched {
+                continue;
+            }
+            if let Some(captures) = assignment.captures(&line)
+                && shannon_entropy(&captures[1]) >= HIGH_ENTROPY_THRESHOLD
+            {
+                finding("high-entropy-string", "hardcoded secret", &captures[1]);
+            }
+        }
+    }
+    findings
+}
+
+/// Force a negative verdict when any unsuppressed secret finding remains
+///
+/// Returns the number of such findings.
+pub fn apply_secret_findings(result: &mut IntentVerificationResult) -> usize {
+    let leaked = result
+        .findings
+        .iter()
+        .filter(|f| !f.suppressed && f.rule.starts_with(SECRET_RULE_PREFIX))
+        .count();
+    if leaked > 0 {
+        result.is_intent_fulfilled = false;
+        result.explanation = format!(
+            "{}; {} possible secret(s) found in the changes",
+            result.explanation, leaked
+        );
+    }
+    leaked
+}
+
+/// Shannon entropy of `text` in bits per character
+pub fn shannon_entropy(text: &str) -> f64 {
+    let chars: Vec<char> = text.chars().collect();
+    if chars.is_empty() {
+        return 0.0;
+    }
+    let mut counts = std::collections::HashMap::new();
+    for c in &chars {
+        *counts.entry(c).or_insert(0usize) += 1;
+    }
+    let len = chars.len() as f64;
+    counts
+        .values()
+        .map(|&count| {
+            let p = count as f64 / len;
+            -p * p.log2()
+        })
+        .sum()
+}
+
+/// Added lines of a file change with their 1-based line numbers in the new content
+pub(crate) fn added_lines(file_change: &FileChange) -> Vec<(usize, String)> {
+    let Some(content) = file_change.content.as_deref() else {
+        return vec![];
+    };
+    let old = file_change.old_content.as_deref().unwrap_or("");
+    TextDiff::from_lines(old, content)
+        .iter_all_changes()
+        .filter(|change| change.tag() == ChangeTag::Insert)
+        .filter_map(|change| {
+            Some((
+                change.new_index()? + 1,
+                change.value().trim_end().to_string(),
+            ))
+        })
+        .collect()
+}
+
+/// Keep the first four characters of a secret
+fn redact(secret: &str) -> String {
+    let prefix: String = secret.chars().take(4).collect();
+    format!("{}…[redacted]", prefix)
+}
@@ -0,0 +1,711 @@
+use std::collections::{HashMap, VecDeque};
+use std::panic::AssertUnwindSafe;
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::sync::{Arc, Mutex};
+
+use axum::Router;
+use axum::body::Bytes;
+use axum::extract::rejection::BytesRejection;
+use axum::extract::{DefaultBodyLimit, Path, Request, State};
+use axum::http::header::{AUTHORIZATION, CONTENT_TYPE, WWW_AUTHENTICATE};
+use axum::http::{HeaderMap, Method, StatusCode, Uri};
+use axum::middleware::{self, Next};
+use axum::response::{IntoResponse, Response};
+use axum::routing::{get, post};
+use futures::FutureExt;
+use tokio::net::TcpListener;
+use tokio::sync::Semaphore;
+
+use crate::archetype::IntentArchetype;
+use crate::github::{fetch_issue, parse_issue_reference};
+use crate::openai::verify_intent_with_options;
+use crate::options::AnalysisOptions;
+use crate::profile::VerificationProfile;
+use crate::types::IntentVerificationResult;
+use crate::webhook::{
+    WebhookTrigger, constant_time_eq, github_trigger, gitlab_trigger, verify_github_signature,
+    verify_gitlab_token,
+};
+
+/// Request bodies larger than this are refused with `413` unless configured otherwise
+pub const DEFAULT_MAX_BODY_BYTES: usize = 1024 * 1024;
+
+/// Finished jobs kept for `GET /jobs/{id}` unless configured otherwise
+pub const DEFAULT_MAX_FINISHED_JOBS: usize = 1000;
+
+/// Verifications run at once unless configured otherwise
+pub const DEFAULT_MAX_RUNNING_JOBS: usize = 4;
+
+/// Settings shared by every verification the server runs
+#[derive(Debug, Clone, Default)]
+pub struct ServerConfig {
+    pub api_key: String,
+    pub model: Option<String>,
+    pub base_url: Option<String>,
+    pub options: AnalysisOptions,
+    /// Bearer token clients must send in the `Authorization` header to start and read jobs;
+    /// without it, anyone who can reach the server spends its API key
+    pub auth_token: Option<String>,
+    /// Shared secret webhook deliveries must be signed with (GitHub) or carry (GitLab); the
+    /// webhook routes are disabled without it
+    pub webhook_secret: Option<String>,
+    /// Token used to read the issues a pull request links to in private repositories
+    pub github_token: Option<String>,
+    /// URL every finished job record is POSTed to; not allowed in local-only mode
+    pub callback_url: Option<String>,
+    /// Hosts repositories may be cloned from, e.g. `github.com`; any remote host when empty.
+    /// Local paths and `file://` URLs are refused, except in local-only mode, which only
+    /// accepts those
+    pub allowed_repo_hosts: Vec<String>,
+    /// Largest request body accepted, [`DEFAULT_MAX_BODY_BYTES`] when `None`
+    pub max_body_bytes: Option<usize>,
+    /// Finished jobs kept before the oldest are dropped, [`DEFAULT_MAX_FINISHED_JOBS`] when `None`
+    pub max_finished_jobs: Option<usize>,
+    /// Verifications run at once before new ones are refused, [`DEFAULT_MAX_RUNNING_JOBS`] when
+    /// `None`
+    pub max_running_jobs: Option<usize>,
+}
+
+impl ServerConfig {
+    /// Refuse repositories that aren't remote http(s)/ssh URLs on an allowed host, or in
+    /// local-only mode, that aren't local
+    pub(crate) fn check_repo_url(&self, url: &str) -> Result<(), (StatusCode, String)> {
+        if let Some(policy) = &self.options.local_only {
+            if policy.allows_repository(url) {
+                return Ok(());
+            }
+            return Err((
+                StatusCode::FORBIDDEN,
+                format!(
+                    "Local-only mode only allows local repositories, not '{}'",
+                    url
+                ),
+            ));
+        }
+        let host = remote_host(url).ok_or_else(|| {
+            (
+                StatusCode::BAD_REQUEST,
+                format!("Repository '{}' is not a remote http(s) or ssh URL", url),
+            )
+        })?;
+        if !self.allowed_repo_hosts.is_empty()
+            && !self
+                .allowed_repo_hosts
+                .iter()
+                .any(|allowed| allowed.eq_ignore_ascii_case(host))
+        {
+            return Err((
+                StatusCode::FORBIDDEN,
+                format!("Repository host '{}' is not allowed", host),
+            ));
+        }
+        Ok(())
+    }
+
+    /// Refuse settings that send results off the machine in local-only mode
+    pub(crate) fn check_local_only(&self) -> Result<(), String> {
+        match &self.callback_url {
+            Some(callback_url) if self.options.local_only.is_some() => Err(format!(
+                "Local-only mode doesn't allow POSTing job records to {}",
+                callback_url
+            )),
+            _ => Ok(()),
+        }
+    }
+
+    fn max_body_bytes(&self) -> usize {
+        self.max_body_bytes.unwrap_or(DEFAULT_MAX_BODY_BYTES)
+    }
+
+    fn max_finished_jobs(&self) -> usize {
+        self.max_finished_jobs.unwrap_or(DEFAULT_MAX_FINISHED_JOBS)
+    }
+
+    pub(crate) fn max_running_jobs(&self) -> usize {
+        self.max_running_jobs.unwrap_or(DEFAULT_MAX_RUNNING_JOBS)
+    }
+
+    /// Whether the `Authorization` header value carries the configured bearer token; always
+    /// when none is configured
+    pub(crate) fn is_authorized(&self, authorization: Option<&str>) -> bool {
+        let Some(token) = &self.auth_token else {
+            return true;
+        };
+        authorization
+            .and_then(|value| value.strip_prefix("Bearer "))
+            .is_some_and(|presented| constant_time_eq(presented.as_bytes(), token.as_bytes()))
+    }
+}
+
+/// Host of an `http://`, `https://`, `ssh://` or scp-like `user@host:path` URL
+fn remote_host(url: &str) -> Option<&str> {
+    let authority = match url.split_once("://") {
+        Some((scheme, rest)) => {
+            if !["http", "https", "ssh"].contains(&scheme.to_ascii_lowercase().as_str()) {
+                return None;
+            }
+            let authority = rest.split(['/', '?', '#']).next().unwrap_or_default();
+            authority
+                .rsplit_once('@')
+                .map_or(authority, |(_, host)| host)
+        }
+        None => {
+            let (user_host, path) = url.split_once(':')?;
+            let (_, host) = user_host.split_once('@')?;
+            if path.is_empty() || host.contains('/') {
+                return None;
+            }
+            host
+        }
+    };
+    let host = match authority.strip_prefix('[') {
+        Some(ipv6) => ipv6.split(']').next().unwrap_or_default(),
+        None => authority.split(':').next().unwrap_or_default(),
+    };
+    (!host.is_empty()).then_some(host)
+}
+
+/// State of a verification started through the server
+#[derive(Debug, Clone, Copy, PartialEq, Eq, serde::Serialize, serde::Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum JobStatus {
+    Running,
+    Succeeded,
+    Failed,
+}
+
+/// A verification job as returned by `GET /jobs/{id}`
+#[derive(Debug, Clone, serde::Serialize, serde::Deserialize)]
+pub struct JobRecord {
+    pub id: String,
+    pub status: JobStatus,
+    /// Webhook event that started the job, e.g. `github:acme/calc#42`
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub source: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub result: Option<IntentVerificationResult>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub error: Option<String>,
+}
+
+/// Settings a client may choose for its own verification, merged onto the server's options
+///
+/// Anything that runs programs, writes files or reaches the network (`execution`,
+/// `static_analyzers`, `checkpoint`, `cache_dir`, `proxy`, ...) and the operator's policies
+/// (`local_only`, `redaction`) stay the operator's; requests naming them are rejected.
+#[derive(Debug, Default, serde::Deserialize)]
+#[serde(default, deny_unknown_fields)]
+pub(crate) struct RequestOptions {
+    pub(crate) language: Option<String>,
+    pub(crate) archetype: Option<IntentArchetype>,
+    pub(crate) profile: Option<VerificationProfile>,
+    pub(crate) acceptance_criteria: Option<bool>,
+    pub(crate) change_summary: Option<bool>,
+    pub(crate) clarifications: Vec<String>,
+}
+
+impl RequestOptions {
+    /// The server's options with these settings applied
+    pub(crate) fn apply(&self, options: &AnalysisOptions) -> AnalysisOptions {
+        let mut options = options.clone();
+        if let Some(language) = &self.language {
+            options.language = Some(language.clone());
+        }
+        if let Some(archetype) = self.archetype {
+            options.archetype = Some(archetype);
+        }
+        if let Some(profile) = self.profile {
+            options.profile = profile;
+        }
+        if let Some(acceptance_criteria) = self.acceptance_criteria {
+            options.acceptance_criteria = acceptance_criteria;
+        }
+        if let Some(change_summary) = self.change_summary {
+            options.change_summary = change_summary;
+        }
+        options
+            .clarifications
+            .extend(self.clarifications.iter().cloned());
+        options
+    }
+}
+
+/// Body of `POST /verify`: tests from one repository, changes from another
+#[derive(Debug, serde::Deserialize)]
+#[serde(deny_unknown_fields)]
+struct VerifyRequest {
+    test_repo_url: String,
+    test_commit: String,
+    solution_repo_url: String,
+    solution_commit1: String,
+    solution_commit2: String,
+    user_intent: String,
+    model: Option<String>,
+    #[serde(default)]
+    options: RequestOptions,
+    /// Issues whose intent replaces `user_intent`, as `owner/name#number`
+    #[serde(skip)]
+    linked_issues: Vec<String>,
+}
+
+/// Body of `POST /analyze`: tests read from the changed repository at `head`
+#[derive(Debug, serde::Deserialize)]
+#[serde(deny_unknown_fields)]
+struct AnalyzeRequest {
+    repo_url: String,
+    base: String,
+    head: String,
+    user_intent: String,
+    model: Option<String>,
+    #[serde(default)]
+    options: RequestOptions,
+}
+
+impl VerifyRequest {
+    fn check_repo_urls(&self, config: &ServerConfig) -> Result<(), (StatusCode, String)> {
+        config.check_repo_url(&self.test_repo_url)?;
+        config.check_repo_url(&self.solution_repo_url)
+    }
+}
+
+impl From<WebhookTrigger> for VerifyRequest {
+    fn from(trigger: WebhookTrigger) -> Self {
+        VerifyRequest {
+            test_repo_url: trigger.repo_url.clone(),
+            test_commit: trigger.head.clone(),
+            solution_repo_url: trigger.repo_url,
+            solution_commit1: trigger.base,
+            solution_commit2: trigger.head,
+            user_intent: trigger.intent,
+            model: None,
+            options: RequestOptions::default(),
+            linked_issues: trigger.linked_issues,
+        }
+    }
+}
+
+impl From<AnalyzeRequest> for VerifyRequest {
+    fn from(request: AnalyzeRequest) -> Self {
+        VerifyRequest {
+            test_repo_url: request.repo_url.clone(),
+            test_commit: request.head.clone(),
+            solution_repo_url: request.repo_url,
+            solution_commit1: request.base,
+            solution_commit2: request.head,
+            user_intent: request.user_intent,
+            model: request.model,
+            options: request.options,
+            linked_issues: Vec::new(),
+        }
+    }
+}
+
+/// Job records, with finished ids oldest first so the oldest can be dropped
+#[derive(Default)]
+struct Jobs {
+    records: HashMap<String, JobRecord>,
+    finished: VecDeque<String>,
+}
+
+struct ServerState {
+    config: ServerConfig,
+    jobs: Mutex<Jobs>,
+    next_id: AtomicU64,
+    running: Arc<Semaphore>,
+}
+
+impl ServerState {
+    /// Check the request's repositories, register a job and run the verification in the
+    /// background; the error response when a repository or the callback isn't allowed, or
+    /// `max_running_jobs` verifications are already running
+    fn start_job(self: &Arc<Self>, request: VerifyRequest, source: Option<String>) -> Response {
+        if let Err(message) = self.config.check_local_only() {
+            return error_response(StatusCode::FORBIDDEN, &message);
+        }
+        if let Err((status, message)) = request.check_repo_urls(&self.config) {
+            return error_response(status, &message);
+        }
+        let Ok(permit) = Arc::clone(&self.running).try_acquire_owned() else {
+            return error_response(
+                StatusCode::SERVICE_UNAVAILABLE,
+                &format!(
+                    "{} verifications are already running; retry later",
+                    self.config.max_running_jobs()
+                ),
+            );
+        };
+
+        let id = self.next_id.fetch_add(1, Ordering::SeqCst).to_string();
+        let job = JobRecord {
+            id: id.clone(),
+            status: JobStatus::Running,
+            source,
+            result: None,
+            error: None,
+        };
+        self.jobs
+            .lock()
+            .unwrap()
+            .records
+            .insert(id.clone(), job.clone());
+
+        let state = Arc::clone(self);
+        tokio::spawn(async move {
+            let outcome = AssertUnwindSafe(state.run_job(&request))
+                .catch_unwind()
+                .await
+                .unwrap_or_else(|_| Err("The verification panicked".to_string()));
+            let finished = state.finish_job(&id, outcome);
+            drop(permit);
+
+            if let (Some(job), Some(callback_url)) = (finished, &state.config.callback_url)
+                && let Err(e) = deliver_callback(callback_url, &job).await
+            {
+                eprintln!(
+                    "⚠️  Failed to deliver job {} to {}: {}",
+                    job.id, callback_url, e
+                );
+            }
+        });
+
+        json_response(StatusCode::ACCEPTED, &job)
+    }
+
+    async fn run_job(&self, request: &VerifyRequest) -> Result<IntentVerificationResult, String> {
+        let config = &self.config;
+        let options = request.options.apply(&config.options);
+        let (intent, issue_url) = linked_intent(request, config, &options).await;
+        let mut result = verify_intent_with_options(
+            &request.test_repo_url,
+            &request.test_commit,
+            &request.solution_repo_url,
+            &request.solution_commit1,
+            &request.solution_commit2,
+            &intent,
+            &config.api_key,
+            request.model.as_deref().or(config.model.as_deref()),
+            config.base_url.as_deref(),
+            &options,
+        )
+        .await
+        .map_err(|e| e.to_string())?;
+        result.metadata.issue_url = issue_url;
+        Ok(result)
+    }
+
+    /// Record a job's outcome, dropping the oldest finished jobs beyond the configured limit
+    fn finish_job(
+        &self,
+        id: &str,
+        outcome: Result<IntentVerificationResult, String>,
+    ) -> Option<JobRecord> {
+        let mut jobs = self.jobs.lock().unwrap();
+        let job = jobs.records.get_mut(id)?;
+        match outcome {
+            Ok(result) => {
+                job.status = JobStatus::Succeeded;
+                job.result = Some(result);
+            }
+            Err(error) => {
+                job.status = JobStatus::Failed;
+                job.error = Some(error);
+            }
+        }
+        let job = job.clone();
+
+        jobs.finished.push_back(id.to_string());
+        while jobs.finished.len() > self.config.max_finished_jobs() {
+            if let Some(oldest) = jobs.finished.pop_front() {
+                jobs.records.remove(&oldest);
+            }
+        }
+        Some(job)
+    }
+}
+
+/// Intent stated by the issues a request links to, with the first issue's URL; the request's
+/// own intent when it links none, none can be fetched, or the network is off limits
+async fn linked_intent(
+    request: &VerifyRequest,
+    config: &ServerConfig,
+    options: &AnalysisOptions,
+) -> (String, Option<String>) {
+    if request.linked_issues.is_empty() {
+        return (request.user_intent.clone(), None);
+    }
+    if options.local_only.is_some() {
+        eprintln!("⚠️  Not fetching linked issues in local-only mode");
+        return (request.user_intent.clone(), None);
+    }
+
+    let mut intents = Vec::new();
+    let mut issue_url = None;
+    for reference in &request.linked_issues {
+        let Ok((repository, number)) = parse_issue_reference(reference) else {
+            continue;
+        };
+        match fetch_issue(&repository, number, config.github_token.as_deref()).await {
+            Ok(issue) => {
+                eprintln!("🐙 Using {} as the intent: {}", reference, issue.title);
+                issue_url.get_or_insert(issue.html_url.clone());
+                intents.push(issue.intent());
+            }
+            Err(e) => eprintln!("⚠️  Failed to fetch {}: {}", reference, e),
+        }
+    }
+    if intents.is_empty() {
+        return (request.user_intent.clone(), None);
+    }
+    (intents.join("\n\n"), issue_url)
+}
+
+/// POST a finished job record to the configured callback URL
+async fn deliver_callback(callback_url: &str, job: &JobRecord) -> Result<(), reqwest::Error> {
+    reqwest::Client::new()
+        .post(callback_url)
+        .json(job)
+        .send()
+        .await?
+        .error_for_status()?;
+    Ok(())
+}
+
+/// Serve the verification REST API on `listener` until the task is dropped
+///
+/// Endpoints:
+/// - `POST /verify`: start a verification (`test_repo_url`, `test_commit`,
+///   `solution_repo_url`, `solution_commit1`, `solution_commit2`, `user_intent`, optional
+///   `model` and `options`); responds `202` with the job record. `options` may only set
+///   `language`, `archetype`, `profile`, `acceptance_criteria`, `change_summary` and
+///   `clarifications`; everything else comes from the server's configuration
+/// - `POST /analyze`: same, for a repository whose tests live alongside the code (`repo_url`,
+///   `base`, `head`, `user_intent`)
+/// - `GET /jobs/{id}`: the job record, with `result` or `error` once it has finished
+/// - `POST /webhooks/github`, `POST /webhooks/gitlab`: start a verification for pull/merge
+///   request and push events, using the issues a pull request closes (`Fixes #12`), or else
+///   the request description or commit message, as the intent; other events are acknowledged
+///   with `{"ignored": ...}`. Only served when `webhook_secret` is set
+/// - `GET /health`: `{"status": "ok"}`
+///
+/// When `auth_token` is set, `/verify`, `/analyze` and `/jobs/{id}` answer `401` unless the
+/// request carries `Authorization: Bearer <auth_token>`; webhooks are authenticated by their
+/// signature instead. Once `max_running_jobs` verifications are running, new ones are refused
+/// with `503`.
+///
+/// Repositories must be remote `http(s)://`, `ssh://` or `user@host:path` URLs (`400`
+/// otherwise), on one of `allowed_repo_hosts` when that is set (`403` otherwise). In local-only
+/// mode they must instead be local paths or `file://` URLs (`403` otherwise), so any repository
+/// the server can read is open to its clients. Bodies over `max_body_bytes` are refused with
+/// `413`.
+///
+/// Jobs are kept in memory; once more than `max_finished_jobs` have finished, the oldest finished
+/// ones are dropped and answer `404`. When `callback_url` is set, every finished job record is
+/// also POSTed there; local-only mode doesn't allow that, and `serve` fails with
+/// [`std::io::ErrorKind::InvalidInput`] instead of starting.
+pub async fn serve(listener: TcpListener, config: ServerConfig) -> std::io::Result<()> {
+    config
+        .check_local_only()
+        .map_err(|e| std::io::Error::new(std::io::ErrorKind::InvalidInput, e))?;
+    let state = Arc::new(ServerState {
+        running: Arc::new(Semaphore::new(config.max_running_jobs())),
+        config,
+        jobs: Mutex::new(Jobs::default()),
+        next_id: AtomicU64::new(1),
+    });
+    axum::serve(listener, router(state)).await
+}
+
+fn router(state: Arc<ServerState>) -> Router {
+    let jobs = Router::new()
+        .route("/verify", post(verify))
+        .route("/analyze", post(analyze))
+        .route("/jobs/{id}", get(job))
+        .route_layer(middleware::from_fn_with_state(
+            Arc::clone(&state),
+            require_auth_token,
+        ));
+    Router::new()
+        .route("/health", get(health))
+        .route("/webhooks/github", post(github_webhook))
+        .route("/webhooks/gitlab", post(gitlab_webhook))
+        .merge(jobs)
+        .fallback(no_route)
+        .method_not_allowed_fallback(no_route)
+        .layer(DefaultBodyLimit::max(state.config.max_body_bytes()))
+        .with_state(state)
+}
+
+/// Refuse requests without the configured bearer token
+async fn require_auth_token(
+    State(state): State<Arc<ServerState>>,
+    request: Request,
+    next: Next,
+) -> Response {
+    let authorization = request
+        .headers()
+        .get(AUTHORIZATION)
+        .and_then(|value| value.to_str().ok());
+    if !state.config.is_authorized(authorization) {
+        let mut response =
+            error_response(StatusCode::UNAUTHORIZED, "Missing or invalid bearer token");
+        response
+            .headers_mut()
+            .insert(WWW_AUTHENTICATE, "Bearer".parse().unwrap());
+        return response;
+    }
+    next.run(request).await
+}
+
+async fn health() -> Response {
+    json_response(StatusCode::OK, &serde_json::json!({ "status": "ok" }))
+}
+
+async fn verify(
+    State(state): State<Arc<ServerState>>,
+    body: Result<Bytes, BytesRejection>,
+) -> Response {
+    match read_json::<VerifyRequest>(body, state.config.max_body_bytes()) {
+        Ok(verify) => state.start_job(verify, None),
+        Err((status, message)) => error_response(status, &message),
+    }
+}
+
+async fn analyze(
+    State(state): State<Arc<ServerState>>,
+    body: Result<Bytes, BytesRejection>,
+) -> Response {
+    match read_json::<AnalyzeRequest>(body, state.config.max_body_bytes()) {
+        Ok(analyze) => state.start_job(analyze.into(), None),
+        Err((status, message)) => error_response(status, &message),
+    }
+}
+
+async fn job(State(state): State<Arc<ServerState>>, Path(id): Path<String>) -> Response {
+    let job = state.jobs.lock().unwrap().records.get(&id).cloned();
+    match job {
+        Some(job) => json_response(StatusCode::OK, &job),
+        None => error_response(StatusCode::NOT_FOUND, &format!("No job with id '{}'", id)),
+    }
+}
+
+async fn github_webhook(
+    State(state): State<Arc<ServerState>>,
+    headers: HeaderMap,
+    body: Result<Bytes, BytesRejection>,
+) -> Response {
+    handle_webhook(&state, true, &headers, body)
+}
+
+async fn gitlab_webhook(
+    State(state): State<Arc<ServerState>>,
+    headers: HeaderMap,
+    body: Result<Bytes, BytesRejection>,
+) -> Response {
+    handle_webhook(&state, false, &headers, body)
+}
+
+async fn no_route(method: Method, uri: Uri) -> Response {
+    error_response(
+        StatusCode::NOT_FOUND,
+        &format!("No route for {} {}", method, uri.path()),
+    )
+}
+
+/// Authenticate a webhook delivery and start the verification it calls for
+fn handle_webhook(
+    state: &Arc<ServerState>,
+    is_github: bool,
+    headers: &HeaderMap,
+    body: Result<Bytes, BytesRejection>,
+) -> Response {
+    let Some(secret) = &state.config.webhook_secret else {
+        return error_response(
+            StatusCode::NOT_FOUND,
+            "Webhooks are disabled: the server has no webhook secret",
+        );
+    };
+    let header = |name: &str| {
+        headers
+            .get(name)
+            .and_then(|value| value.to_str().ok())
+            .unwrap_or_default()
+            .to_string()
+    };
+    let (event, credential) = if is_github {
+        (header("x-github-event"), header("x-hub-signature-256"))
+    } else {
+        (header("x-gitlab-event"), header("x-gitlab-token"))
+    };
+
+    let body = match read_body(body, state.config.max_body_bytes()) {
+        Ok(body) => body,
+        Err((status, message)) => return error_response(status, &message),
+    };
+
+    let authentic = if is_github {
+        verify_github_signature(secret, &body, &credential)
+    } else {
+        verify_gitlab_token(secret, &credential)
+    };
+    if !authentic {
+        return error_response(StatusCode::UNAUTHORIZED, "Invalid webhook signature");
+    }
+
+    let payload = String::from_utf8_lossy(&body);
+    let trigger = if is_github {
+        github_trigger(&event, &payload)
+    } else {
+        gitlab_trigger(&event, &payload)
+    };
+    match trigger {
+        Ok(Some(trigger)) => {
+            let source = Some(trigger.source.clone());
+            state.start_job(trigger.into(), source)
+        }
+        Ok(None) => json_response(
+            StatusCode::OK,
+            &serde_json::json!({ "ignored": format!("Nothing to verify for '{}' events", event) }),
+        ),
+        Err(e) => error_response(
+            StatusCode::BAD_REQUEST,
+            &format!("Invalid webhook payload: {}", e),
+        ),
+    }
+}
+
+/// The request body, or `413`/`400` and why when it's over `limit` bytes or couldn't be read
+fn read_body(
+    body: Result<Bytes, BytesRejection>,
+    limit: usize,
+) -> Result<Bytes, (StatusCode, String)> {
+    body.map_err(|rejection| match rejection.status() {
+        StatusCode::PAYLOAD_TOO_LARGE => (
+            StatusCode::PAYLOAD_TOO_LARGE,
+            format!("Request body is larger than {} bytes", limit),
+        ),
+        _ => (StatusCode::BAD_REQUEST, rejection.body_text()),
+    })
+}
+
+/// Parse a JSON request body of at most `limit` bytes, or the error status and why
+fn read_json<T: serde::de::DeserializeOwned>(
+    body: Result<Bytes, BytesRejection>,
+    limit: usize,
+) -> Result<T, (StatusCode, String)> {
+    let body = read_body(body, limit)?;
+    serde_json::from_slice(&body).map_err(|e| {
+        (
+            StatusCode::BAD_REQUEST,
+            format!("Invalid request body: {}", e),
+        )
+    })
+}
+
+fn json_response(status: StatusCode, body: &impl serde::Serialize) -> Response {
+    let body = serde_json::to_vec(body).unwrap_or_default();
+    (status, [(CONTENT_TYPE, "application/json")], body).into_response()
+}
+
+fn error_response(status: StatusCode, message: &str) -> Response {
+    json_response(status, &serde_json::json!({ "error": message }))
+}
@@ -0,0 +1,186 @@
+use std::sync::LazyLock;
+
+use regex::Regex;
+
+use crate::git::{ChangeType, FileChange};
+use crate::secrets::added_lines;
+use crate::types::{Finding, IntentVerificationResult, Severity, TestTargetsWithCode};
+use crate::utils::is_test_path;
+
+/// Rule of the findings reported for solutions that return a value the tests expect verbatim
+pub const SHORTCUT_RULE: &str = "shortcut/hardcoded-expected-value";
+
+/// Lines that check a result, across common test frameworks
+static ASSERTION: LazyLock<Regex> = LazyLock::new(|| {
+    Regex::new(r"(?i)\bassert|\bexpect\s*\(|\.to(?:Be|Equal|StrictEqual|Match)\b|\.should\b")
+        .unwrap()
+});
+
+/// String and number literals
+static LITERAL: LazyLock<Regex> = LazyLock::new(|| {
+    Regex::new(r#""(?:[^"\\]|\\.)*"|'(?:[^'\\]|\\.)*'|-?\b\d+(?:\.\d+)?\b"#).unwrap()
+});
+
+/// A literal asserted by a test
+#[derive(Debug, Clone, PartialEq)]
+struct Expectation {
+    /// Literal as written, including its quotes
+    literal: String,
+    /// Literal without quotes, compared across quote styles
+    value: String,
+    /// Test file, or function for test targets read as functions
+    source: String,
+    /// 1-based line within the source
+    line: usize,
+}
+
+/// Literals of the assertions in `source`, skipping ones too short or common to mean anything
+fn expectations(source_name: &str, source: &str) -> Vec<Expectation> {
+    let mut found = Vec::new();
+    for (i, line) in source.lines().enumerate() {
+        if !ASSERTION.is_match(line) {
+            continue;
+        }
+        for m in LITERAL.find_iter(line) {
+            let literal = m.as_str();
+            let value = literal.trim_matches(|c| c == '"' || c == '\'');
+            let is_string = literal.len() != value.len();
+            let meaningful = if is_string {
+                value.trim().chars().count() >= 3
+            } else {
+                value.trim_start_matches('-').replace('.', "").len() >= 3
+            };
+            if meaningful {
+                found.push(Expectation {
+                    literal: literal.to_string(),
+                    value: value.to_string(),
+                    source: source_name.to_string(),
+                    line: i + 1,
+                });
+            }
+        }
+    }
+    found
+}
+
+/// The literal a line returns outright, e.g. `return "42";`, `=> 42,` or `Ok("42".into())`
+fn returned_literal(line: &str) -> Option<&str> {
+    let line = line.trim();
+    let mut rest = match line
+        .rsplit_once("return ")
+        .or_else(|| line.rsplit_once("=> "))
+    {
+        Some((_, after)) => after.trim_end_matches([';', ',', '}', ' ']),
+        // A bare tail expression; with a trailing comma it's an item of a list instead
+        None if line.ends_with(',') => return None,
+        None => line.trim_end_matches(';'),
+    };
+    for wrapper in ["Ok(", "Some(", "String::from(", "str(", "Promise.resolve("] {
+        if let Some(inner) = rest.strip_prefix(wrapper) {
+            rest = inner.strip_suffix(')').unwrap_or(inner);
+        }
+    }
+    for conversion in [".to_string()", ".to_owned()", ".into()"] {
+        rest = rest.strip_suffix(conversion).unwrap_or(rest);
+    }
+    let rest = rest.trim();
+    LITERAL
+        .find(rest)
+        .filter(|m| m.start() == 0 && m.end() == rest.len())
+        .map(|m| m.as_str())
+}
+
+/// Report solution lines that return a value the tests assert, instead of computing it
+///
+/// Expected values are the string and number literals on assertion lines of `test_sources`
+/// (`(name, content)` pairs). Only lines the changes add to non-test files are checked, and
+/// values the intent itself spells out are allowed, since returning them may be the point.
+pub fn detect_hardcoded_answers(
+    file_changes: &[FileChange],
+    test_sources: &[(String, String)],
+    user_intent: &str,
+) -> Vec<Finding> {
+    let expected: Vec<Expectation> = test_sources
+        .iter()
+        .flat_map(|(path, source)| expectations(path, source))
+        .filter(|e| !user_intent.contains(&e.value))
+        .collect();
+    if expected.is_empty() {
+        return vec![];
+    }
+
+    let mut findings = Vec::new();
+    for file_change in file_changes {
+        if is_test_path(&file_change.path) || file_change.status == ChangeType::Deleted {
+            continue;
+        }
+        for (line_number, line) in added_lines(file_change) {
+            let Some(returned) = returned_literal(&line) else {
+                continue;
+            };
+            let value = returned.trim_matches(|c| c == '"' || c == '\'');
+            if let Some(expectation) = expected.iter().find(|e| e.value == value) {
+                findings.push(Finding {
+                    rule: SHORTCUT_RULE.to_string(),
+                    severity: Severity::High,
+                    file_path: Some(file_change.path.clone()),
+                    line: Some(line_number),
+                    snippet: Some(line.trim().to_string()),
+                    message: format!(
+                        "Suspicious shortcut: returns {}, the value asserted in {} (line {}), instead of computing it",
+                        expectation.literal, expectation.source, expectation.line
+                    ),
+                    suppressed: false,
+                    cwe: None,
+                    content_hash: None,
+                });
+            }
+        }
+    }
+    findings
+}
+
+/// Test code available to a verification: the test targets and the test files the changes
+/// touch
+pub(crate) fn test_sources(
+    targets_with_code: &TestTargetsWithCode,
+    file_changes: &[FileChange],
+) -> Vec<(String, String)> {
+    let mut sources: Vec<(String, String)> = targets_with_code
+        .file_contents
+        .iter()
+        .filter(|f| f.error.is_none())
+        .map(|f| (f.path.clone(), f.content.clone()))
+        .collect();
+    sources.extend(targets_with_code.function_contents.iter().filter_map(|f| {
+        let content = f.content.clone()?;
+        let name = match &f.file_path {
+            Some(path) => format!("{} in {}", f.name, path),
+            None => f.name.clone(),
+        };
+        Some((name, content))
+    }));
+    sources.extend(
+        file_changes
+            .iter()
+            .filter(|f| is_test_path(&f.path))
+            .filter_map(|f| Some((f.path.clone(), f.content.clone()?))),
+    );
+    sources
+}
+
+/// Note unsuppressed shortcuts in the explanation, returning how many there are
+pub fn apply_shortcut_findings(result: &mut IntentVerificationResult) -> usize {
+    let shortcuts = result
+        .findings
+        .iter()
+        .filter(|f| !f.suppressed && f.rule == SHORTCUT_RULE)
+        .count();
+    if shortcuts > 0 {
+        result.explanation = format!(
+            "{}; {} line(s) return values hardcoded from the tests",
+            result.explanation, shortcuts
+        );
+    }
+    shortcuts
+}
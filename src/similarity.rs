@@ -0,0 +1,198 @@
+use std::collections::HashSet;
+use std::hash::{DefaultHasher, Hash, Hasher};
+use std::sync::LazyLock;
+
+use regex::Regex;
+
+use crate::git::FileChange;
+#[cfg(feature = "git")]
+use crate::git::{snapshot_repository, spawn_git};
+use crate::options::AnalysisOptions;
+use crate::secrets::added_lines;
+use crate::snapshot::RepoSnapshot;
+use crate::types::{Finding, IntentVerificationResult, Severity, Warning, WarningKind};
+
+/// Rule of the findings reported for code that closely matches a reference repository
+pub const SIMILARITY_RULE: &str = "similarity/near-copy";
+
+/// Reference repositories to compare the added code against
+#[derive(Debug, Clone, PartialEq, serde::Serialize, serde::Deserialize)]
+#[serde(default)]
+pub struct SimilarityConfig {
+    /// Repository URLs or local paths as `URL[@COMMIT]` (`HEAD` when no commit is given),
+    /// e.g. prior art or earlier submissions for the same bounty
+    pub references: Vec<String>,
+    /// Share (0.0-1.0) of a file's added shingles found in one reference file above which the
+    /// file is reported
+    pub threshold: f32,
+    /// Number of consecutive tokens per shingle
+    pub shingle_size: usize,
+    /// Files adding fewer tokens are too small to judge and are skipped
+    pub min_tokens: usize,
+}
+
+impl Default for SimilarityConfig {
+    fn default() -> Self {
+        SimilarityConfig {
+            references: vec![],
+            threshold: 0.8,
+            shingle_size: 5,
+            min_tokens: 40,
+        }
+    }
+}
+
+static TOKEN: LazyLock<Regex> =
+    LazyLock::new(|| Regex::new(r"[A-Za-z_][A-Za-z0-9_]*|\d+|\S").unwrap());
+
+/// Tokens of source text: identifiers, numbers and single punctuation characters
+fn tokens(text: &str) -> Vec<&str> {
+    TOKEN.find_iter(text).map(|m| m.as_str()).collect()
+}
+
+/// Hashes of every run of `size` consecutive tokens, so formatting doesn't matter
+pub fn shingles(text: &str, size: usize) -> HashSet<u64> {
+    let tokens = tokens(text);
+    tokens
+        .windows(size.max(1))
+        .map(|window| {
+            let mut hasher = DefaultHasher::new();
+            window.hash(&mut hasher);
+            hasher.finish()
+        })
+        .collect()
+}
+
+/// Share of `shingles` also found in `reference` (0.0 when `shingles` is empty)
+pub fn containment(shingles: &HashSet<u64>, reference: &HashSet<u64>) -> f32 {
+    if shingles.is_empty() {
+        return 0.0;
+    }
+    shingles.intersection(reference).count() as f32 / shingles.len() as f32
+}
+
+/// Report changed files whose added code is mostly found in one file of a reference
+///
+/// References are `(name, snapshot)` pairs. Only the lines a change adds are compared, so
+/// keeping existing code doesn't count as copying. Each file is reported once, against its
+/// closest match.
+pub fn detect_near_copies(
+    file_changes: &[FileChange],
+    references: &[(String, RepoSnapshot)],
+    config: &SimilarityConfig,
+) -> Vec<Finding> {
+    let reference_shingles: Vec<(&str, &str, HashSet<u64>)> = references
+        .iter()
+        .flat_map(|(name, snapshot)| {
+            snapshot.files.iter().map(move |(path, content)| {
+                (
+                    name.as_str(),
+                    path.as_str(),
+                    shingles(content, config.shingle_size),
+                )
+            })
+        })
+        .collect();
+
+    let mut findings = Vec::new();
+    for file_change in file_changes {
+        let added = added_lines(file_change);
+        let Some(&(first_line, _)) = added.first() else {
+            continue;
+        };
+        let added_text = added
+            .iter()
+            .map(|(_, line)| line.as_str())
+            .collect::<Vec<_>>()
+            .join("\n");
+        if tokens(&added_text).len() < config.min_tokens {
+            continue;
+        }
+        let added_shingles = shingles(&added_text, config.shingle_size);
+        let closest = reference_shingles
+            .iter()
+            .map(|(name, path, reference)| (name, path, containment(&added_shingles, reference)))
+            .max_by(|a, b| a.2.total_cmp(&b.2));
+        if let Some((name, path, score)) = closest
+            && score >= config.threshold
+        {
+            findings.push(Finding {
+                rule: SIMILARITY_RULE.to_string(),
+                severity: Severity::High,
+                file_path: Some(file_change.path.clone()),
+                line: Some(first_line),
+                snippet: Some(format!("{}:{}", name, path)),
+                message: format!(
+                    "{:.0}% of the code added to {} matches {} in {}",
+                    score * 100.0,
+                    file_change.path,
+                    path,
+                    name
+                ),
+                suppressed: false,
+                cwe: None,
+                content_hash: None,
+            });
+        }
+    }
+    findings
+}
+
+/// Note unsuppressed near-copies in the explanation, returning how many there are
+///
+/// The verdict is left alone: copied code can still fulfill the intent, but the findings let
+/// a policy (`fail_on_severity`) or a reviewer withhold a payout.
+pub fn apply_similarity_findings(result: &mut IntentVerificationResult) -> usize {
+    let copies = result
+        .findings
+        .iter()
+        .filter(|f| !f.suppressed && f.rule == SIMILARITY_RULE)
+        .count();
+    if copies > 0 {
+        result.explanation = format!(
+            "{}; {} file(s) closely match reference code",
+            result.explanation, copies
+        );
+    }
+    copies
+}
+
+/// Fetch the configured references and compare the changes against them, turning references
+/// that can't be read into warnings
+pub(crate) async fn check_similarity(
+    file_changes: &[FileChange],
+    config: &SimilarityConfig,
+    options: &AnalysisOptions,
+) -> (Vec<Finding>, Vec<Warning>) {
+    let mut references = Vec::new();
+    let mut warnings = Vec::new();
+    for reference in &config.references {
+        let (url, commit) = match reference.rsplit_once('@') {
+            Some((url, commit)) if !commit.contains('/') && !commit.contains(':') => (url, commit),
+            _ => (reference.as_str(), "HEAD"),
+        };
+        let (url, commit) = (url.to_string(), commit.to_string());
+        #[cfg(feature = "git")]
+        let snapshot = spawn_git(options, move |options| {
+            Ok(snapshot_repository(&url, &commit, options)?)
+        })
+        .await;
+        #[cfg(not(feature = "git"))]
+        let snapshot: Result<RepoSnapshot, String> = {
+            let _ = (url, commit, options);
+            Err("reading references needs the `git` feature".to_string())
+        };
+        match snapshot {
+            Ok(snapshot) => references.push((reference.clone(), snapshot)),
+            Err(e) => warnings.push(Warning {
+                kind: WarningKind::Similarity,
+                file_path: None,
+                message: format!("Failed to read reference {}: {}", reference, e),
+            }),
+        }
+    }
+    (
+        detect_near_copies(file_changes, &references, config),
+        warnings,
+    )
+}
@@ -0,0 +1,465 @@
+use std::collections::BTreeMap;
+use std::path::Path;
+
+#[cfg(feature = "git")]
+use git2::{Delta, Repository, StatusOptions};
+
+use crate::binary::binary_placeholder;
+use crate::code_parser::{extract_function_from_content_with_name, is_source_file_by_name};
+use crate::git::{ChangeType, FileChange};
+use crate::test_mapping::map_tests_to_sources;
+use crate::types::{FileContent, FunctionContent, TestTargets, TestTargetsWithCode};
+use crate::utils::locate_snippet;
+
+/// Version control metadata directories, which aren't part of a directory's files
+const VCS_DIRS: &[&str] = &[".git", ".hg", ".svn"];
+
+/// Compare two folders, e.g. an exported release and the build after a change, without any
+/// version control
+///
+/// Same as diffing [`RepoSnapshot::from_directory`] of each, so paths are relative to the
+/// folders and binary files carry the placeholder `get_git_changed_files` uses.
+pub fn diff_directories(
+    before_dir: impl AsRef<Path>,
+    after_dir: impl AsRef<Path>,
+) -> std::io::Result<Vec<FileChange>> {
+    let before = RepoSnapshot::from_directory(before_dir)?;
+    let after = RepoSnapshot::from_directory(after_dir)?;
+    Ok(before.diff(&after))
+}
+
+/// A file's content as stored in a snapshot: text, the binary placeholder or
+/// `[Non-UTF8 content]`
+fn file_text(path: &str, data: Vec<u8>) -> String {
+    // Git's heuristic: a NUL byte early on means binary
+    if data[..data.len().min(8000)].contains(&0) {
+        binary_placeholder(path, &data)
+    } else {
+        String::from_utf8(data).unwrap_or_else(|_| "[Non-UTF8 content]".to_string())
+    }
+}
+
+/// `/`-separated path of an archive entry, `None` for absolute paths and ones climbing out of
+/// the archive with `..`
+#[cfg(feature = "archive")]
+fn archive_path(path: &Path) -> Option<String> {
+    let mut parts = Vec::new();
+    for component in path.components() {
+        match component {
+            std::path::Component::Normal(part) => parts.push(part.to_string_lossy().into_owned()),
+            std::path::Component::CurDir => {}
+            _ => return None,
+        }
+    }
+    (!parts.is_empty()).then(|| parts.join("/"))
+}
+
+/// Regular files of a zip archive, symbolic links left out
+#[cfg(feature = "archive")]
+fn zip_files(data: &[u8]) -> std::io::Result<Vec<(String, Vec<u8>)>> {
+    use std::io::Read;
+
+    let mut archive = zip::ZipArchive::new(std::io::Cursor::new(data))?;
+    let mut files = Vec::new();
+    for index in 0..archive.len() {
+        let mut file = archive.by_index(index)?;
+        if !file.is_file() || file.is_symlink() {
+            continue;
+        }
+        let Some(path) = file.enclosed_name().as_deref().and_then(archive_path) else {
+            continue;
+        };
+        let mut content = Vec::new();
+        file.read_to_end(&mut content)?;
+        files.push((path, content));
+    }
+    Ok(files)
+}
+
+/// Regular files of a tar archive; links and the pax headers code hosts add are left out
+#[cfg(feature = "archive")]
+fn tar_files(reader: impl std::io::Read) -> std::io::Result<Vec<(String, Vec<u8>)>> {
+    use std::io::Read;
+
+    let mut archive = tar::Archive::new(reader);
+    let mut files = Vec::new();
+    for entry in archive.entries()? {
+        let mut entry = entry?;
+        if !entry.header().entry_type().is_file() {
+            continue;
+        }
+        let Some(path) = archive_path(&entry.path()?) else {
+            continue;
+        };
+        let mut content = Vec::new();
+        entry.read_to_end(&mut content)?;
+        files.push((path, content));
+    }
+    Ok(files)
+}
+
+/// In-memory copy of a repository at one revision
+///
+/// Lets callers that can't use git (browser extensions, workers, sandboxes) provide file
+/// contents directly instead of a clone URL.
+#[derive(Debug, Clone, Default, PartialEq, serde::Serialize, serde::Deserialize)]
+pub struct RepoSnapshot {
+    /// File contents keyed by repository-relative path (`/`-separated)
+    pub files: BTreeMap<String, String>,
+}
+
+impl RepoSnapshot {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Build a snapshot from `(path, content)` pairs
+    pub fn from_files<P, C>(files: impl IntoIterator<Item = (P, C)>) -> Self
+    where
+        P: Into<String>,
+        C: Into<String>,
+    {
+        RepoSnapshot {
+            files: files
+                .into_iter()
+                .map(|(path, content)| (path.into(), content.into()))
+                .collect(),
+        }
+    }
+
+    /// Text files of a local repository at `rev`, skipping binary and non-UTF8 files
+    #[cfg(feature = "git")]
+    pub fn from_commit(repo_path: impl AsRef<Path>, rev: &str) -> Result<Self, git2::Error> {
+        let repo = Repository::open(repo_path)?;
+        let tree = repo.revparse_single(rev)?.peel_to_tree()?;
+
+        let mut snapshot = RepoSnapshot::new();
+        tree.walk(git2::TreeWalkMode::PreOrder, |dir, entry| {
+            if entry.kind() == Some(git2::ObjectType::Blob)
+                && let Ok(blob) = entry.to_object(&repo).and_then(|obj| obj.peel_to_blob())
+                && !blob.is_binary()
+                && let (Ok(content), Some(name)) =
+                    (std::str::from_utf8(blob.content()), entry.name())
+            {
+                snapshot.insert(format!("{}{}", dir, name), content);
+            }
+            git2::TreeWalkResult::Ok
+        })?;
+        Ok(snapshot)
+    }
+
+    /// Text files in the working tree of a local repository, uncommitted changes included
+    ///
+    /// Covers tracked files still on disk and untracked files that aren't ignored.
+    #[cfg(feature = "git")]
+    pub fn from_working_tree(repo_path: impl AsRef<Path>) -> Result<Self, git2::Error> {
+        let repo = Repository::open(repo_path)?;
+        let workdir = repo
+            .workdir()
+            .ok_or_else(|| git2::Error::from_str("bare repositories have no working tree"))?
+            .to_path_buf();
+
+        let mut paths: Vec<String> = repo
+            .index()?
+            .iter()
+            .map(|entry| String::from_utf8_lossy(&entry.path).into_owned())
+            .collect();
+        let mut status_options = StatusOptions::new();
+        status_options
+            .include_untracked(true)
+            .recurse_untracked_dirs(true);
+        for entry in repo.statuses(Some(&mut status_options))?.iter() {
+            if entry.status().is_wt_new()
+                && let Some(path) = entry.path()
+            {
+                paths.push(path.to_string());
+            }
+        }
+
+        let mut snapshot = RepoSnapshot::new();
+        for path in paths {
+            // Deleted, binary and non-UTF8 files are left out
+            if let Ok(content) = std::fs::read_to_string(workdir.join(&path))
+                && !content.contains('\0')
+            {
+                snapshot.insert(path, content);
+            }
+        }
+        Ok(snapshot)
+    }
+
+    /// Every file under a directory, keyed by its `/`-separated path relative to `dir`
+    ///
+    /// Binary files are kept as a placeholder with their type, size and digest, and non-UTF-8
+    /// ones as `[Non-UTF8 content]`. Symbolic links and `.git`, `.hg` and `.svn` directories
+    /// are skipped.
+    pub fn from_directory(dir: impl AsRef<Path>) -> std::io::Result<Self> {
+        let dir = dir.as_ref();
+        if !dir.is_dir() {
+            return Err(std::io::Error::new(
+                std::io::ErrorKind::NotFound,
+                format!("{} is not a directory", dir.display()),
+            ));
+        }
+        let mut snapshot = RepoSnapshot::new();
+        snapshot.read_directory(dir, "")?;
+        Ok(snapshot)
+    }
+
+    fn read_directory(&mut self, dir: &Path, prefix: &str) -> std::io::Result<()> {
+        for entry in std::fs::read_dir(dir)? {
+            let entry = entry?;
+            let file_type = entry.file_type()?;
+            let name = entry.file_name().to_string_lossy().into_owned();
+            let path = format!("{}{}", prefix, name);
+            if file_type.is_dir() {
+                if !VCS_DIRS.contains(&name.as_str()) {
+                    self.read_directory(&entry.path(), &format!("{}/", path))?;
+                }
+            } else if file_type.is_file() {
+                let content = file_text(&path, std::fs::read(entry.path())?);
+                self.insert(path, content);
+            }
+        }
+        Ok(())
+    }
+
+    /// Every file of a repository archive at `path`, as downloaded from a code host's archive
+    /// endpoint; see [`RepoSnapshot::from_archive_bytes`]
+    #[cfg(feature = "archive")]
+    pub fn from_archive(path: impl AsRef<Path>) -> std::io::Result<Self> {
+        Self::from_archive_bytes(&std::fs::read(path)?)
+    }
+
+    /// Every file of a `.zip`, `.tar.gz` or `.tar` archive of a repository, told apart by
+    /// their leading bytes
+    ///
+    /// Files are kept like [`RepoSnapshot::from_directory`] does. Code hosts wrap the
+    /// repository in a single top-level folder (`<repo>-<commit>/`), which is stripped when
+    /// every file is under it, so paths match the repository's.
+    #[cfg(feature = "archive")]
+    pub fn from_archive_bytes(data: &[u8]) -> std::io::Result<Self> {
+        let files = if data.starts_with(b"PK\x03\x04") || data.starts_with(b"PK\x05\x06") {
+            zip_files(data)?
+        } else if data.starts_with(&[0x1f, 0x8b]) {
+            tar_files(flate2::read::GzDecoder::new(data))?
+        } else {
+            tar_files(data)?
+        };
+
+        // The archive root folder shared by every file, if any
+        let root = files
+            .first()
+            .and_then(|(path, _)| path.split_once('/'))
+            .map(|(root, _)| format!("{}/", root))
+            .filter(|root| {
+                files
+                    .iter()
+                    .all(|(path, _)| path.starts_with(root.as_str()))
+            });
+        let mut snapshot = RepoSnapshot::new();
+        for (path, data) in files {
+            let path = match &root {
+                Some(root) => path[root.len()..].to_string(),
+                None => path,
+            };
+            if path.split('/').any(|part| VCS_DIRS.contains(&part)) {
+                continue;
+            }
+            let content = file_text(&path, data);
+            snapshot.insert(path, content);
+        }
+        Ok(snapshot)
+    }
+
+    /// Before and after snapshots of the files touched by a unified diff (`git diff` output)
+    ///
+    /// A diff only carries its hunks, so each file holds the hunks' context and changed lines
+    /// (with `...` between hunks) rather than its whole content.
+    #[cfg(feature = "git")]
+    pub fn from_unified_diff(diff: &str) -> Result<(Self, Self), git2::Error> {
+        let diff = git2::Diff::from_buffer(diff.as_bytes())?;
+        let mut before = RepoSnapshot::new();
+        let mut after = RepoSnapshot::new();
+
+        for index in 0..diff.deltas().len() {
+            let Some(patch) = git2::Patch::from_diff(&diff, index)? else {
+                continue;
+            };
+            let delta = patch.delta();
+            let old_path = delta
+                .old_file()
+                .path()
+                .map(|p| p.to_string_lossy().into_owned());
+            let new_path = delta
+                .new_file()
+                .path()
+                .map(|p| p.to_string_lossy().into_owned());
+            let (old_path, new_path) = match delta.status() {
+                Delta::Added => (None, new_path),
+                Delta::Deleted => (old_path, None),
+                _ => (old_path, new_path),
+            };
+
+            // Binary files only show up when added or deleted; there's nothing to compare
+            if delta.flags().is_binary() {
+                for (snapshot, path) in [(&mut before, old_path), (&mut after, new_path)] {
+                    if let Some(path) = path {
+                        snapshot.insert(path, "[Binary file]");
+                    }
+                }
+                continue;
+            }
+
+            let mut old_lines: Vec<String> = Vec::new();
+            let mut new_lines: Vec<String> = Vec::new();
+            for hunk in 0..patch.num_hunks() {
+                if hunk > 0 {
+                    old_lines.push("...\n".to_string());
+                    new_lines.push("...\n".to_string());
+                }
+                for line in 0..patch.num_lines_in_hunk(hunk)? {
+                    let line = patch.line_in_hunk(hunk, line)?;
+                    let text = String::from_utf8_lossy(line.content()).into_owned();
+                    match line.origin() {
+                        ' ' => {
+                            old_lines.push(text.clone());
+                            new_lines.push(text);
+                        }
+                        '-' => old_lines.push(text),
+                        '+' => new_lines.push(text),
+                        _ => {}
+                    }
+                }
+            }
+            if let Some(path) = old_path {
+                before.insert(path, old_lines.concat());
+            }
+            if let Some(path) = new_path {
+                after.insert(path, new_lines.concat());
+            }
+        }
+        Ok((before, after))
+    }
+
+    /// Add or replace a file
+    pub fn insert(&mut self, path: impl Into<String>, content: impl Into<String>) {
+        self.files.insert(path.into(), content.into());
+    }
+
+    pub fn get(&self, path: &str) -> Option<&str> {
+        self.files.get(path).map(String::as_str)
+    }
+
+    /// Files added, modified or deleted going from this snapshot to `head`, sorted by path
+    ///
+    /// Produces the same `FileChange` shape as `get_git_changed_files`.
+    pub fn diff(&self, head: &RepoSnapshot) -> Vec<FileChange> {
+        let mut file_changes = Vec::new();
+
+        for (path, old) in &self.files {
+            match head.files.get(path) {
+                Some(new) if new != old => file_changes.push(FileChange {
+                    path: path.clone(),
+                    status: ChangeType::Modified,
+                    content: Some(new.clone()),
+                    old_content: Some(old.clone()),
+                }),
+                Some(_) => {}
+                None => file_changes.push(FileChange {
+                    path: path.clone(),
+                    status: ChangeType::Deleted,
+                    content: None,
+                    old_content: Some(old.clone()),
+                }),
+            }
+        }
+
+        for (path, new) in &head.files {
+            if !self.files.contains_key(path) {
+                file_changes.push(FileChange {
+                    path: path.clone(),
+                    status: ChangeType::Added,
+                    content: Some(new.clone()),
+                    old_content: None,
+                });
+            }
+        }
+
+        file_changes.sort_by(|a, b| a.path.cmp(&b.path));
+        file_changes
+    }
+
+    /// Read the code for the test targets from this snapshot
+    ///
+    /// Snapshot counterpart of `read_test_targets_code`.
+    pub fn read_test_targets_code(&self, targets: &TestTargets) -> TestTargetsWithCode {
+        let file_contents = targets
+            .files
+            .iter()
+            .map(|file_path| match self.get(file_path) {
+                Some(content) => FileContent {
+                    path: file_path.clone(),
+                    content: content.to_string(),
+                    error: None,
+                },
+                None => FileContent {
+                    path: file_path.clone(),
+                    content: String::new(),
+                    error: Some("File not found in snapshot".to_string()),
+                },
+            })
+            .collect();
+
+        let function_contents = targets
+            .functions
+            .iter()
+            .map(|function_name| {
+                let found = self.find_function(function_name);
+                FunctionContent {
+                    name: function_name.clone(),
+                    file_path: found.as_ref().map(|(path, _)| path.clone()),
+                    line: found.as_ref().and_then(|(path, content)| {
+                        locate_snippet(self.get(path)?, content).map(|(start, _)| start)
+                    }),
+                    error: if found.is_none() {
+                        Some(format!(
+                            "Function '{}' not found in repository",
+                            function_name
+                        ))
+                    } else {
+                        None
+                    },
+                    content: found.map(|(_, content)| content),
+                }
+            })
+            .collect();
+
+        let mut targets_with_code = TestTargetsWithCode {
+            targets: targets.clone(),
+            file_contents,
+            function_contents,
+            tested_sources: vec![],
+        };
+        targets_with_code.tested_sources = map_tests_to_sources(&targets_with_code, |path| {
+            self.get(path).map(str::to_string)
+        });
+        targets_with_code
+    }
+
+    /// First source file defining `function_name`, skipping `target` and hidden directories
+    fn find_function(&self, function_name: &str) -> Option<(String, String)> {
+        self.files.iter().find_map(|(path, content)| {
+            let file_name = path.rsplit('/').next().unwrap_or(path);
+            let skipped = path
+                .split('/')
+                .any(|component| component == "target" || component.starts_with('.'));
+
+            if skipped || !is_source_file_by_name(file_name) {
+                return None;
+            }
+            extract_function_from_content_with_name(content, function_name, file_name)
+                .map(|function_content| (path.clone(), function_content))
+        })
+    }
+}
@@ -0,0 +1,243 @@
+use rusqlite::{Connection, OptionalExtension, params};
+use std::path::Path;
+
+use crate::types::IntentVerificationResult;
+
+/// Identifies what a stored verification was run against
+#[derive(Debug, Clone, PartialEq, serde::Serialize, serde::Deserialize)]
+pub struct VerificationKey {
+    pub repo_url: String,
+    pub base_commit: String,
+    pub head_commit: String,
+    pub intent: String,
+    /// Pull request the verification belongs to, if any
+    pub pr_number: Option<u64>,
+}
+
+/// A verification result loaded from the store
+#[derive(Debug, Clone, serde::Serialize, serde::Deserialize)]
+pub struct StoredResult {
+    pub id: i64,
+    pub key: VerificationKey,
+    /// RFC 3339 timestamp of when the result was saved
+    pub recorded_at: String,
+    pub result: IntentVerificationResult,
+}
+
+/// Pass rate of the verifications recorded on one day
+#[derive(Debug, Clone, PartialEq, serde::Serialize, serde::Deserialize)]
+pub struct PassRatePoint {
+    /// Day in `YYYY-MM-DD` format
+    pub date: String,
+    pub total: u64,
+    pub passed: u64,
+    pub pass_rate: f32,
+}
+
+/// SQLite-backed history of verification results
+///
+/// Every saved verification is kept (nothing is overwritten), so the store can answer
+/// longitudinal questions such as the latest result for a PR or the pass rate over time.
+pub struct ResultStore {
+    conn: Connection,
+}
+
+impl ResultStore {
+    /// Open (or create) a store at the given database path
+    pub fn open(path: impl AsRef<Path>) -> Result<Self, Box<dyn std::error::Error>> {
+        Self::init(Connection::open(path)?)
+    }
+
+    /// Open a temporary store that lives only in memory
+    pub fn open_in_memory() -> Result<Self, Box<dyn std::error::Error>> {
+        Self::init(Connection::open_in_memory()?)
+    }
+
+    fn init(conn: Connection) -> Result<Self, Box<dyn std::error::Error>> {
+        conn.execute_batch(
+            "CREATE TABLE IF NOT EXISTS verification_results (
+                id INTEGER PRIMARY KEY AUTOINCREMENT,
+                repo_url TEXT NOT NULL,
+                base_commit TEXT NOT NULL,
+                head_commit TEXT NOT NULL,
+                intent TEXT NOT NULL,
+                pr_number INTEGER,
+                is_intent_fulfilled INTEGER NOT NULL,
+                confidence REAL NOT NULL,
+                recorded_at TEXT NOT NULL,
+                result_json TEXT NOT NULL
+            );
+            CREATE INDEX IF NOT EXISTS idx_results_repo
+                ON verification_results (repo_url, recorded_at);
+            CREATE INDEX IF NOT EXISTS idx_results_pr
+                ON verification_results (repo_url, pr_number);",
+        )?;
+        Ok(ResultStore { conn })
+    }
+
+    /// Persist a verification result, returning its id
+    ///
+    /// The record time is taken from the result's `finished_at` metadata when present.
+    pub fn save(
+        &self,
+        key: &VerificationKey,
+        result: &IntentVerificationResult,
+    ) -> Result<i64, Box<dyn std::error::Error>> {
+        let recorded_at = if result.metadata.finished_at.is_empty() {
+            chrono::Utc::now().to_rfc3339()
+        } else {
+            result.metadata.finished_at.clone()
+        };
+
+        self.conn.execute(
+            "INSERT INTO verification_results
+                (repo_url, base_commit, head_commit, intent, pr_number,
+                 is_intent_fulfilled, confidence, recorded_at, result_json)
+             VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7, ?8, ?9)",
+            params![
+                key.repo_url,
+                key.base_commit,
+                key.head_commit,
+                key.intent,
+                key.pr_number.map(|n| n as i64),
+                result.is_intent_fulfilled,
+                result.confidence as f64,
+                recorded_at,
+                serde_json::to_string(result)?,
+            ],
+        )?;
+
+        Ok(self.conn.last_insert_rowid())
+    }
+
+    /// Latest result for exactly this repo, commit range and intent
+    pub fn latest(
+        &self,
+        key: &VerificationKey,
+    ) -> Result<Option<StoredResult>, Box<dyn std::error::Error>> {
+        self.query_one(
+            "WHERE repo_url = ?1 AND base_commit = ?2 AND head_commit = ?3 AND intent = ?4",
+            params![key.repo_url, key.base_commit, key.head_commit, key.intent],
+        )
+    }
+
+    /// Latest result recorded for a pull request
+    pub fn latest_for_pr(
+        &self,
+        repo_url: &str,
+        pr_number: u64,
+    ) -> Result<Option<StoredResult>, Box<dyn std::error::Error>> {
+        self.query_one(
+            "WHERE repo_url = ?1 AND pr_number = ?2",
+            params![repo_url, pr_number as i64],
+        )
+    }
+
+    /// All results recorded for a repository, newest first
+    pub fn history(
+        &self,
+        repo_url: &str,
+        limit: usize,
+    ) -> Result<Vec<StoredResult>, Box<dyn std::error::Error>> {
+        let mut stmt = self.conn.prepare(&format!(
+            "{} WHERE repo_url = ?1 ORDER BY recorded_at DESC, id DESC LIMIT ?2",
+            SELECT_COLUMNS
+        ))?;
+        let rows = stmt.query_map(params![repo_url, limit as i64], row_to_stored)?;
+
+        let mut results = Vec::new();
+        for row in rows {
+            results.push(row??);
+        }
+        Ok(results)
+    }
+
+    /// Daily pass rate for a repository (or all repositories when `None`), oldest day first
+    ///
+    /// `since` restricts the range to results recorded at or after the given RFC 3339 timestamp.
+    pub fn pass_rate_over_time(
+        &self,
+        repo_url: Option<&str>,
+        since: Option<&str>,
+    ) -> Result<Vec<PassRatePoint>, Box<dyn std::error::Error>> {
+        let mut stmt = self.conn.prepare(
+            "SELECT substr(recorded_at, 1, 10) AS day,
+                    COUNT(*),
+                    SUM(is_intent_fulfilled)
+             FROM verification_results
+             WHERE (?1 IS NULL OR repo_url = ?1)
+               AND (?2 IS NULL OR recorded_at >= ?2)
+             GROUP BY day
+             ORDER BY day",
+        )?;
+        let rows = stmt.query_map(params![repo_url, since], |row| {
+            let total: i64 = row.get(1)?;
+            let passed: i64 = row.get(2)?;
+            Ok(PassRatePoint {
+                date: row.get(0)?,
+                total: total as u64,
+                passed: passed as u64,
+                pass_rate: if total > 0 {
+                    passed as f32 / total as f32
+                } else {
+                    0.0
+                },
+            })
+        })?;
+
+        let mut points = Vec::new();
+        for row in rows {
+            points.push(row?);
+        }
+        Ok(points)
+    }
+
+    fn query_one(
+        &self,
+        filter: &str,
+        params: impl rusqlite::Params,
+    ) -> Result<Option<StoredResult>, Box<dyn std::error::Error>> {
+        let sql = format!(
+            "{} {} ORDER BY recorded_at DESC, id DESC LIMIT 1",
+            SELECT_COLUMNS, filter
+        );
+        let stored = self
+            .conn
+            .query_row(&sql, params, row_to_stored)
+            .optional()?;
+
+        match stored {
+            Some(result) => Ok(Some(result?)),
+            None => Ok(None),
+        }
+    }
+}
+
+const SELECT_COLUMNS: &str = "SELECT id, repo_url, base_commit, head_commit, intent, pr_number, \
+     recorded_at, result_json FROM verification_results";
+
+/// Convert a row selected with `SELECT_COLUMNS` into a stored result
+///
+/// JSON decoding errors are returned in the inner result so they aren't reported as SQL errors.
+fn row_to_stored(row: &rusqlite::Row) -> rusqlite::Result<Result<StoredResult, serde_json::Error>> {
+    let pr_number: Option<i64> = row.get(5)?;
+    let result_json: String = row.get(7)?;
+    let key = VerificationKey {
+        repo_url: row.get(1)?,
+        base_commit: row.get(2)?,
+        head_commit: row.get(3)?,
+        intent: row.get(4)?,
+        pr_number: pr_number.map(|n| n as u64),
+    };
+    let id = row.get(0)?;
+    let recorded_at = row.get(6)?;
+
+    Ok(
+        serde_json::from_str(&result_json).map(|result| StoredResult {
+            id,
+            key,
+            recorded_at,
+            result,
+        }),
+    )
+}
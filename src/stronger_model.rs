@@ -0,0 +1,214 @@
+use crate::openai::{
+    DEFAULT_MODEL, ask_openai_with_options, observe_prompt, overall_assessment_prompt,
+    record_exchange,
+};
+use crate::options::AnalysisOptions;
+use crate::types::{
+    ChangeRelevance, IntentVerificationResult, PromptStage, TestTargetsWithCode, Warning,
+    WarningKind,
+};
+use crate::utils::extract_json_from_response;
+
+/// Confidence below which the final judgment is re-run when no threshold is configured
+pub const DEFAULT_RETRY_CONFIDENCE: f32 = 0.75;
+
+/// A stronger model the final judgment is re-run with when the first verdict is uncertain
+///
+/// The file analyses are done once, with the regular model; only the verdict is asked again.
+#[derive(Debug, Clone, PartialEq, serde::Serialize, serde::Deserialize)]
+#[serde(default)]
+pub struct StrongerModelConfig {
+    pub model: String,
+    /// Base URL of the stronger model's provider, when it isn't the regular one
+    pub base_url: Option<String>,
+    /// Confidence (0.0-1.0) below which the verdict is re-run
+    pub min_confidence: f32,
+}
+
+impl Default for StrongerModelConfig {
+    fn default() -> Self {
+        StrongerModelConfig {
+            model: "gpt-4o".to_string(),
+            base_url: None,
+            min_confidence: DEFAULT_RETRY_CONFIDENCE,
+        }
+    }
+}
+
+/// A verdict and the assessment behind it, from one model
+#[derive(Debug, Clone, PartialEq, serde::Serialize, serde::Deserialize)]
+pub struct Judgment {
+    pub model: String,
+    pub is_intent_fulfilled: bool,
+    pub confidence: f32,
+    pub assessment: String,
+}
+
+/// Both verdicts of a result whose final judgment was re-run with a stronger model; the
+/// result carries the stronger model's
+#[derive(Debug, Clone, PartialEq, serde::Serialize, serde::Deserialize)]
+pub struct ModelRetry {
+    /// Low confidence, or file analyses that disagree with the verdict
+    pub reasons: Vec<String>,
+    pub initial: Judgment,
+    pub retried: Judgment,
+}
+
+/// Why a result's verdict should be judged again, empty when it shouldn't
+///
+/// Besides a confidence below `min_confidence`, the file analyses disagree with the verdict
+/// when a file the model found required for the intent doesn't support it although the intent
+/// is fulfilled, or when some file supports the intent although it isn't.
+pub fn retry_reasons(result: &IntentVerificationResult, min_confidence: f32) -> Vec<String> {
+    let mut reasons = Vec::new();
+    if result.confidence < min_confidence {
+        reasons.push(format!(
+            "Confidence {:.0}% is below the {:.0}% threshold",
+            result.confidence * 100.0,
+            min_confidence * 100.0
+        ));
+    }
+    let judged = result
+        .files_analyzed
+        .iter()
+        .filter(|analysis| analysis.outcome.is_analyzed());
+    let disagreeing: Vec<&str> = if result.is_intent_fulfilled {
+        judged
+            .filter(|analysis| {
+                !analysis.supports_intent && analysis.relevance == Some(ChangeRelevance::Required)
+            })
+            .map(|analysis| analysis.file_path.as_str())
+            .collect()
+    } else {
+        judged
+            .filter(|analysis| analysis.supports_intent)
+            .map(|analysis| analysis.file_path.as_str())
+            .collect()
+    };
+    if !disagreeing.is_empty() {
+        reasons.push(format!(
+            "File analyses disagree with the verdict: {}",
+            disagreeing.join(", ")
+        ));
+    }
+    reasons
+}
+
+/// Re-run the final judgment with the stronger model when [`retry_reasons`] finds any, and
+/// replace the result's verdict, confidence and assessment with its answer
+///
+/// A failed retry is recorded in the result's warnings and leaves the first verdict.
+#[allow(clippy::too_many_arguments)]
+pub(crate) async fn retry_with_stronger_model(
+    result: &mut IntentVerificationResult,
+    targets_with_code: &TestTargetsWithCode,
+    user_intent: &str,
+    api_key: &str,
+    model: Option<&str>,
+    base_url: Option<&str>,
+    config: &StrongerModelConfig,
+    options: &AnalysisOptions,
+) {
+    let reasons = retry_reasons(result, config.min_confidence);
+    if reasons.is_empty() {
+        return;
+    }
+    let initial = Judgment {
+        model: model.unwrap_or(DEFAULT_MODEL).to_string(),
+        is_intent_fulfilled: result.is_intent_fulfilled,
+        confidence: result.confidence,
+        assessment: result.overall_assessment.clone(),
+    };
+    eprintln!(
+        "🔁 Re-judging with {}: {}",
+        config.model,
+        reasons.join("; ")
+    );
+
+    let prompt = judgment_prompt(result, targets_with_code, user_intent, &initial, options);
+    observe_prompt(options, PromptStage::StrongerModel, &prompt);
+    let reply = ask_openai_with_options(
+        &prompt,
+        api_key,
+        Some(&config.model),
+        config.base_url.as_deref().or(base_url),
+        options,
+    )
+    .await
+    .map_err(|e| e.to_string())
+    .and_then(|reply| {
+        record_exchange(options, PromptStage::StrongerModel, &prompt, &reply);
+        parse_judgment(&config.model, &reply)
+    });
+    match reply {
+        Ok(retried) => {
+            result.is_intent_fulfilled = retried.is_intent_fulfilled;
+            result.confidence = retried.confidence;
+            result.overall_assessment = retried.assessment.clone();
+            result.explanation = format!("{}; re-judged by {}", result.explanation, retried.model);
+            result.model_retry = Some(ModelRetry {
+                reasons,
+                initial,
+                retried,
+            });
+        }
+        Err(e) => {
+            result.warnings.push(Warning {
+                kind: WarningKind::StrongerModel,
+                file_path: None,
+                message: format!("Failed to re-judge with {}: {}", config.model, e),
+            });
+            result.is_partial = true;
+        }
+    }
+}
+
+fn judgment_prompt(
+    result: &IntentVerificationResult,
+    targets_with_code: &TestTargetsWithCode,
+    user_intent: &str,
+    initial: &Judgment,
+    options: &AnalysisOptions,
+) -> String {
+    format!(
+        "{}\n\n\
+         A first review concluded the intent is {} with {:.0}% confidence:\n{}\n\n\
+         That review may be wrong. Weigh the file analyses above yourself and give the final \
+         judgment. Respond with JSON only:\n\
+         {{\"is_intent_fulfilled\": true or false, \"confidence\": 0.0 to 1.0, \"assessment\": \
+         \"a concise overall assessment\"}}",
+        overall_assessment_prompt(
+            &result.files_analyzed,
+            targets_with_code,
+            user_intent,
+            options
+        ),
+        if initial.is_intent_fulfilled {
+            "FULFILLED"
+        } else {
+            "NOT FULFILLED"
+        },
+        initial.confidence * 100.0,
+        initial.assessment
+    )
+}
+
+fn parse_judgment(model: &str, reply: &str) -> Result<Judgment, String> {
+    let json: serde_json::Value =
+        serde_json::from_str(&extract_json_from_response(reply)).map_err(|e| e.to_string())?;
+    Ok(Judgment {
+        model: model.to_string(),
+        is_intent_fulfilled: json["is_intent_fulfilled"]
+            .as_bool()
+            .ok_or("Response has no verdict")?,
+        confidence: json["confidence"]
+            .as_f64()
+            .ok_or("Response has no confidence")?
+            .clamp(0.0, 1.0) as f32,
+        assessment: json["assessment"]
+            .as_str()
+            .unwrap_or_default()
+            .trim()
+            .to_string(),
+    })
+}
@@ -0,0 +1,109 @@
+use std::sync::LazyLock;
+
+use regex::Regex;
+
+use crate::types::TestTargets;
+
+/// File paths with a common source, config or docs extension
+static FILE_PATH: LazyLock<Regex> = LazyLock::new(|| {
+    Regex::new(
+        r"(?:[\w.-]+/)*[\w-]+\.(?:rs|py|ts|tsx|js|jsx|mjs|go|java|kt|rb|sol|move|cairo|c|h|cc|cpp|hpp|cs|swift|php|toml|json|ya?ml|sql|sh|md)\b",
+    )
+    .unwrap()
+});
+
+/// Calls like `parse()`, `Parser::new(..)` or `client.send(..)`, capturing the function name
+static CALL: LazyLock<Regex> =
+    LazyLock::new(|| Regex::new(r"\b([A-Za-z_][A-Za-z0-9_]*)\(").unwrap());
+
+/// Declarations like `fn parse` or `def parse`; "function" is left out, since in prose it's
+/// usually followed by an ordinary word
+static DECLARATION: LazyLock<Regex> =
+    LazyLock::new(|| Regex::new(r"\b(?:fn|def)\s+([A-Za-z_][A-Za-z0-9_]*)").unwrap());
+
+/// Identifiers quoted in backticks, e.g. `` `calculate_sum` `` or `` `Parser::parse` ``
+static QUOTED: LazyLock<Regex> = LazyLock::new(|| {
+    Regex::new(r"`(?:[A-Za-z_][A-Za-z0-9_]*::)*([A-Za-z_][A-Za-z0-9_]*)`").unwrap()
+});
+
+/// Words that look like code (`snake_case` or `camelCase`) and may name a target
+static CODE_LIKE: LazyLock<Regex> = LazyLock::new(|| {
+    Regex::new(r"\b(?:[a-z][a-z0-9]*(?:_[a-z0-9]+)+|[a-z][a-z0-9]*[A-Z][A-Za-z0-9]*)\b").unwrap()
+});
+
+/// Names followed by `(` that aren't functions the intent asks about
+const NOT_TARGETS: &[&str] = &[
+    "if",
+    "for",
+    "while",
+    "match",
+    "return",
+    "assert",
+    "assert_eq",
+    "assert_ne",
+    "e",
+    "i",
+    "eg",
+    "ie",
+    "see",
+];
+
+/// Test targets read from the intent text without the model
+#[derive(Debug, Clone)]
+pub struct HeuristicTargets {
+    pub targets: TestTargets,
+    /// Code-like words that weren't recognized as a function or file, so the model has to
+    /// decide what they refer to
+    pub ambiguous: Vec<String>,
+}
+
+impl HeuristicTargets {
+    /// Whether the targets can be used without asking the model: something was found and
+    /// nothing was left unexplained
+    pub fn is_conclusive(&self) -> bool {
+        self.ambiguous.is_empty() && !self.targets.is_empty()
+    }
+}
+
+/// Extract the functions and files an intent names literally, e.g. `src/foo.rs` and
+/// `my_function()`
+pub fn extract_test_targets_heuristically(prompt: &str) -> HeuristicTargets {
+    let mut files = Vec::new();
+    for m in FILE_PATH.find_iter(prompt) {
+        push_unique(&mut files, m.as_str());
+    }
+    // Paths are matched first so their parts aren't mistaken for functions
+    let text = FILE_PATH.replace_all(prompt, " ");
+
+    let mut functions = Vec::new();
+    for regex in [&*CALL, &*DECLARATION, &*QUOTED] {
+        for captures in regex.captures_iter(&text) {
+            let name = &captures[1];
+            if !NOT_TARGETS.contains(&name.to_lowercase().as_str()) {
+                push_unique(&mut functions, name);
+            }
+        }
+    }
+
+    let mut ambiguous = Vec::new();
+    for m in CODE_LIKE.find_iter(&text) {
+        if !functions.iter().any(|f| f == m.as_str()) {
+            push_unique(&mut ambiguous, m.as_str());
+        }
+    }
+
+    HeuristicTargets {
+        targets: TestTargets {
+            functions,
+            files,
+            ..Default::default()
+        },
+        ambiguous,
+    }
+}
+
+fn push_unique(list: &mut Vec<String>, value: &str) {
+    if !list.iter().any(|v| v == value) {
+        list.push(value.to_string());
+    }
+}
@@ -0,0 +1,160 @@
+use crate::types::TestTargetsWithCode;
+
+/// What kind of code a target names
+#[derive(Debug, Clone, Copy, PartialEq, Eq, serde::Serialize, serde::Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum TargetKind {
+    Function,
+    File,
+    Module,
+    Class,
+    Command,
+    Endpoint,
+}
+
+impl TargetKind {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            TargetKind::Function => "function",
+            TargetKind::File => "file",
+            TargetKind::Module => "module",
+            TargetKind::Class => "class",
+            TargetKind::Command => "command",
+            TargetKind::Endpoint => "endpoint",
+        }
+    }
+}
+
+/// Whether a target was found in the test repository
+#[derive(Debug, Clone, Copy, PartialEq, Eq, serde::Serialize, serde::Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum TargetStatus {
+    /// Its code was read
+    Resolved,
+    /// The repository was searched, but it isn't there
+    NotFound,
+    /// It exists but couldn't be read as text, e.g. a binary file
+    Unreadable,
+    /// The repository couldn't be read, so whether it exists is unknown
+    LookupFailed,
+    /// Only functions and files are looked up; the model is given the name as is
+    NotLookedUp,
+}
+
+impl TargetStatus {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            TargetStatus::Resolved => "resolved",
+            TargetStatus::NotFound => "not found",
+            TargetStatus::Unreadable => "unreadable",
+            TargetStatus::LookupFailed => "lookup failed",
+            TargetStatus::NotLookedUp => "not looked up",
+        }
+    }
+}
+
+/// One extracted target and where it was found
+#[derive(Debug, Clone, PartialEq, serde::Serialize, serde::Deserialize)]
+pub struct TargetResolution {
+    pub kind: TargetKind,
+    pub name: String,
+    pub status: TargetStatus,
+    pub file_path: Option<String>,
+    /// 1-based line the function starts at; `None` for whole files
+    pub line: Option<usize>,
+    /// Why the target wasn't resolved
+    pub error: Option<String>,
+}
+
+impl TargetResolution {
+    /// Whether the target should have been found but wasn't: not found, unreadable or
+    /// not looked up because the repository couldn't be read
+    pub fn is_unresolved(&self) -> bool {
+        matches!(
+            self.status,
+            TargetStatus::NotFound | TargetStatus::Unreadable | TargetStatus::LookupFailed
+        )
+    }
+}
+
+/// Every target of the intent, in extraction order, with the code it was resolved to
+///
+/// Functions and files missing from `function_contents` and `file_contents` weren't looked
+/// up at all, which happens when the test repository couldn't be read.
+pub fn target_resolution(targets_with_code: &TestTargetsWithCode) -> Vec<TargetResolution> {
+    let targets = &targets_with_code.targets;
+    let lookup_failed = |kind, name: &String| TargetResolution {
+        kind,
+        name: name.clone(),
+        status: TargetStatus::LookupFailed,
+        file_path: None,
+        line: None,
+        error: Some("The test repository couldn't be read".to_string()),
+    };
+
+    let mut resolution = Vec::new();
+    for name in &targets.functions {
+        resolution.push(
+            match targets_with_code
+                .function_contents
+                .iter()
+                .find(|function| &function.name == name)
+            {
+                Some(function) => TargetResolution {
+                    kind: TargetKind::Function,
+                    name: name.clone(),
+                    status: if function.content.is_some() {
+                        TargetStatus::Resolved
+                    } else {
+                        TargetStatus::NotFound
+                    },
+                    file_path: function.file_path.clone(),
+                    line: function.line,
+                    error: function.error.clone(),
+                },
+                None => lookup_failed(TargetKind::Function, name),
+            },
+        );
+    }
+    for path in &targets.files {
+        resolution.push(
+            match targets_with_code
+                .file_contents
+                .iter()
+                .find(|file| &file.path == path)
+            {
+                Some(file) => TargetResolution {
+                    kind: TargetKind::File,
+                    name: path.clone(),
+                    status: match &file.error {
+                        None => TargetStatus::Resolved,
+                        Some(error) if error.starts_with("File not found") => {
+                            TargetStatus::NotFound
+                        }
+                        Some(_) => TargetStatus::Unreadable,
+                    },
+                    file_path: file.error.is_none().then(|| path.clone()),
+                    line: None,
+                    error: file.error.clone(),
+                },
+                None => lookup_failed(TargetKind::File, path),
+            },
+        );
+    }
+    for (kind, names) in [
+        (TargetKind::Module, &targets.modules),
+        (TargetKind::Class, &targets.classes),
+        (TargetKind::Command, &targets.commands),
+        (TargetKind::Endpoint, &targets.endpoints),
+    ] {
+        resolution.extend(names.iter().map(|name| TargetResolution {
+            kind,
+            name: name.clone(),
+            status: TargetStatus::NotLookedUp,
+            file_path: None,
+            line: None,
+            error: None,
+        }));
+    }
+    resolution
+}
@@ -0,0 +1,49 @@
+use opentelemetry::trace::TracerProvider as _;
+use opentelemetry_otlp::{SpanExporter, WithExportConfig};
+use opentelemetry_sdk::Resource;
+use opentelemetry_sdk::trace::SdkTracerProvider;
+use tracing_subscriber::layer::SubscriberExt;
+use tracing_subscriber::util::SubscriberInitExt;
+
+/// Keeps the OpenTelemetry exporter running; dropping it flushes the remaining spans
+pub struct OtelGuard {
+    provider: SdkTracerProvider,
+}
+
+impl Drop for OtelGuard {
+    fn drop(&mut self) {
+        if let Err(e) = self.provider.shutdown() {
+            eprintln!("⚠️  Failed to flush traces: {}", e);
+        }
+    }
+}
+
+/// Export the verification spans (clone, diff, target extraction, file analysis and every LLM
+/// request) to an OTLP collector over HTTP
+///
+/// `endpoint` is the full traces URL, e.g. `http://localhost:4318/v1/traces`; when `None`,
+/// the standard `OTEL_EXPORTER_OTLP_*` environment variables apply. This installs the global
+/// `tracing` subscriber, so call it once at startup and keep the guard alive.
+pub fn init_otel_tracing(
+    service_name: &str,
+    endpoint: Option<&str>,
+) -> Result<OtelGuard, Box<dyn std::error::Error>> {
+    let mut exporter = SpanExporter::builder().with_http();
+    if let Some(endpoint) = endpoint {
+        exporter = exporter.with_endpoint(endpoint);
+    }
+    let provider = SdkTracerProvider::builder()
+        .with_batch_exporter(exporter.build()?)
+        .with_resource(
+            Resource::builder()
+                .with_service_name(service_name.to_string())
+                .build(),
+        )
+        .build();
+
+    let tracer = provider.tracer("intent-verification");
+    tracing_subscriber::registry()
+        .with(tracing_opentelemetry::layer().with_tracer(tracer))
+        .try_init()?;
+    Ok(OtelGuard { provider })
+}
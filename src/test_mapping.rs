@@ -0,0 +1,318 @@
+use std::sync::LazyLock;
+
+use regex::Regex;
+
+use crate::code_parser::{extract_function_from_content_with_name, is_source_file_by_name};
+use crate::types::{FunctionContent, TestTargetsWithCode};
+use crate::utils::is_test_path;
+
+/// Most source files added to the context, so a test importing half the crate doesn't crowd
+/// out the changes
+pub const MAX_TESTED_SOURCES: usize = 10;
+
+/// Rust `use` declarations, capturing the path and any `{...}` group after it
+static RUST_USE: LazyLock<Regex> = LazyLock::new(|| {
+    Regex::new(r"(?m)^\s*(?:pub\s+)?use\s+((?:\w+::)*\w+)(?:::\{([^}]*)\}|::\*)?\s*;").unwrap()
+});
+
+/// Python `from a.b import c, d` and `import a.b`
+static PYTHON_IMPORT: LazyLock<Regex> = LazyLock::new(|| {
+    Regex::new(r"(?m)^\s*(?:from\s+(\.*[\w.]*)\s+import\s+\(?([\w, ]+)|import\s+([\w.]+))").unwrap()
+});
+
+/// Relative JavaScript and TypeScript imports and requires, e.g. `from '../src/sum'`
+static JS_IMPORT: LazyLock<Regex> = LazyLock::new(|| {
+    Regex::new(r#"(?:\bfrom\s+|\bimport\s+|\brequire\(\s*)['"](\.{1,2}/[^'"]+)['"]"#).unwrap()
+});
+
+/// Calls, capturing the function name
+static CALL: LazyLock<Regex> = LazyLock::new(|| Regex::new(r"\b([A-Za-z_]\w*)\s*\(").unwrap());
+
+/// Rust paths that never point into the repository
+const EXTERNAL_CRATES: &[&str] = &["std", "core", "alloc"];
+
+const JS_EXTENSIONS: &[&str] = &["ts", "tsx", "js", "jsx", "mjs"];
+
+/// A source file a test target imports, with the functions the tests call in it
+#[derive(Debug, Clone, PartialEq, serde::Serialize, serde::Deserialize)]
+pub struct TestedSource {
+    pub path: String,
+    pub content: String,
+    /// Test files importing it
+    pub tested_by: Vec<String>,
+    /// Functions it defines that the tests call
+    pub functions: Vec<String>,
+}
+
+/// The source files the test targets import and exercise, found statically from their
+/// `use`, `import` and `require` statements
+///
+/// The test files are the file targets under test paths and the files test function targets
+/// (see `is_test_function`) were found in; `read_file` reads a repository file, `None` when it doesn't exist. Imports
+/// are resolved for Rust (crate-relative module paths and `super`), Python (package and
+/// relative imports) and JavaScript/TypeScript (relative paths). Sources that are targets
+/// already aren't repeated, and at most [`MAX_TESTED_SOURCES`] are returned.
+pub fn map_tests_to_sources(
+    targets_with_code: &TestTargetsWithCode,
+    read_file: impl Fn(&str) -> Option<String>,
+) -> Vec<TestedSource> {
+    let mut test_files: Vec<(String, String)> = targets_with_code
+        .file_contents
+        .iter()
+        .filter(|file| file.error.is_none() && is_test_path(&file.path))
+        .map(|file| (file.path.clone(), file.content.clone()))
+        .collect();
+    for path in targets_with_code
+        .function_contents
+        .iter()
+        .filter(|function| is_test_function(function))
+        .filter_map(|function| function.file_path.as_deref())
+    {
+        if !test_files.iter().any(|(test_path, _)| test_path == path)
+            && let Some(content) = read_file(path)
+        {
+            test_files.push((path.to_string(), content));
+        }
+    }
+
+    let mut sources: Vec<TestedSource> = Vec::new();
+    for (test_path, test_content) in &test_files {
+        let called: Vec<&str> = CALL
+            .captures_iter(test_content)
+            .map(|captures| captures.get(1).unwrap().as_str())
+            .collect();
+        for candidates in imported_paths(test_path, test_content) {
+            let Some((path, content)) = candidates
+                .iter()
+                .find_map(|path| Some((path.clone(), read_file(path)?)))
+            else {
+                continue;
+            };
+            if is_test_path(&path)
+                || targets_with_code.targets.files.contains(&path)
+                || !is_source_file_by_name(&path)
+            {
+                continue;
+            }
+
+            let functions: Vec<String> = called
+                .iter()
+                .filter(|name| {
+                    extract_function_from_content_with_name(&content, name, &path).is_some()
+                })
+                .map(|name| name.to_string())
+                .collect();
+            // Any crate's imports fall back to the root module, so it must be called into
+            if path.ends_with("src/lib.rs") && functions.is_empty() {
+                continue;
+            }
+
+            let source = match sources.iter_mut().position(|source| source.path == path) {
+                Some(i) => &mut sources[i],
+                None if sources.len() < MAX_TESTED_SOURCES => {
+                    sources.push(TestedSource {
+                        path: path.clone(),
+                        content,
+                        tested_by: vec![],
+                        functions: vec![],
+                    });
+                    sources.last_mut().unwrap()
+                }
+                None => continue,
+            };
+            if !source.tested_by.contains(test_path) {
+                source.tested_by.push(test_path.clone());
+            }
+            for function in functions {
+                if !source.functions.contains(&function) {
+                    source.functions.push(function);
+                }
+            }
+        }
+    }
+    sources
+}
+
+/// Whether a found function target is a test: it's in a test file, named like a test or
+/// marked as one
+fn is_test_function(function: &FunctionContent) -> bool {
+    let content = function.content.as_deref().unwrap_or_default();
+    function.file_path.as_deref().is_some_and(is_test_path)
+        || function.name.starts_with("test")
+        || content.contains("#[test]")
+        || content.contains("#[tokio::test")
+}
+
+/// For each import of a test file, the repository paths it may refer to, most specific first
+fn imported_paths(test_path: &str, content: &str) -> Vec<Vec<String>> {
+    let extension = test_path.rsplit('.').next().unwrap_or_default();
+    match extension {
+        "rs" => rust_imports(test_path, content),
+        "py" => python_imports(test_path, content),
+        _ if JS_EXTENSIONS.contains(&extension) => js_imports(test_path, content),
+        _ => vec![],
+    }
+}
+
+fn rust_imports(test_path: &str, content: &str) -> Vec<Vec<String>> {
+    // Integration tests in `<crate>/tests/` import from `<crate>/src/`
+    let crate_root = match test_path.rfind("tests/") {
+        Some(i) if i == 0 || test_path[..i].ends_with('/') => &test_path[..i],
+        _ => test_path
+            .rfind("src/")
+            .map(|i| &test_path[..i])
+            .unwrap_or_default(),
+    };
+
+    let mut imports = Vec::new();
+    for captures in RUST_USE.captures_iter(content) {
+        let path = &captures[1];
+        let full_paths: Vec<String> = match captures.get(2) {
+            Some(group) => group
+                .as_str()
+                .split(',')
+                .map(|item| item.split(" as ").next().unwrap_or_default().trim())
+                .filter(|item| !item.is_empty() && *item != "*")
+                .map(|item| format!("{}::{}", path, item))
+                .chain([path.to_string()])
+                .collect(),
+            None => vec![path.to_string()],
+        };
+        for full_path in full_paths {
+            let segments: Vec<&str> = full_path.split("::").collect();
+            match segments[0] {
+                "super" => imports.push(parent_module(test_path)),
+                "self" => {}
+                first if EXTERNAL_CRATES.contains(&first) => {}
+                _ => imports.push(rust_module_files(crate_root, &segments[1..])),
+            }
+        }
+    }
+    imports
+}
+
+/// `src/<a>/<b>.rs` or `src/<a>/<b>/mod.rs` for the longest module prefix of `segments`,
+/// down to the crate's `lib.rs`
+fn rust_module_files(crate_root: &str, segments: &[&str]) -> Vec<String> {
+    let mut candidates = Vec::new();
+    for len in (1..=segments.len()).rev() {
+        let module = segments[..len].join("/");
+        candidates.push(format!("{}src/{}.rs", crate_root, module));
+        candidates.push(format!("{}src/{}/mod.rs", crate_root, module));
+    }
+    candidates.push(format!("{}src/lib.rs", crate_root));
+    candidates
+}
+
+/// The file of the module a Rust file's `super` refers to
+fn parent_module(path: &str) -> Vec<String> {
+    let dir = match path.strip_suffix("/mod.rs") {
+        Some(module_dir) => parent_dir(module_dir),
+        None => parent_dir(path),
+    };
+    let mut candidates = vec![format!("{}.rs", dir), format!("{}/mod.rs", dir)];
+    if dir.ends_with("src") {
+        candidates.push(format!("{}/lib.rs", dir));
+        candidates.push(format!("{}/main.rs", dir));
+    }
+    candidates
+}
+
+fn python_imports(test_path: &str, content: &str) -> Vec<Vec<String>> {
+    let test_dir = parent_dir(test_path);
+    let mut imports = Vec::new();
+    for captures in PYTHON_IMPORT.captures_iter(content) {
+        let (module, names) = match (captures.get(1), captures.get(3)) {
+            (Some(module), _) => (module.as_str(), captures.get(2).map(|n| n.as_str())),
+            (None, Some(module)) => (module.as_str(), None),
+            _ => continue,
+        };
+
+        // Leading dots climb from the test's package
+        let dots = module.chars().take_while(|c| *c == '.').count();
+        let module_path = module[dots..].replace('.', "/");
+        let bases: Vec<String> = if dots > 0 {
+            let mut dir = test_dir.to_string();
+            for _ in 1..dots {
+                dir = parent_dir(&dir).to_string();
+            }
+            vec![dir]
+        } else {
+            vec![String::new(), "src".to_string()]
+        };
+
+        let join = |base: &str, module_path: &str| {
+            [base, module_path]
+                .into_iter()
+                .filter(|part| !part.is_empty())
+                .collect::<Vec<_>>()
+                .join("/")
+        };
+        // `from pkg import module` imports a module as often as a function
+        for name in names
+            .into_iter()
+            .flat_map(|names| names.split(','))
+            .map(|name| name.split(" as ").next().unwrap_or_default().trim())
+            .filter(|name| !name.is_empty())
+        {
+            let submodule = join(&module_path, name);
+            imports.push(
+                bases
+                    .iter()
+                    .map(|base| format!("{}.py", join(base, &submodule)))
+                    .collect(),
+            );
+        }
+        if !module_path.is_empty() {
+            imports.push(
+                bases
+                    .iter()
+                    .flat_map(|base| {
+                        let module = join(base, &module_path);
+                        [format!("{}.py", module), format!("{}/__init__.py", module)]
+                    })
+                    .collect(),
+            );
+        }
+    }
+    imports
+}
+
+fn js_imports(test_path: &str, content: &str) -> Vec<Vec<String>> {
+    let test_dir = parent_dir(test_path);
+    JS_IMPORT
+        .captures_iter(content)
+        .map(|captures| {
+            let path = normalize_path(&format!("{}/{}", test_dir, &captures[1]));
+            let mut candidates = vec![path.clone()];
+            for extension in JS_EXTENSIONS {
+                candidates.push(format!("{}.{}", path, extension));
+            }
+            for extension in JS_EXTENSIONS {
+                candidates.push(format!("{}/index.{}", path, extension));
+            }
+            candidates
+        })
+        .collect()
+}
+
+fn parent_dir(path: &str) -> &str {
+    path.rsplit_once('/')
+        .map(|(dir, _)| dir)
+        .unwrap_or_default()
+}
+
+/// `path` with `.` and `..` components resolved
+fn normalize_path(path: &str) -> String {
+    let mut components: Vec<&str> = Vec::new();
+    for component in path.split('/') {
+        match component {
+            "" | "." => {}
+            ".." => {
+                components.pop();
+            }
+            _ => components.push(component),
+        }
+    }
+    components.join("/")
+}
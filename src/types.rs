@@ -1,9 +1,78 @@
 use crate::ChangeType;
+use crate::ambiguity::IntentAmbiguity;
+use crate::attestation::Attestation;
+use crate::binary::BinaryChange;
+use crate::calibration::ConfidenceCalibration;
+use crate::change_summary::ChangeSummary;
+use crate::citations::Citation;
+use crate::coverage::CoverageEvidence;
+use crate::criteria::CriterionResult;
+use crate::escalation::Escalation;
+use crate::execution::TestRunResult;
+use crate::language_stats::LanguageStats;
+use crate::prioritization::SkippedFile;
+use crate::redaction::Redaction;
+use crate::regression::Regression;
+use crate::stronger_model::ModelRetry;
+use crate::target_resolution::TargetResolution;
+use crate::test_mapping::TestedSource;
 
-#[derive(Debug, Clone, serde::Serialize, serde::Deserialize)]
+/// Version of the serialized result schema, bumped on incompatible changes
+pub const SCHEMA_VERSION: &str = "1.0";
+
+/// Version of the prompt templates used for analysis
+pub const PROMPT_VERSION: &str = "5";
+
+/// What the intent expects to work
+///
+/// Only functions and files are looked up in the test repository; the other targets are given
+/// to the model as they were named. Targets missing from older answers and caches are empty.
+#[derive(Debug, Clone, Default, serde::Serialize, serde::Deserialize)]
 pub struct TestTargets {
     pub functions: Vec<String>,
     pub files: Vec<String>,
+    /// Modules or packages, e.g. `auth::session` or `payments.refunds`
+    #[serde(default)]
+    pub modules: Vec<String>,
+    /// Classes, structs, traits and other types
+    #[serde(default)]
+    pub classes: Vec<String>,
+    /// CLI commands or subcommands, e.g. `cargo xtask release`
+    #[serde(default)]
+    pub commands: Vec<String>,
+    /// API endpoints as `METHOD /path`, or just the path when no method is named
+    #[serde(default)]
+    pub endpoints: Vec<String>,
+}
+
+impl TestTargets {
+    pub fn is_empty(&self) -> bool {
+        self.functions.is_empty()
+            && self.files.is_empty()
+            && self.modules.is_empty()
+            && self.classes.is_empty()
+            && self.commands.is_empty()
+            && self.endpoints.is_empty()
+    }
+
+    /// The modules, classes, commands and endpoints as `kind: a, b` lines, or "none"
+    pub fn other_targets(&self) -> String {
+        let lines: Vec<String> = [
+            ("Modules", &self.modules),
+            ("Classes", &self.classes),
+            ("Commands", &self.commands),
+            ("Endpoints", &self.endpoints),
+        ]
+        .into_iter()
+        .filter(|(_, names)| !names.is_empty())
+        .map(|(kind, names)| format!("{}: {}", kind, names.join(", ")))
+        .collect();
+        if lines.is_empty() {
+            "none".to_string()
+        } else {
+            lines.join("\n")
+        }
+    }
 }
 
 #[derive(Debug, Clone, serde::Serialize, serde::Deserialize)]
@@ -11,6 +80,9 @@ pub struct TestTargetsWithCode {
     pub targets: TestTargets,
     pub file_contents: Vec<FileContent>,
     pub function_contents: Vec<FunctionContent>,
+    /// Source files the test targets import, see `map_tests_to_sources`
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub tested_sources: Vec<TestedSource>,
 }
 
 #[derive(Debug, Clone, serde::Serialize, serde::Deserialize)]
@@ -24,6 +96,9 @@ pub struct FileContent {
 pub struct FunctionContent {
     pub name: String,
     pub file_path: Option<String>,
+    /// 1-based line of `file_path` the function starts at
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub line: Option<usize>,
     pub content: Option<String>,
     pub error: Option<String>,
 }
@@ -35,6 +110,307 @@ pub struct IntentVerificationResult {
     pub explanation: String,
     pub files_analyzed: Vec<FileIntentAnalysis>,
     pub overall_assessment: String,
+    #[serde(default)]
+    pub metadata: ResultMetadata,
+    /// Problems encountered while producing this result that did not abort the run
+    #[serde(default)]
+    pub warnings: Vec<Warning>,
+    /// True when some stage or file failed and the verdict is based on incomplete information
+    #[serde(default)]
+    pub is_partial: bool,
+    /// Deterministic findings reported alongside the LLM analysis
+    #[serde(default)]
+    pub findings: Vec<Finding>,
+    /// Aggregated review risk (0.0-1.0), see `apply_risk_scores`
+    #[serde(default)]
+    pub risk_score: f32,
+    /// Prompts that were built but not sent, when `AnalysisOptions::dry_run` is set
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub prompts: Vec<PromptPreview>,
+    /// Outcome of actually running the tests, when `AnalysisOptions::execution` is set
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub test_run: Option<TestRunResult>,
+    /// Outcome of running the tests with the solution reverted, when
+    /// `ExecutionConfig::counterfactual` is set
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub test_run_without_solution: Option<TestRunResult>,
+    /// Which changed functions the test targets exercise; `None` when no test target code
+    /// could be read
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub coverage_evidence: Option<CoverageEvidence>,
+    /// Changed files the model classified as unrelated to the intent
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub unrelated_changes: Vec<String>,
+    /// Share of the changed lines in files needed for or supporting the intent (0.0-1.0),
+    /// see `apply_scope`
+    #[serde(default = "full_scope")]
+    pub scope_score: f32,
+    /// Per-criterion verdicts, when `AnalysisOptions::acceptance_criteria` is set
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub acceptance_criteria: Vec<CriterionResult>,
+    /// Signature of the result's canonical hash, when signing was requested
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub attestation: Option<Attestation>,
+    /// Questions for a human reviewer when the verdict is uncertain, see `escalation`
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub escalation: Option<Escalation>,
+    /// Changes since the earlier result in `AnalysisOptions::previous_result`
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub regression: Option<Regression>,
+    /// Raw and calibrated confidence, when `AnalysisOptions::calibrate_confidence` is set and
+    /// the provider returned log-probabilities
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub calibration: Option<ConfidenceCalibration>,
+    /// Changed files that weren't analyzed, e.g. beyond `AnalysisOptions::max_files_analyzed`
+    /// or over the size limits
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub skipped_files: Vec<SkippedFile>,
+    /// What the changes add, change and remove, when `AnalysisOptions::change_summary` is set
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub change_summary: Option<ChangeSummary>,
+    /// Changed files and lines per language, see `language_stats`
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub language_stats: Vec<LanguageStats>,
+    /// Size, type and digest of the changed binary files
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub binary_changes: Vec<BinaryChange>,
+    /// The first and the stronger model's verdicts, when `AnalysisOptions::stronger_model`
+    /// re-ran an uncertain one
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub model_retry: Option<ModelRetry>,
+    /// Every extracted target and the code it was resolved to, so a verdict caused by a failed
+    /// lookup can be told from a missing implementation
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub target_resolution: Vec<TargetResolution>,
+    /// Why the intent is too ambiguous to verify and what to clarify, when it scored above
+    /// `AnalysisOptions::max_intent_ambiguity`; nothing else was checked
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub needs_clarification: Option<IntentAmbiguity>,
+}
+
+fn full_scope() -> f32 {
+    1.0
+}
+
+/// An unfulfilled result with nothing analyzed, the same as deserializing `{}` would give
+impl Default for IntentVerificationResult {
+    fn default() -> Self {
+        IntentVerificationResult {
+            is_intent_fulfilled: false,
+            confidence: 0.0,
+            explanation: String::new(),
+            files_analyzed: vec![],
+            overall_assessment: String::new(),
+            metadata: ResultMetadata::default(),
+            warnings: vec![],
+            is_partial: false,
+            findings: vec![],
+            risk_score: 0.0,
+            prompts: vec![],
+            test_run: None,
+            test_run_without_solution: None,
+            coverage_evidence: None,
+            unrelated_changes: vec![],
+            scope_score: full_scope(),
+            acceptance_criteria: vec![],
+            attestation: None,
+            escalation: None,
+            regression: None,
+            calibration: None,
+            skipped_files: vec![],
+            change_summary: None,
+            language_stats: vec![],
+            binary_changes: vec![],
+            model_retry: None,
+            target_resolution: vec![],
+            needs_clarification: None,
+        }
+    }
+}
+
+/// How a changed file relates to the intent, ordered from least to most relevant
+#[derive(
+    Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash, serde::Serialize, serde::Deserialize,
+)]
+#[serde(rename_all = "lowercase")]
+pub enum ChangeRelevance {
+    /// Has nothing to do with the intent
+    Unrelated,
+    /// Helps (tests, docs, wiring) but the intent could be met without it
+    Supporting,
+    /// The intent can't be met without this change
+    Required,
+}
+
+/// Whether a changed file got a judgment from the model
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default, serde::Serialize, serde::Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum AnalysisOutcome {
+    #[default]
+    Analyzed,
+    /// The model refused or the provider's content filter blocked every block of the file,
+    /// also after a retry with string literals and comments masked; the file is left out of
+    /// the verdict
+    Refused,
+    /// The verification was cancelled before the file was analyzed; the file is left out of the
+    /// verdict
+    Cancelled,
+}
+
+impl AnalysisOutcome {
+    pub fn is_analyzed(&self) -> bool {
+        *self == AnalysisOutcome::Analyzed
+    }
+}
+
+/// Severity of a finding, ordered from least to most severe
+#[derive(
+    Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash, serde::Serialize, serde::Deserialize,
+)]
+#[serde(rename_all = "lowercase")]
+pub enum Severity {
+    Info,
+    Low,
+    Medium,
+    High,
+    Critical,
+}
+
+/// A specific issue detected in the changes
+#[derive(Debug, Clone, PartialEq, serde::Serialize, serde::Deserialize)]
+pub struct Finding {
+    /// Identifier of the rule that produced the finding (e.g. `secrets/aws-access-key`)
+    pub rule: String,
+    pub severity: Severity,
+    pub file_path: Option<String>,
+    /// 1-based line number, when the finding can be pinned to a line
+    pub line: Option<usize>,
+    /// Offending code or text, when available
+    pub snippet: Option<String>,
+    pub message: String,
+    /// Accepted via a baseline file; reported but ignored by verdict policies
+    #[serde(default)]
+    pub suppressed: bool,
+    /// CWE identifier (e.g. `CWE-89`), set by the security profile
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub cwe: Option<String>,
+    /// SHA-256 of the full offending content when `snippet` only shows part of it, e.g. a
+    /// redacted secret; identifies the finding in baselines
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub content_hash: Option<String>,
+}
+
+/// Pipeline stage a warning originates from
+#[derive(Debug, Clone, PartialEq, serde::Serialize, serde::Deserialize)]
+pub enum WarningKind {
+    TargetExtraction,
+    TargetLookup,
+    FileAnalysis,
+    ResponseParsing,
+    OverallAssessment,
+    TestExecution,
+    StaticAnalysis,
+    AcceptanceCriteria,
+    Similarity,
+    CodeOwners,
+    /// The model refused to analyze a block, or the provider's content filter blocked it
+    Refusal,
+    ChangeSummary,
+    StrongerModel,
+}
+
+/// Pipeline step a prompt belongs to
+#[derive(Debug, Clone, Copy, PartialEq, Eq, serde::Serialize, serde::Deserialize)]
+pub enum PromptStage {
+    TargetExtraction,
+    FileAnalysis,
+    OverallAssessment,
+    AcceptanceCriteria,
+    /// A reviewer's question about a finished result, see `VerificationConversation`
+    FollowUp,
+    /// The overview of what the changes do, see `ChangeSummary`
+    ChangeSummary,
+    /// The final judgment re-run with `AnalysisOptions::stronger_model`
+    StrongerModel,
+}
+
+/// One chat message of a prompt
+#[derive(Debug, Clone, PartialEq, serde::Serialize, serde::Deserialize)]
+pub struct PromptMessage {
+    /// `system`, `user` or `assistant`
+    pub role: String,
+    pub content: String,
+}
+
+/// A prompt exactly as it would be sent to the model
+#[derive(Debug, Clone, PartialEq, serde::Serialize, serde::Deserialize)]
+pub struct PromptPreview {
+    pub stage: PromptStage,
+    /// Changed file the prompt analyzes, for `FileAnalysis` prompts
+    pub file_path: Option<String>,
+    pub messages: Vec<PromptMessage>,
+    /// Rough input token count, see `estimate_tokens`
+    pub estimated_tokens: usize,
+}
+
+/// A non-fatal problem encountered during verification
+#[derive(Debug, Clone, serde::Serialize, serde::Deserialize)]
+pub struct Warning {
+    pub kind: WarningKind,
+    pub file_path: Option<String>,
+    pub message: String,
+}
+
+/// Information about how and when a result was produced, so stored results
+/// remain interpretable as the crate evolves
+#[derive(Debug, Clone, Default, serde::Serialize, serde::Deserialize)]
+pub struct ResultMetadata {
+    pub schema_version: String,
+    pub tool_version: String,
+    pub model: String,
+    pub prompt_version: String,
+    /// RFC 3339 timestamp
+    pub started_at: String,
+    /// RFC 3339 timestamp
+    pub finished_at: String,
+    pub duration_ms: u64,
+    /// Issue the intent was taken from, see `verify_intent_from_issue`
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub issue_url: Option<String>,
+    /// Changed files whose analysis was reused from `AnalysisOptions::analysis_cache`
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub reused_analyses: Vec<String>,
+    /// What `AnalysisOptions::redaction` stripped from the intent, tests and changes
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub redactions: Vec<Redaction>,
+}
+
+impl ResultMetadata {
+    /// Start recording metadata for a run using the given model
+    pub fn start(model: &str) -> Self {
+        ResultMetadata {
+            schema_version: SCHEMA_VERSION.to_string(),
+            tool_version: env!("CARGO_PKG_VERSION").to_string(),
+            model: model.to_string(),
+            prompt_version: PROMPT_VERSION.to_string(),
+            started_at: chrono::Utc::now().to_rfc3339(),
+            finished_at: String::new(),
+            duration_ms: 0,
+            issue_url: None,
+            reused_analyses: vec![],
+            redactions: vec![],
+        }
+    }
+
+    /// Mark the run as finished, filling in the end time and duration
+    pub fn finish(mut self) -> Self {
+        let finished = chrono::Utc::now();
+        self.duration_ms = chrono::DateTime::parse_from_rfc3339(&self.started_at)
+            .map(|started| (finished - started.to_utc()).num_milliseconds().max(0) as u64)
+            .unwrap_or(0);
+        self.finished_at = finished.to_rfc3339();
+        self
+    }
 }
 
 #[derive(Debug, Clone, serde::Serialize, serde::Deserialize)]
@@ -44,4 +420,50 @@ pub struct FileIntentAnalysis {
     pub supports_intent: bool,
     pub reasoning: String,
     pub relevant_changes: Vec<String>,
+    /// Line-level locations of the relevant changes, validated against the file content
+    #[serde(default)]
+    pub locations: Vec<ChangeLocation>,
+    /// Review risk of this file (0.0-1.0), see `apply_risk_scores`
+    #[serde(default)]
+    pub risk_score: f32,
+    /// How the change relates to the intent; `None` when the model didn't classify it
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub relevance: Option<ChangeRelevance>,
+    /// Owners of the file from the repository's CODEOWNERS file, see `apply_code_owners`
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub owners: Vec<String>,
+    /// Code the model quoted for its claims, checked against the cited files
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub citations: Vec<Citation>,
+    /// Confidence the model reported for its answer (0.0-1.0)
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub confidence: Option<f32>,
+    /// Probability that the file supports the intent, from the log-probabilities of the
+    /// model's answer; see `ConfidenceCalibration`
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub support_probability: Option<f32>,
+    /// Whether the model judged the file at all
+    #[serde(default, skip_serializing_if = "AnalysisOutcome::is_analyzed")]
+    pub outcome: AnalysisOutcome,
+}
+
+/// Analysis of one changed file with the warnings, prompts and findings it produced
+#[derive(Debug, Clone, serde::Serialize, serde::Deserialize)]
+pub struct FileAnalysisResult {
+    pub analysis: FileIntentAnalysis,
+    pub warnings: Vec<Warning>,
+    pub prompts: Vec<PromptPreview>,
+    pub findings: Vec<Finding>,
+}
+
+/// A relevant change pinned to a line range of a file
+#[derive(Debug, Clone, PartialEq, serde::Serialize, serde::Deserialize)]
+pub struct ChangeLocation {
+    pub file_path: String,
+    /// First line of the snippet (1-based)
+    pub start_line: usize,
+    /// Last line of the snippet (1-based, inclusive)
+    pub end_line: usize,
+    /// Code quoted from the file
+    pub snippet: String,
 }
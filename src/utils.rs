@@ -3,14 +3,93 @@
 /// Looks for JSON block between braces. Returns the original response if no JSON is found.
 pub fn extract_json_from_response(response: &str) -> String {
     // Look for JSON block between ```json and ``` or just find { ... }
-    if let Some(start) = response.find('{') {
-        if let Some(end) = response.rfind('}') {
-            if end > start {
-                return response[start..=end].to_string();
-            }
-        }
+    if let Some(start) = response.find('{')
+        && let Some(end) = response.rfind('}')
+        && end > start
+    {
+        return response[start..=end].to_string();
     }
 
     // If no JSON found, return the original response
     response.to_string()
 }
+
+/// Rough token count of a text, at about four characters per token
+///
+/// Good enough for cost estimates across common models; use the model's tokenizer when an
+/// exact count matters.
+pub fn estimate_tokens(text: &str) -> usize {
+    text.chars().count().div_ceil(4)
+}
+
+/// Whether a repository path looks like a test file (`tests/`, `test_*`, `*_test.*`, `*.spec.*`)
+pub(crate) fn is_test_path(path: &str) -> bool {
+    let lower = path.to_lowercase();
+    lower.starts_with("tests/")
+        || lower.contains("/tests/")
+        || lower.contains("test_")
+        || lower.contains("_test.")
+        || lower.contains(".test.")
+        || lower.contains(".spec.")
+}
+
+/// Locate a quoted code snippet in file content
+///
+/// Lines are compared after trimming whitespace and blank lines are ignored, so a snippet
+/// quoted with different indentation still matches. Single-line snippets may be a fragment
+/// of a line. Returns the 1-based inclusive `(start_line, end_line)` of the first match.
+pub fn locate_snippet(content: &str, snippet: &str) -> Option<(usize, usize)> {
+    let needle: Vec<&str> = snippet
+        .lines()
+        .map(str::trim)
+        .filter(|l| !l.is_empty())
+        .collect();
+    if needle.is_empty() {
+        return None;
+    }
+
+    let lines: Vec<&str> = content.lines().collect();
+
+    if needle.len() == 1 {
+        return lines
+            .iter()
+            .position(|line| line.contains(needle[0]))
+            .map(|i| (i + 1, i + 1));
+    }
+
+    for start in 0..lines.len() {
+        let mut matched = 0;
+        for (i, line) in lines.iter().enumerate().skip(start) {
+            let trimmed = line.trim();
+            if trimmed.is_empty() && matched > 0 {
+                continue;
+            }
+            // The first and last lines may be partial quotes
+            let is_edge = matched == 0 || matched == needle.len() - 1;
+            let ok = if is_edge {
+                trimmed.contains(needle[matched])
+            } else {
+                trimmed == needle[matched]
+            };
+            if !ok {
+                break;
+            }
+            matched += 1;
+            if matched == needle.len() {
+                return Some((start + 1, i + 1));
+            }
+        }
+    }
+
+    None
+}
+
+/// Lowercase hex SHA-256 digest of `data`
+pub(crate) fn sha256_hex(data: &[u8]) -> String {
+    use sha2::{Digest, Sha256};
+
+    Sha256::digest(data)
+        .iter()
+        .map(|b| format!("{:02x}", b))
+        .collect()
+}
@@ -0,0 +1,253 @@
+use std::path::Path;
+
+#[cfg(feature = "git")]
+use crate::options::AnalysisOptions;
+#[cfg(feature = "git")]
+use crate::privacy::{check_llm_endpoint, check_repository};
+use crate::verifier::VerificationRequest;
+
+/// Why a verification's inputs were rejected before anything was cloned or sent to the model
+#[derive(Debug, Clone, PartialEq)]
+pub enum ValidationError {
+    /// The intent is empty or only whitespace
+    EmptyIntent,
+    /// The repository is neither a URL nor a local path
+    InvalidRepoUrl { url: String, reason: String },
+    /// The repository is a local path that doesn't exist
+    RepoNotFound { url: String },
+    /// The commit can't be a SHA, branch, tag or other revision
+    InvalidCommit {
+        name: String,
+        commit: String,
+        reason: String,
+    },
+    /// The repository has no such commit, not even in refs outside its branches and tags;
+    /// `name` says which argument it was, e.g. `commit2`
+    CommitNotFound {
+        name: String,
+        commit: String,
+        repo_url: String,
+    },
+    /// No API key was given for the default OpenAI endpoint
+    MissingApiKey,
+    /// The API key can't be a valid key
+    InvalidApiKey { reason: String },
+    /// Local-only mode doesn't allow LLM requests to this endpoint
+    LlmEndpointNotAllowed { endpoint: String },
+    /// Local-only mode doesn't allow reaching this URL, e.g. a remote repository
+    NetworkAccessNotAllowed { url: String },
+}
+
+impl std::fmt::Display for ValidationError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            ValidationError::EmptyIntent => write!(f, "intent is empty"),
+            ValidationError::InvalidRepoUrl { url, reason } => {
+                write!(f, "invalid repository '{}': {}", url, reason)
+            }
+            ValidationError::RepoNotFound { url } => {
+                write!(f, "repository '{}' does not exist", url)
+            }
+            ValidationError::InvalidCommit {
+                name,
+                commit,
+                reason,
+            } => write!(f, "invalid {} '{}': {}", name, commit, reason),
+            ValidationError::CommitNotFound {
+                name,
+                commit,
+                repo_url,
+            } => write!(
+                f,
+                "{} '{}' not found in {}, even after fetching all of its refs",
+                name, commit, repo_url
+            ),
+            ValidationError::MissingApiKey => write!(f, "API key is empty"),
+            ValidationError::InvalidApiKey { reason } => write!(f, "invalid API key: {}", reason),
+            ValidationError::LlmEndpointNotAllowed { endpoint } => write!(
+                f,
+                "local-only mode doesn't allow LLM requests to {}; add it to the allowed endpoints",
+                endpoint
+            ),
+            ValidationError::NetworkAccessNotAllowed { url } => write!(
+                f,
+                "local-only mode doesn't allow network access to {}; use a local path or file:// URL",
+                url
+            ),
+        }
+    }
+}
+
+impl std::error::Error for ValidationError {}
+
+impl ValidationError {
+    /// Whether the error is about a repository or commit rather than the intent or API key
+    pub fn is_repository_error(&self) -> bool {
+        matches!(
+            self,
+            ValidationError::InvalidRepoUrl { .. }
+                | ValidationError::RepoNotFound { .. }
+                | ValidationError::InvalidCommit { .. }
+                | ValidationError::CommitNotFound { .. }
+        )
+    }
+}
+
+/// Check that `intent` says something
+pub fn validate_intent(intent: &str) -> Result<(), ValidationError> {
+    if intent.trim().is_empty() {
+        return Err(ValidationError::EmptyIntent);
+    }
+    Ok(())
+}
+
+/// Check that `url` is a URL git can clone from, or a local path that exists
+///
+/// Accepts `http(s)://`, `ssh://`, `git://` and `file://` URLs and scp-like `user@host:path`.
+pub fn validate_repo_url(url: &str) -> Result<(), ValidationError> {
+    let invalid = |reason: &str| ValidationError::InvalidRepoUrl {
+        url: url.to_string(),
+        reason: reason.to_string(),
+    };
+    if url.trim().is_empty() {
+        return Err(invalid("the URL is empty"));
+    }
+    if url.trim() != url || url.chars().any(char::is_control) {
+        return Err(invalid("the URL contains whitespace or control characters"));
+    }
+
+    if let Some((scheme, rest)) = url.split_once("://") {
+        return match scheme {
+            "http" | "https" | "ssh" | "git" | "git+ssh" => {
+                if rest.split('/').next().is_none_or(str::is_empty) {
+                    Err(invalid("the URL has no host"))
+                } else {
+                    Ok(())
+                }
+            }
+            "file" if Path::new(rest).exists() => Ok(()),
+            "file" => Err(ValidationError::RepoNotFound {
+                url: url.to_string(),
+            }),
+            _ => Err(invalid(&format!("unsupported scheme '{}'", scheme))),
+        };
+    }
+    if Path::new(url).exists() {
+        return Ok(());
+    }
+    // scp-like syntax: user@host:path
+    if let Some((host, path)) = url.split_once(':')
+        && host.contains('@')
+        && !host.contains('/')
+        && !path.is_empty()
+    {
+        return Ok(());
+    }
+    if url.starts_with('/') || url.starts_with('.') || url.starts_with('~') {
+        return Err(ValidationError::RepoNotFound {
+            url: url.to_string(),
+        });
+    }
+    Err(invalid(
+        "expected an http(s), ssh, git or file URL, user@host:path, or an existing local path",
+    ))
+}
+
+/// Check that `commit` could name a revision: a SHA, a branch or tag, or an expression like
+/// `HEAD~1`; whether it exists is only known once the repository is cloned
+///
+/// `name` says which argument it is in the error, e.g. `commit2`.
+pub fn validate_commit(name: &str, commit: &str) -> Result<(), ValidationError> {
+    let invalid = |reason: &str| ValidationError::InvalidCommit {
+        name: name.to_string(),
+        commit: commit.to_string(),
+        reason: reason.to_string(),
+    };
+    if commit.is_empty() {
+        return Err(invalid("the commit is empty"));
+    }
+    if commit.chars().any(|c| c.is_whitespace() || c.is_control()) {
+        return Err(invalid(
+            "the commit contains whitespace or control characters",
+        ));
+    }
+    if commit.starts_with('-') {
+        return Err(invalid("the commit can't start with '-'"));
+    }
+    if commit.contains("..") {
+        return Err(invalid("expected a single commit, not a range"));
+    }
+    if commit.len() > 40 && commit.chars().all(|c| c.is_ascii_hexdigit()) {
+        return Err(invalid("a SHA has at most 40 hex digits"));
+    }
+    Ok(())
+}
+
+/// Check the API key sent to the default OpenAI endpoint
+///
+/// Keys for a custom `base_url` aren't checked, since local servers often take any value.
+pub fn validate_api_key(api_key: &str, base_url: Option<&str>) -> Result<(), ValidationError> {
+    if base_url.is_some() {
+        return Ok(());
+    }
+    if api_key.trim().is_empty() {
+        return Err(ValidationError::MissingApiKey);
+    }
+    if api_key.chars().any(|c| c.is_whitespace() || c.is_control()) {
+        return Err(ValidationError::InvalidApiKey {
+            reason: "the key contains whitespace or control characters".to_string(),
+        });
+    }
+    Ok(())
+}
+
+impl VerificationRequest {
+    /// Check the repositories, commits and intent before anything is cloned
+    pub fn validate(&self) -> Result<(), ValidationError> {
+        validate_intent(&self.user_intent)?;
+        validate_repo_url(&self.solution_repo_url)?;
+        validate_commit("commit1", &self.solution_commit1)?;
+        validate_commit("commit2", &self.solution_commit2)?;
+        if self.test_repo_url != self.solution_repo_url {
+            validate_repo_url(&self.test_repo_url)?;
+        }
+        validate_commit("test commit", &self.test_commit)
+    }
+}
+
+/// Check a verification's inputs, skipping the API key when no real model is called
+#[cfg(feature = "git")]
+pub(crate) fn validate_inputs(
+    request: &VerificationRequest,
+    api_key: &str,
+    base_url: Option<&str>,
+    options: &AnalysisOptions,
+) -> Result<(), ValidationError> {
+    request.validate()?;
+    if options.calls_model() {
+        validate_api_key(api_key, base_url)?;
+    }
+    check_repository(options, &request.solution_repo_url)?;
+    check_repository(options, &request.test_repo_url)?;
+    check_llm_endpoint(options, base_url)
+}
+
+/// Turn a revision that isn't in the repository into [`ValidationError::CommitNotFound`],
+/// keeping every other git error as is
+#[cfg(feature = "git")]
+pub(crate) fn commit_not_found(
+    error: git2::Error,
+    name: &str,
+    commit: &str,
+    repo_url: &str,
+) -> Box<dyn std::error::Error> {
+    if error.code() == git2::ErrorCode::NotFound {
+        Box::new(ValidationError::CommitNotFound {
+            name: name.to_string(),
+            commit: commit.to_string(),
+            repo_url: repo_url.to_string(),
+        })
+    } else {
+        Box::new(error)
+    }
+}
@@ -0,0 +1,191 @@
+use std::error::Error;
+#[cfg(feature = "git")]
+use std::path::{Path, PathBuf};
+
+#[cfg(feature = "git")]
+use git2::Repository;
+
+use crate::binary::is_unreadable;
+use crate::code_parser::is_source_file_by_name;
+use crate::git::FileChange;
+#[cfg(feature = "git")]
+use crate::git::{
+    blob_text, clone_repository, git_options, load_file_change, resolve_revision, tree_changes,
+};
+#[cfg(feature = "git")]
+use crate::options::AnalysisOptions;
+use crate::snapshot::RepoSnapshot;
+use crate::test_mapping::map_tests_to_sources;
+use crate::types::{FileContent, TestTargets, TestTargetsWithCode};
+#[cfg(feature = "git")]
+use crate::validation::commit_not_found;
+
+/// A version control system the changes and the test targets are read from
+///
+/// Implement it to verify repositories in Mercurial, Perforce or anything else with
+/// [`verify_intent_with_vcs`](crate::verify_intent_with_vcs); the analysis pipeline only sees
+/// [`FileChange`]s and file contents. Revisions are whatever the backend understands, e.g.
+/// commit hashes, changelist numbers or directory names. [`GitVersionControl`] is the git
+/// implementation.
+///
+/// Calls block, so backends that talk to a server should keep them short.
+pub trait VersionControl: Send + Sync {
+    /// The files added, modified or deleted from `from` to `to`, with their contents
+    ///
+    /// Binary content is replaced by a placeholder, see `binary_placeholder`.
+    fn list_changes(&self, from: &str, to: &str) -> Result<Vec<FileChange>, Box<dyn Error>>;
+
+    /// Content of `path` at `rev`, `None` when the file doesn't exist there
+    fn read_file_at(&self, rev: &str, path: &str) -> Result<Option<String>, Box<dyn Error>>;
+
+    /// The canonical identifier of `rev`, e.g. the full commit hash of a branch name
+    fn resolve_rev(&self, rev: &str) -> Result<String, Box<dyn Error>>;
+
+    /// Paths of every file at `rev`, so functions can be searched for by name
+    ///
+    /// Backends that can't list files leave this empty; only file targets are read then.
+    fn list_files_at(&self, _rev: &str) -> Result<Vec<String>, Box<dyn Error>> {
+        Ok(vec![])
+    }
+}
+
+/// Read the code of the test targets at `rev` of any version control system
+///
+/// Counterpart of `read_test_targets_code`: functions are searched for in the source files
+/// `list_files_at` returns, and the sources the test targets import are mapped too.
+pub fn read_test_targets_code_from(
+    vcs: &dyn VersionControl,
+    targets: &TestTargets,
+    rev: &str,
+) -> Result<TestTargetsWithCode, Box<dyn Error>> {
+    let rev = vcs.resolve_rev(rev)?;
+
+    let mut sources = Vec::new();
+    for path in vcs.list_files_at(&rev)? {
+        if is_source_file_by_name(&path)
+            && let Some(content) = vcs.read_file_at(&rev, &path)?
+            && !is_unreadable(&content)
+        {
+            sources.push((path, content));
+        }
+    }
+    let snapshot = RepoSnapshot::from_files(sources);
+    let mut targets_with_code = snapshot.read_test_targets_code(targets);
+
+    targets_with_code.file_contents = targets
+        .files
+        .iter()
+        .map(|path| {
+            let (content, error) = match vcs.read_file_at(&rev, path)? {
+                Some(content) if is_unreadable(&content) => {
+                    (String::new(), Some("Binary file".to_string()))
+                }
+                Some(content) => (content, None),
+                None => (
+                    String::new(),
+                    Some(format!("File not found in revision {}", rev)),
+                ),
+            };
+            Ok(FileContent {
+                path: path.clone(),
+                content,
+                error,
+            })
+        })
+        .collect::<Result<_, Box<dyn Error>>>()?;
+    targets_with_code.tested_sources = map_tests_to_sources(&targets_with_code, |path| {
+        vcs.read_file_at(&rev, path).ok().flatten()
+    });
+    Ok(targets_with_code)
+}
+
+/// [`VersionControl`] over a git repository, cloned once and removed when dropped
+///
+/// Revisions are anything `git rev-parse` accepts; missing ones are fetched from `origin`.
+#[cfg(feature = "git")]
+#[derive(Debug)]
+pub struct GitVersionControl {
+    repo_url: String,
+    dir: PathBuf,
+    options: AnalysisOptions,
+}
+
+#[cfg(feature = "git")]
+impl GitVersionControl {
+    /// Clone `repo_url` into `options.cache_dir` (or the temp directory) through
+    /// `options.proxy`
+    pub fn open(repo_url: &str, options: &AnalysisOptions) -> Result<Self, Box<dyn Error>> {
+        let options = git_options(options);
+        let (_, dir) = clone_repository(repo_url, "git_vcs", &options)?;
+        Ok(GitVersionControl {
+            repo_url: repo_url.to_string(),
+            dir,
+            options,
+        })
+    }
+
+    fn commit<'r>(
+        &self,
+        repo: &'r Repository,
+        rev: &str,
+    ) -> Result<git2::Commit<'r>, Box<dyn Error>> {
+        Ok(resolve_revision(repo, rev, &self.options)
+            .map_err(|e| commit_not_found(e, "revision", rev, &self.repo_url))?
+            .peel_to_commit()?)
+    }
+}
+
+#[cfg(feature = "git")]
+impl VersionControl for GitVersionControl {
+    fn list_changes(&self, from: &str, to: &str) -> Result<Vec<FileChange>, Box<dyn Error>> {
+        let repo = Repository::open(&self.dir)?;
+        let tree1 = self.commit(&repo, from)?.tree()?;
+        let tree2 = self.commit(&repo, to)?.tree()?;
+        Ok(tree_changes(&repo, &tree1, &tree2)?
+            .iter()
+            .map(|change| load_file_change(&repo, change))
+            .collect())
+    }
+
+    fn read_file_at(&self, rev: &str, path: &str) -> Result<Option<String>, Box<dyn Error>> {
+        let repo = Repository::open(&self.dir)?;
+        let tree = self.commit(&repo, rev)?.tree()?;
+        let entry = match tree.get_path(Path::new(path)) {
+            Ok(entry) => entry,
+            Err(e) if e.code() == git2::ErrorCode::NotFound => return Ok(None),
+            Err(e) => return Err(e.into()),
+        };
+        match entry.to_object(&repo)?.into_blob() {
+            Ok(blob) => Ok(Some(blob_text(path, &blob))),
+            // A directory isn't a file
+            Err(_) => Ok(None),
+        }
+    }
+
+    fn resolve_rev(&self, rev: &str) -> Result<String, Box<dyn Error>> {
+        let repo = Repository::open(&self.dir)?;
+        Ok(self.commit(&repo, rev)?.id().to_string())
+    }
+
+    fn list_files_at(&self, rev: &str) -> Result<Vec<String>, Box<dyn Error>> {
+        let repo = Repository::open(&self.dir)?;
+        let tree = self.commit(&repo, rev)?.tree()?;
+        let mut paths = Vec::new();
+        tree.walk(git2::TreeWalkMode::PreOrder, |dir, entry| {
+            if entry.kind() == Some(git2::ObjectType::Blob)
+                && let Some(name) = entry.name()
+            {
+                paths.push(format!("{}{}", dir, name));
+            }
+            git2::TreeWalkResult::Ok
+        })?;
+        Ok(paths)
+    }
+}
+
+#[cfg(feature = "git")]
+impl Drop for GitVersionControl {
+    fn drop(&mut self) {
+        std::fs::remove_dir_all(&self.dir).ok();
+    }
+}
@@ -0,0 +1,251 @@
+#[cfg(feature = "git")]
+use std::sync::Arc;
+
+#[cfg(feature = "git")]
+use crate::observer::{AnalysisObserver, ObserverHandle};
+#[cfg(feature = "git")]
+use crate::openai::verify_intent_with_options;
+#[cfg(feature = "git")]
+use crate::options::AnalysisOptions;
+#[cfg(feature = "git")]
+use crate::progress::{CancellationToken, Progress, ProgressHandler};
+#[cfg(feature = "git")]
+use crate::types::IntentVerificationResult;
+
+/// What to verify: the tests, the changes and the intent
+#[derive(Debug, Clone, PartialEq, serde::Serialize, serde::Deserialize)]
+pub struct VerificationRequest {
+    /// Repository URL or local path containing the tests
+    pub test_repo_url: String,
+    /// Commit to read the tests from
+    pub test_commit: String,
+    /// Repository URL or local path containing the changes
+    pub solution_repo_url: String,
+    /// Commit before the changes
+    pub solution_commit1: String,
+    /// Commit after the changes
+    pub solution_commit2: String,
+    /// What the tests are expected to prove
+    pub user_intent: String,
+}
+
+impl VerificationRequest {
+    /// Request verifying the changes from `base` to `head`, with the tests read at `head`
+    pub fn new(repo_url: &str, base: &str, head: &str, user_intent: &str) -> Self {
+        VerificationRequest {
+            test_repo_url: repo_url.to_string(),
+            test_commit: head.to_string(),
+            solution_repo_url: repo_url.to_string(),
+            solution_commit1: base.to_string(),
+            solution_commit2: head.to_string(),
+            user_intent: user_intent.to_string(),
+        }
+    }
+
+    /// Read the tests from another repository or commit
+    pub fn with_tests(mut self, test_repo_url: &str, test_commit: &str) -> Self {
+        self.test_repo_url = test_repo_url.to_string();
+        self.test_commit = test_commit.to_string();
+        self
+    }
+}
+
+/// Verifies intents with one model provider and set of options
+///
+/// ```no_run
+/// # async fn example() -> Result<(), Box<dyn std::error::Error>> {
+/// use intent_verification::IntentVerifier;
+///
+/// let result = IntentVerifier::builder()
+///     .repo("https://github.com/org/repo")
+///     .commits("abc123", "def456")
+///     .intent("calculate_sum() adds two numbers")
+///     .api_key("sk-...")
+///     .model("gpt-4o")
+///     .build()?
+///     .verify()
+///     .await?;
+/// # Ok(())
+/// # }
+/// ```
+#[cfg(feature = "git")]
+#[derive(Debug, Clone)]
+pub struct IntentVerifier {
+    request: VerificationRequest,
+    api_key: String,
+    model: Option<String>,
+    base_url: Option<String>,
+    options: AnalysisOptions,
+}
+
+#[cfg(feature = "git")]
+impl IntentVerifier {
+    pub fn builder() -> IntentVerifierBuilder {
+        IntentVerifierBuilder::default()
+    }
+
+    /// Verify the request the verifier was built with
+    pub async fn verify(&self) -> Result<IntentVerificationResult, Box<dyn std::error::Error>> {
+        self.verify_request(&self.request).await
+    }
+
+    /// Verify another request with the same provider and options
+    pub async fn verify_request(
+        &self,
+        request: &VerificationRequest,
+    ) -> Result<IntentVerificationResult, Box<dyn std::error::Error>> {
+        verify_intent_with_options(
+            &request.test_repo_url,
+            &request.test_commit,
+            &request.solution_repo_url,
+            &request.solution_commit1,
+            &request.solution_commit2,
+            &request.user_intent,
+            &self.api_key,
+            self.model.as_deref(),
+            self.base_url.as_deref(),
+            &self.options,
+        )
+        .await
+    }
+
+    pub fn request(&self) -> &VerificationRequest {
+        &self.request
+    }
+
+    pub fn options(&self) -> &AnalysisOptions {
+        &self.options
+    }
+}
+
+/// Builder of an [`IntentVerifier`], see [`IntentVerifier::builder`]
+///
+/// The tests are read from the changed repository at the head commit unless
+/// [`test_repo`](Self::test_repo) or [`test_commit`](Self::test_commit) say otherwise.
+#[cfg(feature = "git")]
+#[derive(Debug, Clone, Default)]
+pub struct IntentVerifierBuilder {
+    repo_url: Option<String>,
+    test_repo_url: Option<String>,
+    test_commit: Option<String>,
+    commits: Option<(String, String)>,
+    intent: Option<String>,
+    api_key: String,
+    model: Option<String>,
+    base_url: Option<String>,
+    options: AnalysisOptions,
+}
+
+#[cfg(feature = "git")]
+impl IntentVerifierBuilder {
+    /// Repository URL or local path containing the changes
+    pub fn repo(mut self, repo_url: &str) -> Self {
+        self.repo_url = Some(repo_url.to_string());
+        self
+    }
+
+    /// Commits before and after the changes
+    pub fn commits(mut self, base: &str, head: &str) -> Self {
+        self.commits = Some((base.to_string(), head.to_string()));
+        self
+    }
+
+    /// Repository containing the tests, when it isn't the changed one
+    pub fn test_repo(mut self, test_repo_url: &str) -> Self {
+        self.test_repo_url = Some(test_repo_url.to_string());
+        self
+    }
+
+    /// Commit to read the tests from, when it isn't the head commit
+    pub fn test_commit(mut self, test_commit: &str) -> Self {
+        self.test_commit = Some(test_commit.to_string());
+        self
+    }
+
+    /// What the tests are expected to prove
+    pub fn intent(mut self, user_intent: &str) -> Self {
+        self.intent = Some(user_intent.to_string());
+        self
+    }
+
+    /// Take the repositories, commits and intent from a request
+    pub fn request(self, request: VerificationRequest) -> Self {
+        self.repo(&request.solution_repo_url)
+            .commits(&request.solution_commit1, &request.solution_commit2)
+            .test_repo(&request.test_repo_url)
+            .test_commit(&request.test_commit)
+            .intent(&request.user_intent)
+    }
+
+    pub fn api_key(mut self, api_key: &str) -> Self {
+        self.api_key = api_key.to_string();
+        self
+    }
+
+    /// Model to use (`DEFAULT_MODEL` when not set)
+    pub fn model(mut self, model: &str) -> Self {
+        self.model = Some(model.to_string());
+        self
+    }
+
+    /// Base URL of an OpenAI-compatible API
+    pub fn base_url(mut self, base_url: &str) -> Self {
+        self.base_url = Some(base_url.to_string());
+        self
+    }
+
+    /// Options of the verification; hooks set before are kept
+    pub fn options(mut self, options: AnalysisOptions) -> Self {
+        self.options = AnalysisOptions {
+            progress: options.progress.or(self.options.progress),
+            cancellation: options.cancellation.or(self.options.cancellation),
+            observer: options.observer.or(self.options.observer),
+            ..options
+        };
+        self
+    }
+
+    /// Call `handler` with progress updates
+    pub fn on_progress(mut self, handler: impl Fn(&Progress) + Send + Sync + 'static) -> Self {
+        self.options.progress = Some(ProgressHandler::new(handler));
+        self
+    }
+
+    /// Notify `observer` of each step of the pipeline
+    pub fn observer(mut self, observer: Arc<impl AnalysisObserver + 'static>) -> Self {
+        self.options.observer = Some(ObserverHandle::new(observer));
+        self
+    }
+
+    /// Stop the verification early once `token` is cancelled
+    pub fn cancellation(mut self, token: CancellationToken) -> Self {
+        self.options.cancellation = Some(token);
+        self
+    }
+
+    /// Fails when the changed repository, the commits or the intent are missing
+    pub fn build(self) -> Result<IntentVerifier, Box<dyn std::error::Error>> {
+        let repo_url = self
+            .repo_url
+            .ok_or("The verifier needs the repository with the changes")?;
+        let (base, head) = self
+            .commits
+            .ok_or("The verifier needs the commits before and after the changes")?;
+        let intent = self.intent.ok_or("The verifier needs an intent")?;
+
+        Ok(IntentVerifier {
+            request: VerificationRequest {
+                test_repo_url: self.test_repo_url.unwrap_or_else(|| repo_url.clone()),
+                test_commit: self.test_commit.unwrap_or_else(|| head.clone()),
+                solution_repo_url: repo_url,
+                solution_commit1: base,
+                solution_commit2: head,
+                user_intent: intent,
+            },
+            api_key: self.api_key,
+            model: self.model,
+            base_url: self.base_url,
+            options: self.options,
+        })
+    }
+}
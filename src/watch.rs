@@ -0,0 +1,91 @@
+use std::collections::hash_map::DefaultHasher;
+use std::hash::{Hash, Hasher};
+use std::path::{Path, PathBuf};
+use std::time::{Duration, UNIX_EPOCH};
+
+use git2::{Repository, StatusOptions};
+
+/// How often the working tree is checked for changes
+const POLL_INTERVAL: Duration = Duration::from_millis(300);
+
+/// Fingerprint of a local repository's HEAD and uncommitted changes
+///
+/// Changes whenever HEAD moves or a modified or untracked (non-ignored) file is edited,
+/// added or removed.
+pub fn working_tree_fingerprint(repo_path: impl AsRef<Path>) -> Result<u64, git2::Error> {
+    let repo = Repository::open(repo_path)?;
+    let workdir = repo.workdir().map(Path::to_path_buf).unwrap_or_default();
+
+    let mut hasher = DefaultHasher::new();
+    repo.head()
+        .ok()
+        .and_then(|head| head.target())
+        .hash(&mut hasher);
+
+    let mut status_options = StatusOptions::new();
+    status_options
+        .include_untracked(true)
+        .recurse_untracked_dirs(true);
+    let statuses = repo.statuses(Some(&mut status_options))?;
+    // Statuses come sorted by path, so the same state always hashes the same
+    for entry in statuses.iter() {
+        entry.path_bytes().hash(&mut hasher);
+        entry.status().bits().hash(&mut hasher);
+        // Files can change again without changing status, so include their mtime and size
+        if let Ok(metadata) = std::fs::metadata(workdir.join(entry.path().unwrap_or_default())) {
+            metadata
+                .modified()
+                .ok()
+                .and_then(|time| time.duration_since(UNIX_EPOCH).ok())
+                .hash(&mut hasher);
+            metadata.len().hash(&mut hasher);
+        }
+    }
+
+    Ok(hasher.finish())
+}
+
+/// Polls a local repository's working tree and reports debounced changes
+pub struct WorkingTreeWatcher {
+    path: PathBuf,
+    debounce: Duration,
+    fingerprint: u64,
+}
+
+impl WorkingTreeWatcher {
+    /// Start watching from the current state of the working tree
+    ///
+    /// A change is reported once the working tree has stayed the same for `debounce`, so a
+    /// burst of saves triggers a single re-run.
+    pub fn new(path: impl Into<PathBuf>, debounce: Duration) -> Result<Self, git2::Error> {
+        let path = path.into();
+        let fingerprint = working_tree_fingerprint(&path)?;
+        Ok(WorkingTreeWatcher {
+            path,
+            debounce,
+            fingerprint,
+        })
+    }
+
+    /// Wait until the working tree changes and then settles
+    pub async fn wait_for_change(&mut self) -> Result<(), git2::Error> {
+        loop {
+            tokio::time::sleep(POLL_INTERVAL).await;
+            let mut current = working_tree_fingerprint(&self.path)?;
+            if current == self.fingerprint {
+                continue;
+            }
+
+            loop {
+                tokio::time::sleep(self.debounce).await;
+                let next = working_tree_fingerprint(&self.path)?;
+                if next == current {
+                    break;
+                }
+                current = next;
+            }
+            self.fingerprint = current;
+            return Ok(());
+        }
+    }
+}
@@ -0,0 +1,276 @@
+use std::error::Error;
+
+use sha2::{Digest, Sha256};
+
+use crate::github::{PullRequestContext, parse_issue_reference};
+
+/// Verification requested by a webhook delivery
+#[derive(Debug, Clone, PartialEq, serde::Serialize, serde::Deserialize)]
+pub struct WebhookTrigger {
+    /// Where the event came from, e.g. `github:acme/calc#42` or `gitlab:acme/calc@main`
+    pub source: String,
+    pub repo_url: String,
+    pub base: String,
+    pub head: String,
+    pub intent: String,
+    /// Issues the pull request closes, as `owner/name#number`; their intent replaces `intent`
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub linked_issues: Vec<String>,
+}
+
+/// Pull request actions that change what needs to be verified
+const GITHUB_PR_ACTIONS: &[&str] = &["opened", "synchronize", "reopened", "edited"];
+
+/// Turn a GitHub delivery into a verification, or `None` for events that need none
+///
+/// `event` is the `X-GitHub-Event` header. Pull requests use their title and description as
+/// the intent, along with the issues their description closes (see [`linked_issues`]); pushes
+/// use the head commit message.
+pub fn github_trigger(
+    event: &str,
+    payload: &str,
+) -> Result<Option<WebhookTrigger>, Box<dyn Error>> {
+    match event {
+        "pull_request" => {
+            let action = serde_json::from_str::<serde_json::Value>(payload)?["action"]
+                .as_str()
+                .unwrap_or_default()
+                .to_string();
+            if !GITHUB_PR_ACTIONS.contains(&action.as_str()) {
+                return Ok(None);
+            }
+            let pr = PullRequestContext::from_event_json(payload)?;
+            Ok(Some(WebhookTrigger {
+                source: format!("github:{}#{}", pr.repository, pr.number),
+                intent: pr.intent(),
+                linked_issues: linked_issues(
+                    &pr.repository,
+                    pr.body.as_deref().unwrap_or_default(),
+                ),
+                repo_url: pr.clone_url,
+                base: pr.base_sha,
+                head: pr.head_sha,
+            }))
+        }
+        "push" => {
+            let push: GithubPush = serde_json::from_str(payload)?;
+            Ok(push_trigger(
+                format!("github:{}@{}", push.repository.full_name, push.after),
+                push.repository.clone_url,
+                push.before,
+                push.after,
+                push.head_commit.map(|commit| commit.message),
+            ))
+        }
+        _ => Ok(None),
+    }
+}
+
+/// Turn a GitLab delivery into a verification, or `None` for events that need none
+///
+/// `event` is the `X-Gitlab-Event` header. Merge requests are compared between the commits of
+/// their `diff_refs`, in the target project, and use their title and description as the intent.
+pub fn gitlab_trigger(
+    event: &str,
+    payload: &str,
+) -> Result<Option<WebhookTrigger>, Box<dyn Error>> {
+    match event {
+        "Merge Request Hook" => {
+            let hook: GitlabMergeRequestHook = serde_json::from_str(payload)?;
+            let mr = hook.object_attributes;
+            if matches!(mr.action.as_deref(), Some("close" | "merge" | "approved")) {
+                return Ok(None);
+            }
+            let intent = match mr.description.as_deref().map(str::trim) {
+                Some(description) if !description.is_empty() => {
+                    format!("{}\n\n{}", mr.title, description)
+                }
+                _ => mr.title.clone(),
+            };
+            // Branch names can move before the job runs, and the target branch may be missing
+            // or stale in a fork, so the diff is pinned to commits of the target project, which
+            // also serves the head of merge requests from forks
+            let diff_refs = mr.diff_refs.ok_or_else(|| {
+                format!(
+                    "Merge request {}!{} has no diff_refs",
+                    hook.project.path_with_namespace, mr.iid
+                )
+            })?;
+            Ok(Some(WebhookTrigger {
+                source: format!("gitlab:{}!{}", hook.project.path_with_namespace, mr.iid),
+                repo_url: hook.project.git_http_url,
+                base: diff_refs.base_sha,
+                head: diff_refs.head_sha,
+                intent,
+                linked_issues: Vec::new(),
+            }))
+        }
+        "Push Hook" => {
+            let push: GitlabPush = serde_json::from_str(payload)?;
+            let message = push.commits.last().map(|commit| commit.message.clone());
+            Ok(push_trigger(
+                format!("gitlab:{}@{}", push.project.path_with_namespace, push.after),
+                push.project.git_http_url,
+                push.before,
+                push.after,
+                message,
+            ))
+        }
+        _ => Ok(None),
+    }
+}
+
+/// Verification for a push, skipping branch creation and deletion
+fn push_trigger(
+    source: String,
+    repo_url: String,
+    before: String,
+    after: String,
+    message: Option<String>,
+) -> Option<WebhookTrigger> {
+    let is_null = |sha: &str| sha.chars().all(|c| c == '0');
+    if is_null(&before) || is_null(&after) {
+        return None;
+    }
+    Some(WebhookTrigger {
+        source,
+        repo_url,
+        base: before,
+        head: after,
+        intent: message?.trim().to_string(),
+        linked_issues: Vec::new(),
+    })
+}
+
+/// Keywords GitHub recognizes for closing an issue from a pull request description
+const CLOSING_KEYWORDS: &[&str] = &[
+    "close", "closes", "closed", "fix", "fixes", "fixed", "resolve", "resolves", "resolved",
+];
+
+/// Issues closed by `Fixes #12`-style references in `text`, as `owner/name#number`
+///
+/// `#12` refers to an issue of `repository`; `owner/name#12` may name another repository.
+pub fn linked_issues(repository: &str, text: &str) -> Vec<String> {
+    let words: Vec<&str> = text.split_whitespace().collect();
+    let mut issues = Vec::new();
+    for pair in words.windows(2) {
+        let keyword = pair[0].trim_end_matches(':').to_lowercase();
+        if !CLOSING_KEYWORDS.contains(&keyword.as_str()) {
+            continue;
+        }
+        let reference = pair[1].trim_end_matches(|c: char| !c.is_ascii_digit());
+        let reference = match reference.strip_prefix('#') {
+            Some(number) => format!("{}#{}", repository, number),
+            None => reference.to_string(),
+        };
+        if parse_issue_reference(&reference).is_ok() && !issues.contains(&reference) {
+            issues.push(reference);
+        }
+    }
+    issues
+}
+
+/// Check a GitHub `X-Hub-Signature-256` header against the shared secret
+pub fn verify_github_signature(secret: &str, payload: &[u8], signature: &str) -> bool {
+    let Some(expected) = signature.strip_prefix("sha256=") else {
+        return false;
+    };
+    let actual: String = hmac_sha256(secret.as_bytes(), payload)
+        .iter()
+        .map(|b| format!("{:02x}", b))
+        .collect();
+    constant_time_eq(actual.as_bytes(), expected.to_ascii_lowercase().as_bytes())
+}
+
+/// Check a GitLab `X-Gitlab-Token` header against the shared secret
+pub fn verify_gitlab_token(secret: &str, token: &str) -> bool {
+    constant_time_eq(secret.as_bytes(), token.as_bytes())
+}
+
+fn hmac_sha256(key: &[u8], message: &[u8]) -> [u8; 32] {
+    const BLOCK_SIZE: usize = 64;
+
+    let mut block = [0u8; BLOCK_SIZE];
+    if key.len() > BLOCK_SIZE {
+        block[..32].copy_from_slice(&Sha256::digest(key));
+    } else {
+        block[..key.len()].copy_from_slice(key);
+    }
+
+    let pad = |byte: u8| block.map(|b| b ^ byte);
+    let inner = Sha256::new()
+        .chain_update(pad(0x36))
+        .chain_update(message)
+        .finalize();
+    Sha256::new()
+        .chain_update(pad(0x5c))
+        .chain_update(inner)
+        .finalize()
+        .into()
+}
+
+pub(crate) fn constant_time_eq(a: &[u8], b: &[u8]) -> bool {
+    a.len() == b.len() && a.iter().zip(b).fold(0u8, |acc, (x, y)| acc | (x ^ y)) == 0
+}
+
+#[derive(serde::Deserialize)]
+struct GithubPush {
+    before: String,
+    after: String,
+    head_commit: Option<GithubCommit>,
+    repository: GithubRepository,
+}
+
+#[derive(serde::Deserialize)]
+struct GithubCommit {
+    message: String,
+}
+
+#[derive(serde::Deserialize)]
+struct GithubRepository {
+    full_name: String,
+    clone_url: String,
+}
+
+#[derive(serde::Deserialize)]
+struct GitlabMergeRequestHook {
+    project: GitlabProject,
+    object_attributes: GitlabMergeRequest,
+}
+
+#[derive(serde::Deserialize)]
+struct GitlabMergeRequest {
+    iid: u64,
+    title: String,
+    description: Option<String>,
+    action: Option<String>,
+    /// Missing or null until GitLab has computed the merge request's diff
+    #[serde(default)]
+    diff_refs: Option<GitlabDiffRefs>,
+}
+
+#[derive(serde::Deserialize)]
+struct GitlabDiffRefs {
+    base_sha: String,
+    head_sha: String,
+}
+
+#[derive(serde::Deserialize)]
+struct GitlabPush {
+    before: String,
+    after: String,
+    commits: Vec<GitlabCommit>,
+    project: GitlabProject,
+}
+
+#[derive(serde::Deserialize)]
+struct GitlabCommit {
+    #[serde(default)]
+    message: String,
+}
+
+#[derive(serde::Deserialize)]
+struct GitlabProject {
+    path_with_namespace: String,
+    git_http_url: String,
+}
@@ -0,0 +1,84 @@
+use intent_verification::{
+    AnalysisOptions, DEFAULT_MAX_AMBIGUITY, LlmClient, MockProvider, RepoSnapshot,
+    intent_ambiguity, render_markdown, verify_intent_with_snapshots,
+};
+
+#[test]
+fn test_intent_ambiguity() {
+    let vague = intent_ambiguity("make it work");
+    println!("\n❓ Ambiguity: {:#?}", vague);
+    assert_eq!(vague.score, 1.0);
+    assert!(vague.reasons.iter().any(|r| r.contains("\"make it work\"")));
+    assert_eq!(vague.questions.len(), vague.reasons.len());
+
+    let specific = intent_ambiguity(
+        "The `sum` function in src/lib.rs returns the sum of two numbers instead of panicking",
+    );
+    assert_eq!(specific.score, 0.0);
+    assert!(specific.questions.is_empty());
+
+    assert!(intent_ambiguity("The sum function adds two numbers").score < DEFAULT_MAX_AMBIGUITY);
+    assert!(intent_ambiguity("clean up stuff").score > DEFAULT_MAX_AMBIGUITY);
+}
+
+async fn verify(
+    intent: &str,
+    clarifications: Vec<String>,
+) -> (intent_verification::IntentVerificationResult, MockProvider) {
+    let base = RepoSnapshot::from_files([("src/lib.rs", "pub fn sum() {}\n")]);
+    let head = RepoSnapshot::from_files([("src/lib.rs", "pub fn sum() -> i32 {\n    2\n}\n")]);
+    let mock = MockProvider::new()
+        .respond_when(
+            "Extract from the following prompt",
+            r#"{"functions": ["sum"], "files": []}"#,
+        )
+        .respond_when(
+            "STEP 2",
+            r#"{"supports_intent": true, "reasoning": "returns 2", "relevant_changes": []}"#,
+        )
+        .respond("Assessment");
+    let options = AnalysisOptions {
+        llm_client: Some(LlmClient::mock(mock.clone())),
+        max_intent_ambiguity: Some(DEFAULT_MAX_AMBIGUITY),
+        clarifications,
+        ..Default::default()
+    };
+    let result =
+        verify_intent_with_snapshots(&head, &base, &head, intent, "", None, None, &options)
+            .await
+            .unwrap();
+    (result, mock)
+}
+
+#[tokio::test(flavor = "current_thread")]
+async fn test_ambiguous_intent_needs_clarification() {
+    let (result, mock) = verify("make it work", vec![]).await;
+
+    let ambiguity = result
+        .needs_clarification
+        .as_ref()
+        .expect("needs clarification");
+    assert!(ambiguity.score > DEFAULT_MAX_AMBIGUITY);
+    assert!(!result.is_intent_fulfilled);
+    assert!(result.files_analyzed.is_empty());
+    assert!(mock.calls().is_empty(), "Nothing is sent to the model");
+
+    let md = render_markdown(&result);
+    println!("\n{}", md);
+    assert!(md.starts_with("## ❓ Intent needs clarification"));
+    assert!(md.contains("### Clarifying questions\n\n1. "));
+    assert!(!md.contains("Intent not fulfilled"));
+}
+
+#[tokio::test(flavor = "current_thread")]
+async fn test_clarified_intent_is_verified() {
+    let (result, mock) = verify(
+        "make it work",
+        vec!["`sum` should return 2 instead of nothing".to_string()],
+    )
+    .await;
+
+    assert!(result.needs_clarification.is_none());
+    assert!(result.is_intent_fulfilled);
+    assert!(!mock.calls().is_empty());
+}
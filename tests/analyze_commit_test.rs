@@ -0,0 +1,132 @@
+#![cfg(feature = "git")]
+
+mod common;
+
+use intent_verification::{
+    AnalysisOptions, LlmClient, MockProvider, ValidationError, analyze_commit,
+};
+
+/// Create a local repository with a stub, an implementation and an unrelated commit
+fn init_local_repo() -> (String, Vec<String>) {
+    let path = common::unique_path("analyze_commit_test_repo");
+    let commits = [
+        ("src/lib.rs", common::SUM_STUB),
+        ("src/lib.rs", common::SUM_IMPL),
+        ("README.md", "# Calculator\n"),
+    ]
+    .into_iter()
+    .map(|(file, content)| common::commit_files(&path, "c", &[(file, content)]))
+    .collect();
+    (path, commits)
+}
+
+fn mock() -> MockProvider {
+    MockProvider::new()
+        .respond_when(
+            "Extract from the following prompt",
+            r#"{"functions": ["sum"], "files": ["src/lib.rs"]}"#,
+        )
+        .respond_when(
+            "STEP 2",
+            r#"{"supports_intent": true, "reasoning": "ok", "relevant_changes": []}"#,
+        )
+        .respond("Assessment")
+}
+
+#[tokio::test(flavor = "current_thread")]
+async fn test_commit_is_diffed_against_its_parent() {
+    let (path, commits) = init_local_repo();
+    let mock = mock();
+    let options = AnalysisOptions {
+        llm_client: Some(LlmClient::mock(mock.clone())),
+        ..Default::default()
+    };
+
+    let result = analyze_commit(
+        &path,
+        &commits[1],
+        "The sum function adds two numbers",
+        "",
+        None,
+        None,
+        &options,
+    )
+    .await
+    .unwrap();
+    let files: Vec<&str> = result
+        .files_analyzed
+        .iter()
+        .map(|f| f.file_path.as_str())
+        .collect();
+    assert_eq!(files, vec!["src/lib.rs"], "Only the commit's own changes");
+    assert!(result.is_intent_fulfilled);
+
+    // Revisions resolve to the commit they name
+    let result = analyze_commit(
+        &path,
+        "HEAD",
+        "The sum function adds two numbers",
+        "",
+        None,
+        None,
+        &options,
+    )
+    .await
+    .unwrap();
+    let files: Vec<&str> = result
+        .files_analyzed
+        .iter()
+        .map(|f| f.file_path.as_str())
+        .collect();
+    assert_eq!(files, vec!["README.md"]);
+
+    println!("\n✅ {} model calls", mock.call_count());
+    std::fs::remove_dir_all(&path).ok();
+}
+
+#[tokio::test(flavor = "current_thread")]
+async fn test_root_and_unknown_commits_fail() {
+    let (path, commits) = init_local_repo();
+    let mock = mock();
+    let options = AnalysisOptions {
+        llm_client: Some(LlmClient::mock(mock.clone())),
+        ..Default::default()
+    };
+
+    let error = analyze_commit(
+        &path,
+        &commits[0],
+        "The sum function adds two numbers",
+        "",
+        None,
+        None,
+        &options,
+    )
+    .await
+    .unwrap_err();
+    assert!(error.to_string().contains("no parent"), "{}", error);
+
+    let error = analyze_commit(
+        &path,
+        "0123456789abcdef0123456789abcdef01234567",
+        "The sum function adds two numbers",
+        "",
+        None,
+        None,
+        &options,
+    )
+    .await
+    .unwrap_err();
+    assert!(
+        matches!(
+            error.downcast_ref::<ValidationError>(),
+            Some(ValidationError::CommitNotFound { .. })
+        ),
+        "{}",
+        error
+    );
+    assert_eq!(mock.call_count(), 0, "Nothing is sent");
+
+    println!("\n✅ {}", error);
+    std::fs::remove_dir_all(&path).ok();
+}
@@ -0,0 +1,126 @@
+use intent_verification::{AnalysisOptions, LlmClient, MockProvider, analyze_file};
+
+const SUM: &str = "pub fn sum(a: i32, b: i32) -> i32 {\n    a + b\n}\n";
+
+fn mock() -> MockProvider {
+    MockProvider::new()
+        .respond_when(
+            "Extract from the following prompt",
+            r#"{"functions": ["sum"], "files": []}"#,
+        )
+        .respond_when(
+            "STEP 2",
+            r#"{"supports_intent": true, "reasoning": "sum adds its arguments", "relevant_changes": ["a + b"]}"#,
+        )
+}
+
+fn options(mock: &MockProvider) -> AnalysisOptions {
+    AnalysisOptions {
+        llm_client: Some(LlmClient::mock(mock.clone())),
+        ..Default::default()
+    }
+}
+
+#[tokio::test(flavor = "current_thread")]
+async fn test_analyze_content() {
+    let mock = mock();
+    let result = analyze_file(
+        SUM,
+        Some("rust"),
+        "The sum function adds two numbers",
+        "",
+        None,
+        None,
+        &options(&mock),
+    )
+    .await
+    .unwrap();
+
+    assert_eq!(result.analysis.file_path, "snippet.rs");
+    assert!(result.analysis.supports_intent);
+    assert_eq!(result.analysis.reasoning, "sum adds its arguments");
+    assert!(result.warnings.is_empty(), "{:?}", result.warnings);
+
+    let calls = mock.calls();
+    assert_eq!(calls.len(), 2, "Target extraction, then the file analysis");
+    let prompt = calls[1].prompt();
+    assert!(prompt.contains("snippet.rs"));
+    assert!(
+        prompt.contains("a + b"),
+        "The whole file is analyzed as added"
+    );
+
+    println!("\n✅ {}", result.analysis.reasoning);
+}
+
+#[tokio::test(flavor = "current_thread")]
+async fn test_analyze_path() {
+    let path = format!(
+        "/tmp/analyze_file_test_{}_{}.rs",
+        std::process::id(),
+        std::time::SystemTime::now()
+            .duration_since(std::time::UNIX_EPOCH)
+            .unwrap()
+            .as_nanos()
+    );
+    std::fs::write(&path, SUM).unwrap();
+
+    let mock = mock();
+    let result = analyze_file(
+        &path,
+        None,
+        "The sum function adds two numbers",
+        "",
+        None,
+        None,
+        &options(&mock),
+    )
+    .await
+    .unwrap();
+    assert_eq!(result.analysis.file_path, path);
+    assert!(result.analysis.supports_intent);
+    assert!(
+        mock.calls()[1].prompt().contains("pub fn sum"),
+        "The file's contents are read"
+    );
+
+    std::fs::remove_file(&path).ok();
+    println!("\n✅ Analyzed {}", path);
+}
+
+#[tokio::test(flavor = "current_thread")]
+async fn test_unknown_language_and_dry_run() {
+    let mock = mock();
+    for language in [None, Some("cobol")] {
+        let error = analyze_file(
+            SUM,
+            language,
+            "The sum function adds two numbers",
+            "",
+            None,
+            None,
+            &options(&mock),
+        )
+        .await
+        .unwrap_err();
+        println!("✅ {}", error);
+    }
+    assert_eq!(mock.call_count(), 0, "Nothing is sent");
+
+    let result = analyze_file(
+        SUM,
+        Some("rust"),
+        "sum() adds two numbers",
+        "",
+        None,
+        None,
+        &AnalysisOptions {
+            dry_run: true,
+            ..Default::default()
+        },
+    )
+    .await
+    .unwrap();
+    assert_eq!(result.prompts.len(), 1, "The file analysis prompt");
+    assert_eq!(result.prompts[0].file_path.as_deref(), Some("snippet.rs"));
+}
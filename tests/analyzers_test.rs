@@ -0,0 +1,118 @@
+#[cfg(feature = "exec")]
+use intent_verification::{ChangeType, FileChange, run_static_analyzers};
+use intent_verification::{Severity, StaticAnalyzer};
+
+#[cfg(feature = "exec")]
+fn temp_dir(name: &str) -> std::path::PathBuf {
+    let dir = std::env::temp_dir().join(format!(
+        "analyzers_test_{}_{}_{}",
+        name,
+        std::process::id(),
+        std::time::SystemTime::now()
+            .duration_since(std::time::UNIX_EPOCH)
+            .unwrap()
+            .as_nanos()
+    ));
+    std::fs::create_dir_all(&dir).unwrap();
+    dir
+}
+
+#[cfg(feature = "exec")]
+fn changed(path: &str) -> FileChange {
+    FileChange {
+        path: path.to_string(),
+        status: ChangeType::Modified,
+        content: None,
+        old_content: None,
+    }
+}
+
+#[test]
+fn test_parse_analyzer_output() {
+    let dir = std::path::Path::new("/tmp/checkout");
+
+    let clippy = r#"{"reason":"compiler-artifact","target":{"name":"demo"}}
+{"reason":"compiler-message","message":{"message":"unneeded `return` statement","code":{"code":"clippy::needless_return"},"level":"warning","spans":[{"file_name":"src/lib.rs","line_start":2,"is_primary":true,"text":[{"text":"    return x;"}]}]}}
+{"reason":"compiler-message","message":{"message":"1 warning emitted","code":null,"level":"warning","spans":[]}}"#;
+    let findings = StaticAnalyzer::Clippy.parse_output(clippy, dir);
+    println!("\n🔎 Clippy findings: {:#?}", findings);
+    assert_eq!(findings.len(), 1, "Summary messages have no primary span");
+    assert_eq!(findings[0].rule, "clippy/needless_return");
+    assert_eq!(findings[0].severity, Severity::Medium);
+    assert_eq!(findings[0].file_path.as_deref(), Some("src/lib.rs"));
+    assert_eq!(findings[0].line, Some(2));
+    assert_eq!(findings[0].snippet.as_deref(), Some("return x;"));
+
+    let ruff = r#"[{"code":"F401","message":"`os` imported but unused","filename":"/tmp/checkout/app/main.py","location":{"row":1,"column":8}}]"#;
+    let findings = StaticAnalyzer::Ruff.parse_output(ruff, dir);
+    assert_eq!(findings[0].rule, "ruff/F401");
+    assert_eq!(
+        findings[0].file_path.as_deref(),
+        Some("app/main.py"),
+        "Paths should be relative to the checkout"
+    );
+
+    let eslint = r#"[{"filePath":"/tmp/checkout/src/index.ts","messages":[{"ruleId":"no-unused-vars","severity":2,"message":"'x' is defined but never used.","line":3},{"ruleId":"eqeqeq","severity":1,"message":"Expected '==='","line":5}]}]"#;
+    let findings = StaticAnalyzer::Eslint.parse_output(eslint, dir);
+    assert_eq!(findings.len(), 2);
+    assert_eq!(findings[0].rule, "eslint/no-unused-vars");
+    assert_eq!(findings[0].severity, Severity::Medium);
+    assert_eq!(findings[1].severity, Severity::Low);
+    assert_eq!(findings[1].file_path.as_deref(), Some("src/index.ts"));
+
+    assert!(
+        StaticAnalyzer::Ruff
+            .parse_output("not json", dir)
+            .is_empty()
+    );
+    assert_eq!(
+        "Clippy".parse::<StaticAnalyzer>(),
+        Ok(StaticAnalyzer::Clippy)
+    );
+    assert!("pylint".parse::<StaticAnalyzer>().is_err());
+}
+
+#[cfg(feature = "exec")]
+#[tokio::test]
+async fn test_run_static_analyzers_on_changed_files() {
+    let dir = temp_dir("clippy");
+    std::fs::create_dir_all(dir.join("src")).unwrap();
+    std::fs::write(
+        dir.join("Cargo.toml"),
+        "[package]\nname = \"demo\"\nversion = \"0.1.0\"\nedition = \"2021\"\n\n[workspace]\n",
+    )
+    .unwrap();
+    std::fs::write(
+        dir.join("src/lib.rs"),
+        "mod other;\n\npub fn id(x: i32) -> i32 {\n    return x;\n}\n",
+    )
+    .unwrap();
+    std::fs::write(
+        dir.join("src/other.rs"),
+        "pub fn id2(x: i32) -> i32 {\n    return x;\n}\n",
+    )
+    .unwrap();
+
+    let (findings, warnings) = run_static_analyzers(
+        &dir,
+        &[StaticAnalyzer::Clippy, StaticAnalyzer::Ruff],
+        &[changed("src/lib.rs")],
+    )
+    .await;
+    println!("\n🔎 Findings: {:#?}", findings);
+
+    assert!(warnings.is_empty(), "Unexpected warnings: {:?}", warnings);
+    assert!(
+        findings
+            .iter()
+            .any(|f| f.rule == "clippy/needless_return" && f.line == Some(4)),
+        "Clippy should report the needless return"
+    );
+    assert!(
+        findings
+            .iter()
+            .all(|f| f.file_path.as_deref() == Some("src/lib.rs")),
+        "Only diagnostics on changed files should be kept"
+    );
+    std::fs::remove_dir_all(&dir).ok();
+}
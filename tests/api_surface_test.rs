@@ -0,0 +1,107 @@
+use intent_verification::{
+    ChangeType, FileChange, Severity, breaking_change_findings, claims_non_breaking,
+    detect_breaking_changes, public_api,
+};
+
+fn modified(path: &str, old: &str, new: &str) -> FileChange {
+    FileChange {
+        path: path.to_string(),
+        status: ChangeType::Modified,
+        content: Some(new.to_string()),
+        old_content: Some(old.to_string()),
+    }
+}
+
+#[test]
+fn test_public_api() {
+    let rust = "pub fn sum(\n    a: i32,\n    b: i32,\n) -> i32 {\n    a + b\n}\n\
+                pub(crate) fn helper() {}\nfn private() {}\npub const LIMIT: usize = 10;\n\
+                impl Foo {\n    pub async fn run(&self) {}\n}\n";
+    let items = public_api("src/lib.rs", rust);
+    println!("\n📚 Public API: {:#?}", items);
+    let signatures: Vec<&str> = items.iter().map(|i| i.signature.as_str()).collect();
+    assert_eq!(
+        signatures,
+        vec![
+            "pub fn sum( a: i32, b: i32, ) -> i32",
+            "pub const LIMIT: usize",
+            "pub async fn run(&self)"
+        ]
+    );
+
+    let python = "def mean(values: list[float]) -> float:\n    pass\n\ndef _private():\n    pass\n\nclass Stats:\n    def method(self):\n        pass\n";
+    let names: Vec<String> = public_api("stats.py", python)
+        .into_iter()
+        .map(|i| format!("{} {}", i.kind, i.name))
+        .collect();
+    assert_eq!(names, vec!["def mean", "class Stats"]);
+
+    let ts = "export function add(a: number, b = 1): number {\n  return a + b;\n}\nfunction local() {}\nexport interface Options {}\n";
+    let items = public_api("src/index.ts", ts);
+    assert_eq!(items.len(), 2);
+    assert_eq!(
+        items[0].signature,
+        "export function add(a: number, b = 1): number"
+    );
+
+    assert!(public_api("README.md", "pub fn sum() {}").is_empty());
+}
+
+#[test]
+fn test_detect_breaking_changes() {
+    let changes = vec![
+        modified(
+            "src/math.rs",
+            "pub fn sum(a: i32, b: i32) -> i32 {\n    a + b\n}\npub fn mean(v: &[i32]) -> i32 {\n    0\n}\npub fn moved() {}\n",
+            "pub fn sum(a: i64, b: i64) -> i64 {\n    a + b\n}\npub fn mean(v: &[i32]) -> i32 {\n    1\n}\n",
+        ),
+        FileChange {
+            path: "src/util.rs".to_string(),
+            status: ChangeType::Added,
+            content: Some("pub fn moved() {}\n".to_string()),
+            old_content: None,
+        },
+        FileChange {
+            path: "src/legacy.rs".to_string(),
+            status: ChangeType::Deleted,
+            content: None,
+            old_content: Some("pub struct Legacy;\n".to_string()),
+        },
+        modified("src/main.rs", "pub fn cli() {}\n", ""),
+    ];
+
+    let breaking = detect_breaking_changes(&changes);
+    println!("\n💥 Breaking changes: {:#?}", breaking);
+    let names: Vec<&str> = breaking.iter().map(|b| b.before.name.as_str()).collect();
+    assert_eq!(
+        names,
+        vec!["sum", "Legacy"],
+        "Body-only changes, moves and binaries are not breaking"
+    );
+    assert_eq!(
+        breaking[0].after.as_ref().unwrap().signature,
+        "pub fn sum(a: i64, b: i64) -> i64"
+    );
+    assert!(breaking[1].after.is_none());
+
+    let findings = breaking_change_findings(&breaking, true);
+    assert_eq!(findings[0].rule, "semver/signature-changed");
+    assert_eq!(findings[1].rule, "semver/removed");
+    assert!(findings.iter().all(|f| f.severity == Severity::High));
+    assert_eq!(
+        breaking_change_findings(&breaking, false)[0].severity,
+        Severity::Medium
+    );
+}
+
+#[test]
+fn test_claims_non_breaking() {
+    assert!(claims_non_breaking(
+        "Non-breaking refactor of the parser module"
+    ));
+    assert!(claims_non_breaking(
+        "Speed up sum; this must not break existing callers"
+    ));
+    assert!(claims_non_breaking("Keep the API backward compatible"));
+    assert!(!claims_non_breaking("Rename sum to add"));
+}
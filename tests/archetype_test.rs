@@ -0,0 +1,286 @@
+#![cfg(feature = "git")]
+
+use intent_verification::{
+    ARCHETYPE_RULE_PREFIX, AnalysisOptions, ChangeType, EvidenceRequirement, FileChange, Finding,
+    IntentArchetype, IntentVerificationResult, PromptStage, Severity, VerificationProfile,
+    apply_archetype, missing_evidence, verify_intent_with_options,
+};
+
+fn change(path: &str, old: &str, new: &str) -> FileChange {
+    FileChange {
+        path: path.to_string(),
+        status: ChangeType::Modified,
+        content: Some(new.to_string()),
+        old_content: Some(old.to_string()),
+    }
+}
+
+fn fulfilled_result() -> IntentVerificationResult {
+    serde_json::from_str(
+        r#"{"is_intent_fulfilled":true,"confidence":0.9,"explanation":"1 out of 1 changed files support the test intent","files_analyzed":[],"overall_assessment":""}"#,
+    )
+    .unwrap()
+}
+
+#[test]
+fn test_archetype_names() {
+    for archetype in IntentArchetype::ALL {
+        assert_eq!(archetype.name().parse::<IntentArchetype>(), Ok(archetype));
+        assert_eq!(
+            serde_json::to_value(archetype).unwrap(),
+            serde_json::Value::String(archetype.to_string())
+        );
+    }
+    assert_eq!(
+        "Security_Patch".parse::<IntentArchetype>(),
+        Ok(IntentArchetype::SecurityPatch)
+    );
+    let error = "chore".parse::<IntentArchetype>().unwrap_err();
+    assert!(error.contains("bug-fix, feature, refactor, performance, security-patch"));
+}
+
+#[test]
+fn test_archetype_settings() {
+    assert_eq!(
+        IntentArchetype::BugFix.required_evidence(),
+        &[EvidenceRequirement::Tests]
+    );
+    assert_eq!(
+        IntentArchetype::Refactor.required_evidence(),
+        &[EvidenceRequirement::StablePublicApi]
+    );
+    assert_eq!(
+        IntentArchetype::SecurityPatch.profile(),
+        VerificationProfile::Security
+    );
+    assert_eq!(
+        IntentArchetype::Feature.profile(),
+        VerificationProfile::Standard
+    );
+
+    let policy = IntentArchetype::SecurityPatch.verdict_policy();
+    assert!(policy.require_intent_fulfilled);
+    assert_eq!(policy.min_confidence, 0.7);
+    assert_eq!(policy.fail_on_severity, Some(Severity::Medium));
+
+    let options = AnalysisOptions {
+        archetype: Some(IntentArchetype::SecurityPatch),
+        ..Default::default()
+    };
+    assert_eq!(
+        options.verification_profile(),
+        VerificationProfile::Security
+    );
+    let options = AnalysisOptions {
+        archetype: Some(IntentArchetype::Refactor),
+        profile: VerificationProfile::Security,
+        ..Default::default()
+    };
+    assert_eq!(
+        options.verification_profile(),
+        VerificationProfile::Security,
+        "An explicit security profile is kept"
+    );
+}
+
+#[test]
+fn test_bug_fix_needs_tests() {
+    let fix = change(
+        "src/lib.rs",
+        "pub fn div(a: i32, b: i32) -> i32 {\n    a / b\n}\n",
+        "pub fn div(a: i32, b: i32) -> Option<i32> {\n    a.checked_div(b)\n}\n",
+    );
+    let result = fulfilled_result();
+
+    let missing = missing_evidence(IntentArchetype::BugFix, std::slice::from_ref(&fix), &result);
+    println!("\n🧾 {:#?}", missing);
+    assert_eq!(missing.len(), 1);
+    assert_eq!(
+        missing[0].rule,
+        format!("{}missing-tests", ARCHETYPE_RULE_PREFIX)
+    );
+
+    let with_test_file = change(
+        "tests/div_test.rs",
+        "",
+        "#[test]\nfn zero() {\n    assert_eq!(div(1, 0), None);\n}\n",
+    );
+    assert!(
+        missing_evidence(
+            IntentArchetype::BugFix,
+            &[fix.clone(), with_test_file],
+            &result
+        )
+        .is_empty()
+    );
+
+    let inline = change(
+        "src/lib.rs",
+        "pub fn div(a: i32, b: i32) -> i32 {\n    a / b\n}\n",
+        "pub fn div(a: i32, b: i32) -> Option<i32> {\n    a.checked_div(b)\n}\n\n#[cfg(test)]\nmod tests {\n    #[test]\n    fn zero() {}\n}\n",
+    );
+    assert!(
+        missing_evidence(IntentArchetype::BugFix, &[inline], &result).is_empty(),
+        "Inline test modules count as tests"
+    );
+}
+
+#[test]
+fn test_performance_and_refactor_evidence() {
+    let result = fulfilled_result();
+    let faster = change(
+        "src/sort.rs",
+        "fn sort() {}\n",
+        "fn sort() { /* faster */ }\n",
+    );
+    assert_eq!(
+        missing_evidence(
+            IntentArchetype::Performance,
+            std::slice::from_ref(&faster),
+            &result
+        )[0]
+        .rule,
+        format!("{}missing-benchmarks", ARCHETYPE_RULE_PREFIX)
+    );
+    let bench = change("benches/sort.rs", "", "fn bench_sort() {}\n");
+    assert!(
+        missing_evidence(
+            IntentArchetype::Performance,
+            &[faster.clone(), bench],
+            &result
+        )
+        .is_empty()
+    );
+
+    assert!(
+        missing_evidence(
+            IntentArchetype::Refactor,
+            std::slice::from_ref(&faster),
+            &result
+        )
+        .is_empty()
+    );
+    let mut breaking = fulfilled_result();
+    breaking.findings.push(Finding {
+        rule: "semver/removed".to_string(),
+        severity: Severity::Medium,
+        file_path: Some("src/sort.rs".to_string()),
+        line: None,
+        snippet: None,
+        message: "Public function `sort_by` was removed".to_string(),
+        suppressed: false,
+        cwe: None,
+        content_hash: None,
+    });
+    let missing = missing_evidence(IntentArchetype::Refactor, &[faster], &breaking);
+    assert_eq!(missing.len(), 1);
+    assert!(
+        missing[0]
+            .message
+            .contains("1 public item(s) were removed or changed")
+    );
+}
+
+#[test]
+fn test_apply_archetype() {
+    let mut result = fulfilled_result();
+    let changes = [change("src/lib.rs", "", "pub fn new_feature() {}\n")];
+    assert_eq!(
+        apply_archetype(&mut result, IntentArchetype::Feature, &changes),
+        1
+    );
+    assert!(
+        !result.is_intent_fulfilled,
+        "Missing evidence fails the verdict"
+    );
+    assert!(
+        result
+            .explanation
+            .ends_with("1 evidence requirement(s) of a feature unmet")
+    );
+    assert_eq!(result.findings.len(), 1);
+    assert_eq!(result.findings[0].severity, Severity::High);
+}
+
+#[tokio::test]
+async fn test_archetype_prompts_and_evidence() {
+    let path = format!(
+        "/tmp/archetype_test_repo_{}_{}",
+        std::process::id(),
+        std::time::SystemTime::now()
+            .duration_since(std::time::UNIX_EPOCH)
+            .unwrap()
+            .as_nanos()
+    );
+    let repo = git2::Repository::init(&path).unwrap();
+    let signature = git2::Signature::now("Test", "test@example.com").unwrap();
+    let commit = |message: &str, content: &str| {
+        std::fs::create_dir_all(format!("{}/src", path)).unwrap();
+        std::fs::write(format!("{}/src/lib.rs", path), content).unwrap();
+        let mut index = repo.index().unwrap();
+        index.add_path(std::path::Path::new("src/lib.rs")).unwrap();
+        let tree = repo.find_tree(index.write_tree().unwrap()).unwrap();
+        let parents = match repo.head() {
+            Ok(head) => vec![head.peel_to_commit().unwrap()],
+            Err(_) => vec![],
+        };
+        let parents: Vec<&git2::Commit> = parents.iter().collect();
+        repo.commit(
+            Some("HEAD"),
+            &signature,
+            &signature,
+            message,
+            &tree,
+            &parents,
+        )
+        .unwrap()
+        .to_string()
+    };
+    let first = commit("bug", "pub fn abs(n: i32) -> i32 {\n    n\n}\n");
+    let second = commit("fix", "pub fn abs(n: i32) -> i32 {\n    n.abs()\n}\n");
+    let options = AnalysisOptions {
+        dry_run: true,
+        archetype: Some(IntentArchetype::BugFix),
+        ..Default::default()
+    };
+
+    let result = verify_intent_with_options(
+        &path,
+        &second,
+        &path,
+        &first,
+        &second,
+        "abs should return the absolute value for negative numbers",
+        "test-key",
+        None,
+        Some("http://127.0.0.1:9"),
+        &options,
+    )
+    .await
+    .expect("Dry run should succeed without the model");
+
+    let contains = |stage: PromptStage, text: &str| {
+        result
+            .prompts
+            .iter()
+            .filter(|p| p.stage == stage)
+            .any(|p| p.messages.iter().any(|m| m.content.contains(text)))
+    };
+    assert!(contains(
+        PromptStage::FileAnalysis,
+        "The intent is a bug fix."
+    ));
+    assert!(contains(
+        PromptStage::OverallAssessment,
+        "This is a bug fix:"
+    ));
+    assert!(
+        result
+            .findings
+            .iter()
+            .any(|f| f.rule == format!("{}missing-tests", ARCHETYPE_RULE_PREFIX)),
+        "The fix comes without a test"
+    );
+
+    std::fs::remove_dir_all(&path).ok();
+}
@@ -0,0 +1,160 @@
+#![cfg(feature = "archive")]
+use std::io::Write;
+
+use intent_verification::{ChangeType, RepoSnapshot, TestTargets, binary_metadata};
+
+const LIB: &str = "pub fn sum(a: i32, b: i32) -> i32 {\n    a + b\n}\n";
+const TEST: &str = "use calc::sum;\n\n#[test]\nfn test_sum() {\n    assert_eq!(sum(1, 2), 3);\n}\n";
+
+/// Files as a code host's archive endpoint lays them out, under `calc-<commit>/`
+fn repo_files() -> Vec<(&'static str, &'static [u8])> {
+    vec![
+        ("calc-1a2b3c/src/lib.rs", LIB.as_bytes()),
+        ("calc-1a2b3c/tests/sum_test.rs", TEST.as_bytes()),
+        ("calc-1a2b3c/logo.png", b"\x89PNG\r\n\x1a\n\0\0\0\rIHDR"),
+        ("calc-1a2b3c/.git/HEAD", b"ref: refs/heads/main\n"),
+    ]
+}
+
+fn zip_archive(files: &[(&str, &[u8])]) -> Vec<u8> {
+    let mut writer = zip::ZipWriter::new(std::io::Cursor::new(Vec::new()));
+    for (path, content) in files {
+        writer
+            .start_file(*path, zip::write::SimpleFileOptions::default())
+            .unwrap();
+        writer.write_all(content).unwrap();
+    }
+    writer.finish().unwrap().into_inner()
+}
+
+fn tar_archive(files: &[(&str, &[u8])]) -> Vec<u8> {
+    let mut builder = tar::Builder::new(Vec::new());
+    for (path, content) in files {
+        let mut header = tar::Header::new_gnu();
+        header.set_size(content.len() as u64);
+        header.set_mode(0o644);
+        header.set_cksum();
+        builder.append_data(&mut header, path, *content).unwrap();
+    }
+    builder.into_inner().unwrap()
+}
+
+fn gzip(data: &[u8]) -> Vec<u8> {
+    let mut encoder = flate2::write::GzEncoder::new(Vec::new(), flate2::Compression::default());
+    encoder.write_all(data).unwrap();
+    encoder.finish().unwrap()
+}
+
+fn assert_repo(snapshot: &RepoSnapshot) {
+    assert_eq!(
+        snapshot.files.keys().collect::<Vec<_>>(),
+        vec!["logo.png", "src/lib.rs", "tests/sum_test.rs"]
+    );
+    assert_eq!(snapshot.get("src/lib.rs"), Some(LIB));
+    let logo = binary_metadata(snapshot.get("logo.png").unwrap()).unwrap();
+    assert_eq!(logo.mime_type, "image/png");
+}
+
+#[test]
+fn test_zip_archive() {
+    assert_repo(&RepoSnapshot::from_archive_bytes(&zip_archive(&repo_files())).unwrap());
+}
+
+#[test]
+fn test_tarball() {
+    let tarball = gzip(&tar_archive(&repo_files()));
+    assert_repo(&RepoSnapshot::from_archive_bytes(&tarball).unwrap());
+    assert_repo(&RepoSnapshot::from_archive_bytes(&tar_archive(&repo_files())).unwrap());
+
+    let path = std::env::temp_dir().join(format!("archive_test_{}.tar.gz", std::process::id()));
+    std::fs::write(&path, &tarball).unwrap();
+    let snapshot = RepoSnapshot::from_archive(&path);
+    std::fs::remove_file(&path).unwrap();
+    assert_repo(&snapshot.unwrap());
+}
+
+#[test]
+fn test_archive_without_root_folder() {
+    let snapshot = RepoSnapshot::from_archive_bytes(&zip_archive(&[
+        ("src/lib.rs", LIB.as_bytes()),
+        ("README.md", b"# Calc\n"),
+    ]))
+    .unwrap();
+    assert_eq!(
+        snapshot.files.keys().collect::<Vec<_>>(),
+        vec!["README.md", "src/lib.rs"]
+    );
+}
+
+#[test]
+fn test_archive_entries_outside_the_archive_are_skipped() {
+    let mut tarball = tar::Builder::new(Vec::new());
+    let mut header = tar::Header::new_gnu();
+    header.set_size(4);
+    header.set_mode(0o644);
+    // `append_data` refuses `..`, so the name goes in the header directly
+    header.as_gnu_mut().unwrap().name[..9].copy_from_slice(b"../escape");
+    header.set_cksum();
+    tarball.append(&header, &b"evil"[..]).unwrap();
+    let mut header = tar::Header::new_gnu();
+    header.set_size(LIB.len() as u64);
+    header.set_mode(0o644);
+    header.set_cksum();
+    tarball
+        .append_data(&mut header, "src/lib.rs", LIB.as_bytes())
+        .unwrap();
+    let mut header = tar::Header::new_gnu();
+    header.set_size(7);
+    header.set_mode(0o644);
+    header.set_cksum();
+    tarball
+        .append_data(&mut header, "README.md", &b"# Calc\n"[..])
+        .unwrap();
+
+    let snapshot = RepoSnapshot::from_archive_bytes(&tarball.into_inner().unwrap()).unwrap();
+    assert_eq!(
+        snapshot.files.keys().collect::<Vec<_>>(),
+        vec!["README.md", "src/lib.rs"]
+    );
+}
+
+#[test]
+fn test_invalid_archive() {
+    assert!(RepoSnapshot::from_archive_bytes(b"PK\x03\x04 not really a zip").is_err());
+    assert!(RepoSnapshot::from_archive("/nonexistent/calc.zip").is_err());
+}
+
+#[test]
+fn test_diff_and_read_targets_from_archives() {
+    let before = RepoSnapshot::from_archive_bytes(&zip_archive(&[(
+        "calc-0f0f0f/src/lib.rs",
+        b"pub fn sum(a: i32, b: i32) -> i32 {\n    todo!()\n}\n",
+    )]))
+    .unwrap();
+    let after = RepoSnapshot::from_archive_bytes(&gzip(&tar_archive(&repo_files()))).unwrap();
+
+    let changes: Vec<_> = before
+        .diff(&after)
+        .into_iter()
+        .map(|change| (change.path, change.status))
+        .collect();
+    assert_eq!(
+        changes,
+        vec![
+            ("logo.png".to_string(), ChangeType::Added),
+            ("src/lib.rs".to_string(), ChangeType::Modified),
+            ("tests/sum_test.rs".to_string(), ChangeType::Added),
+        ]
+    );
+
+    let targets = after.read_test_targets_code(&TestTargets {
+        functions: vec!["test_sum".to_string()],
+        files: vec!["tests/sum_test.rs".to_string()],
+        ..Default::default()
+    });
+    assert_eq!(
+        targets.function_contents[0].file_path.as_deref(),
+        Some("tests/sum_test.rs")
+    );
+    assert_eq!(targets.file_contents[0].content, TEST);
+}
@@ -0,0 +1,141 @@
+use ed25519_dalek::Signer;
+use intent_verification::{
+    ATTESTATION_ALGORITHM, Attestation, IntentVerificationResult, SCHEMA_VERSION,
+    canonical_result_hash, canonical_result_json, sign_result, signing_key_from_hex,
+    verify_attestation,
+};
+use sha2::{Digest, Sha256};
+
+const SEED: &str = "9d61b19deffd5a60ba844af492ec2cc44449c5697b326919703bac031cae7f60";
+
+fn sample_result() -> IntentVerificationResult {
+    serde_json::from_str(
+        r#"{"is_intent_fulfilled":true,"confidence":0.9,"explanation":"1 out of 1 changed files support the test intent","files_analyzed":[],"overall_assessment":""}"#,
+    )
+    .unwrap()
+}
+
+#[test]
+fn test_canonical_hash_is_stable() {
+    let result = sample_result();
+    let json = canonical_result_json(&result);
+    println!("\n📄 Canonical JSON: {}", json);
+
+    assert!(!json.contains('\n'), "Canonical JSON has no line breaks");
+    let confidence = json.find("\"confidence\"").unwrap();
+    let explanation = json.find("\"explanation\"").unwrap();
+    assert!(confidence < explanation, "Keys should be sorted");
+
+    // A JSON round trip doesn't change the hash
+    let reparsed: IntentVerificationResult =
+        serde_json::from_str(&serde_json::to_string_pretty(&result).unwrap()).unwrap();
+    assert_eq!(
+        canonical_result_hash(&result),
+        canonical_result_hash(&reparsed)
+    );
+    assert_eq!(canonical_result_hash(&result).len(), 64);
+}
+
+#[test]
+fn test_sign_and_verify() {
+    let key = signing_key_from_hex(SEED).unwrap();
+    let mut result = sample_result();
+    let attestation = sign_result(&result, &key);
+    println!("\n🔏 Attestation: {:?}", attestation);
+
+    assert_eq!(attestation.algorithm, ATTESTATION_ALGORITHM);
+    assert_eq!(attestation.result_hash, canonical_result_hash(&result));
+    assert_eq!(attestation.payload, canonical_result_json(&result));
+    assert_eq!(attestation.schema_version, SCHEMA_VERSION);
+    assert_eq!(
+        attestation.public_key,
+        "d75a980182b10ab7d54bfed3c964073a0ee172f3daa62325af021a68f707511a"
+    );
+    result.attestation = Some(attestation.clone());
+
+    // Attaching the attestation doesn't change the hash it covers
+    assert_eq!(attestation.result_hash, canonical_result_hash(&result));
+    verify_attestation(&result, None).expect("Fresh attestation should verify");
+    verify_attestation(&result, Some(&attestation.public_key.to_uppercase()))
+        .expect("Trusted key comparison ignores case");
+
+    // Survives being written and read back as a report
+    let report: IntentVerificationResult =
+        serde_json::from_str(&serde_json::to_string_pretty(&result).unwrap()).unwrap();
+    verify_attestation(&report, None).expect("Attestation should survive a round trip");
+}
+
+#[test]
+fn test_verify_rejects_tampering() {
+    let key = signing_key_from_hex(SEED).unwrap();
+    let mut result = sample_result();
+    result.attestation = Some(sign_result(&result, &key));
+
+    let mut flipped = result.clone();
+    flipped.is_intent_fulfilled = false;
+    let error = verify_attestation(&flipped, None).unwrap_err();
+    assert!(error.to_string().contains("modified"), "Got: {}", error);
+
+    // Rehashing without the key leaves a signature that doesn't match
+    let mut forged = flipped.clone();
+    let attestation = forged.attestation.as_mut().unwrap();
+    attestation.payload = canonical_result_json(&flipped);
+    attestation.result_hash = canonical_result_hash(&flipped);
+    let error = verify_attestation(&forged, None).unwrap_err();
+    assert!(error.to_string().contains("Signature"), "Got: {}", error);
+
+    let other_key = "3b6a27bcceb6a42d62a3a8d02a6f0d73653215771de243a63ac048a18b59da29";
+    let error = verify_attestation(&result, Some(other_key)).unwrap_err();
+    assert!(
+        error.to_string().contains("different key"),
+        "Got: {}",
+        error
+    );
+
+    let error = verify_attestation(&sample_result(), None).unwrap_err();
+    assert!(
+        error.to_string().contains("no attestation"),
+        "Got: {}",
+        error
+    );
+}
+
+#[test]
+fn test_verify_payload_signed_with_an_older_schema() {
+    let key = signing_key_from_hex(SEED).unwrap();
+    let mut result = sample_result();
+
+    // Signed before `scope_score` existed; it now reads back as its default
+    let mut signed: serde_json::Value =
+        serde_json::from_str(&canonical_result_json(&result)).unwrap();
+    signed.as_object_mut().unwrap().remove("scope_score");
+    let payload = signed.to_string();
+    let digest = Sha256::digest(payload.as_bytes());
+    let hex = |bytes: &[u8]| -> String { bytes.iter().map(|b| format!("{:02x}", b)).collect() };
+    result.attestation = Some(Attestation {
+        algorithm: ATTESTATION_ALGORITHM.to_string(),
+        result_hash: hex(&digest),
+        public_key: hex(key.verifying_key().as_bytes()),
+        signature: hex(&key.sign(&digest).to_bytes()),
+        schema_version: "0.9".to_string(),
+        payload,
+    });
+
+    assert_ne!(
+        result.attestation.as_ref().unwrap().result_hash,
+        canonical_result_hash(&result),
+        "The result no longer serializes to the signed bytes"
+    );
+    verify_attestation(&result, None).expect("The signed payload should still verify");
+
+    result.confidence = 0.1;
+    let error = verify_attestation(&result, None).unwrap_err();
+    assert!(error.to_string().contains("modified"), "Got: {}", error);
+}
+
+#[test]
+fn test_signing_key_from_hex_rejects_bad_seeds() {
+    assert!(signing_key_from_hex("abcd").is_err());
+    assert!(signing_key_from_hex(&"zz".repeat(32)).is_err());
+    assert!(signing_key_from_hex(&format!("{}\n", SEED)).is_ok());
+}
@@ -0,0 +1,159 @@
+#![cfg(feature = "git")]
+
+mod common;
+
+use intent_verification::{AnalysisOptions, BatchJob, BatchManifest, run_batch};
+
+/// Create a local repository with a stub commit and an implementation commit
+fn init_local_repo(dir: &str) -> (String, String, String) {
+    common::init_repo_with(
+        &format!("{}/repo", dir),
+        "src/lib.rs",
+        common::SUM_STUB,
+        common::SUM_IMPL,
+    )
+}
+
+fn temp_dir() -> String {
+    let dir = format!(
+        "/tmp/batch_test_{}_{}",
+        std::process::id(),
+        std::time::SystemTime::now()
+            .duration_since(std::time::UNIX_EPOCH)
+            .unwrap()
+            .as_nanos()
+    );
+    std::fs::create_dir_all(&dir).unwrap();
+    dir
+}
+
+#[test]
+fn test_manifest_formats() {
+    let json = r#"{
+        "concurrency": 2,
+        "jobs": [
+            { "name": "sum", "repo_url": "https://github.com/acme/calc", "base": "a1", "head": "b2", "intent": "Sum works" },
+            { "repo_url": "https://github.com/acme/calc", "base": "b2", "head": "c3", "intent": "Sub works",
+              "test_repo_url": "https://github.com/acme/calc-tests", "test_commit": "main" }
+        ]
+    }"#;
+    let toml = r#"
+concurrency = 2
+
+[[jobs]]
+name = "sum"
+repo_url = "https://github.com/acme/calc"
+base = "a1"
+head = "b2"
+intent = "Sum works"
+
+[[jobs]]
+repo_url = "https://github.com/acme/calc"
+base = "b2"
+head = "c3"
+intent = "Sub works"
+test_repo_url = "https://github.com/acme/calc-tests"
+test_commit = "main"
+"#;
+
+    let from_json = BatchManifest::from_json(json).expect("Should parse JSON manifests");
+    let from_toml = BatchManifest::from_toml(toml).expect("Should parse TOML manifests");
+
+    println!("\n📋 Manifest: {:#?}", from_json);
+
+    assert_eq!(
+        from_json, from_toml,
+        "Both formats should describe the same jobs"
+    );
+    assert_eq!(from_json.concurrency, Some(2));
+    assert_eq!(from_json.jobs.len(), 2);
+    assert_eq!(from_json.jobs[0].test_repo_url, None);
+    assert_eq!(from_json.jobs[1].test_commit.as_deref(), Some("main"));
+
+    let dir = temp_dir();
+    let path = format!("{}/jobs.toml", dir);
+    std::fs::write(&path, toml).unwrap();
+    assert_eq!(BatchManifest::load(&path).unwrap(), from_toml);
+
+    assert!(
+        BatchManifest::from_json(r#"{"jobs": [{"repo_url": "x"}]}"#).is_err(),
+        "Jobs without commits or intent should be rejected"
+    );
+}
+
+#[tokio::test]
+async fn test_run_batch_records_every_job() {
+    let dir = temp_dir();
+    let (repo_path, first, second) = init_local_repo(&dir);
+
+    let job = |name: &str, repo_url: &str| BatchJob {
+        name: Some(name.to_string()),
+        repo_url: repo_url.to_string(),
+        base: first.clone(),
+        head: second.clone(),
+        intent: "The sum function adds two numbers".to_string(),
+        test_repo_url: None,
+        test_commit: None,
+    };
+    let manifest = BatchManifest {
+        concurrency: Some(2),
+        jobs: vec![
+            job("local", &repo_path),
+            job("missing", &format!("{}/missing", dir)),
+            job("local again", &repo_path),
+        ],
+    };
+    let options = AnalysisOptions {
+        cache_dir: Some(dir.clone()),
+        ..Default::default()
+    };
+
+    // Nothing listens on port 9, so the LLM calls fail without network access
+    let summary = run_batch(
+        &manifest,
+        "test-key",
+        None,
+        Some("http://127.0.0.1:9"),
+        &options,
+    )
+    .await;
+
+    println!("\n📊 Batch summary: {:#?}", summary);
+
+    assert_eq!(summary.total, 3);
+    assert_eq!(
+        summary
+            .outcomes
+            .iter()
+            .map(|o| o.name.as_str())
+            .collect::<Vec<_>>(),
+        vec!["local", "missing", "local again"],
+        "Outcomes should keep manifest order"
+    );
+    assert_eq!(
+        summary.fulfilled + summary.not_fulfilled + summary.failed,
+        3,
+        "Every job should be accounted for"
+    );
+    let local = summary.outcomes[0]
+        .result
+        .as_ref()
+        .expect("Local repository should be verified through its mirror");
+    assert_eq!(local.files_analyzed.len(), 1);
+    let missing = summary.outcomes[1].error.as_deref().unwrap_or_default();
+    assert!(
+        missing.starts_with("Failed to fetch"),
+        "Unreachable repositories should fail before verification: {}",
+        missing
+    );
+
+    let mirrors = std::fs::read_dir(&dir)
+        .unwrap()
+        .flatten()
+        .filter(|entry| entry.file_name().to_string_lossy().starts_with("mirror_"))
+        .count();
+    assert_eq!(
+        mirrors, 1,
+        "The repository should be mirrored once, and failed fetches cleaned up"
+    );
+}
@@ -0,0 +1,226 @@
+use intent_verification::{
+    AnalysisOptions, BinaryPolicy, ChangeType, FileChange, LlmClient, MockProvider, RepoSnapshot,
+    Severity, UNEXPECTED_BINARY_RULE, binary_changes, binary_metadata, binary_placeholder,
+    is_binary_placeholder, mime_type, render_markdown, unexpected_binary_findings,
+    verify_intent_with_snapshots,
+};
+
+const PNG: &[u8] = b"\x89PNG\r\n\x1a\n\0\0\0\rIHDR";
+const ELF: &[u8] = b"\x7fELF\x02\x01\x01\0\0\0\0\0";
+
+fn change(path: &str, status: ChangeType, old: Option<&[u8]>, new: Option<&[u8]>) -> FileChange {
+    FileChange {
+        path: path.to_string(),
+        status,
+        content: new.map(|data| binary_placeholder(path, data)),
+        old_content: old.map(|data| binary_placeholder(path, data)),
+    }
+}
+
+#[test]
+fn test_binary_placeholder_round_trip() {
+    let placeholder = binary_placeholder("assets/logo.png", PNG);
+    println!("\n🖼️ {}", placeholder);
+    assert!(placeholder.starts_with("[Binary file: image/png, 16 bytes, sha256 "));
+    assert!(is_binary_placeholder(&placeholder));
+    assert!(is_binary_placeholder("[Binary file]"));
+    assert!(!is_binary_placeholder("fn main() {}"));
+
+    let metadata = binary_metadata(&placeholder).unwrap();
+    assert_eq!(metadata.mime_type, "image/png");
+    assert_eq!(metadata.size, 16);
+    assert_eq!(metadata.sha256.len(), 64);
+    assert!(
+        binary_metadata("[Binary file]").is_none(),
+        "Bare placeholders have no metadata"
+    );
+}
+
+#[test]
+fn test_mime_type() {
+    assert_eq!(mime_type("a.bin", PNG), "image/png");
+    assert_eq!(mime_type("lib/libfoo.so", ELF), "application/x-elf");
+    assert_eq!(
+        mime_type("tool.exe", b"MZ\x90\0"),
+        "application/vnd.microsoft.portable-executable"
+    );
+    assert_eq!(
+        mime_type("photo.JPG", b""),
+        "image/jpeg",
+        "Extension fallback"
+    );
+    assert_eq!(
+        mime_type("data.bin", b"\x01\x02"),
+        "application/octet-stream"
+    );
+}
+
+#[test]
+fn test_binary_changes() {
+    let changes = vec![
+        change(
+            "assets/logo.png",
+            ChangeType::Modified,
+            Some(PNG),
+            Some(&[PNG, &[0; 10]].concat()),
+        ),
+        change("bin/helper.so", ChangeType::Added, None, Some(ELF)),
+        change(
+            "docs/manual.pdf",
+            ChangeType::Deleted,
+            Some(b"%PDF-1.7"),
+            None,
+        ),
+        FileChange {
+            path: "src/lib.rs".to_string(),
+            status: ChangeType::Modified,
+            content: Some("fn a() {}\n".to_string()),
+            old_content: Some("fn b() {}\n".to_string()),
+        },
+    ];
+
+    let binaries = binary_changes(&changes);
+    println!("\n📦 Binary changes: {:#?}", binaries);
+    let summary: Vec<(&str, &str, i64)> = binaries
+        .iter()
+        .map(|b| (b.file_path.as_str(), b.mime_type.as_str(), b.size_delta))
+        .collect();
+    assert_eq!(
+        summary,
+        vec![
+            ("assets/logo.png", "image/png", 10),
+            ("bin/helper.so", "application/x-elf", 12),
+            ("docs/manual.pdf", "application/pdf", -8),
+        ]
+    );
+    assert_ne!(
+        binaries[0].old.as_ref().unwrap().sha256,
+        binaries[0].new.as_ref().unwrap().sha256
+    );
+    assert!(binaries[1].old.is_none());
+    assert!(binaries[2].new.is_none());
+}
+
+#[test]
+fn test_unexpected_binary_findings() {
+    let binaries = binary_changes(&[
+        change("assets/icon.png", ChangeType::Added, None, Some(PNG)),
+        change("vendor/libfast.so", ChangeType::Added, None, Some(ELF)),
+        change("docs/diagram.png", ChangeType::Added, None, Some(PNG)),
+        change("assets/old.png", ChangeType::Modified, Some(PNG), Some(PNG)),
+    ]);
+    let policy = BinaryPolicy {
+        allowed: vec!["assets/**".to_string()],
+        ..Default::default()
+    };
+
+    let findings = unexpected_binary_findings(&binaries, &policy);
+    println!("\n🚩 Findings: {:#?}", findings);
+    let flagged: Vec<(&str, Severity)> = findings
+        .iter()
+        .map(|f| (f.file_path.as_deref().unwrap(), f.severity))
+        .collect();
+    assert_eq!(
+        flagged,
+        vec![
+            ("vendor/libfast.so", Severity::High),
+            ("docs/diagram.png", Severity::Medium),
+        ],
+        "Allowed and modified binaries aren't reported; executables are High"
+    );
+    assert!(findings.iter().all(|f| f.rule == UNEXPECTED_BINARY_RULE));
+    assert!(findings[0].message.contains("Unexpected binary executable"));
+}
+
+#[tokio::test(flavor = "current_thread")]
+async fn test_binary_changes_in_result() {
+    let base = RepoSnapshot::from_files([("src/lib.rs", "pub fn run() {}\n")]);
+    let head = RepoSnapshot::from_files([
+        (
+            "src/lib.rs",
+            "pub fn run() {\n    helper();\n}\n".to_string(),
+        ),
+        ("bin/helper.so", binary_placeholder("bin/helper.so", ELF)),
+    ]);
+    let mock = MockProvider::new()
+        .respond_when(
+            "Extract from the following prompt",
+            r#"{"functions": ["run"], "files": []}"#,
+        )
+        .respond_when(
+            "STEP 2",
+            r#"{"supports_intent": true, "reasoning": "calls it", "relevant_changes": []}"#,
+        )
+        .respond("Assessment");
+    let options = AnalysisOptions {
+        llm_client: Some(LlmClient::mock(mock.clone())),
+        binary_policy: Some(BinaryPolicy::default()),
+        ..Default::default()
+    };
+
+    let result = verify_intent_with_snapshots(
+        &head,
+        &base,
+        &head,
+        "run calls the helper",
+        "",
+        None,
+        None,
+        &options,
+    )
+    .await
+    .unwrap();
+
+    assert_eq!(result.binary_changes.len(), 1);
+    assert_eq!(result.binary_changes[0].mime_type, "application/x-elf");
+    assert!(result.findings.iter().any(
+        |f| f.rule == UNEXPECTED_BINARY_RULE && f.file_path.as_deref() == Some("bin/helper.so")
+    ));
+    assert!(
+        mock.calls()
+            .iter()
+            .all(|call| !call.prompt().contains("sha256")),
+        "Binary files aren't sent to the model"
+    );
+    let md = render_markdown(&result);
+    assert!(md.contains("| `bin/helper.so` | Added | application/x-elf | +12 bytes |"));
+}
+
+#[cfg(feature = "git")]
+#[test]
+fn test_git_changes_carry_binary_metadata() {
+    let path = format!(
+        "/tmp/binary_test_repo_{}_{}",
+        std::process::id(),
+        std::time::SystemTime::now()
+            .duration_since(std::time::UNIX_EPOCH)
+            .unwrap()
+            .as_nanos()
+    );
+    let repo = git2::Repository::init(&path).unwrap();
+    let signature = git2::Signature::now("Test", "test@example.com").unwrap();
+    let commit = |file: &str, data: &[u8]| {
+        std::fs::write(std::path::Path::new(&path).join(file), data).unwrap();
+        let mut index = repo.index().unwrap();
+        index.add_path(std::path::Path::new(file)).unwrap();
+        let tree = repo.find_tree(index.write_tree().unwrap()).unwrap();
+        let parents = match repo.head() {
+            Ok(head) => vec![head.peel_to_commit().unwrap()],
+            Err(_) => vec![],
+        };
+        let parents: Vec<&git2::Commit> = parents.iter().collect();
+        repo.commit(Some("HEAD"), &signature, &signature, file, &tree, &parents)
+            .unwrap()
+            .to_string()
+    };
+    let first = commit("README.md", b"Readme\n");
+    let second = commit("logo.png", PNG);
+
+    let changes = intent_verification::get_git_changed_files(&path, &first, &second).unwrap();
+    std::fs::remove_dir_all(&path).ok();
+
+    let logo = changes.iter().find(|c| c.path == "logo.png").unwrap();
+    let metadata = binary_metadata(logo.content.as_deref().unwrap()).unwrap();
+    assert_eq!(metadata.mime_type, "image/png");
+    assert_eq!(metadata.size, PNG.len() as u64);
+}
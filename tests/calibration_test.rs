@@ -0,0 +1,168 @@
+use intent_verification::{
+    AnalysisOptions, LlmClient, MockProvider, RepoSnapshot, render_markdown,
+    verify_intent_with_snapshots,
+};
+
+fn snapshots() -> (RepoSnapshot, RepoSnapshot) {
+    let base = RepoSnapshot::from_files([
+        (
+            "src/lib.rs",
+            "pub fn sum(a: i32, b: i32) -> i32 {\n    todo!()\n}\n",
+        ),
+        (
+            "src/util.rs",
+            "pub fn double(a: i32) -> i32 {\n    todo!()\n}\n",
+        ),
+    ]);
+    let head = RepoSnapshot::from_files([
+        (
+            "src/lib.rs",
+            "pub fn sum(a: i32, b: i32) -> i32 {\n    a + b\n}\n",
+        ),
+        (
+            "src/util.rs",
+            "pub fn double(a: i32) -> i32 {\n    a * 2\n}\n",
+        ),
+    ]);
+    (base, head)
+}
+
+fn mock() -> MockProvider {
+    MockProvider::new()
+        .respond_when(
+            "Extract from the following prompt",
+            r#"{"functions": ["sum"], "files": []}"#,
+        )
+        .respond_with_logprobs(
+            "SOLUTION FILE: src/lib.rs",
+            &[
+                (r#"{"supports_intent": "#, -0.01),
+                ("true", -0.105),
+                (
+                    r#", "reasoning": "sum adds", "confidence": 0.95, "relevant_changes": []}"#,
+                    -0.2,
+                ),
+            ],
+        )
+        .respond_with_logprobs(
+            "SOLUTION FILE: src/util.rs",
+            &[
+                (r#"{"supports_intent": "#, -0.01),
+                ("false", -0.693),
+                (
+                    r#", "reasoning": "unrelated", "relevant_changes": []}"#,
+                    -0.2,
+                ),
+            ],
+        )
+        .respond("Assessment")
+}
+
+async fn verify(
+    mock: &MockProvider,
+    calibrate_confidence: bool,
+) -> intent_verification::IntentVerificationResult {
+    let (base, head) = snapshots();
+    verify_intent_with_snapshots(
+        &head,
+        &base,
+        &head,
+        "The sum function adds two numbers",
+        "",
+        None,
+        None,
+        &AnalysisOptions {
+            llm_client: Some(LlmClient::mock(mock.clone())),
+            calibrate_confidence,
+            ..Default::default()
+        },
+    )
+    .await
+    .unwrap()
+}
+
+#[tokio::test(flavor = "current_thread")]
+async fn test_confidence_from_logprobs() {
+    let result = verify(&mock(), true).await;
+
+    let probability = |path: &str| {
+        result
+            .files_analyzed
+            .iter()
+            .find(|fa| fa.file_path == path)
+            .unwrap()
+            .support_probability
+            .unwrap()
+    };
+    assert!((probability("src/lib.rs") - 0.9).abs() < 0.01);
+    assert!(
+        (probability("src/util.rs") - 0.5).abs() < 0.01,
+        "A false answer is turned into the probability of supporting the intent"
+    );
+    let lib = result
+        .files_analyzed
+        .iter()
+        .find(|fa| fa.file_path == "src/lib.rs")
+        .unwrap();
+    assert_eq!(lib.confidence, Some(0.95));
+
+    let calibration = result.calibration.clone().expect("Calibrated");
+    assert!(result.is_intent_fulfilled);
+    assert_eq!(calibration.files_with_logprobs, 2);
+    assert_eq!(calibration.self_reported, Some(0.95));
+    // At least one of the two files supports the intent: 1 - 0.1 * 0.5
+    assert!(
+        (calibration.calibrated - 0.95).abs() < 0.01,
+        "{:?}",
+        calibration
+    );
+    assert_eq!(result.confidence, calibration.calibrated);
+    assert!((calibration.raw - 0.65).abs() < 0.01, "{:?}", calibration);
+
+    assert!(
+        render_markdown(&result).contains("calibrated from the log-probabilities of 2 file(s)")
+    );
+    println!(
+        "\n✅ Confidence {:.2} calibrated from {:.2}",
+        calibration.calibrated, calibration.raw
+    );
+}
+
+#[tokio::test(flavor = "current_thread")]
+async fn test_no_calibration_without_option_or_logprobs() {
+    let mock = mock();
+    let result = verify(&mock, false).await;
+    assert!(result.calibration.is_none());
+    assert!(
+        result
+            .files_analyzed
+            .iter()
+            .all(|fa| fa.support_probability.is_none()),
+        "Logprobs aren't used unless asked for"
+    );
+    assert!(
+        mock.calls().iter().all(|call| !call.logprobs),
+        "Logprobs aren't requested"
+    );
+
+    let mock = MockProvider::new()
+        .respond_when(
+            "Extract from the following prompt",
+            r#"{"functions": ["sum"], "files": []}"#,
+        )
+        .respond_when(
+            "STEP 2",
+            r#"{"supports_intent": true, "reasoning": "adds", "relevant_changes": []}"#,
+        )
+        .respond("Assessment");
+    let result = verify(&mock, true).await;
+    assert!(
+        mock.calls().iter().any(|call| call.logprobs),
+        "Logprobs are requested"
+    );
+    assert!(
+        result.calibration.is_none(),
+        "Providers without logprobs keep the uncalibrated confidence"
+    );
+    println!("\n✅ Confidence {:.2}", result.confidence);
+}
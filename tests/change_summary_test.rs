@@ -0,0 +1,155 @@
+use intent_verification::{
+    AnalysisOptions, ApiItem, ChangeType, FileChange, LlmClient, MockProvider, RepoSnapshot,
+    endpoints, render_markdown, summarize_changes, verify_intent_with_snapshots,
+};
+
+fn modified(path: &str, old: &str, new: &str) -> FileChange {
+    FileChange {
+        path: path.to_string(),
+        status: ChangeType::Modified,
+        content: Some(new.to_string()),
+        old_content: Some(old.to_string()),
+    }
+}
+
+#[test]
+fn test_endpoints() {
+    let rust = "#[get(\"/users\")]\nasync fn list() {}\n\
+                let app = Router::new().route(\"/health\", get(health));\n";
+    let found: Vec<String> = endpoints("src/api.rs", rust)
+        .into_iter()
+        .map(|e| format!("{} {}", e.method, e.path))
+        .collect();
+    assert_eq!(found, vec!["GET /users", "GET /health"]);
+
+    let express = "app.post('/orders', create);\nrouter.delete(`/orders/:id`, remove);\n";
+    let found: Vec<String> = endpoints("src/app.js", express)
+        .into_iter()
+        .map(|e| format!("{} {}", e.method, e.path))
+        .collect();
+    assert_eq!(found, vec!["POST /orders", "DELETE /orders/:id"]);
+
+    let flask = "@app.route(\"/login\")\ndef login():\n    pass\n";
+    let found = endpoints("app.py", flask);
+    assert_eq!(found.len(), 1);
+    assert_eq!(found[0].method, "ANY");
+    assert_eq!(found[0].file_path, "app.py");
+}
+
+#[test]
+fn test_summarize_changes() {
+    let changes = vec![
+        modified(
+            "src/math.rs",
+            "pub fn sum(a: i32, b: i32) -> i32 {\n    a + b\n}\npub fn old() {}\n",
+            "pub fn sum(a: i64, b: i64) -> i64 {\n    a + b\n}\npub fn mean(v: &[i64]) -> i64 {\n    0\n}\n",
+        ),
+        modified(
+            "src/api.rs",
+            "#[get(\"/v1/users\")]\nasync fn users() {}\n",
+            "#[get(\"/v2/users\")]\nasync fn users() {}\n",
+        ),
+        modified("tests/math.rs", "", "pub fn helper() {}\n"),
+    ];
+
+    let summary = summarize_changes(&changes);
+    println!("\n📝 Change summary: {:#?}", summary);
+    let names =
+        |items: &[ApiItem]| -> Vec<String> { items.iter().map(|i| i.name.clone()).collect() };
+    assert_eq!(
+        names(&summary.added),
+        vec!["mean"],
+        "Test files are ignored"
+    );
+    assert_eq!(summary.modified.len(), 1);
+    assert_eq!(summary.modified[0].before.name, "sum");
+    assert_eq!(names(&summary.removed), vec!["old"]);
+    assert_eq!(summary.added_endpoints[0].path, "/v2/users");
+    assert_eq!(summary.removed_endpoints[0].path, "/v1/users");
+
+    let changelog = summary.to_changelog();
+    println!("\n{}", changelog);
+    assert!(changelog.contains("### Added\n\n- `pub fn mean(v: &[i64]) -> i64` (src/math.rs)"));
+    assert!(changelog.contains("- `GET /v2/users` (src/api.rs)"));
+    assert!(changelog.contains(
+        "### Changed\n\n- `pub fn sum(a: i32, b: i32) -> i32` is now `pub fn sum(a: i64, b: i64) -> i64`"
+    ));
+    assert!(changelog.contains("### Removed\n\n- `pub fn old()` (src/math.rs)"));
+
+    assert!(summarize_changes(&[modified("README.md", "a", "b")]).is_empty());
+}
+
+#[tokio::test(flavor = "current_thread")]
+async fn test_change_summary_in_result() {
+    let base =
+        RepoSnapshot::from_files([("src/lib.rs", "pub fn sum(a: i32) -> i32 {\n    a\n}\n")]);
+    let head = RepoSnapshot::from_files([(
+        "src/lib.rs",
+        "pub fn sum(a: i32) -> i32 {\n    a\n}\npub fn double(a: i32) -> i32 {\n    a * 2\n}\n",
+    )]);
+    let mock = MockProvider::new()
+        .respond_when(
+            "Extract from the following prompt",
+            r#"{"functions": ["double"], "files": []}"#,
+        )
+        .respond_when(
+            "STEP 2",
+            r#"{"supports_intent": true, "reasoning": "adds", "relevant_changes": []}"#,
+        )
+        .respond_when(
+            "Summarize what the following code changes do",
+            "Adds a function that doubles a number.",
+        )
+        .respond("Assessment");
+    let options = AnalysisOptions {
+        llm_client: Some(LlmClient::mock(mock.clone())),
+        change_summary: true,
+        ..Default::default()
+    };
+
+    let result = verify_intent_with_snapshots(
+        &head,
+        &base,
+        &head,
+        "Add a function that doubles a number",
+        "",
+        None,
+        None,
+        &options,
+    )
+    .await
+    .unwrap();
+
+    let summary = result.change_summary.as_ref().expect("summary requested");
+    assert_eq!(summary.added[0].name, "double");
+    assert_eq!(summary.overview, "Adds a function that doubles a number.");
+    let prompt = mock
+        .calls()
+        .into_iter()
+        .map(|call| call.prompt())
+        .find(|prompt| prompt.contains("Summarize what the following code changes do"))
+        .unwrap();
+    assert!(prompt.contains("pub fn double(a: i32) -> i32"));
+
+    let md = render_markdown(&result);
+    assert!(md.contains("### What changed\n\nAdds a function that doubles a number."));
+    assert!(md.contains("#### Added"));
+
+    let options = AnalysisOptions {
+        llm_client: Some(LlmClient::mock(mock.clone())),
+        ..Default::default()
+    };
+    let result = verify_intent_with_snapshots(
+        &head,
+        &base,
+        &head,
+        "Add a function that doubles a number",
+        "",
+        None,
+        None,
+        &options,
+    )
+    .await
+    .unwrap();
+    assert!(result.change_summary.is_none(), "Off by default");
+}
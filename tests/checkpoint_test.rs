@@ -0,0 +1,284 @@
+#![cfg(feature = "git")]
+
+mod common;
+
+use std::sync::atomic::{AtomicUsize, Ordering};
+use std::sync::{Arc, Mutex};
+
+use intent_verification::{
+    AnalysisObserver, AnalysisOptions, AnalysisOutcome, CancellationToken, FileAnalysisResult,
+    ObserverHandle, ProgressHandler, ProgressStage, verify_intent_with_options,
+};
+
+/// Create a local repository where the second commit adds three files
+fn init_local_repo() -> (String, String, String) {
+    let path = common::unique_path("checkpoint_test_repo");
+    let first = common::commit_files(&path, "readme", &[("README.md", "# Math\n")]);
+    let second = common::commit_files(
+        &path,
+        "math",
+        &[
+            (
+                "src/add.rs",
+                "pub fn add(a: i32, b: i32) -> i32 {\n    a + b\n}\n",
+            ),
+            (
+                "src/sub.rs",
+                "pub fn sub(a: i32, b: i32) -> i32 {\n    a - b\n}\n",
+            ),
+            (
+                "src/mul.rs",
+                "pub fn mul(a: i32, b: i32) -> i32 {\n    a * b\n}\n",
+            ),
+        ],
+    );
+    (path, first, second)
+}
+
+/// Serve chat completions on a local port, answering every request with the same JSON and
+/// counting the requests
+fn start_model() -> (String, Arc<AtomicUsize>) {
+    use std::io::{Read, Write};
+
+    let listener = std::net::TcpListener::bind("127.0.0.1:0").unwrap();
+    let url = format!("http://{}/v1", listener.local_addr().unwrap());
+    let requests = Arc::new(AtomicUsize::new(0));
+    let counter = requests.clone();
+
+    let content = serde_json::json!({
+        "functions": ["add"],
+        "files": ["src/add.rs"],
+        "supports_intent": true,
+        "relevance": "required",
+        "reasoning": "The arithmetic is implemented",
+        "relevant_changes": [],
+        "locations": [],
+        "confidence": 0.9
+    })
+    .to_string();
+    let body = serde_json::json!({
+        "id": "chatcmpl-test",
+        "object": "chat.completion",
+        "created": 0,
+        "model": "test-model",
+        "choices": [{
+            "index": 0,
+            "message": {"role": "assistant", "content": content},
+            "finish_reason": "stop"
+        }]
+    })
+    .to_string();
+
+    std::thread::spawn(move || {
+        for stream in listener.incoming() {
+            let mut stream = stream.unwrap();
+            let mut request = Vec::new();
+            let mut buffer = [0u8; 4096];
+            loop {
+                let n = stream.read(&mut buffer).unwrap();
+                request.extend_from_slice(&buffer[..n]);
+                let text = String::from_utf8_lossy(&request).to_string();
+                if let Some((headers, rest)) = text.split_once("\r\n\r\n") {
+                    let length = headers
+                        .lines()
+                        .find_map(|line| {
+                            line.to_lowercase()
+                                .strip_prefix("content-length:")
+                                .map(|v| v.trim().parse::<usize>().unwrap())
+                        })
+                        .unwrap_or(0);
+                    if rest.len() >= length || n == 0 {
+                        break;
+                    }
+                }
+            }
+            counter.fetch_add(1, Ordering::SeqCst);
+            let response = format!(
+                "HTTP/1.1 200 OK\r\ncontent-type: application/json\r\nconnection: close\r\ncontent-length: {}\r\n\r\n{}",
+                body.len(),
+                body
+            );
+            stream.write_all(response.as_bytes()).unwrap();
+        }
+    });
+
+    (url, requests)
+}
+
+#[tokio::test(flavor = "current_thread")]
+async fn test_interrupted_verification_resumes_from_checkpoint() {
+    let (path, first, second) = init_local_repo();
+    let (url, requests) = start_model();
+    let checkpoint = format!("{}_checkpoint.json", path);
+
+    // Interrupt the first run once one file has been analyzed
+    let cancellation = CancellationToken::new();
+    let token = cancellation.clone();
+    let options = AnalysisOptions {
+        checkpoint: Some(checkpoint.clone()),
+        cancellation: Some(cancellation),
+        progress: Some(ProgressHandler::new(move |progress| {
+            if progress.stage == ProgressStage::AnalyzingFiles && progress.files_done == 1 {
+                token.cancel();
+            }
+        })),
+        ..Default::default()
+    };
+    let interrupted = verify_intent_with_options(
+        &path,
+        &second,
+        &path,
+        &first,
+        &second,
+        "Make the calculator handle arithmetic",
+        "test-key",
+        None,
+        Some(&url),
+        &options,
+    )
+    .await;
+    assert!(interrupted.is_err(), "The first run should be cancelled");
+    let first_run_requests = requests.load(Ordering::SeqCst);
+    println!("\n💾 Interrupted after {} request(s)", first_run_requests);
+    assert_eq!(first_run_requests, 2, "Extraction and one file analysis");
+    assert!(
+        std::path::Path::new(&checkpoint).exists(),
+        "The checkpoint should survive the interruption"
+    );
+
+    let options = AnalysisOptions {
+        checkpoint: Some(checkpoint.clone()),
+        ..Default::default()
+    };
+    let resumed = verify_intent_with_options(
+        &path,
+        &second,
+        &path,
+        &first,
+        &second,
+        "Make the calculator handle arithmetic",
+        "test-key",
+        None,
+        Some(&url),
+        &options,
+    )
+    .await
+    .expect("The resumed run should succeed");
+
+    let resumed_requests = requests.load(Ordering::SeqCst) - first_run_requests;
+    println!(
+        "\n▶️ Resumed with {} request(s), reusing {:?}",
+        resumed_requests, resumed.metadata.reused_analyses
+    );
+    assert_eq!(
+        resumed_requests, 3,
+        "Only the two remaining files and the assessment should reach the model"
+    );
+    assert_eq!(resumed.metadata.reused_analyses.len(), 1);
+    assert_eq!(resumed.files_analyzed.len(), 3);
+    assert!(!resumed.is_partial, "{:?}", resumed.warnings);
+    assert!(
+        !std::path::Path::new(&checkpoint).exists(),
+        "A completed verification should remove its checkpoint"
+    );
+
+    let _ = std::fs::remove_dir_all(&path);
+}
+
+/// Collects the outcome of every file result
+#[derive(Default)]
+struct Outcomes(Mutex<Vec<(String, AnalysisOutcome, bool)>>);
+
+impl AnalysisObserver for Outcomes {
+    fn on_file_result(&self, result: &FileAnalysisResult) {
+        self.0.lock().unwrap().push((
+            result.analysis.file_path.clone(),
+            result.analysis.outcome,
+            result.analysis.supports_intent,
+        ));
+    }
+}
+
+#[tokio::test(flavor = "current_thread")]
+async fn test_files_skipped_by_cancellation_are_marked_cancelled() {
+    let (path, first, second) = init_local_repo();
+    let (url, _requests) = start_model();
+
+    let cancellation = CancellationToken::new();
+    let token = cancellation.clone();
+    let outcomes = Arc::new(Outcomes::default());
+    let options = AnalysisOptions {
+        cancellation: Some(cancellation),
+        observer: Some(ObserverHandle::new(outcomes.clone())),
+        progress: Some(ProgressHandler::new(move |progress| {
+            if progress.stage == ProgressStage::AnalyzingFiles && progress.files_done == 1 {
+                token.cancel();
+            }
+        })),
+        ..Default::default()
+    };
+    let interrupted = verify_intent_with_options(
+        &path,
+        &second,
+        &path,
+        &first,
+        &second,
+        "Make the calculator handle arithmetic",
+        "test-key",
+        None,
+        Some(&url),
+        &options,
+    )
+    .await;
+    assert!(interrupted.is_err(), "The run should be cancelled");
+
+    let outcomes = outcomes.0.lock().unwrap().clone();
+    println!("\n🛑 File results: {:?}", outcomes);
+    assert_eq!(outcomes.len(), 3);
+    let analyzed: Vec<_> = outcomes
+        .iter()
+        .filter(|(_, outcome, _)| *outcome == AnalysisOutcome::Analyzed)
+        .collect();
+    assert_eq!(analyzed.len(), 1, "Only the first file reached the model");
+    assert!(analyzed[0].2, "The analyzed file supports the intent");
+    for (file, outcome, supports_intent) in &outcomes {
+        if *outcome != AnalysisOutcome::Analyzed {
+            assert_eq!(*outcome, AnalysisOutcome::Cancelled, "{}", file);
+            assert!(!supports_intent, "{}", file);
+        }
+    }
+
+    let _ = std::fs::remove_dir_all(&path);
+}
+
+#[tokio::test(flavor = "current_thread")]
+async fn test_dry_run_ignores_checkpoint() {
+    let (path, first, second) = init_local_repo();
+    let checkpoint = format!("{}_checkpoint.json", path);
+    let options = AnalysisOptions {
+        dry_run: true,
+        checkpoint: Some(checkpoint.clone()),
+        ..Default::default()
+    };
+
+    verify_intent_with_options(
+        &path,
+        &second,
+        &path,
+        &first,
+        &second,
+        "Implement add() in src/add.rs",
+        "unused",
+        None,
+        None,
+        &options,
+    )
+    .await
+    .expect("Dry runs should succeed offline");
+    assert!(
+        !std::path::Path::new(&checkpoint).exists(),
+        "Dry runs have nothing to checkpoint"
+    );
+
+    let _ = std::fs::remove_dir_all(&path);
+}
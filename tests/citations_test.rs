@@ -0,0 +1,104 @@
+use intent_verification::{
+    AnalysisOptions, LlmClient, MockProvider, RepoSnapshot, WarningKind, render_markdown,
+    verify_intent_with_snapshots,
+};
+
+#[tokio::test(flavor = "current_thread")]
+async fn test_citations_are_checked_against_the_files() {
+    let base = RepoSnapshot::from_files([(
+        "src/lib.rs",
+        "pub fn sum(a: i32, b: i32) -> i32 {\n    todo!()\n}\n",
+    )]);
+    let head = RepoSnapshot::from_files([
+        (
+            "src/lib.rs",
+            "/// Adds two numbers\npub fn sum(a: i32, b: i32) -> i32 {\n    a + b\n}\n",
+        ),
+        (
+            "tests/sum.rs",
+            "#[test]\nfn test_sum() {\n    assert_eq!(sum(2, 3), 5);\n}\n",
+        ),
+    ]);
+    let reply = serde_json::json!({
+        "supports_intent": true,
+        "reasoning": "sum returns a + b, which the test expects",
+        "relevant_changes": ["a + b"],
+        "citations": [
+            {"claim": "sum returns a + b", "file": "src/lib.rs", "start_line": 3, "end_line": 3, "quote": "a + b"},
+            {"claim": "the signature is unchanged", "file": "src/lib.rs", "start_line": 1, "end_line": 1, "quote": "pub fn sum(a: i32, b: i32) -> i32"},
+            {"claim": "the test expects 5", "file": "tests/sum.rs", "start_line": 3, "end_line": 3, "quote": "assert_eq!(sum(2, 3), 5);"},
+            {"claim": "overflow is checked", "file": "src/lib.rs", "start_line": 3, "end_line": 3, "quote": "a.checked_add(b)"},
+            {"claim": "no quote"}
+        ]
+    });
+    let mock = MockProvider::new()
+        .respond_when(
+            "Extract from the following prompt",
+            r#"{"functions": [], "files": ["tests/sum.rs"]}"#,
+        )
+        .respond_when("SOLUTION FILE: src/lib.rs", &reply.to_string())
+        .respond_when(
+            "STEP 2",
+            r#"{"supports_intent": true, "reasoning": "test", "relevant_changes": []}"#,
+        )
+        .respond("Assessment");
+    let options = AnalysisOptions {
+        llm_client: Some(LlmClient::mock(mock.clone())),
+        ..Default::default()
+    };
+
+    let result = verify_intent_with_snapshots(
+        &head,
+        &base,
+        &head,
+        "The sum function adds two numbers",
+        "",
+        None,
+        None,
+        &options,
+    )
+    .await
+    .unwrap();
+    assert!(
+        mock.calls()
+            .iter()
+            .any(|call| call.prompt().contains("citations (array)")),
+        "The prompt asks for citations"
+    );
+
+    let analysis = result
+        .files_analyzed
+        .iter()
+        .find(|fa| fa.file_path == "src/lib.rs")
+        .unwrap();
+    let citations: Vec<(&str, usize, usize, bool)> = analysis
+        .citations
+        .iter()
+        .map(|c| (c.file_path.as_str(), c.start_line, c.end_line, c.verified))
+        .collect();
+    assert_eq!(
+        citations,
+        vec![
+            ("src/lib.rs", 3, 3, true),
+            ("src/lib.rs", 2, 2, true),
+            ("tests/sum.rs", 3, 3, true),
+            ("src/lib.rs", 3, 3, false),
+        ],
+        "Off lines are corrected, made-up quotes are flagged and empty ones dropped"
+    );
+    assert_eq!(analysis.citations[3].claim, "overflow is checked");
+
+    let warning = result
+        .warnings
+        .iter()
+        .find(|w| w.file_path.as_deref() == Some("src/lib.rs"))
+        .expect("The hallucinated quote is reported");
+    assert_eq!(warning.kind, WarningKind::ResponseParsing);
+    assert!(warning.message.contains("1 of 4 citations"));
+
+    let markdown = render_markdown(&result);
+    assert!(markdown.contains("`src/lib.rs:2-2` the signature is unchanged"));
+    assert!(markdown.contains("⚠️ unverified `src/lib.rs:3-3` overflow is checked"));
+
+    println!("\n✅ {}", warning.message);
+}
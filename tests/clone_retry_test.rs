@@ -0,0 +1,223 @@
+#![cfg(feature = "git")]
+
+use std::io::{BufRead, BufReader, Write};
+use std::net::TcpListener;
+use std::sync::Arc;
+use std::sync::atomic::{AtomicU32, Ordering};
+use std::time::Duration;
+
+use intent_verification::{
+    AnalysisObserver, AnalysisOptions, CloneRetryConfig, GitCredentials, GitVersionControl,
+    ObserverHandle, VersionControl, is_transient,
+};
+
+#[derive(Default)]
+struct RetryCounter {
+    retries: AtomicU32,
+}
+
+impl AnalysisObserver for RetryCounter {
+    fn on_clone_retry(&self, _repo_url: &str, attempt: u32, error: &git2::Error) {
+        assert!(is_transient(error));
+        assert_eq!(self.retries.fetch_add(1, Ordering::SeqCst) + 1, attempt);
+    }
+}
+
+/// A git server answering every request with `status`
+fn http_server(status: &'static str) -> String {
+    let listener = TcpListener::bind("127.0.0.1:0").unwrap();
+    let url = format!("http://{}/repo.git", listener.local_addr().unwrap());
+    std::thread::spawn(move || {
+        for mut stream in listener.incoming().flatten() {
+            // Read the request headers before answering
+            let mut reader = BufReader::new(&stream);
+            let mut line = String::new();
+            while reader.read_line(&mut line).is_ok_and(|read| read > 2) {
+                line.clear();
+            }
+            let response = format!(
+                "HTTP/1.1 {}\r\nContent-Length: 0\r\nConnection: close\r\n\r\n",
+                status
+            );
+            stream.write_all(response.as_bytes()).ok();
+        }
+    });
+    url
+}
+
+fn clone_with_retries(url: &str) -> (Result<GitVersionControl, String>, u32) {
+    let counter = Arc::new(RetryCounter::default());
+    let options = AnalysisOptions {
+        clone_retry: Some(CloneRetryConfig {
+            max_attempts: 3,
+            initial_backoff_ms: 1,
+            max_backoff_ms: 1,
+        }),
+        observer: Some(ObserverHandle::new(counter.clone())),
+        ..Default::default()
+    };
+    let vcs = GitVersionControl::open(url, &options).map_err(|e| e.to_string());
+    (vcs, counter.retries.load(Ordering::SeqCst))
+}
+
+#[test]
+fn test_backoff_doubles_up_to_the_limit() {
+    let config = CloneRetryConfig {
+        max_attempts: 6,
+        initial_backoff_ms: 500,
+        max_backoff_ms: 3_000,
+    };
+    let backoffs: Vec<_> = (1..=5).map(|attempt| config.backoff(attempt)).collect();
+    assert_eq!(
+        backoffs,
+        [500, 1_000, 2_000, 3_000, 3_000].map(Duration::from_millis)
+    );
+    assert_eq!(config.backoff(u32::MAX), Duration::from_millis(3_000));
+}
+
+#[test]
+fn test_transient_errors() {
+    use git2::{Error, ErrorClass, ErrorCode};
+
+    let error = |code, class, message| Error::new(code, class, message);
+    assert!(is_transient(&error(
+        ErrorCode::GenericError,
+        ErrorClass::Net,
+        "failed to resolve address for github.com"
+    )));
+    assert!(is_transient(&error(
+        ErrorCode::GenericError,
+        ErrorClass::Http,
+        "unexpected http status code: 502"
+    )));
+    assert!(is_transient(&error(
+        ErrorCode::GenericError,
+        ErrorClass::Http,
+        "unexpected http status code: 429"
+    )));
+    assert!(!is_transient(&error(
+        ErrorCode::GenericError,
+        ErrorClass::Http,
+        "unexpected http status code: 404"
+    )));
+    assert!(!is_transient(&error(
+        ErrorCode::Auth,
+        ErrorClass::Http,
+        "too many redirects or authentication replays"
+    )));
+    assert!(!is_transient(&error(
+        ErrorCode::NotFound,
+        ErrorClass::Os,
+        "failed to resolve path '/nonexistent'"
+    )));
+}
+
+#[test]
+fn test_server_errors_are_retried() {
+    let (vcs, retries) = clone_with_retries(&http_server("503 Service Unavailable"));
+    assert!(vcs.unwrap_err().contains("503"));
+    assert_eq!(retries, 2);
+}
+
+#[test]
+fn test_missing_repository_is_not_retried() {
+    let (vcs, retries) = clone_with_retries(&http_server("404 Not Found"));
+    assert!(vcs.unwrap_err().contains("404"));
+    assert_eq!(retries, 0);
+}
+
+#[test]
+fn test_credentials_are_sent_without_putting_them_in_the_url() {
+    let listener = TcpListener::bind("127.0.0.1:0").unwrap();
+    let url = format!("http://{}/repo.git", listener.local_addr().unwrap());
+    let (sender, authorizations) = std::sync::mpsc::channel();
+    std::thread::spawn(move || {
+        // Ask for credentials until some are sent, then report the repository as missing
+        for mut stream in listener.incoming().flatten() {
+            let mut reader = BufReader::new(&stream);
+            let mut line = String::new();
+            let mut authorization = None;
+            while reader.read_line(&mut line).is_ok_and(|read| read > 2) {
+                if let Some(value) = line.strip_prefix("Authorization: ") {
+                    authorization = Some(value.trim().to_string());
+                }
+                line.clear();
+            }
+            let status = match &authorization {
+                Some(_) => "404 Not Found",
+                None => "401 Unauthorized\r\nWWW-Authenticate: Basic realm=\"git\"",
+            };
+            let response = format!(
+                "HTTP/1.1 {}\r\nContent-Length: 0\r\nConnection: close\r\n\r\n",
+                status
+            );
+            stream.write_all(response.as_bytes()).ok();
+            if let Some(authorization) = authorization {
+                sender.send(authorization).ok();
+            }
+        }
+    });
+
+    let credentials = GitCredentials::token("t0ken");
+    assert!(!format!("{:?}", credentials).contains("t0ken"));
+    let options = AnalysisOptions {
+        git_credentials: Some(credentials),
+        ..Default::default()
+    };
+    let error = GitVersionControl::open(&url, &options)
+        .map(|_| ())
+        .unwrap_err()
+        .to_string();
+
+    assert_eq!(
+        authorizations.recv_timeout(Duration::from_secs(5)).unwrap(),
+        "Basic eC1hY2Nlc3MtdG9rZW46dDBrZW4=",
+        "Expected x-access-token:t0ken"
+    );
+    assert!(error.contains("404"), "Unexpected error: {}", error);
+    assert!(!error.contains("t0ken"));
+}
+
+#[test]
+fn test_clone_checks_out_the_default_branch() {
+    let path = std::env::temp_dir().join(format!(
+        "clone_retry_test_{}_{}",
+        std::process::id(),
+        std::time::SystemTime::now()
+            .duration_since(std::time::UNIX_EPOCH)
+            .unwrap()
+            .as_nanos()
+    ));
+    let repo = git2::Repository::init(&path).unwrap();
+    let signature = git2::Signature::now("Test", "test@example.com").unwrap();
+    std::fs::write(path.join("README.md"), "# Calc\n").unwrap();
+    let mut index = repo.index().unwrap();
+    index.add_path(std::path::Path::new("README.md")).unwrap();
+    let tree = repo.find_tree(index.write_tree().unwrap()).unwrap();
+    let commit = repo
+        .commit(
+            Some("refs/heads/trunk"),
+            &signature,
+            &signature,
+            "Initial commit",
+            &tree,
+            &[],
+        )
+        .unwrap();
+    repo.set_head("refs/heads/trunk").unwrap();
+    let commit = repo.find_commit(commit).unwrap();
+    repo.tag_lightweight("v1.0", commit.as_object(), false)
+        .unwrap();
+
+    let vcs = GitVersionControl::open(path.to_str().unwrap(), &AnalysisOptions::default());
+    std::fs::remove_dir_all(&path).ok();
+    let vcs = vcs.unwrap();
+    let id = commit.id().to_string();
+    assert_eq!(vcs.resolve_rev("HEAD").unwrap(), id);
+    assert_eq!(vcs.resolve_rev("trunk").unwrap(), id);
+    assert_eq!(vcs.resolve_rev("v1.0").unwrap(), id);
+    assert_eq!(
+        vcs.read_file_at("HEAD", "README.md").unwrap().as_deref(),
+        Some("# Calc\n")
+    );
+}
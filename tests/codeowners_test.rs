@@ -0,0 +1,217 @@
+#![cfg(feature = "git")]
+
+mod common;
+
+use intent_verification::{
+    AnalysisOptions, ChangeType, CodeOwners, FileIntentAnalysis, IntentVerificationResult,
+    RepoSnapshot, apply_code_owners, files_by_owner, render_markdown, verify_intent_with_options,
+    verify_intent_with_snapshots,
+};
+
+const CODEOWNERS: &str = "\
+# Default owners
+*                   @acme/core
+
+*.js                @acme/frontend   # inline comment
+/docs/              @acme/docs
+apps/               @acme/apps
+/scripts/*          @acme/devops @alice
+/vendor/            
+src/payments/**     @acme/payments security@acme.dev
+";
+
+fn analysis(path: &str, supports_intent: bool) -> FileIntentAnalysis {
+    FileIntentAnalysis {
+        file_path: path.to_string(),
+        change_type: ChangeType::Modified,
+        supports_intent,
+        reasoning: "reasoning".to_string(),
+        relevant_changes: vec![],
+        locations: vec![],
+        risk_score: 0.0,
+        relevance: None,
+        owners: vec![],
+        citations: vec![],
+        confidence: None,
+        support_probability: None,
+        outcome: Default::default(),
+    }
+}
+
+fn result(files: Vec<FileIntentAnalysis>) -> IntentVerificationResult {
+    let mut result: IntentVerificationResult = serde_json::from_str(
+        r#"{"is_intent_fulfilled":true,"confidence":0.9,"explanation":"1 out of 1 changed files support the test intent","files_analyzed":[],"overall_assessment":""}"#,
+    )
+    .unwrap();
+    result.files_analyzed = files;
+    result
+}
+
+/// Create a local repository with a CODEOWNERS file, a stub commit and an implementation commit
+fn init_local_repo() -> (String, String, String) {
+    let path = common::unique_path("codeowners_test_repo");
+    let first = common::commit_files(
+        &path,
+        "stub",
+        &[
+            (".github/CODEOWNERS", CODEOWNERS),
+            ("src/payments/charge.rs", "pub fn charge() {}\n"),
+            ("README.md", "# Payments\n"),
+        ],
+    );
+    let second = common::commit_files(
+        &path,
+        "implement",
+        &[
+            (
+                "src/payments/charge.rs",
+                "pub fn charge(amount: u64) -> u64 {\n    amount\n}\n",
+            ),
+            ("vendor/lib.rs", "pub fn vendored() {}\n"),
+        ],
+    );
+    (path, first, second)
+}
+
+#[test]
+fn test_owners_of_paths() {
+    let owners = CodeOwners::parse(CODEOWNERS);
+    let cases: &[(&str, &[&str])] = &[
+        ("Cargo.toml", &["@acme/core"]),
+        ("web/app.js", &["@acme/frontend"]),
+        ("docs/guide/intro.md", &["@acme/docs"]),
+        ("src/docs/notes.md", &["@acme/core"]),
+        ("apps/api/main.rs", &["@acme/apps"]),
+        ("services/apps/worker.rs", &["@acme/apps"]),
+        ("scripts/deploy.sh", &["@acme/devops", "@alice"]),
+        ("scripts/ci/build.sh", &["@acme/core"]),
+        ("vendor/lib.rs", &[]),
+        (
+            "src/payments/stripe/charge.rs",
+            &["@acme/payments", "security@acme.dev"],
+        ),
+    ];
+    for (path, expected) in cases {
+        println!("👥 {} -> {:?}", path, owners.owners_of(path));
+        assert_eq!(
+            owners.owners_of(path),
+            *expected,
+            "Unexpected owners for {}",
+            path
+        );
+    }
+
+    assert!(
+        CodeOwners::parse("").owners_of("src/lib.rs").is_empty(),
+        "No rules means no owners"
+    );
+}
+
+#[test]
+fn test_report_grouped_by_owner() {
+    let mut result = result(vec![
+        analysis("src/payments/charge.rs", true),
+        analysis("web/app.js", false),
+        analysis("vendor/lib.rs", false),
+        analysis("Cargo.toml", true),
+    ]);
+    apply_code_owners(&mut result, &CodeOwners::parse(CODEOWNERS));
+    assert_eq!(
+        result.files_analyzed[0].owners,
+        vec!["@acme/payments", "security@acme.dev"]
+    );
+
+    let groups: Vec<(Option<&str>, Vec<&str>)> = files_by_owner(&result)
+        .into_iter()
+        .map(|(owner, files)| {
+            (
+                owner,
+                files.iter().map(|fa| fa.file_path.as_str()).collect(),
+            )
+        })
+        .collect();
+    println!("\n👥 {:#?}", groups);
+    assert_eq!(
+        groups,
+        vec![
+            (Some("@acme/core"), vec!["Cargo.toml"]),
+            (Some("@acme/frontend"), vec!["web/app.js"]),
+            (Some("@acme/payments"), vec!["src/payments/charge.rs"]),
+            (Some("security@acme.dev"), vec!["src/payments/charge.rs"]),
+            (None, vec!["vendor/lib.rs"]),
+        ]
+    );
+
+    let markdown = render_markdown(&result);
+    assert!(markdown.contains("### Files by owner"));
+    assert!(markdown.contains(
+        "- **@acme/payments** (1 of 1 files support the intent): `src/payments/charge.rs`"
+    ));
+    assert!(markdown.contains("- **No owner** (0 of 1 files support the intent): `vendor/lib.rs`"));
+
+    let json = serde_json::to_value(&result).unwrap();
+    assert!(
+        json["files_analyzed"][2].get("owners").is_none(),
+        "Unowned files shouldn't serialize an empty owner list"
+    );
+}
+
+#[tokio::test]
+async fn test_verification_attaches_owners() {
+    let (path, first, second) = init_local_repo();
+    let options = AnalysisOptions {
+        dry_run: true,
+        code_owners: true,
+        ..Default::default()
+    };
+
+    let result = verify_intent_with_options(
+        &path,
+        &second,
+        &path,
+        &first,
+        &second,
+        "Charge the given amount",
+        "test-key",
+        None,
+        Some("http://127.0.0.1:9"),
+        &options,
+    )
+    .await
+    .expect("Dry run should succeed without the model");
+    let owners = |result: &IntentVerificationResult, file: &str| {
+        result
+            .files_analyzed
+            .iter()
+            .find(|fa| fa.file_path == file)
+            .map(|fa| fa.owners.clone())
+            .unwrap()
+    };
+    assert_eq!(
+        owners(&result, "src/payments/charge.rs"),
+        vec!["@acme/payments", "security@acme.dev"]
+    );
+    assert!(owners(&result, "vendor/lib.rs").is_empty());
+
+    let base = RepoSnapshot::from_commit(&path, &first).unwrap();
+    let head = RepoSnapshot::from_commit(&path, &second).unwrap();
+    let result = verify_intent_with_snapshots(
+        &head,
+        &base,
+        &head,
+        "Charge the given amount",
+        "test-key",
+        None,
+        None,
+        &options,
+    )
+    .await
+    .expect("Dry run should succeed without the model");
+    assert_eq!(
+        owners(&result, "src/payments/charge.rs"),
+        vec!["@acme/payments", "security@acme.dev"],
+        "Snapshots read CODEOWNERS from the head"
+    );
+
+    std::fs::remove_dir_all(&path).ok();
+}
@@ -0,0 +1,94 @@
+//! Helpers shared by the integration tests
+#![allow(dead_code)]
+
+use std::path::Path;
+use std::sync::atomic::{AtomicUsize, Ordering};
+
+/// `src/lib.rs` before the change: `sum` is a stub
+pub const SUM_STUB: &str = "pub fn sum(a: i32, b: i32) -> i32 {\n    todo!()\n}\n";
+/// `src/lib.rs` after the change: `sum` is implemented
+pub const SUM_IMPL: &str = "pub fn sum(a: i32, b: i32) -> i32 {\n    a + b\n}\n";
+
+/// A path under `/tmp` starting with `prefix` that no other test uses
+pub fn unique_path(prefix: &str) -> String {
+    static COUNTER: AtomicUsize = AtomicUsize::new(0);
+    format!(
+        "/tmp/{}_{}_{}_{}",
+        prefix,
+        std::process::id(),
+        std::time::SystemTime::now()
+            .duration_since(std::time::UNIX_EPOCH)
+            .unwrap()
+            .as_nanos(),
+        COUNTER.fetch_add(1, Ordering::Relaxed)
+    )
+}
+
+/// Commit `files` (path and content) on top of `HEAD` of the repository at `path`, creating
+/// the repository if needed; returns the commit id
+pub fn commit_files(path: &str, message: &str, files: &[(&str, &str)]) -> String {
+    let changes: Vec<(&str, Option<&str>)> = files
+        .iter()
+        .map(|(file, content)| (*file, Some(*content)))
+        .collect();
+    commit_changes(path, message, &changes)
+}
+
+/// Like [`commit_files`], but files with `None` content are deleted
+pub fn commit_changes(path: &str, message: &str, files: &[(&str, Option<&str>)]) -> String {
+    let repo = git2::Repository::open(path)
+        .or_else(|_| git2::Repository::init(path))
+        .unwrap();
+    let signature = git2::Signature::now("Test", "test@example.com").unwrap();
+
+    let mut index = repo.index().unwrap();
+    for (file, content) in files {
+        let full_path = Path::new(path).join(file);
+        match content {
+            Some(content) => {
+                std::fs::create_dir_all(full_path.parent().unwrap()).unwrap();
+                std::fs::write(&full_path, content).unwrap();
+                index.add_path(Path::new(file)).unwrap();
+            }
+            None => {
+                std::fs::remove_file(&full_path).unwrap();
+                index.remove_path(Path::new(file)).unwrap();
+            }
+        }
+    }
+    index.write().unwrap();
+    let tree = repo.find_tree(index.write_tree().unwrap()).unwrap();
+    let parents = match repo.head() {
+        Ok(head) => vec![head.peel_to_commit().unwrap()],
+        Err(_) => vec![],
+    };
+    let parents: Vec<&git2::Commit> = parents.iter().collect();
+    repo.commit(
+        Some("HEAD"),
+        &signature,
+        &signature,
+        message,
+        &tree,
+        &parents,
+    )
+    .unwrap()
+    .to_string()
+}
+
+/// A repository at `path` where `file` goes from `stub` to `implementation`, with its path
+/// and the two commit ids
+pub fn init_repo_with(
+    path: &str,
+    file: &str,
+    stub: &str,
+    implementation: &str,
+) -> (String, String, String) {
+    let first = commit_files(path, "stub", &[(file, stub)]);
+    let second = commit_files(path, "implement", &[(file, implementation)]);
+    (path.to_string(), first, second)
+}
+
+/// A fresh repository where `src/lib.rs` goes from [`SUM_STUB`] to [`SUM_IMPL`]
+pub fn init_local_repo() -> (String, String, String) {
+    init_repo_with(&unique_path("test_repo"), "src/lib.rs", SUM_STUB, SUM_IMPL)
+}
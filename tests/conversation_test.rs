@@ -0,0 +1,116 @@
+use intent_verification::{
+    AnalysisOptions, LlmClient, MockProvider, RepoSnapshot, VerificationConversation,
+    verify_intent_with_snapshots,
+};
+
+#[tokio::test(flavor = "current_thread")]
+async fn test_follow_ups_keep_the_context() {
+    let base = RepoSnapshot::from_files([(
+        "src/lib.rs",
+        "pub fn sum(a: i32, b: i32) -> i32 {\n    todo!()\n}\n",
+    )]);
+    let head = RepoSnapshot::from_files([(
+        "src/lib.rs",
+        "pub fn sum(a: i32, b: i32) -> i32 {\n    a - b\n}\n",
+    )]);
+    let mock = MockProvider::new()
+        .respond_when(
+            "Extract from the following prompt",
+            r#"{"functions": ["sum"], "files": []}"#,
+        )
+        .respond_when(
+            "STEP 2",
+            r#"{"supports_intent": false, "reasoning": "sum subtracts instead of adding", "relevant_changes": ["a - b"]}"#,
+        )
+        .respond_when(
+            "Is it only the operator",
+            r#"{"answer": "Yes, replacing - with + is enough", "files": ["./src/lib.rs", "src/other.rs"], "verdict_changed": false}"#,
+        )
+        .respond_when(
+            "Why doesn't",
+            r#"{"answer": "It returns a - b", "files": ["src/lib.rs"], "verdict_changed": false}"#,
+        )
+        .respond("Assessment");
+    let options = AnalysisOptions {
+        llm_client: Some(LlmClient::mock(mock.clone())),
+        ..Default::default()
+    };
+    let intent = "The sum function adds two numbers";
+    let result =
+        verify_intent_with_snapshots(&head, &base, &head, intent, "", None, None, &options)
+            .await
+            .unwrap();
+    assert!(!result.is_intent_fulfilled);
+    let calls_before = mock.call_count();
+
+    let mut conversation = VerificationConversation::new(result, intent, "", None, None, &options);
+    let first = conversation
+        .ask("Why doesn't src/lib.rs support the intent?")
+        .await
+        .unwrap();
+    assert_eq!(first.answer, "It returns a - b");
+    assert_eq!(first.files, vec!["src/lib.rs"]);
+    assert!(!first.verdict_changed);
+
+    let prompt = mock.calls()[calls_before].prompt();
+    assert!(prompt.contains(intent));
+    assert!(prompt.contains("NOT FULFILLED"));
+    assert!(
+        prompt.contains("sum subtracts instead of adding"),
+        "The file analyses are the context"
+    );
+
+    let second = conversation
+        .ask("Is it only the operator that is wrong?")
+        .await
+        .unwrap();
+    assert_eq!(
+        second.files,
+        vec!["src/lib.rs"],
+        "Only files of the result are kept"
+    );
+    let call = &mock.calls()[calls_before + 1];
+    assert_eq!(
+        call.messages.len(),
+        5,
+        "Context, acknowledgement, the first exchange and the new question"
+    );
+    assert_eq!(call.messages[3].role, "assistant");
+    assert!(call.messages[3].content.contains("It returns a - b"));
+    assert_eq!(conversation.answers(), &[first, second.clone()]);
+
+    println!("\n✅ {}", second.answer);
+}
+
+#[tokio::test(flavor = "current_thread")]
+async fn test_plain_text_answers() {
+    let mock = MockProvider::new().respond("The verdict rests on the subtraction.");
+    let options = AnalysisOptions {
+        llm_client: Some(LlmClient::mock(mock.clone())),
+        ..Default::default()
+    };
+    let result = serde_json::from_value(serde_json::json!({
+        "is_intent_fulfilled": false,
+        "confidence": 0.4,
+        "explanation": "No file supports the intent",
+        "files_analyzed": [],
+        "overall_assessment": ""
+    }))
+    .unwrap();
+    let mut conversation = VerificationConversation::new(
+        result,
+        "The sum function adds two numbers",
+        "",
+        None,
+        None,
+        &options,
+    );
+
+    let answer = conversation.ask("What decided the verdict?").await.unwrap();
+    assert_eq!(answer.answer, "The verdict rests on the subtraction.");
+    assert!(answer.files.is_empty());
+
+    assert!(conversation.ask("  ").await.is_err());
+    assert_eq!(mock.call_count(), 1, "Empty questions aren't sent");
+    println!("\n✅ {}", answer.answer);
+}
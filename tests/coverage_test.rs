@@ -0,0 +1,104 @@
+use intent_verification::{
+    ChangeType, FileChange, FileContent, FunctionContent, TestTargets, TestTargetsWithCode,
+    coverage_evidence,
+};
+
+fn targets_with_code() -> TestTargetsWithCode {
+    TestTargetsWithCode {
+        targets: TestTargets {
+            functions: vec!["test_sum".to_string(), "test_missing".to_string()],
+            files: vec!["tests/math_test.rs".to_string()],
+            ..Default::default()
+        },
+        file_contents: vec![FileContent {
+            path: "tests/math_test.rs".to_string(),
+            content: "#[test]\nfn test_mean() {\n    assert_eq!(mean(&[1, 3]), 2);\n}\n"
+                .to_string(),
+            error: None,
+        }],
+        function_contents: vec![
+            FunctionContent {
+                name: "test_sum".to_string(),
+                file_path: Some("tests/math_test.rs".to_string()),
+                line: None,
+                content: Some("fn test_sum() {\n    assert_eq!(sum(1, 2), 3);\n}".to_string()),
+                error: None,
+            },
+            FunctionContent {
+                name: "test_missing".to_string(),
+                file_path: None,
+                line: None,
+                content: None,
+                error: Some("Function not found".to_string()),
+            },
+        ],
+        tested_sources: vec![],
+    }
+}
+
+#[test]
+fn test_coverage_evidence() {
+    let file_changes = vec![
+        FileChange {
+            path: "src/math.rs".to_string(),
+            status: ChangeType::Modified,
+            content: Some(
+                "pub fn sum(a: i32, b: i32) -> i32 {\n    a + b\n}\n\n\
+                 pub fn mean(v: &[i32]) -> i32 {\n    sum(v[0], v[1]) / 2\n}\n\n\
+                 pub fn summary() -> String {\n    String::new()\n}\n\n\
+                 pub fn unchanged() {}\n"
+                    .to_string(),
+            ),
+            old_content: Some(
+                "pub fn sum(a: i32, b: i32) -> i32 {\n    todo!()\n}\n\n\
+                 pub fn unchanged() {}\n"
+                    .to_string(),
+            ),
+        },
+        FileChange {
+            path: "tests/math_test.rs".to_string(),
+            status: ChangeType::Added,
+            content: Some("fn helper() {}\n".to_string()),
+            old_content: None,
+        },
+    ];
+
+    let evidence = coverage_evidence(&targets_with_code(), &file_changes);
+    println!("\n🗺️ Coverage evidence: {:#?}", evidence);
+
+    let exercised = |function: &str| {
+        evidence
+            .functions
+            .iter()
+            .find(|f| f.function == function)
+            .unwrap_or_else(|| panic!("{} should be listed as changed", function))
+            .exercised_by
+            .clone()
+    };
+    assert_eq!(exercised("sum"), vec!["test_sum"]);
+    assert_eq!(exercised("mean"), vec!["tests/math_test.rs"]);
+    assert!(
+        exercised("summary").is_empty(),
+        "Only whole-word references should count"
+    );
+    assert!(
+        evidence.functions.iter().all(|f| f.function != "unchanged"),
+        "Unchanged functions should not be listed"
+    );
+    assert!(
+        evidence
+            .functions
+            .iter()
+            .all(|f| f.file_path == "src/math.rs"),
+        "Changes to test files are not checked for coverage"
+    );
+    assert_eq!(evidence.unexercised().count(), 1);
+    assert!((evidence.exercised_ratio - 2.0 / 3.0).abs() < 1e-6);
+}
+
+#[test]
+fn test_coverage_evidence_without_changed_functions() {
+    let evidence = coverage_evidence(&targets_with_code(), &[]);
+    assert!(evidence.functions.is_empty());
+    assert_eq!(evidence.exercised_ratio, 1.0);
+}
@@ -0,0 +1,85 @@
+use intent_verification::{
+    ChangeType, CriterionResult, FileChange, IntentVerificationResult, changes_as_diff,
+    merge_acceptance_criteria,
+};
+
+fn criterion(text: &str, passed: bool) -> CriterionResult {
+    CriterionResult {
+        criterion: text.to_string(),
+        passed,
+        evidence: format!("Evidence for {}", text),
+        files: vec!["src/lib.rs".to_string()],
+    }
+}
+
+fn sample_result() -> IntentVerificationResult {
+    serde_json::from_str(
+        r#"{"is_intent_fulfilled":true,"confidence":0.9,"explanation":"1 out of 1 changed files support the test intent","files_analyzed":[],"overall_assessment":""}"#,
+    )
+    .unwrap()
+}
+
+#[test]
+fn test_changes_as_diff() {
+    let changes = vec![
+        FileChange {
+            path: "src/lib.rs".to_string(),
+            status: ChangeType::Modified,
+            content: Some("pub fn sum(a: i32, b: i32) -> i32 {\n    a + b\n}\n".to_string()),
+            old_content: Some("pub fn sum(a: i32, b: i32) -> i32 {\n    todo!()\n}\n".to_string()),
+        },
+        FileChange {
+            path: "assets/logo.png".to_string(),
+            status: ChangeType::Added,
+            content: Some("[Binary file]".to_string()),
+            old_content: None,
+        },
+        FileChange {
+            path: "src/old.rs".to_string(),
+            status: ChangeType::Deleted,
+            content: None,
+            old_content: Some("pub fn old() {}\n".to_string()),
+        },
+    ];
+
+    let diff = changes_as_diff(&changes);
+    println!("\n📝 Diff:\n{}", diff);
+    assert!(diff.contains("--- a/src/lib.rs\n+++ b/src/lib.rs\n"));
+    assert!(diff.contains("-    todo!()\n+    a + b\n"));
+    assert!(diff.contains("Binary file assets/logo.png changed"));
+    assert!(diff.contains("-pub fn old() {}"));
+}
+
+#[test]
+fn test_merge_acceptance_criteria() {
+    let mut result = sample_result();
+    merge_acceptance_criteria(
+        &mut result,
+        vec![criterion("sum adds", true), criterion("sum wraps", true)],
+    );
+    assert!(result.is_intent_fulfilled);
+    assert!(
+        result
+            .explanation
+            .ends_with("; 2 of 2 acceptance criteria met")
+    );
+    assert_eq!(result.acceptance_criteria.len(), 2);
+
+    let mut result = sample_result();
+    merge_acceptance_criteria(
+        &mut result,
+        vec![criterion("sum adds", true), criterion("sum wraps", false)],
+    );
+    assert!(
+        !result.is_intent_fulfilled,
+        "An unmet criterion should fail the verdict"
+    );
+
+    let mut result = sample_result();
+    merge_acceptance_criteria(&mut result, vec![]);
+    assert!(result.is_intent_fulfilled);
+    assert!(
+        !result.explanation.contains("acceptance criteria"),
+        "Nothing should change when no criteria were checked"
+    );
+}
@@ -0,0 +1,181 @@
+#![cfg(feature = "git")]
+
+mod common;
+
+use intent_verification::{
+    AnalysisOptions, ChangeType, CrossRepoResult, FileIntentAnalysis, IntentVerificationResult,
+    RepoChanges, verify_cross_repo_intent,
+};
+
+/// Create a local repository with a stub commit and an implementation commit of one file
+fn init_local_repo(
+    name: &str,
+    file: &str,
+    stub: &str,
+    implementation: &str,
+) -> (String, String, String) {
+    common::init_repo_with(
+        &common::unique_path(&format!("cross_repo_test_{}", name)),
+        file,
+        stub,
+        implementation,
+    )
+}
+
+fn analysis(path: &str, supports_intent: bool, reasoning: &str) -> FileIntentAnalysis {
+    FileIntentAnalysis {
+        file_path: path.to_string(),
+        change_type: ChangeType::Modified,
+        supports_intent,
+        reasoning: reasoning.to_string(),
+        relevant_changes: vec![],
+        locations: vec![],
+        risk_score: 0.0,
+        relevance: None,
+        owners: vec![],
+        citations: vec![],
+        confidence: None,
+        support_probability: None,
+        outcome: Default::default(),
+    }
+}
+
+#[test]
+fn test_parse_repo_changes() {
+    let changes = RepoChanges::parse("https://github.com/acme/token-sdk.git@abc..def").unwrap();
+    assert_eq!(changes.name, "token-sdk");
+    assert_eq!(changes.repo_url, "https://github.com/acme/token-sdk.git");
+    assert_eq!(changes.commit1, "abc");
+    assert_eq!(changes.commit2, "def");
+
+    let named = RepoChanges::parse("contracts=git@github.com:acme/token.git@v1..main").unwrap();
+    assert_eq!(named.name, "contracts");
+    assert_eq!(named.repo_url, "git@github.com:acme/token.git");
+    assert_eq!(
+        named.qualified_path("src/Token.sol"),
+        "contracts/src/Token.sol"
+    );
+
+    assert_eq!(
+        RepoChanges::new("git@github.com:acme/token.git", "a", "b").name,
+        "token"
+    );
+    assert!(RepoChanges::parse("https://github.com/acme/token").is_err());
+    assert!(RepoChanges::parse("https://github.com/acme/token@abc").is_err());
+}
+
+#[test]
+fn test_contributions_per_repository() {
+    let mut result: IntentVerificationResult = serde_json::from_str(
+        r#"{"is_intent_fulfilled":true,"confidence":0.9,"explanation":"2 out of 3 changed files support the test intent","files_analyzed":[],"overall_assessment":""}"#,
+    )
+    .unwrap();
+    result.files_analyzed = vec![
+        analysis("contracts/src/Token.sol", true, "adds the burn function"),
+        analysis("contracts/README.md", false, "documentation only"),
+        analysis("sdk/src/token.ts", true, "exposes burn() to clients"),
+    ];
+    let repos = vec![
+        RepoChanges::parse("contracts=/tmp/contracts@a..b").unwrap(),
+        RepoChanges::parse("sdk=/tmp/sdk@a..b").unwrap(),
+        RepoChanges::parse("docs=/tmp/docs@a..b").unwrap(),
+    ];
+
+    let combined = CrossRepoResult::from_result(result, &repos);
+    for contribution in &combined.contributions {
+        println!("🔗 {}", contribution.summary);
+    }
+    assert!(combined.is_intent_fulfilled());
+    assert_eq!(combined.contributions[0].files_changed, 2);
+    assert_eq!(
+        combined.contributions[0].supporting_files,
+        vec!["contracts/src/Token.sol"]
+    );
+    assert!(
+        combined.contributions[1]
+            .summary
+            .contains("sdk/src/token.ts (exposes burn() to clients)"),
+        "The summary should explain what the repository contributes"
+    );
+    assert_eq!(combined.contributions[2].summary, "docs: no changes");
+    assert_eq!(
+        combined
+            .idle_repos()
+            .map(|c| c.name.as_str())
+            .collect::<Vec<_>>(),
+        vec!["docs"]
+    );
+}
+
+#[tokio::test]
+async fn test_changes_of_all_repositories_are_analyzed_together() {
+    let (contracts, c1, c2) = init_local_repo(
+        "contracts",
+        "src/lib.rs",
+        "pub fn burn(amount: u64) {\n    todo!()\n}\n",
+        "pub fn burn(amount: u64) {\n    SUPPLY.fetch_sub(amount);\n}\n",
+    );
+    let (sdk, s1, s2) = init_local_repo(
+        "sdk",
+        "src/lib.rs",
+        "pub fn burn_tokens() {}\n",
+        "pub fn burn_tokens(amount: u64) {\n    contracts::burn(amount)\n}\n",
+    );
+    let options = AnalysisOptions {
+        dry_run: true,
+        ..Default::default()
+    };
+    let repos = vec![
+        RepoChanges::parse(&format!("contracts={}@{}..{}", contracts, c1, c2)).unwrap(),
+        RepoChanges::parse(&format!("sdk={}@{}..{}", sdk, s1, s2)).unwrap(),
+    ];
+
+    let combined = verify_cross_repo_intent(
+        &contracts,
+        &c2,
+        &repos,
+        "Holders can burn tokens through the SDK",
+        "test-key",
+        None,
+        Some("http://127.0.0.1:9"),
+        &options,
+    )
+    .await
+    .expect("Dry run should succeed without the model");
+
+    let paths: Vec<&str> = combined
+        .result
+        .files_analyzed
+        .iter()
+        .map(|a| a.file_path.as_str())
+        .collect();
+    println!("\n📝 Analyzed: {:?}", paths);
+    assert_eq!(paths, vec!["contracts/src/lib.rs", "sdk/src/lib.rs"]);
+    assert_eq!(combined.contributions.len(), 2);
+    assert!(combined.contributions.iter().all(|c| c.files_changed == 1));
+    assert!(
+        combined.result.prompts.iter().any(|p| p
+            .messages
+            .iter()
+            .any(|m| m.content.contains("sdk/src/lib.rs"))),
+        "The prompts should name each file with its repository"
+    );
+
+    let duplicate = vec![repos[0].clone(), repos[0].clone()];
+    let error = verify_cross_repo_intent(
+        &contracts,
+        &c2,
+        &duplicate,
+        "Holders can burn tokens",
+        "test-key",
+        None,
+        Some("http://127.0.0.1:9"),
+        &options,
+    )
+    .await
+    .unwrap_err();
+    assert!(error.to_string().contains("used twice"));
+
+    std::fs::remove_dir_all(&contracts).ok();
+    std::fs::remove_dir_all(&sdk).ok();
+}
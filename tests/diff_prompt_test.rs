@@ -0,0 +1,207 @@
+#![cfg(feature = "git")]
+
+mod common;
+
+use intent_verification::{
+    AnalysisCache, AnalysisOptions, ChangeType, FileChange, LlmClient, MockProvider, PromptStage,
+    RepoSnapshot, diff_hunks, verify_intent_with_options, verify_intent_with_snapshots,
+};
+
+/// A file of `n` numbered functions, with `changed` ones returning something else
+fn numbered_functions(n: usize, changed: &[usize]) -> String {
+    (1..=n)
+        .map(|i| {
+            let body = if changed.contains(&i) {
+                format!("{} * 2", i)
+            } else {
+                i.to_string()
+            };
+            format!("pub fn f{}() -> i32 {{\n    {}\n}}\n\n", i, body)
+        })
+        .collect()
+}
+
+/// Create a local repository where a commit modifies a long file in two places and adds one
+fn init_local_repo() -> (String, String, String) {
+    let path = common::unique_path("diff_prompt_test_repo");
+    let first = common::commit_files(
+        &path,
+        "numbers",
+        &[("src/numbers.rs", numbered_functions(40, &[]).as_str())],
+    );
+    let second = common::commit_files(
+        &path,
+        "double",
+        &[
+            ("src/numbers.rs", numbered_functions(40, &[3, 35]).as_str()),
+            (
+                "src/double.rs",
+                "pub fn double(x: i32) -> i32 {\n    x * 2\n}\n",
+            ),
+        ],
+    );
+    (path, first, second)
+}
+
+#[test]
+fn test_diff_hunks() {
+    let change = FileChange {
+        path: "src/numbers.rs".to_string(),
+        status: ChangeType::Modified,
+        content: Some(numbered_functions(40, &[3, 35])),
+        old_content: Some(numbered_functions(40, &[])),
+    };
+    let hunks = diff_hunks(&change, 3);
+    println!("\n🧩 {:#?}", hunks);
+    assert_eq!(
+        hunks.len(),
+        2,
+        "Changes far apart should give separate hunks"
+    );
+    assert!(hunks[0].starts_with("@@ -"));
+    assert!(hunks[0].contains("-    3\n+    3 * 2\n"));
+    assert!(
+        hunks[0].contains(" pub fn f3() -> i32 {\n"),
+        "Context is included"
+    );
+    assert!(
+        !hunks[0].contains("f10()"),
+        "Lines beyond the context are left out"
+    );
+    assert!(hunks[1].contains("+    35 * 2\n"));
+
+    let added = FileChange {
+        status: ChangeType::Added,
+        old_content: None,
+        ..change.clone()
+    };
+    assert!(
+        diff_hunks(&added, 3).is_empty(),
+        "Added files have no hunks"
+    );
+    let binary = FileChange {
+        content: Some("[Binary file]".to_string()),
+        ..change.clone()
+    };
+    assert!(
+        diff_hunks(&binary, 3).is_empty(),
+        "Binary files have no hunks"
+    );
+    let unchanged = FileChange {
+        content: change.old_content.clone(),
+        ..change
+    };
+    assert!(diff_hunks(&unchanged, 3).is_empty());
+}
+
+#[tokio::test]
+async fn test_modified_files_are_prompted_with_hunks() {
+    let (path, first, second) = init_local_repo();
+    let options = AnalysisOptions {
+        dry_run: true,
+        ..Default::default()
+    };
+
+    let result = verify_intent_with_options(
+        &path,
+        &second,
+        &path,
+        &first,
+        &second,
+        "Functions 3 and 35 should return twice their number",
+        "test-key",
+        None,
+        Some("http://127.0.0.1:9"),
+        &options,
+    )
+    .await
+    .expect("Dry run should succeed without the model");
+    let request = |file: &str| {
+        let prompt = result
+            .prompts
+            .iter()
+            .find(|p| p.stage == PromptStage::FileAnalysis && p.file_path.as_deref() == Some(file))
+            .unwrap();
+        prompt.messages.last().unwrap().content.clone()
+    };
+
+    let modified = request("src/numbers.rs");
+    println!("\n📝 Modified file request:\n{}", modified);
+    assert!(modified.contains("(changed hunks only, with 3 lines of context"));
+    assert!(modified.contains("+    3 * 2\n"));
+    assert!(modified.contains("+    35 * 2\n"));
+    assert!(
+        !modified.contains("pub fn f20()"),
+        "Unchanged code away from the hunks shouldn't be sent"
+    );
+
+    let added = request("src/double.rs");
+    assert!(!added.contains("changed hunks only"));
+    assert!(
+        added.contains("pub fn double(x: i32) -> i32 {\n    x * 2\n}"),
+        "Added files are sent whole"
+    );
+
+    std::fs::remove_dir_all(&path).ok();
+}
+
+#[tokio::test(flavor = "current_thread")]
+async fn test_cached_hunks_are_not_reused_for_another_base() {
+    let numbers = |changed: &[usize]| {
+        RepoSnapshot::from_files([("src/numbers.rs", numbered_functions(40, changed))])
+    };
+    let head = numbers(&[3, 35]);
+    let mock = MockProvider::new()
+        .respond_when(
+            "Extract from the following prompt",
+            r#"{"functions": ["f3", "f35"], "files": []}"#,
+        )
+        .respond_when(
+            "STEP 2",
+            r#"{"supports_intent": true, "reasoning": "doubles them", "relevant_changes": []}"#,
+        )
+        .respond("Assessment");
+    let options = AnalysisOptions {
+        llm_client: Some(LlmClient::mock(mock.clone())),
+        analysis_cache: Some(AnalysisCache::in_memory()),
+        ..Default::default()
+    };
+    let verify = async |base: &RepoSnapshot| {
+        verify_intent_with_snapshots(
+            &head,
+            base,
+            &head,
+            "Functions 3 and 35 should return twice their number",
+            "",
+            None,
+            None,
+            &options,
+        )
+        .await
+        .unwrap()
+    };
+    let hunk_prompts = || {
+        mock.calls()
+            .iter()
+            .map(|call| call.prompt())
+            .filter(|prompt| prompt.contains("changed hunks only"))
+            .collect::<Vec<_>>()
+    };
+
+    verify(&numbers(&[])).await;
+    // Same new content, but function 3 was already doubled on this base
+    let result = verify(&numbers(&[3])).await;
+
+    assert!(result.metadata.reused_analyses.is_empty());
+    let prompts = hunk_prompts();
+    assert_eq!(
+        prompts.len(),
+        2,
+        "The file should be analyzed for each base"
+    );
+    assert!(prompts[0].contains("+    3 * 2\n"));
+    assert!(
+        !prompts[1].contains("+    3 * 2\n") && prompts[1].contains("+    35 * 2\n"),
+        "The second analysis should see only the change from its own base"
+    );
+}
@@ -0,0 +1,125 @@
+use std::path::{Path, PathBuf};
+
+use intent_verification::{
+    AnalysisOptions, ChangeType, LlmClient, MockProvider, RepoSnapshot, binary_metadata,
+    diff_directories, verify_intent_with_snapshots,
+};
+
+const PNG: &[u8] = b"\x89PNG\r\n\x1a\n\0\0\0\rIHDR";
+
+fn temp_dir(name: &str) -> PathBuf {
+    std::env::temp_dir().join(format!(
+        "directory_test_{}_{}_{}",
+        name,
+        std::process::id(),
+        std::time::SystemTime::now()
+            .duration_since(std::time::UNIX_EPOCH)
+            .unwrap()
+            .as_nanos()
+    ))
+}
+
+fn write(dir: &Path, path: &str, content: &[u8]) {
+    let path = dir.join(path);
+    std::fs::create_dir_all(path.parent().unwrap()).unwrap();
+    std::fs::write(path, content).unwrap();
+}
+
+/// A release before and after `sum` was implemented, with a new logo and a removed note
+fn releases() -> (PathBuf, PathBuf) {
+    let (before, after) = (temp_dir("before"), temp_dir("after"));
+    write(
+        &before,
+        "src/lib.rs",
+        b"pub fn sum(a: i32, b: i32) -> i32 {\n    todo!()\n}\n",
+    );
+    write(&before, "NOTES.txt", b"Unreleased\n");
+    write(&before, "README.md", b"# Calc\n");
+    write(&before, ".git/HEAD", b"ref: refs/heads/main\n");
+    write(
+        &after,
+        "src/lib.rs",
+        b"pub fn sum(a: i32, b: i32) -> i32 {\n    a + b\n}\n",
+    );
+    write(&after, "README.md", b"# Calc\n");
+    write(&after, "assets/logo.png", PNG);
+    write(&after, ".git/HEAD", b"ref: refs/heads/release\n");
+    (before, after)
+}
+
+#[test]
+fn test_diff_directories() {
+    let (before, after) = releases();
+    let changes = diff_directories(&before, &after).unwrap();
+    std::fs::remove_dir_all(&before).ok();
+    std::fs::remove_dir_all(&after).ok();
+
+    let summary: Vec<(&str, ChangeType)> = changes
+        .iter()
+        .map(|change| (change.path.as_str(), change.status.clone()))
+        .collect();
+    assert_eq!(
+        summary,
+        vec![
+            ("NOTES.txt", ChangeType::Deleted),
+            ("assets/logo.png", ChangeType::Added),
+            ("src/lib.rs", ChangeType::Modified),
+        ],
+        "Unchanged files and .git are left out"
+    );
+    assert!(changes[2].content.as_deref().unwrap().contains("a + b"));
+    let logo = binary_metadata(changes[1].content.as_deref().unwrap()).unwrap();
+    assert_eq!(logo.mime_type, "image/png");
+    assert_eq!(logo.size, PNG.len() as u64);
+}
+
+#[test]
+fn test_missing_directory() {
+    let error = RepoSnapshot::from_directory(temp_dir("missing")).unwrap_err();
+    assert_eq!(error.kind(), std::io::ErrorKind::NotFound);
+    assert!(diff_directories(temp_dir("missing"), std::env::temp_dir()).is_err());
+}
+
+#[tokio::test(flavor = "current_thread")]
+async fn test_verify_directory_pair() {
+    let (before, after) = releases();
+    let base = RepoSnapshot::from_directory(&before).unwrap();
+    let head = RepoSnapshot::from_directory(&after).unwrap();
+    std::fs::remove_dir_all(&before).ok();
+    std::fs::remove_dir_all(&after).ok();
+
+    let mock = MockProvider::new()
+        .respond_when(
+            "Extract from the following prompt",
+            r#"{"functions": ["sum"], "files": []}"#,
+        )
+        .respond_when(
+            "STEP 2",
+            r#"{"supports_intent": true, "reasoning": "adds", "relevant_changes": []}"#,
+        )
+        .respond("Assessment");
+    let options = AnalysisOptions {
+        llm_client: Some(LlmClient::mock(mock)),
+        ..Default::default()
+    };
+    let result = verify_intent_with_snapshots(
+        &head,
+        &base,
+        &head,
+        "sum adds two numbers",
+        "",
+        None,
+        None,
+        &options,
+    )
+    .await
+    .unwrap();
+
+    assert!(
+        result
+            .files_analyzed
+            .iter()
+            .any(|analysis| analysis.file_path == "src/lib.rs" && analysis.supports_intent)
+    );
+    assert_eq!(result.binary_changes.len(), 1);
+}
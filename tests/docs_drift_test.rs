@@ -0,0 +1,107 @@
+use std::collections::BTreeMap;
+
+use intent_verification::{
+    ChangeType, DOCS_DRIFT_RULE, DocsDriftConfig, FileChange, detect_docs_drift,
+};
+
+fn lib_change() -> FileChange {
+    FileChange {
+        path: "src/lib.rs".to_string(),
+        status: ChangeType::Modified,
+        content: Some(
+            "/// Adds two numbers\npub fn sum(a: i64, b: i64) -> i64 {\n    a + b\n}\n\n\
+             /// Mean of the values\npub fn mean(v: &[i64]) -> i64 {\n    v.iter().sum::<i64>() / v.len() as i64\n}\n"
+                .to_string(),
+        ),
+        old_content: Some(
+            "/// Adds two numbers\npub fn sum(a: i32, b: i32) -> i32 {\n    a + b\n}\n\n\
+             /// Mean of the values\npub fn mean(v: &[i32]) -> i32 {\n    0\n}\n"
+                .to_string(),
+        ),
+    }
+}
+
+fn docs() -> BTreeMap<String, String> {
+    BTreeMap::from([
+        (
+            "README.md".to_string(),
+            "# Calc\n\nCall `sum(1, 2)` to add numbers. Sums are exact.\n".to_string(),
+        ),
+        (
+            "docs/guide.md".to_string(),
+            "Nothing about the changed functions here.\n".to_string(),
+        ),
+    ])
+}
+
+#[test]
+fn test_detect_docs_drift() {
+    let findings = detect_docs_drift(&[lib_change()], &docs(), &DocsDriftConfig::default());
+    println!("\n📚 Docs drift: {:#?}", findings);
+
+    assert!(findings.iter().all(|f| f.rule == DOCS_DRIFT_RULE));
+    let readme = findings
+        .iter()
+        .find(|f| f.file_path.as_deref() == Some("README.md"))
+        .expect("The README mentions the changed sum function");
+    assert_eq!(readme.snippet.as_deref(), Some("sum"));
+    assert!(
+        findings
+            .iter()
+            .all(|f| f.file_path.as_deref() != Some("docs/guide.md")),
+        "Docs that don't mention changed items are fine"
+    );
+
+    let doc_comments: Vec<_> = findings
+        .iter()
+        .filter(|f| f.file_path.as_deref() == Some("src/lib.rs"))
+        .filter_map(|f| f.snippet.as_deref())
+        .collect();
+    assert_eq!(doc_comments, vec!["sum", "mean"]);
+}
+
+#[test]
+fn test_docs_updated_with_the_changes_are_not_reported() {
+    let mut change = lib_change();
+    change.content = change.content.map(|c| {
+        c.replace("/// Adds two numbers", "/// Adds two 64-bit numbers")
+            .replace("/// Mean of the values", "/// Mean of the 64-bit values")
+    });
+    let readme = FileChange {
+        path: "README.md".to_string(),
+        status: ChangeType::Modified,
+        content: Some("Call `sum(1, 2)` to add 64-bit numbers.\n".to_string()),
+        old_content: Some("Call `sum(1, 2)` to add numbers.\n".to_string()),
+    };
+
+    let findings = detect_docs_drift(&[change, readme], &docs(), &DocsDriftConfig::default());
+    assert!(findings.is_empty(), "Unexpected findings: {:#?}", findings);
+}
+
+#[test]
+fn test_docs_drift_config() {
+    let config = DocsDriftConfig {
+        ignore: vec!["sum".to_string()],
+        check_doc_comments: false,
+        ..Default::default()
+    };
+    assert!(detect_docs_drift(&[lib_change()], &docs(), &config).is_empty());
+
+    let config = DocsDriftConfig::default();
+    assert!(config.is_doc_path("README.md"));
+    assert!(config.is_doc_path("docs/api/index.md"));
+    assert!(!config.is_doc_path("docs/diagram.png"));
+    assert!(!config.is_doc_path("src/README.md"));
+
+    let dir = std::env::temp_dir().join(format!("docs_drift_test_{}", std::process::id()));
+    std::fs::create_dir_all(dir.join("docs/api")).unwrap();
+    std::fs::write(dir.join("README.md"), "readme").unwrap();
+    std::fs::write(dir.join("docs/api/index.md"), "api").unwrap();
+    std::fs::write(dir.join("docs/logo.svg"), "<svg/>").unwrap();
+    let files = config.read_doc_files(&dir);
+    std::fs::remove_dir_all(&dir).ok();
+    assert_eq!(
+        files.keys().collect::<Vec<_>>(),
+        vec!["README.md", "docs/api/index.md"]
+    );
+}
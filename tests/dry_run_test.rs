@@ -0,0 +1,162 @@
+#![cfg(feature = "git")]
+
+mod common;
+
+use intent_verification::{
+    AnalysisOptions, PromptStage, TestTargets, estimate_tokens, verify_intent_with_options,
+};
+
+#[test]
+fn test_estimate_tokens() {
+    assert_eq!(estimate_tokens(""), 0);
+    assert_eq!(estimate_tokens("abcd"), 1);
+    assert_eq!(estimate_tokens("abcde"), 2);
+}
+
+#[tokio::test]
+async fn test_dry_run_builds_prompts_without_calling_the_model() {
+    let (path, first, second) = common::init_local_repo();
+    let options = AnalysisOptions {
+        dry_run: true,
+        ..Default::default()
+    };
+
+    // Any request to this endpoint would fail and show up as a warning
+    let result = verify_intent_with_options(
+        &path,
+        &second,
+        &path,
+        &first,
+        &second,
+        "The sum function should add two numbers",
+        "test-key",
+        None,
+        Some("http://127.0.0.1:9"),
+        &options,
+    )
+    .await
+    .expect("Dry run should succeed without the model");
+
+    println!(
+        "\n🧪 Dry run prompts: {}",
+        serde_json::to_string_pretty(&result.prompts).unwrap()
+    );
+
+    assert!(
+        result.warnings.is_empty(),
+        "No request should have been sent: {:?}",
+        result.warnings
+    );
+    let stages: Vec<PromptStage> = result.prompts.iter().map(|p| p.stage).collect();
+    assert_eq!(
+        stages,
+        vec![
+            PromptStage::TargetExtraction,
+            PromptStage::FileAnalysis,
+            PromptStage::OverallAssessment
+        ]
+    );
+
+    let file_prompt = &result.prompts[1];
+    assert_eq!(file_prompt.file_path.as_deref(), Some("src/lib.rs"));
+    assert_eq!(file_prompt.messages[0].role, "system");
+    assert!(
+        file_prompt
+            .messages
+            .iter()
+            .any(|m| m.role == "user" && m.content.contains("a + b")),
+        "The file analysis prompt should contain the changed code"
+    );
+    assert!(
+        result.prompts.iter().all(|p| p.estimated_tokens > 0),
+        "Every prompt should carry a token estimate"
+    );
+    assert!(!result.is_intent_fulfilled);
+}
+
+#[tokio::test]
+async fn test_reviewed_targets_skip_extraction() {
+    let (path, first, second) = common::init_local_repo();
+    let options = AnalysisOptions {
+        dry_run: true,
+        targets: Some(TestTargets {
+            functions: vec!["sum".to_string()],
+            files: vec!["src/lib.rs".to_string()],
+            ..Default::default()
+        }),
+        clarifications: vec!["Overflow should wrap around".to_string()],
+        ..Default::default()
+    };
+
+    let result = verify_intent_with_options(
+        &path,
+        &second,
+        &path,
+        &first,
+        &second,
+        "The sum function should add two numbers",
+        "test-key",
+        None,
+        Some("http://127.0.0.1:9"),
+        &options,
+    )
+    .await
+    .expect("Dry run should succeed without the model");
+
+    let stages: Vec<PromptStage> = result.prompts.iter().map(|p| p.stage).collect();
+    assert_eq!(
+        stages,
+        vec![PromptStage::FileAnalysis, PromptStage::OverallAssessment],
+        "Reviewed targets should replace the extraction step"
+    );
+    assert!(
+        result.prompts[0]
+            .messages
+            .iter()
+            .any(|m| m.content.contains("pub fn sum")),
+        "The reviewed targets should be resolved in the test repository"
+    );
+    assert!(
+        result.prompts.iter().all(|p| p
+            .messages
+            .iter()
+            .any(|m| m.content.contains("Overflow should wrap around"))),
+        "Every prompt should carry the clarifications"
+    );
+}
+
+#[tokio::test]
+async fn test_dry_run_records_the_acceptance_criteria_prompt() {
+    let (path, first, second) = common::init_local_repo();
+    let options = AnalysisOptions {
+        dry_run: true,
+        acceptance_criteria: true,
+        ..Default::default()
+    };
+
+    let result = verify_intent_with_options(
+        &path,
+        &second,
+        &path,
+        &first,
+        &second,
+        "The sum function should add two numbers",
+        "test-key",
+        None,
+        Some("http://127.0.0.1:9"),
+        &options,
+    )
+    .await
+    .expect("Dry run should succeed without the model");
+
+    let prompt = result
+        .prompts
+        .iter()
+        .find(|p| p.stage == PromptStage::AcceptanceCriteria)
+        .expect("The decomposition prompt should be recorded");
+    assert!(prompt.messages[0].content.contains("acceptance criteria"));
+    assert!(
+        result.acceptance_criteria.is_empty(),
+        "Criteria can't be checked without the model's decomposition"
+    );
+}
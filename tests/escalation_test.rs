@@ -0,0 +1,142 @@
+use intent_verification::{
+    ChangeLocation, ChangeType, DEFAULT_ESCALATION_CONFIDENCE, FileIntentAnalysis, Finding,
+    IntentVerificationResult, Severity, apply_escalation, escalation, render_markdown,
+};
+
+fn analysis(path: &str, supports_intent: bool, line: Option<usize>) -> FileIntentAnalysis {
+    FileIntentAnalysis {
+        file_path: path.to_string(),
+        change_type: ChangeType::Modified,
+        supports_intent,
+        reasoning: format!("Reasoning about {}", path),
+        relevant_changes: vec![],
+        locations: line
+            .map(|line| ChangeLocation {
+                file_path: path.to_string(),
+                start_line: line,
+                end_line: line + 2,
+                snippet: "a + b".to_string(),
+            })
+            .into_iter()
+            .collect(),
+        risk_score: 0.0,
+        relevance: None,
+        owners: vec![],
+        citations: vec![],
+        confidence: None,
+        support_probability: None,
+        outcome: Default::default(),
+    }
+}
+
+fn result(confidence: f32, files_analyzed: Vec<FileIntentAnalysis>) -> IntentVerificationResult {
+    let mut result: IntentVerificationResult = serde_json::from_str(
+        r#"{"is_intent_fulfilled":true,"confidence":0.9,"explanation":"1 out of 1 changed files support the test intent","files_analyzed":[],"overall_assessment":""}"#,
+    )
+    .unwrap();
+    result.confidence = confidence;
+    result.files_analyzed = files_analyzed;
+    result
+}
+
+#[test]
+fn test_confident_result_is_not_escalated() {
+    let result = result(0.9, vec![analysis("src/lib.rs", true, Some(3))]);
+    assert_eq!(escalation(&result, DEFAULT_ESCALATION_CONFIDENCE), None);
+}
+
+#[test]
+fn test_low_confidence_asks_about_each_uncertain_file() {
+    let result = result(
+        0.45,
+        vec![
+            analysis("src/lib.rs", true, Some(3)),
+            analysis("src/config.rs", false, Some(10)),
+            analysis("src/main.rs", true, None),
+        ],
+    );
+    let escalation = escalation(&result, DEFAULT_ESCALATION_CONFIDENCE).expect("Should escalate");
+    println!("\n🙋 Escalation: {:#?}", escalation);
+
+    assert_eq!(escalation.reasons.len(), 1);
+    assert!(escalation.reasons[0].contains("45%"));
+    assert_eq!(
+        escalation.questions.len(),
+        2,
+        "Supporting files pinned to lines need no question"
+    );
+
+    let config = &escalation.questions[0];
+    assert_eq!(config.file_path.as_deref(), Some("src/config.rs"));
+    assert!(config.question.contains("Reasoning about src/config.rs"));
+    let location = config
+        .location
+        .as_ref()
+        .expect("Question should point at code");
+    assert_eq!((location.start_line, location.end_line), (10, 12));
+
+    let main = &escalation.questions[1];
+    assert_eq!(main.file_path.as_deref(), Some("src/main.rs"));
+    assert!(main.question.starts_with("Which part of `src/main.rs`"));
+    assert_eq!(main.location, None);
+}
+
+#[test]
+fn test_conflicting_findings_are_escalated() {
+    let mut result = result(0.9, vec![analysis("src/db.rs", true, Some(1))]);
+    result.is_intent_fulfilled = false;
+    result.findings.push(Finding {
+        rule: "migrations/drop-table".to_string(),
+        severity: Severity::High,
+        file_path: Some("src/db.rs".to_string()),
+        line: Some(42),
+        snippet: Some("DROP TABLE users".to_string()),
+        message: "Dropping table `users` deletes its data irreversibly".to_string(),
+        suppressed: false,
+        cwe: None,
+        content_hash: None,
+    });
+    // Suppressed findings are accepted and don't need a reviewer
+    result.findings.push(Finding {
+        suppressed: true,
+        line: Some(7),
+        ..result.findings[0].clone()
+    });
+
+    apply_escalation(&mut result, DEFAULT_ESCALATION_CONFIDENCE);
+    let escalation = result.escalation.clone().expect("Should escalate");
+    assert_eq!(escalation.reasons.len(), 1);
+    assert!(escalation.reasons[0].contains("1 high-severity finding"));
+    assert_eq!(escalation.questions.len(), 1);
+    let question = &escalation.questions[0];
+    assert!(question.question.contains("migrations/drop-table"));
+    let location = question.location.as_ref().unwrap();
+    assert_eq!(location.start_line, 42);
+    assert_eq!(location.snippet, "DROP TABLE users");
+
+    let markdown = render_markdown(&result);
+    assert!(markdown.contains("### Needs human review"));
+    assert!(markdown.contains("(`src/db.rs:42-42`)"));
+}
+
+#[test]
+fn test_failed_file_analysis_is_escalated_once() {
+    let mut result = result(0.3, vec![analysis("src/lib.rs", false, None)]);
+    result.warnings = serde_json::from_str(
+        r#"[{"kind":"FileAnalysis","file_path":"src/lib.rs","message":"connection refused"}]"#,
+    )
+    .unwrap();
+
+    let escalation = escalation(&result, DEFAULT_ESCALATION_CONFIDENCE).expect("Should escalate");
+    assert_eq!(escalation.reasons.len(), 2);
+    assert_eq!(
+        escalation.questions.len(),
+        1,
+        "A file that failed to analyze gets one question"
+    );
+    assert!(
+        escalation.questions[0]
+            .question
+            .contains("connection refused")
+    );
+}
@@ -0,0 +1,180 @@
+#![cfg(feature = "git")]
+
+mod common;
+
+use intent_verification::{
+    AnalysisOptions, BatchJob, EvalCase, EvalCaseOutcome, EvalCorpus, EvalReport, PROMPT_VERSION,
+    run_eval,
+};
+
+/// Create a local repository with a stub commit and an implementation commit
+fn init_local_repo(dir: &str) -> (String, String, String) {
+    common::init_repo_with(
+        &format!("{}/repo", dir),
+        "src/lib.rs",
+        common::SUM_STUB,
+        common::SUM_IMPL,
+    )
+}
+
+fn temp_dir() -> String {
+    let dir = format!(
+        "/tmp/eval_test_{}_{}",
+        std::process::id(),
+        std::time::SystemTime::now()
+            .duration_since(std::time::UNIX_EPOCH)
+            .unwrap()
+            .as_nanos()
+    );
+    std::fs::create_dir_all(&dir).unwrap();
+    dir
+}
+
+fn outcome(name: &str, expected: bool, predicted: Option<bool>) -> EvalCaseOutcome {
+    EvalCaseOutcome {
+        name: name.to_string(),
+        expected,
+        predicted,
+        confidence: predicted.map(|_| 0.8),
+        error: predicted.is_none().then(|| "clone failed".to_string()),
+    }
+}
+
+#[test]
+fn test_corpus_formats() {
+    let json = r#"{
+        "cases": [
+            { "name": "sum", "repo_url": "https://github.com/acme/calc", "base": "a1", "head": "b2",
+              "intent": "Sum works", "expected": true }
+        ]
+    }"#;
+    let toml = r#"
+[[cases]]
+name = "sum"
+repo_url = "https://github.com/acme/calc"
+base = "a1"
+head = "b2"
+intent = "Sum works"
+expected = true
+"#;
+
+    let from_json = EvalCorpus::from_json(json).expect("Should parse JSON corpora");
+    let from_toml = EvalCorpus::from_toml(toml).expect("Should parse TOML corpora");
+    println!("\n📋 Corpus: {:#?}", from_json);
+
+    assert_eq!(from_json, from_toml);
+    assert_eq!(from_json.cases[0].job.intent, "Sum works");
+    assert!(from_json.cases[0].expected);
+    assert!(
+        EvalCorpus::from_json(
+            r#"{"cases": [{"repo_url": "x", "base": "a", "head": "b", "intent": "i"}]}"#
+        )
+        .is_err(),
+        "Cases without a label should be rejected"
+    );
+}
+
+#[test]
+fn test_report_metrics() {
+    let report = EvalReport::from_outcomes(
+        "gpt-4o",
+        PROMPT_VERSION,
+        vec![
+            outcome("tp 1", true, Some(true)),
+            outcome("tp 2", true, Some(true)),
+            outcome("fn", true, Some(false)),
+            outcome("fp", false, Some(true)),
+            outcome("tn", false, Some(false)),
+            outcome("error", true, None),
+        ],
+    );
+    println!("\n📊 Report: {:#?}", report);
+
+    assert_eq!(report.prompt_version, PROMPT_VERSION);
+    assert_eq!(report.total, 6);
+    assert_eq!(report.errors, 1);
+    assert_eq!(
+        (
+            report.true_positives,
+            report.false_positives,
+            report.true_negatives,
+            report.false_negatives
+        ),
+        (2, 1, 1, 1)
+    );
+    assert_eq!(
+        report.accuracy,
+        Some(0.6),
+        "Errors are left out of accuracy"
+    );
+    assert!((report.precision.unwrap() - 2.0 / 3.0).abs() < 1e-6);
+    assert!((report.recall.unwrap() - 2.0 / 3.0).abs() < 1e-6);
+    assert!((report.f1.unwrap() - 2.0 / 3.0).abs() < 1e-6);
+    assert_eq!(
+        report
+            .mismatches()
+            .map(|c| c.name.as_str())
+            .collect::<Vec<_>>(),
+        vec!["fn", "fp", "error"]
+    );
+
+    let empty = EvalReport::from_outcomes(
+        "gpt-4o",
+        PROMPT_VERSION,
+        vec![outcome("tn", false, Some(false))],
+    );
+    assert_eq!(empty.accuracy, Some(1.0));
+    assert_eq!(empty.precision, None, "No positive verdicts, so undefined");
+    assert_eq!(empty.recall, None);
+}
+
+#[tokio::test]
+async fn test_run_eval_pairs_verdicts_with_labels() {
+    let dir = temp_dir();
+    let (repo_path, first, second) = init_local_repo(&dir);
+    let case = |name: &str, repo_url: &str, expected: bool| EvalCase {
+        job: BatchJob {
+            name: Some(name.to_string()),
+            repo_url: repo_url.to_string(),
+            base: first.clone(),
+            head: second.clone(),
+            intent: "The sum function adds two numbers".to_string(),
+            test_repo_url: None,
+            test_commit: None,
+        },
+        expected,
+    };
+    let corpus = EvalCorpus {
+        concurrency: None,
+        cases: vec![
+            case("implemented", &repo_path, true),
+            case("missing", &format!("{}/missing", dir), false),
+        ],
+    };
+    let options = AnalysisOptions {
+        cache_dir: Some(dir.clone()),
+        ..Default::default()
+    };
+
+    // Nothing listens on port 9, so every file analysis fails and the verdict is negative
+    let report = run_eval(
+        &corpus,
+        "test-key",
+        Some("test-model"),
+        Some("http://127.0.0.1:9"),
+        &options,
+    )
+    .await;
+    println!("\n📊 Report: {:#?}", report);
+
+    assert_eq!(report.model, "test-model");
+    assert_eq!(report.total, 2);
+    assert_eq!(report.errors, 1);
+    assert_eq!(report.cases[0].name, "implemented");
+    assert_eq!(report.cases[0].predicted, Some(false));
+    assert_eq!(report.false_negatives, 1);
+    assert_eq!(report.accuracy, Some(0.0));
+    assert!(report.cases[1].error.is_some());
+
+    std::fs::remove_dir_all(&dir).ok();
+}
@@ -0,0 +1,67 @@
+#![cfg(feature = "git")]
+
+mod common;
+
+use intent_verification::{
+    AnalysisOptions, EVIDENCE_BUNDLE_VERSION, EvidenceBundle, EvidenceRecorder,
+    verify_intent_with_options,
+};
+
+#[tokio::test]
+async fn test_evidence_bundle() {
+    let (path, first, second) = common::init_local_repo();
+    let recorder = EvidenceRecorder::new();
+    let options = AnalysisOptions {
+        evidence: Some(recorder.clone()),
+        ..Default::default()
+    };
+
+    // The unreachable endpoint makes every model call fail fast
+    let result = verify_intent_with_options(
+        &path,
+        &second,
+        &path,
+        &first,
+        &second,
+        "The sum function should add two numbers",
+        "test-key",
+        None,
+        Some("http://127.0.0.1:9"),
+        &options,
+    )
+    .await
+    .expect("Model failures should degrade to warnings");
+
+    let bundle = recorder.bundle(&result);
+    println!(
+        "\n🧾 Evidence bundle: {}",
+        serde_json::to_string_pretty(&bundle.hashes).unwrap()
+    );
+
+    assert_eq!(bundle.bundle_version, EVIDENCE_BUNDLE_VERSION);
+    assert!(bundle.diff.contains("--- a/src/lib.rs"));
+    assert!(bundle.diff.contains("+    a + b"));
+    assert!(
+        bundle.targets.is_some(),
+        "The targets read for the run should be recorded"
+    );
+    assert_eq!(
+        bundle.exchanges.len(),
+        recorder.exchange_count(),
+        "Only completed exchanges are recorded"
+    );
+    assert!(bundle.verify_hashes());
+
+    let file = format!("{}/evidence.json", path);
+    bundle.save(&file).unwrap();
+    let mut loaded = EvidenceBundle::load(&file).unwrap();
+    assert!(loaded.verify_hashes(), "Hashes should survive a round trip");
+
+    loaded.result.is_intent_fulfilled = !loaded.result.is_intent_fulfilled;
+    assert!(
+        !loaded.verify_hashes(),
+        "Editing the verdict should break its hash"
+    );
+
+    std::fs::remove_dir_all(&path).ok();
+}
@@ -0,0 +1,606 @@
+#![cfg(feature = "git")]
+
+mod common;
+
+use intent_verification::{
+    AnalysisOptions, ExecutionConfig, IntentVerificationResult, TargetKind, TargetResolution,
+    TargetStatus, TestOutcome, TestRunResult, detect_test_command, merge_counterfactual_run,
+    merge_test_run, parse_test_counts, parse_test_outcomes, run_tests, verify_intent_with_options,
+};
+
+fn temp_dir(name: &str) -> std::path::PathBuf {
+    let dir = std::env::temp_dir().join(format!(
+        "execution_test_{}_{}_{}",
+        name,
+        std::process::id(),
+        std::time::SystemTime::now()
+            .duration_since(std::time::UNIX_EPOCH)
+            .unwrap()
+            .as_nanos()
+    ));
+    std::fs::create_dir_all(&dir).unwrap();
+    dir
+}
+
+/// Create a local repository with a stub commit and an implementation commit
+fn init_local_repo() -> (String, String, String) {
+    common::init_repo_with(
+        &temp_dir("repo").to_string_lossy(),
+        "src/lib.rs",
+        common::SUM_STUB,
+        common::SUM_IMPL,
+    )
+}
+
+/// Passes like `cargo test` when `src/lib.rs` implements the sum, fails like it otherwise
+const SUM_TEST: &str = "if grep -q 'a + b' src/lib.rs; \
+    then echo 'test result: ok. 1 passed; 0 failed'; \
+    else echo 'test result: FAILED. 0 passed; 1 failed'; exit 101; fi";
+
+/// Run `script` with `sh` as a local process
+fn sh(script: &str) -> ExecutionConfig {
+    ExecutionConfig {
+        command: Some(vec!["sh".to_string(), "-c".to_string(), script.to_string()]),
+        allow_unsandboxed: true,
+        ..Default::default()
+    }
+}
+
+fn sample_run(passed: bool) -> TestRunResult {
+    TestRunResult {
+        command: vec!["cargo".to_string(), "test".to_string()],
+        passed,
+        exit_code: Some(if passed { 0 } else { 101 }),
+        timed_out: false,
+        tests_passed: Some(passed as usize),
+        tests_failed: Some(!passed as usize),
+        output_tail: String::new(),
+        duration_ms: 10,
+        tests: vec![],
+    }
+}
+
+/// A run listing the outcome of each test in `tests`
+fn run_with(tests: &[(&str, bool)]) -> TestRunResult {
+    let tests: Vec<TestOutcome> = tests
+        .iter()
+        .map(|(name, passed)| TestOutcome {
+            name: name.to_string(),
+            passed: *passed,
+        })
+        .collect();
+    TestRunResult {
+        tests: tests.clone(),
+        ..sample_run(tests.iter().all(|test| test.passed))
+    }
+}
+
+/// A fulfilled result whose intent names the test function `name`
+fn result_naming(name: &str) -> IntentVerificationResult {
+    IntentVerificationResult {
+        target_resolution: vec![TargetResolution {
+            kind: TargetKind::Function,
+            name: name.to_string(),
+            status: TargetStatus::Resolved,
+            file_path: Some("tests/sum.rs".to_string()),
+            line: Some(1),
+            error: None,
+        }],
+        ..sample_result(true)
+    }
+}
+
+fn sample_result(is_intent_fulfilled: bool) -> IntentVerificationResult {
+    IntentVerificationResult {
+        is_intent_fulfilled,
+        confidence: 0.6,
+        explanation: "1 out of 1 changed files support the test intent".to_string(),
+        ..Default::default()
+    }
+}
+
+#[test]
+fn test_detect_test_command() {
+    let dir = temp_dir("detect");
+    assert_eq!(detect_test_command(&dir), None);
+
+    std::fs::write(dir.join("package.json"), "{}").unwrap();
+    assert_eq!(detect_test_command(&dir).unwrap(), vec!["npm", "test"]);
+
+    std::fs::write(dir.join("pyproject.toml"), "").unwrap();
+    assert_eq!(detect_test_command(&dir).unwrap(), vec!["pytest"]);
+
+    std::fs::write(dir.join("Cargo.toml"), "").unwrap();
+    assert_eq!(
+        detect_test_command(&dir).unwrap(),
+        vec!["cargo", "test"],
+        "Cargo projects take precedence"
+    );
+}
+
+#[test]
+fn test_parse_test_counts() {
+    let cargo = "running 2 tests\ntest result: ok. 2 passed; 0 failed; 0 ignored\n\
+                 running 3 tests\ntest result: FAILED. 1 passed; 2 failed; 0 ignored\n";
+    assert_eq!(parse_test_counts(cargo), (Some(3), Some(2)));
+
+    let pytest = "tests/test_sum.py .F.\n===== 2 passed, 1 failed in 0.12s =====\n";
+    assert_eq!(parse_test_counts(pytest), (Some(2), Some(1)));
+
+    let jest = "Test Suites: 1 passed, 1 total\nTests:       4 passed, 4 total\n";
+    assert_eq!(parse_test_counts(jest), (Some(4), Some(0)));
+
+    assert_eq!(parse_test_counts("Done."), (None, None));
+}
+
+#[test]
+fn test_parse_test_outcomes() {
+    let cargo = "running 3 tests\ntest tests::test_sum ... ok\ntest tests::test_overflow ... FAILED\n\
+                 test tests::test_slow ... ignored\n";
+    let pytest =
+        "tests/test_sum.py::test_add PASSED    [ 50%]\ntests/test_sum.py::test_sub FAILED [100%]\n";
+
+    let outcomes: Vec<(String, bool)> = parse_test_outcomes(&format!("{}{}", cargo, pytest))
+        .into_iter()
+        .map(|test| (test.name, test.passed))
+        .collect();
+    assert_eq!(
+        outcomes,
+        [
+            ("tests::test_sum", true),
+            ("tests::test_overflow", false),
+            ("tests/test_sum.py::test_add", true),
+            ("tests/test_sum.py::test_sub", false),
+        ]
+        .map(|(name, passed)| (name.to_string(), passed))
+    );
+    assert!(parse_test_outcomes("Done.").is_empty());
+}
+
+#[test]
+fn test_merge_test_run() {
+    let mut result = sample_result(true);
+    merge_test_run(&mut result, sample_run(true));
+    assert!(result.is_intent_fulfilled);
+    assert!(
+        (result.confidence - 0.8).abs() < 1e-6,
+        "Agreement should raise the confidence"
+    );
+    assert!(
+        result
+            .explanation
+            .ends_with("; the tests passed when run (`cargo test`)")
+    );
+
+    let mut result = sample_result(true);
+    merge_test_run(&mut result, sample_run(false));
+    assert!(
+        !result.is_intent_fulfilled,
+        "Failing tests should overrule the model"
+    );
+    assert!(result.test_run.is_some());
+
+    let mut result = sample_result(false);
+    merge_test_run(&mut result, sample_run(true));
+    assert!(
+        !result.is_intent_fulfilled,
+        "Passing tests alone should not make the intent fulfilled"
+    );
+    assert_eq!(result.confidence, 0.6);
+}
+
+#[test]
+fn test_merge_counterfactual_run() {
+    let mut result = sample_result(true);
+    merge_counterfactual_run(&mut result, sample_run(false));
+    assert!(result.is_intent_fulfilled);
+    assert!(
+        result
+            .explanation
+            .ends_with("; the tests failed without the solution")
+    );
+
+    let mut result = sample_result(true);
+    merge_counterfactual_run(&mut result, sample_run(true));
+    assert!(
+        !result.is_intent_fulfilled,
+        "Tests that pass without the solution don't show the intent is fulfilled"
+    );
+    assert!(result.test_run_without_solution.is_some());
+}
+
+#[test]
+fn test_inconclusive_runs_leave_the_verdict_alone() {
+    let runs = [
+        TestRunResult {
+            exit_code: Some(127),
+            output_tail: "sh: 1: pytest: not found".to_string(),
+            ..sample_run(false)
+        },
+        TestRunResult {
+            output_tail: "error: failed to get `serde` as a dependency of package `calc`\n\
+                          Caused by: Could not resolve host: index.crates.io"
+                .to_string(),
+            ..sample_run(false)
+        },
+        TestRunResult {
+            tests_passed: None,
+            tests_failed: None,
+            ..sample_run(false)
+        },
+    ];
+    for run in runs {
+        let reason = run
+            .inconclusive_reason()
+            .expect("The run should be inconclusive");
+        println!("\n🤷 {}", reason);
+
+        let mut result = sample_result(true);
+        merge_test_run(&mut result, run.clone());
+        assert!(
+            result.is_intent_fulfilled,
+            "A run that couldn't test anything should not overrule the model"
+        );
+        assert_eq!(result.confidence, 0.6);
+        assert!(result.is_partial);
+        assert!(result.warnings[0].message.contains(reason));
+        assert!(result.test_run.is_some());
+
+        let mut result = sample_result(true);
+        merge_counterfactual_run(&mut result, run);
+        assert!(result.is_intent_fulfilled);
+        assert!(
+            result.warnings[0]
+                .message
+                .starts_with("The tests without the solution were inconclusive")
+        );
+        assert!(result.test_run_without_solution.is_some());
+    }
+
+    let timed_out = TestRunResult {
+        timed_out: true,
+        exit_code: None,
+        tests_passed: None,
+        tests_failed: None,
+        ..sample_run(false)
+    };
+    assert_eq!(timed_out.inconclusive_reason(), None);
+    assert_eq!(sample_run(false).inconclusive_reason(), None);
+}
+
+#[test]
+fn test_counterfactual_compares_the_named_tests() {
+    let with_solution = run_with(&[("tests::test_sum", true), ("tests::test_other", true)]);
+
+    // The suite fails without the solution, but not because of the named test
+    let mut result = result_naming("test_sum");
+    merge_test_run(&mut result, with_solution.clone());
+    merge_counterfactual_run(
+        &mut result,
+        run_with(&[("tests::test_sum", true), ("tests::test_other", false)]),
+    );
+    assert!(
+        !result.is_intent_fulfilled,
+        "The named test passing without the solution shows nothing"
+    );
+    assert!(result.explanation.contains("none of the named tests"));
+
+    let mut result = result_naming("test_sum");
+    merge_test_run(&mut result, with_solution.clone());
+    merge_counterfactual_run(
+        &mut result,
+        run_with(&[("tests::test_sum", false), ("tests::test_other", true)]),
+    );
+    assert!(result.is_intent_fulfilled);
+    assert!(
+        result
+            .explanation
+            .ends_with("; 1 of 1 named tests failed without the solution and passed with it")
+    );
+
+    // Without outcomes for the named test, the whole suite decides as before
+    let mut result = result_naming("test_missing");
+    merge_test_run(&mut result, with_solution);
+    merge_counterfactual_run(&mut result, run_with(&[("tests::test_other", false)]));
+    assert!(result.is_intent_fulfilled);
+    assert!(
+        result
+            .explanation
+            .ends_with("; the tests failed without the solution")
+    );
+}
+
+#[tokio::test]
+async fn test_run_tests() {
+    let dir = temp_dir("run");
+
+    let run = run_tests(
+        &dir,
+        &sh("echo 'test result: ok. 2 passed; 0 failed; 0 ignored'"),
+    )
+    .await
+    .unwrap();
+    println!("\n🧪 Test run: {:#?}", run);
+    assert!(run.passed);
+    assert_eq!((run.tests_passed, run.tests_failed), (Some(2), Some(0)));
+    assert!(run.output_tail.contains("test result: ok"));
+
+    let run = run_tests(&dir, &sh("echo boom >&2; exit 3")).await.unwrap();
+    assert!(!run.passed);
+    assert_eq!(run.exit_code, Some(3));
+    assert_eq!(run.output_tail, "boom");
+    assert_eq!(
+        run.inconclusive_reason(),
+        Some("no test results were reported")
+    );
+
+    let run = run_tests(&dir, &sh("pytest-not-installed")).await.unwrap();
+    assert_eq!(run.exit_code, Some(127));
+    assert_eq!(
+        run.inconclusive_reason(),
+        Some("the test command wasn't found")
+    );
+
+    let run = run_tests(
+        &dir,
+        &ExecutionConfig {
+            timeout_secs: Some(1),
+            ..sh("sleep 10")
+        },
+    )
+    .await
+    .unwrap();
+    assert!(run.timed_out && !run.passed);
+
+    // SAFETY: no other test reads this variable
+    unsafe { std::env::set_var("IV_EXECUTION_TEST_SECRET", "s3cret") };
+    let run = run_tests(&dir, &sh("test -z \"$IV_EXECUTION_TEST_SECRET\""))
+        .await
+        .unwrap();
+    assert!(run.passed, "Secrets should not reach the test process");
+
+    assert!(
+        run_tests(&dir, &ExecutionConfig::default()).await.is_err(),
+        "A directory without a known project needs an explicit command"
+    );
+
+    let error = run_tests(
+        &dir,
+        &ExecutionConfig {
+            allow_unsandboxed: false,
+            ..sh("true")
+        },
+    )
+    .await
+    .unwrap_err();
+    assert!(
+        error.to_string().contains("outside a container"),
+        "Got: {}",
+        error
+    );
+}
+
+#[tokio::test]
+async fn test_container_is_limited_and_killed_on_timeout() {
+    let dir = temp_dir("container");
+    // Stands in for docker: logs its arguments and keeps `run` going until the timeout
+    let log = dir.join("docker.log");
+    let runtime = dir.join("fake-docker");
+    std::fs::write(
+        &runtime,
+        format!(
+            "#!/bin/sh\necho \"$@\" >> {}\n[ \"$1\" = run ] && sleep 10\nexit 0\n",
+            log.display()
+        ),
+    )
+    .unwrap();
+    std::fs::set_permissions(
+        &runtime,
+        std::os::unix::fs::PermissionsExt::from_mode(0o755),
+    )
+    .unwrap();
+
+    let run = run_tests(
+        &dir,
+        &ExecutionConfig {
+            command: Some(vec!["cargo".to_string(), "test".to_string()]),
+            timeout_secs: Some(1),
+            container_runtime: Some(runtime.to_string_lossy().to_string()),
+            ..Default::default()
+        },
+    )
+    .await
+    .unwrap();
+    assert!(run.timed_out);
+
+    let log = std::fs::read_to_string(&log).unwrap();
+    let calls: Vec<&str> = log.lines().collect();
+    println!("\n🐳 Container calls: {:#?}", calls);
+    assert_eq!(calls.len(), 2, "Expected `run`, then `kill`");
+    let name = calls[0]
+        .split_once("--name ")
+        .and_then(|(_, rest)| rest.split_whitespace().next())
+        .expect("The container should be named");
+    for flag in [
+        "--network none",
+        "--memory 2g",
+        "--cpus 2",
+        "--pids-limit 512",
+    ] {
+        assert!(calls[0].contains(flag), "Missing {}", flag);
+    }
+    assert!(
+        calls[0].ends_with(" rust:1 cargo test"),
+        "The default image for cargo should be used"
+    );
+    let owner = std::fs::metadata(&dir).unwrap();
+    assert!(
+        calls[0].contains(&format!(
+            "--user {}:{}",
+            std::os::unix::fs::MetadataExt::uid(&owner),
+            std::os::unix::fs::MetadataExt::gid(&owner)
+        )),
+        "The container should run as the owner of the checkout"
+    );
+    assert_eq!(calls[1], format!("kill {}", name));
+
+    std::fs::remove_dir_all(&dir).ok();
+}
+
+#[tokio::test]
+async fn test_container_leaves_removable_files() {
+    let available = std::process::Command::new("docker")
+        .arg("info")
+        .stdout(std::process::Stdio::null())
+        .stderr(std::process::Stdio::null())
+        .status()
+        .is_ok_and(|status| status.success());
+    if !available {
+        println!("Skipping test - no docker daemon available");
+        return;
+    }
+
+    let dir = temp_dir("real_container");
+    let run = run_tests(
+        &dir,
+        &ExecutionConfig {
+            command: Some(vec![
+                "sh".to_string(),
+                "-c".to_string(),
+                "mkdir target && touch target/out && echo 'test result: ok. 1 passed; 0 failed'"
+                    .to_string(),
+            ]),
+            container_image: Some("busybox".to_string()),
+            ..Default::default()
+        },
+    )
+    .await
+    .unwrap();
+    println!("\n🐳 Container run: {:#?}", run);
+    assert!(run.passed, "Test output: {}", run.output_tail);
+
+    let owner = std::fs::metadata(&dir).unwrap();
+    let created = std::fs::metadata(dir.join("target/out")).unwrap();
+    assert_eq!(
+        std::os::unix::fs::MetadataExt::uid(&created),
+        std::os::unix::fs::MetadataExt::uid(&owner),
+        "Files written in the container should belong to the checkout's owner"
+    );
+    std::fs::remove_dir_all(&dir).expect("The checkout should be removable");
+}
+
+#[tokio::test]
+async fn test_verify_intent_runs_tests_on_the_solution() {
+    let (path, first, second) = init_local_repo();
+    let options = AnalysisOptions {
+        execution: Some(sh(SUM_TEST)),
+        ..Default::default()
+    };
+
+    // The tests are taken from the stub commit, so they only pass with the solution applied
+    let result = verify_intent_with_options(
+        &path,
+        &first,
+        &path,
+        &first,
+        &second,
+        "The sum function should add two numbers",
+        "test-key",
+        None,
+        Some("http://127.0.0.1:9"),
+        &options,
+    )
+    .await
+    .unwrap();
+
+    let run = result.test_run.as_ref().expect("The tests should have run");
+    assert!(run.passed, "Test output: {}", run.output_tail);
+    assert!(result.explanation.contains("the tests passed when run"));
+}
+
+#[tokio::test]
+async fn test_metadata_duration_covers_the_test_run() {
+    let (path, first, second) = init_local_repo();
+    let options = AnalysisOptions {
+        execution: Some(sh("sleep 0.2")),
+        ..Default::default()
+    };
+
+    let result = verify_intent_with_options(
+        &path,
+        &first,
+        &path,
+        &first,
+        &second,
+        "The sum function should add two numbers",
+        "test-key",
+        None,
+        Some("http://127.0.0.1:9"),
+        &options,
+    )
+    .await
+    .unwrap();
+
+    let run = result.test_run.as_ref().expect("The tests should have run");
+    assert!(
+        result.metadata.duration_ms >= run.duration_ms,
+        "The run took {} ms but the metadata only covers {} ms",
+        run.duration_ms,
+        result.metadata.duration_ms
+    );
+}
+
+#[tokio::test]
+async fn test_counterfactual_run_without_the_solution() {
+    let (path, first, second) = init_local_repo();
+    let verify = |script: &str| {
+        let options = AnalysisOptions {
+            execution: Some(ExecutionConfig {
+                counterfactual: true,
+                ..sh(script)
+            }),
+            ..Default::default()
+        };
+        let (path, first, second) = (path.clone(), first.clone(), second.clone());
+        async move {
+            verify_intent_with_options(
+                &path,
+                &second,
+                &path,
+                &first,
+                &second,
+                "The sum function should add two numbers",
+                "test-key",
+                None,
+                Some("http://127.0.0.1:9"),
+                &options,
+            )
+            .await
+            .unwrap()
+        }
+    };
+
+    let result = verify(SUM_TEST).await;
+    let with = result.test_run.as_ref().expect("The tests should have run");
+    let without = result
+        .test_run_without_solution
+        .as_ref()
+        .expect("The tests should also have run without the solution");
+    println!("\n🔁 Without the solution: {:#?}", without);
+    assert!(
+        with.passed && !without.passed,
+        "Expected a fail → pass transition"
+    );
+    assert!(
+        result
+            .explanation
+            .ends_with("; the tests failed without the solution")
+    );
+
+    let result = verify("true").await;
+    assert!(result.test_run_without_solution.unwrap().passed);
+    assert!(
+        !result.is_intent_fulfilled,
+        "Trivially green tests should not count as fulfilling the intent"
+    );
+}
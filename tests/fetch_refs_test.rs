@@ -0,0 +1,107 @@
+#![cfg(feature = "git")]
+
+mod common;
+
+use intent_verification::TestTargets;
+use intent_verification::{ValidationError, get_git_changed_files, read_test_targets_code};
+
+/// Create a local repository with a commit on `main`, one on a `feature` branch, and one only
+/// reachable from a pull request ref, like GitHub's `refs/pull/<n>/head`
+fn init_local_repo() -> (String, String, String, String) {
+    let path = common::unique_path("fetch_refs_test_repo");
+    let base = common::commit_files(&path, "commit", &[("src/lib.rs", common::SUM_STUB)]);
+    let feature = common::commit_files(&path, "commit", &[("src/lib.rs", common::SUM_IMPL)]);
+
+    let repo = git2::Repository::open(&path).unwrap();
+    let base_commit = repo.revparse_single(&base).unwrap();
+    let feature_commit = repo.find_commit(git2::Oid::from_str(&feature).unwrap());
+    repo.branch("feature", &feature_commit.unwrap(), false)
+        .unwrap();
+    repo.reset(&base_commit, git2::ResetType::Hard, None)
+        .unwrap();
+
+    let pull = common::commit_files(
+        &path,
+        "commit",
+        &[(
+            "src/lib.rs",
+            "pub fn sum(a: i32, b: i32) -> i32 {\n    b + a\n}\n",
+        )],
+    );
+    repo.reference(
+        "refs/pull/1/head",
+        git2::Oid::from_str(&pull).unwrap(),
+        false,
+        "pull request",
+    )
+    .unwrap();
+    repo.reset(&base_commit, git2::ResetType::Hard, None)
+        .unwrap();
+
+    (path, base, feature, pull)
+}
+
+#[test]
+fn test_commits_off_the_default_branch_are_found() {
+    let (path, base, feature, pull) = init_local_repo();
+    // A file:// URL goes through git's transport like a remote, so only branches and tags are
+    // cloned at first
+    let url = format!("file://{}", path);
+
+    let changes = get_git_changed_files(&url, &base, &feature).unwrap();
+    assert_eq!(
+        changes.len(),
+        1,
+        "The feature branch commit should be found"
+    );
+
+    let changes = get_git_changed_files(&url, &base, "feature").unwrap();
+    assert_eq!(
+        changes.len(),
+        1,
+        "Branch names should resolve to origin's branches"
+    );
+
+    let changes = get_git_changed_files(&url, &base, &pull).unwrap();
+    assert_eq!(changes.len(), 1, "Pull request commits should be fetched");
+    assert!(changes[0].content.as_deref().unwrap().contains("b + a"));
+
+    let changes = get_git_changed_files(&url, &base, "refs/pull/1/head").unwrap();
+    assert!(changes[0].content.as_deref().unwrap().contains("b + a"));
+
+    let targets = TestTargets {
+        files: vec!["src/lib.rs".to_string()],
+        functions: vec![],
+        ..Default::default()
+    };
+    let code = read_test_targets_code(&targets, &url, &pull).unwrap();
+    assert!(code.file_contents[0].content.contains("b + a"));
+
+    println!("\n✅ Commits on branches and pull request refs were fetched");
+    let _ = std::fs::remove_dir_all(&path);
+}
+
+#[test]
+fn test_missing_commit_after_fetching_all_refs() {
+    let (path, base, _, _) = init_local_repo();
+    let url = format!("file://{}", path);
+    let unknown = "0123456789012345678901234567890123456789";
+
+    let error = get_git_changed_files(&url, &base, unknown).unwrap_err();
+    assert!(
+        matches!(
+            error.downcast_ref::<ValidationError>(),
+            Some(ValidationError::CommitNotFound { name, .. }) if name == "commit2"
+        ),
+        "Expected commit2 not to be found, got: {}",
+        error
+    );
+    assert!(
+        error.to_string().contains("fetching all"),
+        "The message should say every ref was fetched: {}",
+        error
+    );
+
+    println!("\n✅ {}", error);
+    let _ = std::fs::remove_dir_all(&path);
+}
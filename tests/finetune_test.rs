@@ -0,0 +1,159 @@
+use intent_verification::{
+    AnalysisOptions, EvidenceBundle, EvidenceRecorder, FineTuneManifest, LlmClient, MockProvider,
+    RedactionConfig, RepoSnapshot, ReviewedRun, export_fine_tuning, fine_tuning_examples,
+    verify_intent_with_snapshots,
+};
+
+/// Record a mocked verification of `sum` whose file analysis says `supports_intent`
+async fn recorded_run(supports_intent: bool) -> EvidenceBundle {
+    let base = RepoSnapshot::from_files([(
+        "src/lib.rs",
+        "pub fn sum(a: i32, b: i32) -> i32 {\n    todo!()\n}\n",
+    )]);
+    let head = RepoSnapshot::from_files([(
+        "src/lib.rs",
+        "// Maintained by jane.doe@acme.io\npub fn sum(a: i32, b: i32) -> i32 {\n    a + b\n}\n",
+    )]);
+    let mock = MockProvider::new()
+        .respond_when(
+            "Extract from the following prompt",
+            r#"{"functions": ["sum"], "files": ["src/lib.rs"]}"#,
+        )
+        .respond_when(
+            "STEP 2",
+            &format!(
+                r#"{{"supports_intent": {}, "reasoning": "sum adds", "relevant_changes": []}}"#,
+                supports_intent
+            ),
+        )
+        .respond("Assessment");
+    let recorder = EvidenceRecorder::new();
+    let options = AnalysisOptions {
+        llm_client: Some(LlmClient::mock(mock)),
+        evidence: Some(recorder.clone()),
+        ..Default::default()
+    };
+
+    let result = verify_intent_with_snapshots(
+        &head,
+        &base,
+        &head,
+        "The sum function adds two numbers",
+        "",
+        None,
+        None,
+        &options,
+    )
+    .await
+    .unwrap();
+    assert_eq!(result.is_intent_fulfilled, supports_intent);
+    recorder.bundle(&result)
+}
+
+#[tokio::test(flavor = "current_thread")]
+async fn test_examples_from_an_approved_run() {
+    let bundle = recorded_run(true).await;
+    let examples = fine_tuning_examples(&bundle, true).unwrap();
+
+    assert_eq!(examples.len(), bundle.exchanges.len());
+    assert_eq!(
+        examples.len(),
+        3,
+        "Extraction, file analysis and assessment"
+    );
+    for (example, exchange) in examples.iter().zip(&bundle.exchanges) {
+        let (reply, prompt) = example.messages.split_last().unwrap();
+        assert_eq!(prompt, exchange.prompt.messages.as_slice());
+        assert_eq!(reply.role, "assistant");
+        assert_eq!(reply.content, exchange.response);
+    }
+
+    let overridden = fine_tuning_examples(&bundle, false).unwrap_err();
+    assert!(overridden.contains("overrode"), "{}", overridden);
+
+    let mut tampered = bundle.clone();
+    tampered.exchanges[1].response = r#"{"supports_intent": false}"#.to_string();
+    assert!(fine_tuning_examples(&tampered, true).is_err());
+
+    println!(
+        "\n✅ {} examples, override refused: {}",
+        examples.len(),
+        overridden
+    );
+}
+
+#[tokio::test(flavor = "current_thread")]
+async fn test_export_manifest() {
+    let dir = format!(
+        "/tmp/finetune_test_{}_{}",
+        std::process::id(),
+        std::time::SystemTime::now()
+            .duration_since(std::time::UNIX_EPOCH)
+            .unwrap()
+            .as_nanos()
+    );
+    std::fs::create_dir_all(&dir).unwrap();
+    recorded_run(true)
+        .await
+        .save(format!("{}/fulfilled.json", dir))
+        .unwrap();
+    recorded_run(false)
+        .await
+        .save(format!("{}/rejected.json", dir))
+        .unwrap();
+    std::fs::write(
+        format!("{}/runs.toml", dir),
+        r#"
+[redaction]
+emails = true
+
+[[runs]]
+bundle = "fulfilled.json"
+approved = true
+
+[[runs]]
+bundle = "rejected.json"
+approved = true
+
+[[runs]]
+bundle = "missing.json"
+approved = false
+"#,
+    )
+    .unwrap();
+
+    let manifest = FineTuneManifest::load(format!("{}/runs.toml", dir)).unwrap();
+    assert_eq!(manifest.redaction, Some(RedactionConfig::default()));
+    assert_eq!(
+        manifest.runs[0],
+        ReviewedRun {
+            bundle: format!("{}/fulfilled.json", dir),
+            approved: true,
+        },
+        "Bundle paths are relative to the manifest"
+    );
+
+    let export = export_fine_tuning(&manifest).unwrap();
+    assert_eq!(export.runs_used, 1);
+    assert_eq!(export.examples.len(), 3);
+    let reasons: Vec<&str> = export.skipped.iter().map(|s| s.reason.as_str()).collect();
+    assert!(reasons[0].contains("overrode"), "{:?}", reasons);
+    assert!(reasons[1].contains("couldn't read"), "{:?}", reasons);
+
+    let jsonl = export.to_jsonl().unwrap();
+    assert_eq!(jsonl.lines().count(), 3);
+    for line in jsonl.lines() {
+        let example: serde_json::Value = serde_json::from_str(line).unwrap();
+        let messages = example["messages"].as_array().unwrap();
+        assert_eq!(messages.last().unwrap()["role"], "assistant");
+    }
+    assert!(!jsonl.contains("jane.doe@acme.io"), "Examples are redacted");
+    assert!(jsonl.contains("[REDACTED:email]"));
+
+    println!(
+        "\n✅ Exported {} examples, skipped {:?}",
+        export.examples.len(),
+        reasons
+    );
+    std::fs::remove_dir_all(&dir).ok();
+}
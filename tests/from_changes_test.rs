@@ -0,0 +1,101 @@
+use intent_verification::{
+    AnalysisOptions, ChangeType, FileChange, FunctionContent, LlmClient, MockProvider, TestTargets,
+    TestTargetsWithCode, verify_intent_from_changes,
+};
+
+fn changes() -> Vec<FileChange> {
+    vec![
+        FileChange {
+            path: "calc/sum.py".to_string(),
+            status: ChangeType::Modified,
+            content: Some("def sum(a, b):\n    return a + b\n".to_string()),
+            old_content: Some("def sum(a, b):\n    raise NotImplementedError\n".to_string()),
+        },
+        FileChange {
+            path: "calc/legacy.py".to_string(),
+            status: ChangeType::Deleted,
+            content: None,
+            old_content: Some("def old():\n    pass\n".to_string()),
+        },
+    ]
+}
+
+fn targets() -> TestTargetsWithCode {
+    TestTargetsWithCode {
+        targets: TestTargets {
+            functions: vec!["test_sum".to_string()],
+            files: vec![],
+            ..Default::default()
+        },
+        file_contents: vec![],
+        function_contents: vec![FunctionContent {
+            name: "test_sum".to_string(),
+            file_path: Some("tests/test_sum.py".to_string()),
+            line: None,
+            content: Some("def test_sum():\n    assert sum(2, 3) == 5\n".to_string()),
+            error: None,
+        }],
+        tested_sources: vec![],
+    }
+}
+
+#[tokio::test(flavor = "current_thread")]
+async fn test_verify_prebuilt_changes() {
+    let mock = MockProvider::new()
+        .respond_when(
+            "STEP 2",
+            r#"{"supports_intent": true, "reasoning": "sum returns a + b", "relevant_changes": ["return a + b"]}"#,
+        )
+        .respond("The sum is implemented");
+    let options = AnalysisOptions {
+        llm_client: Some(LlmClient::mock(mock.clone())),
+        ..Default::default()
+    };
+
+    let result = verify_intent_from_changes(
+        changes(),
+        targets(),
+        "The sum function adds two numbers",
+        "",
+        None,
+        None,
+        &options,
+    )
+    .await
+    .unwrap();
+
+    assert!(result.is_intent_fulfilled, "{}", result.explanation);
+    assert_eq!(result.files_analyzed.len(), 2);
+    assert_eq!(result.overall_assessment, "The sum is implemented");
+    assert!(result.warnings.is_empty(), "{:?}", result.warnings);
+
+    let prompts: Vec<String> = mock.calls().iter().map(|call| call.prompt()).collect();
+    assert_eq!(
+        prompts.len(),
+        2,
+        "The given targets replace the extraction, and deleted files aren't sent"
+    );
+    assert!(prompts[0].contains("SOLUTION FILE: calc/sum.py"));
+    assert!(
+        prompts[0].contains("assert sum(2, 3) == 5"),
+        "The test target code is the caller's"
+    );
+
+    println!("\n✅ {}", result.explanation);
+}
+
+#[tokio::test(flavor = "current_thread")]
+async fn test_invalid_intent_fails_before_any_request() {
+    let mock = MockProvider::new().respond("{}");
+    let options = AnalysisOptions {
+        llm_client: Some(LlmClient::mock(mock.clone())),
+        ..Default::default()
+    };
+
+    let error = verify_intent_from_changes(changes(), targets(), "  ", "", None, None, &options)
+        .await
+        .unwrap_err();
+    assert_eq!(mock.call_count(), 0);
+
+    println!("\n✅ {}", error);
+}
@@ -0,0 +1,104 @@
+#![cfg(feature = "git")]
+
+mod common;
+
+use intent_verification::{
+    AnalysisOptions, ChangeType, LlmClient, MockProvider, verify_function_intent,
+};
+
+/// Create a local repository whose second commit changes two functions of the same file
+fn init_local_repo() -> (String, String, String) {
+    common::init_repo_with(
+        &common::unique_path("function_intent_test_repo"),
+        "src/lib.rs",
+        "pub fn sum(a: i32, b: i32) -> i32 {\n    todo!()\n}\n\n\
+         pub fn mul(a: i32, b: i32) -> i32 {\n    todo!()\n}\n\n\
+         pub fn div(a: i32, b: i32) -> i32 {\n    a / b\n}\n",
+        "pub fn sum(a: i32, b: i32) -> i32 {\n    a + b\n}\n\n\
+         pub fn mul(a: i32, b: i32) -> i32 {\n    a * b * 1000\n}\n\n\
+         pub fn div(a: i32, b: i32) -> i32 {\n    a / b\n}\n",
+    )
+}
+
+#[tokio::test(flavor = "current_thread")]
+async fn test_only_the_function_is_sent() {
+    let (path, first, second) = init_local_repo();
+    let mock = MockProvider::new()
+        .respond_when(
+            "Extract from the following prompt",
+            r#"{"functions": ["sum"], "files": []}"#,
+        )
+        .respond_when(
+            "STEP 2",
+            r#"{"supports_intent": true, "reasoning": "sum adds", "relevant_changes": []}"#,
+        )
+        .respond("Assessment");
+    let options = AnalysisOptions {
+        llm_client: Some(LlmClient::mock(mock.clone())),
+        ..Default::default()
+    };
+
+    let result = verify_function_intent(
+        "sum",
+        &path,
+        &first,
+        &second,
+        "The sum function adds two numbers",
+        "",
+        None,
+        None,
+        &options,
+    )
+    .await
+    .unwrap();
+
+    assert!(result.is_intent_fulfilled);
+    assert_eq!(result.files_analyzed.len(), 1);
+    assert_eq!(result.files_analyzed[0].file_path, "src/lib.rs");
+    assert_eq!(result.files_analyzed[0].change_type, ChangeType::Modified);
+
+    let analysis = mock
+        .calls()
+        .into_iter()
+        .map(|call| call.prompt())
+        .find(|prompt| prompt.contains("STEP 2"))
+        .unwrap();
+    assert!(analysis.contains("a + b"));
+    assert!(
+        !analysis.contains("a * b * 1000"),
+        "Other functions of the file aren't sent"
+    );
+
+    println!("\n✅ {}", result.files_analyzed[0].reasoning);
+    std::fs::remove_dir_all(&path).ok();
+}
+
+#[tokio::test(flavor = "current_thread")]
+async fn test_unchanged_function_fails() {
+    let (path, first, second) = init_local_repo();
+    let mock = MockProvider::new().respond(r#"{"functions": [], "files": []}"#);
+    let options = AnalysisOptions {
+        llm_client: Some(LlmClient::mock(mock.clone())),
+        ..Default::default()
+    };
+
+    for name in ["div", "missing"] {
+        let error = verify_function_intent(
+            name,
+            &path,
+            &first,
+            &second,
+            "The sum function adds two numbers",
+            "",
+            None,
+            None,
+            &options,
+        )
+        .await
+        .unwrap_err();
+        assert!(error.to_string().contains("isn't changed"), "{}", error);
+        println!("✅ {}", error);
+    }
+
+    std::fs::remove_dir_all(&path).ok();
+}
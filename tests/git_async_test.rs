@@ -0,0 +1,57 @@
+#![cfg(feature = "git")]
+
+mod common;
+
+use intent_verification::{
+    TestTargets, get_git_changed_files, get_git_changed_files_async, read_test_targets_code,
+    read_test_targets_code_async,
+};
+
+#[tokio::test(flavor = "current_thread")]
+async fn test_async_variants_match_blocking_ones() {
+    let (path, first, second) = common::init_local_repo();
+
+    let changes = get_git_changed_files_async(&path, &first, &second)
+        .await
+        .expect("Should diff the local repository");
+    let blocking = get_git_changed_files(&path, &first, &second).unwrap();
+    println!("\n📝 {} changed file(s)", changes.len());
+    assert_eq!(changes.len(), blocking.len());
+    assert_eq!(changes[0].path, "src/lib.rs");
+    assert_eq!(changes[0].content, blocking[0].content);
+
+    let targets = TestTargets {
+        functions: vec!["sum".to_string()],
+        files: vec!["src/lib.rs".to_string()],
+        ..Default::default()
+    };
+    let with_code = read_test_targets_code_async(&targets, &path, &second)
+        .await
+        .expect("Should read the targets");
+    let blocking = read_test_targets_code(&targets, &path, &second).unwrap();
+    assert_eq!(
+        with_code.function_contents[0].content,
+        blocking.function_contents[0].content
+    );
+    assert!(
+        with_code.function_contents[0]
+            .content
+            .as_deref()
+            .unwrap()
+            .contains("a + b")
+    );
+
+    std::fs::remove_dir_all(&path).ok();
+}
+
+#[tokio::test(flavor = "current_thread")]
+async fn test_async_errors_keep_their_git_type() {
+    let error = get_git_changed_files_async("/nonexistent/git-async-repo", "HEAD~1", "HEAD")
+        .await
+        .expect_err("The repository doesn't exist");
+    println!("\n❌ {}", error);
+    assert!(
+        error.downcast_ref::<git2::Error>().is_some(),
+        "Callers classify failures by error type"
+    );
+}
@@ -1,3 +1,5 @@
+#![cfg(feature = "git")]
+
 use intent_verification::{ChangeType, get_git_changed_files};
 
 #[test]
@@ -34,7 +36,7 @@ fn test_alkahest_rs_repo_diff() {
 
             // Basic assertions
             assert!(
-                file_changes.len() > 0,
+                !file_changes.is_empty(),
                 "Should find some changed files between these commits"
             );
 
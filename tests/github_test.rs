@@ -0,0 +1,122 @@
+use intent_verification::{
+    GithubIssue, PullRequestContext, STICKY_COMMENT_MARKER, parse_issue_reference,
+    sticky_comment_body,
+};
+
+const EVENT: &str = r#"{
+    "action": "synchronize",
+    "number": 42,
+    "pull_request": {
+        "number": 42,
+        "title": "Add sum function",
+        "body": "The `sum` tests should pass.\r\n",
+        "base": {
+            "sha": "1111111111111111111111111111111111111111",
+            "repo": { "full_name": "acme/calc", "clone_url": "https://github.com/acme/calc.git" }
+        },
+        "head": {
+            "sha": "2222222222222222222222222222222222222222",
+            "repo": { "full_name": "fork/calc", "clone_url": "https://github.com/fork/calc.git" }
+        }
+    },
+    "repository": { "full_name": "acme/calc", "clone_url": "https://github.com/acme/calc.git" }
+}"#;
+
+#[test]
+fn test_pull_request_context_from_event() {
+    let pr = PullRequestContext::from_event_json(EVENT).expect("Should parse the event");
+
+    println!("\n🐙 Pull request: {:#?}", pr);
+
+    assert_eq!(
+        pr.repository, "acme/calc",
+        "Comments go to the base repository"
+    );
+    assert_eq!(pr.number, 42);
+    assert_eq!(
+        pr.clone_url, "https://github.com/fork/calc.git",
+        "Changes should be cloned from the head repository"
+    );
+    assert_eq!(pr.base_sha, "1111111111111111111111111111111111111111");
+    assert_eq!(pr.head_sha, "2222222222222222222222222222222222222222");
+    assert_eq!(
+        pr.intent(),
+        "Add sum function\n\nThe `sum` tests should pass."
+    );
+}
+
+#[test]
+fn test_pull_request_context_rejects_other_events() {
+    let push_event = r#"{"ref": "refs/heads/main", "repository": {"full_name": "acme/calc", "clone_url": "https://github.com/acme/calc.git"}}"#;
+
+    let err = PullRequestContext::from_event_json(push_event)
+        .expect_err("Push events have no pull request");
+    assert!(err.to_string().contains("Not a pull request event"));
+}
+
+#[test]
+fn test_sticky_comment_body() {
+    let body = sticky_comment_body("## ✅ Intent fulfilled");
+
+    assert!(
+        body.starts_with(STICKY_COMMENT_MARKER),
+        "The marker lets later runs find and update the comment"
+    );
+    assert!(body.ends_with("## ✅ Intent fulfilled"));
+}
+
+const ISSUE: &str = r#"{
+    "number": 7,
+    "title": "Add a sum function",
+    "body": "We need to add numbers.\r\n\r\n## Acceptance criteria\r\n- [ ] `sum(1, 2)` returns 3\r\n- [x] Negative numbers are supported\r\n\r\n## Notes\r\n- Not part of the criteria\r\n",
+    "html_url": "https://github.com/acme/calc/issues/7",
+    "labels": [{ "name": "enhancement" }]
+}"#;
+
+#[test]
+fn test_github_issue_intent() {
+    let issue = GithubIssue::from_json("acme/calc", ISSUE).expect("Should parse the issue");
+
+    println!("\n🐙 Issue: {:#?}", issue);
+
+    assert_eq!(issue.labels, vec!["enhancement"]);
+    assert_eq!(
+        issue.acceptance_criteria(),
+        vec!["`sum(1, 2)` returns 3", "Negative numbers are supported"],
+        "Only the items of the acceptance criteria section should be listed"
+    );
+    assert_eq!(
+        issue.intent(),
+        "Add a sum function\n\nAcceptance criteria:\n- `sum(1, 2)` returns 3\n- Negative numbers are supported"
+    );
+
+    let without_criteria = GithubIssue {
+        body: Some("We need to add numbers.\n".to_string()),
+        ..issue
+    };
+    assert!(without_criteria.acceptance_criteria().is_empty());
+    assert_eq!(
+        without_criteria.intent(),
+        "Add a sum function\n\nWe need to add numbers."
+    );
+}
+
+#[test]
+fn test_github_issue_rejects_pull_requests() {
+    let pull_request = r#"{"number": 8, "title": "Fix", "body": null, "html_url": "https://github.com/acme/calc/pull/8", "pull_request": {}}"#;
+
+    let err = GithubIssue::from_json("acme/calc", pull_request)
+        .expect_err("Pull requests are not issues");
+    assert!(err.to_string().contains("is a pull request"));
+}
+
+#[test]
+fn test_parse_issue_reference() {
+    assert_eq!(
+        parse_issue_reference("acme/calc#42"),
+        Ok(("acme/calc".to_string(), 42))
+    );
+    assert!(parse_issue_reference("acme/calc").is_err());
+    assert!(parse_issue_reference("calc#42").is_err());
+    assert!(parse_issue_reference("acme/calc#forty-two").is_err());
+}
@@ -0,0 +1,156 @@
+#![cfg(feature = "grpc")]
+
+mod common;
+
+use intent_verification::{
+    AnalyzeRepositoryRequest, IntentVerificationClient, RequestOptions, ServerConfig,
+    VerifyIntentRequest, serve_grpc,
+};
+
+#[tokio::test]
+async fn test_grpc_service() {
+    let listener = tokio::net::TcpListener::bind("127.0.0.1:0").await.unwrap();
+    let address = listener.local_addr().unwrap();
+    // Nothing listens on port 9, so the LLM calls fail without network access
+    let config = ServerConfig {
+        api_key: "test-key".to_string(),
+        base_url: Some("http://127.0.0.1:9".to_string()),
+        ..Default::default()
+    };
+    tokio::spawn(serve_grpc(listener, config));
+
+    let mut client = IntentVerificationClient::connect(format!("http://{}", address))
+        .await
+        .expect("Should connect to the gRPC server");
+
+    let (path, first, second) = common::init_local_repo();
+    let result = client
+        .analyze_repository(AnalyzeRepositoryRequest {
+            repo_url: path.clone(),
+            base: first.clone(),
+            head: second.clone(),
+            user_intent: "The sum function should add two numbers".to_string(),
+            model: None,
+            options: Some(RequestOptions {
+                language: Some("vi".to_string()),
+                archetype: Some("bug-fix".to_string()),
+                clarifications: vec!["Only integers".to_string()],
+                ..Default::default()
+            }),
+        })
+        .await
+        .expect("Verification should complete with warnings")
+        .into_inner();
+
+    println!("\n📡 gRPC result: {:#?}", result);
+
+    assert_eq!(result.files_analyzed.len(), 1);
+    assert_eq!(result.files_analyzed[0].file_path, "src/lib.rs");
+    assert_eq!(result.files_analyzed[0].change_type, "Modified");
+    assert!(result.is_partial);
+    assert!(!result.warnings.is_empty());
+    let full: serde_json::Value =
+        serde_json::from_str(&result.result_json).expect("result_json should be valid JSON");
+    assert_eq!(full["files_analyzed"][0]["file_path"], "src/lib.rs");
+
+    let missing = client
+        .verify_intent(VerifyIntentRequest {
+            test_repo_url: path.clone(),
+            test_commit: second.clone(),
+            solution_repo_url: path,
+            solution_commit1: first,
+            solution_commit2: second,
+            user_intent: " ".to_string(),
+            model: None,
+            options: None,
+        })
+        .await
+        .expect_err("A blank intent should be rejected");
+    assert_eq!(missing.code(), tonic::Code::InvalidArgument);
+    assert!(missing.message().contains("user_intent"));
+
+    let unknown = client
+        .analyze_repository(AnalyzeRepositoryRequest {
+            repo_url: "/nonexistent/repository".to_string(),
+            base: "a1".to_string(),
+            head: "b2".to_string(),
+            user_intent: "Anything".to_string(),
+            model: None,
+            options: None,
+        })
+        .await
+        .expect_err("Unreadable repositories should fail the call");
+    assert_eq!(unknown.code(), tonic::Code::Internal);
+
+    let invalid = client
+        .analyze_repository(AnalyzeRepositoryRequest {
+            repo_url: "/nonexistent/repository".to_string(),
+            base: "a1".to_string(),
+            head: "b2".to_string(),
+            user_intent: "Anything".to_string(),
+            model: None,
+            options: Some(RequestOptions {
+                profile: Some("reckless".to_string()),
+                ..Default::default()
+            }),
+        })
+        .await
+        .expect_err("Unknown profiles should be rejected");
+    assert_eq!(invalid.code(), tonic::Code::InvalidArgument);
+    assert!(
+        invalid.message().contains("reckless"),
+        "{}",
+        invalid.message()
+    );
+}
+
+#[tokio::test]
+async fn test_grpc_service_requires_the_auth_token() {
+    let listener = tokio::net::TcpListener::bind("127.0.0.1:0").await.unwrap();
+    let address = listener.local_addr().unwrap();
+    let config = ServerConfig {
+        api_key: "test-key".to_string(),
+        base_url: Some("http://127.0.0.1:9".to_string()),
+        auth_token: Some("s3cret".to_string()),
+        ..Default::default()
+    };
+    tokio::spawn(serve_grpc(listener, config));
+
+    let mut client = IntentVerificationClient::connect(format!("http://{}", address))
+        .await
+        .expect("Should connect to the gRPC server");
+    let request = |token: Option<&str>| {
+        let mut request = tonic::Request::new(AnalyzeRepositoryRequest {
+            repo_url: "/nonexistent/repository".to_string(),
+            base: "a1".to_string(),
+            head: "b2".to_string(),
+            user_intent: "Anything".to_string(),
+            model: None,
+            options: None,
+        });
+        if let Some(token) = token {
+            request
+                .metadata_mut()
+                .insert("authorization", token.parse().unwrap());
+        }
+        request
+    };
+
+    for token in [None, Some("Bearer wrong")] {
+        let denied = client
+            .analyze_repository(request(token))
+            .await
+            .expect_err("Calls without the token should be refused");
+        assert_eq!(denied.code(), tonic::Code::Unauthenticated, "{:?}", token);
+    }
+
+    let failed = client
+        .analyze_repository(request(Some("Bearer s3cret")))
+        .await
+        .expect_err("The repository doesn't exist");
+    assert_eq!(
+        failed.code(),
+        tonic::Code::Internal,
+        "Authorized calls should reach the verification"
+    );
+}
@@ -0,0 +1,260 @@
+#![cfg(feature = "git")]
+
+mod common;
+
+use std::sync::Arc;
+use std::sync::atomic::{AtomicUsize, Ordering};
+
+use intent_verification::{AnalysisCache, AnalysisOptions, blob_hash, verify_intent_with_options};
+
+/// Serve chat completions on a local port, answering every request with the same JSON and
+/// counting the requests
+fn start_model() -> (String, Arc<AtomicUsize>) {
+    use std::io::{Read, Write};
+
+    let listener = std::net::TcpListener::bind("127.0.0.1:0").unwrap();
+    let url = format!("http://{}/v1", listener.local_addr().unwrap());
+    let requests = Arc::new(AtomicUsize::new(0));
+    let counter = requests.clone();
+
+    let content = serde_json::json!({
+        "functions": ["sum"],
+        "files": ["src/lib.rs"],
+        "supports_intent": true,
+        "relevance": "required",
+        "reasoning": "sum now adds its arguments",
+        "relevant_changes": ["a + b"],
+        "locations": [],
+        "confidence": 0.9
+    })
+    .to_string();
+    let body = serde_json::json!({
+        "id": "chatcmpl-test",
+        "object": "chat.completion",
+        "created": 0,
+        "model": "test-model",
+        "choices": [{
+            "index": 0,
+            "message": {"role": "assistant", "content": content},
+            "finish_reason": "stop"
+        }]
+    })
+    .to_string();
+
+    std::thread::spawn(move || {
+        for stream in listener.incoming() {
+            let mut stream = stream.unwrap();
+            let mut request = Vec::new();
+            let mut buffer = [0u8; 4096];
+            loop {
+                let n = stream.read(&mut buffer).unwrap();
+                request.extend_from_slice(&buffer[..n]);
+                let text = String::from_utf8_lossy(&request).to_string();
+                if let Some((headers, rest)) = text.split_once("\r\n\r\n") {
+                    let length = headers
+                        .lines()
+                        .find_map(|line| {
+                            line.to_lowercase()
+                                .strip_prefix("content-length:")
+                                .map(|v| v.trim().parse::<usize>().unwrap())
+                        })
+                        .unwrap_or(0);
+                    if rest.len() >= length || n == 0 {
+                        break;
+                    }
+                }
+            }
+            counter.fetch_add(1, Ordering::SeqCst);
+            let response = format!(
+                "HTTP/1.1 200 OK\r\ncontent-type: application/json\r\nconnection: close\r\ncontent-length: {}\r\n\r\n{}",
+                body.len(),
+                body
+            );
+            stream.write_all(response.as_bytes()).unwrap();
+        }
+    });
+
+    (url, requests)
+}
+
+#[test]
+fn test_blob_hash_matches_git() {
+    let change = intent_verification::FileChange {
+        path: "empty.txt".to_string(),
+        status: intent_verification::ChangeType::Added,
+        content: Some(String::new()),
+        old_content: None,
+    };
+    assert_eq!(
+        blob_hash(&change).as_deref(),
+        Some("e69de29bb2d1d6434b8b29ae775ad8c2e48c5391"),
+        "Should match `git hash-object` for an empty file"
+    );
+
+    let deleted = intent_verification::FileChange {
+        content: None,
+        status: intent_verification::ChangeType::Deleted,
+        ..change
+    };
+    assert_eq!(blob_hash(&deleted), None);
+}
+
+#[tokio::test]
+async fn test_second_run_reuses_file_analyses() {
+    let (path, first, second) = common::init_local_repo();
+    let (url, requests) = start_model();
+    let state_path = format!("{}.state.json", path);
+    let intent = "The sum function should add two numbers";
+
+    let run = |cache: AnalysisCache| {
+        let (path, first, second, url) = (path.clone(), first.clone(), second.clone(), url.clone());
+        async move {
+            let options = AnalysisOptions {
+                analysis_cache: Some(cache),
+                ..Default::default()
+            };
+            verify_intent_with_options(
+                &path,
+                &second,
+                &path,
+                &first,
+                &second,
+                intent,
+                "test-key",
+                None,
+                Some(&url),
+                &options,
+            )
+            .await
+            .expect("Verification against the local model should succeed")
+        }
+    };
+
+    let first_result = run(AnalysisCache::open(&state_path).unwrap()).await;
+    let first_requests = requests.load(Ordering::SeqCst);
+    println!("\n📨 First run: {} request(s)", first_requests);
+    assert!(
+        first_result.warnings.is_empty(),
+        "{:?}",
+        first_result.warnings
+    );
+    assert!(first_result.metadata.reused_analyses.is_empty());
+    assert!(
+        std::path::Path::new(&state_path).exists(),
+        "The state should be written after the run"
+    );
+
+    let cache = AnalysisCache::open(&state_path).unwrap();
+    assert_eq!(
+        cache.len(),
+        1,
+        "The analysis of src/lib.rs should be stored"
+    );
+    let second_result = run(cache).await;
+    let second_requests = requests.load(Ordering::SeqCst) - first_requests;
+    println!("♻️  Second run: {} request(s)", second_requests);
+    assert_eq!(second_result.metadata.reused_analyses, vec!["src/lib.rs"]);
+    assert!(
+        second_requests < first_requests,
+        "Neither the targets nor the file should be sent to the model again"
+    );
+    assert_eq!(
+        second_result.files_analyzed[0].reasoning,
+        first_result.files_analyzed[0].reasoning
+    );
+    assert_eq!(
+        second_result.is_intent_fulfilled,
+        first_result.is_intent_fulfilled
+    );
+
+    std::fs::remove_file(&state_path).ok();
+    std::fs::remove_dir_all(&path).ok();
+}
+
+#[tokio::test]
+async fn test_failed_analyses_are_not_stored() {
+    let (path, first, second) = common::init_local_repo();
+    let cache = AnalysisCache::in_memory();
+    let options = AnalysisOptions {
+        analysis_cache: Some(cache.clone()),
+        ..Default::default()
+    };
+
+    let result = verify_intent_with_options(
+        &path,
+        &second,
+        &path,
+        &first,
+        &second,
+        "The sum function should add two numbers",
+        "test-key",
+        None,
+        Some("http://127.0.0.1:9"),
+        &options,
+    )
+    .await
+    .expect("Verification should finish with warnings");
+
+    assert!(!result.warnings.is_empty());
+    assert!(
+        cache.is_empty(),
+        "Analyses that failed should be retried next time"
+    );
+    assert!(result.metadata.reused_analyses.is_empty());
+
+    std::fs::remove_dir_all(&path).ok();
+}
+
+#[tokio::test]
+async fn test_same_content_on_another_base_is_analyzed_again() {
+    let path = common::unique_path("incremental_base");
+    let stub = common::commit_files(&path, "stub", &[("src/lib.rs", common::SUM_STUB)]);
+    let other_stub = common::commit_files(
+        &path,
+        "other stub",
+        &[(
+            "src/lib.rs",
+            "pub fn sum(a: i32, b: i32) -> i32 {\n    a - b\n}\n",
+        )],
+    );
+    let implemented = common::commit_files(&path, "implement", &[("src/lib.rs", common::SUM_IMPL)]);
+    let (url, _) = start_model();
+    let options = AnalysisOptions {
+        analysis_cache: Some(AnalysisCache::in_memory()),
+        ..Default::default()
+    };
+    let verify = |base: &str| {
+        let (path, url, options) = (path.clone(), url.clone(), options.clone());
+        let (base, implemented) = (base.to_string(), implemented.clone());
+        async move {
+            verify_intent_with_options(
+                &path,
+                &implemented,
+                &path,
+                &base,
+                &implemented,
+                "The sum function should add two numbers",
+                "test-key",
+                None,
+                Some(&url),
+                &options,
+            )
+            .await
+            .unwrap()
+        }
+    };
+
+    verify(&stub).await;
+    let result = verify(&other_stub).await;
+
+    assert!(
+        result.metadata.reused_analyses.is_empty(),
+        "The change from another base wasn't judged yet"
+    );
+    assert_eq!(
+        verify(&other_stub).await.metadata.reused_analyses,
+        vec!["src/lib.rs"]
+    );
+
+    std::fs::remove_dir_all(&path).ok();
+}
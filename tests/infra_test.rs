@@ -0,0 +1,117 @@
+use intent_verification::{
+    ChangeType, FileChange, InfraKind, infra_kind, infra_review_instruction, structural_diff,
+};
+
+fn modified(path: &str, old: &str, new: &str) -> FileChange {
+    FileChange {
+        path: path.to_string(),
+        status: ChangeType::Modified,
+        content: Some(new.to_string()),
+        old_content: Some(old.to_string()),
+    }
+}
+
+fn diff_lines(file_change: &FileChange) -> Vec<String> {
+    structural_diff(file_change)
+        .expect("Should be diffed structurally")
+        .iter()
+        .map(|change| change.to_string())
+        .collect()
+}
+
+#[test]
+fn test_infra_kind() {
+    let manifest = "apiVersion: v1\nkind: Service\n";
+    assert_eq!(infra_kind("Dockerfile", ""), Some(InfraKind::Dockerfile));
+    assert_eq!(
+        infra_kind("docker/api.Dockerfile", ""),
+        Some(InfraKind::Dockerfile)
+    );
+    assert_eq!(infra_kind("infra/main.tf", ""), Some(InfraKind::Terraform));
+    assert_eq!(
+        infra_kind(".github/workflows/ci.yml", ""),
+        Some(InfraKind::CiConfig)
+    );
+    assert_eq!(infra_kind(".gitlab-ci.yml", ""), Some(InfraKind::CiConfig));
+    assert_eq!(
+        infra_kind("docker-compose.prod.yaml", ""),
+        Some(InfraKind::Compose)
+    );
+    assert_eq!(
+        infra_kind("deploy/service.yaml", manifest),
+        Some(InfraKind::Kubernetes)
+    );
+    assert_eq!(infra_kind("config/settings.yaml", "debug: true\n"), None);
+    assert_eq!(infra_kind("src/lib.rs", ""), None);
+}
+
+#[test]
+fn test_kubernetes_structural_diff() {
+    let old = "apiVersion: apps/v1\nkind: Deployment\nmetadata:\n  name: web\nspec:\n  replicas: 2\n  template:\n    spec:\n      containers:\n        - name: app\n          image: web:1.4.2\n        - name: proxy\n          image: envoy:1.30\n";
+    let new = "apiVersion: apps/v1\nkind: Deployment\nmetadata:\n  name: web\nspec:\n  replicas: 3\n  template:\n    spec:\n      hostNetwork: true\n      containers:\n        - name: init\n          image: busybox:1.36\n        - name: app\n          image: web:latest\n        - name: proxy\n          image: envoy:1.30\n";
+
+    let lines = diff_lines(&modified("deploy/web.yaml", old, new));
+    println!("\n☸️ Structural diff:\n{}", lines.join("\n"));
+
+    assert!(lines.contains(&"~ Deployment/web.spec.replicas: 2 -> 3".to_string()));
+    assert!(lines.contains(&"+ Deployment/web.spec.template.spec.hostNetwork = true".to_string()));
+    assert!(
+        lines.contains(
+            &"~ Deployment/web.spec.template.spec.containers[app].image: web:1.4.2 -> web:latest"
+                .to_string()
+        )
+    );
+    assert!(
+        lines.iter().all(|l| !l.contains("containers[proxy]")),
+        "Named items should not shift when one is inserted before them"
+    );
+}
+
+#[test]
+fn test_dockerfile_and_terraform_structural_diff() {
+    let old = "FROM node:20-slim\n# deps\nRUN npm ci \\\n    --omit=dev\nUSER node\nCMD [\"node\", \"server.js\"]\n";
+    let new = "FROM node:latest\nRUN npm ci \\\n    --omit=dev\nCMD [\"node\", \"server.js\"]\n";
+    let lines = diff_lines(&modified("Dockerfile", old, new));
+    println!("\n🐳 Structural diff:\n{}", lines.join("\n"));
+    assert_eq!(
+        lines,
+        vec!["~ FROM: node:20-slim -> node:latest", "- USER = node"]
+    );
+
+    let old = "resource \"aws_security_group\" \"web\" {\n  name = \"web\"\n  ingress {\n    cidr_blocks = [\"10.0.0.0/8\"]\n  }\n}\n";
+    let new = "resource \"aws_security_group\" \"web\" {\n  name = \"web\"\n  ingress {\n    cidr_blocks = [\"0.0.0.0/0\"]\n  }\n}\n";
+    let lines = diff_lines(&modified("infra/main.tf", old, new));
+    assert_eq!(
+        lines,
+        vec![
+            "~ resource.aws_security_group.web.ingress.cidr_blocks: [\"10.0.0.0/8\"] -> [\"0.0.0.0/0\"]"
+        ]
+    );
+}
+
+#[test]
+fn test_infra_review_instruction() {
+    let workflow = modified(
+        ".github/workflows/ci.yml",
+        "on: pull_request\npermissions:\n  contents: read\n",
+        "on: pull_request_target\npermissions: write-all\n",
+    );
+    let instruction = infra_review_instruction(&workflow).expect("CI configs are reviewed");
+    println!("\n🛠️ Instruction:\n{}", instruction);
+    assert!(instruction.contains("misconfiguration risks"));
+    assert!(instruction.contains("pull_request_target"));
+    assert!(instruction.contains("~ on: pull_request -> pull_request_target"));
+
+    let helm = modified(
+        "charts/web/templates/deployment.yaml",
+        "{{- if .Values.enabled }}\napiVersion: apps/v1\nkind: Deployment\n{{- end }}\n",
+        "{{- if .Values.web.enabled }}\napiVersion: apps/v1\nkind: Deployment\n{{- end }}\n",
+    );
+    assert!(
+        structural_diff(&helm).is_none(),
+        "Templates that aren't valid YAML fall back to the text diff"
+    );
+    assert!(infra_review_instruction(&helm).is_some());
+
+    assert!(infra_review_instruction(&modified("src/lib.rs", "", "fn main() {}")).is_none());
+}
@@ -1,3 +1,5 @@
+#![cfg(feature = "git")]
+
 use dotenvy::dotenv;
 use intent_verification::verify_intent;
 use std::env;
@@ -74,7 +76,7 @@ async fn test_verify_typescript_sample_repo() {
 
             // Assertions
             assert!(
-                result.confidence >= 0.0 && result.confidence <= 1.0,
+                (0.0..=1.0).contains(&result.confidence),
                 "Confidence should be between 0 and 1"
             );
             assert!(!result.explanation.is_empty(), "Should have an explanation");
@@ -167,7 +169,7 @@ async fn test_verify_rust_sample_repo() {
 
             // Assertions
             assert!(
-                result.confidence >= 0.0 && result.confidence <= 1.0,
+                (0.0..=1.0).contains(&result.confidence),
                 "Confidence should be between 0 and 1"
             );
             assert!(!result.explanation.is_empty(), "Should have an explanation");
@@ -260,7 +262,7 @@ async fn test_verify_py_sample_repo() {
 
             // Assertions
             assert!(
-                result.confidence >= 0.0 && result.confidence <= 1.0,
+                (0.0..=1.0).contains(&result.confidence),
                 "Confidence should be between 0 and 1"
             );
             assert!(!result.explanation.is_empty(), "Should have an explanation");
@@ -0,0 +1,92 @@
+#![cfg(feature = "git")]
+
+mod common;
+
+use intent_verification::{
+    AnalysisOptions, IntentVerdict, MultiIntentResult, split_acceptance_criteria,
+    verify_intents_with_options,
+};
+
+#[test]
+fn test_split_acceptance_criteria() {
+    let intent = "Add a statistics module.\n\n\
+                  1. sum adds two numbers\n\
+                  2. mean returns the average,\n   rounding down\n\
+                  3. mean of an empty slice is 0\n";
+    let criteria = split_acceptance_criteria(intent);
+    println!("\n📋 Criteria: {:#?}", criteria);
+    assert_eq!(criteria.len(), 3);
+    assert_eq!(
+        criteria[1],
+        "Add a statistics module.\nAcceptance criterion: mean returns the average, rounding down"
+    );
+
+    assert_eq!(
+        split_acceptance_criteria("- only one item"),
+        vec!["- only one item"],
+        "A single item is not a list of criteria"
+    );
+    assert_eq!(
+        split_acceptance_criteria("- sum works\n- mean works"),
+        vec!["sum works", "mean works"]
+    );
+}
+
+#[tokio::test]
+async fn test_verify_intents_shares_one_run() {
+    let (path, first, second) = common::init_local_repo();
+    let intents = vec![
+        "The sum function should add two numbers".to_string(),
+        "The sum function should not panic".to_string(),
+    ];
+    let options = AnalysisOptions {
+        dry_run: true,
+        ..Default::default()
+    };
+
+    let multi = verify_intents_with_options(
+        &path, &second, &path, &first, &second, &intents, "test-key", None, None, &options,
+    )
+    .await
+    .expect("Dry runs should not need the model");
+
+    assert_eq!(multi.verdicts.len(), 2);
+    for (verdict, intent) in multi.verdicts.iter().zip(&intents) {
+        assert_eq!(&verdict.intent, intent);
+        assert_eq!(verdict.result.files_analyzed.len(), 1);
+        assert!(
+            verdict.result.prompts.iter().any(|p| p
+                .messages
+                .iter()
+                .any(|m| m.content.contains(intent.as_str()))),
+            "Each intent should get its own prompts"
+        );
+    }
+    assert!(!multi.all_fulfilled);
+    assert_eq!(multi.fulfilled_count, 0);
+}
+
+#[test]
+fn test_multi_intent_aggregate() {
+    let verdict = |fulfilled: bool, confidence: f32| {
+        let mut result: intent_verification::IntentVerificationResult =
+            serde_json::from_str(r#"{"is_intent_fulfilled":true,"confidence":1.0,"explanation":"","files_analyzed":[],"overall_assessment":""}"#)
+                .unwrap();
+        result.is_intent_fulfilled = fulfilled;
+        result.confidence = confidence;
+        IntentVerdict {
+            intent: String::new(),
+            result,
+        }
+    };
+
+    let multi = MultiIntentResult::from_verdicts(vec![verdict(true, 0.9), verdict(true, 0.7)]);
+    assert!(multi.all_fulfilled);
+    assert_eq!(multi.confidence, 0.7);
+
+    let multi = MultiIntentResult::from_verdicts(vec![verdict(true, 0.9), verdict(false, 0.8)]);
+    assert!(!multi.all_fulfilled);
+    assert_eq!(multi.fulfilled_count, 1);
+
+    assert!(!MultiIntentResult::from_verdicts(vec![]).all_fulfilled);
+}
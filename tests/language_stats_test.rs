@@ -0,0 +1,110 @@
+use intent_verification::{
+    AnalysisOptions, ChangeType, FileChange, LlmClient, MockProvider, RepoSnapshot,
+    detect_language, language_stats, render_markdown, verify_intent_with_snapshots,
+};
+
+fn change(path: &str, status: ChangeType, old: Option<&str>, new: Option<&str>) -> FileChange {
+    FileChange {
+        path: path.to_string(),
+        status,
+        content: new.map(str::to_string),
+        old_content: old.map(str::to_string),
+    }
+}
+
+#[test]
+fn test_detect_language() {
+    assert_eq!(detect_language("src/lib.rs"), "Rust");
+    assert_eq!(detect_language("app/models.py"), "Python");
+    assert_eq!(detect_language("web/App.TSX"), "TypeScript");
+    assert_eq!(detect_language("docker/Dockerfile"), "Dockerfile");
+    assert_eq!(detect_language("README.md"), "Markdown");
+    assert_eq!(detect_language("LICENSE"), "Other");
+    assert_eq!(detect_language("assets/logo.png"), "Other");
+}
+
+#[test]
+fn test_language_stats() {
+    let changes = vec![
+        change(
+            "src/lib.rs",
+            ChangeType::Modified,
+            Some("fn a() {}\nfn b() {}\n"),
+            Some("fn a() {}\nfn c() {}\nfn d() {}\n"),
+        ),
+        change("src/new.rs", ChangeType::Added, None, Some("fn e() {}\n")),
+        change(
+            "tools/gen.py",
+            ChangeType::Deleted,
+            Some("import os\nprint(os)\n"),
+            None,
+        ),
+        change("README.md", ChangeType::Modified, Some("a\n"), Some("b\n")),
+        change("assets/logo.png", ChangeType::Added, None, None),
+    ];
+
+    let stats = language_stats(&changes);
+    println!("\n🗂️ Language stats: {:#?}", stats);
+    let summary: Vec<(&str, usize, usize, usize)> = stats
+        .iter()
+        .map(|s| (s.language.as_str(), s.files, s.lines_added, s.lines_removed))
+        .collect();
+    assert_eq!(
+        summary,
+        vec![
+            ("Rust", 2, 3, 1),
+            ("Markdown", 1, 1, 1),
+            ("Python", 1, 0, 2),
+            ("Other", 1, 0, 0),
+        ],
+        "Most changed lines first, ties by name"
+    );
+    assert!(language_stats(&[]).is_empty());
+}
+
+#[tokio::test(flavor = "current_thread")]
+async fn test_language_stats_in_result() {
+    let base = RepoSnapshot::from_files([("src/sum.rs", "pub fn sum() {}\n")]);
+    let head = RepoSnapshot::from_files([
+        ("src/sum.rs", "pub fn sum(a: i32) -> i32 {\n    a\n}\n"),
+        ("docs/sum.md", "Sums numbers\n"),
+    ]);
+    let mock = MockProvider::new()
+        .respond_when(
+            "Extract from the following prompt",
+            r#"{"functions": ["sum"], "files": []}"#,
+        )
+        .respond_when(
+            "STEP 2",
+            r#"{"supports_intent": true, "reasoning": "adds", "relevant_changes": []}"#,
+        )
+        .respond("Assessment");
+    let options = AnalysisOptions {
+        llm_client: Some(LlmClient::mock(mock)),
+        ..Default::default()
+    };
+
+    let result = verify_intent_with_snapshots(
+        &head,
+        &base,
+        &head,
+        "The sum function adds numbers",
+        "",
+        None,
+        None,
+        &options,
+    )
+    .await
+    .unwrap();
+
+    let languages: Vec<&str> = result
+        .language_stats
+        .iter()
+        .map(|s| s.language.as_str())
+        .collect();
+    assert_eq!(languages, vec!["Rust", "Markdown"]);
+    assert!(
+        render_markdown(&result)
+            .contains("**Languages:** Rust (1 files, +3 -1), Markdown (1 files, +1 -0)")
+    );
+}
@@ -0,0 +1,62 @@
+#![cfg(feature = "git")]
+
+mod common;
+
+use intent_verification::{ChangeType, get_git_changed_files, get_git_changed_files_lazy};
+
+/// Create a local repository where the second commit adds, modifies and deletes a file
+fn init_local_repo() -> (String, String, String) {
+    let path = common::unique_path("lazy_blobs_test_repo");
+    let first = common::commit_changes(
+        &path,
+        "initial",
+        &[
+            ("src/lib.rs", Some(common::SUM_STUB)),
+            ("src/old.rs", Some("pub fn old() {}\n")),
+        ],
+    );
+    let second = common::commit_changes(
+        &path,
+        "implement",
+        &[
+            ("src/lib.rs", Some(common::SUM_IMPL)),
+            ("src/old.rs", None),
+            ("src/new.rs", Some("pub fn new() {}\n")),
+        ],
+    );
+    (path, first, second)
+}
+
+#[test]
+fn test_lazy_changes_load_like_eager_ones() {
+    let (path, first, second) = init_local_repo();
+
+    let eager = get_git_changed_files(&path, &first, &second).expect("Should diff eagerly");
+    let (blobs, lazy) =
+        get_git_changed_files_lazy(&path, &first, &second).expect("Should diff lazily");
+    println!("\n💤 {} lazily diffed file(s)", lazy.len());
+    assert_eq!(lazy.len(), eager.len());
+
+    for (lazy, eager) in lazy.iter().zip(&eager) {
+        assert_eq!(lazy.path, eager.path);
+        assert_eq!(lazy.status, eager.status);
+        match lazy.status {
+            ChangeType::Added => assert!(lazy.new_oid.is_some() && lazy.old_oid.is_none()),
+            ChangeType::Modified => assert!(lazy.new_oid.is_some() && lazy.old_oid.is_some()),
+            ChangeType::Deleted => assert!(lazy.new_oid.is_none() && lazy.old_oid.is_some()),
+        }
+
+        let loaded = blobs
+            .load(lazy)
+            .expect("Should read the blobs while the store lives");
+        assert_eq!(loaded.content, eager.content, "{}", lazy.path);
+        assert_eq!(loaded.old_content, eager.old_content, "{}", lazy.path);
+    }
+
+    let modified = lazy.iter().find(|c| c.path == "src/lib.rs").unwrap();
+    let loaded = blobs.load(modified).unwrap();
+    assert!(loaded.content.unwrap().contains("a + b"));
+    assert!(loaded.old_content.unwrap().contains("todo!()"));
+
+    let _ = std::fs::remove_dir_all(&path);
+}
@@ -0,0 +1,303 @@
+#![cfg(feature = "git")]
+
+mod common;
+
+use std::sync::{Arc, Mutex};
+
+use intent_verification::{
+    AnalysisOptions, FetchRequest, FetchResponse, LlmClient, RepoSnapshot,
+    verify_intent_with_options, verify_intent_with_snapshots,
+};
+
+/// Chat completion whose content answers extraction, file analysis and assessment alike
+fn completion_body() -> String {
+    let content = serde_json::json!({
+        "functions": ["sum"],
+        "files": ["src/lib.rs"],
+        "supports_intent": true,
+        "relevance": "required",
+        "reasoning": "sum now adds its arguments",
+        "relevant_changes": ["a + b"],
+        "locations": [],
+        "confidence": 0.9
+    })
+    .to_string();
+    serde_json::json!({
+        "id": "chatcmpl-test",
+        "object": "chat.completion",
+        "created": 0,
+        "model": "test-model",
+        "choices": [{
+            "index": 0,
+            "message": {"role": "assistant", "content": content},
+            "finish_reason": "stop"
+        }]
+    })
+    .to_string()
+}
+
+/// Serve chat completions on a local port, answering every request with the same JSON and
+/// recording whether each request carried the `x-shared-client` header
+fn start_model() -> (String, Arc<Mutex<Vec<bool>>>) {
+    use std::io::{Read, Write};
+
+    let listener = std::net::TcpListener::bind("127.0.0.1:0").unwrap();
+    let url = format!("http://{}/v1", listener.local_addr().unwrap());
+    let requests = Arc::new(Mutex::new(Vec::new()));
+    let recorded = requests.clone();
+    let body = completion_body();
+
+    std::thread::spawn(move || {
+        for stream in listener.incoming() {
+            let mut stream = stream.unwrap();
+            let mut request = Vec::new();
+            let mut buffer = [0u8; 4096];
+            loop {
+                let n = stream.read(&mut buffer).unwrap();
+                request.extend_from_slice(&buffer[..n]);
+                let text = String::from_utf8_lossy(&request).to_string();
+                if let Some((headers, rest)) = text.split_once("\r\n\r\n") {
+                    let length = headers
+                        .lines()
+                        .find_map(|line| {
+                            line.to_lowercase()
+                                .strip_prefix("content-length:")
+                                .map(|v| v.trim().parse::<usize>().unwrap())
+                        })
+                        .unwrap_or(0);
+                    if rest.len() >= length || n == 0 {
+                        break;
+                    }
+                }
+            }
+            let text = String::from_utf8_lossy(&request).to_lowercase();
+            recorded
+                .lock()
+                .unwrap()
+                .push(text.contains("x-shared-client: yes"));
+            let response = format!(
+                "HTTP/1.1 200 OK\r\ncontent-type: application/json\r\nconnection: close\r\ncontent-length: {}\r\n\r\n{}",
+                body.len(),
+                body
+            );
+            stream.write_all(response.as_bytes()).unwrap();
+        }
+    });
+
+    (url, requests)
+}
+
+/// A client whose requests can be told apart by their `x-shared-client` header
+fn shared_client(api_key: &str, url: &str) -> LlmClient {
+    let mut headers = reqwest::header::HeaderMap::new();
+    headers.insert("x-shared-client", "yes".parse().unwrap());
+    let http_client = reqwest::Client::builder()
+        .default_headers(headers)
+        .build()
+        .unwrap();
+    LlmClient::with_http_client(api_key, Some(url), http_client)
+}
+
+#[tokio::test(flavor = "current_thread")]
+async fn test_verification_reuses_the_shared_client() {
+    let (path, first, second) = common::init_local_repo();
+    let (url, requests) = start_model();
+    let options = AnalysisOptions {
+        llm_client: Some(shared_client("test-key", &url)),
+        ..Default::default()
+    };
+
+    verify_intent_with_options(
+        &path,
+        &second,
+        &path,
+        &first,
+        &second,
+        "Make the calculator add numbers",
+        "test-key",
+        None,
+        Some(&url),
+        &options,
+    )
+    .await
+    .expect("Verification against the local model should succeed");
+
+    let requests = requests.lock().unwrap().clone();
+    println!(
+        "\n🔌 {} request(s) through the shared client: {:?}",
+        requests.len(),
+        requests
+    );
+    assert!(
+        requests.len() >= 3,
+        "Extraction, file analysis and assessment should all run"
+    );
+    assert!(
+        requests.iter().all(|&shared| shared),
+        "Every request should go through the shared client"
+    );
+
+    let _ = std::fs::remove_dir_all(&path);
+}
+
+#[tokio::test(flavor = "current_thread")]
+async fn test_shared_client_for_another_key_is_not_used() {
+    let (path, first, second) = common::init_local_repo();
+    let (url, requests) = start_model();
+    let options = AnalysisOptions {
+        llm_client: Some(shared_client("other-key", &url)),
+        ..Default::default()
+    };
+
+    verify_intent_with_options(
+        &path,
+        &second,
+        &path,
+        &first,
+        &second,
+        "Make the calculator add numbers",
+        "test-key",
+        None,
+        Some(&url),
+        &options,
+    )
+    .await
+    .expect("Verification against the local model should succeed");
+
+    let requests = requests.lock().unwrap().clone();
+    assert!(!requests.is_empty());
+    assert!(
+        requests.iter().all(|&shared| !shared),
+        "A client for another API key must not send this verification's requests"
+    );
+
+    let _ = std::fs::remove_dir_all(&path);
+}
+
+#[test]
+fn test_llm_client_serves() {
+    let client = LlmClient::new(
+        "key",
+        Some("http://localhost:1/v1"),
+        &AnalysisOptions::default(),
+    )
+    .expect("Should build the client");
+    assert!(client.serves("key", Some("http://localhost:1/v1")));
+    assert!(!client.serves("key", None));
+    assert!(!client.serves("other", Some("http://localhost:1/v1")));
+
+    let invalid_proxy = AnalysisOptions {
+        proxy: Some("not a proxy url".to_string()),
+        ..Default::default()
+    };
+    assert!(LlmClient::new("key", None, &invalid_proxy).is_err());
+}
+
+#[tokio::test(flavor = "current_thread")]
+async fn test_verification_through_host_fetch() {
+    let requests = Arc::new(Mutex::new(Vec::<FetchRequest>::new()));
+    let recorded = requests.clone();
+    let client = LlmClient::with_fetch(
+        "test-key",
+        Some("https://llm.example/v1/"),
+        move |request| {
+            recorded.lock().unwrap().push(request);
+            Box::pin(async {
+                Ok(FetchResponse {
+                    status: 200,
+                    body: completion_body(),
+                })
+            })
+        },
+    );
+    let options = AnalysisOptions {
+        llm_client: Some(client),
+        ..Default::default()
+    };
+
+    let base = RepoSnapshot::from_files([
+        ("src/lib.rs", common::SUM_STUB),
+        (
+            "tests/sum_tests.rs",
+            "#[test]\nfn sums() {\n    assert_eq!(calc::sum(1, 2), 3);\n}\n",
+        ),
+    ]);
+    let mut head = base.clone();
+    head.insert("src/lib.rs", common::SUM_IMPL);
+
+    let result = verify_intent_with_snapshots(
+        &head,
+        &base,
+        &head,
+        "Make the calculator add numbers",
+        "test-key",
+        None,
+        Some("https://llm.example/v1/"),
+        &options,
+    )
+    .await
+    .expect("Verification through the host's fetch should succeed");
+
+    let requests = requests.lock().unwrap().clone();
+    println!(
+        "\n🌐 {} request(s) through the host's fetch",
+        requests.len()
+    );
+    assert!(result.is_intent_fulfilled);
+    assert!(requests.len() >= 3);
+    let request = &requests[0];
+    assert_eq!(request.method, "POST");
+    assert_eq!(request.url, "https://llm.example/v1/chat/completions");
+    assert!(
+        request
+            .headers
+            .contains(&("authorization".to_string(), "Bearer test-key".to_string()))
+    );
+    let body: serde_json::Value = serde_json::from_str(&request.body).unwrap();
+    assert!(body["messages"].is_array());
+}
+
+#[tokio::test(flavor = "current_thread")]
+async fn test_host_fetch_errors_are_reported() {
+    let client = LlmClient::with_fetch("test-key", None, |_| {
+        Box::pin(async {
+            Ok(FetchResponse {
+                status: 401,
+                body: r#"{"error": {"message": "Incorrect API key provided"}}"#.to_string(),
+            })
+        })
+    });
+    let options = AnalysisOptions {
+        llm_client: Some(client),
+        ..Default::default()
+    };
+    let base = RepoSnapshot::from_files([("src/lib.rs", common::SUM_STUB)]);
+    let mut head = base.clone();
+    head.insert("src/lib.rs", common::SUM_IMPL);
+
+    let result = verify_intent_with_snapshots(
+        &head,
+        &base,
+        &head,
+        "Make the calculator add numbers",
+        "test-key",
+        None,
+        None,
+        &options,
+    )
+    .await;
+    let report = match result {
+        Ok(result) => result
+            .warnings
+            .iter()
+            .map(|w| w.message.clone())
+            .collect::<Vec<_>>()
+            .join("\n"),
+        Err(e) => e.to_string(),
+    };
+    assert!(
+        report.contains("Incorrect API key provided"),
+        "The provider's error should be surfaced: {}",
+        report
+    );
+}
@@ -0,0 +1,33 @@
+use intent_verification::locate_snippet;
+
+const CONTENT: &str = "use std::fmt;\n\npub fn sum(a: i32, b: i32) -> i32 {\n    let total = a + b;\n\n    total\n}\n";
+
+#[test]
+fn test_locate_single_line_snippet() {
+    assert_eq!(locate_snippet(CONTENT, "let total = a + b;"), Some((4, 4)));
+    assert_eq!(
+        locate_snippet(CONTENT, "a + b"),
+        Some((4, 4)),
+        "Fragments of a line should match"
+    );
+}
+
+#[test]
+fn test_locate_multi_line_snippet_with_different_indentation() {
+    let snippet = "pub fn sum(a: i32, b: i32) -> i32 {\nlet total = a + b;\ntotal\n}";
+    assert_eq!(
+        locate_snippet(CONTENT, snippet),
+        Some((3, 7)),
+        "Indentation and blank lines should be ignored"
+    );
+}
+
+#[test]
+fn test_locate_hallucinated_snippet() {
+    assert_eq!(locate_snippet(CONTENT, "return a * b;"), None);
+    assert_eq!(
+        locate_snippet(CONTENT, "let total = a + b;\nreturn total;"),
+        None
+    );
+    assert_eq!(locate_snippet(CONTENT, "   \n"), None);
+}
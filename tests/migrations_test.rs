@@ -0,0 +1,125 @@
+use intent_verification::{
+    ChangeType, FileChange, IntentVerificationResult, Severity, apply_migration_findings,
+    is_migration_path, parse_sql, scan_migrations,
+};
+
+fn added(path: &str, content: &str) -> FileChange {
+    FileChange {
+        path: path.to_string(),
+        status: ChangeType::Added,
+        content: Some(content.to_string()),
+        old_content: None,
+    }
+}
+
+const MIGRATION: &str = "-- Clean up accounts
+CREATE TABLE audit_log (id serial PRIMARY KEY, note text DEFAULT 'a;b');
+/* legacy data
+   is gone */
+DROP TABLE IF EXISTS public.legacy_users;
+ALTER TABLE accounts
+    DROP COLUMN nickname,
+    ALTER COLUMN balance TYPE integer,
+    ADD COLUMN created_at timestamptz,
+    DROP CONSTRAINT accounts_email_key;
+ALTER TABLE accounts RENAME COLUMN mail TO email;
+DELETE FROM sessions;
+DELETE FROM tokens WHERE expires_at < now();
+";
+
+#[test]
+fn test_is_migration_path() {
+    assert!(is_migration_path("migrations/0003_drop_users.sql"));
+    assert!(is_migration_path("db/migrate/add_index.sql"));
+    assert!(is_migration_path("sql/V2_1__accounts.sql"));
+    assert!(is_migration_path("schema/20240101120000_accounts.sql"));
+    assert!(!is_migration_path("queries/report.sql"));
+    assert!(!is_migration_path("migrations/0003_drop_users.py"));
+}
+
+#[test]
+fn test_parse_sql() {
+    let statements = parse_sql(MIGRATION);
+    println!("\n🗃️ Statements: {:#?}", statements);
+
+    assert_eq!(statements.len(), 6);
+    assert_eq!(
+        statements[0].text,
+        "CREATE TABLE audit_log (id serial PRIMARY KEY, note text DEFAULT 'a;b')",
+        "Semicolons inside quotes don't end a statement"
+    );
+    assert_eq!(statements[0].line, 2);
+    assert_eq!(
+        statements[1].text,
+        "DROP TABLE IF EXISTS public.legacy_users"
+    );
+    assert_eq!(statements[1].line, 5);
+    assert_eq!(statements[2].line, 6);
+}
+
+#[test]
+fn test_scan_migrations() {
+    let findings = scan_migrations(
+        &[added("migrations/0007_cleanup.sql", MIGRATION)],
+        "Rename the mail column to email",
+    );
+    println!("\n🗃️ Migration findings: {:#?}", findings);
+
+    let rules: Vec<(&str, Severity)> = findings
+        .iter()
+        .map(|f| (f.rule.as_str(), f.severity))
+        .collect();
+    assert_eq!(
+        rules,
+        vec![
+            ("migrations/drop-table", Severity::High),
+            ("migrations/drop-column", Severity::High),
+            ("migrations/column-type", Severity::High),
+            ("migrations/rename-column", Severity::Low),
+            ("migrations/delete-all", Severity::High),
+        ],
+        "Constraint drops and filtered deletes are fine; the intent asks for the rename"
+    );
+    assert_eq!(findings[0].line, Some(5));
+    assert!(findings[1].message.contains("`accounts.nickname`"));
+}
+
+#[test]
+fn test_existing_statements_and_other_files_are_ignored() {
+    let edited = FileChange {
+        path: "migrations/0007_cleanup.sql".to_string(),
+        status: ChangeType::Modified,
+        content: Some(
+            "DROP TABLE legacy_users;\nCREATE INDEX accounts_email ON accounts (email);\n"
+                .to_string(),
+        ),
+        old_content: Some("DROP   TABLE legacy_users;\n".to_string()),
+    };
+    let query = added("queries/cleanup.sql", "DROP TABLE reports;\n");
+
+    assert!(scan_migrations(&[edited, query], "Add an index").is_empty());
+}
+
+#[test]
+fn test_destructive_migrations_fail_the_verdict() {
+    let mut result: IntentVerificationResult = serde_json::from_str(
+        r#"{"is_intent_fulfilled":true,"confidence":0.9,"explanation":"1 out of 1 changed files support the test intent","files_analyzed":[],"overall_assessment":""}"#,
+    )
+    .unwrap();
+    result.findings = scan_migrations(
+        &[added("migrations/0008.sql", "TRUNCATE TABLE orders;\n")],
+        "Speed up the order report",
+    );
+
+    assert_eq!(apply_migration_findings(&mut result), 1);
+    assert!(!result.is_intent_fulfilled);
+    assert!(
+        result
+            .explanation
+            .ends_with("; 1 destructive migration statement(s) the intent doesn't ask for")
+    );
+
+    result.is_intent_fulfilled = true;
+    result.findings[0].suppressed = true;
+    assert_eq!(apply_migration_findings(&mut result), 0);
+}
@@ -0,0 +1,251 @@
+use intent_verification::{
+    AnalysisOptions, LlmClient, MockProvider, RepoSnapshot, verify_intent_with_snapshots,
+};
+
+const TARGETS: &str = r#"{"functions": ["sum"], "files": ["src/lib.rs"]}"#;
+const SUPPORTS: &str = r#"{
+    "supports_intent": true,
+    "relevance": "required",
+    "reasoning": "sum now adds its arguments",
+    "relevant_changes": ["a + b"],
+    "locations": [],
+    "confidence": 0.9
+}"#;
+
+fn snapshots() -> (RepoSnapshot, RepoSnapshot) {
+    let base = RepoSnapshot::from_files([(
+        "src/lib.rs",
+        "pub fn sum(a: i32, b: i32) -> i32 {\n    todo!()\n}\n",
+    )]);
+    let head = RepoSnapshot::from_files([(
+        "src/lib.rs",
+        "pub fn sum(a: i32, b: i32) -> i32 {\n    a + b\n}\n",
+    )]);
+    (base, head)
+}
+
+fn options(mock: &MockProvider) -> AnalysisOptions {
+    AnalysisOptions {
+        llm_client: Some(LlmClient::mock(mock.clone())),
+        ..Default::default()
+    }
+}
+
+#[tokio::test(flavor = "current_thread")]
+async fn test_verification_runs_against_the_mock() {
+    let (base, head) = snapshots();
+    let mock = MockProvider::new()
+        .respond_when("Extract from the following prompt", TARGETS)
+        .respond_when("STEP 2", SUPPORTS)
+        .respond("sum adds its arguments, so the tests should pass.");
+
+    // No API key is needed when the mock answers
+    let result = verify_intent_with_snapshots(
+        &head,
+        &base,
+        &head,
+        "The sum function adds two numbers",
+        "",
+        Some("test-model"),
+        None,
+        &options(&mock),
+    )
+    .await
+    .expect("The mock should answer every request");
+
+    assert!(
+        result.is_intent_fulfilled,
+        "The mocked analysis supports the intent"
+    );
+    assert!(!result.is_partial, "Warnings: {:?}", result.warnings);
+    assert_eq!(
+        result.overall_assessment,
+        "sum adds its arguments, so the tests should pass."
+    );
+
+    let calls = mock.calls();
+    assert_eq!(
+        calls.len(),
+        3,
+        "Targets, the file and the overall assessment"
+    );
+    assert!(calls.iter().all(|call| call.model == "test-model"));
+    assert!(
+        calls[0]
+            .prompt()
+            .contains("The sum function adds two numbers")
+    );
+    assert!(calls[1].prompt().contains("SOLUTION FILE: src/lib.rs"));
+    assert_eq!(calls[1].messages[0].role, "system");
+
+    println!("\n✅ {} requests answered by the mock", calls.len());
+}
+
+#[tokio::test(flavor = "current_thread")]
+async fn test_rule_based_replies() {
+    let (base, head) = snapshots();
+    let mock = MockProvider::new()
+        .respond_with(|call| {
+            call.prompt()
+                .contains("Extract from the following prompt")
+                .then(|| TARGETS.to_string())
+        })
+        .respond_with(|call| {
+            let supports = call.prompt().contains("a + b");
+            call.prompt().contains("STEP 2").then(|| {
+                SUPPORTS.replace(
+                    "\"supports_intent\": true",
+                    &format!("\"supports_intent\": {}", supports),
+                )
+            })
+        })
+        .respond("Assessment");
+
+    let result = verify_intent_with_snapshots(
+        &head,
+        &base,
+        &head,
+        "The sum function adds two numbers",
+        "",
+        None,
+        None,
+        &options(&mock),
+    )
+    .await
+    .unwrap();
+    assert!(result.files_analyzed[0].supports_intent);
+
+    // Rules see the request, so a wrong implementation flips the verdict
+    let wrong = RepoSnapshot::from_files([(
+        "src/lib.rs",
+        "pub fn sum(a: i32, b: i32) -> i32 {\n    a - b\n}\n",
+    )]);
+    let result = verify_intent_with_snapshots(
+        &wrong,
+        &base,
+        &wrong,
+        "The sum function adds two numbers",
+        "",
+        None,
+        None,
+        &options(&mock),
+    )
+    .await
+    .unwrap();
+    assert!(!result.files_analyzed[0].supports_intent);
+    assert_eq!(mock.call_count(), 6);
+
+    println!("\n✅ Rules computed replies from the requests");
+}
+
+#[tokio::test(flavor = "current_thread")]
+async fn test_failure_injection() {
+    let (base, head) = snapshots();
+    let mock = MockProvider::new()
+        .fail_when("STEP 2", "model overloaded")
+        .respond_when("Extract from the following prompt", TARGETS)
+        .respond("Assessment");
+    mock.fail_next("rate limited");
+
+    let result = verify_intent_with_snapshots(
+        &head,
+        &base,
+        &head,
+        "The sum function adds two numbers",
+        "",
+        None,
+        None,
+        &options(&mock),
+    )
+    .await
+    .expect("Failed requests are recorded as warnings");
+
+    assert!(result.is_partial, "Both injected failures should show");
+    let messages: Vec<&str> = result.warnings.iter().map(|w| w.message.as_str()).collect();
+    assert!(
+        messages.iter().any(|m| m.contains("rate limited")),
+        "The queued failure hits the first request: {:?}",
+        messages
+    );
+    assert!(
+        messages.iter().any(|m| m.contains("model overloaded")),
+        "The file analysis fails: {:?}",
+        messages
+    );
+
+    let unanswered = MockProvider::new();
+    let result = verify_intent_with_snapshots(
+        &head,
+        &base,
+        &head,
+        "The sum function adds two numbers",
+        "",
+        None,
+        None,
+        &options(&unanswered),
+    )
+    .await
+    .expect("Unanswered requests fail like API errors");
+    assert!(result.is_partial);
+    assert!(
+        result.warnings.iter().any(|w| w
+            .message
+            .contains("MockProvider has no reply for request #1")),
+        "Warnings: {:?}",
+        result.warnings
+    );
+
+    println!("\n✅ Injected failures surfaced as warnings");
+}
+
+#[tokio::test(flavor = "current_thread")]
+async fn test_structured_targets_reach_the_assessment() {
+    let (base, head) = snapshots();
+    let mock = MockProvider::new()
+        .respond_when(
+            "Extract from the following prompt",
+            r#"```json
+{
+  "functions": ["sum"],
+  "files": [],
+  "modules": ["math"],
+  "classes": ["Calculator"],
+  "commands": ["calc add"],
+  "endpoints": ["POST /sum"]
+}
+```"#,
+        )
+        .respond_when("STEP 2", SUPPORTS)
+        .respond("sum adds its arguments.");
+
+    let result = verify_intent_with_snapshots(
+        &head,
+        &base,
+        &head,
+        "The Calculator in the math module sums numbers for `calc add` and POST /sum",
+        "",
+        None,
+        None,
+        &options(&mock),
+    )
+    .await
+    .unwrap();
+    assert!(result.is_intent_fulfilled);
+
+    let calls = mock.calls();
+    assert!(calls[0].prompt().contains("\"endpoints\": [\"...\"]"));
+    let assessment = calls.last().unwrap().prompt();
+    assert!(
+        assessment.contains(
+            "Modules: math\nClasses: Calculator\nCommands: calc add\nEndpoints: POST /sum"
+        )
+    );
+}
+
+#[test]
+fn test_targets_without_the_richer_schema_parse() {
+    let targets: intent_verification::TestTargets = serde_json::from_str(TARGETS).unwrap();
+    assert_eq!(targets.functions, vec!["sum"]);
+    assert!(targets.modules.is_empty() && targets.endpoints.is_empty());
+    assert_eq!(targets.other_targets(), "none");
+}
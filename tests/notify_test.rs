@@ -0,0 +1,146 @@
+use intent_verification::{
+    IntentVerificationResult, NotifyChannel, NotifyConfig, NotifyKind, notification_payload,
+    send_notifications, verdict_summary,
+};
+
+fn sample_result() -> IntentVerificationResult {
+    IntentVerificationResult {
+        is_intent_fulfilled: true,
+        confidence: 0.85,
+        explanation: "2 out of 3 changed files support the test intent".to_string(),
+        overall_assessment: "The changes implement the required function.".to_string(),
+        ..Default::default()
+    }
+}
+
+/// Accept one HTTP request on a local port and hand its body to the test
+fn start_receiver() -> (String, std::sync::mpsc::Receiver<String>) {
+    use std::io::{Read, Write};
+
+    let listener = std::net::TcpListener::bind("127.0.0.1:0").unwrap();
+    let url = format!("http://{}/hook", listener.local_addr().unwrap());
+    let (sender, receiver) = std::sync::mpsc::channel();
+
+    std::thread::spawn(move || {
+        let (mut stream, _) = listener.accept().unwrap();
+        let mut request = Vec::new();
+        let mut buffer = [0u8; 4096];
+        loop {
+            let n = stream.read(&mut buffer).unwrap();
+            request.extend_from_slice(&buffer[..n]);
+            let text = String::from_utf8_lossy(&request).to_string();
+            if let Some((headers, body)) = text.split_once("\r\n\r\n") {
+                let length = headers
+                    .lines()
+                    .find_map(|line| {
+                        line.to_lowercase()
+                            .strip_prefix("content-length:")
+                            .map(|v| v.trim().parse::<usize>().unwrap())
+                    })
+                    .unwrap_or(0);
+                if body.len() >= length || n == 0 {
+                    stream
+                        .write_all(b"HTTP/1.1 200 OK\r\ncontent-length: 0\r\n\r\n")
+                        .unwrap();
+                    sender.send(body.to_string()).unwrap();
+                    return;
+                }
+            }
+        }
+    });
+
+    (url, receiver)
+}
+
+#[test]
+fn test_notification_payloads() {
+    let config = NotifyConfig::from_toml(
+        r#"
+report_url = "https://ci.example.com/runs/42"
+
+[[channels]]
+kind = "slack"
+url = "https://hooks.slack.com/services/T000/B000/XXXX"
+
+[[channels]]
+kind = "discord"
+url = "https://discord.com/api/webhooks/1/abc"
+"#,
+    )
+    .expect("Should parse the notification config");
+    assert_eq!(config.channels.len(), 2);
+    assert_eq!(config.channels[1].kind, NotifyKind::Discord);
+
+    let mut result = sample_result();
+    assert_eq!(
+        verdict_summary(&result),
+        "✅ Intent fulfilled (confidence 85%): 2 out of 3 changed files support the test intent"
+    );
+
+    let report_url = config.report_url.as_deref();
+    let slack = notification_payload(NotifyKind::Slack, &result, report_url);
+    let discord = notification_payload(NotifyKind::Discord, &result, report_url);
+
+    println!("\n📣 Slack payload: {}", slack);
+
+    assert!(
+        slack["text"]
+            .as_str()
+            .unwrap()
+            .ends_with("\n<https://ci.example.com/runs/42|Full report>")
+    );
+    assert!(
+        discord["content"]
+            .as_str()
+            .unwrap()
+            .ends_with("\n[Full report](https://ci.example.com/runs/42)")
+    );
+
+    result.is_intent_fulfilled = false;
+    result.is_partial = true;
+    let webhook = notification_payload(NotifyKind::Webhook, &result, None);
+    assert_eq!(webhook["is_intent_fulfilled"], false);
+    assert!(webhook["report_url"].is_null());
+    assert!(
+        webhook["summary"]
+            .as_str()
+            .unwrap()
+            .starts_with("❌ Intent not fulfilled (confidence 85%, partial analysis)")
+    );
+}
+
+#[tokio::test]
+async fn test_send_notifications() {
+    let (url, received) = start_receiver();
+    let config = NotifyConfig {
+        report_url: None,
+        channels: vec![
+            // Nothing listens on port 9
+            NotifyChannel {
+                kind: NotifyKind::Slack,
+                url: "http://127.0.0.1:9/hook".to_string(),
+            },
+            NotifyChannel {
+                kind: NotifyKind::Webhook,
+                url,
+            },
+        ],
+    };
+
+    let error = send_notifications(&config, &sample_result())
+        .await
+        .expect_err("The unreachable channel should be reported");
+    assert!(
+        error
+            .to_string()
+            .contains("Slack (http://127.0.0.1:9/hook)")
+    );
+
+    let body: serde_json::Value = serde_json::from_str(
+        &received
+            .recv_timeout(std::time::Duration::from_secs(10))
+            .expect("The other channels should still be notified"),
+    )
+    .unwrap();
+    assert_eq!(body["is_intent_fulfilled"], true);
+}
@@ -0,0 +1,212 @@
+#![cfg(feature = "git")]
+
+mod common;
+
+use std::sync::atomic::{AtomicUsize, Ordering};
+use std::sync::{Arc, Mutex};
+
+use intent_verification::{
+    AnalysisObserver, AnalysisOptions, FileAnalysisResult, IntentVerificationResult,
+    IntentVerifier, PromptPreview, TestTargets,
+};
+
+/// Serve chat completions on a local port, answering every request with the same JSON and
+/// counting the requests
+fn start_model() -> (String, Arc<AtomicUsize>) {
+    use std::io::{Read, Write};
+
+    let listener = std::net::TcpListener::bind("127.0.0.1:0").unwrap();
+    let url = format!("http://{}/v1", listener.local_addr().unwrap());
+    let requests = Arc::new(AtomicUsize::new(0));
+    let counter = requests.clone();
+
+    let content = serde_json::json!({
+        "functions": ["sum"],
+        "files": ["src/lib.rs"],
+        "supports_intent": true,
+        "relevance": "required",
+        "reasoning": "sum now adds its arguments",
+        "relevant_changes": ["a + b"],
+        "locations": [],
+        "confidence": 0.9
+    })
+    .to_string();
+    let body = serde_json::json!({
+        "id": "chatcmpl-test",
+        "object": "chat.completion",
+        "created": 0,
+        "model": "test-model",
+        "choices": [{
+            "index": 0,
+            "message": {"role": "assistant", "content": content},
+            "finish_reason": "stop"
+        }]
+    })
+    .to_string();
+
+    std::thread::spawn(move || {
+        for stream in listener.incoming() {
+            let mut stream = stream.unwrap();
+            let mut request = Vec::new();
+            let mut buffer = [0u8; 4096];
+            loop {
+                let n = stream.read(&mut buffer).unwrap();
+                request.extend_from_slice(&buffer[..n]);
+                let text = String::from_utf8_lossy(&request).to_string();
+                if let Some((headers, rest)) = text.split_once("\r\n\r\n") {
+                    let length = headers
+                        .lines()
+                        .find_map(|line| {
+                            line.to_lowercase()
+                                .strip_prefix("content-length:")
+                                .map(|v| v.trim().parse::<usize>().unwrap())
+                        })
+                        .unwrap_or(0);
+                    if rest.len() >= length || n == 0 {
+                        break;
+                    }
+                }
+            }
+            counter.fetch_add(1, Ordering::SeqCst);
+            let response = format!(
+                "HTTP/1.1 200 OK\r\ncontent-type: application/json\r\nconnection: close\r\ncontent-length: {}\r\n\r\n{}",
+                body.len(),
+                body
+            );
+            stream.write_all(response.as_bytes()).unwrap();
+        }
+    });
+
+    (url, requests)
+}
+
+/// Records the name of each event, in order
+#[derive(Default)]
+struct RecordingObserver {
+    events: Mutex<Vec<String>>,
+    responses: AtomicUsize,
+}
+
+impl RecordingObserver {
+    fn record(&self, event: &str) {
+        self.events.lock().unwrap().push(event.to_string());
+    }
+
+    fn count(&self, event: &str) -> usize {
+        self.events
+            .lock()
+            .unwrap()
+            .iter()
+            .filter(|e| e.as_str() == event)
+            .count()
+    }
+}
+
+impl AnalysisObserver for RecordingObserver {
+    fn on_clone_start(&self, _repo_url: &str) {
+        self.record("clone");
+    }
+
+    fn on_targets_extracted(&self, targets: &TestTargets) {
+        assert!(targets.files.contains(&"src/lib.rs".to_string()));
+        self.record("targets");
+    }
+
+    fn on_prompt_built(&self, prompt: &PromptPreview) {
+        assert!(!prompt.messages.is_empty());
+        self.record("prompt");
+    }
+
+    fn on_llm_response(&self, _prompt: &PromptPreview, response: &str) {
+        assert!(!response.is_empty());
+        self.responses.fetch_add(1, Ordering::SeqCst);
+        self.record("response");
+    }
+
+    fn on_file_result(&self, result: &FileAnalysisResult) {
+        assert_eq!(result.analysis.file_path, "src/lib.rs");
+        self.record("file");
+    }
+
+    fn on_complete(&self, result: &IntentVerificationResult) {
+        assert_eq!(result.files_analyzed.len(), 1);
+        self.record("complete");
+    }
+}
+
+#[tokio::test(flavor = "current_thread")]
+async fn test_observer_sees_every_step() {
+    let (path, first, second) = common::init_local_repo();
+    let (url, requests) = start_model();
+    let observer = Arc::new(RecordingObserver::default());
+
+    let result = IntentVerifier::builder()
+        .repo(&path)
+        .commits(&first, &second)
+        .intent("The sum function should add two numbers")
+        .api_key("unused")
+        .base_url(&url)
+        .observer(Arc::clone(&observer))
+        .build()
+        .unwrap()
+        .verify()
+        .await
+        .unwrap();
+    assert!(!result.is_partial, "Warnings: {:?}", result.warnings);
+
+    let events = observer.events.lock().unwrap().clone();
+    println!("\n📋 Events: {:?}", events);
+    assert!(observer.count("clone") >= 2, "Tests and changes are cloned");
+    assert_eq!(observer.count("targets"), 1);
+    assert_eq!(observer.count("file"), 1);
+    assert_eq!(events.last().map(String::as_str), Some("complete"));
+    assert_eq!(
+        observer.responses.load(Ordering::SeqCst),
+        requests.load(Ordering::SeqCst),
+        "Every model request should be observed"
+    );
+    assert_eq!(observer.count("prompt"), observer.count("response"));
+
+    // Extraction, reading the tests, diffing, the file's analysis, then the assessment
+    assert_eq!(
+        events,
+        [
+            "prompt", "response", "targets", "clone", "clone", "prompt", "response", "file",
+            "prompt", "response", "complete"
+        ]
+    );
+
+    println!("✅ The observer saw every step of the verification");
+    let _ = std::fs::remove_dir_all(&path);
+}
+
+#[tokio::test(flavor = "current_thread")]
+async fn test_observer_sees_dry_run_prompts() {
+    let (path, first, second) = common::init_local_repo();
+    let observer = Arc::new(RecordingObserver::default());
+
+    let result = IntentVerifier::builder()
+        .repo(&path)
+        .commits(&first, &second)
+        .intent("Implement sum() in src/lib.rs")
+        .options(AnalysisOptions {
+            dry_run: true,
+            ..Default::default()
+        })
+        .observer(Arc::clone(&observer))
+        .build()
+        .unwrap()
+        .verify()
+        .await
+        .unwrap();
+
+    assert_eq!(observer.count("prompt"), result.prompts.len());
+    assert_eq!(observer.count("response"), 0, "A dry run sends nothing");
+    assert_eq!(observer.count("complete"), 1);
+
+    println!(
+        "\n✅ Dry run prompts were observed: {}",
+        result.prompts.len()
+    );
+    let _ = std::fs::remove_dir_all(&path);
+}
@@ -0,0 +1,81 @@
+use intent_verification::AnalysisOptions;
+
+#[test]
+fn test_language_instruction() {
+    let mut options = AnalysisOptions {
+        language: Some("vi".to_string()),
+        ..Default::default()
+    };
+    let instruction = options
+        .language_instruction()
+        .expect("Vietnamese should produce an instruction");
+
+    println!("\n🌐 Language instruction: {}", instruction);
+
+    assert!(instruction.contains("in Vietnamese"));
+    assert!(
+        instruction.contains("Keep JSON keys"),
+        "Machine-readable fields must stay unchanged"
+    );
+
+    options.language = Some("Brazilian Portuguese".to_string());
+    assert!(
+        options
+            .language_instruction()
+            .unwrap()
+            .contains("in Brazilian Portuguese")
+    );
+}
+
+#[test]
+fn test_language_instruction_defaults_to_english() {
+    assert!(AnalysisOptions::default().language_instruction().is_none());
+
+    let options = AnalysisOptions {
+        language: Some("EN".to_string()),
+        ..Default::default()
+    };
+    assert!(options.language_instruction().is_none());
+}
+
+#[test]
+fn test_concurrency_is_at_least_one() {
+    assert_eq!(AnalysisOptions::default().concurrency(), 1);
+
+    let options = AnalysisOptions {
+        concurrency: Some(0),
+        ..Default::default()
+    };
+    assert_eq!(options.concurrency(), 1);
+
+    let options = AnalysisOptions {
+        concurrency: Some(8),
+        ..Default::default()
+    };
+    assert_eq!(options.concurrency(), 8);
+}
+
+#[test]
+fn test_clarification_instruction() {
+    assert!(
+        AnalysisOptions::default()
+            .clarification_instruction()
+            .is_none()
+    );
+
+    let options = AnalysisOptions {
+        clarifications: vec![
+            "Overflow should saturate".to_string(),
+            "  ".to_string(),
+            "Only test_sum matters".to_string(),
+        ],
+        ..Default::default()
+    };
+    let instruction = options
+        .clarification_instruction()
+        .expect("Clarifications should produce an instruction");
+
+    println!("\n💬 Clarification instruction: {}", instruction);
+
+    assert!(instruction.ends_with("- Overflow should saturate\n- Only test_sum matters"));
+}
@@ -0,0 +1,24 @@
+#![cfg(feature = "otel")]
+
+use intent_verification::init_otel_tracing;
+
+#[tokio::test(flavor = "multi_thread")]
+async fn test_otel_tracing_installs_once() {
+    // Nothing listens on port 9, so exports fail without affecting the caller
+    let guard = init_otel_tracing("intent-verify-test", Some("http://127.0.0.1:9/v1/traces"))
+        .expect("Should set up the exporter");
+    tracing::info_span!("llm_request", model = "test-model").in_scope(|| {});
+
+    let again = init_otel_tracing("intent-verify-test", None);
+    println!(
+        "\n🔭 Second setup: {:?}",
+        again.as_ref().err().map(|e| e.to_string())
+    );
+    assert!(
+        again.is_err(),
+        "The global subscriber can only be installed once"
+    );
+
+    // Flushes on drop, as at the end of `main`
+    drop(guard);
+}
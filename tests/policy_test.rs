@@ -0,0 +1,133 @@
+use intent_verification::{
+    ChangeType, FileIntentAnalysis, Finding, IntentVerificationResult, Severity, VerdictPolicy,
+};
+
+fn file(path: &str, supports_intent: bool) -> FileIntentAnalysis {
+    FileIntentAnalysis {
+        file_path: path.to_string(),
+        change_type: ChangeType::Modified,
+        supports_intent,
+        reasoning: String::new(),
+        relevant_changes: vec![],
+        locations: vec![],
+        risk_score: 0.0,
+        relevance: None,
+        owners: vec![],
+        citations: vec![],
+        confidence: None,
+        support_probability: None,
+        outcome: Default::default(),
+    }
+}
+
+fn finding(path: &str, severity: Severity) -> Finding {
+    Finding {
+        rule: "test/rule".to_string(),
+        severity,
+        file_path: Some(path.to_string()),
+        line: Some(1),
+        snippet: None,
+        message: "Something is off".to_string(),
+        suppressed: false,
+        cwe: None,
+        content_hash: None,
+    }
+}
+
+fn sample_result() -> IntentVerificationResult {
+    IntentVerificationResult {
+        is_intent_fulfilled: true,
+        confidence: 0.8,
+        files_analyzed: vec![file("src/sum.rs", true), file("README.md", false)],
+        ..Default::default()
+    }
+}
+
+#[test]
+fn test_default_policy_follows_model_verdict() {
+    let policy = VerdictPolicy::default();
+
+    let decision = policy.evaluate(&sample_result());
+    assert!(decision.passed);
+    assert!(decision.reasons.is_empty());
+
+    let mut result = sample_result();
+    result.is_intent_fulfilled = false;
+    let decision = policy.evaluate(&result);
+    assert!(!decision.passed);
+    assert_eq!(
+        decision.reasons,
+        vec!["Intent is not fulfilled".to_string()]
+    );
+}
+
+#[test]
+fn test_policy_min_confidence_and_required_files() {
+    let policy = VerdictPolicy {
+        min_confidence: 0.9,
+        required_files: vec!["src/**/*.rs".to_string(), "tests/*.rs".to_string()],
+        ..Default::default()
+    };
+
+    let decision = policy.evaluate(&sample_result());
+
+    println!("\n⚖️  Policy decision: {:?}", decision);
+
+    assert!(!decision.passed);
+    assert_eq!(
+        decision.reasons.len(),
+        2,
+        "Confidence and the unmatched tests/*.rs pattern should both fail"
+    );
+    assert!(decision.reasons[0].contains("below the required 0.90"));
+    assert!(decision.reasons[1].contains("tests/*.rs"));
+}
+
+#[test]
+fn test_policy_findings_severity_and_file_limits() {
+    let mut result = sample_result();
+    result.findings = vec![
+        finding("src/sum.rs", Severity::Low),
+        finding("src/sum.rs", Severity::Medium),
+        finding("src/config.rs", Severity::High),
+    ];
+
+    let lenient = VerdictPolicy {
+        max_files_with_issues: Some(2),
+        fail_on_severity: Some(Severity::Critical),
+        ..Default::default()
+    };
+    assert!(lenient.evaluate(&result).passed);
+
+    let strict = VerdictPolicy {
+        max_files_with_issues: Some(1),
+        fail_on_severity: Some(Severity::Medium),
+        ..Default::default()
+    };
+    let decision = strict.evaluate(&result);
+    assert!(!decision.passed);
+    assert!(
+        decision
+            .reasons
+            .iter()
+            .any(|r| r.contains("2 files have issues"))
+    );
+    assert!(
+        decision
+            .reasons
+            .iter()
+            .any(|r| r.contains("2 findings at or above Medium"))
+    );
+}
+
+#[test]
+fn test_policy_deserializes_with_defaults() {
+    let policy: VerdictPolicy =
+        serde_json::from_str(r#"{ "min_confidence": 0.7, "fail_on_severity": "high" }"#)
+            .expect("Policy should deserialize");
+
+    assert!(policy.require_intent_fulfilled);
+    assert_eq!(policy.min_confidence, 0.7);
+    assert_eq!(policy.fail_on_severity, Some(Severity::High));
+    assert!(policy.required_files.is_empty());
+}
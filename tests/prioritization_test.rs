@@ -0,0 +1,255 @@
+use intent_verification::{
+    AnalysisOptions, ChangeType, FileChange, IntentVerificationResult, LlmClient, MockProvider,
+    RepoSnapshot, SkipReason, TestTargets, TestTargetsWithCode, change_priority,
+    prioritize_changes, render_markdown, verify_intent_with_snapshots,
+};
+
+fn added(path: &str, content: &str) -> FileChange {
+    FileChange {
+        path: path.to_string(),
+        status: ChangeType::Added,
+        content: Some(content.to_string()),
+        old_content: None,
+    }
+}
+
+fn targets(functions: &[&str], files: &[&str]) -> TestTargetsWithCode {
+    TestTargetsWithCode {
+        targets: TestTargets {
+            functions: functions.iter().map(|f| f.to_string()).collect(),
+            files: files.iter().map(|f| f.to_string()).collect(),
+            ..Default::default()
+        },
+        file_contents: vec![],
+        function_contents: vec![],
+        tested_sources: vec![],
+    }
+}
+
+#[test]
+fn test_priority_signals() {
+    let intent = "The parser rejects empty input";
+    let targets = targets(&["parse_config"], &["tests/parser.rs"]);
+    let score = |change: &FileChange| change_priority(change, intent, &targets);
+
+    let named = score(&added("src/config.rs", "pub fn parse_config() {}"));
+    let target_file = score(&added("tests/parser.rs", "#[test] fn t() {}"));
+    let path = score(&added("src/parser/input.rs", "pub fn read() {}"));
+    let similar = score(&added("src/other.rs", "// rejects empty input\n"));
+    let unrelated = score(&added("README.md", "Install with cargo"));
+
+    assert!(
+        named >= 3.0 && target_file >= 3.0,
+        "Target overlap weighs most"
+    );
+    assert!((2.0..3.0).contains(&path), "{}", path);
+    assert!(similar > 0.0 && similar <= 1.0, "{}", similar);
+    assert_eq!(unrelated, 0.0);
+    println!(
+        "\n✅ {:.2} {:.2} {:.2} {:.2} {:.2}",
+        named, target_file, path, similar, unrelated
+    );
+}
+
+#[test]
+fn test_prioritize_keeps_the_order() {
+    let changes = vec![
+        added("docs/a.md", "notes"),
+        added("src/sum.rs", "pub fn sum() {}"),
+        added("docs/b.md", "more notes"),
+        added("src/lib.rs", "mod sum;"),
+    ];
+    let targets = targets(&["sum"], &[]);
+
+    let (analyzed, skipped) = prioritize_changes(&changes, "sum adds numbers", &targets, 2);
+    let analyzed: Vec<&str> = analyzed.iter().map(|fc| fc.path.as_str()).collect();
+    assert_eq!(analyzed, vec!["src/sum.rs", "src/lib.rs"]);
+    let skipped: Vec<&str> = skipped.iter().map(|s| s.file_path.as_str()).collect();
+    assert_eq!(
+        skipped,
+        vec!["docs/a.md", "docs/b.md"],
+        "Ties keep their order"
+    );
+
+    let (analyzed, skipped) = prioritize_changes(&changes, "sum adds numbers", &targets, 10);
+    assert_eq!(analyzed.len(), 4);
+    assert!(skipped.is_empty());
+}
+
+#[tokio::test(flavor = "current_thread")]
+async fn test_max_files_analyzed() {
+    let base = RepoSnapshot::from_files([("README.md", "Old readme\n")]);
+    let head = RepoSnapshot::from_files([
+        ("README.md", "New readme\n"),
+        (
+            "src/sum.rs",
+            "pub fn sum(a: i32, b: i32) -> i32 {\n    a + b\n}\n",
+        ),
+        ("CHANGELOG.md", "Released\n"),
+    ]);
+    let mock = MockProvider::new()
+        .respond_when(
+            "Extract from the following prompt",
+            r#"{"functions": ["sum"], "files": []}"#,
+        )
+        .respond_when(
+            "STEP 2",
+            r#"{"supports_intent": true, "reasoning": "adds", "relevant_changes": []}"#,
+        )
+        .respond("Assessment");
+    let options = AnalysisOptions {
+        llm_client: Some(LlmClient::mock(mock.clone())),
+        max_files_analyzed: Some(1),
+        ..Default::default()
+    };
+
+    let result = verify_intent_with_snapshots(
+        &head,
+        &base,
+        &head,
+        "The sum function adds two numbers",
+        "",
+        None,
+        None,
+        &options,
+    )
+    .await
+    .unwrap();
+
+    assert_eq!(result.files_analyzed.len(), 1);
+    assert_eq!(result.files_analyzed[0].file_path, "src/sum.rs");
+    let mut skipped: Vec<&str> = result
+        .skipped_files
+        .iter()
+        .map(|s| s.file_path.as_str())
+        .collect();
+    skipped.sort();
+    assert_eq!(skipped, vec!["CHANGELOG.md", "README.md"]);
+    assert!(
+        result
+            .skipped_files
+            .iter()
+            .all(|s| s.reason == SkipReason::MaxFiles)
+    );
+    assert_eq!(
+        mock.calls()
+            .iter()
+            .filter(|call| call.prompt().contains("STEP 2"))
+            .count(),
+        1,
+        "Skipped files aren't sent"
+    );
+    assert!(
+        result
+            .explanation
+            .contains("2 less relevant files not analyzed")
+    );
+    assert!(render_markdown(&result).contains("2 changed files were not analyzed"));
+
+    println!("\n✅ {}", result.explanation);
+}
+
+#[tokio::test(flavor = "current_thread")]
+async fn test_size_limits() {
+    let base = RepoSnapshot::from_files([("src/lib.rs", "mod sum;\n")]);
+    let head = RepoSnapshot::from_files([
+        ("src/lib.rs", "mod sum;\nmod big;\n".to_string()),
+        (
+            "src/sum.rs",
+            "pub fn sum(a: i32, b: i32) -> i32 {\n    a + b\n}\n".to_string(),
+        ),
+        ("src/big.rs", "// generated\n".repeat(200)),
+        ("src/extra.rs", "pub fn extra() {}\n".to_string()),
+    ]);
+    let mock = MockProvider::new()
+        .respond_when(
+            "Extract from the following prompt",
+            r#"{"functions": ["sum"], "files": []}"#,
+        )
+        .respond_when(
+            "STEP 2",
+            r#"{"supports_intent": true, "reasoning": "adds", "relevant_changes": []}"#,
+        )
+        .respond("Assessment");
+    let verify = async |options: AnalysisOptions| {
+        let options = AnalysisOptions {
+            llm_client: Some(LlmClient::mock(mock.clone())),
+            ..options
+        };
+        verify_intent_with_snapshots(
+            &head,
+            &base,
+            &head,
+            "The sum function adds two numbers",
+            "",
+            None,
+            None,
+            &options,
+        )
+        .await
+        .unwrap()
+    };
+    let analyzed = |result: &IntentVerificationResult| {
+        let mut paths: Vec<String> = result
+            .files_analyzed
+            .iter()
+            .map(|fa| fa.file_path.clone())
+            .collect();
+        paths.sort();
+        paths
+    };
+
+    let result = verify(AnalysisOptions {
+        max_file_bytes: Some(1000),
+        ..Default::default()
+    })
+    .await;
+    assert_eq!(
+        analyzed(&result),
+        vec!["src/extra.rs", "src/lib.rs", "src/sum.rs"]
+    );
+    assert_eq!(result.skipped_files.len(), 1);
+    assert_eq!(result.skipped_files[0].file_path, "src/big.rs");
+    assert_eq!(result.skipped_files[0].reason, SkipReason::TooLarge);
+    assert!(
+        result
+            .explanation
+            .contains("1 files over the size limits not analyzed")
+    );
+    assert!(
+        mock.calls()
+            .iter()
+            .all(|call| !call.prompt().contains("// generated")),
+        "Files over the limit aren't attempted"
+    );
+
+    let result = verify(AnalysisOptions {
+        max_total_tokens: Some(100),
+        ..Default::default()
+    })
+    .await;
+    assert_eq!(
+        analyzed(&result),
+        vec!["src/extra.rs", "src/lib.rs", "src/sum.rs"],
+        "Smaller files after an oversized one still fit the budget"
+    );
+
+    let result = verify(AnalysisOptions {
+        max_total_files: Some(2),
+        max_files_analyzed: Some(1),
+        ..Default::default()
+    })
+    .await;
+    assert_eq!(analyzed(&result).len(), 1);
+    let mut reasons: Vec<SkipReason> = result.skipped_files.iter().map(|s| s.reason).collect();
+    reasons.sort_by_key(|reason| format!("{:?}", reason));
+    assert_eq!(
+        reasons,
+        vec![
+            SkipReason::MaxFiles,
+            SkipReason::TooLarge,
+            SkipReason::TooLarge
+        ]
+    );
+    println!("\n✅ {}", result.explanation);
+}
@@ -0,0 +1,343 @@
+#![cfg(feature = "git")]
+
+mod common;
+
+use intent_verification::{
+    AnalysisOptions, LlmClient, LocalOnlyPolicy, MockProvider, RepoSnapshot, ValidationError,
+    verify_intent_with_options, verify_intent_with_snapshots,
+};
+
+fn local_only(endpoint: &str) -> AnalysisOptions {
+    AnalysisOptions {
+        local_only: Some(LocalOnlyPolicy::new([endpoint])),
+        ..Default::default()
+    }
+}
+
+#[test]
+fn test_policy() {
+    let policy = LocalOnlyPolicy::new(["http://llm.internal:8000/v1/"]);
+    assert!(policy.allows_llm_endpoint(Some("http://llm.internal:8000/v1")));
+    assert!(policy.allows_llm_endpoint(Some("http://llm.internal:8000/v1/chat")));
+    assert!(
+        !policy.allows_llm_endpoint(Some("http://llm.internal:8000/v10")),
+        "Only paths under the endpoint are allowed"
+    );
+    assert!(!policy.allows_llm_endpoint(Some("https://api.openai.com/v1")));
+    assert!(
+        !policy.allows_llm_endpoint(None),
+        "The default endpoint is public"
+    );
+
+    assert!(policy.allows_repository("file:///srv/repos/app"));
+    assert!(policy.allows_repository("/tmp"));
+    assert!(!policy.allows_repository("https://github.com/org/repo"));
+    assert!(!policy.allows_repository("git@github.com:org/repo.git"));
+
+    println!("\n✅ Only the allowlisted endpoint and local repositories pass");
+}
+
+#[tokio::test(flavor = "current_thread")]
+async fn test_public_endpoints_fail_fast() {
+    let (path, first, second) = common::init_local_repo();
+
+    for base_url in [None, Some("https://api.openai.com/v1")] {
+        let error = verify_intent_with_options(
+            &path,
+            &second,
+            &path,
+            &first,
+            &second,
+            "sum() adds two numbers",
+            "sk-test",
+            None,
+            base_url,
+            &local_only("http://llm.internal:8000/v1"),
+        )
+        .await
+        .unwrap_err();
+        assert!(
+            matches!(
+                error.downcast_ref::<ValidationError>(),
+                Some(ValidationError::LlmEndpointNotAllowed { .. })
+            ),
+            "Expected the endpoint to be refused, got: {}",
+            error
+        );
+        println!("✅ {}", error);
+    }
+
+    let base = RepoSnapshot::from_commit(&path, &first).unwrap();
+    let head = RepoSnapshot::from_commit(&path, &second).unwrap();
+    let error = verify_intent_with_snapshots(
+        &head,
+        &base,
+        &head,
+        "sum() adds two numbers",
+        "sk-test",
+        None,
+        None,
+        &local_only("http://llm.internal:8000/v1"),
+    )
+    .await
+    .unwrap_err();
+    assert!(error.to_string().contains("default OpenAI endpoint"));
+
+    std::fs::remove_dir_all(&path).ok();
+}
+
+#[tokio::test(flavor = "current_thread")]
+async fn test_remote_repositories_are_refused() {
+    let (path, first, second) = common::init_local_repo();
+    let error = verify_intent_with_options(
+        "https://github.com/org/tests",
+        "main",
+        &path,
+        &first,
+        &second,
+        "sum() adds two numbers",
+        "sk-test",
+        None,
+        Some("http://127.0.0.1:9"),
+        &local_only("http://127.0.0.1:9"),
+    )
+    .await
+    .unwrap_err();
+    assert!(
+        matches!(
+            error.downcast_ref::<ValidationError>(),
+            Some(ValidationError::NetworkAccessNotAllowed { url }) if url == "https://github.com/org/tests"
+        ),
+        "Expected the remote to be refused, got: {}",
+        error
+    );
+
+    println!("\n✅ {}", error);
+    std::fs::remove_dir_all(&path).ok();
+}
+
+#[tokio::test(flavor = "current_thread")]
+async fn test_allowed_endpoint_and_local_repositories_run() {
+    let (path, first, second) = common::init_local_repo();
+
+    // Requests to the allowed endpoint are sent; nothing listens there, so they fail as usual
+    let result = verify_intent_with_options(
+        &path,
+        &second,
+        &format!("file://{}", path),
+        &first,
+        &second,
+        "sum() adds two numbers",
+        "sk-test",
+        None,
+        Some("http://127.0.0.1:9"),
+        &local_only("http://127.0.0.1:9"),
+    )
+    .await
+    .expect("The allowed endpoint and local repositories should pass the policy");
+    assert!(
+        result
+            .warnings
+            .iter()
+            .all(|w| !w.message.contains("local-only")),
+        "Warnings: {:?}",
+        result.warnings
+    );
+
+    // A mock client sends nothing over the network
+    let mock = MockProvider::new().respond("{}");
+    let options = AnalysisOptions {
+        llm_client: Some(LlmClient::mock(mock.clone())),
+        ..local_only("http://llm.internal:8000/v1")
+    };
+    verify_intent_with_options(
+        &path,
+        &second,
+        &path,
+        &first,
+        &second,
+        "sum() adds two numbers",
+        "",
+        None,
+        None,
+        &options,
+    )
+    .await
+    .unwrap();
+    assert!(mock.call_count() > 0);
+
+    println!("\n✅ Local verification ran under the policy");
+    std::fs::remove_dir_all(&path).ok();
+}
+
+/// Run the `intent-verify` binary in local-only mode with an unreachable allowed LLM endpoint,
+/// returning its stderr and whether `listener` was contacted
+#[cfg(feature = "cli")]
+fn run_local_only_cli(
+    args: &[&str],
+    env: &[(&str, &str)],
+    listener: &std::net::TcpListener,
+) -> (bool, String, bool) {
+    let dir = common::unique_path("local_only_cli");
+    std::fs::create_dir_all(&dir).unwrap();
+    let output = std::process::Command::new(env!("CARGO_BIN_EXE_intent-verify"))
+        .args(args)
+        .args([
+            "--local-only",
+            "--allowed-endpoint",
+            "http://127.0.0.1:9",
+            "--base-url",
+            "http://127.0.0.1:9",
+            "--api-key",
+            "test-key",
+        ])
+        .envs(env.iter().copied())
+        // Keep any `.env` file out of the way
+        .current_dir(&dir)
+        .output()
+        .unwrap();
+    listener.set_nonblocking(true).unwrap();
+    let contacted = listener.accept().is_ok();
+    (
+        output.status.success(),
+        String::from_utf8_lossy(&output.stderr).to_string(),
+        contacted,
+    )
+}
+
+#[cfg(feature = "cli")]
+#[test]
+fn test_cli_refuses_issues_in_local_only_mode() {
+    let (path, _, second) = common::init_local_repo();
+    let api = std::net::TcpListener::bind("127.0.0.1:0").unwrap();
+    let api_url = format!("http://{}", api.local_addr().unwrap());
+
+    let (success, stderr, contacted) = run_local_only_cli(
+        &[
+            "analyze",
+            "--repo",
+            &path,
+            "--head",
+            &second,
+            "--issue",
+            "acme/calc#12",
+        ],
+        &[("GITHUB_API_URL", &api_url)],
+        &api,
+    );
+
+    println!("\n🔒 stderr: {}", stderr);
+    assert!(!success);
+    assert!(
+        stderr.contains("the GitHub issue acme/calc#12"),
+        "{}",
+        stderr
+    );
+    assert!(!contacted, "The GitHub API should not be contacted");
+}
+
+#[cfg(feature = "cli")]
+#[test]
+fn test_cli_refuses_notifications_in_local_only_mode() {
+    let (path, _, second) = common::init_local_repo();
+    let channel = std::net::TcpListener::bind("127.0.0.1:0").unwrap();
+    let notify_path = format!("{}.toml", common::unique_path("notify"));
+    std::fs::write(
+        &notify_path,
+        format!(
+            "[[channels]]\nkind = \"webhook\"\nurl = \"http://{}/hook\"\n",
+            channel.local_addr().unwrap()
+        ),
+    )
+    .unwrap();
+
+    let (success, stderr, contacted) = run_local_only_cli(
+        &[
+            "analyze",
+            "--repo",
+            &path,
+            "--head",
+            &second,
+            "--intent",
+            "The sum function should add two numbers",
+            "--notify",
+            &notify_path,
+        ],
+        &[],
+        &channel,
+    );
+
+    println!("\n🔒 stderr: {}", stderr);
+    assert!(!success);
+    assert!(
+        stderr.contains("--local-only doesn't allow sending"),
+        "{}",
+        stderr
+    );
+    assert!(!contacted, "No notification should be sent");
+}
+
+#[cfg(feature = "cli")]
+#[test]
+fn test_cli_refuses_github_mode_in_local_only_mode() {
+    let api = std::net::TcpListener::bind("127.0.0.1:0").unwrap();
+    let api_url = format!("http://{}", api.local_addr().unwrap());
+    let event_path = format!("{}.json", common::unique_path("event"));
+    std::fs::write(
+        &event_path,
+        serde_json::json!({
+            "pull_request": {
+                "number": 7,
+                "title": "Implement sum",
+                "body": null,
+                "base": { "sha": "aaa111" },
+                "head": { "sha": "bbb222", "repo": null },
+            },
+            "repository": { "full_name": "acme/calc", "clone_url": "https://github.com/acme/calc.git" },
+        })
+        .to_string(),
+    )
+    .unwrap();
+
+    let (success, stderr, contacted) = run_local_only_cli(
+        &["analyze", "--github", "--github-token", "t0ken"],
+        &[
+            ("GITHUB_EVENT_PATH", &event_path),
+            ("GITHUB_API_URL", &api_url),
+        ],
+        &api,
+    );
+
+    println!("\n🔒 stderr: {}", stderr);
+    assert!(!success);
+    assert!(
+        stderr.contains("--github clones the pull request"),
+        "{}",
+        stderr
+    );
+    assert!(!contacted, "No comment should be posted");
+}
+
+#[cfg(all(feature = "cli", feature = "server"))]
+#[test]
+fn test_cli_refuses_callbacks_in_local_only_mode() {
+    let callback = std::net::TcpListener::bind("127.0.0.1:0").unwrap();
+    let callback_url = format!("http://{}/results", callback.local_addr().unwrap());
+
+    let (success, stderr, contacted) = run_local_only_cli(
+        &[
+            "serve",
+            "--listen",
+            "127.0.0.1:0",
+            "--callback-url",
+            &callback_url,
+        ],
+        &[],
+        &callback,
+    );
+
+    println!("\n🔒 stderr: {}", stderr);
+    assert!(!success);
+    assert!(stderr.contains("--local-only doesn't allow"), "{}", stderr);
+    assert!(!contacted, "Nothing should be POSTed to the callback");
+}
@@ -0,0 +1,180 @@
+#![cfg(feature = "git")]
+
+use intent_verification::{
+    AnalysisOptions, Finding, IntentVerificationResult, PromptStage, Severity, VerificationProfile,
+    apply_security_profile, cwe_for_rule, tag_cwe, verify_intent_with_options,
+};
+
+fn finding(rule: &str, severity: Severity) -> Finding {
+    Finding {
+        rule: rule.to_string(),
+        severity,
+        file_path: Some("src/db.py".to_string()),
+        line: Some(3),
+        snippet: None,
+        message: "Issue".to_string(),
+        suppressed: false,
+        cwe: None,
+        content_hash: None,
+    }
+}
+
+fn result_with(findings: Vec<Finding>) -> IntentVerificationResult {
+    let mut result: IntentVerificationResult = serde_json::from_str(
+        r#"{"is_intent_fulfilled":true,"confidence":0.9,"explanation":"1 out of 1 changed files support the test intent","files_analyzed":[],"overall_assessment":""}"#,
+    )
+    .unwrap();
+    result.findings = findings;
+    result
+}
+
+#[test]
+fn test_profile_from_str() {
+    assert_eq!(
+        "Security".parse::<VerificationProfile>(),
+        Ok(VerificationProfile::Security)
+    );
+    assert_eq!(
+        "standard".parse::<VerificationProfile>(),
+        Ok(VerificationProfile::Standard)
+    );
+    assert!("paranoid".parse::<VerificationProfile>().is_err());
+    assert_eq!(
+        VerificationProfile::default(),
+        VerificationProfile::Standard
+    );
+}
+
+#[test]
+fn test_cwe_tagging() {
+    assert_eq!(cwe_for_rule("secrets/aws-access-key"), Some("CWE-798"));
+    assert_eq!(cwe_for_rule("ruff/S608"), Some("CWE-89"));
+    assert_eq!(cwe_for_rule("clippy/needless_return"), None);
+
+    let mut findings = vec![
+        finding("ruff/S608", Severity::Low),
+        finding("clippy/needless_return", Severity::Medium),
+    ];
+    tag_cwe(&mut findings);
+    println!("\n🛡️ Tagged findings: {:#?}", findings);
+    assert_eq!(findings[0].cwe.as_deref(), Some("CWE-89"));
+    assert_eq!(findings[1].cwe, None);
+}
+
+#[test]
+fn test_security_profile_weights_the_verdict() {
+    let mut result = result_with(vec![finding("ruff/S608", Severity::Medium)]);
+    assert_eq!(
+        apply_security_profile(&mut result, VerificationProfile::Standard),
+        0
+    );
+    assert!(
+        result.is_intent_fulfilled,
+        "The standard profile should leave the verdict alone"
+    );
+    assert_eq!(result.findings[0].cwe, None);
+
+    assert_eq!(
+        apply_security_profile(&mut result, VerificationProfile::Security),
+        1
+    );
+    assert!(!result.is_intent_fulfilled);
+    assert!(
+        result
+            .explanation
+            .ends_with("; 1 security issue(s) remain in the changes")
+    );
+
+    let mut suppressed = finding("ruff/S608", Severity::High);
+    suppressed.suppressed = true;
+    let mut result = result_with(vec![
+        suppressed,
+        finding("ruff/S324", Severity::Low),
+        finding("clippy/needless_return", Severity::High),
+    ]);
+    assert_eq!(
+        apply_security_profile(&mut result, VerificationProfile::Security),
+        0
+    );
+    assert!(
+        result.is_intent_fulfilled,
+        "Suppressed, low-severity and non-security findings should not fail the verdict"
+    );
+}
+
+#[tokio::test]
+async fn test_security_profile_prompts() {
+    let path = format!(
+        "/tmp/profile_test_repo_{}_{}",
+        std::process::id(),
+        std::time::SystemTime::now()
+            .duration_since(std::time::UNIX_EPOCH)
+            .unwrap()
+            .as_nanos()
+    );
+    let repo = git2::Repository::init(&path).unwrap();
+    let signature = git2::Signature::now("Test", "test@example.com").unwrap();
+    let mut commits = Vec::new();
+    for content in [
+        "def find(db, name):\n    return db.execute(f\"SELECT * FROM users WHERE name = '{name}'\")\n",
+        "def find(db, name):\n    return db.execute(\"SELECT * FROM users WHERE name = ?\", (name,))\n",
+    ] {
+        std::fs::write(format!("{}/db.py", path), content).unwrap();
+        let mut index = repo.index().unwrap();
+        index.add_path(std::path::Path::new("db.py")).unwrap();
+        let tree = repo.find_tree(index.write_tree().unwrap()).unwrap();
+        let parents: Vec<git2::Commit> = repo
+            .head()
+            .ok()
+            .map(|head| head.peel_to_commit().unwrap())
+            .into_iter()
+            .collect();
+        let parents: Vec<&git2::Commit> = parents.iter().collect();
+        let id = repo
+            .commit(Some("HEAD"), &signature, &signature, "c", &tree, &parents)
+            .unwrap();
+        commits.push(id.to_string());
+    }
+
+    let options = AnalysisOptions {
+        dry_run: true,
+        profile: VerificationProfile::Security,
+        ..Default::default()
+    };
+    let result = verify_intent_with_options(
+        &path,
+        &commits[1],
+        &path,
+        &commits[0],
+        &commits[1],
+        "Fix the SQL injection in find",
+        "test-key",
+        None,
+        Some("http://127.0.0.1:9"),
+        &options,
+    )
+    .await
+    .expect("Dry run should succeed without the model");
+
+    let prompt_text = |stage: PromptStage| {
+        result
+            .prompts
+            .iter()
+            .find(|p| p.stage == stage)
+            .map(|p| {
+                p.messages
+                    .iter()
+                    .map(|m| m.content.as_str())
+                    .collect::<Vec<_>>()
+                    .join("\n")
+            })
+            .unwrap_or_default()
+    };
+    assert!(
+        prompt_text(PromptStage::FileAnalysis).contains("security_issues"),
+        "File analysis should ask for the vulnerabilities left in the code"
+    );
+    assert!(prompt_text(PromptStage::OverallAssessment).contains("security review"));
+
+    std::fs::remove_dir_all(&path).ok();
+}
@@ -0,0 +1,63 @@
+#![cfg(feature = "git")]
+
+use intent_verification::{
+    AnalysisOptions, CancellationToken, Cancelled, Progress, ProgressStage,
+    verify_intent_with_options,
+};
+
+#[test]
+fn test_progress_percent() {
+    assert_eq!(
+        Progress::new(ProgressStage::ExtractingTargets, 0, 0).percent,
+        0.0
+    );
+    assert_eq!(
+        Progress::new(ProgressStage::AnalyzingFiles, 0, 0).percent,
+        15.0
+    );
+    assert_eq!(
+        Progress::new(ProgressStage::AnalyzingFiles, 2, 4).percent,
+        55.0
+    );
+    assert_eq!(
+        Progress::new(ProgressStage::AnalyzingFiles, 4, 4).percent,
+        95.0
+    );
+    assert_eq!(Progress::new(ProgressStage::Done, 4, 4).percent, 100.0);
+}
+
+#[tokio::test]
+async fn test_cancelled_verification_stops_early() {
+    let token = CancellationToken::new();
+    assert!(token.check().is_ok());
+    token.cancel();
+    assert!(token.clone().is_cancelled(), "Clones should share state");
+    token.cancelled().await;
+
+    let options = AnalysisOptions {
+        cancellation: Some(token),
+        ..Default::default()
+    };
+    let result = verify_intent_with_options(
+        "/nonexistent/repo",
+        "HEAD",
+        "/nonexistent/repo",
+        "HEAD~1",
+        "HEAD",
+        "Check that sum works",
+        "sk-invalid",
+        None,
+        Some("http://127.0.0.1:9"),
+        &options,
+    )
+    .await;
+
+    let error = result.expect_err("Cancelled verification should fail");
+    assert!(
+        error.downcast_ref::<Cancelled>().is_some(),
+        "Error should be Cancelled, got: {}",
+        error
+    );
+
+    println!("\n✅ Cancelled verification returned: {}", error);
+}
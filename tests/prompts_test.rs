@@ -0,0 +1,144 @@
+#![cfg(feature = "git")]
+
+mod common;
+
+use intent_verification::{
+    AnalysisOptions, PROMPT_VERSION, PromptRegistry, PromptStage, PromptTemplates,
+    compare_prompt_versions, verify_intent_with_options,
+};
+
+/// Version "5-test": the current templates with a reworded per-file request
+fn candidate() -> PromptTemplates {
+    PromptTemplates {
+        version: "5-test".to_string(),
+        file_analysis: "Intent: {intent}\nFile: {path} ({change_type}){block_info}\n```\n{code}\n```\nAnswer with JSON {supports_intent, reasoning}.".to_string(),
+        ..PromptTemplates::current().clone()
+    }
+}
+
+#[test]
+fn test_registry() {
+    let mut registry = PromptRegistry::new();
+    assert_eq!(PromptTemplates::current().version, PROMPT_VERSION);
+    assert_eq!(
+        registry.versions().collect::<Vec<_>>(),
+        vec![PROMPT_VERSION]
+    );
+
+    registry.register(candidate());
+    assert_eq!(
+        registry.versions().collect::<Vec<_>>(),
+        vec![PROMPT_VERSION, "5-test"]
+    );
+    assert_eq!(registry.get("5-test"), Some(&candidate()));
+    assert_eq!(registry.get("missing"), None);
+}
+
+#[test]
+fn test_load_templates_from_toml() {
+    let path = format!("/tmp/prompts_test_{}.toml", std::process::id());
+    std::fs::write(&path, toml::to_string(&candidate()).unwrap()).unwrap();
+    let loaded = PromptTemplates::load(&path).expect("Should load TOML templates");
+    assert_eq!(loaded, candidate());
+    std::fs::remove_file(&path).ok();
+
+    assert!(
+        PromptTemplates::load("/tmp/does-not-exist.json").is_err(),
+        "Missing files should be reported"
+    );
+}
+
+#[tokio::test]
+async fn test_custom_templates_are_rendered_and_recorded() {
+    let (path, first, second) = common::init_local_repo();
+    let options = AnalysisOptions {
+        dry_run: true,
+        prompts: Some(candidate()),
+        ..Default::default()
+    };
+
+    let result = verify_intent_with_options(
+        &path,
+        &second,
+        &path,
+        &first,
+        &second,
+        "The sum function should add {numbers}",
+        "test-key",
+        None,
+        Some("http://127.0.0.1:9"),
+        &options,
+    )
+    .await
+    .expect("Dry run should succeed without the model");
+
+    assert_eq!(result.metadata.prompt_version, "5-test");
+    let file_prompt = result
+        .prompts
+        .iter()
+        .find(|p| p.stage == PromptStage::FileAnalysis)
+        .unwrap();
+    let request = &file_prompt.messages.last().unwrap().content;
+    println!("\n📝 Rendered request:\n{}", request);
+    assert!(request.starts_with(
+        "Intent: The sum function should add {numbers}\nFile: src/lib.rs (Modified) (changed hunks only"
+    ));
+    assert!(request.contains("+    a + b"));
+    assert!(
+        request.ends_with("Answer with JSON {supports_intent, reasoning}."),
+        "Braces that aren't placeholders should be kept"
+    );
+
+    let extraction = &result.prompts[0];
+    assert_eq!(extraction.stage, PromptStage::TargetExtraction);
+    assert!(
+        extraction.messages[0]
+            .content
+            .contains("{\n  \"functions\": [\"...\"],"),
+        "The current extraction template should render as before"
+    );
+
+    std::fs::remove_dir_all(&path).ok();
+}
+
+#[tokio::test]
+async fn test_compare_prompt_versions() {
+    let (path, first, second) = common::init_local_repo();
+    let options = AnalysisOptions {
+        dry_run: true,
+        ..Default::default()
+    };
+
+    let comparison = compare_prompt_versions(
+        PromptTemplates::current(),
+        &candidate(),
+        &path,
+        &second,
+        &path,
+        &first,
+        &second,
+        "The sum function should add two numbers",
+        "test-key",
+        None,
+        Some("http://127.0.0.1:9"),
+        &options,
+    )
+    .await
+    .expect("Both dry runs should succeed");
+
+    println!("\n🧪 Diff: {:#?}", comparison.diff);
+    assert_eq!(comparison.version_a, PROMPT_VERSION);
+    assert_eq!(comparison.version_b, "5-test");
+    assert!(comparison.verdicts_agree());
+    assert!(!comparison.diff.has_regressions());
+    let file_prompt = |result: &intent_verification::IntentVerificationResult| {
+        result.prompts[1].messages.last().unwrap().content.clone()
+    };
+    assert_ne!(
+        file_prompt(&comparison.result_a),
+        file_prompt(&comparison.result_b),
+        "Each run should use its own templates"
+    );
+
+    std::fs::remove_dir_all(&path).ok();
+}
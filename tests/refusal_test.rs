@@ -0,0 +1,158 @@
+use intent_verification::{
+    AnalysisOptions, AnalysisOutcome, LlmClient, MockProvider, RepoSnapshot, WarningKind,
+    verify_intent_with_snapshots,
+};
+
+fn mock() -> MockProvider {
+    MockProvider::new()
+        .respond_when(
+            "Extract from the following prompt",
+            r#"{"functions": ["sum"], "files": []}"#,
+        )
+        // Only the string literal trips the filter, so the sanitized retry goes through
+        .refuse_when("rm -rf /", "I can't help with that.")
+        .filter_when("shellcode_payload")
+        .respond_when(
+            "sorry_trigger",
+            "I'm sorry, but I can't assist with that request.",
+        )
+        .fail_when(
+            "azure_trigger",
+            "The prompt triggered Azure OpenAI's content management policy",
+        )
+        .respond_when(
+            "STEP 2",
+            r#"{"supports_intent": true, "reasoning": "sum adds", "relevant_changes": []}"#,
+        )
+        .respond("Assessment")
+}
+
+async fn verify(
+    files: &[(&str, &str)],
+    mock: &MockProvider,
+) -> intent_verification::IntentVerificationResult {
+    let base = RepoSnapshot::from_files(Vec::<(&str, &str)>::new());
+    let head = RepoSnapshot::from_files(files.iter().copied());
+    verify_intent_with_snapshots(
+        &head,
+        &base,
+        &head,
+        "The sum function adds two numbers",
+        "",
+        None,
+        None,
+        &AnalysisOptions {
+            llm_client: Some(LlmClient::mock(mock.clone())),
+            ..Default::default()
+        },
+    )
+    .await
+    .unwrap()
+}
+
+#[tokio::test(flavor = "current_thread")]
+async fn test_refusal_retried_with_sanitized_prompt() {
+    let mock = mock();
+    let result = verify(
+        &[(
+            "src/lib.rs",
+            "// Never run \"rm -rf /\" here\npub fn sum(a: i32, b: i32) -> i32 {\n    let _note = \"rm -rf /\";\n    a + b\n}\n",
+        )],
+        &mock,
+    )
+    .await;
+
+    let analysis = &result.files_analyzed[0];
+    assert_eq!(analysis.outcome, AnalysisOutcome::Analyzed);
+    assert!(analysis.supports_intent);
+    assert!(result.warnings.is_empty(), "{:?}", result.warnings);
+
+    let analyses: Vec<String> = mock
+        .calls()
+        .into_iter()
+        .map(|call| call.prompt())
+        .filter(|prompt| prompt.contains("STEP 2"))
+        .collect();
+    assert_eq!(analyses.len(), 2, "The refused block is sent once more");
+    assert!(analyses[1].contains("\"[string]\""));
+    assert!(analyses[1].contains("// [comment]"));
+    assert!(analyses[1].contains("a + b"), "The code itself is kept");
+    println!("\n✅ {}", analysis.reasoning);
+}
+
+#[tokio::test(flavor = "current_thread")]
+async fn test_refused_files_are_left_out_of_the_verdict() {
+    let mock = mock();
+    let result = verify(
+        &[
+            (
+                "src/lib.rs",
+                "pub fn sum(a: i32, b: i32) -> i32 {\n    a + b\n}\n",
+            ),
+            ("src/filtered.rs", "pub fn shellcode_payload() {}\n"),
+            ("src/sorry.rs", "pub fn sorry_trigger() {}\n"),
+            ("src/azure.rs", "pub fn azure_trigger() {}\n"),
+        ],
+        &mock,
+    )
+    .await;
+
+    for path in ["src/filtered.rs", "src/sorry.rs", "src/azure.rs"] {
+        let analysis = result
+            .files_analyzed
+            .iter()
+            .find(|fa| fa.file_path == path)
+            .unwrap();
+        assert_eq!(analysis.outcome, AnalysisOutcome::Refused, "{}", path);
+        assert!(!analysis.supports_intent);
+        assert!(
+            analysis
+                .reasoning
+                .starts_with("The provider refused to analyze this file"),
+            "{}",
+            analysis.reasoning
+        );
+        assert!(
+            result
+                .warnings
+                .iter()
+                .any(|w| w.kind == WarningKind::Refusal && w.file_path.as_deref() == Some(path)),
+            "{}",
+            path
+        );
+    }
+    let lib = result
+        .files_analyzed
+        .iter()
+        .find(|fa| fa.file_path == "src/lib.rs")
+        .unwrap();
+    assert_eq!(lib.outcome, AnalysisOutcome::Analyzed);
+
+    assert!(
+        result.is_intent_fulfilled,
+        "Refusals don't count against the intent"
+    );
+    assert!(result.is_partial);
+    assert!(
+        result
+            .explanation
+            .starts_with("1 out of 1 changed files support the test intent (3 refused"),
+        "{}",
+        result.explanation
+    );
+
+    let json = serde_json::to_value(&result).unwrap();
+    let outcomes: Vec<&serde_json::Value> = json["files_analyzed"]
+        .as_array()
+        .unwrap()
+        .iter()
+        .map(|fa| &fa["outcome"])
+        .collect();
+    assert_eq!(
+        outcomes.iter().filter(|o| **o == "refused").count(),
+        3,
+        "Analyzed files don't serialize the outcome"
+    );
+    assert_eq!(outcomes.iter().filter(|o| o.is_null()).count(), 1);
+    println!("\n✅ {}", result.explanation);
+}
@@ -0,0 +1,159 @@
+#![cfg(feature = "git")]
+
+mod common;
+
+use intent_verification::{
+    AnalysisOptions, CriterionResult, Finding, IntentVerificationResult, PromptStage, Regression,
+    Severity, render_markdown, verify_intent_with_options,
+};
+
+fn result(fulfilled: bool, criteria: &[(&str, bool)]) -> IntentVerificationResult {
+    let mut result: IntentVerificationResult = serde_json::from_str(
+        r#"{"is_intent_fulfilled":true,"confidence":0.9,"explanation":"1 out of 1 changed files support the test intent","files_analyzed":[],"overall_assessment":""}"#,
+    )
+    .unwrap();
+    result.is_intent_fulfilled = fulfilled;
+    result.metadata.finished_at = "2026-10-01T12:00:00Z".to_string();
+    result.acceptance_criteria = criteria
+        .iter()
+        .map(|(criterion, passed)| CriterionResult {
+            criterion: criterion.to_string(),
+            passed: *passed,
+            evidence: format!("checked {}", criterion),
+            files: vec!["src/lib.rs".to_string()],
+        })
+        .collect();
+    result
+}
+
+#[test]
+fn test_criteria_regressions() {
+    let previous = result(
+        true,
+        &[
+            ("Adds two numbers", true),
+            ("Handles overflow", false),
+            ("Documents the function", true),
+            ("Rejects NaN", true),
+        ],
+    );
+    let mut current = result(
+        false,
+        &[
+            ("Adds two numbers", false),
+            ("Handles overflow", true),
+            ("Rejects NaN", true),
+        ],
+    );
+    current.findings.push(Finding {
+        rule: "secrets/github-token".to_string(),
+        severity: Severity::High,
+        file_path: Some("src/lib.rs".to_string()),
+        line: Some(3),
+        snippet: Some("ghp_…[redacted]".to_string()),
+        message: "Possible GitHub token added in the changes".to_string(),
+        suppressed: false,
+        cwe: None,
+        content_hash: None,
+    });
+
+    let regression = Regression::between(&previous, &current);
+    println!("\n📉 {:#?}", regression.summary());
+    assert!(regression.has_regressions());
+    assert_eq!(regression.previous_run_at, "2026-10-01T12:00:00Z");
+    assert_eq!(regression.broken_criteria.len(), 1);
+    assert_eq!(regression.broken_criteria[0].criterion, "Adds two numbers");
+    assert_eq!(regression.restored_criteria, vec!["Handles overflow"]);
+    assert_eq!(
+        regression.unchecked_criteria,
+        vec!["Documents the function"]
+    );
+    assert_eq!(
+        regression.summary(),
+        vec![
+            "The intent was fulfilled before and isn't anymore".to_string(),
+            "Criterion no longer met: Adds two numbers (checked Adds two numbers)".to_string(),
+            "New finding `secrets/github-token`: Possible GitHub token added in the changes"
+                .to_string(),
+        ]
+    );
+
+    current.regression = Some(regression);
+    let markdown = render_markdown(&current);
+    assert!(markdown.contains("### Regressions since the previous run"));
+    assert!(markdown.contains("- Criterion no longer met: Adds two numbers"));
+
+    let unchanged = Regression::between(&previous, &previous);
+    assert!(!unchanged.has_regressions());
+    assert!(unchanged.summary().is_empty());
+}
+
+#[tokio::test]
+async fn test_previous_criteria_are_checked_again() {
+    let (path, first, second) = common::init_local_repo();
+    let previous = result(true, &[("sum returns a + b", true)]);
+    let options = AnalysisOptions {
+        dry_run: true,
+        previous_result: Some(Box::new(previous.clone())),
+        ..Default::default()
+    };
+
+    let dry_run = verify_intent_with_options(
+        &path,
+        &second,
+        &path,
+        &first,
+        &second,
+        "The sum function should add two numbers",
+        "test-key",
+        None,
+        Some("http://127.0.0.1:9"),
+        &options,
+    )
+    .await
+    .expect("Dry run should succeed without the model");
+    let criteria_prompts: Vec<&str> = dry_run
+        .prompts
+        .iter()
+        .filter(|p| p.stage == PromptStage::AcceptanceCriteria)
+        .map(|p| p.messages[0].content.as_str())
+        .collect();
+    assert_eq!(criteria_prompts.len(), 1);
+    assert!(
+        criteria_prompts[0].contains("Acceptance criterion: \"sum returns a + b\""),
+        "The earlier criterion should be checked instead of decomposing the intent again"
+    );
+    assert!(
+        dry_run.regression.is_none(),
+        "Dry runs have no verdict to compare"
+    );
+
+    let options = AnalysisOptions {
+        dry_run: false,
+        ..options
+    };
+    let result = verify_intent_with_options(
+        &path,
+        &second,
+        &path,
+        &first,
+        &second,
+        "The sum function should add two numbers",
+        "test-key",
+        None,
+        Some("http://127.0.0.1:9"),
+        &options,
+    )
+    .await
+    .expect("Verification should finish with warnings");
+    let regression = result.regression.expect("The result should be compared");
+    println!("\n📉 {:#?}", regression);
+    assert!(regression.has_regressions(), "The verdict was lost");
+    assert_eq!(
+        regression.unchecked_criteria,
+        vec!["sum returns a + b"],
+        "The model was unreachable, so the criterion couldn't be checked"
+    );
+
+    std::fs::remove_dir_all(&path).ok();
+}
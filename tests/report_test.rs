@@ -0,0 +1,281 @@
+use intent_verification::{
+    ChangeLocation, ChangeType, FileChange, FileIntentAnalysis, Finding, IntentVerificationResult,
+    Severity, Warning, WarningKind, render_github_annotations, render_html, render_junit,
+    render_markdown, render_sarif,
+};
+
+fn sample_result() -> IntentVerificationResult {
+    IntentVerificationResult {
+        is_intent_fulfilled: true,
+        confidence: 0.65,
+        explanation: "1 out of 2 changed files support the test intent".to_string(),
+        files_analyzed: vec![
+            FileIntentAnalysis {
+                file_path: "src/sum.rs".to_string(),
+                change_type: ChangeType::Modified,
+                supports_intent: true,
+                reasoning: "Implements the sum function used by the tests".to_string(),
+                relevant_changes: vec!["Added `pub fn sum(a: i32, b: i32) -> i32`".to_string()],
+                locations: vec![ChangeLocation {
+                    file_path: "src/sum.rs".to_string(),
+                    start_line: 1,
+                    end_line: 3,
+                    snippet: "pub fn sum(a: i32, b: i32) -> i32 {".to_string(),
+                }],
+                risk_score: 0.0,
+                relevance: None,
+                owners: vec![],
+                citations: vec![],
+                confidence: None,
+                support_probability: None,
+                outcome: Default::default(),
+            },
+            FileIntentAnalysis {
+                file_path: "docs/a|b.md".to_string(),
+                change_type: ChangeType::Added,
+                supports_intent: false,
+                reasoning: "Documentation only".to_string(),
+                relevant_changes: vec![],
+                locations: vec![],
+                risk_score: 0.0,
+                relevance: None,
+                owners: vec![],
+                citations: vec![],
+                confidence: None,
+                support_probability: None,
+                outcome: Default::default(),
+            },
+        ],
+        overall_assessment: "The changes implement the required function.".to_string(),
+        ..Default::default()
+    }
+}
+
+#[test]
+fn test_render_markdown_report() {
+    let markdown = render_markdown(&sample_result());
+
+    println!("\n📝 Markdown report:\n{}", markdown);
+
+    assert!(
+        markdown.starts_with("## ✅ Intent fulfilled"),
+        "Should start with the verdict banner"
+    );
+    assert!(markdown.contains("**Confidence:** 65%"));
+    assert!(
+        markdown.contains("| `src/sum.rs` | Modified | ✅ Supports |"),
+        "Should contain a table row per file"
+    );
+    assert!(
+        markdown.contains("`docs/a\\|b.md`"),
+        "Pipes in paths should be escaped inside the table"
+    );
+    assert_eq!(
+        markdown.matches("<details>").count(),
+        2,
+        "Should render one collapsible block per file"
+    );
+    assert!(markdown.contains("- Added `pub fn sum(a: i32, b: i32) -> i32`"));
+    assert!(markdown.contains("- `src/sum.rs:1-3`"));
+    assert!(
+        !markdown.contains("Partial result"),
+        "Complete results should not show a warning block"
+    );
+}
+
+#[test]
+fn test_render_markdown_report_with_warnings() {
+    let mut result = sample_result();
+    result.is_partial = true;
+    result.warnings.push(Warning {
+        kind: WarningKind::FileAnalysis,
+        file_path: Some("src/big.rs".to_string()),
+        message: "rate limited".to_string(),
+    });
+
+    let markdown = render_markdown(&result);
+
+    assert!(markdown.contains("⚠️ **Partial result:**"));
+    assert!(markdown.contains("> - FileAnalysis (`src/big.rs`): rate limited"));
+}
+
+#[test]
+fn test_render_markdown_report_escapes_html() {
+    let mut result = sample_result();
+    result.files_analyzed[0].file_path = "src/<img src=x>.rs".to_string();
+    result.files_analyzed[0].reasoning =
+        "Done.\n</details><script>alert(1)</script> Keeps `Vec<u8>` as is".to_string();
+
+    let markdown = render_markdown(&result);
+
+    assert!(markdown.contains("<summary><code>src/&lt;img src=x&gt;.rs</code>"));
+    assert!(markdown.contains("&lt;/details&gt;&lt;script&gt;alert(1)&lt;/script&gt;"));
+    assert!(
+        markdown.contains("`Vec<u8>`"),
+        "Code spans are shown verbatim and must not be escaped"
+    );
+    assert_eq!(markdown.matches("</details>").count(), 2);
+    assert!(!markdown.contains("<script>"));
+}
+
+#[test]
+fn test_render_markdown_report_not_fulfilled() {
+    let mut result = sample_result();
+    result.is_intent_fulfilled = false;
+    result.files_analyzed.clear();
+
+    let markdown = render_markdown(&result);
+
+    assert!(markdown.starts_with("## ❌ Intent not fulfilled"));
+    assert!(markdown.contains("_No changed files were analyzed._"));
+    assert!(!markdown.contains("<details>"));
+}
+
+#[test]
+fn test_render_html_report_with_side_by_side_diff() {
+    let file_changes = vec![FileChange {
+        path: "src/sum.rs".to_string(),
+        status: ChangeType::Modified,
+        content: Some("pub fn sum(a: i32, b: i32) -> i32 {\n    a + b\n}\n".to_string()),
+        old_content: Some("pub fn sum(a: i32, b: i32) -> i32 {\n    todo!()\n}\n".to_string()),
+    }];
+    let mut result = sample_result();
+    result.files_analyzed[0].relevant_changes = vec!["Replaced todo!() with `a + b`".to_string()];
+    result.files_analyzed[1].reasoning = "<script>alert(1)</script>".to_string();
+
+    let html = render_html(&result, &file_changes);
+
+    assert!(html.starts_with("<!DOCTYPE html>"));
+    assert!(html.contains("✅ Intent fulfilled"));
+    assert!(
+        html.contains("<td class=\"del\">    todo!()</td>"),
+        "Removed line should be rendered on the left side"
+    );
+    assert!(
+        html.contains("<td class=\"ins relevant\">    a + b</td>"),
+        "Added line inside a change location should be highlighted on the right side"
+    );
+    assert!(
+        html.contains("No diff available for this file."),
+        "Files without a matching change should still be listed"
+    );
+    assert!(
+        html.contains("&lt;script&gt;") && !html.contains("<script>"),
+        "Model output should be HTML-escaped"
+    );
+}
+
+#[test]
+fn test_render_junit_report() {
+    let mut result = sample_result();
+    result.files_analyzed[1].reasoning = "Docs \"only\" & nothing <else>".to_string();
+
+    let xml = render_junit(&result);
+
+    println!("\n🧪 JUnit report:\n{}", xml);
+
+    assert!(xml.starts_with("<?xml version=\"1.0\" encoding=\"UTF-8\"?>"));
+    assert!(
+        xml.contains("<testsuite name=\"intent-verification\" tests=\"3\" failures=\"1\">"),
+        "Should count the overall verdict plus one case per file"
+    );
+    assert_eq!(
+        xml.matches("<failure").count(),
+        1,
+        "Only the unsupported file should fail"
+    );
+    assert!(xml.contains("Docs &quot;only&quot; &amp; nothing &lt;else&gt;"));
+
+    result.is_intent_fulfilled = false;
+    let xml = render_junit(&result);
+    assert!(xml.contains("failures=\"2\""));
+}
+
+#[test]
+fn test_render_github_annotations() {
+    let mut result = sample_result();
+    result.findings.push(Finding {
+        rule: "secrets/aws-access-key".to_string(),
+        severity: Severity::High,
+        file_path: Some("src/config.rs".to_string()),
+        line: Some(12),
+        snippet: None,
+        message: "Hardcoded AWS access key".to_string(),
+        suppressed: false,
+        cwe: None,
+        content_hash: None,
+    });
+
+    let annotations = render_github_annotations(&result);
+
+    println!("\n🐙 GitHub annotations: {:#?}", annotations);
+
+    assert_eq!(annotations.len(), 3);
+    assert_eq!(annotations[0].path, "src/config.rs");
+    assert_eq!(annotations[0].start_line, 12);
+    assert_eq!(annotations[0].annotation_level, "failure");
+    assert_eq!(
+        (annotations[1].start_line, annotations[1].end_line),
+        (1, 3),
+        "Supporting file should be annotated at its change location"
+    );
+    assert_eq!(annotations[1].annotation_level, "notice");
+    assert_eq!(annotations[2].path, "docs/a|b.md");
+    assert_eq!(annotations[2].annotation_level, "warning");
+
+    let json = serde_json::to_value(&annotations[0]).unwrap();
+    assert_eq!(json["annotation_level"], "failure");
+}
+
+#[test]
+fn test_render_sarif_report() {
+    let mut result = sample_result();
+    result.is_intent_fulfilled = false;
+    result.findings.push(Finding {
+        rule: "secrets/aws-access-key".to_string(),
+        severity: Severity::Critical,
+        file_path: Some("src/config.rs".to_string()),
+        line: Some(12),
+        snippet: None,
+        message: "Hardcoded AWS access key".to_string(),
+        suppressed: true,
+        cwe: None,
+        content_hash: None,
+    });
+
+    let sarif = render_sarif(&result);
+
+    println!(
+        "\n🔎 SARIF report:\n{}",
+        serde_json::to_string_pretty(&sarif).unwrap()
+    );
+
+    assert_eq!(sarif["version"], "2.1.0");
+    assert_eq!(
+        sarif["runs"][0]["tool"]["driver"]["name"],
+        "intent-verification"
+    );
+
+    let results = sarif["runs"][0]["results"].as_array().unwrap();
+    assert_eq!(
+        results.len(),
+        3,
+        "Should report the verdict, the finding and the unsupported file"
+    );
+    assert_eq!(results[0]["ruleId"], "intent/not-fulfilled");
+    assert_eq!(results[0]["level"], "error");
+    assert_eq!(results[1]["ruleId"], "secrets/aws-access-key");
+    assert_eq!(
+        results[1]["locations"][0]["physicalLocation"]["region"]["startLine"],
+        12
+    );
+    assert_eq!(
+        results[1]["suppressions"][0]["kind"], "external",
+        "Baselined findings should be marked as suppressed"
+    );
+    assert_eq!(results[2]["ruleId"], "intent/unsupported-change");
+    assert_eq!(
+        results[2]["locations"][0]["physicalLocation"]["artifactLocation"]["uri"],
+        "docs/a|b.md"
+    );
+}
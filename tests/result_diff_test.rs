@@ -0,0 +1,98 @@
+use intent_verification::{
+    ChangeType, FileIntentAnalysis, Finding, IntentVerificationResult, Severity,
+};
+
+fn file(path: &str, supports_intent: bool) -> FileIntentAnalysis {
+    FileIntentAnalysis {
+        file_path: path.to_string(),
+        change_type: ChangeType::Modified,
+        supports_intent,
+        reasoning: String::new(),
+        relevant_changes: vec![],
+        locations: vec![],
+        risk_score: 0.0,
+        relevance: None,
+        owners: vec![],
+        citations: vec![],
+        confidence: None,
+        support_probability: None,
+        outcome: Default::default(),
+    }
+}
+
+fn finding(rule: &str, line: usize) -> Finding {
+    Finding {
+        rule: rule.to_string(),
+        severity: Severity::High,
+        file_path: Some("src/lib.rs".to_string()),
+        line: Some(line),
+        snippet: Some(format!("snippet for {}", rule)),
+        message: String::new(),
+        suppressed: false,
+        cwe: None,
+        content_hash: None,
+    }
+}
+
+fn result(
+    is_intent_fulfilled: bool,
+    confidence: f32,
+    files_analyzed: Vec<FileIntentAnalysis>,
+    findings: Vec<Finding>,
+) -> IntentVerificationResult {
+    IntentVerificationResult {
+        is_intent_fulfilled,
+        confidence,
+        files_analyzed,
+        findings,
+        ..Default::default()
+    }
+}
+
+#[test]
+fn test_diff_after_fix() {
+    let before = result(
+        false,
+        0.4,
+        vec![file("src/lib.rs", false), file("src/old.rs", true)],
+        vec![finding("secrets/token", 10)],
+    );
+    let after = result(
+        true,
+        0.9,
+        vec![file("src/lib.rs", true), file("tests/lib_test.rs", true)],
+        vec![finding("secrets/token", 12), finding("sql/drop-table", 3)],
+    );
+
+    let diff = before.diff(&after);
+
+    println!("\n🔀 Result diff: {:#?}", diff);
+
+    assert!(!diff.was_fulfilled && diff.is_fulfilled);
+    assert!((diff.confidence_delta - 0.5).abs() < 1e-6);
+    assert_eq!(diff.newly_supporting, vec!["src/lib.rs".to_string()]);
+    assert!(diff.no_longer_supporting.is_empty());
+    assert_eq!(diff.added_files, vec!["tests/lib_test.rs".to_string()]);
+    assert_eq!(diff.removed_files, vec!["src/old.rs".to_string()]);
+    assert_eq!(
+        diff.new_findings.len(),
+        1,
+        "A finding that only moved lines is not new"
+    );
+    assert_eq!(diff.new_findings[0].rule, "sql/drop-table");
+    assert!(diff.resolved_findings.is_empty());
+    assert!(diff.has_regressions(), "A new finding is a regression");
+}
+
+#[test]
+fn test_diff_detects_regressions() {
+    let before = result(true, 0.9, vec![file("src/lib.rs", true)], vec![]);
+    let after = result(false, 0.6, vec![file("src/lib.rs", false)], vec![]);
+
+    let diff = before.diff(&after);
+
+    assert_eq!(diff.no_longer_supporting, vec!["src/lib.rs".to_string()]);
+    assert!(diff.confidence_delta < 0.0);
+    assert!(diff.has_regressions());
+    assert!(!before.diff(&before).has_regressions());
+}
@@ -0,0 +1,38 @@
+use intent_verification::{
+    IntentVerificationResult, PROMPT_VERSION, ResultMetadata, SCHEMA_VERSION,
+};
+
+#[test]
+fn test_result_metadata_start_and_finish() {
+    let metadata = ResultMetadata::start("gpt-4o-mini");
+    std::thread::sleep(std::time::Duration::from_millis(5));
+    let metadata = metadata.finish();
+
+    println!("\n🏷️  Metadata: {:?}", metadata);
+
+    assert_eq!(metadata.schema_version, SCHEMA_VERSION);
+    assert_eq!(metadata.prompt_version, PROMPT_VERSION);
+    assert_eq!(metadata.tool_version, env!("CARGO_PKG_VERSION"));
+    assert_eq!(metadata.model, "gpt-4o-mini");
+    assert!(!metadata.started_at.is_empty());
+    assert!(!metadata.finished_at.is_empty());
+    assert!(metadata.duration_ms >= 5, "Duration should cover the run");
+}
+
+#[test]
+fn test_result_without_metadata_still_deserializes() {
+    // Results stored before the metadata block was introduced
+    let json = r#"{
+        "is_intent_fulfilled": true,
+        "confidence": 0.9,
+        "explanation": "1 out of 1 changed files support the test intent",
+        "files_analyzed": [],
+        "overall_assessment": "Looks good"
+    }"#;
+
+    let result: IntentVerificationResult =
+        serde_json::from_str(json).expect("Legacy result should deserialize");
+
+    assert!(result.metadata.schema_version.is_empty());
+    assert_eq!(result.metadata.duration_ms, 0);
+}
@@ -0,0 +1,99 @@
+use intent_verification::{
+    ChangeType, FileChange, FileIntentAnalysis, Finding, IntentVerificationResult, Severity,
+    apply_risk_scores, file_criticality,
+};
+
+fn analysis(path: &str, supports_intent: bool) -> FileIntentAnalysis {
+    FileIntentAnalysis {
+        file_path: path.to_string(),
+        change_type: ChangeType::Modified,
+        supports_intent,
+        reasoning: String::new(),
+        relevant_changes: vec![],
+        locations: vec![],
+        risk_score: 0.0,
+        relevance: None,
+        owners: vec![],
+        citations: vec![],
+        confidence: None,
+        support_probability: None,
+        outcome: Default::default(),
+    }
+}
+
+fn change(path: &str, old_lines: usize, new_lines: usize) -> FileChange {
+    FileChange {
+        path: path.to_string(),
+        status: ChangeType::Modified,
+        content: Some(
+            (0..new_lines)
+                .map(|i| format!("new line {}\n", i))
+                .collect(),
+        ),
+        old_content: Some(
+            (0..old_lines)
+                .map(|i| format!("old line {}\n", i))
+                .collect(),
+        ),
+    }
+}
+
+#[test]
+fn test_file_criticality_heuristics() {
+    assert_eq!(file_criticality("src/auth/session.rs"), 1.0);
+    assert_eq!(file_criticality("migrations/001_init.sql"), 1.0);
+    assert_eq!(file_criticality(".github/workflows/ci.yml"), 1.0);
+    assert_eq!(file_criticality("Cargo.toml"), 0.7);
+    assert_eq!(file_criticality("src/math.rs"), 0.5);
+    assert_eq!(file_criticality("tests/sum_tests.rs"), 0.2);
+    assert_eq!(file_criticality("README.md"), 0.2);
+}
+
+#[test]
+fn test_apply_risk_scores() {
+    let mut result = IntentVerificationResult {
+        is_intent_fulfilled: true,
+        confidence: 0.8,
+        files_analyzed: vec![
+            analysis("README.md", true),
+            analysis("src/auth/login.rs", false),
+        ],
+        findings: vec![Finding {
+            rule: "secrets/token".to_string(),
+            severity: Severity::Critical,
+            file_path: Some("src/auth/login.rs".to_string()),
+            line: Some(3),
+            snippet: None,
+            message: String::new(),
+            suppressed: false,
+            cwe: None,
+            content_hash: None,
+        }],
+        ..Default::default()
+    };
+    let file_changes = vec![
+        change("README.md", 2, 3),
+        change("src/auth/login.rs", 100, 400),
+    ];
+
+    apply_risk_scores(&mut result, &file_changes);
+
+    let readme = result.files_analyzed[0].risk_score;
+    let login = result.files_analyzed[1].risk_score;
+
+    println!(
+        "\n🎯 Risk scores: README.md={:.2}, login.rs={:.2}, overall={:.2}",
+        readme, login, result.risk_score
+    );
+
+    assert!(readme < 0.2, "Small docs change should be low risk");
+    assert!(
+        login > 0.8,
+        "Large critical change with a critical finding should be high risk"
+    );
+    assert!((0.0..=1.0).contains(&result.risk_score));
+    assert!(
+        result.risk_score > readme && result.risk_score <= login,
+        "Overall risk should be dominated by the riskiest file"
+    );
+}
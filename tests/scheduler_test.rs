@@ -0,0 +1,153 @@
+#![cfg(feature = "git")]
+
+mod common;
+
+use std::sync::{Arc, Mutex};
+use std::time::{Duration, Instant};
+
+use intent_verification::{
+    AnalysisOptions, ProgressHandler, RateLimiter, ScheduledJobStatus, SchedulerConfig,
+    VerificationJob, VerificationScheduler,
+};
+
+fn dry_run_options(cache_dir: &str) -> AnalysisOptions {
+    AnalysisOptions {
+        dry_run: true,
+        cache_dir: Some(cache_dir.to_string()),
+        ..Default::default()
+    }
+}
+
+#[tokio::test(flavor = "current_thread")]
+async fn test_scheduler_runs_jobs_by_priority() {
+    let (path, first, second) = common::init_local_repo();
+    let cache_dir = format!("{}_cache", path);
+    let scheduler = VerificationScheduler::new(SchedulerConfig {
+        options: dry_run_options(&cache_dir),
+        max_concurrent_jobs: 1,
+        ..Default::default()
+    });
+
+    // Record the order jobs start in through their progress callbacks
+    let started = Arc::new(Mutex::new(Vec::<String>::new()));
+    let job = |name: &str, priority: i32| {
+        let started = Arc::clone(&started);
+        let name = name.to_string();
+        let mut job = VerificationJob::new(&path, &first, &second, "Implement sum() in src/lib.rs")
+            .with_priority(priority);
+        let mut options = dry_run_options(&cache_dir);
+        options.progress = Some(ProgressHandler::new(move |_| {
+            let mut started = started.lock().unwrap();
+            if !started.contains(&name) {
+                started.push(name.clone());
+            }
+        }));
+        job.options = Some(options);
+        job
+    };
+
+    let low = scheduler.submit(job("low", 0));
+    let high = scheduler.submit(job("high", 10));
+    let medium = scheduler.submit(job("medium", 5));
+    assert_eq!(scheduler.status(low), Some(ScheduledJobStatus::Queued));
+
+    scheduler.run_until_idle().await;
+
+    let started = started.lock().unwrap().clone();
+    println!("\n📋 Start order: {:?}", started);
+    assert_eq!(
+        started,
+        vec!["high", "medium", "low"],
+        "Higher priorities should start first"
+    );
+    for id in [low, high, medium] {
+        let job = scheduler
+            .job(id)
+            .expect("Submitted jobs should be queryable");
+        assert_eq!(job.status, ScheduledJobStatus::Succeeded, "{:?}", job.error);
+        let result = job.result.expect("Finished jobs should carry their result");
+        assert!(
+            !result.prompts.is_empty(),
+            "Dry runs should return the prompts"
+        );
+    }
+    assert_eq!(scheduler.jobs().len(), 3);
+    assert!(
+        scheduler.job(99).is_none(),
+        "Unknown ids should have no job"
+    );
+
+    let _ = std::fs::remove_dir_all(&path);
+    let _ = std::fs::remove_dir_all(&cache_dir);
+}
+
+#[tokio::test(flavor = "current_thread")]
+async fn test_scheduler_cancels_queued_jobs_and_reports_failures() {
+    let (path, first, second) = common::init_local_repo();
+    let cache_dir = format!("{}_cache", path);
+    let scheduler = VerificationScheduler::new(SchedulerConfig {
+        options: dry_run_options(&cache_dir),
+        ..Default::default()
+    });
+
+    let cancelled = scheduler.submit(VerificationJob::new(
+        &path,
+        &first,
+        &second,
+        "Implement sum()",
+    ));
+    let missing = scheduler.submit(VerificationJob::new(
+        &format!("{}_missing", path),
+        &first,
+        &second,
+        "Implement sum()",
+    ));
+    assert!(
+        scheduler.cancel(cancelled),
+        "Queued jobs should be cancellable"
+    );
+    assert!(
+        !scheduler.cancel(cancelled),
+        "Cancelling twice should report nothing to cancel"
+    );
+
+    scheduler.run_until_idle().await;
+
+    assert_eq!(
+        scheduler.status(cancelled),
+        Some(ScheduledJobStatus::Cancelled)
+    );
+    let failed = scheduler.job(missing).unwrap();
+    println!("\n❌ Failed job error: {:?}", failed.error);
+    assert_eq!(failed.status, ScheduledJobStatus::Failed);
+    assert!(
+        failed.error.is_some(),
+        "Failed jobs should carry their error"
+    );
+    assert!(
+        !scheduler.cancel(missing),
+        "Finished jobs can't be cancelled"
+    );
+
+    let _ = std::fs::remove_dir_all(&path);
+    let _ = std::fs::remove_dir_all(&cache_dir);
+}
+
+#[tokio::test(flavor = "current_thread")]
+async fn test_rate_limiter_spaces_requests_across_clones() {
+    // 600 requests per minute is one every 100ms
+    let limiter = RateLimiter::per_minute(600);
+    let shared = limiter.clone();
+
+    let start = Instant::now();
+    limiter.acquire().await;
+    shared.acquire().await;
+    limiter.acquire().await;
+    let elapsed = start.elapsed();
+
+    println!("\n⏱️ Three requests took {:?}", elapsed);
+    assert!(
+        elapsed >= Duration::from_millis(190),
+        "Clones should share one budget"
+    );
+}
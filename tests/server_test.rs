@@ -0,0 +1,725 @@
+#![cfg(feature = "server")]
+
+use intent_verification::{
+    AnalysisOptions, CloneRetryConfig, JobRecord, JobStatus, LocalOnlyPolicy, ServerConfig, serve,
+    verify_intent_with_options,
+};
+
+fn test_config() -> ServerConfig {
+    ServerConfig {
+        api_key: "test-key".to_string(),
+        // Nothing listens here, so LLM calls fail fast without network access
+        base_url: Some("http://127.0.0.1:9".to_string()),
+        options: AnalysisOptions {
+            clone_retry: Some(CloneRetryConfig {
+                max_attempts: 1,
+                ..Default::default()
+            }),
+            ..Default::default()
+        },
+        ..Default::default()
+    }
+}
+
+/// A remote repository nothing serves, so clones fail fast
+const UNREACHABLE_REPO: &str = "http://127.0.0.1:9/acme/calc.git";
+
+/// Start a server on a free port, returning its base URL
+async fn start_server(config: ServerConfig) -> String {
+    let listener = tokio::net::TcpListener::bind("127.0.0.1:0").await.unwrap();
+    let addr = listener.local_addr().unwrap();
+    tokio::spawn(serve(listener, config));
+    format!("http://{}", addr)
+}
+
+/// Accept one HTTP request on a free port and send its body back through the channel
+fn start_callback_receiver() -> (String, std::sync::mpsc::Receiver<String>) {
+    use std::io::{Read, Write};
+
+    let listener = std::net::TcpListener::bind("127.0.0.1:0").unwrap();
+    let url = format!("http://{}/results", listener.local_addr().unwrap());
+    let (sender, receiver) = std::sync::mpsc::channel();
+
+    std::thread::spawn(move || {
+        let (mut stream, _) = listener.accept().unwrap();
+        let mut request = Vec::new();
+        let mut buffer = [0u8; 4096];
+        loop {
+            let n = stream.read(&mut buffer).unwrap();
+            request.extend_from_slice(&buffer[..n]);
+            let text = String::from_utf8_lossy(&request).to_string();
+            if let Some((headers, body)) = text.split_once("\r\n\r\n") {
+                let length = headers
+                    .lines()
+                    .find_map(|line| {
+                        line.to_lowercase()
+                            .strip_prefix("content-length:")
+                            .map(|v| v.trim().parse::<usize>().unwrap())
+                    })
+                    .unwrap_or(0);
+                if body.len() >= length || n == 0 {
+                    stream
+                        .write_all(b"HTTP/1.1 200 OK\r\ncontent-length: 0\r\n\r\n")
+                        .unwrap();
+                    sender.send(body.to_string()).unwrap();
+                    return;
+                }
+            }
+        }
+    });
+
+    (url, receiver)
+}
+
+#[tokio::test(flavor = "multi_thread")]
+async fn test_server_routes() {
+    let url = start_server(test_config()).await;
+    let client = reqwest::Client::new();
+
+    let health: serde_json::Value = client
+        .get(format!("{}/health", url))
+        .send()
+        .await
+        .unwrap()
+        .json()
+        .await
+        .unwrap();
+    assert_eq!(health["status"], "ok");
+
+    let response = client
+        .post(format!("{}/verify", url))
+        .body("{\"test_repo_url\": 1}")
+        .send()
+        .await
+        .unwrap();
+    assert_eq!(
+        response.status(),
+        400,
+        "Malformed requests should be rejected"
+    );
+
+    let response = client
+        .get(format!("{}/jobs/999", url))
+        .send()
+        .await
+        .unwrap();
+    assert_eq!(response.status(), 404, "Unknown jobs should not be found");
+
+    let response = client
+        .delete(format!("{}/verify", url))
+        .send()
+        .await
+        .unwrap();
+    assert_eq!(response.status(), 404);
+}
+
+#[tokio::test(flavor = "multi_thread")]
+async fn test_server_rejects_operator_options() {
+    let url = start_server(test_config()).await;
+    let client = reqwest::Client::new();
+    let request = |extra: serde_json::Value| {
+        let mut body = serde_json::json!({
+            "repo_url": UNREACHABLE_REPO,
+            "base": "HEAD~1",
+            "head": "HEAD",
+            "user_intent": "The sum tests should pass",
+        });
+        body.as_object_mut()
+            .unwrap()
+            .extend(extra.as_object().unwrap().clone());
+        client.post(format!("{}/analyze", url)).json(&body).send()
+    };
+
+    // Running a program on the host is the operator's call, not the client's
+    let execution = serde_json::json!({ "command": ["sh", "-c", "touch /tmp/pwned"] });
+    for extra in [
+        serde_json::json!({ "options": { "execution": execution } }),
+        serde_json::json!({ "execution": execution }),
+        serde_json::json!({ "options": { "local_only": null } }),
+    ] {
+        let response = request(extra.clone()).await.unwrap();
+        assert_eq!(response.status(), 400, "{} should be rejected", extra);
+        let body: serde_json::Value = response.json().await.unwrap();
+        assert!(
+            body["error"].as_str().unwrap().contains("unknown field"),
+            "{}",
+            body
+        );
+    }
+
+    let response = request(serde_json::json!({
+        "options": { "archetype": "bug-fix", "acceptance_criteria": true, "language": "vi" }
+    }))
+    .await
+    .unwrap();
+    assert_eq!(
+        response.status(),
+        202,
+        "Allowed settings should be accepted"
+    );
+}
+
+#[tokio::test(flavor = "multi_thread")]
+async fn test_server_job_lifecycle() {
+    let url = start_server(test_config()).await;
+    let client = reqwest::Client::new();
+
+    let response = client
+        .post(format!("{}/analyze", url))
+        .json(&serde_json::json!({
+            "repo_url": UNREACHABLE_REPO,
+            "base": "HEAD~1",
+            "head": "HEAD",
+            "user_intent": "The sum tests should pass",
+        }))
+        .send()
+        .await
+        .unwrap();
+    assert_eq!(response.status(), 202, "Jobs should be accepted");
+    let job: JobRecord = response.json().await.unwrap();
+    assert_eq!(job.status, JobStatus::Running);
+
+    println!("\n🚀 Started job {}", job.id);
+
+    let mut finished = None;
+    for _ in 0..100 {
+        let job: JobRecord = client
+            .get(format!("{}/jobs/{}", url, job.id))
+            .send()
+            .await
+            .unwrap()
+            .json()
+            .await
+            .unwrap();
+        if job.status != JobStatus::Running {
+            finished = Some(job);
+            break;
+        }
+        tokio::time::sleep(std::time::Duration::from_millis(100)).await;
+    }
+
+    let job = finished.expect("Job should finish");
+    println!("🏁 Job finished: {:?} {:?}", job.status, job.error);
+    assert_eq!(job.status, JobStatus::Failed);
+    assert!(job.error.is_some(), "Failed jobs should carry the error");
+}
+
+#[tokio::test(flavor = "multi_thread")]
+async fn test_server_webhooks_and_callback() {
+    let (callback_url, callback) = start_callback_receiver();
+    let url = start_server(ServerConfig {
+        webhook_secret: Some("s3cret".to_string()),
+        callback_url: Some(callback_url),
+        ..test_config()
+    })
+    .await;
+    let client = reqwest::Client::new();
+
+    let response = client
+        .post(format!("{}/webhooks/gitlab", url))
+        .header("X-Gitlab-Event", "Push Hook")
+        .header("X-Gitlab-Token", "wrong")
+        .body("{}")
+        .send()
+        .await
+        .unwrap();
+    assert_eq!(
+        response.status(),
+        401,
+        "Deliveries without the secret should be rejected"
+    );
+
+    let response = client
+        .post(format!("{}/webhooks/gitlab", url))
+        .header("X-Gitlab-Event", "Note Hook")
+        .header("X-Gitlab-Token", "s3cret")
+        .body("{}")
+        .send()
+        .await
+        .unwrap();
+    assert_eq!(response.status(), 200, "Unrelated events should be ignored");
+
+    let push = serde_json::json!({
+        "before": "aaa111",
+        "after": "bbb222",
+        "commits": [{ "id": "bbb222", "message": "Make the sum tests pass" }],
+        "project": {
+            "path_with_namespace": "acme/calc",
+            "git_http_url": UNREACHABLE_REPO,
+        },
+    });
+    let response = client
+        .post(format!("{}/webhooks/gitlab", url))
+        .header("X-Gitlab-Event", "Push Hook")
+        .header("X-Gitlab-Token", "s3cret")
+        .json(&push)
+        .send()
+        .await
+        .unwrap();
+    assert_eq!(response.status(), 202, "Pushes should start a job");
+    let job: JobRecord = response.json().await.unwrap();
+    assert_eq!(job.source.as_deref(), Some("gitlab:acme/calc@bbb222"));
+
+    let delivered = tokio::task::spawn_blocking(move || {
+        callback.recv_timeout(std::time::Duration::from_secs(10))
+    })
+    .await
+    .unwrap()
+    .expect("The finished job should be delivered to the callback URL");
+
+    println!("\n📬 Callback body: {}", delivered);
+
+    let delivered: JobRecord = serde_json::from_str(&delivered).unwrap();
+    assert_eq!(delivered.id, job.id);
+    assert_eq!(delivered.status, JobStatus::Failed);
+}
+
+#[tokio::test(flavor = "multi_thread")]
+async fn test_server_rejects_local_repositories() {
+    let url = start_server(ServerConfig {
+        allowed_repo_hosts: vec!["github.com".to_string()],
+        ..test_config()
+    })
+    .await;
+    let client = reqwest::Client::new();
+    let analyze = |repo_url: &str| {
+        client
+            .post(format!("{}/analyze", url))
+            .json(&serde_json::json!({
+                "repo_url": repo_url,
+                "base": "HEAD~1",
+                "head": "HEAD",
+                "user_intent": "The sum tests should pass",
+            }))
+            .send()
+    };
+
+    for repo_url in [
+        "/etc",
+        "../secrets",
+        "file:///etc",
+        "FILE:///etc",
+        "ext::sh -c touch% /tmp/pwned",
+    ] {
+        let response = analyze(repo_url).await.unwrap();
+        assert_eq!(response.status(), 400, "'{}' should be rejected", repo_url);
+    }
+
+    let response = analyze("https://gitlab.com/acme/calc.git").await.unwrap();
+    assert_eq!(
+        response.status(),
+        403,
+        "Hosts outside the allowlist should be refused"
+    );
+
+    for repo_url in [
+        "https://github.com/acme/calc.git",
+        "ssh://git@github.com/acme/calc.git",
+        "git@github.com:acme/calc.git",
+    ] {
+        let response = analyze(repo_url).await.unwrap();
+        assert_eq!(response.status(), 202, "'{}' should be accepted", repo_url);
+    }
+
+    let response = client
+        .post(format!("{}/verify", url))
+        .json(&serde_json::json!({
+            "test_repo_url": "https://github.com/acme/calc.git",
+            "test_commit": "HEAD",
+            "solution_repo_url": "/etc",
+            "solution_commit1": "HEAD~1",
+            "solution_commit2": "HEAD",
+            "user_intent": "The sum tests should pass",
+        }))
+        .send()
+        .await
+        .unwrap();
+    assert_eq!(
+        response.status(),
+        400,
+        "The solution repository should be checked too"
+    );
+}
+
+#[tokio::test(flavor = "multi_thread")]
+async fn test_server_local_only_mode() {
+    let local_only = AnalysisOptions {
+        local_only: Some(LocalOnlyPolicy::new(["http://127.0.0.1:9"])),
+        ..test_config().options
+    };
+
+    // Finished jobs would leave the machine through the callback
+    let listener = tokio::net::TcpListener::bind("127.0.0.1:0").await.unwrap();
+    let error = serve(
+        listener,
+        ServerConfig {
+            callback_url: Some("http://127.0.0.1:9/results".to_string()),
+            options: local_only.clone(),
+            ..test_config()
+        },
+    )
+    .await
+    .expect_err("A callback should keep the server from starting");
+    println!("\n🔒 {}", error);
+    assert_eq!(error.kind(), std::io::ErrorKind::InvalidInput);
+
+    let url = start_server(ServerConfig {
+        options: local_only,
+        ..test_config()
+    })
+    .await;
+    let client = reqwest::Client::new();
+    let analyze = |repo_url: String| {
+        client
+            .post(format!("{}/analyze", url))
+            .json(&serde_json::json!({
+                "repo_url": repo_url,
+                "base": "HEAD~1",
+                "head": "HEAD",
+                "user_intent": "The sum tests should pass",
+            }))
+            .send()
+    };
+
+    let response = analyze("https://github.com/acme/calc.git".to_string())
+        .await
+        .unwrap();
+    assert_eq!(
+        response.status(),
+        403,
+        "Remote repositories should be refused"
+    );
+
+    let local = std::env::temp_dir().display().to_string();
+    for repo_url in [local.clone(), format!("file://{}", local)] {
+        let response = analyze(repo_url.clone()).await.unwrap();
+        assert_eq!(response.status(), 202, "'{}' should be accepted", repo_url);
+    }
+}
+
+#[test]
+fn test_pipeline_future_is_send() {
+    fn assert_send<T: Send>(_: &T) {}
+
+    // Jobs are `tokio::spawn`ed, which needs the verification future to be `Send`
+    let options = AnalysisOptions::default();
+    let verification = verify_intent_with_options("", "", "", "", "", "", "", None, None, &options);
+    assert_send(&verification);
+}
+
+#[tokio::test(flavor = "multi_thread")]
+async fn test_server_requires_the_auth_token() {
+    let url = start_server(ServerConfig {
+        auth_token: Some("s3cret".to_string()),
+        ..test_config()
+    })
+    .await;
+    let client = reqwest::Client::new();
+    let analyze = |authorization: Option<&str>| {
+        let mut request = client
+            .post(format!("{}/analyze", url))
+            .json(&serde_json::json!({
+                "repo_url": UNREACHABLE_REPO,
+                "base": "HEAD~1",
+                "head": "HEAD",
+                "user_intent": "The sum tests should pass",
+            }));
+        if let Some(authorization) = authorization {
+            request = request.header("Authorization", authorization);
+        }
+        request.send()
+    };
+
+    for authorization in [None, Some("Bearer wrong"), Some("s3cret")] {
+        let response = analyze(authorization).await.unwrap();
+        assert_eq!(
+            response.status(),
+            401,
+            "{:?} should be refused",
+            authorization
+        );
+        assert_eq!(response.headers()["www-authenticate"], "Bearer");
+    }
+
+    let response = analyze(Some("Bearer s3cret")).await.unwrap();
+    assert_eq!(response.status(), 202);
+    let job: JobRecord = response.json().await.unwrap();
+
+    let job_url = format!("{}/jobs/{}", url, job.id);
+    let response = client.get(&job_url).send().await.unwrap();
+    assert_eq!(response.status(), 401, "Job records need the token too");
+    let response = client
+        .get(&job_url)
+        .bearer_auth("s3cret")
+        .send()
+        .await
+        .unwrap();
+    assert_eq!(response.status(), 200);
+
+    let health = client.get(format!("{}/health", url)).send().await.unwrap();
+    assert_eq!(health.status(), 200, "Health checks stay open");
+    let webhook = client
+        .post(format!("{}/webhooks/gitlab", url))
+        .body("{}")
+        .send()
+        .await
+        .unwrap();
+    assert_eq!(
+        webhook.status(),
+        404,
+        "Webhooks are authenticated by their secret, not the token"
+    );
+}
+
+#[tokio::test(flavor = "multi_thread")]
+async fn test_server_limits_running_jobs() {
+    // Holds each connection for a few seconds without answering, so clones from it stall
+    let stalled = std::net::TcpListener::bind("127.0.0.1:0").unwrap();
+    let stalled_repo = format!("http://{}/acme/calc.git", stalled.local_addr().unwrap());
+    std::thread::spawn(move || {
+        for connection in stalled.incoming() {
+            std::thread::spawn(move || {
+                std::thread::sleep(std::time::Duration::from_secs(3));
+                drop(connection);
+            });
+        }
+    });
+
+    let url = start_server(ServerConfig {
+        max_running_jobs: Some(1),
+        ..test_config()
+    })
+    .await;
+    let client = reqwest::Client::new();
+    let analyze = |repo_url: &str| {
+        client
+            .post(format!("{}/analyze", url))
+            .json(&serde_json::json!({
+                "repo_url": repo_url,
+                "base": "HEAD~1",
+                "head": "HEAD",
+                "user_intent": "The sum tests should pass",
+            }))
+            .send()
+    };
+
+    let response = analyze(&stalled_repo).await.unwrap();
+    assert_eq!(response.status(), 202);
+
+    let response = analyze(UNREACHABLE_REPO).await.unwrap();
+    assert_eq!(
+        response.status(),
+        503,
+        "A second job should wait for the first to finish"
+    );
+    let body: serde_json::Value = response.json().await.unwrap();
+    println!("\n🚦 {}", body);
+    assert!(body["error"].as_str().unwrap().contains("already running"));
+}
+
+#[tokio::test(flavor = "multi_thread")]
+async fn test_server_rejects_large_bodies() {
+    let url = start_server(ServerConfig {
+        max_body_bytes: Some(1024),
+        webhook_secret: Some("s3cret".to_string()),
+        ..test_config()
+    })
+    .await;
+    let client = reqwest::Client::new();
+
+    let response = client
+        .post(format!("{}/analyze", url))
+        .json(&serde_json::json!({
+            "repo_url": UNREACHABLE_REPO,
+            "base": "HEAD~1",
+            "head": "HEAD",
+            "user_intent": "x".repeat(4096),
+        }))
+        .send()
+        .await
+        .unwrap();
+    assert_eq!(response.status(), 413, "Oversized bodies should be refused");
+
+    let response = client
+        .post(format!("{}/webhooks/github", url))
+        .header("X-GitHub-Event", "pull_request")
+        .body("x".repeat(4096))
+        .send()
+        .await
+        .unwrap();
+    assert_eq!(response.status(), 413);
+}
+
+#[tokio::test(flavor = "multi_thread")]
+async fn test_server_drops_oldest_finished_jobs() {
+    let url = start_server(ServerConfig {
+        max_finished_jobs: Some(2),
+        ..test_config()
+    })
+    .await;
+    let client = reqwest::Client::new();
+
+    let mut ids = Vec::new();
+    for _ in 0..3 {
+        let job: JobRecord = client
+            .post(format!("{}/analyze", url))
+            .json(&serde_json::json!({
+                "repo_url": UNREACHABLE_REPO,
+                "base": "HEAD~1",
+                "head": "HEAD",
+                "user_intent": "The sum tests should pass",
+            }))
+            .send()
+            .await
+            .unwrap()
+            .json()
+            .await
+            .unwrap();
+
+        // Wait for each job so they finish in order
+        for _ in 0..100 {
+            let job: JobRecord = client
+                .get(format!("{}/jobs/{}", url, job.id))
+                .send()
+                .await
+                .unwrap()
+                .json()
+                .await
+                .unwrap();
+            if job.status != JobStatus::Running {
+                break;
+            }
+            tokio::time::sleep(std::time::Duration::from_millis(100)).await;
+        }
+        ids.push(job.id);
+    }
+
+    let status = |id: String| {
+        let request = client.get(format!("{}/jobs/{}", url, id)).send();
+        async move { request.await.unwrap().status() }
+    };
+    assert_eq!(
+        status(ids[0].clone()).await,
+        404,
+        "The oldest finished job should be dropped"
+    );
+    assert_eq!(status(ids[1].clone()).await, 200);
+    assert_eq!(status(ids[2].clone()).await, 200);
+}
+
+#[tokio::test(flavor = "multi_thread")]
+async fn test_server_webhooks_require_a_secret() {
+    let url = start_server(test_config()).await;
+
+    let response = reqwest::Client::new()
+        .post(format!("{}/webhooks/gitlab", url))
+        .header("X-Gitlab-Event", "Push Hook")
+        .body("{}")
+        .send()
+        .await
+        .unwrap();
+    assert_eq!(
+        response.status(),
+        404,
+        "Webhooks should be disabled without a secret"
+    );
+}
+
+/// Answer one GitHub API request with an issue, sending the requested path through the channel
+fn start_github_api() -> (String, std::sync::mpsc::Receiver<String>) {
+    use std::io::{Read, Write};
+
+    let listener = std::net::TcpListener::bind("127.0.0.1:0").unwrap();
+    let url = format!("http://{}", listener.local_addr().unwrap());
+    let (sender, receiver) = std::sync::mpsc::channel();
+
+    std::thread::spawn(move || {
+        let (mut stream, _) = listener.accept().unwrap();
+        let mut request = Vec::new();
+        let mut buffer = [0u8; 4096];
+        while !String::from_utf8_lossy(&request).contains("\r\n\r\n") {
+            let n = stream.read(&mut buffer).unwrap();
+            request.extend_from_slice(&buffer[..n]);
+        }
+        let issue = serde_json::json!({
+            "number": 12,
+            "title": "Add a sum function",
+            "body": "It should add two numbers",
+            "html_url": "https://github.com/acme/calc/issues/12",
+        })
+        .to_string();
+        write!(
+            stream,
+            "HTTP/1.1 200 OK\r\ncontent-type: application/json\r\ncontent-length: {}\r\n\r\n{}",
+            issue.len(),
+            issue
+        )
+        .unwrap();
+        let request = String::from_utf8_lossy(&request).to_string();
+        let path = request.split_whitespace().nth(1).unwrap_or_default();
+        sender.send(path.to_string()).unwrap();
+    });
+
+    (url, receiver)
+}
+
+/// `X-Hub-Signature-256` header for `payload`: HMAC-SHA256 keyed with the secret
+fn github_signature(secret: &str, payload: &str) -> String {
+    use sha2::{Digest, Sha256};
+
+    let mut key = [0u8; 64];
+    key[..secret.len()].copy_from_slice(secret.as_bytes());
+    let pad = |byte: u8| key.map(|b| b ^ byte);
+    let inner = Sha256::new()
+        .chain_update(pad(0x36))
+        .chain_update(payload)
+        .finalize();
+    let mac = Sha256::new()
+        .chain_update(pad(0x5c))
+        .chain_update(inner)
+        .finalize();
+    let hex: String = mac.iter().map(|b| format!("{:02x}", b)).collect();
+    format!("sha256={}", hex)
+}
+
+#[tokio::test(flavor = "multi_thread")]
+async fn test_server_webhooks_fetch_linked_issues() {
+    let (api_url, api_requests) = start_github_api();
+    // Only this test talks to the GitHub API
+    unsafe { std::env::set_var("GITHUB_API_URL", &api_url) };
+    let url = start_server(ServerConfig {
+        webhook_secret: Some("s3cret".to_string()),
+        ..test_config()
+    })
+    .await;
+
+    let payload = serde_json::json!({
+        "action": "opened",
+        "pull_request": {
+            "number": 7,
+            "title": "Implement sum",
+            "body": "Fixes #12",
+            "base": { "sha": "aaa111" },
+            "head": { "sha": "bbb222", "repo": { "full_name": "acme/calc", "clone_url": UNREACHABLE_REPO } },
+        },
+        "repository": { "full_name": "acme/calc", "clone_url": UNREACHABLE_REPO },
+    })
+    .to_string();
+    let response = reqwest::Client::new()
+        .post(format!("{}/webhooks/github", url))
+        .header("X-GitHub-Event", "pull_request")
+        .header("X-Hub-Signature-256", github_signature("s3cret", &payload))
+        .body(payload)
+        .send()
+        .await
+        .unwrap();
+    assert_eq!(response.status(), 202, "Pull requests should start a job");
+
+    let path = tokio::task::spawn_blocking(move || {
+        api_requests.recv_timeout(std::time::Duration::from_secs(10))
+    })
+    .await
+    .unwrap()
+    .expect("The linked issue should be fetched");
+    assert_eq!(path, "/repos/acme/calc/issues/12");
+}
@@ -0,0 +1,251 @@
+#![cfg(feature = "git")]
+
+use intent_verification::{
+    AnalysisOptions, ChangeType, FileChange, IntentVerificationResult, SHORTCUT_RULE,
+    apply_shortcut_findings, detect_hardcoded_answers, verify_intent_with_options,
+};
+
+fn modified(path: &str, old: &str, new: &str) -> FileChange {
+    FileChange {
+        path: path.to_string(),
+        status: ChangeType::Modified,
+        content: Some(new.to_string()),
+        old_content: Some(old.to_string()),
+    }
+}
+
+fn source(path: &str, content: &str) -> (String, String) {
+    (path.to_string(), content.to_string())
+}
+
+#[test]
+fn test_returned_test_expectations_are_reported() {
+    let tests = vec![
+        source(
+            "tests/greeting_test.rs",
+            "#[test]\nfn greets() {\n    assert_eq!(greet(\"Ada\"), \"Hello, Ada!\");\n    assert_eq!(answer(), 4242);\n}\n",
+        ),
+        source(
+            "tests/test_slug.py",
+            "def test_slug():\n    assert slugify('Hello World') == 'hello-world'\n",
+        ),
+        source(
+            "web/format.test.ts",
+            "it('formats', () => {\n  expect(format(1234.5)).toBe(\"1,234.50\");\n});\n",
+        ),
+    ];
+    let changes = vec![
+        modified(
+            "src/lib.rs",
+            "pub fn greet(name: &str) -> String {\n    todo!()\n}\n",
+            "pub fn greet(name: &str) -> String {\n    \"Hello, Ada!\".to_string()\n}\n\npub fn answer() -> u32 {\n    return 4242;\n}\n",
+        ),
+        modified(
+            "slug.py",
+            "def slugify(text):\n    pass\n",
+            "def slugify(text):\n    if text == 'Hello World':\n        return \"hello-world\"\n    return text\n",
+        ),
+        modified(
+            "web/format.ts",
+            "export const format = (n: number) => '';\n",
+            "export const format = (n: number) => \"1,234.50\";\n",
+        ),
+    ];
+
+    let findings = detect_hardcoded_answers(&changes, &tests, "Greet people by name");
+    for finding in &findings {
+        println!(
+            "🎯 {}:{} {}",
+            finding.file_path.as_deref().unwrap(),
+            finding.line.unwrap(),
+            finding.message
+        );
+    }
+    let locations: Vec<(&str, usize)> = findings
+        .iter()
+        .map(|f| (f.file_path.as_deref().unwrap(), f.line.unwrap()))
+        .collect();
+    assert_eq!(
+        locations,
+        vec![
+            ("src/lib.rs", 2),
+            ("src/lib.rs", 6),
+            ("slug.py", 3),
+            ("web/format.ts", 1)
+        ]
+    );
+    assert!(findings.iter().all(|f| f.rule == SHORTCUT_RULE));
+    assert_eq!(
+        findings[0].snippet.as_deref(),
+        Some("\"Hello, Ada!\".to_string()")
+    );
+    assert!(
+        findings[0]
+            .message
+            .contains("\"Hello, Ada!\", the value asserted in tests/greeting_test.rs (line 3)"),
+        "The message should point at the assertion: {}",
+        findings[0].message
+    );
+}
+
+#[test]
+fn test_computed_and_intended_values_are_not_reported() {
+    let tests = vec![source(
+        "tests/version_test.rs",
+        "assert_eq!(version(), \"v2.0.0\");\nassert_eq!(add(2, 3), 5);\nassert_eq!(status(), \"ready\");\n",
+    )];
+    let changes = vec![
+        // The intent asks for this exact string
+        modified(
+            "src/version.rs",
+            "",
+            "pub fn version() -> &'static str {\n    \"v2.0.0\"\n}\n",
+        ),
+        // Too short a number to tell
+        modified(
+            "src/add.rs",
+            "",
+            "pub fn add(a: u8, b: u8) -> u8 {\n    5\n}\n",
+        ),
+        // An item of a list rather than a returned value
+        modified(
+            "src/states.rs",
+            "",
+            "pub const STATES: &[&str] = &[\n    \"ready\",\n];\n",
+        ),
+        // Test files may repeat their own expectations
+        modified(
+            "tests/helpers.rs",
+            "",
+            "fn expected() -> &'static str {\n    \"ready\"\n}\n",
+        ),
+    ];
+    let findings = detect_hardcoded_answers(&changes, &tests, "Bump the version to v2.0.0");
+    assert!(findings.is_empty(), "Unexpected findings: {:#?}", findings);
+
+    let existing = modified(
+        "src/status.rs",
+        "pub fn status() -> &'static str {\n    \"ready\"\n}\n",
+        "pub fn status() -> &'static str {\n    \"ready\"\n}\n\npub fn other() {}\n",
+    );
+    assert!(
+        detect_hardcoded_answers(&[existing], &tests, "Add other").is_empty(),
+        "Lines that were already there aren't part of the solution"
+    );
+}
+
+#[test]
+fn test_apply_shortcut_findings() {
+    let mut result: IntentVerificationResult = serde_json::from_str(
+        r#"{"is_intent_fulfilled":true,"confidence":0.9,"explanation":"1 out of 1 changed files support the test intent","files_analyzed":[],"overall_assessment":""}"#,
+    )
+    .unwrap();
+    result.findings = detect_hardcoded_answers(
+        &[modified(
+            "src/lib.rs",
+            "",
+            "fn answer() -> u32 {\n    4242\n}\n",
+        )],
+        &[source(
+            "tests/answer_test.rs",
+            "assert_eq!(answer(), 4242);\n",
+        )],
+        "Compute the answer",
+    );
+    assert_eq!(apply_shortcut_findings(&mut result), 1);
+    assert!(
+        result
+            .explanation
+            .ends_with("1 line(s) return values hardcoded from the tests")
+    );
+}
+
+#[tokio::test]
+async fn test_verification_reports_shortcuts() {
+    let path = format!(
+        "/tmp/shortcuts_test_repo_{}_{}",
+        std::process::id(),
+        std::time::SystemTime::now()
+            .duration_since(std::time::UNIX_EPOCH)
+            .unwrap()
+            .as_nanos()
+    );
+    let repo = git2::Repository::init(&path).unwrap();
+    let signature = git2::Signature::now("Test", "test@example.com").unwrap();
+    let commit = |message: &str, files: &[(&str, &str)]| {
+        let mut index = repo.index().unwrap();
+        for (file, content) in files {
+            let full_path = std::path::Path::new(&path).join(file);
+            std::fs::create_dir_all(full_path.parent().unwrap()).unwrap();
+            std::fs::write(&full_path, content).unwrap();
+            index.add_path(std::path::Path::new(file)).unwrap();
+        }
+        let tree = repo.find_tree(index.write_tree().unwrap()).unwrap();
+        let parents = match repo.head() {
+            Ok(head) => vec![head.peel_to_commit().unwrap()],
+            Err(_) => vec![],
+        };
+        let parents: Vec<&git2::Commit> = parents.iter().collect();
+        repo.commit(
+            Some("HEAD"),
+            &signature,
+            &signature,
+            message,
+            &tree,
+            &parents,
+        )
+        .unwrap()
+        .to_string()
+    };
+    let first = commit(
+        "stub",
+        &[(
+            "src/lib.rs",
+            "pub fn fib(n: u64) -> u64 {\n    todo!()\n}\n",
+        )],
+    );
+    let second = commit(
+        "implement",
+        &[
+            (
+                "src/lib.rs",
+                "pub fn fib(n: u64) -> u64 {\n    return 6765;\n}\n",
+            ),
+            (
+                "tests/fib_test.rs",
+                "#[test]\nfn twentieth() {\n    assert_eq!(fib(20), 6765);\n}\n",
+            ),
+        ],
+    );
+    let options = AnalysisOptions {
+        dry_run: true,
+        ..Default::default()
+    };
+
+    let result = verify_intent_with_options(
+        &path,
+        &second,
+        &path,
+        &first,
+        &second,
+        "Implement the Fibonacci function",
+        "test-key",
+        None,
+        Some("http://127.0.0.1:9"),
+        &options,
+    )
+    .await
+    .expect("Dry run should succeed without the model");
+
+    let shortcut = result
+        .findings
+        .iter()
+        .find(|f| f.rule == SHORTCUT_RULE)
+        .expect("The hardcoded value should be reported");
+    println!("\n🎯 {}", shortcut.message);
+    assert_eq!(shortcut.file_path.as_deref(), Some("src/lib.rs"));
+    assert_eq!(shortcut.line, Some(2));
+    assert!(shortcut.message.contains("tests/fib_test.rs (line 3)"));
+
+    std::fs::remove_dir_all(&path).ok();
+}
@@ -0,0 +1,211 @@
+#![cfg(feature = "git")]
+
+mod common;
+
+use intent_verification::{
+    AnalysisOptions, ChangeType, FileChange, IntentVerificationResult, RepoSnapshot,
+    SIMILARITY_RULE, SimilarityConfig, WarningKind, apply_similarity_findings, containment,
+    detect_near_copies, shingles, verify_intent_with_options,
+};
+
+const ORIGINAL: &str = r#"
+pub fn levenshtein(a: &str, b: &str) -> usize {
+    let a: Vec<char> = a.chars().collect();
+    let b: Vec<char> = b.chars().collect();
+    let mut previous: Vec<usize> = (0..=b.len()).collect();
+    for i in 1..=a.len() {
+        let mut current = vec![i; b.len() + 1];
+        for j in 1..=b.len() {
+            let cost = if a[i - 1] == b[j - 1] { 0 } else { 1 };
+            current[j] = (previous[j] + 1).min(current[j - 1] + 1).min(previous[j - 1] + cost);
+        }
+        previous = current;
+    }
+    previous[b.len()]
+}
+"#;
+
+const INDEPENDENT: &str = r#"
+pub fn levenshtein(left: &str, right: &str) -> usize {
+    if left.is_empty() {
+        return right.chars().count();
+    }
+    if right.is_empty() {
+        return left.chars().count();
+    }
+    let first = left.chars().next().unwrap();
+    let rest_left = &left[first.len_utf8()..];
+    let other = right.chars().next().unwrap();
+    let rest_right = &right[other.len_utf8()..];
+    let substitution = levenshtein(rest_left, rest_right) + usize::from(first != other);
+    substitution
+        .min(levenshtein(rest_left, right) + 1)
+        .min(levenshtein(left, rest_right) + 1)
+}
+"#;
+
+fn added(path: &str, content: &str) -> FileChange {
+    FileChange {
+        path: path.to_string(),
+        status: ChangeType::Added,
+        content: Some(content.to_string()),
+        old_content: None,
+    }
+}
+
+/// Create a local repository with a stub commit and a commit adding `src/lib.rs`
+fn init_local_repo(name: &str, content: &str) -> (String, String, String) {
+    let path = common::unique_path(&format!("similarity_test_{}", name));
+    let first = common::commit_files(&path, "readme", &[("README.md", "# Strings\n")]);
+    let second = common::commit_files(&path, "add distance", &[("src/lib.rs", content)]);
+    (path, first, second)
+}
+
+#[test]
+fn test_shingles_ignore_formatting() {
+    let compact = "fn add(a: i32, b: i32) -> i32 { a + b }";
+    let spread = "fn add(a: i32,\n       b: i32)\n    -> i32\n{\n    a + b\n}\n";
+    let a = shingles(compact, 5);
+    let b = shingles(spread, 5);
+    assert!(!a.is_empty());
+    assert_eq!(
+        a, b,
+        "Whitespace and line breaks shouldn't change the shingles"
+    );
+    assert_eq!(containment(&a, &b), 1.0);
+    assert_eq!(containment(&shingles("", 5), &b), 0.0);
+}
+
+#[test]
+fn test_near_copies_are_reported() {
+    let reference = RepoSnapshot::from_files([("src/distance.rs", ORIGINAL)]);
+    let references = vec![("prior-art".to_string(), reference)];
+    // A copy with different indentation and an extra comment line
+    let copied = format!("// Edit distance\n{}", ORIGINAL.replace("    ", "  "));
+    let changes = vec![
+        added("src/lib.rs", &copied),
+        added("src/other.rs", INDEPENDENT),
+        added("src/small.rs", "pub fn one() -> u8 { 1 }\n"),
+    ];
+
+    let findings = detect_near_copies(&changes, &references, &SimilarityConfig::default());
+    for finding in &findings {
+        println!("🔍 {}", finding.message);
+    }
+    assert_eq!(findings.len(), 1, "Only the copied file should be reported");
+    let finding = &findings[0];
+    assert_eq!(finding.rule, SIMILARITY_RULE);
+    assert_eq!(finding.file_path.as_deref(), Some("src/lib.rs"));
+    assert_eq!(
+        finding.snippet.as_deref(),
+        Some("prior-art:src/distance.rs")
+    );
+    assert_eq!(finding.line, Some(1));
+    assert!(
+        finding
+            .message
+            .contains("matches src/distance.rs in prior-art")
+    );
+}
+
+#[test]
+fn test_only_added_lines_are_compared() {
+    let reference = RepoSnapshot::from_files([("src/distance.rs", ORIGINAL)]);
+    let references = vec![("upstream".to_string(), reference)];
+    // The copied function was already in the file; the change only adds a small helper
+    let change = FileChange {
+        path: "src/lib.rs".to_string(),
+        status: ChangeType::Modified,
+        content: Some(format!(
+            "{}\npub fn is_close(a: &str, b: &str) -> bool {{\n    levenshtein(a, b) <= 2\n}}\n",
+            ORIGINAL
+        )),
+        old_content: Some(ORIGINAL.to_string()),
+    };
+    let config = SimilarityConfig {
+        min_tokens: 5,
+        ..Default::default()
+    };
+    assert!(
+        detect_near_copies(&[change], &references, &config).is_empty(),
+        "Existing code shouldn't count as copied"
+    );
+}
+
+#[test]
+fn test_apply_similarity_findings() {
+    let mut result: IntentVerificationResult = serde_json::from_str(
+        r#"{"is_intent_fulfilled":true,"confidence":0.9,"explanation":"1 out of 1 changed files support the test intent","files_analyzed":[],"overall_assessment":""}"#,
+    )
+    .unwrap();
+    let reference = RepoSnapshot::from_files([("src/distance.rs", ORIGINAL)]);
+    result.findings = detect_near_copies(
+        &[added("src/lib.rs", ORIGINAL)],
+        &[("prior-art".to_string(), reference)],
+        &SimilarityConfig::default(),
+    );
+
+    assert_eq!(apply_similarity_findings(&mut result), 1);
+    assert!(result.is_intent_fulfilled, "The verdict itself is kept");
+    assert!(
+        result
+            .explanation
+            .ends_with("1 file(s) closely match reference code")
+    );
+
+    result.findings[0].suppressed = true;
+    result.explanation = "unchanged".to_string();
+    assert_eq!(apply_similarity_findings(&mut result), 0);
+    assert_eq!(result.explanation, "unchanged");
+}
+
+#[tokio::test]
+async fn test_verification_compares_against_references() {
+    let (solution, first, second) = init_local_repo("solution", ORIGINAL);
+    let (reference, _, reference_head) = init_local_repo("reference", ORIGINAL);
+    let options = AnalysisOptions {
+        similarity: Some(SimilarityConfig {
+            references: vec![
+                format!("{}@{}", reference, reference_head),
+                "/tmp/similarity_test_missing_reference".to_string(),
+            ],
+            ..Default::default()
+        }),
+        ..Default::default()
+    };
+
+    let result = verify_intent_with_options(
+        &solution,
+        &second,
+        &solution,
+        &first,
+        &second,
+        "Add an edit distance function",
+        "test-key",
+        None,
+        Some("http://127.0.0.1:9"),
+        &options,
+    )
+    .await
+    .expect("Verification should finish with warnings");
+
+    let copies: Vec<_> = result
+        .findings
+        .iter()
+        .filter(|f| f.rule == SIMILARITY_RULE)
+        .collect();
+    println!("\n🔍 Findings: {:#?}", copies);
+    assert_eq!(copies.len(), 1);
+    assert_eq!(copies[0].file_path.as_deref(), Some("src/lib.rs"));
+    assert!(
+        result
+            .warnings
+            .iter()
+            .any(|w| w.kind == WarningKind::Similarity
+                && w.message.contains("similarity_test_missing_reference")),
+        "A reference that can't be read should be reported"
+    );
+
+    std::fs::remove_dir_all(&solution).ok();
+    std::fs::remove_dir_all(&reference).ok();
+}
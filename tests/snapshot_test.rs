@@ -0,0 +1,146 @@
+#![cfg(feature = "git")]
+
+use intent_verification::{ChangeType, RepoSnapshot, TestTargets};
+
+#[test]
+fn test_snapshot_diff() {
+    let base = RepoSnapshot::from_files([
+        (
+            "src/lib.rs",
+            "pub fn sum(a: i32, b: i32) -> i32 {\n    todo!()\n}\n",
+        ),
+        ("README.md", "# Sample\n"),
+        ("docs/old.md", "Old docs\n"),
+    ]);
+    let mut head = base.clone();
+    head.insert(
+        "src/lib.rs",
+        "pub fn sum(a: i32, b: i32) -> i32 {\n    a + b\n}\n",
+    );
+    head.files.remove("docs/old.md");
+    head.insert("tests/sum_tests.rs", "#[test]\nfn sums() {}\n");
+
+    let changes = base.diff(&head);
+
+    println!("\n📸 Snapshot diff: {:#?}", changes);
+
+    let summary: Vec<(&str, ChangeType)> = changes
+        .iter()
+        .map(|c| (c.path.as_str(), c.status.clone()))
+        .collect();
+    assert_eq!(
+        summary,
+        vec![
+            ("docs/old.md", ChangeType::Deleted),
+            ("src/lib.rs", ChangeType::Modified),
+            ("tests/sum_tests.rs", ChangeType::Added),
+        ],
+        "Unchanged files should be left out and changes sorted by path"
+    );
+    assert!(changes[0].content.is_none());
+    assert_eq!(changes[0].old_content.as_deref(), Some("Old docs\n"));
+    assert!(changes[1].content.as_deref().unwrap().contains("a + b"));
+    assert!(
+        changes[1]
+            .old_content
+            .as_deref()
+            .unwrap()
+            .contains("todo!()")
+    );
+    assert!(changes[2].old_content.is_none());
+}
+
+#[test]
+fn test_snapshot_read_test_targets_code() {
+    let snapshot = RepoSnapshot::from_files([
+        (
+            "src/lib.rs",
+            "pub fn sum(a: i32, b: i32) -> i32 {\n    a + b\n}\n",
+        ),
+        ("target/debug/build.rs", "fn hidden() {}\n"),
+        ("tests/sum_tests.rs", "#[test]\nfn sums() {}\n"),
+    ]);
+    let targets = TestTargets {
+        functions: vec!["sum".to_string(), "hidden".to_string()],
+        files: vec!["tests/sum_tests.rs".to_string(), "missing.rs".to_string()],
+        ..Default::default()
+    };
+
+    let with_code = snapshot.read_test_targets_code(&targets);
+
+    assert_eq!(with_code.file_contents.len(), 2);
+    assert!(with_code.file_contents[0].error.is_none());
+    assert!(with_code.file_contents[1].error.is_some());
+
+    let sum = &with_code.function_contents[0];
+    assert_eq!(sum.file_path.as_deref(), Some("src/lib.rs"));
+    assert!(sum.content.as_deref().unwrap().contains("a + b"));
+    assert!(
+        with_code.function_contents[1].content.is_none(),
+        "Functions under target/ should be ignored"
+    );
+}
+
+#[test]
+fn test_snapshots_from_unified_diff() {
+    let diff = r#"diff --git a/src/lib.rs b/src/lib.rs
+index 1111111..2222222 100644
+--- a/src/lib.rs
++++ b/src/lib.rs
+@@ -1,3 +1,3 @@
+ pub fn sum(a: i32, b: i32) -> i32 {
+-    todo!()
++    a + b
+ }
+@@ -20,3 +20,3 @@
+ pub fn sub(a: i32, b: i32) -> i32 {
+-    todo!()
++    a - b
+ }
+diff --git a/tests/sum_tests.rs b/tests/sum_tests.rs
+new file mode 100644
+index 0000000..3333333
+--- /dev/null
++++ b/tests/sum_tests.rs
+@@ -0,0 +1,2 @@
++#[test]
++fn sums() {}
+diff --git a/docs/old.md b/docs/old.md
+deleted file mode 100644
+index 4444444..0000000
+--- a/docs/old.md
++++ /dev/null
+@@ -1 +0,0 @@
+-Old docs
+"#;
+
+    let (before, after) =
+        RepoSnapshot::from_unified_diff(diff).expect("Should parse git diff output");
+    let changes = before.diff(&after);
+
+    println!("\n🩹 Changes from diff: {:#?}", changes);
+
+    let summary: Vec<(&str, ChangeType)> = changes
+        .iter()
+        .map(|c| (c.path.as_str(), c.status.clone()))
+        .collect();
+    assert_eq!(
+        summary,
+        vec![
+            ("docs/old.md", ChangeType::Deleted),
+            ("src/lib.rs", ChangeType::Modified),
+            ("tests/sum_tests.rs", ChangeType::Added),
+        ]
+    );
+    assert_eq!(
+        after.get("src/lib.rs").unwrap(),
+        "pub fn sum(a: i32, b: i32) -> i32 {\n    a + b\n}\n...\npub fn sub(a: i32, b: i32) -> i32 {\n    a - b\n}\n",
+        "Hunks should be kept in order with a separator between them"
+    );
+    assert!(before.get("src/lib.rs").unwrap().contains("todo!()"));
+    assert_eq!(
+        after.get("tests/sum_tests.rs"),
+        Some("#[test]\nfn sums() {}\n")
+    );
+    assert_eq!(before.get("docs/old.md"), Some("Old docs\n"));
+}
@@ -0,0 +1,110 @@
+#![cfg(feature = "store")]
+
+use intent_verification::{IntentVerificationResult, ResultMetadata, ResultStore, VerificationKey};
+
+fn key(head_commit: &str, pr_number: Option<u64>) -> VerificationKey {
+    VerificationKey {
+        repo_url: "https://github.com/VAR-META-Tech/intent-verification-sample-rs".to_string(),
+        base_commit: "818d444d".to_string(),
+        head_commit: head_commit.to_string(),
+        intent: "I want to ensure the tests/sum_tests.rs works correctly".to_string(),
+        pr_number,
+    }
+}
+
+fn result(is_intent_fulfilled: bool, finished_at: &str) -> IntentVerificationResult {
+    IntentVerificationResult {
+        is_intent_fulfilled,
+        confidence: if is_intent_fulfilled { 0.9 } else { 0.3 },
+        metadata: ResultMetadata {
+            finished_at: finished_at.to_string(),
+            ..Default::default()
+        },
+        ..Default::default()
+    }
+}
+
+#[test]
+fn test_store_latest_and_history() {
+    let store = ResultStore::open_in_memory().expect("Failed to open store");
+
+    store
+        .save(
+            &key("aaaa", Some(7)),
+            &result(false, "2025-01-01T10:00:00+00:00"),
+        )
+        .unwrap();
+    store
+        .save(
+            &key("bbbb", Some(7)),
+            &result(true, "2025-01-02T10:00:00+00:00"),
+        )
+        .unwrap();
+    store
+        .save(
+            &key("cccc", None),
+            &result(false, "2025-01-02T12:00:00+00:00"),
+        )
+        .unwrap();
+
+    let latest_pr = store
+        .latest_for_pr(&key("", None).repo_url, 7)
+        .unwrap()
+        .expect("PR 7 should have results");
+    assert_eq!(latest_pr.key.head_commit, "bbbb");
+    assert!(latest_pr.result.is_intent_fulfilled);
+
+    let latest = store.latest(&key("aaaa", None)).unwrap().unwrap();
+    assert_eq!(latest.key.pr_number, Some(7));
+    assert!(!latest.result.is_intent_fulfilled);
+
+    assert!(store.latest(&key("dddd", None)).unwrap().is_none());
+    assert!(
+        store
+            .latest_for_pr("https://example.com/other", 7)
+            .unwrap()
+            .is_none()
+    );
+
+    let history = store.history(&key("", None).repo_url, 10).unwrap();
+    let heads: Vec<&str> = history.iter().map(|r| r.key.head_commit.as_str()).collect();
+    assert_eq!(
+        heads,
+        vec!["cccc", "bbbb", "aaaa"],
+        "History is newest first"
+    );
+}
+
+#[test]
+fn test_store_pass_rate_over_time() {
+    let store = ResultStore::open_in_memory().expect("Failed to open store");
+
+    store
+        .save(&key("a", None), &result(true, "2025-01-01T09:00:00+00:00"))
+        .unwrap();
+    store
+        .save(&key("b", None), &result(false, "2025-01-01T10:00:00+00:00"))
+        .unwrap();
+    store
+        .save(&key("c", None), &result(true, "2025-01-03T10:00:00+00:00"))
+        .unwrap();
+
+    let points = store.pass_rate_over_time(None, None).unwrap();
+
+    println!("\n📈 Pass rate over time: {:?}", points);
+
+    assert_eq!(points.len(), 2);
+    assert_eq!(points[0].date, "2025-01-01");
+    assert_eq!((points[0].total, points[0].passed), (2, 1));
+    assert!((points[0].pass_rate - 0.5).abs() < 1e-6);
+    assert_eq!(points[1].date, "2025-01-03");
+    assert_eq!(points[1].pass_rate, 1.0);
+
+    let since = store
+        .pass_rate_over_time(
+            Some(&key("", None).repo_url),
+            Some("2025-01-02T00:00:00+00:00"),
+        )
+        .unwrap();
+    assert_eq!(since.len(), 1);
+}
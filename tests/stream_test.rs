@@ -0,0 +1,159 @@
+#![cfg(feature = "git")]
+
+mod common;
+
+use futures::StreamExt;
+use intent_verification::{
+    AnalysisOptions, FileAnalysisResult, PromptStage, WarningKind,
+    analyze_repository_changes_stream,
+};
+
+/// Create a local repository where the second commit adds three files
+fn init_local_repo() -> (String, String, String) {
+    let path = common::unique_path("stream_test_repo");
+    let first = common::commit_files(&path, "readme", &[("README.md", "# Math\n")]);
+    let second = common::commit_files(
+        &path,
+        "math",
+        &[
+            (
+                "src/add.rs",
+                "pub fn add(a: i32, b: i32) -> i32 {\n    a + b\n}\n",
+            ),
+            (
+                "src/sub.rs",
+                "pub fn sub(a: i32, b: i32) -> i32 {\n    a - b\n}\n",
+            ),
+            (
+                "src/mul.rs",
+                "pub fn mul(a: i32, b: i32) -> i32 {\n    a * b\n}\n",
+            ),
+        ],
+    );
+    (path, first, second)
+}
+
+#[tokio::test(flavor = "current_thread")]
+async fn test_stream_yields_every_changed_file() {
+    let (path, first, second) = init_local_repo();
+    let options = AnalysisOptions {
+        dry_run: true,
+        concurrency: Some(2),
+        ..Default::default()
+    };
+
+    let stream = analyze_repository_changes_stream(
+        &path,
+        &second,
+        &path,
+        &first,
+        &second,
+        "Implement add() in src/add.rs",
+        "unused",
+        None,
+        None,
+        &options,
+    )
+    .await
+    .expect("Should read the local repository");
+    let results: Vec<FileAnalysisResult> = stream.collect().await;
+
+    let mut paths: Vec<&str> = results
+        .iter()
+        .map(|r| r.analysis.file_path.as_str())
+        .collect();
+    paths.sort();
+    println!("\n📡 Streamed {} file(s): {:?}", results.len(), paths);
+    assert_eq!(paths, vec!["src/add.rs", "src/mul.rs", "src/sub.rs"]);
+    for result in &results {
+        assert!(
+            result
+                .prompts
+                .iter()
+                .all(|p| p.stage == PromptStage::FileAnalysis),
+            "Dry runs should return each file's prompts with its result"
+        );
+        assert!(!result.prompts.is_empty());
+    }
+
+    let _ = std::fs::remove_dir_all(&path);
+}
+
+#[tokio::test(flavor = "current_thread")]
+async fn test_stream_attaches_setup_warnings_to_the_first_result() {
+    let (path, first, second) = init_local_repo();
+    // Nothing listens on port 9, so the model is unreachable
+    let options = AnalysisOptions {
+        timeout_secs: Some(5),
+        ..Default::default()
+    };
+
+    let stream = analyze_repository_changes_stream(
+        &path,
+        &second,
+        &path,
+        &first,
+        &second,
+        "Make the calculator handle arithmetic",
+        "unused",
+        None,
+        Some("http://127.0.0.1:9"),
+        &options,
+    )
+    .await
+    .expect("Unreachable models shouldn't prevent streaming");
+    let results: Vec<FileAnalysisResult> = stream.collect().await;
+
+    assert_eq!(results.len(), 3);
+    let extraction_warnings: Vec<usize> = results
+        .iter()
+        .map(|r| {
+            r.warnings
+                .iter()
+                .filter(|w| matches!(w.kind, WarningKind::TargetExtraction))
+                .count()
+        })
+        .collect();
+    println!(
+        "\n⚠️ Target extraction warnings per result: {:?}",
+        extraction_warnings
+    );
+    assert_eq!(extraction_warnings, vec![1, 0, 0]);
+    for result in &results {
+        assert!(!result.analysis.supports_intent);
+        assert!(
+            result
+                .warnings
+                .iter()
+                .any(|w| matches!(w.kind, WarningKind::FileAnalysis)),
+            "Each file should report its own failed analysis"
+        );
+    }
+
+    let _ = std::fs::remove_dir_all(&path);
+}
+
+#[tokio::test(flavor = "current_thread")]
+async fn test_stream_fails_when_changes_cannot_be_read() {
+    let options = AnalysisOptions {
+        dry_run: true,
+        ..Default::default()
+    };
+    let outcome = analyze_repository_changes_stream(
+        "/tmp/stream_test_missing_repo",
+        "HEAD",
+        "/tmp/stream_test_missing_repo",
+        "HEAD~1",
+        "HEAD",
+        "Implement add()",
+        "unused",
+        None,
+        None,
+        &options,
+    )
+    .await;
+    assert!(
+        outcome.is_err(),
+        "A missing repository should fail before streaming"
+    );
+}
@@ -0,0 +1,137 @@
+use intent_verification::{
+    AnalysisOptions, IntentVerificationResult, LlmClient, MockProvider, RepoSnapshot,
+    StrongerModelConfig, WarningKind, render_markdown, verify_intent_with_snapshots,
+};
+
+fn snapshots() -> (RepoSnapshot, RepoSnapshot) {
+    let base = RepoSnapshot::from_files([
+        (
+            "src/lib.rs",
+            "pub fn sum(a: i32, b: i32) -> i32 {\n    todo!()\n}\n",
+        ),
+        ("src/util.rs", "pub fn noop() {}\n"),
+    ]);
+    let head = RepoSnapshot::from_files([
+        (
+            "src/lib.rs",
+            "pub fn sum(a: i32, b: i32) -> i32 {\n    a + b\n}\n",
+        ),
+        ("src/util.rs", "pub fn noop() {\n    // tidied\n}\n"),
+    ]);
+    (base, head)
+}
+
+/// The stronger model answers unless `overloaded`
+fn mock(util_supports: bool, overloaded: bool) -> MockProvider {
+    let mock = if overloaded {
+        MockProvider::new().fail_when("A first review concluded", "model overloaded")
+    } else {
+        MockProvider::new()
+    };
+    mock.respond_when(
+            "Extract from the following prompt",
+            r#"{"functions": ["sum"], "files": []}"#,
+        )
+        .respond_when(
+            "SOLUTION FILE: src/lib.rs",
+            r#"{"supports_intent": true, "reasoning": "implements sum", "relevant_changes": []}"#,
+        )
+        .respond_when(
+            "SOLUTION FILE: src/util.rs",
+            &format!(
+                r#"{{"supports_intent": {}, "reasoning": "a comment", "relevant_changes": []}}"#,
+                util_supports
+            ),
+        )
+        .respond_when(
+            "A first review concluded",
+            r#"{"is_intent_fulfilled": true, "confidence": 0.92, "assessment": "sum is implemented; util is unrelated"}"#,
+        )
+        .respond("The changes look partly relevant.")
+}
+
+async fn verify(mock: &MockProvider) -> IntentVerificationResult {
+    let (base, head) = snapshots();
+    let options = AnalysisOptions {
+        llm_client: Some(LlmClient::mock(mock.clone())),
+        stronger_model: Some(StrongerModelConfig {
+            model: "strong-model".to_string(),
+            ..Default::default()
+        }),
+        ..Default::default()
+    };
+    verify_intent_with_snapshots(
+        &head,
+        &base,
+        &head,
+        "The sum function adds two numbers",
+        "",
+        None,
+        None,
+        &options,
+    )
+    .await
+    .unwrap()
+}
+
+#[tokio::test(flavor = "current_thread")]
+async fn test_low_confidence_is_rejudged_by_the_stronger_model() {
+    let mock = mock(false, false);
+    let result = verify(&mock).await;
+
+    let retry = result.model_retry.as_ref().expect("verdict was re-judged");
+    println!("\n🔁 Model retry: {:#?}", retry);
+    assert!(retry.reasons[0].contains("below the 75% threshold"));
+    assert_eq!(retry.initial.confidence, 0.65);
+    assert_eq!(
+        retry.initial.assessment,
+        "The changes look partly relevant."
+    );
+    assert_eq!(retry.retried.model, "strong-model");
+    assert!(result.is_intent_fulfilled);
+    assert_eq!(result.confidence, 0.92);
+    assert_eq!(
+        result.overall_assessment,
+        "sum is implemented; util is unrelated"
+    );
+    assert!(result.explanation.ends_with("re-judged by strong-model"));
+
+    let retried: Vec<_> = mock
+        .calls()
+        .into_iter()
+        .filter(|call| call.model == "strong-model")
+        .collect();
+    assert_eq!(retried.len(), 1, "Only the final judgment is re-run");
+    assert!(
+        retried[0]
+            .prompt()
+            .contains("FULFILLED with 65% confidence")
+    );
+    assert!(render_markdown(&result).contains("_Re-judged by strong-model"));
+}
+
+#[tokio::test(flavor = "current_thread")]
+async fn test_confident_verdicts_are_not_rejudged() {
+    let mock = mock(true, false);
+    let result = verify(&mock).await;
+
+    assert!(result.model_retry.is_none());
+    assert_eq!(result.confidence, 1.0);
+    assert!(mock.calls().iter().all(|call| call.model != "strong-model"));
+}
+
+#[tokio::test(flavor = "current_thread")]
+async fn test_failed_retry_keeps_the_first_verdict() {
+    let mock = mock(false, true);
+    let result = verify(&mock).await;
+
+    assert!(result.model_retry.is_none());
+    assert_eq!(result.confidence, 0.65);
+    assert!(result.is_partial);
+    assert!(
+        result
+            .warnings
+            .iter()
+            .any(|w| w.kind == WarningKind::StrongerModel && w.message.contains("overloaded"))
+    );
+}
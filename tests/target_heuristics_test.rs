@@ -0,0 +1,145 @@
+#![cfg(feature = "git")]
+
+mod common;
+
+use intent_verification::{
+    AnalysisOptions, PromptStage, WarningKind, extract_test_targets_heuristically,
+    extract_test_targets_with_ai, verify_intent_with_options,
+};
+
+/// Create a local repository with a stub commit and an implementation commit
+fn init_local_repo() -> (String, String, String) {
+    common::init_repo_with(
+        &common::unique_path("target_heuristics_test_repo"),
+        "src/lib.rs",
+        "pub fn calculate_sum(a: i32, b: i32) -> i32 {\n    todo!()\n}\n",
+        "pub fn calculate_sum(a: i32, b: i32) -> i32 {\n    a + b\n}\n",
+    )
+}
+
+#[test]
+fn test_literal_targets() {
+    let heuristic = extract_test_targets_heuristically(
+        "Fix `parse_header` in src/http/parser.rs so Client::send() and retryCount(3) work, \
+         see tests/http_test.rs (e.g. if(headers) is empty)",
+    );
+    println!("\n🎯 {:#?}", heuristic);
+    assert_eq!(
+        heuristic.targets.files,
+        vec!["src/http/parser.rs", "tests/http_test.rs"]
+    );
+    assert_eq!(
+        heuristic.targets.functions,
+        vec!["send", "retryCount", "parse_header"]
+    );
+    assert!(heuristic.ambiguous.is_empty());
+    assert!(heuristic.is_conclusive());
+
+    let heuristic = extract_test_targets_heuristically("def load_config should accept YAML");
+    assert_eq!(heuristic.targets.functions, vec!["load_config"]);
+    assert!(heuristic.is_conclusive());
+}
+
+#[test]
+fn test_ambiguous_prompts_need_the_model() {
+    let heuristic =
+        extract_test_targets_heuristically("I want to test the calculate_sum in math.rs");
+    assert_eq!(heuristic.targets.files, vec!["math.rs"]);
+    assert!(heuristic.targets.functions.is_empty());
+    assert_eq!(heuristic.ambiguous, vec!["calculate_sum"]);
+    assert!(
+        !heuristic.is_conclusive(),
+        "calculate_sum may be a function, a module or a test"
+    );
+
+    let heuristic = extract_test_targets_heuristically("The sum function should add two numbers");
+    assert!(heuristic.targets.functions.is_empty() && heuristic.targets.files.is_empty());
+    assert!(
+        !heuristic.is_conclusive(),
+        "Prompts naming nothing are left to the model"
+    );
+}
+
+#[tokio::test]
+async fn test_literal_prompts_skip_the_model() {
+    // Any request to this endpoint would fail
+    let targets = extract_test_targets_with_ai(
+        "Make calculate_sum() in src/lib.rs add its arguments",
+        "test-key",
+        None,
+        Some("http://127.0.0.1:9"),
+    )
+    .await
+    .expect("No request should be needed");
+    assert_eq!(targets.functions, vec!["calculate_sum"]);
+    assert_eq!(targets.files, vec!["src/lib.rs"]);
+
+    let (path, first, second) = init_local_repo();
+    let result = verify_intent_with_options(
+        &path,
+        &second,
+        &path,
+        &first,
+        &second,
+        "Make calculate_sum() in src/lib.rs add its arguments",
+        "test-key",
+        None,
+        Some("http://127.0.0.1:9"),
+        &AnalysisOptions {
+            dry_run: true,
+            ..Default::default()
+        },
+    )
+    .await
+    .expect("Dry run should succeed without the model");
+    assert!(
+        result
+            .prompts
+            .iter()
+            .all(|p| p.stage != PromptStage::TargetExtraction),
+        "The targets were named literally, so no extraction prompt is needed"
+    );
+    assert!(
+        result.coverage_evidence.is_some(),
+        "The target code should have been read"
+    );
+
+    std::fs::remove_dir_all(&path).ok();
+}
+
+#[tokio::test]
+async fn test_offline_fallback() {
+    let (path, first, second) = init_local_repo();
+    let result = verify_intent_with_options(
+        &path,
+        &second,
+        &path,
+        &first,
+        &second,
+        "The calculate_sum helper in src/lib.rs should add two numbers",
+        "test-key",
+        None,
+        Some("http://127.0.0.1:9"),
+        &AnalysisOptions::default(),
+    )
+    .await
+    .expect("Verification should finish with warnings");
+
+    let warning = result
+        .warnings
+        .iter()
+        .find(|w| w.kind == WarningKind::TargetExtraction)
+        .expect("The model was unreachable");
+    println!("\n⚠️  {}", warning.message);
+    assert!(
+        warning
+            .message
+            .ends_with("using the 1 target(s) named in the intent")
+    );
+    assert!(
+        result.coverage_evidence.is_some(),
+        "The file named in the intent should have been read"
+    );
+
+    std::fs::remove_dir_all(&path).ok();
+}
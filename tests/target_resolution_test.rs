@@ -0,0 +1,104 @@
+use intent_verification::{
+    AnalysisOptions, IntentVerificationResult, LlmClient, MockProvider, RepoSnapshot, TargetKind,
+    TargetStatus, TestTargets, TestTargetsWithCode, render_markdown, target_resolution,
+    verify_intent_with_snapshots,
+};
+
+#[tokio::test(flavor = "current_thread")]
+async fn test_result_reports_target_resolution() {
+    let base = RepoSnapshot::from_files([(
+        "src/lib.rs",
+        "pub fn sum(a: i32, b: i32) -> i32 {\n    todo!()\n}\n",
+    )]);
+    let head = RepoSnapshot::from_files([(
+        "src/lib.rs",
+        "// Arithmetic\n\npub fn sum(a: i32, b: i32) -> i32 {\n    a + b\n}\n",
+    )]);
+    let mock = MockProvider::new()
+        .respond_when(
+            "Extract from the following prompt",
+            r#"{"functions": ["sum", "total"], "files": ["src/lib.rs", "src/total.rs"], "endpoints": ["GET /sum"]}"#,
+        )
+        .respond_when(
+            "STEP 2",
+            r#"{"supports_intent": false, "reasoning": "no total", "relevant_changes": []}"#,
+        )
+        .respond("total is missing.");
+    let options = AnalysisOptions {
+        llm_client: Some(LlmClient::mock(mock)),
+        ..Default::default()
+    };
+
+    let result = verify_intent_with_snapshots(
+        &head,
+        &base,
+        &head,
+        "sum and total add numbers, served at GET /sum",
+        "",
+        None,
+        None,
+        &options,
+    )
+    .await
+    .unwrap();
+
+    println!("\n🎯 Target resolution: {:#?}", result.target_resolution);
+    let statuses: Vec<(&str, TargetKind, TargetStatus)> = result
+        .target_resolution
+        .iter()
+        .map(|target| (target.name.as_str(), target.kind, target.status))
+        .collect();
+    assert_eq!(
+        statuses,
+        vec![
+            ("sum", TargetKind::Function, TargetStatus::Resolved),
+            ("total", TargetKind::Function, TargetStatus::NotFound),
+            ("src/lib.rs", TargetKind::File, TargetStatus::Resolved),
+            ("src/total.rs", TargetKind::File, TargetStatus::NotFound),
+            ("GET /sum", TargetKind::Endpoint, TargetStatus::NotLookedUp),
+        ]
+    );
+    assert_eq!(
+        result.target_resolution[0].file_path.as_deref(),
+        Some("src/lib.rs")
+    );
+    assert_eq!(result.target_resolution[0].line, Some(3));
+
+    let md = render_markdown(&result);
+    assert!(
+        md.contains("2 of 5 targets weren't found in the test repository: `total`, `src/total.rs`")
+    );
+    assert!(md.contains("| `sum` | function | resolved | `src/lib.rs:3` |"));
+    assert!(md.contains("| `GET /sum` | endpoint | not looked up | - |"));
+}
+
+#[test]
+fn test_unread_targets_are_lookup_failures() {
+    let resolution = target_resolution(&TestTargetsWithCode {
+        targets: TestTargets {
+            functions: vec!["sum".to_string()],
+            files: vec!["src/lib.rs".to_string()],
+            ..Default::default()
+        },
+        file_contents: vec![],
+        function_contents: vec![],
+        tested_sources: vec![],
+    });
+
+    assert!(
+        resolution
+            .iter()
+            .all(|target| target.status == TargetStatus::LookupFailed)
+    );
+    assert!(resolution.iter().all(|target| target.is_unresolved()));
+}
+
+#[test]
+fn test_empty_target_resolution_is_not_serialized() {
+    let json = serde_json::to_value(IntentVerificationResult::default()).unwrap();
+
+    assert!(
+        json.get("target_resolution").is_none(),
+        "Results without targets should serialize like before the field existed"
+    );
+}
@@ -0,0 +1,194 @@
+use intent_verification::{
+    AnalysisOptions, LlmClient, MockProvider, RepoSnapshot, TestTargets, TestedSource,
+    verify_intent_with_snapshots,
+};
+
+fn targets(files: &[&str], functions: &[&str]) -> TestTargets {
+    TestTargets {
+        functions: functions.iter().map(|f| f.to_string()).collect(),
+        files: files.iter().map(|f| f.to_string()).collect(),
+        ..Default::default()
+    }
+}
+
+fn summary(sources: &[TestedSource]) -> Vec<(&str, Vec<&str>, Vec<&str>)> {
+    sources
+        .iter()
+        .map(|source| {
+            (
+                source.path.as_str(),
+                source.tested_by.iter().map(String::as_str).collect(),
+                source.functions.iter().map(String::as_str).collect(),
+            )
+        })
+        .collect()
+}
+
+#[test]
+fn test_rust_imports() {
+    let repo = RepoSnapshot::from_files([
+        (
+            "src/lib.rs",
+            "pub mod math;\npub fn version() -> u32 {\n    1\n}\n",
+        ),
+        (
+            "src/math.rs",
+            "pub fn sum(a: i32, b: i32) -> i32 {\n    a + b\n}\n\npub fn product(a: i32, b: i32) -> i32 {\n    a * b\n}\n",
+        ),
+        (
+            "src/geometry/mod.rs",
+            "pub fn area(w: u32, h: u32) -> u32 {\n    w * h\n}\n",
+        ),
+        (
+            "tests/math_test.rs",
+            "use calc::math::{sum, product as times};\nuse calc::geometry::area;\nuse serde_json::Value;\nuse std::fmt;\n\n#[test]\nfn test_sum() {\n    assert_eq!(sum(1, 2), 3);\n    assert_eq!(times(2, 3), 6);\n    assert_eq!(area(2, 2), 4);\n}\n",
+        ),
+    ]);
+
+    let with_code = repo.read_test_targets_code(&targets(&["tests/math_test.rs"], &[]));
+    println!(
+        "\n🧭 Tested sources: {:#?}",
+        summary(&with_code.tested_sources)
+    );
+    assert_eq!(
+        summary(&with_code.tested_sources),
+        vec![
+            ("src/math.rs", vec!["tests/math_test.rs"], vec!["sum"]),
+            (
+                "src/geometry/mod.rs",
+                vec!["tests/math_test.rs"],
+                vec!["area"]
+            ),
+        ],
+        "lib.rs, which no test calls into, isn't added for the serde_json import"
+    );
+    assert!(with_code.tested_sources[0].content.contains("a + b"));
+}
+
+#[test]
+fn test_unit_tests_map_to_their_parent_module() {
+    let repo = RepoSnapshot::from_files([
+        (
+            "src/parser.rs",
+            "mod tests;\npub fn parse(s: &str) -> u32 {\n    s.len() as u32\n}\n",
+        ),
+        (
+            "src/parser/tests.rs",
+            "use super::*;\n\n#[test]\nfn test_parse() {\n    assert_eq!(parse(\"ab\"), 2);\n}\n",
+        ),
+    ]);
+
+    let with_code = repo.read_test_targets_code(&targets(&[], &["test_parse"]));
+    assert_eq!(
+        summary(&with_code.tested_sources),
+        vec![("src/parser.rs", vec!["src/parser/tests.rs"], vec!["parse"])],
+        "Test function targets map through the file they were found in"
+    );
+}
+
+#[test]
+fn test_python_and_javascript_imports() {
+    let repo = RepoSnapshot::from_files([
+        (
+            "app/pricing.py",
+            "def total(items):\n    return sum(items)\n",
+        ),
+        (
+            "app/tax/__init__.py",
+            "def vat(amount):\n    return amount * 0.2\n",
+        ),
+        (
+            "tests/test_pricing.py",
+            "from app.pricing import total\nimport app.tax\n\ndef test_total():\n    assert total([1, 2]) == 3\n    assert app.tax.vat(10) == 2\n",
+        ),
+        (
+            "web/src/cart.ts",
+            "export function addItem(cart: string[], item: string) {\n  return [...cart, item];\n}\n",
+        ),
+        (
+            "web/test/cart.test.ts",
+            "import { addItem } from '../src/cart';\n\ntest('adds', () => {\n  expect(addItem([], 'a')).toEqual(['a']);\n});\n",
+        ),
+    ]);
+
+    let with_code = repo.read_test_targets_code(&targets(
+        &["tests/test_pricing.py", "web/test/cart.test.ts"],
+        &[],
+    ));
+    assert_eq!(
+        summary(&with_code.tested_sources),
+        vec![
+            (
+                "app/pricing.py",
+                vec!["tests/test_pricing.py"],
+                vec!["total"]
+            ),
+            (
+                "app/tax/__init__.py",
+                vec!["tests/test_pricing.py"],
+                vec!["vat"]
+            ),
+            (
+                "web/src/cart.ts",
+                vec!["web/test/cart.test.ts"],
+                vec!["addItem"]
+            ),
+        ]
+    );
+}
+
+#[tokio::test(flavor = "current_thread")]
+async fn test_tested_sources_are_in_the_context() {
+    let base = RepoSnapshot::from_files([
+        (
+            "src/math.rs",
+            "pub fn sum(a: i32, b: i32) -> i32 {\n    todo!()\n}\n",
+        ),
+        (
+            "tests/sum_tests.rs",
+            "use calc::math::sum;\n\n#[test]\nfn adds() {\n    assert_eq!(sum(2, 3), 5);\n}\n",
+        ),
+    ]);
+    let head = RepoSnapshot::from_files([
+        (
+            "src/math.rs",
+            "pub fn sum(a: i32, b: i32) -> i32 {\n    a + b\n}\n",
+        ),
+        (
+            "tests/sum_tests.rs",
+            "use calc::math::sum;\n\n#[test]\nfn adds() {\n    assert_eq!(sum(2, 3), 5);\n}\n",
+        ),
+    ]);
+    let mock = MockProvider::new()
+        .respond_when(
+            "STEP 2",
+            r#"{"supports_intent": true, "reasoning": "adds", "relevant_changes": []}"#,
+        )
+        .respond("Assessment");
+    let options = AnalysisOptions {
+        llm_client: Some(LlmClient::mock(mock.clone())),
+        ..Default::default()
+    };
+
+    verify_intent_with_snapshots(
+        &head,
+        &base,
+        &head,
+        "The tests in tests/sum_tests.rs pass",
+        "",
+        None,
+        None,
+        &options,
+    )
+    .await
+    .unwrap();
+
+    let analysis = mock
+        .calls()
+        .into_iter()
+        .find(|call| call.prompt().contains("SOLUTION FILE: src/math.rs"))
+        .unwrap();
+    assert!(analysis.prompt().contains(
+        "- File 'src/math.rs' (imported by tests/sum_tests.rs; calls sum):\n```\npub fn sum(a: i32, b: i32) -> i32 {\n    a + b\n}"
+    ));
+}
@@ -0,0 +1,194 @@
+#![cfg(feature = "git")]
+
+mod common;
+
+use std::collections::HashMap;
+use std::sync::atomic::{AtomicUsize, Ordering};
+use std::sync::{Arc, Mutex};
+
+use intent_verification::{AnalysisOptions, verify_intent_with_options};
+use tracing::field::{Field, Visit};
+use tracing::span::{Attributes, Id, Record};
+use tracing_subscriber::layer::{Context, Layer, SubscriberExt};
+
+/// Name and fields of a span
+type RecordedSpan = (String, HashMap<String, String>);
+
+/// Name and recorded fields of every span opened
+#[derive(Clone, Default)]
+struct SpanRecorder {
+    spans: Arc<Mutex<Vec<RecordedSpan>>>,
+    ids: Arc<Mutex<HashMap<u64, usize>>>,
+}
+
+struct FieldVisitor<'a>(&'a mut HashMap<String, String>);
+
+impl Visit for FieldVisitor<'_> {
+    fn record_debug(&mut self, field: &Field, value: &dyn std::fmt::Debug) {
+        self.0.insert(
+            field.name().to_string(),
+            format!("{:?}", value).trim_matches('"').to_string(),
+        );
+    }
+}
+
+impl<S: tracing::Subscriber> Layer<S> for SpanRecorder {
+    fn on_new_span(&self, attrs: &Attributes<'_>, id: &Id, _ctx: Context<'_, S>) {
+        let mut fields = HashMap::new();
+        attrs.record(&mut FieldVisitor(&mut fields));
+        let mut spans = self.spans.lock().unwrap();
+        spans.push((attrs.metadata().name().to_string(), fields));
+        self.ids
+            .lock()
+            .unwrap()
+            .insert(id.into_u64(), spans.len() - 1);
+    }
+
+    fn on_record(&self, id: &Id, values: &Record<'_>, _ctx: Context<'_, S>) {
+        if let Some(&index) = self.ids.lock().unwrap().get(&id.into_u64()) {
+            values.record(&mut FieldVisitor(&mut self.spans.lock().unwrap()[index].1));
+        }
+    }
+}
+
+/// Serve chat completions on a local port, answering every request with the same JSON and
+/// reporting token usage
+fn start_model() -> (String, Arc<AtomicUsize>) {
+    use std::io::{Read, Write};
+
+    let listener = std::net::TcpListener::bind("127.0.0.1:0").unwrap();
+    let url = format!("http://{}/v1", listener.local_addr().unwrap());
+    let requests = Arc::new(AtomicUsize::new(0));
+    let counter = requests.clone();
+
+    let content = serde_json::json!({
+        "functions": ["sum"],
+        "files": ["src/lib.rs"],
+        "supports_intent": true,
+        "relevance": "required",
+        "reasoning": "sum now adds its arguments",
+        "relevant_changes": ["a + b"],
+        "locations": [],
+        "confidence": 0.9
+    })
+    .to_string();
+    let body = serde_json::json!({
+        "id": "chatcmpl-test",
+        "object": "chat.completion",
+        "created": 0,
+        "model": "test-model",
+        "choices": [{
+            "index": 0,
+            "message": {"role": "assistant", "content": content},
+            "finish_reason": "stop"
+        }],
+        "usage": {"prompt_tokens": 42, "completion_tokens": 7, "total_tokens": 49}
+    })
+    .to_string();
+
+    std::thread::spawn(move || {
+        for stream in listener.incoming() {
+            let mut stream = stream.unwrap();
+            let mut request = Vec::new();
+            let mut buffer = [0u8; 4096];
+            loop {
+                let n = stream.read(&mut buffer).unwrap();
+                request.extend_from_slice(&buffer[..n]);
+                let text = String::from_utf8_lossy(&request).to_string();
+                if let Some((headers, rest)) = text.split_once("\r\n\r\n") {
+                    let length = headers
+                        .lines()
+                        .find_map(|line| {
+                            line.to_lowercase()
+                                .strip_prefix("content-length:")
+                                .map(|v| v.trim().parse::<usize>().unwrap())
+                        })
+                        .unwrap_or(0);
+                    if rest.len() >= length || n == 0 {
+                        break;
+                    }
+                }
+            }
+            counter.fetch_add(1, Ordering::SeqCst);
+            let response = format!(
+                "HTTP/1.1 200 OK\r\ncontent-type: application/json\r\nconnection: close\r\ncontent-length: {}\r\n\r\n{}",
+                body.len(),
+                body
+            );
+            stream.write_all(response.as_bytes()).unwrap();
+        }
+    });
+
+    (url, requests)
+}
+
+#[tokio::test(flavor = "current_thread")]
+async fn test_verification_spans() {
+    let (path, first, second) = common::init_local_repo();
+    let (url, requests) = start_model();
+    let recorder = SpanRecorder::default();
+    let _subscriber =
+        tracing::subscriber::set_default(tracing_subscriber::registry().with(recorder.clone()));
+
+    let options = AnalysisOptions {
+        timeout_secs: Some(10),
+        ..Default::default()
+    };
+    verify_intent_with_options(
+        &path,
+        &second,
+        &path,
+        &first,
+        &second,
+        "Make the calculator add numbers",
+        "test-key",
+        Some("test-model"),
+        Some(&url),
+        &options,
+    )
+    .await
+    .expect("Verification against the local model should succeed");
+
+    let spans = recorder.spans.lock().unwrap().clone();
+    let names: Vec<&str> = spans.iter().map(|(name, _)| name.as_str()).collect();
+    println!("\n🔭 Spans: {:?}", names);
+    let span = |name: &str| {
+        spans
+            .iter()
+            .find(|(n, _)| n == name)
+            .map(|(_, fields)| fields.clone())
+            .unwrap_or_else(|| panic!("Missing span {}", name))
+    };
+
+    for name in [
+        "verification",
+        "extract_targets",
+        "read_targets",
+        "clone",
+        "assess",
+    ] {
+        span(name);
+    }
+    assert_eq!(span("verification")["model"], "test-model");
+    assert_eq!(span("diff")["repo"], path);
+    assert_eq!(
+        span("diff")["files"],
+        "1",
+        "The diff span should count the changed files"
+    );
+    assert_eq!(span("analyze_file")["file"], "src/lib.rs");
+
+    let llm_requests: Vec<_> = spans.iter().filter(|(n, _)| n == "llm_request").collect();
+    assert_eq!(llm_requests.len(), requests.load(Ordering::SeqCst));
+    let file_request = llm_requests
+        .iter()
+        .map(|(_, fields)| fields)
+        .find(|fields| fields.get("file").map(String::as_str) == Some("src/lib.rs"))
+        .expect("The file analysis request should name its file");
+    assert_eq!(file_request["model"], "test-model");
+    assert_eq!(file_request["prompt_tokens"], "42");
+    assert_eq!(file_request["completion_tokens"], "7");
+    assert!(file_request.contains_key("latency_ms"));
+
+    let _ = std::fs::remove_dir_all(&path);
+}
@@ -0,0 +1,235 @@
+#![cfg(feature = "ffi")]
+
+mod common;
+
+use intent_verification::{
+    AnalysisOptions, IvErrorCode, ValidationError, iv_last_error_code, validate_api_key,
+    validate_commit, validate_intent, validate_repo_url, verify_intent_c,
+    verify_intent_with_options,
+};
+use std::ffi::CString;
+
+#[test]
+fn test_validate_commit() {
+    for commit in [
+        "2fd75de38547b530ea18cbe86d47c5f7e9817265",
+        "2fd75de",
+        "HEAD",
+        "HEAD~1",
+        "main",
+        "feature/validation",
+        "v1.2.0",
+    ] {
+        assert!(
+            validate_commit("commit1", commit).is_ok(),
+            "{} should be accepted",
+            commit
+        );
+    }
+
+    for commit in [
+        "",
+        "abc def",
+        "--upload-pack=evil",
+        "main..feature",
+        "2fd75de38547b530ea18cbe86d47c5f7e98172650",
+    ] {
+        let error = validate_commit("commit2", commit).unwrap_err();
+        assert!(
+            matches!(&error, ValidationError::InvalidCommit { name, .. } if name == "commit2"),
+            "{:?} should be rejected, got {:?}",
+            commit,
+            error
+        );
+        println!("✅ {}", error);
+    }
+}
+
+#[test]
+fn test_validate_repo_url() {
+    let existing = std::env::temp_dir();
+    for url in [
+        "https://github.com/VAR-META-Tech/intent-verification",
+        "ssh://git@github.com/org/repo.git",
+        "git@github.com:org/repo.git",
+        existing.to_str().unwrap(),
+    ] {
+        assert!(validate_repo_url(url).is_ok(), "{} should be accepted", url);
+    }
+
+    assert_eq!(
+        validate_repo_url("/nonexistent/intent-verification-repo"),
+        Err(ValidationError::RepoNotFound {
+            url: "/nonexistent/intent-verification-repo".to_string()
+        })
+    );
+    for url in ["", "  ", "ftp://example.com/repo", "https://", "not a repo"] {
+        let error = validate_repo_url(url).unwrap_err();
+        assert!(
+            matches!(error, ValidationError::InvalidRepoUrl { .. }),
+            "{:?} should be rejected, got {:?}",
+            url,
+            error
+        );
+        println!("✅ {}", error);
+    }
+}
+
+#[test]
+fn test_validate_intent_and_api_key() {
+    assert_eq!(validate_intent(" \n "), Err(ValidationError::EmptyIntent));
+    assert!(validate_intent("Implement sum()").is_ok());
+
+    assert_eq!(
+        validate_api_key("", None),
+        Err(ValidationError::MissingApiKey)
+    );
+    assert!(matches!(
+        validate_api_key("sk-abc def", None),
+        Err(ValidationError::InvalidApiKey { .. })
+    ));
+    assert!(validate_api_key("sk-abc", None).is_ok());
+    // Local servers often take any key
+    assert!(validate_api_key("", Some("http://localhost:8080/v1")).is_ok());
+}
+
+#[tokio::test(flavor = "current_thread")]
+async fn test_empty_intent_fails_before_cloning() {
+    // The repository doesn't exist, so reaching git would fail with a different error
+    let error = verify_intent_with_options(
+        "/nonexistent/intent-verification-repo",
+        "HEAD",
+        "/nonexistent/intent-verification-repo",
+        "HEAD~1",
+        "HEAD",
+        "   ",
+        "sk-unused",
+        None,
+        None,
+        &AnalysisOptions::default(),
+    )
+    .await
+    .unwrap_err();
+
+    assert_eq!(
+        error.downcast_ref::<ValidationError>(),
+        Some(&ValidationError::EmptyIntent)
+    );
+    assert_eq!(error.to_string(), "intent is empty");
+    println!("✅ {}", error);
+}
+
+#[tokio::test(flavor = "current_thread")]
+async fn test_missing_api_key_fails_before_cloning() {
+    let error = verify_intent_with_options(
+        "/nonexistent/intent-verification-repo",
+        "HEAD",
+        "https://github.com/VAR-META-Tech/intent-verification",
+        "HEAD~1",
+        "HEAD",
+        "Implement sum()",
+        "",
+        None,
+        None,
+        &AnalysisOptions::default(),
+    )
+    .await
+    .unwrap_err();
+
+    // The test repository is checked before the key
+    assert!(matches!(
+        error.downcast_ref::<ValidationError>(),
+        Some(ValidationError::RepoNotFound { .. })
+    ));
+
+    let (path, first, second) = common::init_local_repo();
+    let error = verify_intent_with_options(
+        &path,
+        &second,
+        &path,
+        &first,
+        &second,
+        "Implement sum()",
+        "",
+        None,
+        None,
+        &AnalysisOptions::default(),
+    )
+    .await
+    .unwrap_err();
+    assert_eq!(
+        error.downcast_ref::<ValidationError>(),
+        Some(&ValidationError::MissingApiKey)
+    );
+
+    let _ = std::fs::remove_dir_all(&path);
+}
+
+#[tokio::test(flavor = "current_thread")]
+async fn test_unknown_commit_is_named() {
+    let (path, first, _) = common::init_local_repo();
+    let unknown = "0000000000000000000000000000000000000001";
+
+    let error = verify_intent_with_options(
+        &path,
+        &first,
+        &path,
+        &first,
+        unknown,
+        "Implement sum()",
+        "",
+        None,
+        None,
+        &AnalysisOptions {
+            dry_run: true,
+            ..Default::default()
+        },
+    )
+    .await
+    .unwrap_err();
+
+    assert_eq!(
+        error.downcast_ref::<ValidationError>(),
+        Some(&ValidationError::CommitNotFound {
+            name: "commit2".to_string(),
+            commit: unknown.to_string(),
+            repo_url: path.clone(),
+        })
+    );
+    assert!(
+        error.to_string().starts_with("commit2 '0000000"),
+        "The message should name the argument: {}",
+        error
+    );
+    println!("✅ {}", error);
+
+    let _ = std::fs::remove_dir_all(&path);
+}
+
+#[test]
+fn test_ffi_reports_invalid_input() {
+    let (path, first, second) = common::init_local_repo();
+    let repo = CString::new(path.clone()).unwrap();
+    let first = CString::new(first).unwrap();
+    let second = CString::new(second).unwrap();
+    let intent = CString::new("").unwrap();
+    let api_key = CString::new("sk-invalid").unwrap();
+
+    let result = unsafe {
+        verify_intent_c(
+            repo.as_ptr(),
+            second.as_ptr(),
+            repo.as_ptr(),
+            first.as_ptr(),
+            second.as_ptr(),
+            intent.as_ptr(),
+            api_key.as_ptr(),
+            std::ptr::null(),
+            std::ptr::null(),
+        )
+    };
+    assert!(result.is_null(), "An empty intent should fail");
+    assert_eq!(iv_last_error_code(), IvErrorCode::InvalidInput);
+
+    let _ = std::fs::remove_dir_all(&path);
+}
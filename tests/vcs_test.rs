@@ -0,0 +1,203 @@
+use std::collections::BTreeMap;
+use std::error::Error;
+
+use intent_verification::{
+    AnalysisOptions, FileChange, LlmClient, MockProvider, RepoSnapshot, TestTargets,
+    VersionControl, read_test_targets_code_from, verify_intent_with_vcs,
+};
+
+/// A backend keeping each revision's files in memory, standing in for Mercurial or Perforce
+struct InMemoryVcs {
+    revisions: BTreeMap<&'static str, RepoSnapshot>,
+    listable: bool,
+}
+
+impl InMemoryVcs {
+    fn new(listable: bool) -> Self {
+        let base = RepoSnapshot::from_files([
+            (
+                "src/math.rs",
+                "pub fn sum(a: i32, b: i32) -> i32 {\n    todo!()\n}\n",
+            ),
+            (
+                "tests/math_test.rs",
+                "fn test_sum() {\n    assert_eq!(sum(2, 3), 5);\n}\n",
+            ),
+        ]);
+        let mut head = base.clone();
+        head.files.insert(
+            "src/math.rs".to_string(),
+            "pub fn sum(a: i32, b: i32) -> i32 {\n    a + b\n}\n".to_string(),
+        );
+        InMemoryVcs {
+            revisions: BTreeMap::from([("cl/1", base), ("cl/2", head)]),
+            listable,
+        }
+    }
+
+    fn revision(&self, rev: &str) -> Result<&RepoSnapshot, Box<dyn Error>> {
+        self.revisions
+            .get(rev)
+            .ok_or_else(|| format!("Unknown changelist {}", rev).into())
+    }
+}
+
+impl VersionControl for InMemoryVcs {
+    fn list_changes(&self, from: &str, to: &str) -> Result<Vec<FileChange>, Box<dyn Error>> {
+        Ok(self.revision(from)?.diff(self.revision(to)?))
+    }
+
+    fn read_file_at(&self, rev: &str, path: &str) -> Result<Option<String>, Box<dyn Error>> {
+        Ok(self.revision(rev)?.get(path).map(str::to_string))
+    }
+
+    fn resolve_rev(&self, rev: &str) -> Result<String, Box<dyn Error>> {
+        self.revision(rev.trim_start_matches("//"))?;
+        Ok(rev.trim_start_matches("//").to_string())
+    }
+
+    fn list_files_at(&self, rev: &str) -> Result<Vec<String>, Box<dyn Error>> {
+        if !self.listable {
+            return Ok(vec![]);
+        }
+        Ok(self.revision(rev)?.files.keys().cloned().collect())
+    }
+}
+
+#[tokio::test(flavor = "current_thread")]
+async fn test_verify_through_a_custom_backend() {
+    let vcs = InMemoryVcs::new(true);
+    let mock = MockProvider::new()
+        .respond_when(
+            "Extract from the following prompt",
+            r#"{"functions": ["test_sum"], "files": []}"#,
+        )
+        .respond_when(
+            "STEP 2",
+            r#"{"supports_intent": true, "reasoning": "adds", "relevant_changes": []}"#,
+        )
+        .respond("Assessment");
+    let options = AnalysisOptions {
+        llm_client: Some(LlmClient::mock(mock.clone())),
+        ..Default::default()
+    };
+
+    let result = verify_intent_with_vcs(
+        &vcs,
+        "//cl/2",
+        &vcs,
+        "cl/1",
+        "cl/2",
+        "test_sum passes",
+        "",
+        None,
+        None,
+        &options,
+    )
+    .await
+    .unwrap();
+
+    assert!(result.is_intent_fulfilled);
+    assert_eq!(result.files_analyzed.len(), 1);
+    assert_eq!(result.files_analyzed[0].file_path, "src/math.rs");
+    assert_eq!(
+        result.target_resolution[0].file_path.as_deref(),
+        Some("tests/math_test.rs")
+    );
+    assert!(
+        mock.calls()
+            .iter()
+            .any(|call| call.prompt().contains("assert_eq!(sum(2, 3), 5)")),
+        "The test function read through the backend is in the context"
+    );
+
+    let missing = verify_intent_with_vcs(
+        &vcs,
+        "cl/2",
+        &vcs,
+        "cl/1",
+        "cl/9",
+        "test_sum passes",
+        "",
+        None,
+        None,
+        &options,
+    )
+    .await;
+    assert!(
+        missing
+            .unwrap_err()
+            .to_string()
+            .contains("Unknown changelist cl/9")
+    );
+}
+
+#[test]
+fn test_backends_that_cant_list_files_read_file_targets_only() {
+    let vcs = InMemoryVcs::new(false);
+    let targets = TestTargets {
+        functions: vec!["test_sum".to_string()],
+        files: vec!["tests/math_test.rs".to_string(), "README.md".to_string()],
+        ..Default::default()
+    };
+
+    let with_code = read_test_targets_code_from(&vcs, &targets, "cl/2").unwrap();
+    assert!(with_code.file_contents[0].error.is_none());
+    assert_eq!(
+        with_code.file_contents[1].error.as_deref(),
+        Some("File not found in revision cl/2")
+    );
+    assert!(with_code.function_contents[0].content.is_none());
+}
+
+#[cfg(feature = "git")]
+#[test]
+fn test_git_backend() {
+    use intent_verification::GitVersionControl;
+
+    let path = format!(
+        "/tmp/vcs_test_repo_{}_{}",
+        std::process::id(),
+        std::time::SystemTime::now()
+            .duration_since(std::time::UNIX_EPOCH)
+            .unwrap()
+            .as_nanos()
+    );
+    let repo = git2::Repository::init(&path).unwrap();
+    let signature = git2::Signature::now("Test", "test@example.com").unwrap();
+    let commit = |file: &str, content: &str| {
+        let full_path = std::path::Path::new(&path).join(file);
+        std::fs::create_dir_all(full_path.parent().unwrap()).unwrap();
+        std::fs::write(full_path, content).unwrap();
+        let mut index = repo.index().unwrap();
+        index.add_path(std::path::Path::new(file)).unwrap();
+        let tree = repo.find_tree(index.write_tree().unwrap()).unwrap();
+        let parents = match repo.head() {
+            Ok(head) => vec![head.peel_to_commit().unwrap()],
+            Err(_) => vec![],
+        };
+        let parents: Vec<&git2::Commit> = parents.iter().collect();
+        repo.commit(Some("HEAD"), &signature, &signature, file, &tree, &parents)
+            .unwrap()
+            .to_string()
+    };
+    let first = commit("src/lib.rs", "pub fn sum() {}\n");
+    let second = commit("src/lib.rs", "pub fn sum() -> i32 {\n    2\n}\n");
+
+    let vcs = GitVersionControl::open(&path, &AnalysisOptions::default()).unwrap();
+    std::fs::remove_dir_all(&path).ok();
+
+    assert_eq!(vcs.resolve_rev("HEAD").unwrap(), second);
+    assert_eq!(
+        vcs.read_file_at(&first, "src/lib.rs").unwrap().as_deref(),
+        Some("pub fn sum() {}\n")
+    );
+    assert_eq!(vcs.read_file_at(&first, "missing.rs").unwrap(), None);
+    assert_eq!(vcs.read_file_at(&first, "src").unwrap(), None);
+    assert_eq!(vcs.list_files_at("HEAD").unwrap(), vec!["src/lib.rs"]);
+
+    let changes = vcs.list_changes(&first, &second).unwrap();
+    assert_eq!(changes.len(), 1);
+    assert!(changes[0].content.as_deref().unwrap().contains("2"));
+    assert!(vcs.resolve_rev("no-such-branch").is_err());
+}
@@ -0,0 +1,174 @@
+#![cfg(feature = "git")]
+
+mod common;
+
+use std::sync::Arc;
+use std::sync::atomic::{AtomicUsize, Ordering};
+
+use intent_verification::{
+    AnalysisOptions, CancellationToken, IntentVerifier, VerificationRequest, verify_intent,
+};
+
+#[test]
+fn test_builder_defaults_tests_to_the_changed_repo() {
+    let verifier = IntentVerifier::builder()
+        .repo("https://example.com/repo.git")
+        .commits("base", "head")
+        .intent("sum() adds two numbers")
+        .build()
+        .expect("Repository, commits and intent are enough");
+
+    println!("\n🧱 Request: {:?}", verifier.request());
+    assert_eq!(
+        verifier.request(),
+        &VerificationRequest::new(
+            "https://example.com/repo.git",
+            "base",
+            "head",
+            "sum() adds two numbers"
+        )
+    );
+
+    let verifier = IntentVerifier::builder()
+        .request(
+            VerificationRequest::new("https://example.com/repo.git", "base", "head", "intent")
+                .with_tests("https://example.com/tests.git", "tests-head"),
+        )
+        .build()
+        .unwrap();
+    assert_eq!(
+        verifier.request().test_repo_url,
+        "https://example.com/tests.git"
+    );
+    assert_eq!(verifier.request().test_commit, "tests-head");
+    assert_eq!(verifier.request().solution_commit2, "head");
+}
+
+#[test]
+fn test_builder_requires_repo_commits_and_intent() {
+    let missing_repo = IntentVerifier::builder()
+        .commits("base", "head")
+        .intent("intent")
+        .build();
+    let missing_commits = IntentVerifier::builder()
+        .repo("repo")
+        .intent("intent")
+        .build();
+    let missing_intent = IntentVerifier::builder()
+        .repo("repo")
+        .commits("base", "head")
+        .build();
+
+    for (case, outcome) in [
+        ("repository", missing_repo),
+        ("commits", missing_commits),
+        ("intent", missing_intent),
+    ] {
+        let error = outcome.expect_err(case).to_string();
+        println!("❌ Missing {}: {}", case, error);
+        assert!(error.contains(case), "{}", error);
+    }
+}
+
+#[test]
+fn test_builder_keeps_hooks_when_options_are_set() {
+    let token = CancellationToken::new();
+    let verifier = IntentVerifier::builder()
+        .repo("repo")
+        .commits("base", "head")
+        .intent("intent")
+        .cancellation(token.clone())
+        .on_progress(|_| {})
+        .options(AnalysisOptions {
+            dry_run: true,
+            ..Default::default()
+        })
+        .build()
+        .unwrap();
+
+    assert!(verifier.options().dry_run);
+    assert!(verifier.options().progress.is_some());
+    token.cancel();
+    assert!(
+        verifier
+            .options()
+            .cancellation
+            .as_ref()
+            .unwrap()
+            .is_cancelled(),
+        "The verifier should keep the caller's token"
+    );
+}
+
+#[tokio::test(flavor = "current_thread")]
+async fn test_verifier_runs_with_hooks() {
+    let (path, first, second) = common::init_local_repo();
+    let updates = Arc::new(AtomicUsize::new(0));
+    let counter = updates.clone();
+
+    let verifier = IntentVerifier::builder()
+        .repo(&path)
+        .commits(&first, &second)
+        .intent("Implement sum() in src/lib.rs")
+        .options(AnalysisOptions {
+            dry_run: true,
+            ..Default::default()
+        })
+        .on_progress(move |_| {
+            counter.fetch_add(1, Ordering::SeqCst);
+        })
+        .build()
+        .unwrap();
+
+    let result = verifier
+        .verify()
+        .await
+        .expect("Dry runs should succeed offline");
+    println!(
+        "\n📨 {} prompt(s), {} progress update(s)",
+        result.prompts.len(),
+        updates.load(Ordering::SeqCst)
+    );
+    assert!(!result.prompts.is_empty());
+    assert!(
+        updates.load(Ordering::SeqCst) > 0,
+        "The progress hook should be called"
+    );
+
+    let again = verifier
+        .verify_request(&VerificationRequest::new(
+            &path,
+            &first,
+            &second,
+            "Implement sum()",
+        ))
+        .await
+        .expect("The verifier should be reusable for other requests");
+    assert_eq!(again.files_analyzed.len(), result.files_analyzed.len());
+
+    let _ = std::fs::remove_dir_all(&path);
+}
+
+#[tokio::test(flavor = "current_thread")]
+async fn test_verify_intent_still_works_positionally() {
+    let (path, first, second) = common::init_local_repo();
+
+    // Nothing listens on port 9, so every model request fails and becomes a warning
+    let result = verify_intent(
+        &path,
+        &second,
+        &path,
+        &first,
+        &second,
+        "Implement sum() in src/lib.rs",
+        "unused",
+        None,
+        Some("http://127.0.0.1:9"),
+    )
+    .await
+    .expect("The wrapper should return a partial result offline");
+    assert_eq!(result.files_analyzed.len(), 1);
+    assert!(result.is_partial);
+
+    let _ = std::fs::remove_dir_all(&path);
+}
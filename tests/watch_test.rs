@@ -0,0 +1,167 @@
+#![cfg(feature = "git")]
+
+mod common;
+
+use std::time::Duration;
+
+use intent_verification::{
+    AnalysisOptions, ChangeType, RepoSnapshot, WorkingTreeWatcher, verify_intent_with_snapshots,
+    working_tree_fingerprint,
+};
+
+/// Create a local repository with one commit containing a stub implementation
+fn init_local_repo() -> String {
+    let path = common::unique_path("watch_test_repo");
+    common::commit_files(
+        &path,
+        "stub",
+        &[
+            ("src/lib.rs", common::SUM_STUB),
+            (".gitignore", "target/\n"),
+        ],
+    );
+    path
+}
+
+#[test]
+fn test_working_tree_snapshot() {
+    let path = init_local_repo();
+    std::fs::write(
+        format!("{}/src/lib.rs", path),
+        "pub fn sum(a: i32, b: i32) -> i32 {\n    a + b\n}\n",
+    )
+    .unwrap();
+    std::fs::create_dir_all(format!("{}/tests", path)).unwrap();
+    std::fs::write(
+        format!("{}/tests/sum_tests.rs", path),
+        "#[test]\nfn sums() {}\n",
+    )
+    .unwrap();
+    std::fs::create_dir_all(format!("{}/target", path)).unwrap();
+    std::fs::write(format!("{}/target/out.rs", path), "fn ignored() {}\n").unwrap();
+
+    let base = RepoSnapshot::from_commit(&path, "HEAD").expect("HEAD should be readable");
+    let head = RepoSnapshot::from_working_tree(&path).expect("Working tree should be readable");
+    let changes = base.diff(&head);
+
+    println!("\n👀 Working tree changes: {:#?}", changes);
+
+    let summary: Vec<(&str, ChangeType)> = changes
+        .iter()
+        .map(|c| (c.path.as_str(), c.status.clone()))
+        .collect();
+    assert_eq!(
+        summary,
+        vec![
+            ("src/lib.rs", ChangeType::Modified),
+            ("tests/sum_tests.rs", ChangeType::Added),
+        ],
+        "Uncommitted and untracked files should show up, ignored files should not"
+    );
+    assert!(base.get("src/lib.rs").unwrap().contains("todo!()"));
+}
+
+#[test]
+fn test_working_tree_fingerprint() {
+    let path = init_local_repo();
+    let clean = working_tree_fingerprint(&path).unwrap();
+    assert_eq!(clean, working_tree_fingerprint(&path).unwrap());
+
+    std::fs::write(format!("{}/notes.md", path), "todo\n").unwrap();
+    let untracked = working_tree_fingerprint(&path).unwrap();
+    assert_ne!(clean, untracked, "New files should change the fingerprint");
+
+    std::fs::write(format!("{}/notes.md", path), "todo: more\n").unwrap();
+    assert_ne!(
+        untracked,
+        working_tree_fingerprint(&path).unwrap(),
+        "Editing a file that is already changed should change the fingerprint"
+    );
+
+    std::fs::remove_file(format!("{}/notes.md", path)).unwrap();
+    assert_eq!(clean, working_tree_fingerprint(&path).unwrap());
+
+    std::fs::create_dir_all(format!("{}/target", path)).unwrap();
+    std::fs::write(format!("{}/target/out.rs", path), "fn ignored() {}\n").unwrap();
+    assert_eq!(
+        clean,
+        working_tree_fingerprint(&path).unwrap(),
+        "Ignored files should not trigger a re-run"
+    );
+}
+
+#[tokio::test]
+async fn test_watcher_reports_debounced_changes() {
+    let path = init_local_repo();
+    let mut watcher = WorkingTreeWatcher::new(&path, Duration::from_millis(200)).unwrap();
+
+    let writer_path = path.clone();
+    let writer = tokio::spawn(async move {
+        for i in 0..3 {
+            tokio::time::sleep(Duration::from_millis(100)).await;
+            std::fs::write(
+                format!("{}/src/lib.rs", writer_path),
+                format!(
+                    "pub fn sum(a: i32, b: i32) -> i32 {{\n    a + b + {}\n}}\n",
+                    i
+                ),
+            )
+            .unwrap();
+        }
+    });
+
+    tokio::time::timeout(Duration::from_secs(10), watcher.wait_for_change())
+        .await
+        .expect("The edit should be noticed")
+        .unwrap();
+    writer.await.unwrap();
+
+    let settled = std::fs::read_to_string(format!("{}/src/lib.rs", path)).unwrap();
+    assert!(
+        settled.contains("a + b + 2"),
+        "The change should be reported once the burst of edits has settled"
+    );
+    assert!(
+        tokio::time::timeout(Duration::from_millis(800), watcher.wait_for_change())
+            .await
+            .is_err(),
+        "No change should be reported while the working tree stays the same"
+    );
+}
+
+#[tokio::test]
+async fn test_verify_intent_with_snapshots() {
+    let path = init_local_repo();
+    std::fs::write(
+        format!("{}/src/lib.rs", path),
+        "pub fn sum(a: i32, b: i32) -> i32 {\n    a + b\n}\n",
+    )
+    .unwrap();
+
+    let head = RepoSnapshot::from_working_tree(&path).unwrap();
+    let result = verify_intent_with_snapshots(
+        &head,
+        &RepoSnapshot::from_commit(&path, "HEAD").unwrap(),
+        &head,
+        "The sum function should add two numbers",
+        "test-key",
+        None,
+        Some("http://127.0.0.1:9"),
+        &AnalysisOptions {
+            dry_run: true,
+            ..Default::default()
+        },
+    )
+    .await
+    .expect("Snapshots need no repository access");
+
+    assert_eq!(result.files_analyzed.len(), 1);
+    assert_eq!(result.files_analyzed[0].file_path, "src/lib.rs");
+    assert!(
+        result.prompts[1]
+            .messages
+            .iter()
+            .any(|m| m.content.contains("a + b")),
+        "The uncommitted change should be analyzed"
+    );
+}
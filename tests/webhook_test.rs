@@ -0,0 +1,189 @@
+#![cfg(feature = "server")]
+
+use intent_verification::{
+    github_trigger, gitlab_trigger, linked_issues, verify_github_signature, verify_gitlab_token,
+};
+
+#[test]
+fn test_github_signature() {
+    // Example from the GitHub webhook documentation
+    let secret = "It's a Secret to Everybody";
+    let signature = "sha256=757107ea0eb2509fc211221cce984b8a37570b6d7586c22c46f4379c8b043e17";
+
+    assert!(verify_github_signature(secret, b"Hello, World!", signature));
+    assert!(
+        !verify_github_signature(secret, b"Hello, World?", signature),
+        "A modified payload should be rejected"
+    );
+    assert!(!verify_github_signature(
+        "other secret",
+        b"Hello, World!",
+        signature
+    ));
+    assert!(
+        !verify_github_signature(secret, b"Hello, World!", "757107ea0eb2509fc211"),
+        "Signatures without the sha256= prefix should be rejected"
+    );
+
+    assert!(verify_gitlab_token("s3cret", "s3cret"));
+    assert!(!verify_gitlab_token("s3cret", "s3cre"));
+}
+
+#[test]
+fn test_github_pull_request_trigger() {
+    let payload = r#"{
+        "action": "opened",
+        "pull_request": {
+            "number": 7,
+            "title": "Implement sum",
+            "body": null,
+            "base": { "sha": "aaa111" },
+            "head": { "sha": "bbb222", "repo": { "full_name": "acme/calc", "clone_url": "https://github.com/acme/calc.git" } }
+        },
+        "repository": { "full_name": "acme/calc", "clone_url": "https://github.com/acme/calc.git" }
+    }"#;
+
+    let trigger = github_trigger("pull_request", payload)
+        .unwrap()
+        .expect("Opened pull requests should be verified");
+
+    println!("\n🪝 GitHub trigger: {:#?}", trigger);
+
+    assert_eq!(trigger.source, "github:acme/calc#7");
+    assert_eq!(trigger.repo_url, "https://github.com/acme/calc.git");
+    assert_eq!(
+        (trigger.base.as_str(), trigger.head.as_str()),
+        ("aaa111", "bbb222")
+    );
+    assert_eq!(trigger.intent, "Implement sum");
+    assert!(trigger.linked_issues.is_empty());
+
+    let linked = payload.replace("\"body\": null", "\"body\": \"Fixes #12\"");
+    let trigger = github_trigger("pull_request", &linked).unwrap().unwrap();
+    assert_eq!(trigger.linked_issues, vec!["acme/calc#12"]);
+
+    let closed = payload.replace("\"opened\"", "\"closed\"");
+    assert!(
+        github_trigger("pull_request", &closed).unwrap().is_none(),
+        "Closed pull requests need no verification"
+    );
+    assert!(github_trigger("ping", "{}").unwrap().is_none());
+}
+
+#[test]
+fn test_linked_issues() {
+    let body =
+        "Adds sum.\n\nFixes #12, closes: other/lib#3.\nResolved #12\nSee #4, fixes the #5 typo";
+    assert_eq!(
+        linked_issues("acme/calc", body),
+        vec!["acme/calc#12", "other/lib#3"]
+    );
+    assert!(linked_issues("acme/calc", "Fixes nothing").is_empty());
+}
+
+#[test]
+fn test_github_push_trigger() {
+    let payload = r#"{
+        "before": "aaa111",
+        "after": "bbb222",
+        "head_commit": { "message": "Make the sum tests pass\n" },
+        "repository": { "full_name": "acme/calc", "clone_url": "https://github.com/acme/calc.git" }
+    }"#;
+
+    let trigger = github_trigger("push", payload).unwrap().unwrap();
+    assert_eq!(trigger.source, "github:acme/calc@bbb222");
+    assert_eq!(trigger.intent, "Make the sum tests pass");
+
+    let new_branch = payload.replace("aaa111", "0000000000000000000000000000000000000000");
+    assert!(
+        github_trigger("push", &new_branch).unwrap().is_none(),
+        "Branch creation has nothing to compare against"
+    );
+}
+
+#[test]
+fn test_gitlab_merge_request_trigger() {
+    let payload = r#"{
+        "object_kind": "merge_request",
+        "project": { "path_with_namespace": "acme/calc", "git_http_url": "https://gitlab.com/acme/calc.git" },
+        "object_attributes": {
+            "iid": 3,
+            "title": "Implement sum",
+            "description": "Tests in tests/sum_tests.rs should pass",
+            "action": "update",
+            "target_branch": "main",
+            "source": { "path_with_namespace": "acme/calc", "git_http_url": "https://gitlab.com/acme/calc.git" },
+            "last_commit": { "id": "ccc333", "message": "wip" },
+            "diff_refs": { "base_sha": "aaa111", "head_sha": "ccc333", "start_sha": "bbb222" }
+        }
+    }"#;
+
+    let trigger = gitlab_trigger("Merge Request Hook", payload)
+        .unwrap()
+        .expect("Updated merge requests should be verified");
+
+    assert_eq!(trigger.source, "gitlab:acme/calc!3");
+    assert_eq!(trigger.repo_url, "https://gitlab.com/acme/calc.git");
+    assert_eq!(
+        trigger.base, "aaa111",
+        "The base should be pinned, not a branch"
+    );
+    assert_eq!(trigger.head, "ccc333");
+    assert_eq!(
+        trigger.intent,
+        "Implement sum\n\nTests in tests/sum_tests.rs should pass"
+    );
+
+    let merged = payload.replace("\"update\"", "\"merge\"");
+    assert!(
+        gitlab_trigger("Merge Request Hook", &merged)
+            .unwrap()
+            .is_none()
+    );
+    assert!(
+        gitlab_trigger("Merge Request Hook", "{}").is_err(),
+        "Malformed payloads should be reported"
+    );
+    let unpinned = payload.replace("\"diff_refs\"", "\"unused\"");
+    assert!(
+        gitlab_trigger("Merge Request Hook", &unpinned).is_err(),
+        "Merge requests without diff_refs have no commits to pin"
+    );
+}
+
+#[test]
+fn test_gitlab_fork_merge_request_trigger() {
+    // The fork's main branch is behind the target's, so neither `origin/main` nor the fork's
+    // clone would give the right base
+    let payload = r#"{
+        "object_kind": "merge_request",
+        "project": { "path_with_namespace": "acme/calc", "git_http_url": "https://gitlab.com/acme/calc.git" },
+        "object_attributes": {
+            "iid": 7,
+            "title": "Implement sum",
+            "description": null,
+            "action": "open",
+            "source_branch": "main",
+            "target_branch": "main",
+            "source_project_id": 2,
+            "target_project_id": 1,
+            "source": { "path_with_namespace": "dev/calc", "git_http_url": "https://gitlab.com/dev/calc.git" },
+            "target": { "path_with_namespace": "acme/calc", "git_http_url": "https://gitlab.com/acme/calc.git" },
+            "last_commit": { "id": "fff999", "message": "Implement sum" },
+            "diff_refs": { "base_sha": "ddd444", "head_sha": "fff999", "start_sha": "eee555" }
+        }
+    }"#;
+
+    let trigger = gitlab_trigger("Merge Request Hook", payload)
+        .unwrap()
+        .expect("Merge requests from forks should be verified");
+
+    assert_eq!(trigger.source, "gitlab:acme/calc!7");
+    assert_eq!(
+        trigger.repo_url, "https://gitlab.com/acme/calc.git",
+        "The target project has both the base and the merge request head"
+    );
+    assert_eq!(trigger.base, "ddd444");
+    assert_eq!(trigger.head, "fff999");
+    assert_eq!(trigger.intent, "Implement sum");
+}